@@ -48,10 +48,15 @@ pub async fn distinguish_comment(
 
   let comment_id = data.comment_id;
   let person_id = local_user_view.person.id;
-  let comment_view = CommentView::read(&mut context.pool(), comment_id, Some(person_id)).await?;
+  let mut comment_view =
+    CommentView::read(&mut context.pool(), comment_id, Some(person_id)).await?;
+  comment_view.redact_nsfw(Some(&local_user_view.local_user));
 
   Ok(Json(CommentResponse {
     comment_view,
     recipient_ids: Vec::new(),
+    ancestor_counts: Vec::new(),
+    post_comments: None,
+    community_mentions: Vec::new(),
   }))
 }
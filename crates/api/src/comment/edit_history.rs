@@ -0,0 +1,33 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  comment::{GetCommentEditHistory, GetCommentEditHistoryResponse},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{comment::Comment, edit_history::CommentEditHistory, post::Post},
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn get_comment_edit_history(
+  data: Query<GetCommentEditHistory>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetCommentEditHistoryResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+
+  let comment = Comment::read(&mut context.pool(), data.comment_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindComment)?;
+  if comment.creator_id != person_id {
+    let post = Post::read(&mut context.pool(), comment.post_id).await?;
+    is_mod_or_admin(&mut context.pool(), person_id, post.community_id).await?;
+  }
+
+  let revisions = CommentEditHistory::list_for_comment(&mut context.pool(), comment.id).await?;
+
+  Ok(Json(GetCommentEditHistoryResponse { revisions }))
+}
@@ -5,7 +5,14 @@ use lemmy_api_common::{
   comment::{CommentResponse, CreateCommentLike},
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, check_downvotes_enabled, local_user_view_from_jwt},
+  utils::{
+    check_allowed_to_vote,
+    check_community_ban,
+    check_community_deleted_or_removed,
+    check_downvotes_enabled,
+    check_person_block,
+    local_user_view_from_jwt,
+  },
 };
 use lemmy_db_schema::{
   newtypes::LocalUserId,
@@ -42,6 +49,24 @@ pub async fn like_comment(
     &mut context.pool(),
   )
   .await?;
+  check_community_deleted_or_removed(orig_comment.community.id, &mut context.pool()).await?;
+
+  // Users can't vote on comments of someone who blocked them, or in communities whose voting
+  // requirements they don't meet, but can remove existing votes
+  if data.score != 0 {
+    check_person_block(
+      local_user_view.person.id,
+      orig_comment.creator.id,
+      &mut context.pool(),
+    )
+    .await?;
+    check_allowed_to_vote(
+      &local_user_view.person,
+      &orig_comment.community,
+      &mut context.pool(),
+    )
+    .await?;
+  }
 
   // Add parent poster or commenter to recipients
   let comment_reply = CommentReply::read_by_comment(&mut context.pool(), comment_id).await;
@@ -0,0 +1,81 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_comment_response,
+  comment::{CommentResponse, LockComment},
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{
+    check_community_ban,
+    check_community_deleted_or_removed,
+    is_mod_or_admin,
+    local_user_view_from_jwt,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    comment::{Comment, CommentUpdateForm},
+    moderator::{ModLockComment, ModLockCommentForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::CommentView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn lock_comment(
+  data: Json<LockComment>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommentResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  let comment_id = data.comment_id;
+  let orig_comment = CommentView::read(&mut context.pool(), comment_id, None).await?;
+
+  check_community_ban(
+    local_user_view.person.id,
+    orig_comment.community.id,
+    &mut context.pool(),
+  )
+  .await?;
+  check_community_deleted_or_removed(orig_comment.community.id, &mut context.pool()).await?;
+
+  // Verify that only the mods can lock
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    orig_comment.community.id,
+  )
+  .await?;
+
+  // Update the comment
+  let locked = data.locked;
+  let updated_comment = Comment::update(
+    &mut context.pool(),
+    comment_id,
+    &CommentUpdateForm {
+      locked: Some(locked),
+      ..Default::default()
+    },
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
+
+  // Mod tables
+  let form = ModLockCommentForm {
+    mod_person_id: local_user_view.person.id,
+    comment_id,
+    locked,
+  };
+  ModLockComment::create(&mut context.pool(), &form).await?;
+
+  ActivityChannel::submit_activity(
+    SendActivityData::LockComment(updated_comment, local_user_view.person.clone()),
+    &context,
+  )
+  .await?;
+
+  Ok(Json(
+    build_comment_response(&context, comment_id, Some(local_user_view), vec![]).await?,
+  ))
+}
@@ -1,3 +1,5 @@
 pub mod distinguish;
+pub mod edit_history;
 pub mod like;
+pub mod lock;
 pub mod save;
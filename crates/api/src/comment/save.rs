@@ -9,7 +9,10 @@ use lemmy_db_schema::{
   traits::Saveable,
 };
 use lemmy_db_views::structs::CommentView;
-use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{time::naive_from_unix, validation::clean_save_tag},
+};
 
 #[tracing::instrument(skip(context))]
 pub async fn save_comment(
@@ -21,6 +24,8 @@ pub async fn save_comment(
   let comment_saved_form = CommentSavedForm {
     comment_id: data.comment_id,
     person_id: local_user_view.person.id,
+    remind_at: data.remind_at.map(naive_from_unix),
+    tag: clean_save_tag(&data.tag)?,
   };
 
   if data.save {
@@ -35,10 +40,15 @@ pub async fn save_comment(
 
   let comment_id = data.comment_id;
   let person_id = local_user_view.person.id;
-  let comment_view = CommentView::read(&mut context.pool(), comment_id, Some(person_id)).await?;
+  let mut comment_view =
+    CommentView::read(&mut context.pool(), comment_id, Some(person_id)).await?;
+  comment_view.redact_nsfw(Some(&local_user_view.local_user));
 
   Ok(Json(CommentResponse {
     comment_view,
     recipient_ids: Vec::new(),
+    ancestor_counts: Vec::new(),
+    post_comments: None,
+    community_mentions: Vec::new(),
   }))
 }
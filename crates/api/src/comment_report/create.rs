@@ -7,6 +7,7 @@ use lemmy_api_common::{
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
     check_community_ban,
+    check_person_block,
     local_user_view_from_jwt,
     sanitize_html,
     send_new_report_email_to_admins,
@@ -40,6 +41,16 @@ pub async fn create_comment_report(
 
   check_community_ban(person_id, comment_view.community.id, &mut context.pool()).await?;
 
+  // Users who are blocked by the creator can only report the comment once
+  let creator_id = comment_view.comment.creator_id;
+  if check_person_block(person_id, creator_id, &mut context.pool())
+    .await
+    .is_err()
+    && CommentReport::exists_for_creator(&mut context.pool(), comment_id, person_id).await?
+  {
+    Err(LemmyErrorType::AlreadyReported)?
+  }
+
   let report_form = CommentReportForm {
     creator_id: person_id,
     comment_id,
@@ -0,0 +1,67 @@
+use crate::community::transfer::make_top_mod;
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  community::{AcceptCommunityTransfer, GetCommunityResponse},
+  context::LemmyContext,
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::community_transfer_request::CommunityTransferRequest;
+use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+/// Finalizes or declines a pending transfer of the top mod position to the local user.
+#[tracing::instrument(skip(context))]
+pub async fn accept_community_transfer(
+  data: Json<AcceptCommunityTransfer>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetCommunityResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+  let community_id = data.community_id;
+
+  let request = CommunityTransferRequest::read_for_community(&mut context.pool(), community_id)
+    .await?
+    .filter(|r| r.recipient_id == person_id)
+    .ok_or(LemmyErrorType::NoPendingCommunityTransfer)?;
+  CommunityTransferRequest::delete_for_community(&mut context.pool(), community_id).await?;
+
+  if data.accept {
+    let community_mods =
+      CommunityModeratorView::for_community(&mut context.pool(), community_id).await?;
+
+    // The sender might have lost the top mod position, or removed the recipient as mod, since
+    // offering the transfer
+    let still_valid = community_mods.first().map(|m| m.moderator.id) == Some(request.sender_id)
+      && community_mods.iter().any(|m| m.moderator.id == person_id);
+    if !still_valid {
+      return Err(LemmyErrorType::NoPendingCommunityTransfer)?;
+    }
+
+    make_top_mod(
+      &mut context.pool(),
+      community_id,
+      community_mods,
+      request.sender_id,
+      person_id,
+    )
+    .await?;
+  }
+
+  let community_view =
+    CommunityView::read(&mut context.pool(), community_id, Some(person_id), false)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
+
+  let moderators = CommunityModeratorView::for_community(&mut context.pool(), community_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
+
+  Ok(Json(GetCommunityResponse {
+    community_view,
+    site: None,
+    moderators,
+    discussion_languages: vec![],
+    mod_reason_templates: None,
+    permissions: None,
+  }))
+}
@@ -5,6 +5,7 @@ use lemmy_api_common::{
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
+    check_mod_action_reason,
     is_mod_or_admin,
     local_user_view_from_jwt,
     remove_user_data_in_community,
@@ -14,20 +15,26 @@ use lemmy_api_common::{
 use lemmy_db_schema::{
   source::{
     community::{
+      Community,
       CommunityFollower,
       CommunityFollowerForm,
       CommunityPersonBan,
       CommunityPersonBanForm,
     },
+    local_site::LocalSite,
     moderator::{ModBanFromCommunity, ModBanFromCommunityForm},
+    person::Person,
   },
   traits::{Bannable, Crud, Followable},
 };
-use lemmy_db_views_actor::structs::PersonView;
+use lemmy_db_views_actor::structs::{CommunityModeratorView, PersonView};
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
   utils::{time::naive_from_unix, validation::is_valid_body_field},
 };
+use tracing::warn;
+
+const EXPIRED_BAN_REASON: &str = "Ban expired";
 
 #[tracing::instrument(skip(context))]
 pub async fn ban_from_community(
@@ -48,6 +55,8 @@ pub async fn ban_from_community(
   )
   .await?;
   is_valid_body_field(&data.reason, false)?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_mod_action_reason(&data.reason, &local_site)?;
 
   let community_user_ban_form = CommunityPersonBanForm {
     community_id: data.community_id,
@@ -111,3 +120,78 @@ pub async fn ban_from_community(
     banned: data.ban,
   }))
 }
+
+/// Lifts the community bans which expired, and returns the unbans which need to be sent out.
+/// Other instances don't lift them by themselves, so for local communities the unban is written
+/// to the modlog and sent in the name of a local mod, or of an admin if there is none. Bans from
+/// remote communities are logged once the unban from their instance arrives.
+///
+/// A ban which can't be lifted is kept for the next run, without holding up the others.
+pub async fn lift_expired_community_bans(
+  context: &LemmyContext,
+) -> Result<Vec<SendActivityData>, LemmyError> {
+  let bans = CommunityPersonBan::list_expired(&mut context.pool()).await?;
+  let mut activities = vec![];
+  for ban in bans {
+    match lift_expired_community_ban(&ban, context).await {
+      Ok(activity) => activities.extend(activity),
+      Err(e) => warn!(
+        "Failed to lift expired ban of person {} in community {}: {e}",
+        ban.person_id, ban.community_id
+      ),
+    }
+  }
+  Ok(activities)
+}
+
+async fn lift_expired_community_ban(
+  ban: &CommunityPersonBan,
+  context: &LemmyContext,
+) -> Result<Option<SendActivityData>, LemmyError> {
+  let community = Community::read(&mut context.pool(), ban.community_id).await?;
+  let activity = if community.local {
+    let mod_ = lifting_mod(&community, context).await?;
+    let form = ModBanFromCommunityForm {
+      mod_person_id: mod_.id,
+      other_person_id: ban.person_id,
+      community_id: community.id,
+      reason: Some(EXPIRED_BAN_REASON.to_string()),
+      banned: Some(false),
+      expires: None,
+    };
+    ModBanFromCommunity::create(&mut context.pool(), &form).await?;
+
+    let banned_person = Person::read(&mut context.pool(), ban.person_id).await?;
+    let data = BanFromCommunity {
+      community_id: community.id,
+      person_id: ban.person_id,
+      ban: false,
+      reason: Some(EXPIRED_BAN_REASON.to_string()),
+      ..Default::default()
+    };
+    Some(SendActivityData::BanFromCommunity(
+      mod_,
+      community.id,
+      banned_person,
+      data,
+    ))
+  } else {
+    None
+  };
+  CommunityPersonBan::delete_expired(&mut context.pool(), ban.id).await?;
+  Ok(activity)
+}
+
+/// The first local mod of the community, or an admin if all mods are remote.
+async fn lifting_mod(community: &Community, context: &LemmyContext) -> Result<Person, LemmyError> {
+  let mods = CommunityModeratorView::for_community(&mut context.pool(), community.id).await?;
+  if let Some(mod_) = mods.into_iter().map(|m| m.moderator).find(|m| m.local) {
+    return Ok(mod_);
+  }
+  let admin = PersonView::admins(&mut context.pool())
+    .await?
+    .into_iter()
+    .next()
+    .ok_or(LemmyErrorType::CouldntFindPerson)?;
+  Ok(admin.person)
+}
@@ -0,0 +1,140 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{
+    CommunityPersonFlairResponse,
+    PickCommunityFlair,
+    RemoveCommunityPersonFlair,
+    SetCommunityPersonFlair,
+  },
+  context::LemmyContext,
+  utils::{
+    check_community_ban,
+    is_mod_or_admin,
+    local_site_to_slur_regex,
+    local_user_view_from_jwt,
+    sanitize_html,
+  },
+};
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::{
+    community::Community,
+    community_flair::{CommunityFlairOption, CommunityPersonFlair, CommunityPersonFlairForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::DbPool,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{
+    slurs::check_slurs,
+    validation::{clean_flair, is_valid_flair_color},
+  },
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn set_community_person_flair(
+  data: Json<SetCommunityPersonFlair>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityPersonFlairResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  let flair = clean_flair(&data.flair)?;
+  check_slurs(&flair, &local_site_to_slur_regex(&local_site))?;
+  is_valid_flair_color(&data.color)?;
+
+  let community_id = data.community_id;
+  is_mod_or_admin(&mut context.pool(), local_user_view.person.id, community_id).await?;
+  check_local_community(community_id, &mut context.pool()).await?;
+
+  let form = CommunityPersonFlairForm {
+    community_id,
+    person_id: data.person_id,
+    flair: sanitize_html(&flair),
+    color: data.color.clone(),
+    set_by_id: Some(local_user_view.person.id),
+  };
+  let flair = CommunityPersonFlair::set(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntSetFlair)?;
+
+  Ok(Json(CommunityPersonFlairResponse { flair: Some(flair) }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn remove_community_person_flair(
+  data: Json<RemoveCommunityPersonFlair>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityPersonFlairResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    data.community_id,
+  )
+  .await?;
+  check_local_community(data.community_id, &mut context.pool()).await?;
+
+  CommunityPersonFlair::remove(&mut context.pool(), data.community_id, data.person_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntSetFlair)?;
+
+  Ok(Json(CommunityPersonFlairResponse { flair: None }))
+}
+
+/// Lets members choose from the flairs which the mods offer. Flairs which a mod assigned are
+/// replaced as well, so that members aren't stuck with them.
+#[tracing::instrument(skip(context))]
+pub async fn pick_community_flair(
+  data: Json<PickCommunityFlair>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityPersonFlairResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+  let community_id = data.community_id;
+  check_community_ban(person_id, community_id, &mut context.pool()).await?;
+  check_local_community(community_id, &mut context.pool()).await?;
+
+  let Some(flair_option_id) = data.flair_option_id else {
+    CommunityPersonFlair::remove(&mut context.pool(), community_id, person_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntSetFlair)?;
+    return Ok(Json(CommunityPersonFlairResponse { flair: None }));
+  };
+
+  let flair_option = CommunityFlairOption::read(&mut context.pool(), flair_option_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::FlairNotAvailable)?;
+  if flair_option.community_id != community_id {
+    Err(LemmyErrorType::FlairNotAvailable)?;
+  }
+
+  let form = CommunityPersonFlairForm {
+    community_id,
+    person_id,
+    flair: flair_option.flair,
+    color: flair_option.color,
+    set_by_id: Some(person_id),
+  };
+  let flair = CommunityPersonFlair::set(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntSetFlair)?;
+
+  Ok(Json(CommunityPersonFlairResponse { flair: Some(flair) }))
+}
+
+/// Flairs belong to the instance of the community, which sends them along with posts and
+/// comments. So they can only be changed here for local communities.
+pub(crate) async fn check_local_community(
+  community_id: CommunityId,
+  pool: &mut DbPool<'_>,
+) -> Result<(), LemmyError> {
+  let community = Community::read(pool, community_id).await?;
+  if !community.local {
+    Err(LemmyErrorType::FlairNotAvailable)?;
+  }
+  Ok(())
+}
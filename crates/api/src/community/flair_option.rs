@@ -0,0 +1,102 @@
+use crate::community::flair::check_local_community;
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  community::{
+    CommunityFlairOptionResponse,
+    CreateCommunityFlairOption,
+    DeleteCommunityFlairOption,
+    DeleteCommunityFlairOptionResponse,
+    ListCommunityFlairOptions,
+    ListCommunityFlairOptionsResponse,
+  },
+  context::LemmyContext,
+  utils::{
+    check_private_instance,
+    is_mod_or_admin,
+    local_site_to_slur_regex,
+    local_user_view_from_jwt,
+    local_user_view_from_jwt_opt,
+    sanitize_html,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    community_flair::{CommunityFlairOption, CommunityFlairOptionForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{
+    slurs::check_slurs,
+    validation::{clean_flair, is_valid_flair_color},
+  },
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn create_community_flair_option(
+  data: Json<CreateCommunityFlairOption>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityFlairOptionResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  let flair = clean_flair(&data.flair)?;
+  check_slurs(&flair, &local_site_to_slur_regex(&local_site))?;
+  is_valid_flair_color(&data.color)?;
+
+  let community_id = data.community_id;
+  is_mod_or_admin(&mut context.pool(), local_user_view.person.id, community_id).await?;
+  check_local_community(community_id, &mut context.pool()).await?;
+
+  let form = CommunityFlairOptionForm {
+    community_id,
+    flair: sanitize_html(&flair),
+    color: data.color.clone(),
+  };
+  let flair_option = CommunityFlairOption::create(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntSetFlair)?;
+
+  Ok(Json(CommunityFlairOptionResponse { flair_option }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_community_flair_option(
+  data: Json<DeleteCommunityFlairOption>,
+  context: Data<LemmyContext>,
+) -> Result<Json<DeleteCommunityFlairOptionResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  let flair_option = CommunityFlairOption::read(&mut context.pool(), data.id).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    flair_option.community_id,
+  )
+  .await?;
+  check_local_community(flair_option.community_id, &mut context.pool()).await?;
+
+  CommunityFlairOption::delete(&mut context.pool(), data.id).await?;
+  Ok(Json(DeleteCommunityFlairOptionResponse {
+    id: data.id,
+    success: true,
+  }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn list_community_flair_options(
+  data: Query<ListCommunityFlairOptions>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListCommunityFlairOptionsResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), &context).await;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let flair_options =
+    CommunityFlairOption::list_for_community(&mut context.pool(), data.community_id).await?;
+
+  Ok(Json(ListCommunityFlairOptionsResponse { flair_options }))
+}
@@ -3,18 +3,14 @@ use actix_web::web::Json;
 use lemmy_api_common::{
   community::{CommunityResponse, FollowCommunity},
   context::LemmyContext,
-  send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, check_community_deleted_or_removed, local_user_view_from_jwt},
+  utils::{local_user_view_from_jwt, update_community_follow},
 };
 use lemmy_db_schema::{
-  source::{
-    actor_language::CommunityLanguage,
-    community::{Community, CommunityFollower, CommunityFollowerForm},
-  },
-  traits::{Crud, Followable},
+  source::{actor_language::CommunityLanguage, community::Community},
+  traits::Crud,
 };
 use lemmy_db_views_actor::structs::CommunityView;
-use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use lemmy_utils::error::LemmyError;
 
 #[tracing::instrument(skip(context))]
 pub async fn follow_community(
@@ -24,39 +20,7 @@ pub async fn follow_community(
   let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
 
   let community = Community::read(&mut context.pool(), data.community_id).await?;
-  let mut community_follower_form = CommunityFollowerForm {
-    community_id: community.id,
-    person_id: local_user_view.person.id,
-    pending: false,
-  };
-
-  if data.follow {
-    if community.local {
-      check_community_ban(local_user_view.person.id, community.id, &mut context.pool()).await?;
-      check_community_deleted_or_removed(community.id, &mut context.pool()).await?;
-
-      CommunityFollower::follow(&mut context.pool(), &community_follower_form)
-        .await
-        .with_lemmy_type(LemmyErrorType::CommunityFollowerAlreadyExists)?;
-    } else {
-      // Mark as pending, the actual federation activity is sent via `SendActivity` handler
-      community_follower_form.pending = true;
-      CommunityFollower::follow(&mut context.pool(), &community_follower_form)
-        .await
-        .with_lemmy_type(LemmyErrorType::CommunityFollowerAlreadyExists)?;
-    }
-  }
-  if !data.follow {
-    CommunityFollower::unfollow(&mut context.pool(), &community_follower_form)
-      .await
-      .with_lemmy_type(LemmyErrorType::CommunityFollowerAlreadyExists)?;
-  }
-
-  ActivityChannel::submit_activity(
-    SendActivityData::FollowCommunity(community, local_user_view.person.clone(), data.follow),
-    &context,
-  )
-  .await?;
+  update_community_follow(community, &local_user_view.person, data.follow, &context).await?;
 
   let community_id = data.community_id;
   let person_id = local_user_view.person.id;
@@ -1,6 +1,13 @@
+pub mod accept_transfer;
 pub mod add_mod;
 pub mod ban;
 pub mod block;
+pub mod flair;
+pub mod flair_option;
 pub mod follow;
 pub mod hide;
+pub mod mod_reason_template;
+pub mod stats;
+pub mod top_contributors;
 pub mod transfer;
+pub mod verify;
@@ -0,0 +1,135 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  community::{
+    CreateModReasonTemplate,
+    DeleteModReasonTemplate,
+    DeleteModReasonTemplateResponse,
+    EditModReasonTemplate,
+    ListModReasonTemplates,
+    ListModReasonTemplatesResponse,
+    ModReasonTemplateResponse,
+  },
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_site_to_slur_regex, local_user_view_from_jwt, sanitize_html},
+};
+use lemmy_db_schema::{
+  source::{
+    local_site::LocalSite,
+    mod_reason_template::{
+      ModReasonTemplate,
+      ModReasonTemplateInsertForm,
+      ModReasonTemplateUpdateForm,
+    },
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  utils::{slurs::check_slurs, validation::clean_mod_reason},
+};
+
+async fn check_reason(reason: &str, context: &LemmyContext) -> LemmyResult<String> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let reason = clean_mod_reason(reason)?;
+  check_slurs(&reason, &local_site_to_slur_regex(&local_site))?;
+  Ok(sanitize_html(&reason))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn create_mod_reason_template(
+  data: Json<CreateModReasonTemplate>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ModReasonTemplateResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let reason = check_reason(&data.reason, &context).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    data.community_id,
+  )
+  .await?;
+
+  let form = ModReasonTemplateInsertForm {
+    community_id: data.community_id,
+    reason,
+  };
+  let mod_reason_template = ModReasonTemplate::create(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntSaveModReason)?;
+
+  Ok(Json(ModReasonTemplateResponse {
+    mod_reason_template,
+  }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn update_mod_reason_template(
+  data: Json<EditModReasonTemplate>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ModReasonTemplateResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let reason = check_reason(&data.reason, &context).await?;
+  let template = ModReasonTemplate::read(&mut context.pool(), data.id).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    template.community_id,
+  )
+  .await?;
+
+  let form = ModReasonTemplateUpdateForm {
+    reason,
+    updated: naive_now(),
+  };
+  let mod_reason_template = ModReasonTemplate::update(&mut context.pool(), data.id, &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntSaveModReason)?;
+
+  Ok(Json(ModReasonTemplateResponse {
+    mod_reason_template,
+  }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_mod_reason_template(
+  data: Json<DeleteModReasonTemplate>,
+  context: Data<LemmyContext>,
+) -> Result<Json<DeleteModReasonTemplateResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let template = ModReasonTemplate::read(&mut context.pool(), data.id).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    template.community_id,
+  )
+  .await?;
+
+  ModReasonTemplate::delete(&mut context.pool(), data.id).await?;
+  Ok(Json(DeleteModReasonTemplateResponse {
+    id: data.id,
+    success: true,
+  }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn list_mod_reason_templates(
+  data: Query<ListModReasonTemplates>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListModReasonTemplatesResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    data.community_id,
+  )
+  .await?;
+
+  let mod_reason_templates =
+    ModReasonTemplate::list_for_community(&mut context.pool(), data.community_id).await?;
+
+  Ok(Json(ListModReasonTemplatesResponse {
+    mod_reason_templates,
+  }))
+}
@@ -0,0 +1,54 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  community::{GetCommunityStats, GetCommunityStatsResponse},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{community::Community, community_aggregates_snapshot::CommunityAggregatesSnapshot},
+  traits::Crud,
+  CommunityStatsRange,
+};
+use lemmy_db_views_actor::structs::CommunityContributorView;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+
+const TOP_COMMENTERS_LIMIT: i64 = 10;
+
+/// The daily series come from the snapshots which are taken by a scheduled task, only the top
+/// commenters are counted from the comments directly.
+#[tracing::instrument(skip(context))]
+pub async fn get_community_stats(
+  data: Query<GetCommunityStats>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetCommunityStatsResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let community_id = data.community_id;
+  is_mod_or_admin(&mut context.pool(), local_user_view.person.id, community_id).await?;
+
+  // Snapshots are only taken of local communities
+  let community = Community::read(&mut context.pool(), community_id).await?;
+  if !community.local {
+    return Err(LemmyErrorType::ObjectNotLocal)?;
+  }
+
+  let range = data.range.unwrap_or_default();
+  let days = match range {
+    CommunityStatsRange::ThirtyDays => 30,
+    CommunityStatsRange::NinetyDays => 90,
+  };
+  let snapshots =
+    CommunityAggregatesSnapshot::list(&mut context.pool(), community_id, days).await?;
+  let top_commenters = CommunityContributorView::list_top_commenters(
+    &mut context.pool(),
+    community_id,
+    days,
+    TOP_COMMENTERS_LIMIT,
+  )
+  .await?;
+
+  Ok(Json(GetCommunityStatsResponse {
+    range,
+    days: snapshots,
+    top_commenters,
+  }))
+}
@@ -0,0 +1,47 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  community::{GetCommunityTopContributors, GetCommunityTopContributorsResponse},
+  context::LemmyContext,
+  utils::{
+    check_community_deleted_or_removed,
+    check_private_instance,
+    local_user_view_from_jwt_opt,
+  },
+};
+use lemmy_db_schema::source::local_site::LocalSite;
+use lemmy_db_views_actor::{
+  community_contributor_view::ContributorWeights,
+  structs::CommunityContributorView,
+};
+use lemmy_utils::error::LemmyError;
+
+const DEFAULT_LIMIT: i64 = 10;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_community_top_contributors(
+  data: Query<GetCommunityTopContributors>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetCommunityTopContributorsResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), &context).await;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  check_private_instance(&local_user_view, &local_site)?;
+  check_community_deleted_or_removed(data.community_id, &mut context.pool()).await?;
+
+  let config = &context.settings().top_contributors;
+  let weights = ContributorWeights {
+    post: config.post_weight,
+    comment: config.comment_weight,
+    score: config.score_weight,
+  };
+  let contributors = CommunityContributorView::list(
+    &mut context.pool(),
+    data.community_id,
+    data.range.unwrap_or_default(),
+    weights,
+    data.limit.unwrap_or(DEFAULT_LIMIT),
+  )
+  .await?;
+
+  Ok(Json(GetCommunityTopContributorsResponse { contributors }))
+}
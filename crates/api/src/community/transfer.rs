@@ -4,15 +4,19 @@ use anyhow::Context;
 use lemmy_api_common::{
   community::{GetCommunityResponse, TransferCommunity},
   context::LemmyContext,
-  utils::{is_admin, is_top_mod, local_user_view_from_jwt},
+  utils::{is_admin, is_top_mod, local_user_view_from_jwt, send_email_to_user},
 };
 use lemmy_db_schema::{
+  newtypes::{CommunityId, PersonId},
   source::{
     community::{CommunityModerator, CommunityModeratorForm},
+    community_transfer_request::{CommunityTransferRequest, CommunityTransferRequestForm},
     moderator::{ModTransferCommunity, ModTransferCommunityForm},
   },
   traits::{Crud, Joinable},
+  utils::DbPool,
 };
+use lemmy_db_views::structs::LocalUserView;
 use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView};
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
@@ -35,51 +39,68 @@ impl Perform for TransferCommunity {
 
     // Fetch the community mods
     let community_id = data.community_id;
-    let mut community_mods =
+    let community_mods =
       CommunityModeratorView::for_community(&mut context.pool(), community_id).await?;
 
     // Make sure transferrer is either the top community mod, or an admin
-    if !(is_top_mod(&local_user_view, &community_mods).is_ok()
-      || is_admin(&local_user_view).is_ok())
-    {
+    let local_user_is_admin = is_admin(&local_user_view).is_ok();
+    if !(is_top_mod(&local_user_view, &community_mods).is_ok() || local_user_is_admin) {
       return Err(LemmyErrorType::NotAnAdmin)?;
     }
 
-    // You have to re-do the community_moderator table, reordering it.
-    // Add the transferee to the top
-    let creator_index = community_mods
-      .iter()
-      .position(|r| r.moderator.id == data.person_id)
-      .context(location_info!())?;
-    let creator_person = community_mods.remove(creator_index);
-    community_mods.insert(0, creator_person);
+    if local_user_is_admin {
+      // Admins can still transfer communities immediately
+      CommunityTransferRequest::delete_for_community(&mut context.pool(), community_id).await?;
+      make_top_mod(
+        &mut context.pool(),
+        community_id,
+        community_mods,
+        local_user_view.person.id,
+        data.person_id,
+      )
+      .await?;
+    } else if data.person_id == local_user_view.person.id {
+      // Transferring to yourself cancels the pending transfer
+      CommunityTransferRequest::delete_for_community(&mut context.pool(), community_id).await?;
+    } else {
+      if !community_mods
+        .iter()
+        .any(|m| m.moderator.id == data.person_id)
+      {
+        return Err(LemmyErrorType::NotAModerator)?;
+      }
 
-    // Delete all the mods
-    let community_id = data.community_id;
-
-    CommunityModerator::delete_for_community(&mut context.pool(), community_id).await?;
-
-    // TODO: this should probably be a bulk operation
-    // Re-add the mods, in the new order
-    for cmod in &community_mods {
-      let community_moderator_form = CommunityModeratorForm {
-        community_id: cmod.community.id,
-        person_id: cmod.moderator.id,
+      let form = CommunityTransferRequestForm {
+        community_id,
+        sender_id: local_user_view.person.id,
+        recipient_id: data.person_id,
       };
-
-      CommunityModerator::join(&mut context.pool(), &community_moderator_form)
+      CommunityTransferRequest::create(&mut context.pool(), &form)
         .await
-        .with_lemmy_type(LemmyErrorType::CommunityModeratorAlreadyExists)?;
-    }
+        .with_lemmy_type(LemmyErrorType::CommunityTransferAlreadyPending)?;
 
-    // Mod tables
-    let form = ModTransferCommunityForm {
-      mod_person_id: local_user_view.person.id,
-      other_person_id: data.person_id,
-      community_id: data.community_id,
-    };
-
-    ModTransferCommunity::create(&mut context.pool(), &form).await?;
+      // Let the recipient know, if they are a local user
+      if let Ok(recipient_view) =
+        LocalUserView::read_person(&mut context.pool(), data.person_id).await
+      {
+        let community = &community_mods.first().context(location_info!())?.community;
+        let inbox_link = format!(
+          "{}/c/{}",
+          context.settings().get_protocol_and_hostname(),
+          community.name
+        );
+        send_email_to_user(
+          &recipient_view,
+          &format!("{} wants to make you the top mod of {}", local_user_view.person.name, community.title),
+          &format!(
+            "<h1>Community transfer</h1><br><div>{} wants to make you the top mod of <a href=\"{}\">{}</a>. You can accept or decline on the community page.</div>",
+            local_user_view.person.name, inbox_link, community.title
+          ),
+          context.settings(),
+        )
+        .await;
+      }
+    }
 
     let community_id = data.community_id;
     let person_id = local_user_view.person.id;
@@ -99,6 +120,52 @@ impl Perform for TransferCommunity {
       site: None,
       moderators,
       discussion_languages: vec![],
+      mod_reason_templates: None,
+      permissions: None,
     })
   }
 }
+
+/// Moves `new_top_mod` to the top of the community's mods, and writes the modlog entry.
+pub(crate) async fn make_top_mod(
+  pool: &mut DbPool<'_>,
+  community_id: CommunityId,
+  mut community_mods: Vec<CommunityModeratorView>,
+  mod_person_id: PersonId,
+  new_top_mod: PersonId,
+) -> Result<(), LemmyError> {
+  // You have to re-do the community_moderator table, reordering it.
+  // Add the transferee to the top
+  let creator_index = community_mods
+    .iter()
+    .position(|r| r.moderator.id == new_top_mod)
+    .context(location_info!())?;
+  let creator_person = community_mods.remove(creator_index);
+  community_mods.insert(0, creator_person);
+
+  // Delete all the mods
+  CommunityModerator::delete_for_community(pool, community_id).await?;
+
+  // TODO: this should probably be a bulk operation
+  // Re-add the mods, in the new order
+  for cmod in &community_mods {
+    let community_moderator_form = CommunityModeratorForm {
+      community_id: cmod.community.id,
+      person_id: cmod.moderator.id,
+    };
+
+    CommunityModerator::join(pool, &community_moderator_form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CommunityModeratorAlreadyExists)?;
+  }
+
+  // Mod tables
+  let form = ModTransferCommunityForm {
+    mod_person_id,
+    other_person_id: new_top_mod,
+    community_id,
+  };
+
+  ModTransferCommunity::create(pool, &form).await?;
+  Ok(())
+}
@@ -0,0 +1,44 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_community_response,
+  community::{CommunityResponse, VerifyCommunity},
+  context::LemmyContext,
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    community::{Community, CommunityUpdateForm},
+    moderator::{AdminVerifyCommunity, AdminVerifyCommunityForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn verify_community(
+  data: Json<VerifyCommunity>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+
+  let community_id = data.community_id;
+  let community_form = CommunityUpdateForm {
+    verified: Some(data.verified),
+    ..Default::default()
+  };
+  Community::update(&mut context.pool(), community_id, &community_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateCommunity)?;
+
+  let form = AdminVerifyCommunityForm {
+    admin_person_id: local_user_view.person.id,
+    community_id,
+    verified: data.verified,
+  };
+  AdminVerifyCommunity::create(&mut context.pool(), &form).await?;
+
+  // The verification only applies to this instance, so it isn't federated
+  build_community_response(&context, local_user_view, community_id).await
+}
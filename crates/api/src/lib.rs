@@ -13,6 +13,8 @@ pub mod comment;
 pub mod comment_report;
 pub mod community;
 pub mod local_user;
+pub mod oauth;
+pub mod person_report;
 pub mod post;
 pub mod post_report;
 pub mod private_message;
@@ -80,15 +82,16 @@ mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
-  use lemmy_api_common::utils::check_validator_time;
+  use lemmy_api_common::utils::{check_person_block, check_validator_time};
   use lemmy_db_schema::{
     source::{
       instance::Instance,
       local_user::{LocalUser, LocalUserInsertForm},
       person::{Person, PersonInsertForm},
+      person_block::{PersonBlock, PersonBlockForm},
       secret::Secret,
     },
-    traits::Crud,
+    traits::{Blockable, Crud},
     utils::build_db_pool_for_tests,
   };
   use lemmy_utils::{claims::Claims, settings::SETTINGS};
@@ -142,4 +145,55 @@ mod tests {
     let num_deleted = Person::delete(pool, inserted_person.id).await.unwrap();
     assert_eq!(1, num_deleted);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_person_block() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let blocker_form = PersonInsertForm::builder()
+      .name("blocker".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let blocker = Person::create(pool, &blocker_form).await.unwrap();
+
+    let blocked_form = PersonInsertForm::builder()
+      .name("blocked".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let blocked = Person::create(pool, &blocked_form).await.unwrap();
+
+    assert!(check_person_block(blocked.id, blocker.id, pool)
+      .await
+      .is_ok());
+
+    let block_form = PersonBlockForm {
+      person_id: blocker.id,
+      target_id: blocked.id,
+    };
+    PersonBlock::block(pool, &block_form).await.unwrap();
+
+    // Only the blocked person is prevented from interacting
+    assert!(check_person_block(blocked.id, blocker.id, pool)
+      .await
+      .is_err());
+    assert!(check_person_block(blocker.id, blocked.id, pool)
+      .await
+      .is_ok());
+
+    PersonBlock::unblock(pool, &block_form).await.unwrap();
+    assert!(check_person_block(blocked.id, blocker.id, pool)
+      .await
+      .is_ok());
+
+    Person::delete(pool, blocker.id).await.unwrap();
+    Person::delete(pool, blocked.id).await.unwrap();
+  }
 }
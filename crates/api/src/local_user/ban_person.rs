@@ -4,10 +4,17 @@ use lemmy_api_common::{
   context::LemmyContext,
   person::{BanPerson, BanPersonResponse},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{is_admin, local_user_view_from_jwt, remove_user_data, sanitize_html_opt},
+  utils::{
+    check_mod_action_reason,
+    is_admin,
+    local_user_view_from_jwt,
+    remove_user_data,
+    sanitize_html_opt,
+  },
 };
 use lemmy_db_schema::{
   source::{
+    local_site::LocalSite,
     moderator::{ModBan, ModBanForm},
     person::{Person, PersonUpdateForm},
   },
@@ -29,6 +36,8 @@ pub async fn ban_from_site(
   is_admin(&local_user_view)?;
 
   is_valid_body_field(&data.reason, false)?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_mod_action_reason(&data.reason, &local_site)?;
 
   let expires = data.expires.map(naive_from_unix);
 
@@ -0,0 +1,129 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ClearPersonBio, ClearPersonDisplayName, ClearPersonProfileResponse},
+  send_activity::{ActivityChannel, SendActivityData},
+  sensitive::Sensitive,
+  utils::{is_admin, local_user_view_from_jwt, sanitize_html_opt, send_email_to_user},
+};
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  source::{
+    moderator::{AdminClearPersonProfile, AdminClearPersonProfileForm},
+    person::{Person, PersonUpdateForm},
+    person_report::PersonReport,
+  },
+  traits::{Crud, Reportable},
+  utils::naive_now,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::PersonView;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::validation::is_valid_body_field,
+};
+
+/// The part of a profile which an admin clears.
+#[derive(Clone, Copy)]
+enum ProfileField {
+  DisplayName,
+  Bio,
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn clear_person_display_name(
+  data: Json<ClearPersonDisplayName>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ClearPersonProfileResponse>, LemmyError> {
+  clear_profile_field(
+    ProfileField::DisplayName,
+    data.person_id,
+    &data.reason,
+    &data.auth,
+    &context,
+  )
+  .await
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn clear_person_bio(
+  data: Json<ClearPersonBio>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ClearPersonProfileResponse>, LemmyError> {
+  clear_profile_field(
+    ProfileField::Bio,
+    data.person_id,
+    &data.reason,
+    &data.auth,
+    &context,
+  )
+  .await
+}
+
+/// Resets the field without banning the person, logs it in the modlog and lets the person know.
+/// Open reports of the profile are resolved, and remote instances get the updated profile.
+async fn clear_profile_field(
+  field: ProfileField,
+  person_id: PersonId,
+  reason: &Option<String>,
+  auth: &Sensitive<String>,
+  context: &Data<LemmyContext>,
+) -> Result<Json<ClearPersonProfileResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(auth, context).await?;
+  is_admin(&local_user_view)?;
+  is_valid_body_field(reason, false)?;
+
+  let person_form = match field {
+    ProfileField::DisplayName => PersonUpdateForm {
+      display_name: Some(None),
+      updated: Some(Some(naive_now())),
+      ..Default::default()
+    },
+    ProfileField::Bio => PersonUpdateForm {
+      bio: Some(None),
+      updated: Some(Some(naive_now())),
+      ..Default::default()
+    },
+  };
+  let person = Person::update(&mut context.pool(), person_id, &person_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateUser)?;
+
+  let reason = sanitize_html_opt(reason);
+  let form = AdminClearPersonProfileForm {
+    admin_person_id: local_user_view.person.id,
+    person_id,
+    cleared_display_name: matches!(field, ProfileField::DisplayName),
+    cleared_bio: matches!(field, ProfileField::Bio),
+    reason: reason.clone(),
+  };
+  AdminClearPersonProfile::create(&mut context.pool(), &form).await?;
+
+  PersonReport::resolve_all_for_object(&mut context.pool(), person_id, local_user_view.person.id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntResolveReport)?;
+
+  // Let the person know, if they are a local user
+  if let Ok(recipient_view) = LocalUserView::read_person(&mut context.pool(), person_id).await {
+    let hostname = &context.settings().hostname;
+    let field_name = match field {
+      ProfileField::DisplayName => "display name",
+      ProfileField::Bio => "bio",
+    };
+    let reason = reason
+      .map(|r| format!("<br><div>The following reason was provided: {r}</div>"))
+      .unwrap_or_default();
+    let subject = format!("Your {field_name} was removed by an admin of {hostname}");
+    let body = format!(
+      "<h1>Profile changed</h1><br><div>An admin of {hostname} removed your {field_name}.</div>\
+      {reason}"
+    );
+    send_email_to_user(&recipient_view, &subject, &body, context.settings()).await;
+  }
+
+  ActivityChannel::submit_activity(SendActivityData::UpdatePerson(person), context).await?;
+
+  let person_view = PersonView::read(&mut context.pool(), person_id).await?;
+  Ok(Json(ClearPersonProfileResponse { person_view }))
+}
@@ -0,0 +1,27 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListMedia, ListMediaResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_views::local_image_view::LocalImageQuery;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_media(
+  data: Query<ListMedia>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListMediaResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  let images = LocalImageQuery {
+    local_user_id: Some(local_user_view.local_user.id),
+    page: data.page,
+    limit: data.limit,
+    ..Default::default()
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  Ok(Json(ListMediaResponse { images }))
+}
@@ -0,0 +1,40 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListSaveTags, ListSaveTagsResponse, SaveTag},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::{comment::CommentSaved, post::PostSaved};
+use lemmy_utils::error::LemmyError;
+use std::collections::BTreeMap;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_save_tags(
+  data: Query<ListSaveTags>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListSaveTagsResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+
+  let post_tags = PostSaved::count_tags_for_person(&mut context.pool(), person_id).await?;
+  let comment_tags = CommentSaved::count_tags_for_person(&mut context.pool(), person_id).await?;
+
+  // Tags are shared between posts and comments, so merge both counts into one entry
+  let mut counts: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+  for (tag, count) in post_tags {
+    counts.entry(tag).or_default().0 = count;
+  }
+  for (tag, count) in comment_tags {
+    counts.entry(tag).or_default().1 = count;
+  }
+  let tags = counts
+    .into_iter()
+    .map(|(tag, (post_count, comment_count))| SaveTag {
+      tag,
+      post_count,
+      comment_count,
+    })
+    .collect();
+
+  Ok(Json(ListSaveTagsResponse { tags }))
+}
@@ -1,82 +1,179 @@
-use crate::Perform;
-use actix_web::web::Data;
+use actix_web::{
+  http::header::USER_AGENT,
+  web::{Data, Json},
+  HttpRequest,
+};
 use bcrypt::verify;
+use chrono::Utc;
 use lemmy_api_common::{
   context::LemmyContext,
   person::{Login, LoginResponse},
-  utils::{check_registration_application, check_user_valid},
+  utils::{
+    check_deletion_not_pending,
+    check_registration_application,
+    check_user_valid,
+    sanitize_html,
+  },
 };
+use lemmy_db_schema::source::login_fingerprint::LoginFingerprint;
 use lemmy_db_views::structs::{LocalUserView, SiteView};
 use lemmy_utils::{
   claims::Claims,
+  email::send_email,
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  rate_limit::get_ip,
+  spawn_try_task,
   utils::validation::check_totp_2fa_valid,
 };
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
 
-#[async_trait::async_trait(?Send)]
-impl Perform for Login {
-  type Response = LoginResponse;
+#[tracing::instrument(skip(context))]
+pub async fn login(
+  data: Json<Login>,
+  req: HttpRequest,
+  context: Data<LemmyContext>,
+) -> Result<Json<LoginResponse>, LemmyError> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
 
-  #[tracing::instrument(skip(context))]
-  async fn perform(&self, context: &Data<LemmyContext>) -> Result<LoginResponse, LemmyError> {
-    let data: &Login = self;
+  // Fetch that username / email
+  let username_or_email = data.username_or_email.clone();
+  let local_user_view =
+    LocalUserView::find_by_email_or_name(&mut context.pool(), &username_or_email)
+      .await
+      .with_lemmy_type(LemmyErrorType::IncorrectLogin)?;
 
-    let site_view = SiteView::read_local(&mut context.pool()).await?;
+  // Verify the password
+  let valid: bool = verify(
+    &data.password,
+    &local_user_view.local_user.password_encrypted,
+  )
+  .unwrap_or(false);
+  if !valid {
+    return Err(LemmyErrorType::IncorrectLogin)?;
+  }
+  check_user_valid(
+    local_user_view.person.banned,
+    local_user_view.person.ban_expires,
+    local_user_view.person.deleted,
+  )?;
+  check_deletion_not_pending(&local_user_view.local_user)?;
 
-    // Fetch that username / email
-    let username_or_email = data.username_or_email.clone();
-    let local_user_view =
-      LocalUserView::find_by_email_or_name(&mut context.pool(), &username_or_email)
-        .await
-        .with_lemmy_type(LemmyErrorType::IncorrectLogin)?;
+  // Check if the user's email is verified if email verification is turned on
+  // However, skip checking verification if the user is an admin
+  if !local_user_view.person.admin
+    && site_view.local_site.require_email_verification
+    && !local_user_view.local_user.email_verified
+  {
+    return Err(LemmyErrorType::EmailNotVerified)?;
+  }
 
-    // Verify the password
-    let valid: bool = verify(
-      &data.password,
-      &local_user_view.local_user.password_encrypted,
-    )
-    .unwrap_or(false);
-    if !valid {
-      return Err(LemmyErrorType::IncorrectLogin)?;
-    }
-    check_user_valid(
-      local_user_view.person.banned,
-      local_user_view.person.ban_expires,
-      local_user_view.person.deleted,
-    )?;
+  check_registration_application(&local_user_view, &site_view.local_site, &mut context.pool())
+    .await?;
+
+  // Check the totp
+  check_totp_2fa_valid(
+    &local_user_view.local_user.totp_2fa_secret,
+    &data.totp_2fa_token,
+    &site_view.site.name,
+    &local_user_view.person.name,
+  )?;
+
+  check_login_fingerprint(&req, &local_user_view, &context).await?;
+
+  // Return the jwt
+  Ok(Json(LoginResponse {
+    jwt: Some(
+      Claims::jwt(
+        local_user_view.local_user.id.0,
+        &context.secret().jwt_secret,
+        &context.settings().hostname,
+      )?
+      .into(),
+    ),
+    verify_email_sent: false,
+    registration_created: false,
+  }))
+}
+
+/// Remembers the device the user logged in from, and tells them by email about logins from new
+/// devices if they want that. The full IP address is neither stored nor sent.
+pub(crate) async fn check_login_fingerprint(
+  req: &HttpRequest,
+  local_user_view: &LocalUserView,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let user_agent = req
+    .headers()
+    .get(USER_AGENT)
+    .and_then(|h| h.to_str().ok())
+    .unwrap_or_default();
+  let ip_prefix = ip_prefix(get_ip(&req.connection_info()));
+  let fingerprint = format!("{:x}", Sha256::digest(format!("{user_agent}\n{ip_prefix}")));
 
-    // Check if the user's email is verified if email verification is turned on
-    // However, skip checking verification if the user is an admin
-    if !local_user_view.person.admin
-      && site_view.local_site.require_email_verification
-      && !local_user_view.local_user.email_verified
-    {
-      return Err(LemmyErrorType::EmailNotVerified)?;
+  let is_new_device = LoginFingerprint::record(
+    &mut context.pool(),
+    local_user_view.local_user.id,
+    &fingerprint,
+  )
+  .await?;
+  if !is_new_device || !local_user_view.local_user.notify_new_logins {
+    return Ok(());
+  }
+
+  if let Some(email) = &local_user_view.local_user.email {
+    let hostname = &context.settings().hostname;
+    let settings_link = format!(
+      "{}/settings",
+      context.settings().get_protocol_and_hostname()
+    );
+    let subject = format!("New login to your account on {hostname}");
+    let body = format!(
+      "<h1>New login</h1><br>\
+      <div>Your account {} was logged into from a new device at {} UTC.</div><br>\
+      <div>Device: {}<br>IP address: {}</div><br>\
+      <div>If this wasn't you, change your password and log out everywhere: \
+      <a href=\"{settings_link}\">{settings_link}</a></div>",
+      local_user_view.person.name,
+      Utc::now().format("%Y-%m-%d %H:%M"),
+      sanitize_html(user_agent),
+      ip_prefix,
+    );
+    // Sent in the background so that a slow mail server doesn't hold up the login
+    let email = email.clone();
+    let name = local_user_view.person.name.clone();
+    let settings = context.settings();
+    spawn_try_task(async move { send_email(&subject, &email, &name, &body, settings).await });
+  }
+  Ok(())
+}
+
+/// The network of the IP address, coarse enough to not identify the user.
+fn ip_prefix(ip: IpAddr) -> String {
+  match ip {
+    IpAddr::V4(ip) => {
+      let [a, b, c, _] = ip.octets();
+      format!("{a}.{b}.{c}.0/24")
+    }
+    IpAddr::V6(ip) => {
+      let [a, b, c, ..] = ip.segments();
+      format!("{a:x}:{b:x}:{c:x}::/48")
     }
+  }
+}
 
-    check_registration_application(&local_user_view, &site_view.local_site, &mut context.pool())
-      .await?;
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
 
-    // Check the totp
-    check_totp_2fa_valid(
-      &local_user_view.local_user.totp_2fa_secret,
-      &data.totp_2fa_token,
-      &site_view.site.name,
-      &local_user_view.person.name,
-    )?;
+  use crate::local_user::login::ip_prefix;
+  use std::net::IpAddr;
 
-    // Return the jwt
-    Ok(LoginResponse {
-      jwt: Some(
-        Claims::jwt(
-          local_user_view.local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
-      ),
-      verify_email_sent: false,
-      registration_created: false,
-    })
+  #[test]
+  fn test_ip_prefix() {
+    let ipv4: IpAddr = "203.0.113.45".parse().unwrap();
+    assert_eq!("203.0.113.0/24", ip_prefix(ipv4));
+    let ipv6: IpAddr = "2001:db8:85a3:8d3:1319:8a2e:370:7348".parse().unwrap();
+    assert_eq!("2001:db8:85a3::/48", ip_prefix(ipv6));
   }
 }
@@ -0,0 +1,33 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{LoginResponse, LogoutEverywhere},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::local_user::LocalUser;
+use lemmy_utils::{claims::Claims, error::LemmyError};
+
+#[tracing::instrument(skip(context))]
+pub async fn logout_everywhere(
+  data: Json<LogoutEverywhere>,
+  context: Data<LemmyContext>,
+) -> Result<Json<LoginResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  let updated_local_user =
+    LocalUser::invalidate_logins(&mut context.pool(), local_user_view.local_user.id).await?;
+
+  // Return a new jwt, so that only the current device stays logged in
+  Ok(Json(LoginResponse {
+    jwt: Some(
+      Claims::jwt(
+        updated_local_user.id.0,
+        &context.secret().jwt_secret,
+        &context.settings().hostname,
+      )?
+      .into(),
+    ),
+    verify_email_sent: false,
+    registration_created: false,
+  }))
+}
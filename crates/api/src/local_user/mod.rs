@@ -3,10 +3,15 @@ pub mod ban_person;
 pub mod block;
 pub mod change_password;
 pub mod change_password_after_reset;
+pub mod clear_profile;
 pub mod get_captcha;
 pub mod list_banned;
+pub mod list_media;
+pub mod list_save_tags;
 pub mod login;
+pub mod logout_everywhere;
 pub mod notifications;
+pub mod remove_content;
 pub mod report_count;
 pub mod reset_password;
 pub mod save_settings;
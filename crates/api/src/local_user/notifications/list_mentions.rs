@@ -27,7 +27,7 @@ impl Perform for GetPersonMentions {
     let person_id = Some(local_user_view.person.id);
     let show_bot_accounts = local_user_view.local_user.show_bot_accounts;
 
-    let mentions = PersonMentionQuery {
+    let mut mentions = PersonMentionQuery {
       recipient_id: person_id,
       my_person_id: person_id,
       sort,
@@ -35,9 +35,13 @@ impl Perform for GetPersonMentions {
       show_bot_accounts,
       page,
       limit,
+      ..Default::default()
     }
     .list(&mut context.pool())
     .await?;
+    for m in &mut mentions {
+      m.comment.redact_nsfw(Some(&local_user_view.local_user));
+    }
 
     Ok(GetPersonMentionsResponse { mentions })
   }
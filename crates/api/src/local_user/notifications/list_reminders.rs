@@ -0,0 +1,27 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{GetReminders, GetRemindersResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_views_actor::reminder_view::ReminderQuery;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_reminders(
+  data: Query<GetReminders>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetRemindersResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  let reminders = ReminderQuery {
+    recipient_id: local_user_view.person.id,
+    unread_only: data.unread_only.unwrap_or_default(),
+    page: data.page,
+    limit: data.limit,
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  Ok(Json(GetRemindersResponse { reminders }))
+}
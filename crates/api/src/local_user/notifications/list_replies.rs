@@ -24,7 +24,7 @@ impl Perform for GetReplies {
     let person_id = Some(local_user_view.person.id);
     let show_bot_accounts = local_user_view.local_user.show_bot_accounts;
 
-    let replies = CommentReplyQuery {
+    let mut replies = CommentReplyQuery {
       recipient_id: person_id,
       my_person_id: person_id,
       sort,
@@ -32,9 +32,13 @@ impl Perform for GetReplies {
       show_bot_accounts,
       page,
       limit,
+      ..Default::default()
     }
     .list(&mut context.pool())
     .await?;
+    for r in &mut replies {
+      r.comment.redact_nsfw(Some(&local_user_view.local_user));
+    }
 
     Ok(GetRepliesResponse { replies })
   }
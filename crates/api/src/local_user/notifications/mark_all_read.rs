@@ -9,6 +9,7 @@ use lemmy_db_schema::source::{
   comment_reply::CommentReply,
   person_mention::PersonMention,
   private_message::PrivateMessage,
+  reminder::Reminder,
 };
 use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
 
@@ -37,6 +38,11 @@ impl Perform for MarkAllAsRead {
       .await
       .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
 
+    // Mark all reminders as read
+    Reminder::mark_all_as_read(&mut context.pool(), person_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdateReminder)?;
+
     Ok(GetRepliesResponse { replies: vec![] })
   }
 }
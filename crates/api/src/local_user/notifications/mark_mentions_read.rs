@@ -0,0 +1,43 @@
+use super::MAX_MARK_AS_READ_BATCH;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{MarkPersonMentionsAsRead, PersonMentionsResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::person_mention::PersonMention;
+use lemmy_db_views_actor::person_mention_view::PersonMentionQuery;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn mark_mentions_as_read(
+  data: Json<MarkPersonMentionsAsRead>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PersonMentionsResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+
+  if data.mention_ids.len() > MAX_MARK_AS_READ_BATCH {
+    return Err(LemmyErrorType::TooManyNotificationsInBatch)?;
+  }
+
+  // Fails for the whole batch if any of the mentions is for someone else
+  PersonMention::mark_many_as_read(&mut context.pool(), &data.mention_ids, person_id, data.read)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
+
+  let person_mention_views = PersonMentionQuery {
+    recipient_id: Some(person_id),
+    my_person_id: Some(person_id),
+    ids: Some(data.mention_ids.clone()),
+    show_bot_accounts: true,
+    ..Default::default()
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  Ok(Json(PersonMentionsResponse {
+    person_mention_views,
+  }))
+}
@@ -0,0 +1,39 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{MarkReminderAsRead, ReminderResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::reminder::{Reminder, ReminderUpdateForm},
+  traits::Crud,
+};
+use lemmy_db_views_actor::structs::ReminderView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn mark_reminder_as_read(
+  data: Json<MarkReminderAsRead>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ReminderResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  let reminder = Reminder::read(&mut context.pool(), data.reminder_id).await?;
+  if local_user_view.person.id != reminder.recipient_id {
+    return Err(LemmyErrorType::CouldntUpdateReminder)?;
+  }
+
+  Reminder::update(
+    &mut context.pool(),
+    reminder.id,
+    &ReminderUpdateForm {
+      read: Some(data.read),
+    },
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdateReminder)?;
+
+  let reminder_view = ReminderView::read(&mut context.pool(), reminder.id).await?;
+
+  Ok(Json(ReminderResponse { reminder_view }))
+}
@@ -0,0 +1,43 @@
+use super::MAX_MARK_AS_READ_BATCH;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{CommentRepliesResponse, MarkCommentRepliesAsRead},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::comment_reply::CommentReply;
+use lemmy_db_views_actor::comment_reply_view::CommentReplyQuery;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn mark_replies_as_read(
+  data: Json<MarkCommentRepliesAsRead>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommentRepliesResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+
+  if data.reply_ids.len() > MAX_MARK_AS_READ_BATCH {
+    return Err(LemmyErrorType::TooManyNotificationsInBatch)?;
+  }
+
+  // Fails for the whole batch if any of the replies is for someone else
+  CommentReply::mark_many_as_read(&mut context.pool(), &data.reply_ids, person_id, data.read)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
+
+  let comment_reply_views = CommentReplyQuery {
+    recipient_id: Some(person_id),
+    my_person_id: Some(person_id),
+    ids: Some(data.reply_ids.clone()),
+    show_bot_accounts: true,
+    ..Default::default()
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  Ok(Json(CommentRepliesResponse {
+    comment_reply_views,
+  }))
+}
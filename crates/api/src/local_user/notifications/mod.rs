@@ -1,6 +1,13 @@
 pub mod list_mentions;
+pub mod list_reminders;
 pub mod list_replies;
 pub mod mark_all_read;
 pub mod mark_mention_read;
+pub mod mark_mentions_read;
+pub mod mark_reminder_read;
+pub mod mark_replies_read;
 pub mod mark_reply_read;
 pub mod unread_count;
+
+/// How many replies or mentions can be marked as read in a single request.
+const MAX_MARK_AS_READ_BATCH: usize = 100;
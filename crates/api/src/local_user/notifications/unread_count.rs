@@ -6,7 +6,7 @@ use lemmy_api_common::{
   utils::local_user_view_from_jwt,
 };
 use lemmy_db_views::structs::PrivateMessageView;
-use lemmy_db_views_actor::structs::{CommentReplyView, PersonMentionView};
+use lemmy_db_views_actor::structs::{CommentReplyView, PersonMentionView, ReminderView};
 use lemmy_utils::error::LemmyError;
 
 #[async_trait::async_trait(?Send)]
@@ -27,10 +27,13 @@ impl Perform for GetUnreadCount {
     let private_messages =
       PrivateMessageView::get_unread_messages(&mut context.pool(), person_id).await?;
 
+    let reminders = ReminderView::get_unread_reminders(&mut context.pool(), person_id).await?;
+
     Ok(Self::Response {
       replies,
       mentions,
       private_messages,
+      reminders,
     })
   }
 }
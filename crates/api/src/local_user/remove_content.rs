@@ -0,0 +1,65 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{RemovePersonContent, RemovePersonContentResponse},
+  post::RemovePost,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{check_mod_action_reason, is_admin, local_user_view_from_jwt, sanitize_html_opt},
+};
+use lemmy_db_schema::source::{local_site::LocalSite, person::Person};
+use lemmy_utils::{error::LemmyError, utils::validation::is_valid_body_field};
+
+/// Removes the posts and comments of a person in local communities. Each removal is logged and
+/// federated like a removal by a mod. Content which is removed already is skipped, so running
+/// it again only removes what was added in between.
+#[tracing::instrument(skip(context))]
+pub async fn remove_person_content(
+  data: Json<RemovePersonContent>,
+  context: Data<LemmyContext>,
+) -> Result<Json<RemovePersonContentResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+  is_valid_body_field(&data.reason, false)?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_mod_action_reason(&data.reason, &local_site)?;
+
+  let admin = local_user_view.person;
+  let reason = sanitize_html_opt(&data.reason);
+
+  let (removed_posts, removed_comments) = Person::remove_content_in_local_communities(
+    &mut context.pool(),
+    data.person_id,
+    admin.id,
+    reason.clone(),
+  )
+  .await?;
+  let removed_post_count = removed_posts.len();
+  let removed_comment_count = removed_comments.len();
+
+  for post in removed_posts {
+    let remove_post = RemovePost {
+      post_id: post.id,
+      removed: true,
+      reason: reason.clone(),
+      auth: data.auth.clone(),
+    };
+    ActivityChannel::submit_activity(
+      SendActivityData::RemovePost(post, admin.clone(), remove_post),
+      &context,
+    )
+    .await?;
+  }
+  for (comment, community) in removed_comments {
+    ActivityChannel::submit_activity(
+      SendActivityData::RemoveComment(comment, admin.clone(), community, reason.clone()),
+      &context,
+    )
+    .await?;
+  }
+
+  Ok(Json(RemovePersonContentResponse {
+    removed_posts: i64::try_from(removed_post_count)?,
+    removed_comments: i64::try_from(removed_comment_count)?,
+  }))
+}
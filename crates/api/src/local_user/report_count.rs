@@ -5,7 +5,12 @@ use lemmy_api_common::{
   person::{GetReportCount, GetReportCountResponse},
   utils::local_user_view_from_jwt,
 };
-use lemmy_db_views::structs::{CommentReportView, PostReportView, PrivateMessageReportView};
+use lemmy_db_views::structs::{
+  CommentReportView,
+  PersonReportView,
+  PostReportView,
+  PrivateMessageReportView,
+};
 use lemmy_utils::error::LemmyError;
 
 #[async_trait::async_trait(?Send)]
@@ -31,10 +36,13 @@ impl Perform for GetReportCount {
     let post_reports =
       PostReportView::get_report_count(&mut context.pool(), person_id, admin, community_id).await?;
 
-    let private_message_reports = if admin && community_id.is_none() {
-      Some(PrivateMessageReportView::get_report_count(&mut context.pool()).await?)
+    let (private_message_reports, person_reports) = if admin && community_id.is_none() {
+      (
+        Some(PrivateMessageReportView::get_report_count(&mut context.pool()).await?),
+        Some(PersonReportView::get_report_count(&mut context.pool()).await?),
+      )
     } else {
-      None
+      (None, None)
     };
 
     Ok(GetReportCountResponse {
@@ -42,6 +50,7 @@ impl Perform for GetReportCount {
       comment_reports,
       post_reports,
       private_message_reports,
+      person_reports,
     })
   }
 }
@@ -0,0 +1,118 @@
+use super::redirect_uri;
+use actix_web::{
+  http::header::LOCATION,
+  web::{Data, Query},
+  HttpResponse,
+};
+use lemmy_api_common::{
+  context::LemmyContext,
+  oauth::AuthorizeOAuth,
+  request::fetch_openid_configuration,
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::{
+    oauth_account::{OAuthState, OAuthStateInsertForm},
+    oauth_provider::OAuthProvider,
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use url::Url;
+use uuid::Uuid;
+
+#[tracing::instrument(skip(context))]
+pub async fn authorize_oauth(
+  data: Query<AuthorizeOAuth>,
+  context: Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let provider = OAuthProvider::read(&mut context.pool(), data.provider_id).await?;
+  if !provider.enabled {
+    return Err(LemmyErrorType::OauthProviderDisabled)?;
+  }
+
+  // Only a logged in user can link an account to theirs
+  let local_user_id = match &data.auth {
+    Some(auth) => Some(
+      local_user_view_from_jwt(auth, &context)
+        .await?
+        .local_user
+        .id,
+    ),
+    None => None,
+  };
+
+  let config = fetch_openid_configuration(context.client(), &provider.issuer).await?;
+  let state_form = OAuthStateInsertForm {
+    oauth_provider_id: provider.id,
+    local_user_id,
+  };
+  let state = OAuthState::create(&mut context.pool(), &state_form).await?;
+
+  let url = authorize_url(
+    &config.authorization_endpoint,
+    &provider,
+    state.state,
+    &redirect_uri(context.settings())?,
+  );
+  Ok(
+    HttpResponse::Found()
+      .insert_header((LOCATION, url.as_str()))
+      .finish(),
+  )
+}
+
+fn authorize_url(
+  authorization_endpoint: &Url,
+  provider: &OAuthProvider,
+  state: Uuid,
+  redirect_uri: &Url,
+) -> Url {
+  let mut url = authorization_endpoint.clone();
+  url
+    .query_pairs_mut()
+    .append_pair("response_type", "code")
+    .append_pair("client_id", &provider.client_id)
+    .append_pair("redirect_uri", redirect_uri.as_str())
+    .append_pair("scope", &provider.scopes)
+    .append_pair("state", &state.to_string());
+  url
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+
+  use super::authorize_url;
+  use lemmy_db_schema::{newtypes::OAuthProviderId, source::oauth_provider::OAuthProvider};
+  use url::Url;
+  use uuid::Uuid;
+
+  #[test]
+  fn test_authorize_url() {
+    let provider = OAuthProvider {
+      id: OAuthProviderId(1),
+      display_name: "Example".to_string(),
+      issuer: "https://id.example.com".to_string(),
+      client_id: "lemmy".to_string(),
+      client_secret: "secret".to_string(),
+      scopes: "openid email".to_string(),
+      enabled: true,
+      published: Default::default(),
+      updated: None,
+    };
+    let endpoint = Url::parse("https://id.example.com/authorize?prompt=login").unwrap();
+    let redirect_uri = Url::parse("https://lemmy.tld/oauth/callback").unwrap();
+    let state = Uuid::nil();
+
+    let url = authorize_url(&endpoint, &provider, state, &redirect_uri);
+    assert_eq!(
+      "https://id.example.com/authorize?prompt=login&response_type=code&client_id=lemmy\
+      &redirect_uri=https%3A%2F%2Flemmy.tld%2Foauth%2Fcallback&scope=openid+email\
+      &state=00000000-0000-0000-0000-000000000000",
+      url.as_str()
+    );
+    // The secret is never sent to the browser
+    assert!(!url.as_str().contains("secret"));
+  }
+}
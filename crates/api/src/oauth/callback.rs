@@ -0,0 +1,304 @@
+use super::{redirect_uri, take_oauth_state};
+use crate::local_user::login::check_login_fingerprint;
+use activitypub_federation::http_signatures::generate_actor_keypair;
+use actix_web::{
+  web::{Data, Json},
+  HttpRequest,
+};
+use lemmy_api_common::{
+  context::LemmyContext,
+  oauth::OAuthCallback,
+  person::LoginResponse,
+  request::{fetch_oauth_user_info, OAuthUserInfo},
+  utils::{
+    check_deletion_not_pending,
+    check_registration_application,
+    check_user_valid,
+    generate_inbox_url,
+    generate_local_apub_endpoint,
+    generate_shared_inbox_url,
+    local_site_to_slur_regex,
+    send_new_applicant_email_to_admins,
+    EndpointType,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    local_site::LocalSite,
+    local_user::{LocalUser, LocalUserInsertForm},
+    oauth_account::{OAuthAccount, OAuthAccountInsertForm},
+    oauth_provider::OAuthProvider,
+    person::{Person, PersonInsertForm},
+    registration_application::{RegistrationApplication, RegistrationApplicationInsertForm},
+  },
+  traits::Crud,
+  RegistrationMode,
+};
+use lemmy_db_views::structs::{LocalUserView, SiteView};
+use lemmy_utils::{
+  claims::Claims,
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{slurs::check_slurs, validation::is_valid_actor_name},
+};
+use uuid::Uuid;
+
+/// Logs in with an account at an OpenID Connect provider. Users who logged in with it before get
+/// their linked account, and so do users whose verified email is the one which the provider
+/// verified. Everyone else gets a new account if OAuth registration is enabled.
+///
+/// Accounts whose email isn't verified are never linked automatically, as the address may not
+/// belong to their owner. These users have to log in and link the provider account instead.
+#[tracing::instrument(skip(context))]
+pub async fn oauth_callback(
+  data: Json<OAuthCallback>,
+  req: HttpRequest,
+  context: Data<LemmyContext>,
+) -> Result<Json<LoginResponse>, LemmyError> {
+  let (provider, state) = take_oauth_state(&data.state, &context).await?;
+  // Linking goes through its own endpoint, which requires the password
+  if state.local_user_id.is_some() {
+    return Err(LemmyErrorType::OauthStateInvalid)?;
+  }
+
+  let user_info = fetch_oauth_user_info(
+    context.client(),
+    &provider,
+    &data.code,
+    &redirect_uri(context.settings())?,
+  )
+  .await?;
+
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let account =
+    OAuthAccount::read_for_provider(&mut context.pool(), provider.id, &user_info.sub).await?;
+  let local_user_view = match account {
+    Some(account) => LocalUserView::read(&mut context.pool(), account.local_user_id).await?,
+    None => {
+      let email = verified_email(&user_info)?;
+      match LocalUserView::find_by_email(&mut context.pool(), &email)
+        .await
+        .ok()
+      {
+        Some(local_user_view) if local_user_view.local_user.email_verified => {
+          link_account(&provider, &user_info, &local_user_view, &context).await?;
+          local_user_view
+        }
+        Some(_) => return Err(LemmyErrorType::OauthAccountNeedsLinking)?,
+        None => {
+          let answer = data.answer.as_deref();
+          let local_user_view =
+            register_oauth_user(&provider, &user_info, email, answer, &site_view, &context).await?;
+          // Like with a normal registration, the account can't log in until it is accepted
+          if !local_user_view.local_user.accepted_application {
+            return Ok(Json(LoginResponse {
+              jwt: None,
+              verify_email_sent: false,
+              registration_created: true,
+            }));
+          }
+          local_user_view
+        }
+      }
+    }
+  };
+
+  check_user_valid(
+    local_user_view.person.banned,
+    local_user_view.person.ban_expires,
+    local_user_view.person.deleted,
+  )?;
+  check_deletion_not_pending(&local_user_view.local_user)?;
+  check_registration_application(&local_user_view, &site_view.local_site, &mut context.pool())
+    .await?;
+  check_login_fingerprint(&req, &local_user_view, &context).await?;
+
+  Ok(Json(LoginResponse {
+    jwt: Some(
+      Claims::jwt(
+        local_user_view.local_user.id.0,
+        &context.secret().jwt_secret,
+        &context.settings().hostname,
+      )?
+      .into(),
+    ),
+    verify_email_sent: false,
+    registration_created: false,
+  }))
+}
+
+/// The email from the provider, which has to be verified there to identify the user.
+fn verified_email(user_info: &OAuthUserInfo) -> Result<String, LemmyError> {
+  match &user_info.email {
+    Some(email) if user_info.email_verified => Ok(email.clone()),
+    _ => Err(LemmyErrorType::OauthEmailNotVerified)?,
+  }
+}
+
+async fn link_account(
+  provider: &OAuthProvider,
+  user_info: &OAuthUserInfo,
+  local_user_view: &LocalUserView,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let form = OAuthAccountInsertForm {
+    local_user_id: local_user_view.local_user.id,
+    oauth_provider_id: provider.id,
+    oauth_user_id: user_info.sub.clone(),
+  };
+  OAuthAccount::create(&mut context.pool(), &form).await?;
+  Ok(())
+}
+
+async fn register_oauth_user(
+  provider: &OAuthProvider,
+  user_info: &OAuthUserInfo,
+  email: String,
+  answer: Option<&str>,
+  site_view: &SiteView,
+  context: &LemmyContext,
+) -> Result<LocalUserView, LemmyError> {
+  let local_site = &site_view.local_site;
+  if !local_site.site_setup
+    || !local_site.oauth_registration
+    || local_site.registration_mode == RegistrationMode::Closed
+  {
+    return Err(LemmyErrorType::OauthRegistrationClosed)?;
+  }
+  let require_registration_application =
+    local_site.registration_mode == RegistrationMode::RequireApplication;
+  let slur_regex = local_site_to_slur_regex(local_site);
+  let answer = match answer {
+    Some(answer) if require_registration_application => {
+      check_slurs(answer, &slur_regex)?;
+      Some(answer.to_string())
+    }
+    None if require_registration_application => {
+      return Err(LemmyErrorType::RegistrationApplicationAnswerRequired)?
+    }
+    _ => None,
+  };
+
+  let username = available_username(user_info, local_site, context).await?;
+  let actor_keypair = generate_actor_keypair()?;
+  let actor_id = generate_local_apub_endpoint(
+    EndpointType::Person,
+    &username,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let person_form = PersonInsertForm::builder()
+    .name(username.clone())
+    .actor_id(Some(actor_id.clone()))
+    .private_key(Some(actor_keypair.private_key))
+    .public_key(actor_keypair.public_key)
+    .inbox_url(Some(generate_inbox_url(&actor_id)?))
+    .shared_inbox_url(Some(generate_shared_inbox_url(&actor_id)?))
+    .instance_id(site_view.site.instance_id)
+    .build();
+  let inserted_person = Person::create(&mut context.pool(), &person_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::UserAlreadyExists)?;
+
+  // The provider has verified the email. The random password can be replaced by resetting it.
+  let local_user_form = LocalUserInsertForm::builder()
+    .person_id(inserted_person.id)
+    .email(Some(email))
+    .password_encrypted(Uuid::new_v4().to_string())
+    .email_verified(Some(true))
+    .accepted_application(Some(!require_registration_application))
+    .default_listing_type(Some(local_site.default_post_listing_type))
+    .build();
+  let inserted_local_user = LocalUser::create(&mut context.pool(), &local_user_form).await?;
+
+  if let Some(answer) = answer {
+    let form = RegistrationApplicationInsertForm {
+      local_user_id: inserted_local_user.id,
+      answer,
+    };
+    RegistrationApplication::create(&mut context.pool(), &form).await?;
+  }
+  if local_site.application_email_admins {
+    send_new_applicant_email_to_admins(&username, &mut context.pool(), context.settings()).await?;
+  }
+
+  let local_user_view = LocalUserView::read(&mut context.pool(), inserted_local_user.id).await?;
+  link_account(provider, user_info, &local_user_view, context).await?;
+  Ok(local_user_view)
+}
+
+/// Picks a name for the new account based on the one at the provider, with a number appended
+/// if it is already taken.
+async fn available_username(
+  user_info: &OAuthUserInfo,
+  local_site: &LocalSite,
+  context: &LemmyContext,
+) -> Result<String, LemmyError> {
+  let max_length = local_site.actor_name_max_length as usize;
+  let slur_regex = local_site_to_slur_regex(local_site);
+  let base = username_base(user_info, max_length);
+  let base = if check_slurs(&base, &slur_regex).is_ok() {
+    base
+  } else {
+    "user".to_string()
+  };
+
+  for suffix in std::iter::once(String::new()).chain((1..100).map(|n| n.to_string())) {
+    let mut name = base.clone();
+    name.truncate(max_length.saturating_sub(suffix.len()));
+    name.push_str(&suffix);
+    if is_valid_actor_name(&name, max_length).is_err() {
+      continue;
+    }
+    if LocalUserView::read_from_name(&mut context.pool(), &name)
+      .await
+      .is_err()
+    {
+      return Ok(name);
+    }
+  }
+  Err(LemmyErrorType::UserAlreadyExists)?
+}
+
+fn username_base(user_info: &OAuthUserInfo, max_length: usize) -> String {
+  let email_name = user_info.email.as_deref().and_then(|e| e.split('@').next());
+  let name: String = user_info
+    .preferred_username
+    .as_deref()
+    .or(email_name)
+    .unwrap_or_default()
+    .chars()
+    .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+    .take(max_length)
+    .collect();
+  if name.len() < 3 {
+    "user".to_string()
+  } else {
+    name
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::username_base;
+  use lemmy_api_common::request::OAuthUserInfo;
+
+  #[test]
+  fn test_username_base() {
+    let user_info = |preferred_username: Option<&str>, email: Option<&str>| OAuthUserInfo {
+      sub: "1234".to_string(),
+      email: email.map(ToString::to_string),
+      email_verified: true,
+      preferred_username: preferred_username.map(ToString::to_string),
+    };
+    assert_eq!(
+      "jane_doe",
+      username_base(&user_info(Some("jane_doe!"), None), 20)
+    );
+    assert_eq!(
+      "jdoe",
+      username_base(&user_info(None, Some("j.doe@example.com")), 20)
+    );
+    assert_eq!("jane", username_base(&user_info(Some("jane_doe"), None), 4));
+    assert_eq!("user", username_base(&user_info(Some("ж"), None), 20));
+    assert_eq!("user", username_base(&user_info(None, None), 20));
+  }
+}
@@ -0,0 +1,63 @@
+use super::{redirect_uri, take_oauth_state};
+use actix_web::web::{Data, Json};
+use bcrypt::verify;
+use lemmy_api_common::{
+  context::LemmyContext,
+  oauth::{LinkOAuthAccount, LinkOAuthAccountResponse},
+  request::fetch_oauth_user_info,
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::oauth_account::{OAuthAccount, OAuthAccountInsertForm};
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+
+/// Links an account at an OpenID Connect provider to the logged in user, so that they can log in
+/// with it from then on.
+#[tracing::instrument(skip(context))]
+pub async fn link_oauth_account(
+  data: Json<LinkOAuthAccount>,
+  context: Data<LemmyContext>,
+) -> Result<Json<LinkOAuthAccountResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  // Confirm it with the current password, before the authorization is used up
+  let valid: bool = verify(
+    &data.password,
+    &local_user_view.local_user.password_encrypted,
+  )
+  .unwrap_or(false);
+  if !valid {
+    return Err(LemmyErrorType::IncorrectLogin)?;
+  }
+
+  // The authorization has to be started by the same user
+  let (provider, state) = take_oauth_state(&data.state, &context).await?;
+  if state.local_user_id != Some(local_user_view.local_user.id) {
+    return Err(LemmyErrorType::OauthStateInvalid)?;
+  }
+
+  let user_info = fetch_oauth_user_info(
+    context.client(),
+    &provider,
+    &data.code,
+    &redirect_uri(context.settings())?,
+  )
+  .await?;
+  if OAuthAccount::read_for_provider(&mut context.pool(), provider.id, &user_info.sub)
+    .await?
+    .is_some()
+  {
+    return Err(LemmyErrorType::OauthAccountAlreadyLinked)?;
+  }
+
+  let form = OAuthAccountInsertForm {
+    local_user_id: local_user_view.local_user.id,
+    oauth_provider_id: provider.id,
+    oauth_user_id: user_info.sub,
+  };
+  OAuthAccount::create(&mut context.pool(), &form).await?;
+
+  Ok(Json(LinkOAuthAccountResponse {
+    oauth_provider_id: provider.id,
+    success: true,
+  }))
+}
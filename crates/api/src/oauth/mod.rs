@@ -0,0 +1,40 @@
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  source::{oauth_account::OAuthState, oauth_provider::OAuthProvider},
+  traits::Crud,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  settings::structs::Settings,
+};
+use url::Url;
+use uuid::Uuid;
+
+pub mod authorize;
+pub mod callback;
+pub mod link;
+
+/// The frontend page which the provider redirects back to. It passes the code and state on to
+/// the callback or link endpoint.
+fn redirect_uri(settings: &Settings) -> Result<Url, LemmyError> {
+  Ok(Url::parse(&format!(
+    "{}/oauth/callback",
+    settings.get_protocol_and_hostname()
+  ))?)
+}
+
+/// Completes a pending authorization, which can only be done once.
+async fn take_oauth_state(
+  state: &str,
+  context: &LemmyContext,
+) -> Result<(OAuthProvider, OAuthState), LemmyError> {
+  let state = Uuid::parse_str(state).with_lemmy_type(LemmyErrorType::OauthStateInvalid)?;
+  let state = OAuthState::take(&mut context.pool(), state)
+    .await
+    .with_lemmy_type(LemmyErrorType::OauthStateInvalid)?;
+  let provider = OAuthProvider::read(&mut context.pool(), state.oauth_provider_id).await?;
+  if !provider.enabled {
+    return Err(LemmyErrorType::OauthProviderDisabled)?;
+  }
+  Ok((provider, state))
+}
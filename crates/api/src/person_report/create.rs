@@ -0,0 +1,61 @@
+use crate::check_report_reason;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{CreatePersonReport, PersonReportResponse},
+  utils::{local_user_view_from_jwt, sanitize_html, send_new_report_email_to_admins},
+};
+use lemmy_db_schema::{
+  source::{
+    local_site::LocalSite,
+    person::Person,
+    person_report::{PersonReport, PersonReportForm},
+  },
+  traits::{Crud, Reportable},
+};
+use lemmy_db_views::structs::PersonReportView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+/// Reports the profile of a person, which is handled by the admins
+#[tracing::instrument(skip(context))]
+pub async fn create_person_report(
+  data: Json<CreatePersonReport>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PersonReportResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  let reason = sanitize_html(data.reason.trim());
+  check_report_reason(&reason, &local_site)?;
+
+  let person = Person::read(&mut context.pool(), data.person_id).await?;
+
+  // Keep the reported fields, in case they are changed before an admin looks at the report
+  let report_form = PersonReportForm {
+    creator_id: local_user_view.person.id,
+    person_id: person.id,
+    original_display_name: person.display_name,
+    original_bio: person.bio,
+    reason,
+  };
+
+  let report = PersonReport::report(&mut context.pool(), &report_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntCreateReport)?;
+
+  let person_report_view = PersonReportView::read(&mut context.pool(), report.id).await?;
+
+  // Email the admins
+  if local_site.reports_email_admins {
+    send_new_report_email_to_admins(
+      &person_report_view.creator.name,
+      &person_report_view.person.name,
+      &mut context.pool(),
+      context.settings(),
+    )
+    .await?;
+  }
+
+  Ok(Json(PersonReportResponse { person_report_view }))
+}
@@ -0,0 +1,29 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListPersonReports, ListPersonReportsResponse},
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_views::person_report_view::PersonReportQuery;
+use lemmy_utils::error::LemmyError;
+
+/// Lists the reports of user profiles. Only admins can see these.
+#[tracing::instrument(skip(context))]
+pub async fn list_person_reports(
+  data: Query<ListPersonReports>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListPersonReportsResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+
+  let person_reports = PersonReportQuery {
+    unresolved_only: data.unresolved_only.unwrap_or_default(),
+    page: data.page,
+    limit: data.limit,
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  Ok(Json(ListPersonReportsResponse { person_reports }))
+}
@@ -0,0 +1,36 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{PersonReportResponse, ResolvePersonReport},
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{source::person_report::PersonReport, traits::Reportable};
+use lemmy_db_views::structs::PersonReportView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+/// Resolves or reopens a report of a user profile
+#[tracing::instrument(skip(context))]
+pub async fn resolve_person_report(
+  data: Json<ResolvePersonReport>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PersonReportResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+
+  let report_id = data.report_id;
+  let person_id = local_user_view.person.id;
+  if data.resolved {
+    PersonReport::resolve(&mut context.pool(), report_id, person_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntResolveReport)?;
+  } else {
+    PersonReport::unresolve(&mut context.pool(), report_id, person_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntResolveReport)?;
+  }
+
+  let person_report_view = PersonReportView::read(&mut context.pool(), report_id).await?;
+
+  Ok(Json(PersonReportResponse { person_report_view }))
+}
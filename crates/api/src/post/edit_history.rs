@@ -0,0 +1,32 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{GetPostEditHistory, GetPostEditHistoryResponse},
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{edit_history::PostEditHistory, post::Post},
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn get_post_edit_history(
+  data: Query<GetPostEditHistory>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetPostEditHistoryResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+
+  let post = Post::read(&mut context.pool(), data.post_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
+  if !Post::is_post_creator(person_id, post.creator_id) {
+    is_mod_or_admin(&mut context.pool(), person_id, post.community_id).await?;
+  }
+
+  let revisions = PostEditHistory::list_for_post(&mut context.pool(), post.id).await?;
+
+  Ok(Json(GetPostEditHistoryResponse { revisions }))
+}
@@ -8,20 +8,20 @@ use lemmy_api_common::{
   utils::{
     check_community_ban,
     check_community_deleted_or_removed,
-    is_admin,
     is_mod_or_admin,
     local_user_view_from_jwt,
   },
 };
 use lemmy_db_schema::{
   source::{
+    local_site::LocalSite,
     moderator::{ModFeaturePost, ModFeaturePostForm},
     post::{Post, PostUpdateForm},
   },
   traits::Crud,
   PostFeatureType,
 };
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
 
 #[tracing::instrument(skip(context))]
 pub async fn feature_post(
@@ -50,7 +50,18 @@ pub async fn feature_post(
     )
     .await?;
   } else {
-    is_admin(&local_user_view)?;
+    // Featuring to the local frontpage is reserved for admins
+    if !local_user_view.person.admin {
+      return Err(LemmyErrorType::OnlyAdminsCanFeatureLocalPosts)?;
+    }
+    let local_site = LocalSite::read(&mut context.pool()).await?;
+    if data.featured
+      && !orig_post.featured_local
+      && Post::count_featured_local(&mut context.pool()).await?
+        >= i64::from(local_site.featured_local_posts_max)
+    {
+      return Err(LemmyErrorType::TooManyFeaturedLocalPosts)?;
+    }
   }
 
   // Update the post
@@ -79,11 +90,14 @@ pub async fn feature_post(
   ModFeaturePost::create(&mut context.pool(), &form).await?;
 
   let person_id = local_user_view.person.id;
-  ActivityChannel::submit_activity(
-    SendActivityData::FeaturePost(post, local_user_view.person, data.featured),
-    &context,
-  )
-  .await?;
+  // Only community features are federated, local ones are specific to this instance
+  if data.feature_type == PostFeatureType::Community {
+    ActivityChannel::submit_activity(
+      SendActivityData::FeaturePost(post, local_user_view.person, data.featured),
+      &context,
+    )
+    .await?;
+  }
 
   build_post_response(&context, orig_post.community_id, person_id, post_id).await
 }
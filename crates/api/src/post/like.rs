@@ -6,9 +6,11 @@ use lemmy_api_common::{
   post::{CreatePostLike, PostResponse},
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
+    check_allowed_to_vote,
     check_community_ban,
     check_community_deleted_or_removed,
     check_downvotes_enabled,
+    check_person_block,
     local_user_view_from_jwt,
     mark_post_as_read,
   },
@@ -46,6 +48,19 @@ pub async fn like_post(
   )
   .await?;
   check_community_deleted_or_removed(post.community_id, &mut context.pool()).await?;
+  let community = Community::read(&mut context.pool(), post.community_id).await?;
+
+  // Users can't vote on posts of someone who blocked them, or in communities whose voting
+  // requirements they don't meet, but can remove existing votes
+  if data.score != 0 {
+    check_person_block(
+      local_user_view.person.id,
+      post.creator_id,
+      &mut context.pool(),
+    )
+    .await?;
+    check_allowed_to_vote(&local_user_view.person, &community, &mut context.pool()).await?;
+  }
 
   let like_form = PostLikeForm {
     post_id: data.post_id,
@@ -73,7 +88,7 @@ pub async fn like_post(
     SendActivityData::LikePostOrComment(
       post.ap_id,
       local_user_view.person.clone(),
-      Community::read(&mut context.pool(), post.community_id).await?,
+      community,
       data.score,
     ),
     &context,
@@ -1,6 +1,9 @@
+pub mod edit_history;
 pub mod feature;
 pub mod get_link_metadata;
 pub mod like;
 pub mod lock;
 pub mod mark_read;
+pub mod request_archive;
 pub mod save;
+pub mod vote_poll;
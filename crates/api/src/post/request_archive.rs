@@ -0,0 +1,48 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  build_response::build_post_response,
+  context::LemmyContext,
+  post::{PostResponse, RequestPostArchive},
+  utils::{archivable_post_url, archive_post_url, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{local_site::LocalSite, post::Post},
+  traits::Crud,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType},
+  spawn_try_task,
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn request_post_archive(
+  data: Json<RequestPostArchive>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PostResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  if !local_site.enable_url_archiving {
+    return Err(LemmyErrorType::UrlArchivingDisabled)?;
+  }
+
+  let post_id = data.post_id;
+  let post = Post::read(&mut context.pool(), post_id).await?;
+  let url = archivable_post_url(&post, &local_site, context.settings())
+    .ok_or(LemmyErrorType::PostUrlNotArchivable)?;
+
+  // Snapshots can take a while, so the archive url is filled in later
+  spawn_try_task(archive_post_url(
+    post_id,
+    url,
+    LemmyContext::clone(&context),
+  ));
+
+  build_post_response(
+    &context,
+    post.community_id,
+    local_user_view.person.id,
+    post_id,
+  )
+  .await
+}
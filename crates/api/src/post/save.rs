@@ -10,7 +10,10 @@ use lemmy_db_schema::{
   traits::Saveable,
 };
 use lemmy_db_views::structs::PostView;
-use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{time::naive_from_unix, validation::clean_save_tag},
+};
 
 #[async_trait::async_trait(?Send)]
 impl Perform for SavePost {
@@ -24,6 +27,8 @@ impl Perform for SavePost {
     let post_saved_form = PostSavedForm {
       post_id: data.post_id,
       person_id: local_user_view.person.id,
+      remind_at: data.remind_at.map(naive_from_unix),
+      tag: clean_save_tag(&data.tag)?,
     };
 
     if data.save {
@@ -43,6 +48,9 @@ impl Perform for SavePost {
     // Mark the post as read
     mark_post_as_read(person_id, post_id, &mut context.pool()).await?;
 
-    Ok(PostResponse { post_view })
+    Ok(PostResponse {
+      post_view,
+      community_mentions: Vec::new(),
+    })
   }
 }
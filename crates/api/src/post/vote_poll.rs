@@ -0,0 +1,68 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{PollResponse, VotePoll},
+  utils::{check_community_ban, check_community_deleted_or_removed, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    poll::{Poll, PollOption, PollVote},
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::PollView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use std::collections::HashSet;
+
+#[tracing::instrument(skip(context))]
+pub async fn vote_poll(
+  data: Json<VotePoll>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PollResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+
+  let post = Post::read(&mut context.pool(), data.post_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
+  check_community_ban(person_id, post.community_id, &mut context.pool()).await?;
+  check_community_deleted_or_removed(post.community_id, &mut context.pool()).await?;
+  if post.deleted || post.removed {
+    Err(LemmyErrorType::CouldntFindPost)?;
+  }
+
+  let poll = Poll::read_for_post(&mut context.pool(), post.id)
+    .await?
+    .ok_or(LemmyErrorType::InvalidPollVote)?;
+  if poll.has_ended() {
+    Err(LemmyErrorType::PollEnded)?;
+  }
+
+  // The options have to belong to this poll, and only one can be picked unless the poll allows
+  // multiple
+  let options = PollOption::list_for_poll(&mut context.pool(), poll.id).await?;
+  let unique = data.option_ids.iter().collect::<HashSet<_>>();
+  let valid = unique.len() == data.option_ids.len()
+    && data
+      .option_ids
+      .iter()
+      .all(|id| options.iter().any(|o| &o.id == id))
+    && (poll.allow_multiple || data.option_ids.len() <= 1);
+  if !valid {
+    Err(LemmyErrorType::InvalidPollVote)?;
+  }
+
+  PollVote::set(
+    &mut context.pool(),
+    poll.id,
+    person_id,
+    data.option_ids.clone(),
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntVotePoll)?;
+
+  let poll = PollView::read(&mut context.pool(), poll, post.creator_id, Some(person_id)).await?;
+  Ok(Json(PollResponse { poll }))
+}
@@ -7,6 +7,7 @@ use lemmy_api_common::{
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
     check_community_ban,
+    check_person_block,
     local_user_view_from_jwt,
     sanitize_html,
     send_new_report_email_to_admins,
@@ -40,6 +41,16 @@ pub async fn create_post_report(
 
   check_community_ban(person_id, post_view.community.id, &mut context.pool()).await?;
 
+  // Users who are blocked by the creator can only report the post once
+  let creator_id = post_view.post.creator_id;
+  if check_person_block(person_id, creator_id, &mut context.pool())
+    .await
+    .is_err()
+    && PostReport::exists_for_creator(&mut context.pool(), post_id, person_id).await?
+  {
+    Err(LemmyErrorType::AlreadyReported)?
+  }
+
   let report_form = PostReportForm {
     creator_id: person_id,
     post_id,
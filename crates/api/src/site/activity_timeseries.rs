@@ -0,0 +1,28 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{GetSiteActivityTimeseries, GetSiteActivityTimeseriesResponse},
+  utils::check_private_instance,
+};
+use lemmy_db_schema::source::{local_site::LocalSite, site_activity_rollup::SiteActivityRollup};
+use lemmy_utils::error::LemmyError;
+
+/// Public statistics endpoint. This only reads the rollup table which is filled by a scheduled
+/// task, so it is cheap to serve and can be cached.
+#[tracing::instrument(skip(context))]
+pub async fn get_site_activity_timeseries(
+  data: Query<GetSiteActivityTimeseries>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetSiteActivityTimeseriesResponse>, LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&None, &local_site)?;
+
+  let interval = data.interval.unwrap_or_default();
+  let range = data.range.unwrap_or(30);
+  let buckets = SiteActivityRollup::list(&mut context.pool(), interval, range).await?;
+
+  Ok(Json(GetSiteActivityTimeseriesResponse {
+    interval,
+    buckets,
+  }))
+}
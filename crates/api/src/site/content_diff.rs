@@ -0,0 +1,77 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  diff::diff_text,
+  site::{GetContentDiff, GetContentDiffResponse},
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    comment::Comment,
+    edit_history::{CommentEditHistory, PostEditHistory},
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+/// Diffs a revision against the text which replaced it, so that edits can be reviewed without
+/// comparing the full revisions by eye.
+#[tracing::instrument(skip(context))]
+pub async fn get_content_diff(
+  data: Query<GetContentDiff>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetContentDiffResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+
+  // Revisions are listed newest first, each one paired with its id
+  let (revisions, current) = if let Some(post_id) = data.post_id {
+    let post = Post::read(&mut context.pool(), post_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
+    if !Post::is_post_creator(person_id, post.creator_id) {
+      is_mod_or_admin(&mut context.pool(), person_id, post.community_id).await?;
+    }
+    let revisions = PostEditHistory::list_for_post(&mut context.pool(), post.id)
+      .await?
+      .into_iter()
+      .map(|r| (r.id, r.content))
+      .collect::<Vec<_>>();
+    (revisions, post.body.unwrap_or_default())
+  } else if let Some(comment_id) = data.comment_id {
+    let comment = Comment::read(&mut context.pool(), comment_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntFindComment)?;
+    if comment.creator_id != person_id {
+      let post = Post::read(&mut context.pool(), comment.post_id).await?;
+      is_mod_or_admin(&mut context.pool(), person_id, post.community_id).await?;
+    }
+    let revisions = CommentEditHistory::list_for_comment(&mut context.pool(), comment.id)
+      .await?
+      .into_iter()
+      .map(|r| (r.id, r.content))
+      .collect::<Vec<_>>();
+    (revisions, comment.content)
+  } else {
+    Err(LemmyErrorType::NoIdGiven)?
+  };
+
+  let position = revisions
+    .iter()
+    .position(|(id, _)| *id == data.revision_id)
+    .ok_or(LemmyErrorType::CouldntFindEditRevision)?;
+  let (_, old) = revisions
+    .get(position)
+    .ok_or(LemmyErrorType::CouldntFindEditRevision)?;
+  // The revision was replaced by the next newer one, or by the current text
+  let new = match position.checked_sub(1).and_then(|i| revisions.get(i)) {
+    Some((_, newer)) => newer,
+    None => &current,
+  };
+
+  Ok(Json(GetContentDiffResponse {
+    hunks: diff_text(old, new),
+  }))
+}
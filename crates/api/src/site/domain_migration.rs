@@ -0,0 +1,173 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  site::{DomainMigrationRows, MigrateDomain, MigrateDomainResponse},
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  newtypes::{CommunityId, PersonId},
+  source::{
+    community::Community,
+    domain_migration::{DomainMigration, DomainMigrationForm},
+    person::Person,
+  },
+  traits::Crud,
+  DomainMigrationStage,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType},
+  spawn_try_task,
+};
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+/// How many community or person updates are queued at once while federating the migration.
+const FEDERATE_BATCH_SIZE: i64 = 100;
+
+#[tracing::instrument(skip(context))]
+pub async fn migrate_domain(
+  data: Json<MigrateDomain>,
+  context: Data<LemmyContext>,
+) -> Result<Json<MigrateDomainResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+
+  let old_domain = data.old_domain.trim().to_lowercase();
+  let new_domain = context.settings().hostname.clone();
+  if old_domain.is_empty() || old_domain == new_domain {
+    return Err(LemmyErrorType::DomainMigrationSameDomain)?;
+  }
+
+  let old_prefix = url_prefix(&context, &old_domain);
+  let rows = DomainMigration::count_rows(&mut context.pool(), &old_domain, &old_prefix)
+    .await?
+    .into_iter()
+    .map(|(table, count)| DomainMigrationRows { table, count })
+    .collect();
+  let token = confirmation_token(&context, &old_domain, &new_domain);
+
+  if data.dry_run.unwrap_or(true) {
+    return Ok(Json(MigrateDomainResponse {
+      rows,
+      confirmation_token: Some(token),
+      migration: None,
+    }));
+  }
+  if data.confirmation_token.as_deref() != Some(token.as_str()) {
+    return Err(LemmyErrorType::InvalidDomainMigrationToken)?;
+  }
+  if DomainMigration::read_unfinished(&mut context.pool())
+    .await?
+    .is_some()
+  {
+    return Err(LemmyErrorType::DomainMigrationInProgress)?;
+  }
+
+  let form = DomainMigrationForm {
+    old_domain: old_domain.clone(),
+    new_domain,
+    admin_person_id: local_user_view.person.id,
+  };
+  let migration = DomainMigration::create(&mut context.pool(), &form).await?;
+  context.add_domain_alias(old_domain);
+  spawn_try_task(run_domain_migration(
+    migration.clone(),
+    context.get_ref().clone(),
+  ));
+
+  Ok(Json(MigrateDomainResponse {
+    rows,
+    confirmation_token: None,
+    migration: Some(migration),
+  }))
+}
+
+/// Runs the migration batch by batch until it is done. The progress is stored after every
+/// batch, so that the migration can be resumed after a restart.
+pub async fn run_domain_migration(
+  mut migration: DomainMigration,
+  context: LemmyContext,
+) -> Result<(), LemmyError> {
+  let old_prefix = url_prefix(&context, &migration.old_domain);
+  let new_prefix = url_prefix(&context, &migration.new_domain);
+  let admin = Person::read(&mut context.pool(), migration.admin_person_id).await?;
+
+  while migration.stage != DomainMigrationStage::Done {
+    migration = if migration.stage.is_federation() {
+      federate_next_batch(&migration, &admin, &context).await?
+    } else {
+      migration
+        .migrate_next_batch(&mut context.pool(), &old_prefix, &new_prefix)
+        .await?
+    };
+  }
+  info!(
+    "Finished moving the instance from {} to {}",
+    migration.old_domain, migration.new_domain
+  );
+  Ok(())
+}
+
+/// Queues updates of the next batch of local communities or persons, so that their followers and
+/// other instances learn about the new urls.
+async fn federate_next_batch(
+  migration: &DomainMigration,
+  admin: &Person,
+  context: &LemmyContext,
+) -> Result<DomainMigration, LemmyError> {
+  let mut activities = vec![];
+  if migration.stage == DomainMigrationStage::FederatePersons {
+    let persons = Person::list_local_after(
+      &mut context.pool(),
+      PersonId(migration.last_id),
+      FEDERATE_BATCH_SIZE,
+    )
+    .await?;
+    for person in persons {
+      activities.push((person.id.0, SendActivityData::UpdatePerson(person)));
+    }
+  } else {
+    let communities = Community::list_local_after(
+      &mut context.pool(),
+      CommunityId(migration.last_id),
+      FEDERATE_BATCH_SIZE,
+    )
+    .await?;
+    for community in communities {
+      let id = community.id.0;
+      activities.push((
+        id,
+        SendActivityData::UpdateCommunity(admin.clone(), community),
+      ));
+    }
+  }
+
+  let Some(last_id) = activities.last().map(|(id, _)| *id) else {
+    return Ok(
+      DomainMigration::advance(&mut context.pool(), migration.id, migration.stage.next(), 0)
+        .await?,
+    );
+  };
+  for (_, activity) in activities {
+    ActivityChannel::queue_activity(activity)?;
+  }
+  Ok(DomainMigration::advance(&mut context.pool(), migration.id, migration.stage, last_id).await?)
+}
+
+fn url_prefix(context: &LemmyContext, domain: &str) -> String {
+  format!("{}://{domain}/", context.settings().get_protocol_string())
+}
+
+/// Ties the token to the instance secret and both domains, so that it can't be reused for a
+/// different migration.
+fn confirmation_token(context: &LemmyContext, old_domain: &str, new_domain: &str) -> String {
+  let hash = format!(
+    "{:x}",
+    Sha256::digest(format!(
+      "{}:{old_domain}:{new_domain}",
+      context.secret().jwt_secret
+    ))
+  );
+  hash.chars().take(16).collect()
+}
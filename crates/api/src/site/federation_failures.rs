@@ -0,0 +1,74 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{
+    FederationFailure,
+    ListFederationFailures,
+    ListFederationFailuresResponse,
+    PurgeFederationFailures,
+    PurgeFederationFailuresResponse,
+  },
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::source::{federation_retry::FederationRetry, instance::Instance};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_federation_failures(
+  data: Query<ListFederationFailures>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListFederationFailuresResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+
+  let instance_id = match &data.instance {
+    Some(domain) => match Instance::read_from_domain(&mut context.pool(), domain).await? {
+      Some(instance) => Some(instance.id),
+      None => return Ok(Json(ListFederationFailuresResponse { failures: vec![] })),
+    },
+    None => None,
+  };
+  let failures = FederationRetry::list(&mut context.pool(), instance_id, data.page, data.limit)
+    .await?
+    .into_iter()
+    .map(|(retry, instance)| {
+      let field = |name: &str| {
+        retry
+          .activity
+          .get(name)
+          .and_then(|v| v.as_str())
+          .map(ToString::to_string)
+      };
+      FederationFailure {
+        id: retry.id,
+        activity_id: field("id"),
+        kind: field("type"),
+        instance,
+        inbox: retry.inbox,
+        attempts: retry.attempts,
+        next_retry_at: retry.next_retry_at,
+        published: retry.published,
+      }
+    })
+    .collect();
+
+  Ok(Json(ListFederationFailuresResponse { failures }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn purge_federation_failures(
+  data: Json<PurgeFederationFailures>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PurgeFederationFailuresResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+
+  let purged = match Instance::read_from_domain(&mut context.pool(), &data.instance).await? {
+    Some(instance) => FederationRetry::purge_for_instance(&mut context.pool(), instance.id).await?,
+    None => 0,
+  };
+
+  Ok(Json(PurgeFederationFailuresResponse {
+    purged: i64::try_from(purged).unwrap_or(i64::MAX),
+  }))
+}
@@ -0,0 +1,113 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{AdminAllowInstance, AdminBlockInstance, GetFederatedInstancesResponse},
+  utils::{build_federated_instances, is_admin, local_user_view_from_jwt, sanitize_html_opt},
+};
+use lemmy_db_schema::{
+  source::{
+    federation_allowlist::{FederationAllowList, FederationAllowListForm},
+    federation_blocklist::{FederationBlockList, FederationBlockListForm},
+    instance::Instance,
+    local_site::LocalSite,
+    moderator::{
+      AdminAllowInstance as AdminAllowInstanceLog,
+      AdminAllowInstanceForm,
+      AdminBlockInstance as AdminBlockInstanceLog,
+      AdminBlockInstanceForm,
+    },
+  },
+  traits::Crud,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType},
+  utils::{time::naive_from_unix, validation::is_valid_body_field},
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn admin_block_instance(
+  data: Json<AdminBlockInstance>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetFederatedInstancesResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+  is_valid_body_field(&data.reason, false)?;
+
+  let domain = data.instance.trim().to_lowercase();
+  if domain == context.settings().get_hostname_without_port()? {
+    return Err(LemmyErrorType::CantBlockLocalInstance)?;
+  }
+  let instance = Instance::read_or_create(&mut context.pool(), domain).await?;
+  let reason = sanitize_html_opt(&data.reason);
+  let expires = data.expires.map(naive_from_unix);
+
+  if data.block {
+    let form = FederationBlockListForm {
+      instance_id: instance.id,
+      reason: reason.clone(),
+      expires,
+      ..Default::default()
+    };
+    FederationBlockList::block(&mut context.pool(), &form).await?;
+  } else {
+    FederationBlockList::unblock(&mut context.pool(), instance.id).await?;
+  }
+
+  // Mod tables
+  let form = AdminBlockInstanceForm {
+    instance_id: instance.id,
+    admin_person_id: local_user_view.person.id,
+    blocked: data.block,
+    reason,
+    expires: expires.filter(|_| data.block),
+  };
+  AdminBlockInstanceLog::create(&mut context.pool(), &form).await?;
+
+  federated_instances_response(&context).await
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn admin_allow_instance(
+  data: Json<AdminAllowInstance>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetFederatedInstancesResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+  is_valid_body_field(&data.reason, false)?;
+
+  let instance =
+    Instance::read_or_create(&mut context.pool(), data.instance.trim().to_lowercase()).await?;
+  let reason = sanitize_html_opt(&data.reason);
+
+  if data.allow {
+    let form = FederationAllowListForm {
+      instance_id: instance.id,
+      reason: reason.clone(),
+      ..Default::default()
+    };
+    FederationAllowList::allow(&mut context.pool(), &form).await?;
+  } else {
+    FederationAllowList::disallow(&mut context.pool(), instance.id).await?;
+  }
+
+  // Mod tables
+  let form = AdminAllowInstanceForm {
+    instance_id: instance.id,
+    admin_person_id: local_user_view.person.id,
+    allowed: data.allow,
+    reason,
+  };
+  AdminAllowInstanceLog::create(&mut context.pool(), &form).await?;
+
+  federated_instances_response(&context).await
+}
+
+async fn federated_instances_response(
+  context: &LemmyContext,
+) -> Result<Json<GetFederatedInstancesResponse>, LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let federated_instances = build_federated_instances(&local_site, &mut context.pool()).await?;
+  Ok(Json(GetFederatedInstancesResponse {
+    federated_instances,
+  }))
+}
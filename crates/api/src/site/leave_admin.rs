@@ -10,6 +10,7 @@ use lemmy_db_schema::{
     actor_language::SiteLanguage,
     language::Language,
     moderator::{ModAdd, ModAddForm},
+    oauth_provider::OAuthProvider,
     person::{Person, PersonUpdateForm},
     tagline::Tagline,
   },
@@ -68,6 +69,7 @@ impl Perform for LeaveAdmin {
     let taglines = Tagline::get_all(&mut context.pool(), site_view.local_site.id).await?;
     let custom_emojis =
       CustomEmojiView::get_all(&mut context.pool(), site_view.local_site.id).await?;
+    let oauth_providers = OAuthProvider::list(&mut context.pool(), true).await?;
 
     Ok(GetSiteResponse {
       site_view,
@@ -78,6 +80,7 @@ impl Perform for LeaveAdmin {
       discussion_languages,
       taglines,
       custom_emojis,
+      oauth_providers,
     })
   }
 }
@@ -0,0 +1,31 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::ListMediaResponse,
+  site::ListAllMedia,
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_views::local_image_view::LocalImageQuery;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_all_media(
+  data: Query<ListAllMedia>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListMediaResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  // Only let admins view all media
+  is_admin(&local_user_view)?;
+
+  let images = LocalImageQuery {
+    person_id: data.person_id,
+    page: data.page,
+    limit: data.limit,
+    ..Default::default()
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  Ok(Json(ListMediaResponse { images }))
+}
@@ -1,5 +1,14 @@
+pub mod activity_timeseries;
+pub mod content_diff;
+pub mod domain_migration;
 mod federated_instances;
+pub mod federation_failures;
+pub mod federation_lists;
 mod leave_admin;
+pub mod list_all_media;
 mod mod_log;
-mod purge;
+pub mod modlog_export;
+pub mod object_federation_status;
+pub mod purge;
 mod registration_applications;
+pub mod vote_anomalies;
@@ -1,5 +1,6 @@
 use crate::Perform;
 use actix_web::web::Data;
+use chrono::NaiveDateTime;
 use lemmy_api_common::{
   context::LemmyContext,
   site::{GetModlog, GetModlogResponse},
@@ -8,19 +9,26 @@ use lemmy_api_common::{
 use lemmy_db_schema::{
   newtypes::{CommunityId, PersonId},
   source::local_site::LocalSite,
+  utils::limit_and_offset,
   ModlogActionType,
 };
+use lemmy_db_views::structs::LocalUserView;
 use lemmy_db_views_moderator::structs::{
+  AdminAllowInstanceView,
+  AdminBlockInstanceView,
+  AdminClearPersonProfileView,
   AdminPurgeCommentView,
   AdminPurgeCommunityView,
   AdminPurgePersonView,
   AdminPurgePostView,
+  AdminVerifyCommunityView,
   ModAddCommunityView,
   ModAddView,
   ModBanFromCommunityView,
   ModBanView,
   ModFeaturePostView,
   ModHideCommunityView,
+  ModLockCommentView,
   ModLockPostView,
   ModRemoveCommentView,
   ModRemoveCommunityView,
@@ -29,6 +37,7 @@ use lemmy_db_views_moderator::structs::{
   ModlogListParams,
 };
 use lemmy_utils::error::LemmyError;
+use std::collections::HashSet;
 use ModlogActionType::*;
 
 #[async_trait::async_trait(?Send)]
@@ -44,22 +53,24 @@ impl Perform for GetModlog {
 
     check_private_instance(&local_user_view, &local_site)?;
 
-    let type_ = data.type_.unwrap_or(All);
-    let community_id = data.community_id;
-
-    let (local_person_id, is_admin) = match local_user_view {
-      Some(s) => (s.person.id, is_admin(&s).is_ok()),
-      None => (PersonId(-1), false),
+    let actions = match &data.actions {
+      Some(actions) if !actions.is_empty() => actions.clone(),
+      _ => vec![data.type_.unwrap_or(All)],
     };
-    let community_id_value = match community_id {
-      Some(s) => s,
-      None => CommunityId(-1),
+    let reason_query = data
+      .reason_query
+      .as_deref()
+      .map(str::trim)
+      .filter(|q| !q.is_empty())
+      .map(ToString::to_string);
+    // Actions without a reason can't match a reason search
+    let listed = |action: ModlogActionType| {
+      (actions.contains(&All) || actions.contains(&action))
+        && (reason_query.is_none() || !WITHOUT_REASON.contains(&action))
     };
-    let is_mod_of_community = data.community_id.is_some()
-      && is_mod_or_admin(&mut context.pool(), local_person_id, community_id_value)
-        .await
-        .is_ok();
-    let hide_modlog_names = local_site.hide_modlog_mod_names && !is_mod_of_community && !is_admin;
+    let community_id = data.community_id;
+    let hide_modlog_names =
+      hide_modlog_names(&local_user_view, community_id, &local_site, context).await;
 
     let mod_person_id = if hide_modlog_names {
       None
@@ -74,51 +85,67 @@ impl Perform for GetModlog {
       page: data.page,
       limit: data.limit,
       hide_modlog_names,
+      since_id: None,
+      reason_query,
     };
-    let removed_posts = match type_ {
-      All | ModRemovePost => ModRemovePostView::list(&mut context.pool(), params).await?,
-      _ => Default::default(),
+    let removed_posts = if listed(ModRemovePost) {
+      ModRemovePostView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
     };
 
-    let locked_posts = match type_ {
-      All | ModLockPost => ModLockPostView::list(&mut context.pool(), params).await?,
-      _ => Default::default(),
+    let locked_posts = if listed(ModLockPost) {
+      ModLockPostView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
     };
 
-    let featured_posts = match type_ {
-      All | ModFeaturePost => ModFeaturePostView::list(&mut context.pool(), params).await?,
-      _ => Default::default(),
+    let featured_posts = if listed(ModFeaturePost) {
+      ModFeaturePostView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
     };
 
-    let removed_comments = match type_ {
-      All | ModRemoveComment => ModRemoveCommentView::list(&mut context.pool(), params).await?,
-      _ => Default::default(),
+    let removed_comments = if listed(ModRemoveComment) {
+      ModRemoveCommentView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
     };
 
-    let banned_from_community = match type_ {
-      All | ModBanFromCommunity => {
-        ModBanFromCommunityView::list(&mut context.pool(), params).await?
-      }
-      _ => Default::default(),
+    let locked_comments = if listed(ModLockComment) {
+      ModLockCommentView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
     };
 
-    let added_to_community = match type_ {
-      All | ModAddCommunity => ModAddCommunityView::list(&mut context.pool(), params).await?,
-      _ => Default::default(),
+    let banned_from_community = if listed(ModBanFromCommunity) {
+      ModBanFromCommunityView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
     };
 
-    let transferred_to_community = match type_ {
-      All | ModTransferCommunity => {
-        ModTransferCommunityView::list(&mut context.pool(), params).await?
-      }
-      _ => Default::default(),
+    let added_to_community = if listed(ModAddCommunity) {
+      ModAddCommunityView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
     };
 
-    let hidden_communities = match type_ {
-      All | ModHideCommunity if other_person_id.is_none() => {
-        ModHideCommunityView::list(&mut context.pool(), params).await?
-      }
-      _ => Default::default(),
+    let transferred_to_community = if listed(ModTransferCommunity) {
+      ModTransferCommunityView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
+    };
+
+    let hidden_communities = if listed(ModHideCommunity) && other_person_id.is_none() {
+      ModHideCommunityView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
+    };
+
+    let admin_verified_communities = if listed(AdminVerifyCommunity) && other_person_id.is_none() {
+      AdminVerifyCommunityView::list(&mut context.pool(), params.clone()).await?
+    } else {
+      Default::default()
     };
 
     // These arrays are only for the full modlog, when a community isn't given
@@ -130,57 +157,72 @@ impl Perform for GetModlog {
       admin_purged_communities,
       admin_purged_posts,
       admin_purged_comments,
+      admin_blocked_instances,
+      admin_allowed_instances,
+      admin_cleared_person_profiles,
     ) = if data.community_id.is_none() {
       (
-        match type_ {
-          All | ModBan => ModBanView::list(&mut context.pool(), params).await?,
-          _ => Default::default(),
+        if listed(ModBan) {
+          ModBanView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
+        },
+        if listed(ModAdd) {
+          ModAddView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
+        },
+        if listed(ModRemoveCommunity) && other_person_id.is_none() {
+          ModRemoveCommunityView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
+        },
+        if listed(AdminPurgePerson) && other_person_id.is_none() {
+          AdminPurgePersonView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
         },
-        match type_ {
-          All | ModAdd => ModAddView::list(&mut context.pool(), params).await?,
-          _ => Default::default(),
+        if listed(AdminPurgeCommunity) && other_person_id.is_none() {
+          AdminPurgeCommunityView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
         },
-        match type_ {
-          All | ModRemoveCommunity if other_person_id.is_none() => {
-            ModRemoveCommunityView::list(&mut context.pool(), params).await?
-          }
-          _ => Default::default(),
+        if listed(AdminPurgePost) && other_person_id.is_none() {
+          AdminPurgePostView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
         },
-        match type_ {
-          All | AdminPurgePerson if other_person_id.is_none() => {
-            AdminPurgePersonView::list(&mut context.pool(), params).await?
-          }
-          _ => Default::default(),
+        if listed(AdminPurgeComment) && other_person_id.is_none() {
+          AdminPurgeCommentView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
         },
-        match type_ {
-          All | AdminPurgeCommunity if other_person_id.is_none() => {
-            AdminPurgeCommunityView::list(&mut context.pool(), params).await?
-          }
-          _ => Default::default(),
+        if listed(AdminBlockInstance) && other_person_id.is_none() {
+          AdminBlockInstanceView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
         },
-        match type_ {
-          All | AdminPurgePost if other_person_id.is_none() => {
-            AdminPurgePostView::list(&mut context.pool(), params).await?
-          }
-          _ => Default::default(),
+        if listed(AdminAllowInstance) && other_person_id.is_none() {
+          AdminAllowInstanceView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
         },
-        match type_ {
-          All | AdminPurgeComment if other_person_id.is_none() => {
-            AdminPurgeCommentView::list(&mut context.pool(), params).await?
-          }
-          _ => Default::default(),
+        if listed(AdminClearPersonProfile) {
+          AdminClearPersonProfileView::list(&mut context.pool(), params.clone()).await?
+        } else {
+          Default::default()
         },
       )
     } else {
       Default::default()
     };
 
-    // Return the jwt
-    Ok(GetModlogResponse {
+    let mut response = GetModlogResponse {
       removed_posts,
       locked_posts,
       featured_posts,
       removed_comments,
+      locked_comments,
       removed_communities,
       banned_from_community,
       banned,
@@ -192,6 +234,101 @@ impl Perform for GetModlog {
       admin_purged_posts,
       admin_purged_comments,
       hidden_communities,
-    })
+      admin_blocked_instances,
+      admin_allowed_instances,
+      admin_cleared_person_profiles,
+      admin_verified_communities,
+    };
+    cut_page(&mut response, data.page, data.limit)?;
+    Ok(response)
   }
 }
+
+/// The action types which are logged without a reason.
+const WITHOUT_REASON: [ModlogActionType; 7] = [
+  AdminVerifyCommunity,
+  ModAdd,
+  ModAddCommunity,
+  ModFeaturePost,
+  ModLockComment,
+  ModLockPost,
+  ModTransferCommunity,
+];
+
+/// Orders the entries of all action types by time, and keeps only those on the requested page.
+/// Each type was read up to the end of the page, so all entries before the page are known.
+fn cut_page(
+  response: &mut GetModlogResponse,
+  page: Option<i64>,
+  limit: Option<i64>,
+) -> Result<(), LemmyError> {
+  // Ids are only unique per action type, so the entries are identified together with their list
+  macro_rules! cut_lists {
+    ($($list:ident.$entry:ident),* $(,)?) => {
+      let (limit, offset) = limit_and_offset(page, limit)?;
+      let limit = usize::try_from(limit).unwrap_or_default();
+      let offset = usize::try_from(offset).unwrap_or_default();
+      let mut entries: Vec<(NaiveDateTime, &str, i32)> = Vec::new();
+      $(
+        entries.extend(
+          response
+            .$list
+            .iter()
+            .map(|v| (v.$entry.when_, stringify!($list), v.$entry.id)),
+        );
+      )*
+      entries.sort_unstable();
+      let page: HashSet<_> = entries.into_iter().rev().skip(offset).take(limit).collect();
+      $(
+        response
+          .$list
+          .retain(|v| page.contains(&(v.$entry.when_, stringify!($list), v.$entry.id)));
+      )*
+    };
+  }
+
+  cut_lists!(
+    removed_posts.mod_remove_post,
+    locked_posts.mod_lock_post,
+    featured_posts.mod_feature_post,
+    removed_comments.mod_remove_comment,
+    locked_comments.mod_lock_comment,
+    removed_communities.mod_remove_community,
+    banned_from_community.mod_ban_from_community,
+    banned.mod_ban,
+    added_to_community.mod_add_community,
+    added.mod_add,
+    transferred_to_community.mod_transfer_community,
+    admin_purged_persons.admin_purge_person,
+    admin_purged_communities.admin_purge_community,
+    admin_purged_posts.admin_purge_post,
+    admin_purged_comments.admin_purge_comment,
+    hidden_communities.mod_hide_community,
+    admin_blocked_instances.admin_block_instance,
+    admin_allowed_instances.admin_allow_instance,
+    admin_cleared_person_profiles.admin_clear_person_profile,
+    admin_verified_communities.admin_verify_community,
+  );
+  Ok(())
+}
+
+/// Mod names are hidden if the site is configured that way, except for admins and the mods of the
+/// community.
+pub(crate) async fn hide_modlog_names(
+  local_user_view: &Option<LocalUserView>,
+  community_id: Option<CommunityId>,
+  local_site: &LocalSite,
+  context: &LemmyContext,
+) -> bool {
+  let (local_person_id, is_admin) = match local_user_view {
+    Some(s) => (s.person.id, is_admin(s).is_ok()),
+    None => (PersonId(-1), false),
+  };
+  let is_mod_of_community = match community_id {
+    Some(community_id) => is_mod_or_admin(&mut context.pool(), local_person_id, community_id)
+      .await
+      .is_ok(),
+    None => false,
+  };
+  local_site.hide_modlog_mod_names && !is_mod_of_community && !is_admin
+}
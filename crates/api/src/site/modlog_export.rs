@@ -0,0 +1,222 @@
+use super::mod_log::hide_modlog_names;
+use actix_web::{
+  web::{Bytes, Data, Query},
+  HttpResponse,
+};
+use futures::{stream, Future, Stream, StreamExt};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::GetModlogExport,
+  utils::{check_private_instance, local_user_view_from_jwt_opt},
+};
+use lemmy_db_schema::{source::local_site::LocalSite, utils::FETCH_LIMIT_MAX, ModlogActionType};
+use lemmy_db_views_moderator::structs::{
+  AdminAllowInstanceView,
+  AdminBlockInstanceView,
+  AdminClearPersonProfileView,
+  AdminPurgeCommentView,
+  AdminPurgeCommunityView,
+  AdminPurgePersonView,
+  AdminPurgePostView,
+  AdminVerifyCommunityView,
+  ModAddCommunityView,
+  ModAddView,
+  ModBanFromCommunityView,
+  ModBanView,
+  ModFeaturePostView,
+  ModHideCommunityView,
+  ModLockCommentView,
+  ModLockPostView,
+  ModRemoveCommentView,
+  ModRemoveCommunityView,
+  ModRemovePostView,
+  ModTransferCommunityView,
+  ModlogListParams,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use serde::Serialize;
+use ModlogActionType::*;
+
+/// Increased whenever the format of the exported lines changes in an incompatible way.
+const MODLOG_EXPORT_SCHEMA_VERSION: i32 = 1;
+
+/// A single line of the export.
+#[derive(Serialize)]
+struct ModlogExportLine<'a, T> {
+  schema_version: i32,
+  #[serde(rename = "type")]
+  type_: ModlogActionType,
+  id: i32,
+  entry: &'a T,
+}
+
+/// Streams the modlog entries page by page, so that a full export neither keeps a transaction
+/// open nor has to be held in memory.
+#[tracing::instrument(skip(context))]
+pub async fn get_modlog_export(
+  data: Query<GetModlogExport>,
+  context: Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), &context).await;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let community_id = data.community_id;
+  let params = ModlogListParams {
+    community_id,
+    mod_person_id: None,
+    other_person_id: None,
+    page: None,
+    limit: Some(FETCH_LIMIT_MAX),
+    hide_modlog_names: hide_modlog_names(&local_user_view, community_id, &local_site, &context)
+      .await,
+    since_id: Some(data.since.unwrap_or_default()),
+    reason_query: None,
+  };
+  // Like in the modlog, site wide actions aren't listed for a community
+  let site_wide = matches!(
+    data.type_,
+    ModBan
+      | ModAdd
+      | ModRemoveCommunity
+      | AdminPurgePerson
+      | AdminPurgeCommunity
+      | AdminPurgePost
+      | AdminPurgeComment
+      | AdminBlockInstance
+      | AdminAllowInstance
+      | AdminClearPersonProfile
+  );
+  if site_wide && community_id.is_some() {
+    return Ok(ndjson_response(stream::empty()));
+  }
+
+  macro_rules! export {
+    ($view:ident, $field:ident) => {
+      ndjson_response(export_pages(
+        context,
+        params,
+        data.type_,
+        |context, params| async move {
+          $view::list(&mut context.pool(), params)
+            .await
+            .map_err(LemmyError::from)
+        },
+        |v: &$view| v.$field.id,
+      ))
+    };
+  }
+  let response = match data.type_ {
+    All => Err(LemmyErrorType::CantExportAllModlogTypes)?,
+    ModRemovePost => export!(ModRemovePostView, mod_remove_post),
+    ModLockPost => export!(ModLockPostView, mod_lock_post),
+    ModFeaturePost => export!(ModFeaturePostView, mod_feature_post),
+    ModRemoveComment => export!(ModRemoveCommentView, mod_remove_comment),
+    ModLockComment => export!(ModLockCommentView, mod_lock_comment),
+    ModRemoveCommunity => export!(ModRemoveCommunityView, mod_remove_community),
+    ModBanFromCommunity => export!(ModBanFromCommunityView, mod_ban_from_community),
+    ModAddCommunity => export!(ModAddCommunityView, mod_add_community),
+    ModTransferCommunity => export!(ModTransferCommunityView, mod_transfer_community),
+    ModAdd => export!(ModAddView, mod_add),
+    ModBan => export!(ModBanView, mod_ban),
+    ModHideCommunity => export!(ModHideCommunityView, mod_hide_community),
+    AdminPurgePerson => export!(AdminPurgePersonView, admin_purge_person),
+    AdminPurgeCommunity => export!(AdminPurgeCommunityView, admin_purge_community),
+    AdminPurgePost => export!(AdminPurgePostView, admin_purge_post),
+    AdminPurgeComment => export!(AdminPurgeCommentView, admin_purge_comment),
+    AdminBlockInstance => export!(AdminBlockInstanceView, admin_block_instance),
+    AdminAllowInstance => export!(AdminAllowInstanceView, admin_allow_instance),
+    AdminClearPersonProfile => export!(AdminClearPersonProfileView, admin_clear_person_profile),
+    AdminVerifyCommunity => export!(AdminVerifyCommunityView, admin_verify_community),
+  };
+  Ok(response)
+}
+
+fn ndjson_response<S>(body: S) -> HttpResponse
+where
+  S: Stream<Item = Result<Bytes, LemmyError>> + 'static,
+{
+  HttpResponse::Ok()
+    .content_type("application/x-ndjson")
+    .streaming(body.map(|bytes| bytes.map_err(actix_web::Error::from)))
+}
+
+/// Reads one page after the other, starting after the id of the last entry of the previous page.
+fn export_pages<V, L, F>(
+  context: Data<LemmyContext>,
+  params: ModlogListParams,
+  type_: ModlogActionType,
+  list: L,
+  id: fn(&V) -> i32,
+) -> impl Stream<Item = Result<Bytes, LemmyError>>
+where
+  V: Serialize,
+  L: Fn(Data<LemmyContext>, ModlogListParams) -> F + Copy + 'static,
+  F: Future<Output = Result<Vec<V>, LemmyError>>,
+{
+  stream::unfold(params.since_id, move |since_id| {
+    let context = context.clone();
+    let params = params.clone();
+    async move {
+      let params = ModlogListParams {
+        since_id: Some(since_id?),
+        ..params
+      };
+      let page = match list(context, params).await {
+        Ok(page) => page,
+        Err(e) => return Some((Err(e), None)),
+      };
+      let last_id = page.last().map(id)?;
+      Some((encode_page(&page, type_, id), Some(last_id)))
+    }
+  })
+}
+
+fn encode_page<V: Serialize>(
+  page: &[V],
+  type_: ModlogActionType,
+  id: fn(&V) -> i32,
+) -> Result<Bytes, LemmyError> {
+  let mut bytes = vec![];
+  for entry in page {
+    let line = ModlogExportLine {
+      schema_version: MODLOG_EXPORT_SCHEMA_VERSION,
+      type_,
+      id: id(entry),
+      entry,
+    };
+    serde_json::to_writer(&mut bytes, &line)?;
+    bytes.push(b'\n');
+  }
+  Ok(bytes.into())
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::encode_page;
+  use lemmy_db_schema::ModlogActionType;
+  use serde_json::{json, Value};
+
+  #[test]
+  fn test_encode_page() {
+    let page = vec![json!({ "id": 3 }), json!({ "id": 5 })];
+    let bytes = encode_page(&page, ModlogActionType::ModLockPost, |v| {
+      i32::try_from(v["id"].as_i64().unwrap()).unwrap()
+    })
+    .unwrap();
+
+    let lines: Vec<Value> = std::str::from_utf8(&bytes)
+      .unwrap()
+      .lines()
+      .map(|l| serde_json::from_str(l).unwrap())
+      .collect();
+    assert_eq!(2, lines.len());
+    assert_eq!(
+      json!({ "schema_version": 1, "type": "ModLockPost", "id": 5, "entry": { "id": 5 } }),
+      lines[1]
+    );
+  }
+}
@@ -0,0 +1,106 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{
+    ActivityDeliveryStatus,
+    GetObjectFederationStatus,
+    GetObjectFederationStatusResponse,
+    ReceivedActivityStatus,
+    SentActivityStatus,
+  },
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    activity::{ReceivedActivity, SentActivity, SentActivityDelivery},
+    comment::Comment,
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use url::Url;
+
+/// Collects everything this instance knows about the federation of a single post or comment, to
+/// help admins find out why it didn't arrive somewhere.
+#[tracing::instrument(skip(context))]
+pub async fn get_object_federation_status(
+  data: Query<GetObjectFederationStatus>,
+  context: Data<LemmyContext>,
+) -> Result<Json<GetObjectFederationStatusResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+
+  let (ap_id, local, published, updated) = if let Some(post_id) = data.post_id {
+    let post = Post::read(&mut context.pool(), post_id).await?;
+    (post.ap_id, post.local, post.published, post.updated)
+  } else if let Some(comment_id) = data.comment_id {
+    let comment = Comment::read(&mut context.pool(), comment_id).await?;
+    (
+      comment.ap_id,
+      comment.local,
+      comment.published,
+      comment.updated,
+    )
+  } else if let Some(ap_id) = &data.ap_id {
+    let ap_id = Url::parse(ap_id)?;
+    if let Some(post) = Post::read_from_apub_id(&mut context.pool(), ap_id.clone()).await? {
+      (post.ap_id, post.local, post.published, post.updated)
+    } else if let Some(comment) = Comment::read_from_apub_id(&mut context.pool(), ap_id).await? {
+      (
+        comment.ap_id,
+        comment.local,
+        comment.published,
+        comment.updated,
+      )
+    } else {
+      Err(LemmyErrorType::CouldntFindObject)?
+    }
+  } else {
+    Err(LemmyErrorType::NoIdGiven)?
+  };
+
+  let mut sent_activities = vec![];
+  for activity in SentActivity::list_for_object(&mut context.pool(), &ap_id).await? {
+    let deliveries = SentActivityDelivery::list_for_activity(&mut context.pool(), activity.id)
+      .await?
+      .into_iter()
+      .map(|d| ActivityDeliveryStatus {
+        inbox: d.inbox,
+        status: d.status,
+        published: d.published,
+        attempts: d.attempts,
+        last_attempt_at: d.last_attempt_at,
+        last_error: d.last_error,
+      })
+      .collect();
+    sent_activities.push(SentActivityStatus {
+      kind: activity
+        .data
+        .get("type")
+        .and_then(|t| t.as_str())
+        .map(ToString::to_string),
+      ap_id: activity.ap_id,
+      published: activity.published,
+      deliveries,
+    });
+  }
+
+  let received_activities = ReceivedActivity::list_for_object(&mut context.pool(), &ap_id)
+    .await?
+    .into_iter()
+    .map(|a| ReceivedActivityStatus {
+      ap_id: a.ap_id,
+      published: a.published,
+    })
+    .collect();
+
+  Ok(Json(GetObjectFederationStatusResponse {
+    ap_id,
+    local,
+    published,
+    updated,
+    sent_activities,
+    received_activities,
+  }))
+}
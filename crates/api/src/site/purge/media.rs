@@ -0,0 +1,34 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{
+  context::LemmyContext,
+  request::delete_image_from_pictrs,
+  site::{PurgeItemResponse, PurgeMedia},
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::source::images::LocalImage;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn purge_media(
+  data: Json<PurgeMedia>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PurgeItemResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  // Only let admin purge an item
+  is_admin(&local_user_view)?;
+
+  let image = LocalImage::read_by_alias(&mut context.pool(), &data.alias).await?;
+
+  delete_image_from_pictrs(
+    context.client(),
+    context.settings(),
+    &image.pictrs_alias,
+    &image.pictrs_delete_token,
+  )
+  .await?;
+
+  LocalImage::delete_by_alias(&mut context.pool(), &image.pictrs_alias).await?;
+
+  Ok(Json(PurgeItemResponse { success: true }))
+}
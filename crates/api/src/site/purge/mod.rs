@@ -1,4 +1,5 @@
 mod comment;
 mod community;
+pub mod media;
 mod person;
 mod post;
@@ -2,17 +2,19 @@ use crate::Perform;
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
-  request::purge_image_from_pictrs,
+  request::{delete_image_from_pictrs, purge_image_from_pictrs},
   site::{PurgeItemResponse, PurgePerson},
   utils::{is_admin, local_user_view_from_jwt, purge_image_posts_for_person, sanitize_html_opt},
 };
 use lemmy_db_schema::{
   source::{
+    images::LocalImage,
     moderator::{AdminPurgePerson, AdminPurgePersonForm},
     person::Person,
   },
   traits::Crud,
 };
+use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::error::LemmyError;
 
 #[async_trait::async_trait(?Send)]
@@ -51,6 +53,24 @@ impl Perform for PurgePerson {
     )
     .await?;
 
+    // Purge the images this person uploaded, if they are local. The rows themselves are removed
+    // by the cascading delete below.
+    if let Ok(local_user_view) = LocalUserView::read_person(&mut context.pool(), person_id).await {
+      let local_images =
+        LocalImage::get_all_by_local_user_id(&mut context.pool(), local_user_view.local_user.id)
+          .await?;
+      for image in local_images {
+        delete_image_from_pictrs(
+          context.client(),
+          context.settings(),
+          &image.pictrs_alias,
+          &image.pictrs_delete_token,
+        )
+        .await
+        .ok();
+      }
+    }
+
     Person::delete(&mut context.pool(), person_id).await?;
 
     // Mod tables
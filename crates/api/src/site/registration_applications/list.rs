@@ -9,7 +9,8 @@ use lemmy_db_schema::source::local_site::LocalSite;
 use lemmy_db_views::registration_application_view::RegistrationApplicationQuery;
 use lemmy_utils::error::LemmyError;
 
-/// Lists registration applications, filterable by undenied only.
+/// Lists registration applications, filterable by undenied only, and by age to preview which ones
+/// expire.
 #[async_trait::async_trait(?Send)]
 impl Perform for ListRegistrationApplications {
   type Response = ListRegistrationApplicationsResponse;
@@ -30,8 +31,10 @@ impl Perform for ListRegistrationApplications {
     let registration_applications = RegistrationApplicationQuery {
       unread_only,
       verified_email_only,
+      older_than_days: data.older_than_days,
       page,
       limit,
+      ..Default::default()
     }
     .list(&mut context.pool())
     .await?;
@@ -0,0 +1,43 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{ListVoteAnomalies, ListVoteAnomaliesResponse, VoteSurge, VoterCount},
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{community::Community, vote_anomaly::VoteAnomaly},
+  traits::Crud,
+};
+use lemmy_utils::error::LemmyError;
+use serde_json::Value;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_vote_anomalies(
+  data: Query<ListVoteAnomalies>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListVoteAnomaliesResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_admin(&local_user_view)?;
+
+  let mut anomalies = vec![];
+  for anomaly in VoteAnomaly::list(&mut context.pool(), data.page, data.limit).await? {
+    let community = Community::read(&mut context.pool(), anomaly.community_id).await?;
+    anomalies.push(VoteSurge {
+      id: anomaly.id,
+      community,
+      post_id: anomaly.post_id,
+      comment_id: anomaly.comment_id,
+      vote_count: anomaly.vote_count,
+      z_score: anomaly.z_score,
+      instances: voter_counts(anomaly.instance_distribution),
+      account_ages: voter_counts(anomaly.account_age_histogram),
+      published: anomaly.published,
+    });
+  }
+
+  Ok(Json(ListVoteAnomaliesResponse { anomalies }))
+}
+
+fn voter_counts(counts: Value) -> Vec<VoterCount> {
+  serde_json::from_value(counts).unwrap_or_default()
+}
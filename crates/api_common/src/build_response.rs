@@ -2,8 +2,9 @@ use crate::{
   comment::CommentResponse,
   community::CommunityResponse,
   context::LemmyContext,
+  image_proxy::{proxy_comment_view_images, proxy_post_view_images},
   post::PostResponse,
-  utils::{check_person_block, get_interface_language, is_mod_or_admin, send_email_to_user},
+  utils::{check_person_block, get_interface_language, is_mod_or_admin, send_emails_to_users},
 };
 use actix_web::web::Json;
 use lemmy_db_schema::{
@@ -12,15 +13,22 @@ use lemmy_db_schema::{
     actor_language::CommunityLanguage,
     comment::Comment,
     comment_reply::{CommentReply, CommentReplyInsertForm},
+    community::Community,
+    community_mention::{CommunityMention, CommunityMentionForm},
+    local_site::LocalSite,
     person::Person,
     person_mention::{PersonMention, PersonMentionInsertForm},
     post::Post,
   },
-  traits::Crud,
+  traits::{ApubActor, Crud},
 };
 use lemmy_db_views::structs::{CommentView, LocalUserView, PostView};
 use lemmy_db_views_actor::structs::CommunityView;
-use lemmy_utils::{error::LemmyError, utils::mention::MentionData};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::{mention::MentionData, muted_words::contains_muted_word},
+};
+use tracing::warn;
 
 pub async fn build_comment_response(
   context: &LemmyContext,
@@ -28,11 +36,19 @@ pub async fn build_comment_response(
   local_user_view: Option<LocalUserView>,
   recipient_ids: Vec<LocalUserId>,
 ) -> Result<CommentResponse, LemmyError> {
-  let person_id = local_user_view.map(|l| l.person.id);
-  let comment_view = CommentView::read(&mut context.pool(), comment_id, person_id).await?;
+  let person_id = local_user_view.as_ref().map(|l| l.person.id);
+  let mut comment_view = CommentView::read(&mut context.pool(), comment_id, person_id).await?;
+  comment_view.redact_nsfw(local_user_view.as_ref().map(|l| &l.local_user));
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  if local_site.proxy_remote_images {
+    proxy_comment_view_images(&mut comment_view, context);
+  }
   Ok(CommentResponse {
     comment_view,
     recipient_ids,
+    ancestor_counts: vec![],
+    post_comments: None,
+    community_mentions: vec![],
   })
 }
 
@@ -70,17 +86,55 @@ pub async fn build_post_response(
   let is_mod_or_admin = is_mod_or_admin(&mut context.pool(), person_id, community_id)
     .await
     .is_ok();
-  let post_view = PostView::read(
+  let mut post_view = PostView::read(
     &mut context.pool(),
     post_id,
     Some(person_id),
     is_mod_or_admin,
   )
   .await?;
-  Ok(Json(PostResponse { post_view }))
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  if local_site.proxy_remote_images {
+    proxy_post_view_images(&mut post_view, context);
+  }
+  Ok(Json(PostResponse {
+    post_view,
+    community_mentions: vec![],
+  }))
+}
+
+/// Stores the mentions of communities which are already known, local or remote. Remote
+/// communities which aren't known yet are fetched when the post or comment is federated.
+/// Mentions which can't be resolved are just left as text, and failing to store them is only
+/// logged, so that it never fails the post or comment.
+#[tracing::instrument(skip_all)]
+pub async fn store_community_mentions(
+  mentions: Vec<MentionData>,
+  post_id: Option<PostId>,
+  comment_id: Option<CommentId>,
+  context: &LemmyContext,
+) {
+  let mut forms = Vec::new();
+  for mention in mentions {
+    let community = if mention.is_local(&context.settings().hostname) {
+      Community::read_from_name(&mut context.pool(), &mention.name, false).await
+    } else {
+      Community::read_from_name_and_domain(&mut context.pool(), &mention.name, &mention.domain)
+        .await
+    };
+    if let Ok(community) = community {
+      forms.push(CommunityMentionForm {
+        community_id: community.id,
+        post_id,
+        comment_id,
+      });
+    }
+  }
+  if let Err(e) = CommunityMention::create_many(&mut context.pool(), &forms).await {
+    warn!("Failed to store mentioned communities: {e}");
+  }
 }
 
-// TODO: this function is a mess and should be split up to handle email seperately
 #[tracing::instrument(skip_all)]
 pub async fn send_local_notifs(
   mentions: Vec<MentionData>,
@@ -91,6 +145,7 @@ pub async fn send_local_notifs(
   context: &LemmyContext,
 ) -> Result<Vec<LocalUserId>, LemmyError> {
   let mut recipient_ids = Vec::new();
+  let mut emails = Vec::new();
   let inbox_link = format!("{}/inbox", context.settings().get_protocol_and_hostname());
 
   // Send the local mentions
@@ -101,15 +156,32 @@ pub async fn send_local_notifs(
     let mention_name = mention.name.clone();
     let user_view = LocalUserView::read_from_name(&mut context.pool(), &mention_name).await;
     if let Ok(mention_user_view) = user_view {
+      // Don't notify users who blocked the comment creator
+      if check_person_block(person.id, mention_user_view.person.id, &mut context.pool())
+        .await
+        .is_err()
+      {
+        continue;
+      }
+
+      // The mention is still stored if the user muted a word in it, but they aren't notified
+      let muted = contains_muted_word(
+        &comment.content,
+        &mention_user_view.local_user.notification_muted_words,
+      );
+
       // TODO
       // At some point, make it so you can't tag the parent creator either
       // This can cause two notifications, one for reply and the other for mention
-      recipient_ids.push(mention_user_view.local_user.id);
+      if !muted {
+        recipient_ids.push(mention_user_view.local_user.id);
+      }
 
       let user_mention_form = PersonMentionInsertForm {
         recipient_id: mention_user_view.person.id,
         comment_id: comment.id,
         read: None,
+        muted: Some(muted),
       };
 
       // Allow this to fail softly, since comment edits might re-update or replace it
@@ -119,15 +191,13 @@ pub async fn send_local_notifs(
         .ok();
 
       // Send an email to those local users that have notifications on
-      if do_send_email {
+      if do_send_email && !muted {
         let lang = get_interface_language(&mention_user_view);
-        send_email_to_user(
-          &mention_user_view,
-          &lang.notification_mentioned_by_subject(&person.name),
-          &lang.notification_mentioned_by_body(&comment.content, &inbox_link, &person.name),
-          context.settings(),
-        )
-        .await
+        emails.push((
+          mention_user_view,
+          lang.notification_mentioned_by_subject(&person.name),
+          lang.notification_mentioned_by_body(&comment.content, &inbox_link, &person.name),
+        ));
       }
     }
   }
@@ -148,12 +218,19 @@ pub async fn send_local_notifs(
     if parent_comment.creator_id != person.id && !creator_blocked {
       let user_view = LocalUserView::read_person(&mut context.pool(), parent_creator_id).await;
       if let Ok(parent_user_view) = user_view {
-        recipient_ids.push(parent_user_view.local_user.id);
+        let muted = contains_muted_word(
+          &comment.content,
+          &parent_user_view.local_user.notification_muted_words,
+        );
+        if !muted {
+          recipient_ids.push(parent_user_view.local_user.id);
+        }
 
         let comment_reply_form = CommentReplyInsertForm {
           recipient_id: parent_user_view.person.id,
           comment_id: comment.id,
           read: None,
+          muted: Some(muted),
         };
 
         // Allow this to fail softly, since comment edits might re-update or replace it
@@ -162,15 +239,13 @@ pub async fn send_local_notifs(
           .await
           .ok();
 
-        if do_send_email {
+        if do_send_email && !muted {
           let lang = get_interface_language(&parent_user_view);
-          send_email_to_user(
-            &parent_user_view,
-            &lang.notification_comment_reply_subject(&person.name),
-            &lang.notification_comment_reply_body(&comment.content, &inbox_link, &person.name),
-            context.settings(),
-          )
-          .await
+          emails.push((
+            parent_user_view,
+            lang.notification_comment_reply_subject(&person.name),
+            lang.notification_comment_reply_body(&comment.content, &inbox_link, &person.name),
+          ));
         }
       }
     }
@@ -185,12 +260,19 @@ pub async fn send_local_notifs(
       let creator_id = post.creator_id;
       let parent_user = LocalUserView::read_person(&mut context.pool(), creator_id).await;
       if let Ok(parent_user_view) = parent_user {
-        recipient_ids.push(parent_user_view.local_user.id);
+        let muted = contains_muted_word(
+          &comment.content,
+          &parent_user_view.local_user.notification_muted_words,
+        );
+        if !muted {
+          recipient_ids.push(parent_user_view.local_user.id);
+        }
 
         let comment_reply_form = CommentReplyInsertForm {
           recipient_id: parent_user_view.person.id,
           comment_id: comment.id,
           read: None,
+          muted: Some(muted),
         };
 
         // Allow this to fail softly, since comment edits might re-update or replace it
@@ -199,19 +281,18 @@ pub async fn send_local_notifs(
           .await
           .ok();
 
-        if do_send_email {
+        if do_send_email && !muted {
           let lang = get_interface_language(&parent_user_view);
-          send_email_to_user(
-            &parent_user_view,
-            &lang.notification_post_reply_subject(&person.name),
-            &lang.notification_post_reply_body(&comment.content, &inbox_link, &person.name),
-            context.settings(),
-          )
-          .await
+          emails.push((
+            parent_user_view,
+            lang.notification_post_reply_subject(&person.name),
+            lang.notification_post_reply_body(&comment.content, &inbox_link, &person.name),
+          ));
         }
       }
     }
   }
 
+  send_emails_to_users(emails, context.settings()).await;
   Ok(recipient_ids)
 }
@@ -1,12 +1,14 @@
-use crate::sensitive::Sensitive;
+use crate::{permissions::Permissions, sensitive::Sensitive};
 use lemmy_db_schema::{
   newtypes::{CommentId, CommentReportId, CommunityId, LanguageId, LocalUserId, PostId},
+  source::{community::Community, edit_history::CommentEditHistory},
   CommentSortType,
   ListingType,
 };
 use lemmy_db_views::structs::{CommentReportView, CommentView};
 use serde::{Deserialize, Serialize};
-use serde_with::skip_serializing_none;
+use serde_with::{formats::CommaSeparator, serde_as, skip_serializing_none, StringWithSeparator};
+use std::collections::HashMap;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 
@@ -20,6 +22,12 @@ pub struct CreateComment {
   pub post_id: PostId,
   pub parent_id: Option<CommentId>,
   pub language_id: Option<LanguageId>,
+  /// Hides the comment behind a warning until it is expanded.
+  pub content_warning: Option<String>,
+  pub nsfw: Option<bool>,
+  /// A random id of the form. Submitting the same form again within ten minutes returns the
+  /// comment which it created, instead of a duplicate.
+  pub form_id: Option<String>,
   pub auth: Sensitive<String>,
 }
 
@@ -42,6 +50,9 @@ pub struct EditComment {
   pub comment_id: CommentId,
   pub content: Option<String>,
   pub language_id: Option<LanguageId>,
+  /// An empty string removes the content warning.
+  pub content_warning: Option<String>,
+  pub nsfw: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
@@ -79,6 +90,18 @@ pub struct RemoveComment {
   pub auth: Sensitive<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Lock a comment thread, so that the comment and its replies can't get new replies (only doable
+/// by mods).
+pub struct LockComment {
+  pub comment_id: CommentId,
+  pub locked: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -86,6 +109,10 @@ pub struct RemoveComment {
 pub struct SaveComment {
   pub comment_id: CommentId,
   pub save: bool,
+  /// Unix timestamp when to be reminded of it. Saving again changes or clears the reminder.
+  pub remind_at: Option<i64>,
+  /// A folder to save it into. Saving again moves it to another tag, or clears it.
+  pub tag: Option<String>,
   pub auth: Sensitive<String>,
 }
 
@@ -97,6 +124,13 @@ pub struct SaveComment {
 pub struct CommentResponse {
   pub comment_view: CommentView,
   pub recipient_ids: Vec<LocalUserId>,
+  /// After creating a comment, the updated child counts of its parent comments, starting at the top
+  /// level.
+  pub ancestor_counts: Vec<(CommentId, i64)>,
+  /// After creating a comment, the updated number of comments on the post.
+  pub post_comments: Option<i64>,
+  /// After creating or editing a comment, the communities which it mentions as `!name@domain`.
+  pub community_mentions: Vec<Community>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -111,6 +145,7 @@ pub struct CreateCommentLike {
 }
 
 #[skip_serializing_none]
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -126,17 +161,38 @@ pub struct GetComments {
   pub post_id: Option<PostId>,
   pub parent_id: Option<CommentId>,
   pub saved_only: Option<bool>,
+  /// Only the saved items with this tag, if `saved_only` is set.
+  pub saved_tag: Option<String>,
   pub liked_only: Option<bool>,
   pub disliked_only: Option<bool>,
+  /// Page over the top-level comments of the post or parent comment instead, with their replies
+  /// up to `max_depth`. `limit` then applies to the top-level comments.
+  pub page_by_branches: Option<bool>,
+  /// Where the next page of top-level comments starts, from the `next_page` of the previous
+  /// response. Implies `page_by_branches`.
+  pub page_cursor: Option<String>,
+  /// Show comments below your hide_content_below_score setting anyway.
+  pub ignore_score_filter: Option<bool>,
+  /// Only include these fields of each comment, like `post.name,creator.name,counts.score`.
+  #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, String>>")]
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub fields: Option<Vec<String>>,
+  /// Also return what the user is allowed to do with each comment.
+  pub include_permissions: Option<bool>,
   pub auth: Option<Sensitive<String>>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// The comment list response.
 pub struct GetCommentsResponse {
   pub comments: Vec<CommentView>,
+  /// Only given when paging by branches, and there are more top-level comments.
+  pub next_page: Option<String>,
+  /// Only given with `include_permissions`, by the id of the comment.
+  pub comment_permissions: Option<HashMap<CommentId, Permissions>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -189,3 +245,21 @@ pub struct ListCommentReports {
 pub struct ListCommentReportsResponse {
   pub comment_reports: Vec<CommentReportView>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the previous contents of an edited comment. Only for its creator, the moderators of the
+/// community and admins.
+pub struct GetCommentEditHistory {
+  pub comment_id: CommentId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The previous contents of a comment, newest first.
+pub struct GetCommentEditHistoryResponse {
+  pub revisions: Vec<CommentEditHistory>,
+}
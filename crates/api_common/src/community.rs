@@ -1,11 +1,34 @@
-use crate::sensitive::Sensitive;
+use crate::{permissions::Permissions, sensitive::Sensitive};
 use lemmy_db_schema::{
-  newtypes::{CommunityId, LanguageId, PersonId},
-  source::site::Site,
+  newtypes::{
+    CommunityFlairOptionId,
+    CommunityId,
+    CommunityPageId,
+    LanguageId,
+    ModReasonTemplateId,
+    PersonId,
+  },
+  source::{
+    community_aggregates_snapshot::CommunityAggregatesSnapshot,
+    community_digest::CommunityDigest,
+    community_flair::{CommunityFlairOption, CommunityPersonFlair},
+    community_page::CommunityPage,
+    community_resync_job::CommunityResyncJob,
+    mod_reason_template::ModReasonTemplate,
+    site::Site,
+  },
+  CommentSortType,
+  CommunityStatsRange,
+  ContributorRange,
   ListingType,
   SortType,
 };
-use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView, PersonView};
+use lemmy_db_views_actor::structs::{
+  CommunityContributorView,
+  CommunityModeratorView,
+  CommunityView,
+  PersonView,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -33,6 +56,10 @@ pub struct GetCommunityResponse {
   pub site: Option<Site>,
   pub moderators: Vec<CommunityModeratorView>,
   pub discussion_languages: Vec<LanguageId>,
+  /// The reasons which the mods can pick for removals and bans. Only given to mods and admins.
+  pub mod_reason_templates: Option<Vec<ModReasonTemplate>>,
+  /// What the user is allowed to do in the community. Only given when reading the community.
+  pub permissions: Option<Permissions>,
 }
 
 #[skip_serializing_none]
@@ -55,6 +82,8 @@ pub struct CreateCommunity {
   pub nsfw: Option<bool>,
   /// Whether to restrict posting only to moderators.
   pub posting_restricted_to_mods: Option<bool>,
+  /// Whether to restrict commenting only to moderators.
+  pub commenting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub auth: Sensitive<String>,
 }
@@ -77,6 +106,8 @@ pub struct ListCommunities {
   pub type_: Option<ListingType>,
   pub sort: Option<SortType>,
   pub show_nsfw: Option<bool>,
+  /// Only list communities which the admins verified.
+  pub verified_only: Option<bool>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub auth: Option<Sensitive<String>>,
@@ -152,7 +183,19 @@ pub struct EditCommunity {
   pub nsfw: Option<bool>,
   /// Whether to restrict posting only to moderators.
   pub posting_restricted_to_mods: Option<bool>,
+  /// Whether to restrict commenting only to moderators.
+  pub commenting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
+  /// The post sort when the request doesn't specify one, which takes precedence over the default
+  /// sort of logged in users. Null clears it.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub default_post_sort: Option<Option<SortType>>,
+  /// The comment sort when the request doesn't specify one. Null clears it.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub default_comment_sort: Option<Option<CommentSortType>>,
+  /// How many days old accounts need to be to vote in the community.
+  pub min_account_age_days_to_vote: Option<i32>,
+  pub only_followers_can_vote: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
@@ -169,6 +212,16 @@ pub struct HideCommunity {
   pub auth: Sensitive<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mark a community as official, or revoke it. Only admins can do this.
+pub struct VerifyCommunity {
+  pub community_id: CommunityId,
+  pub verified: bool,
+  pub auth: Sensitive<String>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -227,8 +280,378 @@ pub struct BlockCommunityResponse {
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// Transfer a community to a new owner.
+///
+/// The transfer only happens once the new owner accepts it with `AcceptCommunityTransfer`, unless
+/// it is done by an admin. The top mod can cancel a pending transfer by transferring to themselves.
 pub struct TransferCommunity {
   pub community_id: CommunityId,
   pub person_id: PersonId,
   pub auth: Sensitive<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Accept or decline becoming the top mod of a community.
+pub struct AcceptCommunityTransfer {
+  pub community_id: CommunityId,
+  pub accept: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create a community page (only doable by moderators).
+pub struct CreateCommunityPage {
+  pub community_id: CommunityId,
+  /// The slug of the page, unique within the community. Example: rules
+  pub slug: String,
+  pub title: String,
+  pub content: String,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Edit a community page (only doable by moderators).
+pub struct EditCommunityPage {
+  pub id: CommunityPageId,
+  pub title: Option<String>,
+  pub content: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a community page (only doable by moderators).
+pub struct DeleteCommunityPage {
+  pub id: CommunityPageId,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get a community page by its slug.
+pub struct GetCommunityPage {
+  pub community_id: CommunityId,
+  pub slug: String,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the pages of a community.
+pub struct ListCommunityPages {
+  pub community_id: CommunityId,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A community page response.
+pub struct CommunityPageResponse {
+  pub community_page: CommunityPage,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The community pages response.
+pub struct ListCommunityPagesResponse {
+  pub community_pages: Vec<CommunityPage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for deleting a community page.
+pub struct DeleteCommunityPageResponse {
+  pub id: CommunityPageId,
+  pub success: bool,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Give a person a flair in a local community (only doable by moderators).
+pub struct SetCommunityPersonFlair {
+  pub community_id: CommunityId,
+  pub person_id: PersonId,
+  pub flair: String,
+  /// A hex color like `#1e90ff`.
+  pub color: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Remove the flair of a person in a community (only doable by moderators).
+pub struct RemoveCommunityPersonFlair {
+  pub community_id: CommunityId,
+  pub person_id: PersonId,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Pick your own flair in a community, from those which its moderators offer. Without a flair
+/// option, your flair is removed.
+pub struct PickCommunityFlair {
+  pub community_id: CommunityId,
+  pub flair_option_id: Option<CommunityFlairOptionId>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The flair of the person, if they still have one.
+pub struct CommunityPersonFlairResponse {
+  pub flair: Option<CommunityPersonFlair>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Offer a flair which members can pick for themselves (only doable by moderators). Members can
+/// pick their flair once the community offers any.
+pub struct CreateCommunityFlairOption {
+  pub community_id: CommunityId,
+  pub flair: String,
+  /// A hex color like `#1e90ff`.
+  pub color: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Stop offering a flair (only doable by moderators). Members who picked it keep it.
+pub struct DeleteCommunityFlairOption {
+  pub id: CommunityFlairOptionId,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the flairs which members of a community can pick.
+pub struct ListCommunityFlairOptions {
+  pub community_id: CommunityId,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A community flair option response.
+pub struct CommunityFlairOptionResponse {
+  pub flair_option: CommunityFlairOption,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The community flair options response.
+pub struct ListCommunityFlairOptionsResponse {
+  pub flair_options: Vec<CommunityFlairOption>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for deleting a community flair option.
+pub struct DeleteCommunityFlairOptionResponse {
+  pub id: CommunityFlairOptionId,
+  pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Add a reason which the mods of a community can pick for removals and bans (only doable by
+/// moderators).
+pub struct CreateModReasonTemplate {
+  pub community_id: CommunityId,
+  pub reason: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Change the text of a mod reason template (only doable by moderators).
+pub struct EditModReasonTemplate {
+  pub id: ModReasonTemplateId,
+  pub reason: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a mod reason template (only doable by moderators).
+pub struct DeleteModReasonTemplate {
+  pub id: ModReasonTemplateId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the mod reason templates of a community (only doable by moderators).
+pub struct ListModReasonTemplates {
+  pub community_id: CommunityId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A mod reason template response.
+pub struct ModReasonTemplateResponse {
+  pub mod_reason_template: ModReasonTemplate,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The mod reason templates of a community.
+pub struct ListModReasonTemplatesResponse {
+  pub mod_reason_templates: Vec<ModReasonTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for deleting a mod reason template.
+pub struct DeleteModReasonTemplateResponse {
+  pub id: ModReasonTemplateId,
+  pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Configure the weekly digest post of a local community (only doable by moderators).
+pub struct EditCommunityDigest {
+  pub community_id: CommunityId,
+  pub enabled: bool,
+  /// The day of the week on which the digest is posted, from 0 for Monday to 6 for Sunday.
+  pub day_of_week: i16,
+  /// The hour (UTC) at which the digest is posted.
+  pub hour: i16,
+  /// How many of the top posts of the past week are listed.
+  pub top_posts: i32,
+  /// Only posts with at least this score are listed.
+  pub min_score: i32,
+  /// Feature the digest in the community, replacing the previous one.
+  pub feature: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the weekly digest settings of a community (only doable by moderators).
+pub struct GetCommunityDigest {
+  pub community_id: CommunityId,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The weekly digest settings of a community, if they were ever configured.
+pub struct CommunityDigestResponse {
+  pub community_digest: Option<CommunityDigest>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the people who contributed the most to a community.
+pub struct GetCommunityTopContributors {
+  pub community_id: CommunityId,
+  pub range: Option<ContributorRange>,
+  /// At most 50, defaults to 10.
+  pub limit: Option<i64>,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The top contributors of a community, ranked from the highest.
+pub struct GetCommunityTopContributorsResponse {
+  pub contributors: Vec<CommunityContributorView>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the statistics of a local community. Only for its moderators and admins.
+pub struct GetCommunityStats {
+  pub community_id: CommunityId,
+  pub range: Option<CommunityStatsRange>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The daily statistics of a community, newest day first. The current day isn't finished yet, so
+/// it isn't included.
+pub struct GetCommunityStatsResponse {
+  pub range: CommunityStatsRange,
+  pub days: Vec<CommunityAggregatesSnapshot>,
+  /// The ten people who wrote the most comments in the range.
+  pub top_commenters: Vec<CommunityContributorView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetch the missing comments of the newest posts in a remote community from its home instance.
+/// This runs in the background, the progress is returned by `GetCommunityResyncJob`. Only for
+/// moderators of the community and admins.
+pub struct ResyncRemoteCommunity {
+  pub community_id: CommunityId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the progress of a community resync.
+pub struct GetCommunityResyncJob {
+  pub job_id: i32,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A community resync which was started, or its progress.
+pub struct CommunityResyncJobResponse {
+  pub job: CommunityResyncJob,
+}
@@ -1,3 +1,4 @@
+use crate::{database_health::DatabaseHealth, site::GetSiteResponse};
 use lemmy_db_schema::{
   source::secret::Secret,
   utils::{ActualDbPool, DbPool},
@@ -7,7 +8,7 @@ use lemmy_utils::{
   settings::{structs::Settings, SETTINGS},
 };
 use reqwest_middleware::ClientWithMiddleware;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 #[derive(Clone)]
 pub struct LemmyContext {
@@ -15,6 +16,12 @@ pub struct LemmyContext {
   client: Arc<ClientWithMiddleware>,
   secret: Arc<Secret>,
   rate_limit_cell: RateLimitCell,
+  settings: &'static Settings,
+  /// Domains which the instance was moved away from, and which redirect to the current one.
+  domain_aliases: Arc<RwLock<Vec<String>>>,
+  database_health: Arc<DatabaseHealth>,
+  /// The site info as it was last read, without the user specific parts.
+  cached_site: Arc<RwLock<Option<GetSiteResponse>>>,
 }
 
 impl LemmyContext {
@@ -29,8 +36,18 @@ impl LemmyContext {
       client: Arc::new(client),
       secret: Arc::new(secret),
       rate_limit_cell,
+      settings: &SETTINGS,
+      domain_aliases: Arc::default(),
+      database_health: Arc::default(),
+      cached_site: Arc::default(),
     }
   }
+  /// Replaces the settings from the config file, so that tests can run multiple instances with
+  /// different hostnames in one process.
+  pub fn with_settings(mut self, settings: &'static Settings) -> LemmyContext {
+    self.settings = settings;
+    self
+  }
   pub fn pool(&self) -> DbPool<'_> {
     DbPool::Pool(&self.pool)
   }
@@ -41,7 +58,7 @@ impl LemmyContext {
     &self.client
   }
   pub fn settings(&self) -> &'static Settings {
-    &SETTINGS
+    self.settings
   }
   pub fn secret(&self) -> &Secret {
     &self.secret
@@ -49,4 +66,33 @@ impl LemmyContext {
   pub fn settings_updated_channel(&self) -> &RateLimitCell {
     &self.rate_limit_cell
   }
+  pub fn domain_aliases(&self) -> Vec<String> {
+    self
+      .domain_aliases
+      .read()
+      .map(|a| a.clone())
+      .unwrap_or_default()
+  }
+  pub fn database_health(&self) -> &DatabaseHealth {
+    &self.database_health
+  }
+  /// The site info for logged out users, while the database is unavailable.
+  pub fn cached_site(&self) -> Option<GetSiteResponse> {
+    self.cached_site.read().ok()?.clone()
+  }
+  pub fn set_cached_site(&self, site: &GetSiteResponse) {
+    if let Ok(mut cached_site) = self.cached_site.write() {
+      *cached_site = Some(GetSiteResponse {
+        my_user: None,
+        ..site.clone()
+      });
+    }
+  }
+  pub fn add_domain_alias(&self, domain: String) {
+    if let Ok(mut aliases) = self.domain_aliases.write() {
+      if !aliases.contains(&domain) {
+        aliases.push(domain);
+      }
+    }
+  }
 }
@@ -0,0 +1,99 @@
+use crate::context::LemmyContext;
+use lemmy_db_schema::utils::{ping_database, ActualDbPool};
+use std::{
+  sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+  time::Duration,
+};
+use tracing::{info, warn};
+
+/// Tracks if the database is reachable. While it isn't, writes are rejected right away, and the
+/// pool is emptied so that no broken connections are handed out once the database is back.
+pub struct DatabaseHealth {
+  available: AtomicBool,
+  /// The size of the pool at the last successful check, which is restored after an outage.
+  healthy_pool_size: AtomicUsize,
+  state_changes: AtomicU64,
+}
+
+impl Default for DatabaseHealth {
+  fn default() -> Self {
+    DatabaseHealth {
+      available: AtomicBool::new(true),
+      healthy_pool_size: AtomicUsize::new(0),
+      state_changes: AtomicU64::new(0),
+    }
+  }
+}
+
+impl DatabaseHealth {
+  pub fn is_available(&self) -> bool {
+    self.available.load(Ordering::Relaxed)
+  }
+
+  /// How often the database became unavailable or available again since startup.
+  pub fn state_changes(&self) -> u64 {
+    self.state_changes.load(Ordering::Relaxed)
+  }
+
+  /// Pings the database with a connection from the pool, and updates the state with the result.
+  pub async fn check(&self, pool: &ActualDbPool) -> bool {
+    match ping_database(pool).await {
+      Ok(()) => {
+        self.record_success(pool).await;
+        true
+      }
+      Err(e) => {
+        self.record_failure(pool, &e.to_string());
+        false
+      }
+    }
+  }
+
+  /// Marks the database as unavailable, and drops the idle connections of the pool which are
+  /// most likely broken. Connections which are in use are dropped when they are returned.
+  pub fn record_failure(&self, pool: &ActualDbPool, reason: &str) {
+    if self.available.swap(false, Ordering::Relaxed) {
+      warn!("Database is unavailable, rejecting writes until it is back: {reason}");
+      pool.retain(|_, _| false);
+      self.state_changes.fetch_add(1, Ordering::Relaxed);
+    }
+  }
+
+  async fn record_success(&self, pool: &ActualDbPool) {
+    if self.available.swap(true, Ordering::Relaxed) {
+      self
+        .healthy_pool_size
+        .store(pool.status().size, Ordering::Relaxed);
+    } else {
+      info!("Database is available again");
+      self.state_changes.fetch_add(1, Ordering::Relaxed);
+      replenish(pool, self.healthy_pool_size.load(Ordering::Relaxed)).await;
+    }
+  }
+
+  /// Checks the database periodically, so that the instance also recovers while it gets no
+  /// requests.
+  pub async fn monitor(context: LemmyContext) {
+    let seconds = context.settings().database.health_check_interval_seconds;
+    let mut interval = tokio::time::interval(Duration::from_secs(seconds.max(1)));
+    loop {
+      interval.tick().await;
+      context.database_health().check(context.inner_pool()).await;
+    }
+  }
+}
+
+/// Opens connections until the pool has the given size again, so that the first requests after
+/// an outage don't all have to wait for new connections.
+async fn replenish(pool: &ActualDbPool, connections: usize) {
+  let mut opened = Vec::with_capacity(connections);
+  while pool.status().size < connections {
+    match pool.get().await {
+      Ok(conn) => opened.push(conn),
+      Err(e) => {
+        warn!("Couldn't open database connections after the outage: {e}");
+        break;
+      }
+    }
+  }
+}
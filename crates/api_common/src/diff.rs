@@ -0,0 +1,453 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+/// Upper bound for the work done by a single diff, roughly the number of compared tokens. Texts
+/// which need more work than this are shown as a whole-text replacement instead.
+const MAX_DIFF_COST: usize = 5_000_000;
+
+/// The longest texts which are diffed, in bytes. Enough for two post bodies of the maximum
+/// length, longer texts are shown as a whole-text replacement.
+const MAX_DIFF_INPUT_LEN: usize = 400_000;
+
+/// The most edits which a single token diff may need. Backtracking keeps the reachable diagonals
+/// of every step, so memory grows with the square of this.
+const MAX_DIFF_EDITS: isize = 1_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum DiffSpanKind {
+  Unchanged,
+  Added,
+  Removed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A piece of text which was kept, added or removed.
+pub struct DiffSpan {
+  pub kind: DiffSpanKind,
+  pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A run of whole lines which are either all unchanged, or changed. Changed lines are split into
+/// word level spans.
+pub struct DiffHunk {
+  pub changed: bool,
+  pub spans: Vec<DiffSpan>,
+}
+
+/// Computes a line and word level diff from `old` to `new`.
+///
+/// Concatenating the unchanged and removed spans gives back `old`, the unchanged and added spans
+/// give `new`.
+pub fn diff_text(old: &str, new: &str) -> Vec<DiffHunk> {
+  diff_text_with_budget(old, new, MAX_DIFF_COST)
+}
+
+fn diff_text_with_budget(old: &str, new: &str, budget: usize) -> Vec<DiffHunk> {
+  if old.len().saturating_add(new.len()) > MAX_DIFF_INPUT_LEN {
+    return replacement_hunk(old, new).into_iter().collect();
+  }
+  let mut budget = budget;
+  let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+  let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+  let Some(edits) = diff_tokens(&old_lines, &new_lines, &mut budget) else {
+    return replacement_hunk(old, new).into_iter().collect();
+  };
+
+  let mut hunks = vec![];
+  let mut unchanged = String::new();
+  let mut removed = String::new();
+  let mut added = String::new();
+  for (kind, line) in edit_texts(&edits, &old_lines, &new_lines) {
+    match kind {
+      DiffSpanKind::Unchanged => {
+        hunks.extend(changed_hunk(&removed, &added, &mut budget));
+        removed.clear();
+        added.clear();
+        unchanged.push_str(line);
+      }
+      DiffSpanKind::Removed | DiffSpanKind::Added => {
+        if !unchanged.is_empty() {
+          hunks.push(DiffHunk {
+            changed: false,
+            spans: vec![span(DiffSpanKind::Unchanged, &unchanged)],
+          });
+          unchanged.clear();
+        }
+        if kind == DiffSpanKind::Removed {
+          removed.push_str(line);
+        } else {
+          added.push_str(line);
+        }
+      }
+    }
+  }
+  hunks.extend(changed_hunk(&removed, &added, &mut budget));
+  if !unchanged.is_empty() {
+    hunks.push(DiffHunk {
+      changed: false,
+      spans: vec![span(DiffSpanKind::Unchanged, &unchanged)],
+    });
+  }
+  hunks
+}
+
+/// Word level diff of the changed lines, falling back to replacing them completely.
+fn changed_hunk(removed: &str, added: &str, budget: &mut usize) -> Option<DiffHunk> {
+  if removed.is_empty() && added.is_empty() {
+    return None;
+  }
+  let old_words = split_words(removed);
+  let new_words = split_words(added);
+  let Some(edits) = diff_tokens(&old_words, &new_words, budget) else {
+    return replacement_hunk(removed, added);
+  };
+
+  let mut spans: Vec<DiffSpan> = vec![];
+  for (kind, text) in edit_texts(&edits, &old_words, &new_words) {
+    match spans.last_mut() {
+      Some(last) if last.kind == kind => last.text.push_str(text),
+      _ => spans.push(span(kind, text)),
+    }
+  }
+  Some(DiffHunk {
+    changed: true,
+    spans,
+  })
+}
+
+fn replacement_hunk(old: &str, new: &str) -> Option<DiffHunk> {
+  let spans: Vec<DiffSpan> = [(DiffSpanKind::Removed, old), (DiffSpanKind::Added, new)]
+    .into_iter()
+    .filter(|(_, text)| !text.is_empty())
+    .map(|(kind, text)| span(kind, text))
+    .collect();
+  if spans.is_empty() {
+    None
+  } else {
+    Some(DiffHunk {
+      changed: true,
+      spans,
+    })
+  }
+}
+
+fn span(kind: DiffSpanKind, text: &str) -> DiffSpan {
+  DiffSpan {
+    kind,
+    text: text.to_string(),
+  }
+}
+
+/// Splits text into alternating runs of whitespace and non-whitespace characters.
+fn split_words(text: &str) -> Vec<&str> {
+  let mut words = vec![];
+  let mut start = 0;
+  let mut prev_whitespace = None;
+  for (i, c) in text.char_indices() {
+    let whitespace = c.is_whitespace();
+    if prev_whitespace.is_some_and(|p| p != whitespace) {
+      words.extend(text.get(start..i));
+      start = i;
+    }
+    prev_whitespace = Some(whitespace);
+  }
+  words.extend(text.get(start..).filter(|w| !w.is_empty()));
+  words
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+  Equal(usize),
+  Delete(usize),
+  Insert(usize),
+}
+
+fn edit_texts<'a>(
+  edits: &'a [Edit],
+  old: &'a [&'a str],
+  new: &'a [&'a str],
+) -> impl Iterator<Item = (DiffSpanKind, &'a str)> + 'a {
+  edits.iter().filter_map(|e| match *e {
+    Edit::Equal(i) => old.get(i).map(|t| (DiffSpanKind::Unchanged, *t)),
+    Edit::Delete(i) => old.get(i).map(|t| (DiffSpanKind::Removed, *t)),
+    Edit::Insert(i) => new.get(i).map(|t| (DiffSpanKind::Added, *t)),
+  })
+}
+
+/// Shortest edit script between the two token lists, or None if it costs more than the budget.
+/// Common prefix and suffix are stripped first, so that small edits of long texts are cheap.
+fn diff_tokens(old: &[&str], new: &[&str], budget: &mut usize) -> Option<Vec<Edit>> {
+  let prefix = old
+    .iter()
+    .zip(new.iter())
+    .take_while(|(a, b)| a == b)
+    .count();
+  let suffix = old
+    .iter()
+    .skip(prefix)
+    .rev()
+    .zip(new.iter().skip(prefix).rev())
+    .take_while(|(a, b)| a == b)
+    .count();
+  let old_middle = old.get(prefix..old.len() - suffix).unwrap_or_default();
+  let new_middle = new.get(prefix..new.len() - suffix).unwrap_or_default();
+
+  let middle = myers(old_middle, new_middle, budget)?;
+  let mut edits: Vec<Edit> = (0..prefix).map(Edit::Equal).collect();
+  edits.extend(middle.into_iter().map(|e| match e {
+    Edit::Equal(i) => Edit::Equal(i + prefix),
+    Edit::Delete(i) => Edit::Delete(i + prefix),
+    Edit::Insert(i) => Edit::Insert(i + prefix),
+  }));
+  edits.extend((old.len() - suffix..old.len()).map(Edit::Equal));
+  Some(edits)
+}
+
+/// Myers' O((N+M)D) diff algorithm. Only the diagonals which were reachable after each step are
+/// kept for backtracking, so memory use is O(D^2). Gives up after `MAX_DIFF_EDITS` steps.
+fn myers(old: &[&str], new: &[&str], budget: &mut usize) -> Option<Vec<Edit>> {
+  let n = old.len() as isize;
+  let m = new.len() as isize;
+  let max = n + m;
+  // v[k + offset] is the furthest x reached on diagonal k = x - y
+  let offset = max + 1;
+  let mut v = vec![0isize; (2 * max + 3) as usize];
+  let get = |v: &[isize], k: isize| v.get((k + offset) as usize).copied().unwrap_or_default();
+  let mut trace: Vec<Vec<isize>> = vec![];
+
+  for d in 0..=max.min(MAX_DIFF_EDITS) {
+    trace.push(
+      v.get((offset - d - 1) as usize..=(offset + d + 1) as usize)
+        .unwrap_or_default()
+        .to_vec(),
+    );
+    for k in (-d..=d).step_by(2) {
+      let mut x = if k == -d || (k != d && get(&v, k - 1) < get(&v, k + 1)) {
+        get(&v, k + 1)
+      } else {
+        get(&v, k - 1) + 1
+      };
+      let mut y = x - k;
+      while x < n && y < m && old.get(x as usize) == new.get(y as usize) {
+        x += 1;
+        y += 1;
+        *budget = budget.checked_sub(1)?;
+      }
+      *budget = budget.checked_sub(1)?;
+      if let Some(slot) = v.get_mut((k + offset) as usize) {
+        *slot = x;
+      }
+      if x >= n && y >= m {
+        return Some(backtrack(&trace, n, m));
+      }
+    }
+  }
+  None
+}
+
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<Edit> {
+  let mut edits = vec![];
+  let (mut x, mut y) = (n, m);
+  for (d, v) in trace.iter().enumerate().rev() {
+    let d = d as isize;
+    // snapshots hold the diagonals -d-1..=d+1
+    let get = |k: isize| v.get((k + d + 1) as usize).copied().unwrap_or_default();
+    let k = x - y;
+    let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+      k + 1
+    } else {
+      k - 1
+    };
+    let prev_x = get(prev_k);
+    let prev_y = prev_x - prev_k;
+    while x > prev_x && y > prev_y {
+      x -= 1;
+      y -= 1;
+      edits.push(Edit::Equal(x as usize));
+    }
+    if d > 0 {
+      if x == prev_x {
+        edits.push(Edit::Insert(prev_y as usize));
+      } else {
+        edits.push(Edit::Delete(prev_x as usize));
+      }
+    }
+    x = prev_x;
+    y = prev_y;
+  }
+  edits.reverse();
+  edits
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::diff::{
+    diff_text,
+    diff_text_with_budget,
+    split_words,
+    DiffHunk,
+    DiffSpanKind,
+    MAX_DIFF_INPUT_LEN,
+  };
+
+  /// Rebuilds the old and new text from the diff.
+  fn apply(hunks: &[DiffHunk]) -> (String, String) {
+    let mut old = String::new();
+    let mut new = String::new();
+    for s in hunks.iter().flat_map(|h| h.spans.iter()) {
+      match s.kind {
+        DiffSpanKind::Unchanged => {
+          old.push_str(&s.text);
+          new.push_str(&s.text);
+        }
+        DiffSpanKind::Removed => old.push_str(&s.text),
+        DiffSpanKind::Added => new.push_str(&s.text),
+      }
+    }
+    (old, new)
+  }
+
+  fn changed_texts(hunks: &[DiffHunk], kind: DiffSpanKind) -> Vec<&str> {
+    hunks
+      .iter()
+      .flat_map(|h| h.spans.iter())
+      .filter(|s| s.kind == kind)
+      .map(|s| s.text.as_str())
+      .collect()
+  }
+
+  #[test]
+  fn test_split_words() {
+    assert_eq!(
+      vec!["hello", " ", "wörld", "\n\t", "🦀"],
+      split_words("hello wörld\n\t🦀")
+    );
+    assert!(split_words("").is_empty());
+  }
+
+  #[test]
+  fn test_diff_identical_and_empty() {
+    assert!(diff_text("", "").is_empty());
+    let hunks = diff_text("same\ntext", "same\ntext");
+    assert_eq!(1, hunks.len());
+    assert!(!hunks[0].changed);
+
+    let hunks = diff_text("", "new text");
+    assert_eq!(vec!["new text"], changed_texts(&hunks, DiffSpanKind::Added));
+    assert!(changed_texts(&hunks, DiffSpanKind::Removed).is_empty());
+  }
+
+  #[test]
+  fn test_diff_words() {
+    let old = "first line\nthe quick brown fox\nlast line";
+    let new = "first line\nthe slow brown fox\nlast line";
+    let hunks = diff_text(old, new);
+    assert_eq!((old.to_string(), new.to_string()), apply(&hunks));
+    assert_eq!(3, hunks.len());
+    assert!(!hunks[0].changed);
+    assert!(hunks[1].changed);
+    assert!(!hunks[2].changed);
+    assert_eq!(vec!["quick"], changed_texts(&hunks, DiffSpanKind::Removed));
+    assert_eq!(vec!["slow"], changed_texts(&hunks, DiffSpanKind::Added));
+  }
+
+  #[test]
+  fn test_diff_unicode() {
+    let old = "Grüße aus 東京 🦀\nzweite Zeile";
+    let new = "Grüße aus 大阪 🦀\nzweite Zeile";
+    let hunks = diff_text(old, new);
+    assert_eq!((old.to_string(), new.to_string()), apply(&hunks));
+    assert_eq!(vec!["東京"], changed_texts(&hunks, DiffSpanKind::Removed));
+    assert_eq!(vec!["大阪"], changed_texts(&hunks, DiffSpanKind::Added));
+  }
+
+  #[test]
+  fn test_diff_code_block() {
+    let old = "Example:\n```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n";
+    let new = "Example:\n```rust\nfn main() {\n        println!(\"hello\");\n    return;\n}\n```\n";
+    let hunks = diff_text(old, new);
+    assert_eq!((old.to_string(), new.to_string()), apply(&hunks));
+    // only the lines inside the code block changed, fences are untouched
+    assert_eq!(3, hunks.len());
+    assert_eq!("Example:\n```rust\nfn main() {\n", hunks[0].spans[0].text);
+    assert!(hunks[1].changed);
+    assert_eq!("}\n```\n", hunks[2].spans[0].text);
+    let removed = changed_texts(&hunks, DiffSpanKind::Removed).concat();
+    assert!(removed.contains("(\"hi\");"));
+    assert!(!removed.contains("main"));
+  }
+
+  #[test]
+  fn test_diff_near_identical_long_text() {
+    let old: String = (0..15_000).map(|i| format!("line {i}\n")).collect();
+    let new = old.replacen("line 7500\n", "line seventy-five hundred\n", 1);
+    let hunks = diff_text(&old, &new);
+    assert_eq!((old.clone(), new.clone()), apply(&hunks));
+    assert_eq!(3, hunks.len());
+    assert_eq!(vec!["7500"], changed_texts(&hunks, DiffSpanKind::Removed));
+    assert_eq!(
+      vec!["seventy-five hundred"],
+      changed_texts(&hunks, DiffSpanKind::Added)
+    );
+
+    // many small scattered edits still finish with an exact diff
+    let new: String = (0..15_000)
+      .map(|i| {
+        if i % 1000 == 0 {
+          format!("changed {i}\n")
+        } else {
+          format!("line {i}\n")
+        }
+      })
+      .collect();
+    let hunks = diff_text(&old, &new);
+    assert_eq!((old, new), apply(&hunks));
+    assert_eq!(15, changed_texts(&hunks, DiffSpanKind::Removed).len());
+  }
+
+  #[test]
+  fn test_diff_over_budget_replaces_whole_text() {
+    let old: String = (0..1000).map(|i| format!("old {i}\n")).collect();
+    let new: String = (0..1000).map(|i| format!("new {i}\n")).collect();
+    let hunks = diff_text_with_budget(&old, &new, 1000);
+    assert_eq!(1, hunks.len());
+    assert_eq!(
+      vec![old.as_str()],
+      changed_texts(&hunks, DiffSpanKind::Removed)
+    );
+    assert_eq!(
+      vec![new.as_str()],
+      changed_texts(&hunks, DiffSpanKind::Added)
+    );
+  }
+
+  #[test]
+  fn test_diff_long_input_replaces_whole_text() {
+    let old = "a\n".repeat(MAX_DIFF_INPUT_LEN / 2);
+    let new = format!("{old}b\n");
+    let hunks = diff_text(&old, &new);
+    assert_eq!(1, hunks.len());
+    assert_eq!((old, new), apply(&hunks));
+  }
+
+  #[test]
+  fn test_diff_many_edits_replaces_whole_text() {
+    let old: String = (0..2000).map(|i| format!("old {i}\n")).collect();
+    let new: String = (0..2000).map(|i| format!("new {i}\n")).collect();
+    let hunks = diff_text(&old, &new);
+    assert_eq!(1, hunks.len());
+    assert_eq!((old, new), apply(&hunks));
+  }
+}
@@ -0,0 +1,263 @@
+use lemmy_utils::{error::LemmyError, settings::structs::EmbedProviderConfig};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use tracing::warn;
+use url::Url;
+
+/// The player of a video link from an allowlisted site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Embed {
+  pub url: Url,
+  pub provider: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct OEmbedResponse {
+  html: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PeerTubeVideo {
+  embed_path: String,
+}
+
+/// Looks up the player of the link. Only links to allowlisted sites get one.
+#[tracing::instrument(skip_all)]
+pub async fn fetch_embed(
+  client: &ClientWithMiddleware,
+  providers: &[EmbedProviderConfig],
+  url: &Url,
+) -> Option<Embed> {
+  let provider = find_embed_provider(providers, url)?;
+  let embed_url = if provider.peertube {
+    checked_player_url(provider, url, fetch_peertube_embed(client, url).await)
+  } else if let Some(endpoint) = &provider.oembed_endpoint {
+    checked_player_url(provider, url, fetch_oembed(client, endpoint, url).await)
+  } else {
+    provider
+      .url_template
+      .as_deref()
+      .and_then(|t| template_embed_url(t, url))
+  }?;
+  Some(Embed {
+    url: embed_url,
+    provider: provider.name.clone(),
+  })
+}
+
+/// Players which were fetched from the provider have to be served over https from the same
+/// site, instead of from wherever the response points to.
+fn checked_player_url(
+  provider: &EmbedProviderConfig,
+  url: &Url,
+  player: Result<Option<Url>, LemmyError>,
+) -> Option<Url> {
+  player
+    .map_err(|e| warn!("Failed to fetch the player of {url}: {e}"))
+    .ok()
+    .flatten()
+    .filter(|p| p.scheme() == "https" && matches_provider(provider, p))
+}
+
+fn find_embed_provider<'a>(
+  providers: &'a [EmbedProviderConfig],
+  url: &Url,
+) -> Option<&'a EmbedProviderConfig> {
+  providers.iter().find(|p| matches_provider(p, url))
+}
+
+fn matches_provider(provider: &EmbedProviderConfig, url: &Url) -> bool {
+  let Some(host) = url.host_str() else {
+    return false;
+  };
+  let host = host.to_lowercase();
+  provider.domains.iter().any(|pattern| {
+    let pattern = pattern.to_lowercase();
+    match pattern.strip_prefix("*.") {
+      Some(parent) => host.ends_with(&format!(".{parent}")),
+      None => host == pattern,
+    }
+  })
+}
+
+async fn fetch_oembed(
+  client: &ClientWithMiddleware,
+  endpoint: &Url,
+  url: &Url,
+) -> Result<Option<Url>, LemmyError> {
+  let mut request_url = endpoint.clone();
+  request_url
+    .query_pairs_mut()
+    .append_pair("url", url.as_str())
+    .append_pair("format", "json");
+  let response = client
+    .get(request_url.as_str())
+    .send()
+    .await?
+    .error_for_status()?
+    .json::<OEmbedResponse>()
+    .await?;
+  Ok(oembed_player_url(&response))
+}
+
+/// oEmbed only gives the html of the player, so the url is taken from its iframe.
+fn oembed_player_url(response: &OEmbedResponse) -> Option<Url> {
+  let html = response.html.as_deref()?;
+  let (_, iframe) = html.split_once("<iframe")?;
+  let (_, src) = iframe.split_once("src=")?;
+  let quote = src.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+  let (src, _) = src.get(1..)?.split_once(quote)?;
+  Url::parse(&src.replace("&amp;", "&")).ok()
+}
+
+async fn fetch_peertube_embed(
+  client: &ClientWithMiddleware,
+  url: &Url,
+) -> Result<Option<Url>, LemmyError> {
+  let Some(id) = video_id(url) else {
+    return Ok(None);
+  };
+  let api_url = url.join(&format!("/api/v1/videos/{id}"))?;
+  let video = client
+    .get(api_url.as_str())
+    .send()
+    .await?
+    .error_for_status()?
+    .json::<PeerTubeVideo>()
+    .await?;
+  Ok(peertube_player_url(url, &video))
+}
+
+/// The embed path is relative to the instance which hosts the video.
+fn peertube_player_url(url: &Url, video: &PeerTubeVideo) -> Option<Url> {
+  url.join(&video.embed_path).ok()
+}
+
+fn template_embed_url(template: &str, url: &Url) -> Option<Url> {
+  let id = video_id(url)?;
+  Url::parse(&template.replace("{id}", &id)).ok()
+}
+
+/// The id of the video, from the `v` query parameter or otherwise the last path segment. Ids
+/// with other characters than letters, digits, `-` and `_` are rejected, so that they can be
+/// put into urls as they are.
+fn video_id(url: &Url) -> Option<String> {
+  let id = url
+    .query_pairs()
+    .find(|(k, _)| k == "v")
+    .map(|(_, v)| v.to_string())
+    .or_else(|| {
+      url
+        .path_segments()?
+        .filter(|s| !s.is_empty())
+        .last()
+        .map(ToString::to_string)
+    })?;
+  let valid = !id.is_empty()
+    && id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+  valid.then_some(id)
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::embed::{
+    find_embed_provider,
+    oembed_player_url,
+    peertube_player_url,
+    template_embed_url,
+    OEmbedResponse,
+    PeerTubeVideo,
+  };
+  use lemmy_utils::settings::structs::{EmbedProviderConfig, Settings};
+  use url::Url;
+
+  fn providers() -> Vec<EmbedProviderConfig> {
+    let mut providers = Settings::default().embed_providers;
+    providers.push(EmbedProviderConfig {
+      name: "PeerTube".to_string(),
+      domains: vec!["*.framatube.org".to_string()],
+      url_template: None,
+      oembed_endpoint: None,
+      peertube: true,
+    });
+    providers
+  }
+
+  #[test]
+  fn test_find_embed_provider() {
+    let providers = providers();
+    let provider = |url: &str| {
+      find_embed_provider(&providers, &Url::parse(url).unwrap()).map(|p| p.name.as_str())
+    };
+    assert_eq!(
+      Some("YouTube"),
+      provider("https://www.youtube.com/watch?v=dQw4w9WgXcQ")
+    );
+    assert_eq!(Some("YouTube"), provider("https://youtu.be/dQw4w9WgXcQ"));
+    assert_eq!(Some("Vimeo"), provider("https://vimeo.com/76979871"));
+    assert_eq!(
+      Some("PeerTube"),
+      provider("https://videos.framatube.org/w/9c9de5e8")
+    );
+    // Not allowlisted, even though the site might advertise oEmbed
+    assert_eq!(None, provider("https://framatube.org/w/9c9de5e8"));
+    assert_eq!(None, provider("https://notyoutube.com/watch?v=dQw4w9WgXcQ"));
+    assert_eq!(None, provider("https://youtube.com.example.com/watch"));
+  }
+
+  #[test]
+  fn test_youtube_oembed() {
+    let response: OEmbedResponse =
+      serde_json::from_str(include_str!("../assets/embed/youtube_oembed.json")).unwrap();
+    assert_eq!(
+      Some(Url::parse("https://www.youtube.com/embed/dQw4w9WgXcQ?feature=oembed").unwrap()),
+      oembed_player_url(&response)
+    );
+  }
+
+  #[test]
+  fn test_vimeo_oembed() {
+    let response: OEmbedResponse =
+      serde_json::from_str(include_str!("../assets/embed/vimeo_oembed.json")).unwrap();
+    assert_eq!(
+      Some(
+        Url::parse("https://player.vimeo.com/video/76979871?h=8272103f6e&app_id=122963").unwrap()
+      ),
+      oembed_player_url(&response)
+    );
+  }
+
+  #[test]
+  fn test_peertube_video() {
+    let video: PeerTubeVideo =
+      serde_json::from_str(include_str!("../assets/embed/peertube_video.json")).unwrap();
+    let url = Url::parse("https://videos.framatube.org/w/kkGMgK9ZtnKfYAgnEtQxbv").unwrap();
+    assert_eq!(
+      Some(
+        Url::parse(
+          "https://videos.framatube.org/videos/embed/9c9de5e8-0a1e-484a-b099-e80766180a6d"
+        )
+        .unwrap()
+      ),
+      peertube_player_url(&url, &video)
+    );
+  }
+
+  #[test]
+  fn test_template_embed_url() {
+    let template = "https://www.youtube-nocookie.com/embed/{id}";
+    let url = Url::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=10").unwrap();
+    assert_eq!(
+      Some(Url::parse("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ").unwrap()),
+      template_embed_url(template, &url)
+    );
+    let url = Url::parse("https://www.youtube.com/watch?v=a%2F..%2Fb").unwrap();
+    assert_eq!(None, template_embed_url(template, &url));
+  }
+}
@@ -0,0 +1,329 @@
+use lemmy_db_schema::{
+  aggregates::structs::{CommentAggregates, CommunityAggregates, PersonAggregates, PostAggregates},
+  source::{comment::Comment, community::Community, person::Person, post::Post},
+};
+use lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_db_views_actor::structs::{CommunityView, PersonView};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use serde::{
+  de::{self, DeserializeOwned, Visitor},
+  forward_to_deserialize_any,
+  ser::Error,
+  Deserializer,
+  Serialize,
+  Serializer,
+};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A view which is returned in lists, of which clients can select the fields they need.
+pub trait SelectableFields {
+  /// The paths of the fields which can be selected. The fields of the structs in the view are
+  /// joined to the name of the struct with a dot, like `post.name`.
+  fn selectable_fields() -> BTreeSet<String>;
+}
+
+impl SelectableFields for PostView {
+  fn selectable_fields() -> BTreeSet<String> {
+    field_paths::<Self>(&[
+      ("post", field_names::<Post>()),
+      ("creator", field_names::<Person>()),
+      ("community", field_names::<Community>()),
+      ("counts", field_names::<PostAggregates>()),
+    ])
+  }
+}
+
+impl SelectableFields for CommentView {
+  fn selectable_fields() -> BTreeSet<String> {
+    field_paths::<Self>(&[
+      ("comment", field_names::<Comment>()),
+      ("creator", field_names::<Person>()),
+      ("post", field_names::<Post>()),
+      ("community", field_names::<Community>()),
+      ("counts", field_names::<CommentAggregates>()),
+    ])
+  }
+}
+
+impl SelectableFields for CommunityView {
+  fn selectable_fields() -> BTreeSet<String> {
+    field_paths::<Self>(&[
+      ("community", field_names::<Community>()),
+      ("counts", field_names::<CommunityAggregates>()),
+    ])
+  }
+}
+
+impl SelectableFields for PersonView {
+  fn selectable_fields() -> BTreeSet<String> {
+    field_paths::<Self>(&[
+      ("person", field_names::<Person>()),
+      ("counts", field_names::<PersonAggregates>()),
+    ])
+  }
+}
+
+/// The fields of the views which a client selected, as a tree of field names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSelection(BTreeMap<String, Selected>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Selected {
+  All,
+  Fields(BTreeMap<String, Selected>),
+}
+
+impl FieldSelection {
+  /// Checks the selected fields against the fields of the view. Without any selected fields,
+  /// the views are returned in full.
+  pub fn new<V: SelectableFields>(fields: Option<&[String]>) -> LemmyResult<Option<Self>> {
+    Self::from_valid(fields, &V::selectable_fields())
+  }
+
+  /// Like `new`, for responses with lists of different views. Each selected field has to exist in
+  /// at least one of them.
+  pub fn from_valid(
+    fields: Option<&[String]>,
+    valid: &BTreeSet<String>,
+  ) -> LemmyResult<Option<Self>> {
+    let fields: Vec<&str> = fields
+      .unwrap_or_default()
+      .iter()
+      .map(|f| f.trim())
+      .filter(|f| !f.is_empty())
+      .collect();
+    if fields.is_empty() {
+      return Ok(None);
+    }
+
+    let unknown: Vec<&str> = fields
+      .iter()
+      .filter(|f| !valid.contains(*f))
+      .copied()
+      .collect();
+    if !unknown.is_empty() {
+      let valid: Vec<&str> = valid.iter().map(String::as_str).collect();
+      return Err(LemmyErrorType::UnknownFields(format!(
+        "{}. Valid fields are: {}",
+        unknown.join(", "),
+        valid.join(", ")
+      )))?;
+    }
+
+    let mut selection = BTreeMap::new();
+    for field in fields {
+      let path: Vec<&str> = field.split('.').collect();
+      insert_path(&mut selection, &path);
+    }
+    Ok(Some(FieldSelection(selection)))
+  }
+
+  /// Removes the fields which weren't selected from each item of the lists in the response.
+  fn prune_lists(&self, response: &mut Value) {
+    if let Value::Object(response) = response {
+      for list in response.values_mut() {
+        if let Value::Array(items) = list {
+          for item in items {
+            prune(item, &self.0);
+          }
+        }
+      }
+    }
+  }
+}
+
+/// A list response, which only includes the selected fields of its views once it is serialized.
+/// The queries themselves are not affected by the selection.
+#[derive(Debug, Clone)]
+pub struct SelectFields<T> {
+  pub response: T,
+  selection: Option<FieldSelection>,
+}
+
+impl<T> SelectFields<T> {
+  pub fn new(response: T, selection: Option<FieldSelection>) -> Self {
+    SelectFields {
+      response,
+      selection,
+    }
+  }
+}
+
+impl<T: Serialize> Serialize for SelectFields<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let Some(selection) = &self.selection else {
+      return self.response.serialize(serializer);
+    };
+    let mut response = serde_json::to_value(&self.response).map_err(S::Error::custom)?;
+    selection.prune_lists(&mut response);
+    response.serialize(serializer)
+  }
+}
+
+fn insert_path(selection: &mut BTreeMap<String, Selected>, path: &[&str]) {
+  match path {
+    [] => {}
+    [name] => {
+      selection.insert((*name).to_string(), Selected::All);
+    }
+    [name, rest @ ..] => {
+      let nested = selection
+        .entry((*name).to_string())
+        .or_insert_with(|| Selected::Fields(BTreeMap::new()));
+      // Selecting the whole struct includes all of its fields already
+      if let Selected::Fields(nested) = nested {
+        insert_path(nested, rest);
+      }
+    }
+  }
+}
+
+fn prune(value: &mut Value, selection: &BTreeMap<String, Selected>) {
+  if let Value::Object(object) = value {
+    let pruned: Map<String, Value> = std::mem::take(object)
+      .into_iter()
+      .filter(|(name, _)| selection.contains_key(name))
+      .collect();
+    *object = pruned;
+    for (name, value) in object.iter_mut() {
+      if let Some(Selected::Fields(nested)) = selection.get(name) {
+        prune(value, nested);
+      }
+    }
+  }
+}
+
+/// The names of the view's fields, followed by the paths of the fields of the nested structs.
+fn field_paths<V: DeserializeOwned>(
+  nested: &[(&str, &'static [&'static str])],
+) -> BTreeSet<String> {
+  let mut paths: BTreeSet<String> = field_names::<V>().iter().map(ToString::to_string).collect();
+  for (name, fields) in nested {
+    paths.extend(fields.iter().map(|f| format!("{name}.{f}")));
+  }
+  paths
+}
+
+/// Reads the names of the serialized fields of a struct from its `Deserialize` implementation,
+/// so that they can't get out of sync. Nothing is actually deserialized.
+fn field_names<T: DeserializeOwned>() -> &'static [&'static str] {
+  let mut reader = FieldNameReader { fields: &[] };
+  // Always fails, once the field names were read
+  T::deserialize(&mut reader).ok();
+  reader.fields
+}
+
+struct FieldNameReader {
+  fields: &'static [&'static str],
+}
+
+impl<'de> Deserializer<'de> for &mut FieldNameReader {
+  type Error = de::value::Error;
+
+  fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+    Err(de::Error::custom(
+      "only the field names of structs can be read",
+    ))
+  }
+
+  fn deserialize_struct<V: Visitor<'de>>(
+    self,
+    _name: &'static str,
+    fields: &'static [&'static str],
+    _visitor: V,
+  ) -> Result<V::Value, Self::Error> {
+    self.fields = fields;
+    Err(de::Error::custom("field names were read"))
+  }
+
+  forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option
+    unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::*;
+  use serde_json::json;
+
+  fn fields(fields: &[&str]) -> Vec<String> {
+    fields.iter().map(ToString::to_string).collect()
+  }
+
+  #[test]
+  fn test_selectable_fields() {
+    let post_fields = PostView::selectable_fields();
+    assert!(post_fields.contains("post"));
+    assert!(post_fields.contains("post.name"));
+    assert!(post_fields.contains("creator.name"));
+    assert!(post_fields.contains("counts.score"));
+    assert!(post_fields.contains("my_vote"));
+    // Fields which are never serialized can't be selected
+    assert!(!post_fields.contains("creator.private_key"));
+
+    let comment_fields = CommentView::selectable_fields();
+    assert!(comment_fields.contains("comment.content"));
+    assert!(comment_fields.contains("post.name"));
+  }
+
+  #[test]
+  fn test_unknown_fields() {
+    let selection = FieldSelection::new::<PostView>(Some(&fields(&["post.name", "post.titel"])));
+    let Err(e) = selection else {
+      panic!("unknown field was accepted");
+    };
+    let LemmyErrorType::UnknownFields(message) = e.error_type else {
+      panic!("wrong error type {:?}", e.error_type);
+    };
+    assert!(message.starts_with("post.titel. Valid fields are: "));
+    assert!(message.contains("post.name"));
+  }
+
+  #[test]
+  fn test_no_selection() {
+    assert_eq!(None, FieldSelection::new::<PostView>(None).unwrap());
+    assert_eq!(
+      None,
+      FieldSelection::new::<PostView>(Some(&fields(&["", " "]))).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_prune_lists() {
+    let selection =
+      FieldSelection::new::<PostView>(Some(&fields(&["post.name", "creator", "creator.name"])))
+        .unwrap();
+    let response = json!({
+      "posts": [{
+        "post": {"id": 1, "name": "First", "body": "Long text"},
+        "creator": {"id": 2, "name": "alice"},
+        "counts": {"score": 5},
+        "saved": false,
+      }],
+      "next_page": 2,
+    });
+    let selected = SelectFields::new(response.clone(), selection);
+    assert_eq!(
+      json!({
+        "posts": [{
+          "post": {"name": "First"},
+          "creator": {"id": 2, "name": "alice"},
+        }],
+        "next_page": 2,
+      }),
+      serde_json::to_value(selected).unwrap()
+    );
+
+    // Without a selection the response is unchanged
+    let full = SelectFields::new(&response, None);
+    assert_eq!(
+      serde_json::to_string(&response).unwrap(),
+      serde_json::to_string(&full).unwrap()
+    );
+  }
+}
@@ -0,0 +1,174 @@
+use crate::context::LemmyContext;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use lemmy_db_schema::source::secret::Secret;
+use lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_utils::utils::markdown::markdown_rewrite_image_links;
+use sha2::Sha256;
+use url::Url;
+
+/// Where the image proxy is served, relative to the instance.
+pub const IMAGE_PROXY_PATH: &str = "/api/v3/image_proxy";
+
+fn mac(url: &str, secret: &Secret) -> Hmac<Sha256> {
+  let mut mac = Hmac::<Sha256>::new_from_slice(secret.jwt_secret.as_bytes())
+    .expect("hmac accepts keys of any length");
+  mac.update(url.as_bytes());
+  mac
+}
+
+/// Signs an image url, so that the proxy only loads images which this instance linked to.
+pub fn image_proxy_signature(url: &str, secret: &Secret) -> String {
+  URL_SAFE_NO_PAD.encode(mac(url, secret).finalize().into_bytes())
+}
+
+pub fn verify_image_proxy_signature(url: &str, signature: &str, secret: &Secret) -> bool {
+  let Ok(signature) = URL_SAFE_NO_PAD.decode(signature) else {
+    return false;
+  };
+  mac(url, secret).verify_slice(&signature).is_ok()
+}
+
+fn local_url(context: &LemmyContext) -> Option<Url> {
+  Url::parse(&context.settings().get_protocol_and_hostname()).ok()
+}
+
+fn proxy_url(url: &Url, local_url: &Url, secret: &Secret) -> Option<Url> {
+  if !matches!(url.scheme(), "http" | "https") || url.host() == local_url.host() {
+    return None;
+  }
+  let mut proxied = local_url.join(IMAGE_PROXY_PATH).ok()?;
+  proxied
+    .query_pairs_mut()
+    .append_pair("url", url.as_str())
+    .append_pair("sig", &image_proxy_signature(url.as_str(), secret));
+  Some(proxied)
+}
+
+fn original_url(url: &Url, local_url: &Url, secret: &Secret) -> Option<Url> {
+  if url.host() != local_url.host() || url.path() != IMAGE_PROXY_PATH {
+    return None;
+  }
+  let param = |name: &str| {
+    url
+      .query_pairs()
+      .find(|(key, _)| key == name)
+      .map(|(_, value)| value)
+  };
+  let original = param("url")?;
+  if !verify_image_proxy_signature(&original, &param("sig")?, secret) {
+    return None;
+  }
+  Url::parse(&original).ok()
+}
+
+/// The url through which clients load a remote image. Images of this instance, and urls which
+/// aren't http, are loaded directly.
+pub fn proxy_image_url(url: &Url, context: &LemmyContext) -> Option<Url> {
+  proxy_url(url, &local_url(context)?, context.secret())
+}
+
+/// Lets clients load the remote images in markdown through the proxy.
+pub fn proxy_markdown_images(text: &str, context: &LemmyContext) -> String {
+  let Some(local_url) = local_url(context) else {
+    return text.to_string();
+  };
+  markdown_rewrite_image_links(text, |url| proxy_url(url, &local_url, context.secret()))
+}
+
+/// Replaces proxied images in markdown with their original urls. The proxied urls are only given
+/// out in responses, so they have to be removed again when clients send back an edited text.
+pub fn restore_markdown_images(text: &str, context: &LemmyContext) -> String {
+  let Some(local_url) = local_url(context) else {
+    return text.to_string();
+  };
+  markdown_rewrite_image_links(text, |url| original_url(url, &local_url, context.secret()))
+}
+
+/// Lets clients load the thumbnail and the images in the body of a post through the proxy.
+pub fn proxy_post_view_images(post_view: &mut PostView, context: &LemmyContext) {
+  let post = &mut post_view.post;
+  if let Some(thumbnail_url) = &post.thumbnail_url {
+    if let Some(proxied) = proxy_image_url(thumbnail_url, context) {
+      post.thumbnail_url = Some(proxied.into());
+    }
+  }
+  post.body = post
+    .body
+    .as_deref()
+    .map(|body| proxy_markdown_images(body, context));
+}
+
+/// Lets clients load the images in a comment through the proxy.
+pub fn proxy_comment_view_images(comment_view: &mut CommentView, context: &LemmyContext) {
+  comment_view.comment.content = proxy_markdown_images(&comment_view.comment.content, context);
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::image_proxy::{original_url, proxy_url, verify_image_proxy_signature};
+  use lemmy_db_schema::source::secret::Secret;
+  use url::Url;
+
+  fn secret(jwt_secret: &str) -> Secret {
+    Secret {
+      id: 1,
+      jwt_secret: jwt_secret.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_proxy_url() {
+    let local_url = Url::parse("https://lemmy.tld").unwrap();
+    let secret = secret("secret");
+    let image = Url::parse("https://remote.tld/image.png?size=(large)").unwrap();
+
+    let proxied = proxy_url(&image, &local_url, &secret).unwrap();
+    assert_eq!(Some("lemmy.tld"), proxied.host_str());
+    assert_eq!("/api/v3/image_proxy", proxied.path());
+    assert_eq!(Some(image), original_url(&proxied, &local_url, &secret));
+
+    // Local images, and proxied urls which are already local, are left alone
+    let local_image = Url::parse("https://lemmy.tld/pictrs/image/a.png").unwrap();
+    assert_eq!(None, proxy_url(&local_image, &local_url, &secret));
+    assert_eq!(None, proxy_url(&proxied, &local_url, &secret));
+    let data = Url::parse("data:image/png;base64,AAAA").unwrap();
+    assert_eq!(None, proxy_url(&data, &local_url, &secret));
+  }
+
+  #[test]
+  fn test_signature() {
+    let local_url = Url::parse("https://lemmy.tld").unwrap();
+    let image = Url::parse("https://remote.tld/image.png").unwrap();
+    let proxied = proxy_url(&image, &local_url, &secret("secret")).unwrap();
+    let (_, signature) = proxied.query_pairs().find(|(key, _)| key == "sig").unwrap();
+
+    assert!(verify_image_proxy_signature(
+      image.as_str(),
+      &signature,
+      &secret("secret")
+    ));
+    assert!(!verify_image_proxy_signature(
+      "https://other.tld/image.png",
+      &signature,
+      &secret("secret")
+    ));
+    assert!(!verify_image_proxy_signature(
+      image.as_str(),
+      &signature,
+      &secret("other secret")
+    ));
+    assert!(!verify_image_proxy_signature(
+      image.as_str(),
+      "not base64!",
+      &secret("secret")
+    ));
+    assert_eq!(
+      None,
+      original_url(&proxied, &local_url, &secret("other secret"))
+    );
+  }
+}
@@ -5,7 +5,20 @@ pub mod community;
 #[cfg(feature = "full")]
 pub mod context;
 pub mod custom_emoji;
+#[cfg(feature = "full")]
+pub mod database_health;
+pub mod diff;
+#[cfg(feature = "full")]
+pub mod embed;
+#[cfg(feature = "full")]
+pub mod field_selection;
+#[cfg(feature = "full")]
+pub mod image_proxy;
+pub mod oauth;
+pub mod permissions;
 pub mod person;
+#[cfg(feature = "full")]
+pub mod pictrs_breaker;
 pub mod post;
 pub mod private_message;
 #[cfg(feature = "full")]
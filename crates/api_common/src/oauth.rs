@@ -0,0 +1,110 @@
+use crate::sensitive::Sensitive;
+use lemmy_db_schema::{newtypes::OAuthProviderId, source::oauth_provider::OAuthProvider};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+use url::Url;
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Add an OpenID Connect provider (admin only).
+pub struct CreateOAuthProvider {
+  pub display_name: String,
+  /// The issuer url, which has to serve `/.well-known/openid-configuration`.
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub issuer: Url,
+  pub client_id: String,
+  pub client_secret: Sensitive<String>,
+  /// Defaults to `openid email`.
+  pub scopes: Option<String>,
+  pub enabled: Option<bool>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Edit an OpenID Connect provider (admin only).
+pub struct EditOAuthProvider {
+  pub id: OAuthProviderId,
+  pub display_name: Option<String>,
+  pub client_id: Option<String>,
+  pub client_secret: Option<Sensitive<String>>,
+  pub scopes: Option<String>,
+  pub enabled: Option<bool>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete an OpenID Connect provider (admin only). The accounts linked through it are removed.
+pub struct DeleteOAuthProvider {
+  pub id: OAuthProviderId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for deleting an OpenID Connect provider.
+pub struct DeleteOAuthProviderResponse {
+  pub id: OAuthProviderId,
+  pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for an OpenID Connect provider.
+pub struct OAuthProviderResponse {
+  pub oauth_provider: OAuthProvider,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Redirects to the login page of an OpenID Connect provider. Pass `auth` to link the provider
+/// account to your existing account instead of logging in.
+pub struct AuthorizeOAuth {
+  pub provider_id: OAuthProviderId,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Logs in with the code the provider redirected back with, creating the account if needed.
+pub struct OAuthCallback {
+  pub code: Sensitive<String>,
+  pub state: String,
+  /// The answer to the registration application, which new accounts need if the site requires
+  /// applications.
+  pub answer: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Links the provider account the provider redirected back with to your account. Confirmed with
+/// your current password.
+pub struct LinkOAuthAccount {
+  pub code: Sensitive<String>,
+  pub state: String,
+  pub password: Sensitive<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for linking a provider account.
+pub struct LinkOAuthAccountResponse {
+  pub oauth_provider_id: OAuthProviderId,
+  pub success: bool,
+}
@@ -0,0 +1,243 @@
+#[cfg(feature = "full")]
+use crate::utils::{
+  check_commenting_allowed,
+  check_community_open,
+  check_moderator,
+  check_not_banned,
+  check_not_blocked,
+  check_post_open,
+  check_post_unlocked,
+  check_posting_allowed,
+  check_thread_unlocked,
+  check_voting_requirements,
+};
+#[cfg(feature = "full")]
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  source::{
+    community::{Community, CommunityFollower},
+    person::Person,
+    person_block::PersonBlock,
+    post::Post,
+  },
+  utils::DbPool,
+};
+#[cfg(feature = "full")]
+use lemmy_db_views::structs::CommentView;
+#[cfg(feature = "full")]
+use lemmy_db_views_actor::structs::{CommunityPersonBanView, CommunityView};
+#[cfg(feature = "full")]
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+#[serde(rename_all = "snake_case")]
+/// Why the user isn't allowed to do something.
+pub enum DeniedReason {
+  NotLoggedIn,
+  BannedFromCommunity,
+  /// The community, post or comment is deleted or removed.
+  Deleted,
+  Locked,
+  ThreadLocked,
+  OnlyModsCanPost,
+  OnlyModsCanComment,
+  /// The community doesn't accept votes from accounts which are this new.
+  NewAccountRestricted,
+  /// The community only accepts votes from its followers.
+  NotAFollower,
+  BlockedByCreator,
+  NotAModOrAdmin,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Whether the user is allowed to do something.
+pub struct Permission {
+  pub allowed: bool,
+  /// Only given if it isn't allowed.
+  pub reason: Option<DeniedReason>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// What the user is allowed to do in a community, or with a post or comment. These are worked out
+/// by the same checks which the actions use.
+pub struct Permissions {
+  pub can_post: Permission,
+  /// For a comment, whether it can be replied to.
+  pub can_comment: Permission,
+  pub can_vote: Permission,
+  pub can_report: Permission,
+  pub can_moderate: Permission,
+}
+
+#[cfg(feature = "full")]
+impl From<DeniedReason> for LemmyErrorType {
+  fn from(reason: DeniedReason) -> Self {
+    match reason {
+      DeniedReason::NotLoggedIn => LemmyErrorType::NotLoggedIn,
+      DeniedReason::BannedFromCommunity => LemmyErrorType::BannedFromCommunity,
+      DeniedReason::Deleted => LemmyErrorType::Deleted,
+      DeniedReason::Locked => LemmyErrorType::Locked,
+      DeniedReason::ThreadLocked => LemmyErrorType::CommentThreadLocked,
+      DeniedReason::OnlyModsCanPost => LemmyErrorType::OnlyModsCanPostInCommunity,
+      DeniedReason::OnlyModsCanComment => LemmyErrorType::OnlyModsCanCommentInCommunity,
+      DeniedReason::NewAccountRestricted | DeniedReason::NotAFollower => {
+        LemmyErrorType::NotAllowedToVote
+      }
+      DeniedReason::BlockedByCreator => LemmyErrorType::PersonIsBlocked,
+      DeniedReason::NotAModOrAdmin => LemmyErrorType::NotAModOrAdmin,
+    }
+  }
+}
+
+#[cfg(feature = "full")]
+impl From<Result<(), DeniedReason>> for Permission {
+  fn from(check: Result<(), DeniedReason>) -> Self {
+    Permission {
+      allowed: check.is_ok(),
+      reason: check.err(),
+    }
+  }
+}
+
+/// How the user is related to a community, which is read once for all of its posts and comments.
+#[cfg(feature = "full")]
+#[derive(Clone, Copy)]
+pub struct CommunityMembership {
+  banned: bool,
+  is_mod_or_admin: bool,
+  is_follower: bool,
+}
+
+#[cfg(feature = "full")]
+impl CommunityMembership {
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    community: &Community,
+  ) -> LemmyResult<Self> {
+    Ok(CommunityMembership {
+      banned: CommunityPersonBanView::get(pool, person_id, community.id)
+        .await
+        .is_ok(),
+      is_mod_or_admin: CommunityView::is_mod_or_admin(pool, person_id, community.id).await?,
+      is_follower: CommunityFollower::is_follower(pool, person_id, community.id).await?,
+    })
+  }
+}
+
+#[cfg(feature = "full")]
+impl Permissions {
+  fn denied(reason: DeniedReason) -> Self {
+    let permission = Permission {
+      allowed: false,
+      reason: Some(reason),
+    };
+    Permissions {
+      can_post: permission,
+      can_comment: permission,
+      can_vote: permission,
+      can_report: permission,
+      can_moderate: permission,
+    }
+  }
+
+  /// Works out the permissions from what was already read. The checks run in the same order as in
+  /// the actions, so that the first failing check gives the reason.
+  fn check(
+    person: &Person,
+    community: &Community,
+    membership: CommunityMembership,
+    post: Option<&Post>,
+    blocked_by_creator: bool,
+  ) -> Self {
+    let in_community =
+      check_not_banned(membership.banned).and_then(|()| check_community_open(community));
+    let can_comment = in_community
+      .and_then(|()| post.map_or(Ok(()), check_post_open))
+      .and_then(|()| post.map_or(Ok(()), check_post_unlocked))
+      .and_then(|()| check_commenting_allowed(community, membership.is_mod_or_admin));
+    let can_post =
+      in_community.and_then(|()| check_posting_allowed(community, membership.is_mod_or_admin));
+    let can_vote = in_community
+      .and_then(|()| check_not_blocked(blocked_by_creator))
+      .and_then(|()| check_voting_requirements(person, community, membership.is_follower));
+    Permissions {
+      can_post: can_post.into(),
+      can_comment: can_comment.into(),
+      can_vote: can_vote.into(),
+      can_report: check_not_banned(membership.banned).into(),
+      can_moderate: check_moderator(membership.is_mod_or_admin).into(),
+    }
+  }
+
+  /// The permissions of the user in a community.
+  pub async fn for_community(
+    pool: &mut DbPool<'_>,
+    person: Option<&Person>,
+    community: &Community,
+  ) -> LemmyResult<Self> {
+    let Some(person) = person else {
+      return Ok(Permissions::denied(DeniedReason::NotLoggedIn));
+    };
+    let membership = CommunityMembership::read(pool, person.id, community).await?;
+    Ok(Permissions::check(
+      person, community, membership, None, false,
+    ))
+  }
+
+  /// The permissions of the user for a post, where commenting and voting also depend on the post.
+  pub async fn for_post(
+    pool: &mut DbPool<'_>,
+    person: Option<&Person>,
+    community: &Community,
+    post: &Post,
+  ) -> LemmyResult<Self> {
+    let Some(person) = person else {
+      return Ok(Permissions::denied(DeniedReason::NotLoggedIn));
+    };
+    let membership = CommunityMembership::read(pool, person.id, community).await?;
+    let blocked = PersonBlock::read(pool, post.creator_id, person.id)
+      .await
+      .is_ok();
+    Ok(Permissions::check(
+      person,
+      community,
+      membership,
+      Some(post),
+      blocked,
+    ))
+  }
+
+  /// The permissions of the user for a comment. Everything is read by the caller, so that it can
+  /// be read once for a whole page of comments.
+  pub fn for_comment(
+    person: &Person,
+    membership: CommunityMembership,
+    comment_view: &CommentView,
+    blocked_by_creator: bool,
+    thread_locked: bool,
+  ) -> Self {
+    let mut permissions = Permissions::check(
+      person,
+      &comment_view.community,
+      membership,
+      Some(&comment_view.post),
+      blocked_by_creator,
+    );
+    if permissions.can_comment.allowed {
+      permissions.can_comment = check_thread_unlocked(thread_locked).into();
+    }
+    permissions
+  }
+}
@@ -1,16 +1,28 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommentReplyId, CommunityId, LanguageId, PersonId, PersonMentionId},
+  newtypes::{
+    CommentReplyId,
+    CommunityId,
+    DbUrl,
+    LanguageId,
+    PersonId,
+    PersonMentionId,
+    PersonReportId,
+    ReminderId,
+  },
+  source::user_data_import::{UserDataImportFailure, UserDataImportJob},
   CommentSortType,
   ListingType,
+  NotificationDigest,
   SortType,
 };
-use lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_db_views::structs::{CommentView, LocalImageView, PersonReportView, PostView};
 use lemmy_db_views_actor::structs::{
   CommentReplyView,
   CommunityModeratorView,
   PersonMentionView,
   PersonView,
+  ReminderView,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -29,6 +41,14 @@ pub struct Login {
   pub totp_2fa_token: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Log out of all devices. Returns a new login for the current one.
+pub struct LogoutEverywhere {
+  pub auth: Sensitive<String>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -137,6 +157,21 @@ pub struct SaveUserSettings {
   pub open_links_in_new_tab: Option<bool>,
   /// Enable infinite scroll
   pub infinite_scroll_enabled: Option<bool>,
+  /// Hide posts and comments with a lower score. Null turns it off again.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub hide_content_below_score: Option<Option<i32>>,
+  /// How often to get an email digest of unread notifications.
+  pub send_notification_digest: Option<NotificationDigest>,
+  /// Hide posts and comments containing any of these keywords from listings. Replaces the
+  /// existing keywords.
+  pub blocked_keywords: Option<Vec<String>>,
+  /// Send an email when logging in from a new device.
+  pub notify_new_logins: Option<bool>,
+  /// Hide yourself from leaderboards like the top contributors of a community.
+  pub exclude_from_leaderboards: Option<bool>,
+  /// Replies and mentions containing any of these words don't notify you, but are still listed.
+  /// Replaces the existing words.
+  pub notification_muted_words: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -180,9 +215,12 @@ pub struct GetPersonDetails {
   pub limit: Option<i64>,
   pub community_id: Option<CommunityId>,
   pub saved_only: Option<bool>,
+  /// Include the full post bodies, instead of only their `body_excerpt`.
+  pub full_body: Option<bool>,
   pub auth: Option<Sensitive<String>>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -192,6 +230,11 @@ pub struct GetPersonDetailsResponse {
   pub comments: Vec<CommentView>,
   pub posts: Vec<PostView>,
   pub moderates: Vec<CommunityModeratorView>,
+  /// Whether the person asked for their account to be deleted. Their content is hidden until
+  /// then.
+  pub deletion_pending: bool,
+  /// When the account will be deleted. Only shown to admins.
+  pub deletion_scheduled_at: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -235,6 +278,106 @@ pub struct BanPerson {
   pub auth: Sensitive<String>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Reset the display name of a person, for example because it is offensive.
+pub struct ClearPersonDisplayName {
+  pub person_id: PersonId,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Reset the bio of a person, for example because it is offensive.
+pub struct ClearPersonBio {
+  pub person_id: PersonId,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The person after their display name or bio was cleared.
+pub struct ClearPersonProfileResponse {
+  pub person_view: PersonView,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Remove all posts and comments of a person in local communities, without banning them.
+/// Useful for deletion requests which are relayed from the home instance of a remote person.
+pub struct RemovePersonContent {
+  pub person_id: PersonId,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// How many posts and comments were removed. Those which were removed before aren't counted.
+pub struct RemovePersonContentResponse {
+  pub removed_posts: i64,
+  pub removed_comments: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Report the profile of a person, like an offensive display name or bio.
+pub struct CreatePersonReport {
+  pub person_id: PersonId,
+  pub reason: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A person report response.
+pub struct PersonReportResponse {
+  pub person_report_view: PersonReportView,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Resolve a person report.
+pub struct ResolvePersonReport {
+  pub report_id: PersonReportId,
+  pub resolved: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List person reports. Only for admins.
+pub struct ListPersonReports {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  /// Only shows the unresolved reports
+  pub unresolved_only: Option<bool>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for list person reports.
+pub struct ListPersonReportsResponse {
+  pub person_reports: Vec<PersonReportView>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -341,6 +484,24 @@ pub struct PersonMentionResponse {
   pub person_mention_view: PersonMentionView,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mark several person mentions as read or unread at once.
+pub struct MarkPersonMentionsAsRead {
+  pub mention_ids: Vec<PersonMentionId>,
+  pub read: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for marking several person mentions.
+pub struct PersonMentionsResponse {
+  pub person_mention_views: Vec<PersonMentionView>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -362,17 +523,102 @@ pub struct CommentReplyResponse {
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
-/// Delete your account.
+/// Mark several comment replies as read or unread at once.
+pub struct MarkCommentRepliesAsRead {
+  pub reply_ids: Vec<CommentReplyId>,
+  pub read: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for marking several comment replies.
+pub struct CommentRepliesResponse {
+  pub comment_reply_views: Vec<CommentReplyView>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the reminders for your saved posts and comments which are due.
+pub struct GetReminders {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub unread_only: Option<bool>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Your reminders.
+pub struct GetRemindersResponse {
+  pub reminders: Vec<ReminderView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mark a reminder as read.
+pub struct MarkReminderAsRead {
+  pub reminder_id: ReminderId,
+  pub read: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for a reminder action.
+pub struct ReminderResponse {
+  pub reminder_view: ReminderView,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete your account. It is deactivated right away, and deleted after the cooling-off period
+/// of the site.
 pub struct DeleteAccount {
   pub password: Sensitive<String>,
+  /// Skip the cooling-off period. The deletion still has to be confirmed through a link which
+  /// is sent by email.
+  pub delete_immediately: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// The response of deleting your account.
-pub struct DeleteAccountResponse {}
+pub struct DeleteAccountResponse {
+  /// When the account will be deleted. None if it was deleted already.
+  pub deletion_scheduled_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Cancel a scheduled account deletion, with the token of the emailed link. Accounts without an
+/// email can't get the link, so they cancel with their username and password instead.
+pub struct CancelAccountDeletion {
+  pub token: Option<Sensitive<String>>,
+  pub username: Option<String>,
+  pub password: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete an account which is scheduled for deletion right away, with the token of the emailed
+/// link.
+pub struct ConfirmAccountDeletion {
+  pub token: Sensitive<String>,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -418,6 +664,7 @@ pub struct GetReportCountResponse {
   pub comment_reports: i64,
   pub post_reports: i64,
   pub private_message_reports: Option<i64>,
+  pub person_reports: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -436,6 +683,7 @@ pub struct GetUnreadCountResponse {
   pub replies: i64,
   pub mentions: i64,
   pub private_messages: i64,
+  pub reminders: i64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
@@ -451,3 +699,132 @@ pub struct VerifyEmail {
 #[cfg_attr(feature = "full", ts(export))]
 /// A response to verifying your email.
 pub struct VerifyEmailResponse {}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the images you've uploaded.
+pub struct ListMedia {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A list of uploaded images.
+pub struct ListMediaResponse {
+  pub images: Vec<LocalImageView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the tags of your saved posts and comments.
+pub struct ListSaveTags {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A tag of saved items, with how many posts and comments are saved with it.
+pub struct SaveTag {
+  pub tag: String,
+  pub post_count: i64,
+  pub comment_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The tags of your saved items, sorted by name.
+pub struct ListSaveTagsResponse {
+  pub tags: Vec<SaveTag>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Export your profile, settings, follows, blocks and saved content, to import them on another
+/// instance.
+pub struct ExportUserData {
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Your exported user data. Communities, users, posts and comments are identified by their
+/// activitypub id, so that they can be found on other instances.
+pub struct UserDataExport {
+  pub display_name: Option<String>,
+  pub bio: Option<String>,
+  pub avatar: Option<DbUrl>,
+  pub banner: Option<DbUrl>,
+  pub matrix_user_id: Option<String>,
+  pub bot_account: Option<bool>,
+  pub show_nsfw: Option<bool>,
+  pub blur_nsfw: Option<bool>,
+  pub auto_expand: Option<bool>,
+  pub show_scores: Option<bool>,
+  pub theme: Option<String>,
+  pub default_sort_type: Option<SortType>,
+  pub default_listing_type: Option<ListingType>,
+  pub interface_language: Option<String>,
+  pub show_avatars: Option<bool>,
+  pub send_notifications_to_email: Option<bool>,
+  pub show_bot_accounts: Option<bool>,
+  pub show_read_posts: Option<bool>,
+  pub open_links_in_new_tab: Option<bool>,
+  pub infinite_scroll_enabled: Option<bool>,
+  pub hide_content_below_score: Option<i32>,
+  pub exclude_from_leaderboards: Option<bool>,
+  #[serde(default)]
+  pub blocked_keywords: Vec<String>,
+  #[serde(default)]
+  pub notification_muted_words: Vec<String>,
+  #[serde(default)]
+  pub followed_communities: Vec<DbUrl>,
+  #[serde(default)]
+  pub blocked_communities: Vec<DbUrl>,
+  #[serde(default)]
+  pub blocked_users: Vec<DbUrl>,
+  #[serde(default)]
+  pub saved_posts: Vec<DbUrl>,
+  #[serde(default)]
+  pub saved_comments: Vec<DbUrl>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Import user data which was exported from this or another instance. Settings which are present
+/// are overwritten right away. Follows, blocks and saved content are added to the existing ones in
+/// the background, the progress is returned by `GetUserDataImportJob`.
+pub struct ImportUserData {
+  pub data: UserDataExport,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the progress of your user data import.
+pub struct GetUserDataImportJob {
+  pub job_id: i32,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A user data import which was started, or its progress.
+pub struct UserDataImportJobResponse {
+  pub job: UserDataImportJob,
+  /// Settings and items which couldn't be imported so far, and why.
+  pub failures: Vec<UserDataImportFailure>,
+}
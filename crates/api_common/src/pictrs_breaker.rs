@@ -0,0 +1,278 @@
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType, LemmyResult},
+  settings::SETTINGS,
+};
+use once_cell::sync::Lazy;
+use reqwest::Response;
+use reqwest_middleware::RequestBuilder;
+use std::{
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex,
+    MutexGuard,
+    PoisonError,
+  },
+  time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// Guards all requests to pictrs, so that an outage doesn't make uploads and post creation hang.
+pub static PICTRS_BREAKER: Lazy<CircuitBreaker> = Lazy::new(|| {
+  let config = SETTINGS.pictrs_config().unwrap_or_default();
+  CircuitBreaker::new(
+    config.breaker_failure_threshold.max(1),
+    Duration::from_secs(config.breaker_open_seconds),
+  )
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+  /// Requests are passed through.
+  Closed,
+  /// The service failed repeatedly, requests are rejected until the open period is over.
+  Open,
+  /// A single probe request is let through to find out if the service is back.
+  HalfOpen,
+}
+
+impl BreakerState {
+  /// The value of the state in metrics.
+  pub fn as_metric(self) -> f64 {
+    match self {
+      BreakerState::Closed => 0.0,
+      BreakerState::HalfOpen => 1.0,
+      BreakerState::Open => 2.0,
+    }
+  }
+}
+
+/// Stops sending requests to a service after consecutive failures, and lets a probe request
+/// through once the open period is over.
+pub struct CircuitBreaker {
+  failure_threshold: u32,
+  open_duration: Duration,
+  inner: Mutex<BreakerInner>,
+  state_changes: AtomicU64,
+}
+
+struct BreakerInner {
+  state: BreakerState,
+  consecutive_failures: u32,
+  /// When the breaker opened, or when the probe request was let through.
+  since: Instant,
+}
+
+impl CircuitBreaker {
+  pub fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+    CircuitBreaker {
+      failure_threshold,
+      open_duration,
+      inner: Mutex::new(BreakerInner {
+        state: BreakerState::Closed,
+        consecutive_failures: 0,
+        since: Instant::now(),
+      }),
+      state_changes: AtomicU64::new(0),
+    }
+  }
+
+  pub fn state(&self) -> BreakerState {
+    self.lock().state
+  }
+
+  /// Whether the last request to the service failed, even if the breaker is still closed.
+  pub fn is_failing(&self) -> bool {
+    let inner = self.lock();
+    inner.state != BreakerState::Closed || inner.consecutive_failures > 0
+  }
+
+  /// How often the breaker changed its state since startup.
+  pub fn state_changes(&self) -> u64 {
+    self.state_changes.load(Ordering::Relaxed)
+  }
+
+  /// Fails fast with `ImageServiceUnavailable` if a request would be rejected, without using up
+  /// the probe. Meant for work which is only worth doing if the request is sent afterwards.
+  pub fn ensure_available(&self) -> LemmyResult<()> {
+    let inner = self.lock();
+    if self.rejects(&inner) {
+      Err(LemmyErrorType::ImageServiceUnavailable)?
+    } else {
+      Ok(())
+    }
+  }
+
+  /// Sends the request unless the breaker is open. Connection errors and server errors count as
+  /// failures, other responses show that the service is up. Other errors, like an upload which
+  /// the client aborted, say nothing about the service and are not counted.
+  pub async fn send(&self, request: RequestBuilder) -> LemmyResult<Response> {
+    self.acquire()?;
+    match request.send().await {
+      Ok(response) if response.status().is_server_error() => {
+        self.record_failure();
+        Ok(response)
+      }
+      Ok(response) => {
+        self.record_success();
+        Ok(response)
+      }
+      Err(e) => {
+        if is_connect_error(&e) {
+          self.record_failure();
+        }
+        Err(LemmyError::from(e))
+      }
+    }
+  }
+
+  /// Checks if a request may be sent. Once the open period is over, the first request becomes
+  /// the probe. The probe is replaced if it didn't report back within another open period.
+  fn acquire(&self) -> LemmyResult<()> {
+    let mut inner = self.lock();
+    if self.rejects(&inner) {
+      return Err(LemmyErrorType::ImageServiceUnavailable)?;
+    }
+    if inner.state != BreakerState::Closed {
+      inner.since = Instant::now();
+      self.transition(&mut inner, BreakerState::HalfOpen);
+    }
+    Ok(())
+  }
+
+  fn rejects(&self, inner: &BreakerInner) -> bool {
+    inner.state != BreakerState::Closed && inner.since.elapsed() < self.open_duration
+  }
+
+  fn record_success(&self) {
+    let mut inner = self.lock();
+    inner.consecutive_failures = 0;
+    self.transition(&mut inner, BreakerState::Closed);
+  }
+
+  fn record_failure(&self) {
+    let mut inner = self.lock();
+    inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+    let open = match inner.state {
+      BreakerState::Closed => inner.consecutive_failures >= self.failure_threshold,
+      BreakerState::Open | BreakerState::HalfOpen => true,
+    };
+    if open {
+      inner.since = Instant::now();
+      self.transition(&mut inner, BreakerState::Open);
+    }
+  }
+
+  fn transition(&self, inner: &mut BreakerInner, state: BreakerState) {
+    if inner.state == state {
+      return;
+    }
+    match state {
+      BreakerState::Open => warn!(
+        "Pictrs is unavailable after {} failed requests, retrying in {:?}",
+        inner.consecutive_failures, self.open_duration
+      ),
+      BreakerState::HalfOpen => info!("Probing if pictrs is available again"),
+      BreakerState::Closed => info!("Pictrs is available again"),
+    }
+    inner.state = state;
+    self.state_changes.fetch_add(1, Ordering::Relaxed);
+  }
+
+  fn lock(&self) -> MutexGuard<'_, BreakerInner> {
+    // The state stays consistent even if another thread panicked while holding the lock
+    self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+}
+
+fn is_connect_error(error: &reqwest_middleware::Error) -> bool {
+  matches!(error, reqwest_middleware::Error::Reqwest(e) if e.is_connect())
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::*;
+  use reqwest_middleware::ClientWithMiddleware;
+
+  #[test]
+  fn test_opens_after_consecutive_failures() {
+    let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+    breaker.record_failure();
+    breaker.record_failure();
+    breaker.record_success();
+    breaker.record_failure();
+    breaker.record_failure();
+    assert_eq!(BreakerState::Closed, breaker.state());
+    assert!(breaker.acquire().is_ok());
+
+    breaker.record_failure();
+    assert_eq!(BreakerState::Open, breaker.state());
+    assert!(breaker.acquire().is_err());
+    assert!(breaker.ensure_available().is_err());
+    assert_eq!(1, breaker.state_changes());
+  }
+
+  #[test]
+  fn test_half_open_probe() {
+    let breaker = CircuitBreaker::new(1, Duration::ZERO);
+    breaker.record_failure();
+    assert_eq!(BreakerState::Open, breaker.state());
+
+    // Without an open period, the probe is let through right away
+    assert!(breaker.ensure_available().is_ok());
+    assert!(breaker.acquire().is_ok());
+    assert_eq!(BreakerState::HalfOpen, breaker.state());
+
+    // A failed probe opens the breaker again
+    breaker.record_failure();
+    assert_eq!(BreakerState::Open, breaker.state());
+
+    assert!(breaker.acquire().is_ok());
+    breaker.record_success();
+    assert_eq!(BreakerState::Closed, breaker.state());
+    assert_eq!(5, breaker.state_changes());
+  }
+
+  #[test]
+  fn test_rejects_during_probe() {
+    let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+    breaker.record_failure();
+    breaker.lock().since = Instant::now() - Duration::from_secs(61);
+
+    assert!(breaker.acquire().is_ok());
+    assert_eq!(BreakerState::HalfOpen, breaker.state());
+    // Only one probe at a time
+    assert!(breaker.acquire().is_err());
+  }
+
+  #[test]
+  fn test_is_failing() {
+    let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+    assert!(!breaker.is_failing());
+    breaker.record_failure();
+    assert_eq!(BreakerState::Closed, breaker.state());
+    assert!(breaker.is_failing());
+    breaker.record_success();
+    assert!(!breaker.is_failing());
+  }
+
+  #[tokio::test]
+  async fn test_connect_error_is_failure() {
+    // Nothing listens on the port once the listener is dropped
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+      .unwrap()
+      .local_addr()
+      .unwrap()
+      .port();
+    let client: ClientWithMiddleware = reqwest::Client::new().into();
+    let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+
+    let res = breaker
+      .send(client.get(format!("http://127.0.0.1:{port}/")))
+      .await;
+    assert!(res.is_err());
+    assert_eq!(BreakerState::Open, breaker.state());
+  }
+}
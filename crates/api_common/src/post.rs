@@ -1,14 +1,29 @@
-use crate::sensitive::Sensitive;
+use crate::{permissions::Permissions, sensitive::Sensitive};
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PostId, PostReportId},
+  newtypes::{
+    CommentId,
+    CommunityId,
+    DbUrl,
+    LanguageId,
+    PollOptionId,
+    PostId,
+    PostReminderId,
+    PostReportId,
+  },
+  source::{
+    community::Community,
+    edit_history::PostEditHistory,
+    post_translation::PostTranslation,
+  },
   ListingType,
   PostFeatureType,
   SortType,
 };
-use lemmy_db_views::structs::{PostReportView, PostView};
-use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView};
+use lemmy_db_views::structs::{PollView, PostReportView, PostView};
+use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView, PostReminderView};
 use serde::{Deserialize, Serialize};
-use serde_with::skip_serializing_none;
+use serde_with::{formats::CommaSeparator, serde_as, skip_serializing_none, StringWithSeparator};
+use std::collections::HashMap;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 use url::Url;
@@ -29,14 +44,40 @@ pub struct CreatePost {
   pub honeypot: Option<String>,
   pub nsfw: Option<bool>,
   pub language_id: Option<LanguageId>,
+  /// Hides the post behind a warning until it is expanded.
+  pub content_warning: Option<String>,
+  /// The body in other languages. The body itself stays the canonical version.
+  pub content_translations: Option<HashMap<LanguageId, String>>,
+  /// A random id of the form. Submitting the same form again within ten minutes returns the
+  /// post which it created, instead of a duplicate.
+  pub form_id: Option<String>,
+  /// Attaches a poll to the post.
+  pub poll: Option<CreatePoll>,
   pub auth: Sensitive<String>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll for a new post.
+pub struct CreatePoll {
+  /// Between 2 and 10 options, in the order in which they are shown.
+  pub options: Vec<String>,
+  /// Unix timestamp after which no more votes are accepted.
+  pub ends_at: Option<i64>,
+  pub allow_multiple: Option<bool>,
+  /// Hides the vote counts from people who haven't voted yet, until the poll ends.
+  pub hide_results_until_voted: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 pub struct PostResponse {
   pub post_view: PostView,
+  /// After creating or editing a post, the communities which its body mentions as `!name@domain`.
+  pub community_mentions: Vec<Community>,
 }
 
 #[skip_serializing_none]
@@ -50,6 +91,7 @@ pub struct GetPost {
   pub auth: Option<Sensitive<String>>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -60,9 +102,16 @@ pub struct GetPostResponse {
   pub moderators: Vec<CommunityModeratorView>,
   /// A list of cross-posts, or other times / communities this link has been posted to.
   pub cross_posts: Vec<PostView>,
+  /// The body of the post in other languages.
+  pub translations: Vec<PostTranslation>,
+  /// The poll of the post, with the votes of the user.
+  pub poll: Option<PollView>,
+  /// What the user is allowed to do with the post.
+  pub permissions: Permissions,
 }
 
 #[skip_serializing_none]
+#[serde_as]
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -75,9 +124,19 @@ pub struct GetPosts {
   pub community_id: Option<CommunityId>,
   pub community_name: Option<String>,
   pub saved_only: Option<bool>,
+  /// Only the saved items with this tag, if `saved_only` is set.
+  pub saved_tag: Option<String>,
   pub liked_only: Option<bool>,
   pub disliked_only: Option<bool>,
   pub moderator_view: Option<bool>,
+  /// Show posts below your hide_content_below_score setting anyway.
+  pub ignore_score_filter: Option<bool>,
+  /// Include the full post bodies, instead of only their `body_excerpt`.
+  pub full_body: Option<bool>,
+  /// Only include these fields of each post, like `post.name,creator.name,counts.score`.
+  #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, String>>")]
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub fields: Option<Vec<String>>,
   pub auth: Option<Sensitive<String>>,
 }
 
@@ -114,6 +173,10 @@ pub struct EditPost {
   pub body: Option<String>,
   pub nsfw: Option<bool>,
   pub language_id: Option<LanguageId>,
+  /// Hides the post behind a warning until it is expanded.
+  pub content_warning: Option<String>,
+  /// Replaces the translations of the body. An empty map removes them all.
+  pub content_translations: Option<HashMap<LanguageId, String>>,
   pub auth: Sensitive<String>,
 }
 
@@ -149,6 +212,15 @@ pub struct MarkPostAsRead {
   pub auth: Sensitive<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Request an archive.org snapshot of a post link.
+pub struct RequestPostArchive {
+  pub post_id: PostId,
+  pub auth: Sensitive<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -170,6 +242,7 @@ pub struct FeaturePost {
   pub auth: Sensitive<String>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -177,6 +250,10 @@ pub struct FeaturePost {
 pub struct SavePost {
   pub post_id: PostId,
   pub save: bool,
+  /// Unix timestamp when to be reminded of it. Saving again changes or clears the reminder.
+  pub remind_at: Option<i64>,
+  /// A folder to save it into. Saving again moves it to another tag, or clears it.
+  pub tag: Option<String>,
   pub auth: Sensitive<String>,
 }
 
@@ -249,7 +326,7 @@ pub struct GetSiteMetadataResponse {
 }
 
 #[skip_serializing_none]
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// Site metadata, from its opengraph tags.
@@ -258,4 +335,108 @@ pub struct SiteMetadata {
   pub description: Option<String>,
   pub(crate) image: Option<DbUrl>,
   pub embed_video_url: Option<DbUrl>,
+  /// The allowlisted video site whose player is the embed url.
+  pub embed_provider: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get reminded of a post, in your inbox.
+pub struct CreatePostReminder {
+  pub post_id: PostId,
+  /// Unix timestamp when to be reminded.
+  pub remind_at: i64,
+  pub note: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response for a post reminder.
+pub struct PostReminderResponse {
+  pub post_reminder_view: PostReminderView,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List your pending post reminders.
+pub struct ListPostReminders {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Your pending post reminders, the next one first.
+pub struct ListPostRemindersResponse {
+  pub post_reminders: Vec<PostReminderView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Cancel a pending post reminder.
+pub struct DeletePostReminder {
+  pub post_reminder_id: PostReminderId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for cancelling a post reminder.
+pub struct DeletePostReminderResponse {
+  pub post_reminder_id: PostReminderId,
+  pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Vote in the poll of a post. Replaces your previous vote, an empty list removes it.
+pub struct VotePoll {
+  pub post_id: PostId,
+  pub option_ids: Vec<PollOptionId>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The poll after voting.
+pub struct PollResponse {
+  pub poll: PollView,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetch the comments of a remote post which are missing here from its home instance. This runs
+/// in the background as a community resync job which only covers the post, the progress is
+/// returned by `GetCommunityResyncJob`. Only for moderators of the community and admins.
+pub struct ResyncRemotePost {
+  pub post_id: PostId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the previous versions of an edited post. Only for its creator, the moderators of the
+/// community and admins.
+pub struct GetPostEditHistory {
+  pub post_id: PostId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The previous names, urls and bodies of a post, newest first.
+pub struct GetPostEditHistoryResponse {
+  pub revisions: Vec<PostEditHistory>,
 }
@@ -1,6 +1,10 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::newtypes::{PersonId, PrivateMessageId, PrivateMessageReportId};
-use lemmy_db_views::structs::{PrivateMessageReportView, PrivateMessageView};
+use lemmy_db_views::structs::{
+  PrivateMessageConversationView,
+  PrivateMessageReportView,
+  PrivateMessageView,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -67,6 +71,38 @@ pub struct PrivateMessagesResponse {
   pub private_messages: Vec<PrivateMessageView>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get your private message conversations, with one entry per correspondent.
+pub struct GetPrivateMessageConversations {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The private message conversations response.
+pub struct PrivateMessageConversationsResponse {
+  pub conversations: Vec<PrivateMessageConversationView>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the private messages between you and another person. The first page has the newest
+/// messages, and each page is in chronological order.
+pub struct GetPrivateMessagesWith {
+  pub person_id: PersonId,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
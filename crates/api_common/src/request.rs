@@ -1,19 +1,35 @@
-use crate::post::SiteMetadata;
+use crate::{embed::fetch_embed, pictrs_breaker::PICTRS_BREAKER, post::SiteMetadata};
 use encoding::{all::encodings, DecoderTrap};
-use lemmy_db_schema::newtypes::DbUrl;
+use lemmy_db_schema::{newtypes::DbUrl, source::oauth_provider::OAuthProvider};
 use lemmy_utils::{
-  error::{LemmyError, LemmyErrorType},
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
   settings::structs::Settings,
   version::VERSION,
   REQWEST_TIMEOUT,
 };
+use once_cell::sync::Lazy;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use reqwest::header::CONTENT_LOCATION;
 use reqwest_middleware::ClientWithMiddleware;
 use serde::Deserialize;
-use tracing::info;
+use std::time::Duration;
+use tokio::{
+  sync::Mutex,
+  time::{sleep, Instant},
+};
+use tracing::{info, warn};
 use url::Url;
 use webpage::HTML;
 
+const ARCHIVE_ORG_URL: &str = "https://web.archive.org";
+/// Minimum delay between two snapshot requests to archive.org, across all posts
+const ARCHIVE_REQUEST_INTERVAL: Duration = Duration::from_secs(10);
+/// Taking a snapshot can be slow, as archive.org loads the page first
+const ARCHIVE_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const ARCHIVE_MAX_ATTEMPTS: u32 = 3;
+
+static LAST_ARCHIVE_REQUEST: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
 /// Fetches the post link html tags (like title, description, image, etc)
 #[tracing::instrument(skip_all)]
 pub async fn fetch_site_metadata(
@@ -93,6 +109,7 @@ fn html_to_site_metadata(html_bytes: &[u8], url: &Url) -> Result<SiteMetadata, L
     description: og_description.or(page_description),
     image: og_image.map(Into::into),
     embed_video_url: og_embed_url.map(Into::into),
+    embed_provider: None,
   })
 }
 
@@ -121,6 +138,7 @@ pub(crate) async fn fetch_pictrs(
   image_url: &Url,
 ) -> Result<PictrsResponse, LemmyError> {
   let pictrs_config = settings.pictrs_config()?;
+  PICTRS_BREAKER.ensure_available()?;
   is_image_content_type(client, image_url).await?;
 
   let fetch_url = format!(
@@ -129,10 +147,8 @@ pub(crate) async fn fetch_pictrs(
     utf8_percent_encode(image_url.as_str(), NON_ALPHANUMERIC) // TODO this might not be needed
   );
 
-  let response = client
-    .get(&fetch_url)
-    .timeout(REQWEST_TIMEOUT)
-    .send()
+  let response = PICTRS_BREAKER
+    .send(client.get(&fetch_url).timeout(REQWEST_TIMEOUT))
     .await?;
 
   let response: PictrsResponse = response.json().await.map_err(LemmyError::from)?;
@@ -155,6 +171,7 @@ pub async fn purge_image_from_pictrs(
   image_url: &Url,
 ) -> Result<(), LemmyError> {
   let pictrs_config = settings.pictrs_config()?;
+  PICTRS_BREAKER.ensure_available()?;
   is_image_content_type(client, image_url).await?;
 
   let alias = image_url
@@ -168,11 +185,13 @@ pub async fn purge_image_from_pictrs(
   let pictrs_api_key = pictrs_config
     .api_key
     .ok_or(LemmyErrorType::PictrsApiKeyNotProvided)?;
-  let response = client
-    .post(&purge_url)
-    .timeout(REQWEST_TIMEOUT)
-    .header("x-api-token", pictrs_api_key)
-    .send()
+  let response = PICTRS_BREAKER
+    .send(
+      client
+        .post(&purge_url)
+        .timeout(REQWEST_TIMEOUT)
+        .header("x-api-token", pictrs_api_key),
+    )
     .await?;
 
   let response: PictrsPurgeResponse = response.json().await.map_err(LemmyError::from)?;
@@ -184,6 +203,96 @@ pub async fn purge_image_from_pictrs(
   }
 }
 
+/// Deletes an uploaded image from pictrs, using the delete token returned on upload
+pub async fn delete_image_from_pictrs(
+  client: &ClientWithMiddleware,
+  settings: &Settings,
+  alias: &str,
+  delete_token: &str,
+) -> Result<(), LemmyError> {
+  let pictrs_config = settings.pictrs_config()?;
+  let url = format!(
+    "{}image/delete/{}/{}",
+    pictrs_config.url, &delete_token, &alias
+  );
+  PICTRS_BREAKER
+    .send(client.delete(&url).timeout(REQWEST_TIMEOUT))
+    .await?
+    .error_for_status()?;
+  Ok(())
+}
+
+/// Sends a request to pictrs through the breaker, so that it becomes available again without
+/// waiting for an upload or thumbnail. Any response which isn't a server error counts.
+pub async fn probe_pictrs(
+  client: &ClientWithMiddleware,
+  settings: &Settings,
+) -> Result<(), LemmyError> {
+  let pictrs_config = settings.pictrs_config()?;
+  let url = format!("{}healthz", pictrs_config.url);
+  PICTRS_BREAKER
+    .send(client.get(&url).timeout(REQWEST_TIMEOUT))
+    .await?;
+  Ok(())
+}
+
+/// Requests an archive.org "save page now" snapshot of the url, and returns the snapshot url.
+/// Failed requests are retried a few times.
+#[tracing::instrument(skip_all)]
+pub async fn fetch_archive_url(
+  client: &ClientWithMiddleware,
+  url: &Url,
+) -> Result<Url, LemmyError> {
+  let mut attempt = 1;
+  loop {
+    match request_archive_snapshot(client, url).await {
+      Ok(archive_url) => return Ok(archive_url),
+      Err(e) if attempt < ARCHIVE_MAX_ATTEMPTS => {
+        warn!("Archiving {url} failed on attempt {attempt}: {e}");
+        sleep(ARCHIVE_REQUEST_INTERVAL * attempt).await;
+        attempt += 1;
+      }
+      Err(e) => return Err(e),
+    }
+  }
+}
+
+async fn request_archive_snapshot(
+  client: &ClientWithMiddleware,
+  url: &Url,
+) -> Result<Url, LemmyError> {
+  // Keep the requests spaced out globally, so that archive.org doesn't block the instance
+  {
+    let mut last_request = LAST_ARCHIVE_REQUEST.lock().await;
+    if let Some(last_request) = *last_request {
+      let elapsed = last_request.elapsed();
+      if elapsed < ARCHIVE_REQUEST_INTERVAL {
+        sleep(ARCHIVE_REQUEST_INTERVAL - elapsed).await;
+      }
+    }
+    *last_request = Some(Instant::now());
+  }
+
+  info!("Requesting archive snapshot for url: {}", url);
+  let response = client
+    .get(format!("{ARCHIVE_ORG_URL}/save/{url}"))
+    .timeout(ARCHIVE_REQUEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?;
+
+  // The snapshot location is either given in a header, or followed as a redirect
+  let snapshot_path = match response.headers().get(CONTENT_LOCATION) {
+    Some(location) => location.to_str()?.to_string(),
+    None => response.url().path().to_string(),
+  };
+  if snapshot_path.starts_with("/web/") {
+    Ok(Url::parse(&format!("{ARCHIVE_ORG_URL}{snapshot_path}"))?)
+  } else {
+    Err(LemmyErrorType::CouldntArchiveUrl)?
+  }
+}
+
 /// Both are options, since the URL might be either an html page, or an image
 /// Returns the SiteMetadata, and a Pictrs URL, if there is a picture associated
 #[tracing::instrument(skip_all)]
@@ -198,7 +307,17 @@ pub async fn fetch_site_data(
       // Fetch metadata
       // Ignore errors, since it may be an image, or not have the data.
       // Warning, this may ignore SSL errors
-      let metadata_option = fetch_site_metadata(client, url).await.ok();
+      let mut metadata_option = fetch_site_metadata(client, url).await.ok();
+      // Only allowlisted video sites get a player, the opengraph video of other sites is dropped
+      let embed = fetch_embed(client, &settings.embed_providers, url).await;
+      if let Some(metadata) = &mut metadata_option {
+        metadata.embed_video_url = None;
+      }
+      if let Some(embed) = embed {
+        let metadata = metadata_option.get_or_insert_with(SiteMetadata::default);
+        metadata.embed_video_url = Some(embed.url.into());
+        metadata.embed_provider = Some(embed.provider);
+      }
       if !include_image {
         return (metadata_option, None);
       }
@@ -244,6 +363,12 @@ pub async fn fetch_site_data(
   }
 }
 
+/// Whether the thumbnail for the url is missing because pictrs was unavailable, so that it should
+/// be generated later. A single failed request is enough, the breaker doesn't need to be open.
+pub fn thumbnail_needs_retry(url: Option<&Url>, thumbnail_url: &Option<DbUrl>) -> bool {
+  url.is_some() && thumbnail_url.is_none() && PICTRS_BREAKER.is_failing()
+}
+
 #[tracing::instrument(skip_all)]
 async fn is_image_content_type(client: &ClientWithMiddleware, url: &Url) -> Result<(), LemmyError> {
   let response = client.get(url.as_str()).send().await?;
@@ -260,6 +385,93 @@ async fn is_image_content_type(client: &ClientWithMiddleware, url: &Url) -> Resu
   }
 }
 
+/// The endpoints of an OpenID Connect provider, as published in its discovery document.
+#[derive(Deserialize, Debug)]
+pub struct OpenIdConfiguration {
+  pub authorization_endpoint: Url,
+  pub token_endpoint: Url,
+  pub userinfo_endpoint: Url,
+}
+
+/// The claims about a user, returned by the userinfo endpoint of an OpenID Connect provider.
+#[derive(Deserialize, Debug)]
+pub struct OAuthUserInfo {
+  /// The subject identifier, which never changes for a user.
+  pub sub: String,
+  pub email: Option<String>,
+  #[serde(default)]
+  pub email_verified: bool,
+  pub preferred_username: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OAuthTokenResponse {
+  access_token: String,
+}
+
+#[tracing::instrument(skip(client))]
+pub async fn fetch_openid_configuration(
+  client: &ClientWithMiddleware,
+  issuer: &str,
+) -> Result<OpenIdConfiguration, LemmyError> {
+  let url = format!(
+    "{}/.well-known/openid-configuration",
+    issuer.trim_end_matches('/')
+  );
+  let config = client
+    .get(url)
+    .send()
+    .await
+    .with_lemmy_type(LemmyErrorType::OauthLoginFailed)?
+    .error_for_status()
+    .with_lemmy_type(LemmyErrorType::OauthLoginFailed)?
+    .json::<OpenIdConfiguration>()
+    .await
+    .with_lemmy_type(LemmyErrorType::OauthLoginFailed)?;
+  Ok(config)
+}
+
+/// Exchanges the authorization code the provider redirected back with for an access token, and
+/// reads the claims about the user with it.
+#[tracing::instrument(skip_all)]
+pub async fn fetch_oauth_user_info(
+  client: &ClientWithMiddleware,
+  provider: &OAuthProvider,
+  code: &str,
+  redirect_uri: &Url,
+) -> Result<OAuthUserInfo, LemmyError> {
+  let config = fetch_openid_configuration(client, &provider.issuer).await?;
+  let token = client
+    .post(config.token_endpoint)
+    .form(&[
+      ("grant_type", "authorization_code"),
+      ("code", code),
+      ("redirect_uri", redirect_uri.as_str()),
+      ("client_id", &provider.client_id),
+      ("client_secret", &provider.client_secret),
+    ])
+    .send()
+    .await
+    .with_lemmy_type(LemmyErrorType::OauthLoginFailed)?
+    .error_for_status()
+    .with_lemmy_type(LemmyErrorType::OauthLoginFailed)?
+    .json::<OAuthTokenResponse>()
+    .await
+    .with_lemmy_type(LemmyErrorType::OauthLoginFailed)?;
+  let user_info = client
+    .get(config.userinfo_endpoint)
+    .bearer_auth(token.access_token)
+    .send()
+    .await
+    .with_lemmy_type(LemmyErrorType::OauthLoginFailed)?
+    .error_for_status()
+    .with_lemmy_type(LemmyErrorType::OauthLoginFailed)?
+    .json::<OAuthUserInfo>()
+    .await
+    .with_lemmy_type(LemmyErrorType::OauthLoginFailed)?;
+  Ok(user_info)
+}
+
 pub fn build_user_agent(settings: &Settings) -> String {
   format!(
     "Lemmy/{}; +{}",
@@ -305,6 +517,7 @@ mod tests {
             .into()
         ),
         embed_video_url: None,
+        embed_provider: None,
       },
       sample_res
     );
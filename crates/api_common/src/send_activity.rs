@@ -47,6 +47,7 @@ pub enum SendActivityData {
   UpdateComment(Comment),
   DeleteComment(Comment, Person, Community),
   RemoveComment(Comment, Person, Community, Option<String>),
+  LockComment(Comment, Person),
   LikePostOrComment(DbUrl, Person, Community, i16),
   FollowCommunity(Community, Person, bool),
   UpdateCommunity(Person, Community),
@@ -58,6 +59,7 @@ pub enum SendActivityData {
   CreatePrivateMessage(PrivateMessageView),
   UpdatePrivateMessage(PrivateMessageView),
   DeletePrivateMessage(Person, PrivateMessage, bool),
+  UpdatePerson(Person),
   DeleteUser(Person),
   CreateReport(Url, Person, Community, String),
 }
@@ -95,10 +97,18 @@ impl ActivityChannel {
         .get()
         .expect("retrieve function pointer")(data, context)
       .await?;
+    } else {
+      Self::queue_activity(data)?;
     }
+    Ok(())
+  }
+
+  /// Hands the activity over to the background task which sends it, even if federation is
+  /// synchronous.
+  pub fn queue_activity(data: SendActivityData) -> LemmyResult<()> {
     // could do `ACTIVITY_CHANNEL.keepalive_sender.lock()` instead and get rid of weak_sender,
     // not sure which way is more efficient
-    else if let Some(sender) = ACTIVITY_CHANNEL.weak_sender.upgrade() {
+    if let Some(sender) = ACTIVITY_CHANNEL.weak_sender.upgrade() {
       sender.send(data)?;
     }
     Ok(())
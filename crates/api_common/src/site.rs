@@ -1,7 +1,17 @@
-use crate::sensitive::Sensitive;
+use crate::{diff::DiffHunk, sensitive::Sensitive};
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, LanguageId, PersonId, PostId},
-  source::{instance::Instance, language::Language, tagline::Tagline},
+  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PersonId, PostId},
+  source::{
+    community::Community,
+    domain_migration::DomainMigration,
+    instance::Instance,
+    language::Language,
+    oauth_provider::OAuthProvider,
+    site_activity_rollup::SiteActivityRollup,
+    tagline::Tagline,
+  },
+  ActivityInterval,
+  DeliveryStatus,
   ListingType,
   ModlogActionType,
   RegistrationMode,
@@ -25,16 +35,21 @@ use lemmy_db_views_actor::structs::{
   PersonView,
 };
 use lemmy_db_views_moderator::structs::{
+  AdminAllowInstanceView,
+  AdminBlockInstanceView,
+  AdminClearPersonProfileView,
   AdminPurgeCommentView,
   AdminPurgeCommunityView,
   AdminPurgePersonView,
   AdminPurgePostView,
+  AdminVerifyCommunityView,
   ModAddCommunityView,
   ModAddView,
   ModBanFromCommunityView,
   ModBanView,
   ModFeaturePostView,
   ModHideCommunityView,
+  ModLockCommentView,
   ModLockPostView,
   ModRemoveCommentView,
   ModRemoveCommunityView,
@@ -42,11 +57,12 @@ use lemmy_db_views_moderator::structs::{
   ModTransferCommunityView,
 };
 use serde::{Deserialize, Serialize};
-use serde_with::skip_serializing_none;
+use serde_with::{formats::CommaSeparator, serde_as, skip_serializing_none, StringWithSeparator};
 #[cfg(feature = "full")]
 use ts_rs::TS;
 
 #[skip_serializing_none]
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -61,6 +77,14 @@ pub struct Search {
   pub listing_type: Option<ListingType>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
+  /// Include the full post bodies, instead of only their `body_excerpt`.
+  pub full_body: Option<bool>,
+  /// Only include these fields of each result, like `post.name,creator.name,counts.score`.
+  #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, String>>")]
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub fields: Option<Vec<String>>,
+  /// Only include communities which the admins verified.
+  pub verified_only: Option<bool>,
   pub auth: Option<Sensitive<String>>,
 }
 
@@ -101,20 +125,45 @@ pub struct ResolveObjectResponse {
 }
 
 #[skip_serializing_none]
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
-/// Fetches the modlog.
+/// Fetches the modlog. The entries of all types are ordered by time, and paginated together.
 pub struct GetModlog {
   pub mod_person_id: Option<PersonId>,
   pub community_id: Option<CommunityId>,
+  /// Pages end after the first 1000 entries.
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub type_: Option<ModlogActionType>,
+  /// Several action types at once, like `ModRemovePost,ModRemoveComment`. Takes precedence over
+  /// `type_`.
+  #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, ModlogActionType>>")]
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub actions: Option<Vec<ModlogActionType>>,
+  /// Only list actions whose reason contains this text, ignoring case.
+  pub reason_query: Option<String>,
   pub other_person_id: Option<PersonId>,
   pub auth: Option<Sensitive<String>>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Exports the modlog as newline delimited JSON, ordered by id. Each line is an object with
+/// `schema_version`, `type`, `id` and `entry` fields, where `entry` has the same format as in
+/// `GetModlogResponse`.
+pub struct GetModlogExport {
+  /// Ids are only unique within one action type, so each type is exported separately.
+  pub type_: ModlogActionType,
+  pub community_id: Option<CommunityId>,
+  /// Only export entries with a higher id, to resume an earlier export.
+  pub since: Option<i32>,
+  pub auth: Option<Sensitive<String>>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -125,6 +174,7 @@ pub struct GetModlogResponse {
   pub locked_posts: Vec<ModLockPostView>,
   pub featured_posts: Vec<ModFeaturePostView>,
   pub removed_comments: Vec<ModRemoveCommentView>,
+  pub locked_comments: Vec<ModLockCommentView>,
   pub removed_communities: Vec<ModRemoveCommunityView>,
   pub banned_from_community: Vec<ModBanFromCommunityView>,
   pub banned: Vec<ModBanView>,
@@ -136,6 +186,10 @@ pub struct GetModlogResponse {
   pub admin_purged_posts: Vec<AdminPurgePostView>,
   pub admin_purged_comments: Vec<AdminPurgeCommentView>,
   pub hidden_communities: Vec<ModHideCommunityView>,
+  pub admin_blocked_instances: Vec<AdminBlockInstanceView>,
+  pub admin_allowed_instances: Vec<AdminAllowInstanceView>,
+  pub admin_cleared_person_profiles: Vec<AdminClearPersonProfileView>,
+  pub admin_verified_communities: Vec<AdminVerifyCommunityView>,
 }
 
 #[skip_serializing_none]
@@ -175,6 +229,8 @@ pub struct CreateSite {
   pub rate_limit_comment_per_second: Option<i32>,
   pub rate_limit_search: Option<i32>,
   pub rate_limit_search_per_second: Option<i32>,
+  pub rate_limit_export: Option<i32>,
+  pub rate_limit_export_per_second: Option<i32>,
   pub federation_enabled: Option<bool>,
   pub federation_debug: Option<bool>,
   pub captcha_enabled: Option<bool>,
@@ -245,6 +301,9 @@ pub struct EditSite {
   /// The number of searches allowed in a given time frame.
   pub rate_limit_search: Option<i32>,
   pub rate_limit_search_per_second: Option<i32>,
+  /// The number of modlog exports and user data exports or imports allowed in a given time frame.
+  pub rate_limit_export: Option<i32>,
+  pub rate_limit_export_per_second: Option<i32>,
   /// Whether to enable federation.
   pub federation_enabled: Option<bool>,
   /// Enables federation debugging.
@@ -262,6 +321,32 @@ pub struct EditSite {
   pub registration_mode: Option<RegistrationMode>,
   /// Whether to email admins for new reports.
   pub reports_email_admins: Option<bool>,
+  /// Whether to request archive.org snapshots for new post links.
+  pub enable_url_archiving: Option<bool>,
+  /// A comma-separated list of domains whose links are never archived.
+  pub url_archiving_excluded_domains: Option<String>,
+  /// The max number of posts featured to the local site at once.
+  pub featured_local_posts_max: Option<i32>,
+  /// Rejects all NSFW content, including NSFW posts and communities from other instances.
+  pub disallow_nsfw_content: Option<bool>,
+  /// Whether to send NSFW content to instances which declared that they refuse it.
+  pub federate_nsfw_outbound: Option<bool>,
+  /// Whether new accounts can be created through an OAuth provider, even if registration is
+  /// closed.
+  pub oauth_registration: Option<bool>,
+  /// Whether posts with a content warning are marked as NSFW.
+  pub content_warning_sets_nsfw: Option<bool>,
+  /// How many days accounts are kept after their deletion was requested. 0 deletes them right
+  /// away.
+  pub account_deletion_cooling_off_days: Option<i32>,
+  /// After how many days unread registration applications are denied automatically. 0 disables
+  /// it.
+  pub application_expire_days: Option<i32>,
+  /// Whether local mods and admins have to give a reason for removals and bans.
+  pub require_mod_action_reason: Option<bool>,
+  /// Whether remote images in posts and comments are loaded through the instance, so that the
+  /// remote servers don't see the IPs of users.
+  pub proxy_remote_images: Option<bool>,
   pub auth: Sensitive<String>,
 }
 
@@ -299,6 +384,8 @@ pub struct GetSiteResponse {
   pub taglines: Vec<Tagline>,
   /// A list of custom emojis your site supports.
   pub custom_emojis: Vec<CustomEmojiView>,
+  /// The OAuth providers users can log in with. Admins also see the disabled ones.
+  pub oauth_providers: Vec<OAuthProvider>,
 }
 
 #[skip_serializing_none]
@@ -320,6 +407,202 @@ pub struct GetFederatedInstancesResponse {
   pub federated_instances: Option<FederatedInstances>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Compares a revision from the edit history of a post or comment with the text which replaced
+/// it. Must provide either a post_id or a comment_id. Only for the creator, the moderators of the
+/// community and admins.
+pub struct GetContentDiff {
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub revision_id: i32,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The changes from the revision to the next one, or to the current text for the newest revision.
+pub struct GetContentDiffResponse {
+  pub hunks: Vec<DiffHunk>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Shows how a post or comment was federated (admin only). Must provide either an ap_id, a
+/// post_id or a comment_id.
+pub struct GetObjectFederationStatus {
+  pub ap_id: Option<String>,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The federation status of a post or comment. Activities are only kept for a limited time, so
+/// older ones are missing.
+pub struct GetObjectFederationStatusResponse {
+  pub ap_id: DbUrl,
+  pub local: bool,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+  /// Activities which this instance sent about the object.
+  pub sent_activities: Vec<SentActivityStatus>,
+  /// Activities about the object which this instance received from other instances.
+  pub received_activities: Vec<ReceivedActivityStatus>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An activity sent by this instance.
+pub struct SentActivityStatus {
+  pub ap_id: DbUrl,
+  /// The activity type, for example `Create` or `Announce`.
+  pub kind: Option<String>,
+  pub published: chrono::NaiveDateTime,
+  pub deliveries: Vec<ActivityDeliveryStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// What happened to a sent activity for a single inbox.
+pub struct ActivityDeliveryStatus {
+  pub inbox: DbUrl,
+  pub status: DeliveryStatus,
+  pub published: chrono::NaiveDateTime,
+  /// How often the retry queue tried to resend the activity to this inbox.
+  pub attempts: i32,
+  pub last_attempt_at: Option<chrono::NaiveDateTime>,
+  /// The error of the last resend attempt, or none if it succeeded.
+  pub last_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An activity received from another instance.
+pub struct ReceivedActivityStatus {
+  pub ap_id: DbUrl,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Lists the deliveries to unreachable instances which are waiting to be retried (admin only).
+pub struct ListFederationFailures {
+  /// Only show the deliveries for the instance with this domain.
+  pub instance: Option<String>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The queued deliveries, newest first.
+pub struct ListFederationFailuresResponse {
+  pub failures: Vec<FederationFailure>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A delivery to an unreachable instance. It is retried with increasing delays, and dropped after
+/// `federation_retry_max_age_days`.
+pub struct FederationFailure {
+  pub id: i64,
+  pub instance: Instance,
+  pub inbox: DbUrl,
+  pub activity_id: Option<String>,
+  /// The activity type, for example `Create` or `Announce`.
+  pub kind: Option<String>,
+  pub attempts: i32,
+  pub next_retry_at: chrono::NaiveDateTime,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Lists the posts and comments which were flagged for coordinated voting (admin only).
+pub struct ListVoteAnomalies {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The flagged vote surges, newest first.
+pub struct ListVoteAnomaliesResponse {
+  pub anomalies: Vec<VoteSurge>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A post or comment which received far more votes than usual in its community, mostly from a
+/// single instance or from new accounts. Nothing is done about it automatically.
+pub struct VoteSurge {
+  pub id: i32,
+  pub community: Community,
+  pub post_id: PostId,
+  /// Set if the votes were on a comment of the post.
+  pub comment_id: Option<CommentId>,
+  pub vote_count: i32,
+  /// How many standard deviations the votes are above the community baseline.
+  pub z_score: f64,
+  /// The number of votes from each instance, most first.
+  pub instances: Vec<VoterCount>,
+  /// The number of voters by account age, youngest first.
+  pub account_ages: Vec<VoterCount>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+pub struct VoterCount {
+  /// An instance domain, or an account age of `day`, `week`, `month`, `year` or `older`.
+  pub name: String,
+  pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Drops all queued deliveries for an instance, for example because it is gone for good (admin
+/// only). They are counted in the `dropped_activities` of the instance.
+pub struct PurgeFederationFailures {
+  pub instance: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The number of dropped deliveries.
+pub struct PurgeFederationFailuresResponse {
+  pub purged: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -331,6 +614,9 @@ pub struct MyUserInfo {
   pub community_blocks: Vec<CommunityBlockView>,
   pub person_blocks: Vec<PersonBlockView>,
   pub discussion_languages: Vec<LanguageId>,
+  /// Communities whose top mod position was offered to you, waiting for `AcceptCommunityTransfer`.
+  pub pending_community_transfers: Vec<Community>,
+  pub blocked_keywords: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -347,8 +633,117 @@ pub struct LeaveAdmin {
 /// A list of federated instances.
 pub struct FederatedInstances {
   pub linked: Vec<Instance>,
-  pub allowed: Vec<Instance>,
-  pub blocked: Vec<Instance>,
+  pub allowed: Vec<AllowedInstance>,
+  pub blocked: Vec<BlockedInstance>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An instance in the federation allowlist.
+pub struct AllowedInstance {
+  pub instance: Instance,
+  pub reason: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An instance in the federation blocklist.
+pub struct BlockedInstance {
+  pub instance: Instance,
+  pub reason: Option<String>,
+  /// The block is lifted automatically at this time.
+  pub expires: Option<chrono::NaiveDateTime>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Adds an instance to the federation blocklist, or removes it (admin only). Blocking an instance
+/// which is blocked already updates the reason and expiry.
+pub struct AdminBlockInstance {
+  /// The domain of the instance.
+  pub instance: String,
+  pub block: bool,
+  pub reason: Option<String>,
+  /// A unix timestamp after which the block is lifted.
+  pub expires: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Adds an instance to the federation allowlist, or removes it (admin only).
+pub struct AdminAllowInstance {
+  /// The domain of the instance.
+  pub instance: String,
+  pub allow: bool,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Moves the local instance from its old domain to the hostname in the config (admin only).
+///
+/// By default this is a dry run, which returns the number of rows that would change and a
+/// confirmation token. The migration only starts when it is called again with that token.
+pub struct MigrateDomain {
+  /// The domain the instance used before.
+  pub old_domain: String,
+  pub dry_run: Option<bool>,
+  pub confirmation_token: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The number of local rows in a table which a domain migration rewrites.
+pub struct DomainMigrationRows {
+  pub table: String,
+  pub count: i64,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The result of a domain migration, or its dry run.
+pub struct MigrateDomainResponse {
+  pub rows: Vec<DomainMigrationRows>,
+  /// Only returned for dry runs.
+  pub confirmation_token: Option<String>,
+  /// The progress of the migration which was started.
+  pub migration: Option<DomainMigration>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetches the activity statistics of the local site over time.
+pub struct GetSiteActivityTimeseries {
+  pub interval: Option<ActivityInterval>,
+  /// The number of buckets to return, at most 365.
+  pub range: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The activity statistics, newest bucket first.
+pub struct GetSiteActivityTimeseriesResponse {
+  pub interval: ActivityInterval,
+  pub buckets: Vec<SiteActivityRollup>,
 }
 
 #[skip_serializing_none]
@@ -395,6 +790,27 @@ pub struct PurgeComment {
   pub auth: Sensitive<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Purges an uploaded image from pictrs, and stops tracking it.
+pub struct PurgeMedia {
+  pub alias: String,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Lists the images uploaded by all local users, optionally filtered by uploader.
+pub struct ListAllMedia {
+  pub person_id: Option<PersonId>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -411,6 +827,8 @@ pub struct PurgeItemResponse {
 pub struct ListRegistrationApplications {
   /// Only shows the unread applications (IE those without an admin actor)
   pub unread_only: Option<bool>,
+  /// Only shows the applications which were submitted more than this many days ago
+  pub older_than_days: Option<i32>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub auth: Sensitive<String>,
@@ -436,6 +854,24 @@ pub struct ApproveRegistrationApplication {
   pub auth: Sensitive<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Denies many registration applications at once, up to 100.
+pub struct BulkDenyRegistrationApplications {
+  pub ids: Vec<i32>,
+  pub deny_reason: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The registration applications which were denied.
+pub struct BulkDenyRegistrationApplicationsResponse {
+  pub registration_applications: Vec<RegistrationApplicationView>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
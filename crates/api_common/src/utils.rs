@@ -1,9 +1,12 @@
 use crate::{
   context::LemmyContext,
-  request::purge_image_from_pictrs,
+  permissions::DeniedReason,
+  request::{fetch_archive_url, purge_image_from_pictrs},
+  send_activity::{ActivityChannel, SendActivityData},
   sensitive::Sensitive,
-  site::FederatedInstances,
+  site::{AllowedInstance, BlockedInstance, FederatedInstances},
 };
+use activitypub_federation::config::Data;
 use anyhow::Context;
 use chrono::NaiveDateTime;
 use lemmy_db_schema::{
@@ -11,19 +14,28 @@ use lemmy_db_schema::{
   newtypes::{CommunityId, DbUrl, LocalUserId, PersonId, PostId},
   source::{
     comment::{Comment, CommentUpdateForm},
-    community::{Community, CommunityModerator, CommunityUpdateForm},
+    community::{
+      Community,
+      CommunityFollower,
+      CommunityFollowerForm,
+      CommunityModerator,
+      CommunityUpdateForm,
+    },
     email_verification::{EmailVerification, EmailVerificationForm},
+    federation_allowlist::FederationAllowList,
+    federation_blocklist::FederationBlockList,
     instance::Instance,
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
+    local_user::LocalUser,
     password_reset_request::PasswordResetRequest,
     person::{Person, PersonUpdateForm},
     person_block::PersonBlock,
-    post::{Post, PostRead, PostReadForm},
+    post::{Post, PostRead, PostReadForm, PostUpdateForm},
     registration_application::RegistrationApplication,
   },
-  traits::{Crud, Readable},
-  utils::DbPool,
+  traits::{Crud, Followable, Readable},
+  utils::{naive_now, DbPool},
   RegistrationMode,
 };
 use lemmy_db_views::{comment_view::CommentQuery, structs::LocalUserView};
@@ -40,11 +52,12 @@ use lemmy_utils::{
   rate_limit::RateLimitConfig,
   settings::structs::Settings,
   utils::slurs::build_slur_regex,
+  SYNCHRONOUS_FEDERATION,
 };
 use regex::Regex;
 use reqwest_middleware::ClientWithMiddleware;
 use rosetta_i18n::{Language, LanguageId};
-use tracing::warn;
+use tracing::{warn, Instrument};
 use url::{ParseError, Url};
 
 #[tracing::instrument(skip_all)]
@@ -54,9 +67,7 @@ pub async fn is_mod_or_admin(
   community_id: CommunityId,
 ) -> Result<(), LemmyError> {
   let is_mod_or_admin = CommunityView::is_mod_or_admin(pool, person_id, community_id).await?;
-  if !is_mod_or_admin {
-    return Err(LemmyErrorType::NotAModOrAdmin)?;
-  }
+  check_moderator(is_mod_or_admin).map_err(LemmyErrorType::from)?;
   Ok(())
 }
 
@@ -147,6 +158,7 @@ pub async fn local_user_view_from_jwt(
     local_user_view.person.ban_expires,
     local_user_view.person.deleted,
   )?;
+  check_deletion_not_pending(&local_user_view.local_user)?;
 
   check_validator_time(&local_user_view.local_user.validator_time, &claims)?;
 
@@ -192,6 +204,16 @@ pub fn check_user_valid(
   Ok(())
 }
 
+/// Accounts which are scheduled for deletion can't be used anymore, unless the deletion is
+/// cancelled through the emailed link.
+pub fn check_deletion_not_pending(local_user: &LocalUser) -> Result<(), LemmyError> {
+  if local_user.deletion_scheduled_at.is_some() {
+    Err(LemmyErrorType::AccountDeletionPending)?
+  } else {
+    Ok(())
+  }
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn check_community_ban(
   person_id: PersonId,
@@ -201,10 +223,32 @@ pub async fn check_community_ban(
   let is_banned = CommunityPersonBanView::get(pool, person_id, community_id)
     .await
     .is_ok();
-  if is_banned {
-    Err(LemmyErrorType::BannedFromCommunity)?
-  } else {
+  check_not_banned(is_banned).map_err(LemmyErrorType::from)?;
+  Ok(())
+}
+
+/// Whether the person meets the minimum account age and follower requirements which the community
+/// sets for voting.
+#[tracing::instrument(skip_all)]
+pub async fn is_allowed_to_vote(
+  person: &Person,
+  community: &Community,
+  pool: &mut DbPool<'_>,
+) -> Result<bool, LemmyError> {
+  let is_follower = !community.only_followers_can_vote
+    || CommunityFollower::is_follower(pool, person.id, community.id).await?;
+  Ok(check_voting_requirements(person, community, is_follower).is_ok())
+}
+
+pub async fn check_allowed_to_vote(
+  person: &Person,
+  community: &Community,
+  pool: &mut DbPool<'_>,
+) -> Result<(), LemmyError> {
+  if is_allowed_to_vote(person, community, pool).await? {
     Ok(())
+  } else {
+    Err(LemmyErrorType::NotAllowedToVote)?
   }
 }
 
@@ -216,19 +260,49 @@ pub async fn check_community_deleted_or_removed(
   let community = Community::read(pool, community_id)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
-  if community.deleted || community.removed {
-    Err(LemmyErrorType::Deleted)?
+  check_community_open(&community).map_err(LemmyErrorType::from)?;
+  Ok(())
+}
+
+/// Follows or unfollows the community and federates it. Follows of remote communities stay pending
+/// until the community sends an Accept.
+#[tracing::instrument(skip_all)]
+pub async fn update_community_follow(
+  community: Community,
+  person: &Person,
+  follow: bool,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let community_follower_form = CommunityFollowerForm {
+    community_id: community.id,
+    person_id: person.id,
+    pending: follow && !community.local,
+  };
+  if follow {
+    if community.local {
+      check_community_ban(person.id, community.id, &mut context.pool()).await?;
+      check_community_deleted_or_removed(community.id, &mut context.pool()).await?;
+    }
+    CommunityFollower::follow(&mut context.pool(), &community_follower_form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CommunityFollowerAlreadyExists)?;
   } else {
-    Ok(())
+    CommunityFollower::unfollow(&mut context.pool(), &community_follower_form)
+      .await
+      .with_lemmy_type(LemmyErrorType::CommunityFollowerAlreadyExists)?;
   }
+
+  ActivityChannel::submit_activity(
+    SendActivityData::FollowCommunity(community, person.clone(), follow),
+    context,
+  )
+  .await?;
+  Ok(())
 }
 
 pub fn check_post_deleted_or_removed(post: &Post) -> Result<(), LemmyError> {
-  if post.deleted || post.removed {
-    Err(LemmyErrorType::Deleted)?
-  } else {
-    Ok(())
-  }
+  check_post_open(post).map_err(LemmyErrorType::from)?;
+  Ok(())
 }
 
 #[tracing::instrument(skip_all)]
@@ -240,13 +314,107 @@ pub async fn check_person_block(
   let is_blocked = PersonBlock::read(pool, potential_blocker_id, my_id)
     .await
     .is_ok();
-  if is_blocked {
-    Err(LemmyErrorType::PersonIsBlocked)?
+  check_not_blocked(is_blocked).map_err(LemmyErrorType::from)?;
+  Ok(())
+}
+
+// These checks only look at what was already read. They are shared by the actions and by
+// `Permissions`, so that clients are told exactly what the actions allow.
+
+pub fn check_not_banned(banned_from_community: bool) -> Result<(), DeniedReason> {
+  if banned_from_community {
+    Err(DeniedReason::BannedFromCommunity)
+  } else {
+    Ok(())
+  }
+}
+
+pub fn check_community_open(community: &Community) -> Result<(), DeniedReason> {
+  if community.deleted || community.removed {
+    Err(DeniedReason::Deleted)
+  } else {
+    Ok(())
+  }
+}
+
+pub fn check_post_open(post: &Post) -> Result<(), DeniedReason> {
+  if post.deleted || post.removed {
+    Err(DeniedReason::Deleted)
+  } else {
+    Ok(())
+  }
+}
+
+pub fn check_post_unlocked(post: &Post) -> Result<(), DeniedReason> {
+  if post.locked {
+    Err(DeniedReason::Locked)
+  } else {
+    Ok(())
+  }
+}
+
+pub fn check_thread_unlocked(thread_locked: bool) -> Result<(), DeniedReason> {
+  if thread_locked {
+    Err(DeniedReason::ThreadLocked)
+  } else {
+    Ok(())
+  }
+}
+
+pub fn check_posting_allowed(
+  community: &Community,
+  is_mod_or_admin: bool,
+) -> Result<(), DeniedReason> {
+  if community.posting_restricted_to_mods && !is_mod_or_admin {
+    Err(DeniedReason::OnlyModsCanPost)
   } else {
     Ok(())
   }
 }
 
+pub fn check_commenting_allowed(
+  community: &Community,
+  is_mod_or_admin: bool,
+) -> Result<(), DeniedReason> {
+  if community.commenting_restricted_to_mods && !is_mod_or_admin {
+    Err(DeniedReason::OnlyModsCanComment)
+  } else {
+    Ok(())
+  }
+}
+
+/// The minimum account age and the follower requirement which the community sets for voting.
+pub fn check_voting_requirements(
+  person: &Person,
+  community: &Community,
+  is_follower: bool,
+) -> Result<(), DeniedReason> {
+  let min_age = chrono::Duration::days(community.min_account_age_days_to_vote.into());
+  if naive_now() - person.published < min_age {
+    Err(DeniedReason::NewAccountRestricted)
+  } else if community.only_followers_can_vote && !is_follower {
+    Err(DeniedReason::NotAFollower)
+  } else {
+    Ok(())
+  }
+}
+
+pub fn check_not_blocked(blocked_by_creator: bool) -> Result<(), DeniedReason> {
+  if blocked_by_creator {
+    Err(DeniedReason::BlockedByCreator)
+  } else {
+    Ok(())
+  }
+}
+
+pub fn check_moderator(is_mod_or_admin: bool) -> Result<(), DeniedReason> {
+  if is_mod_or_admin {
+    Ok(())
+  } else {
+    Err(DeniedReason::NotAModOrAdmin)
+  }
+}
+
 #[tracing::instrument(skip_all)]
 pub fn check_downvotes_enabled(score: i16, local_site: &LocalSite) -> Result<(), LemmyError> {
   if score == -1 && !local_site.enable_downvotes {
@@ -255,6 +423,20 @@ pub fn check_downvotes_enabled(score: i16, local_site: &LocalSite) -> Result<(),
   Ok(())
 }
 
+/// Local mod actions need a reason if the site requires one. Removals and bans which come from
+/// other instances are not checked.
+#[tracing::instrument(skip_all)]
+pub fn check_mod_action_reason(
+  reason: &Option<String>,
+  local_site: &LocalSite,
+) -> Result<(), LemmyError> {
+  let has_reason = reason.as_deref().is_some_and(|r| !r.trim().is_empty());
+  if local_site.require_mod_action_reason && !has_reason {
+    Err(LemmyErrorType::ModReasonRequired)?;
+  }
+  Ok(())
+}
+
 #[tracing::instrument(skip_all)]
 pub fn check_private_instance(
   local_user_view: &Option<LocalUserView>,
@@ -275,9 +457,24 @@ pub async fn build_federated_instances(
     // TODO I hate that this requires 3 queries
     let (linked, allowed, blocked) = lemmy_db_schema::try_join_with_pool!(pool => (
       Instance::linked,
-      Instance::allowlist,
-      Instance::blocklist
+      FederationAllowList::list,
+      FederationBlockList::list
     ))?;
+    let allowed = allowed
+      .into_iter()
+      .map(|(allow, instance)| AllowedInstance {
+        instance,
+        reason: allow.reason,
+      })
+      .collect();
+    let blocked = blocked
+      .into_iter()
+      .map(|(block, instance)| BlockedInstance {
+        instance,
+        reason: block.reason,
+        expires: block.expires,
+      })
+      .collect();
 
     Ok(Some(FederatedInstances {
       linked,
@@ -333,6 +530,25 @@ pub async fn send_email_to_user(
   }
 }
 
+/// Sends notification emails in the background, so that a slow mail server doesn't hold up the
+/// request which triggered them. Like activities, they are sent right away when federation is
+/// synchronous. Each email is given as recipient, subject and body.
+pub async fn send_emails_to_users(
+  emails: Vec<(LocalUserView, String, String)>,
+  settings: &'static Settings,
+) {
+  let send = async move {
+    for (local_user_view, subject, body) in emails {
+      send_email_to_user(&local_user_view, &subject, &body, settings).await;
+    }
+  };
+  if *SYNCHRONOUS_FEDERATION {
+    send.await;
+  } else {
+    tokio::spawn(send.in_current_span());
+  }
+}
+
 pub async fn send_password_reset_email(
   user: &LocalUserView,
   pool: &mut DbPool<'_>,
@@ -414,6 +630,8 @@ pub fn local_site_rate_limit_to_rate_limit_config(
     comment_per_second: l.comment_per_second,
     search: l.search,
     search_per_second: l.search_per_second,
+    export: l.export,
+    export_per_second: l.export_per_second,
   }
 }
 
@@ -428,6 +646,28 @@ pub fn local_site_opt_to_slur_regex(local_site: &Option<LocalSite>) -> Option<Re
     .unwrap_or(None)
 }
 
+/// Fails if the content is marked as NSFW, but the local site doesn't allow any NSFW content.
+pub fn check_nsfw_allowed(nsfw: Option<bool>, local_site: &LocalSite) -> Result<(), LemmyError> {
+  if local_site.disallow_nsfw_content && nsfw.unwrap_or(false) {
+    Err(LemmyErrorType::NsfwNotAllowed)?
+  } else {
+    Ok(())
+  }
+}
+
+/// Content with a content warning is always marked as NSFW if the local site is configured so.
+pub fn nsfw_with_content_warning(
+  nsfw: Option<bool>,
+  has_content_warning: bool,
+  local_site: &LocalSite,
+) -> Option<bool> {
+  if has_content_warning && local_site.content_warning_sets_nsfw {
+    Some(true)
+  } else {
+    nsfw
+  }
+}
+
 pub fn local_site_opt_to_sensitive(local_site: &Option<LocalSite>) -> bool {
   local_site
     .as_ref()
@@ -798,6 +1038,10 @@ pub fn generate_featured_url(actor_id: &DbUrl) -> Result<DbUrl, ParseError> {
   Ok(Url::parse(&format!("{actor_id}/featured"))?.into())
 }
 
+pub fn generate_replies_url(post_id: &DbUrl) -> Result<DbUrl, ParseError> {
+  Ok(Url::parse(&format!("{post_id}/replies"))?.into())
+}
+
 pub fn generate_moderators_url(community_id: &DbUrl) -> Result<DbUrl, LemmyError> {
   Ok(Url::parse(&format!("{community_id}/moderators"))?.into())
 }
@@ -819,12 +1063,73 @@ pub fn sanitize_html_opt(data: &Option<String>) -> Option<String> {
   data.as_ref().map(|d| sanitize_html(d))
 }
 
+/// Returns the post link if it should be archived on archive.org. Links of NSFW posts, links to
+/// this instance and links to excluded domains are never archived.
+pub fn archivable_post_url(
+  post: &Post,
+  local_site: &LocalSite,
+  settings: &Settings,
+) -> Option<Url> {
+  if !local_site.enable_url_archiving || post.nsfw || post.archive_url.is_some() {
+    return None;
+  }
+  let url: Url = post.url.clone()?.into();
+  let domain = url.domain()?;
+  if domain == settings.hostname
+    || is_url_archiving_excluded(domain, &local_site.url_archiving_excluded_domains)
+  {
+    None
+  } else {
+    Some(url)
+  }
+}
+
+/// Checks the domain, and its parent domains, against the comma-separated exclusion list
+fn is_url_archiving_excluded(domain: &str, excluded_domains: &Option<String>) -> bool {
+  excluded_domains
+    .iter()
+    .flat_map(|d| d.split(','))
+    .map(|d| d.trim().to_lowercase())
+    .filter(|d| !d.is_empty())
+    .any(|excluded| domain == excluded || domain.ends_with(&format!(".{excluded}")))
+}
+
+/// Requests an archive.org snapshot of the post link, and saves it to the post. The snapshot is
+/// dropped if the link was edited in the meantime.
+pub async fn archive_post_url(
+  post_id: PostId,
+  url: Url,
+  context: LemmyContext,
+) -> Result<(), LemmyError> {
+  let archive_url = fetch_archive_url(context.client(), &url).await?;
+  let post = Post::read(&mut context.pool(), post_id).await?;
+  if post.url.as_deref() != Some(&url) {
+    return Ok(());
+  }
+  Post::update(
+    &mut context.pool(),
+    post_id,
+    &PostUpdateForm {
+      archive_url: Some(Some(archive_url.into())),
+      ..Default::default()
+    },
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntArchiveUrl)?;
+  Ok(())
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
-  use crate::utils::{honeypot_check, password_length_check, sanitize_html};
+  use crate::utils::{
+    honeypot_check,
+    is_url_archiving_excluded,
+    password_length_check,
+    sanitize_html,
+  };
 
   #[test]
   #[rustfmt::skip]
@@ -852,4 +1157,18 @@ mod tests {
     let sanitized = sanitize_html("Hello&nbsp;World");
     assert_eq!(sanitized, "Hello World");
   }
+
+  #[test]
+  fn test_url_archiving_excluded() {
+    let excluded = Some("example.com, Archive.org".to_string());
+    assert!(is_url_archiving_excluded("example.com", &excluded));
+    assert!(is_url_archiving_excluded("www.example.com", &excluded));
+    assert!(is_url_archiving_excluded("web.archive.org", &excluded));
+    assert!(!is_url_archiving_excluded("notexample.com", &excluded));
+    assert!(!is_url_archiving_excluded("example.com", &None));
+    assert!(!is_url_archiving_excluded(
+      "example.com",
+      &Some(String::new())
+    ));
+  }
 }
@@ -1,39 +1,52 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
-  build_response::{build_comment_response, send_local_notifs},
+  build_response::{build_comment_response, send_local_notifs, store_community_mentions},
   comment::{CommentResponse, CreateComment},
   context::LemmyContext,
+  image_proxy::restore_markdown_images,
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
+    check_commenting_allowed,
     check_community_ban,
     check_community_deleted_or_removed,
+    check_nsfw_allowed,
     check_post_deleted_or_removed,
+    check_post_unlocked,
+    check_thread_unlocked,
     generate_local_apub_endpoint,
     get_post,
     local_site_to_slur_regex,
     local_user_view_from_jwt,
+    nsfw_with_content_warning,
     sanitize_html,
+    sanitize_html_opt,
     EndpointType,
   },
 };
 use lemmy_db_schema::{
+  aggregates::structs::PostAggregates,
   impls::actor_language::default_post_language,
   source::{
     actor_language::CommunityLanguage,
     comment::{Comment, CommentInsertForm, CommentLike, CommentLikeForm, CommentUpdateForm},
     comment_reply::{CommentReply, CommentReplyUpdateForm},
+    community::Community,
+    community_mention::CommunityMention,
+    form_submission::{FormClaim, FormSubmission, FormSubmissionForm},
     local_site::LocalSite,
     person_mention::{PersonMention, PersonMentionUpdateForm},
   },
   traits::{Crud, Likeable},
 };
+use lemmy_db_views_actor::structs::CommunityView;
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
   utils::{
-    mention::scrape_text_for_mentions,
+    markdown::normalize_spoilers,
+    mention::{scrape_text_for_community_mentions, scrape_text_for_mentions},
     slurs::remove_slurs,
-    validation::is_valid_body_field,
+    validation::{is_valid_body_field, is_valid_content_warning, is_valid_form_id},
   },
 };
 
@@ -47,12 +60,21 @@ pub async fn create_comment(
   let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
   let local_site = LocalSite::read(&mut context.pool()).await?;
 
-  let content = remove_slurs(
-    &data.content.clone(),
-    &local_site_to_slur_regex(&local_site),
-  );
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  let content = remove_slurs(&data.content.clone(), &slur_regex);
   is_valid_body_field(&Some(content.clone()), false)?;
+  let content = normalize_spoilers(&content)?;
+  let content = restore_markdown_images(&content, &context);
   let content = sanitize_html(&content);
+  let content_warning = data
+    .content_warning
+    .as_ref()
+    .map(|c| remove_slurs(c, &slur_regex));
+  is_valid_content_warning(&content_warning)?;
+  let content_warning = sanitize_html_opt(&content_warning).filter(|c| !c.is_empty());
+  let nsfw = nsfw_with_content_warning(data.nsfw, content_warning.is_some(), &local_site);
+  check_nsfw_allowed(nsfw, &local_site)?;
+  is_valid_form_id(&data.form_id)?;
 
   // Check for a community ban
   let post_id = data.post_id;
@@ -64,9 +86,19 @@ pub async fn create_comment(
   check_post_deleted_or_removed(&post)?;
 
   // Check if post is locked, no new comments
-  if post.locked {
-    return Err(LemmyErrorType::Locked)?;
-  }
+  check_post_unlocked(&post).map_err(LemmyErrorType::from)?;
+
+  let community = Community::read(&mut context.pool(), community_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
+  let is_mod = community.commenting_restricted_to_mods
+    && CommunityView::is_mod_or_admin(
+      &mut context.pool(),
+      local_user_view.local_user.person_id,
+      community_id,
+    )
+    .await?;
+  check_commenting_allowed(&community, is_mod).map_err(LemmyErrorType::from)?;
 
   // Fetch the parent, if it exists
   let parent_opt = if let Some(parent_id) = data.parent_id {
@@ -82,6 +114,8 @@ pub async fn create_comment(
       return Err(LemmyErrorType::CouldntCreateComment)?;
     }
     check_comment_depth(parent)?;
+    let thread_locked = Comment::is_thread_locked(&mut context.pool(), &parent.path).await?;
+    check_thread_unlocked(thread_locked).map_err(LemmyErrorType::from)?;
   }
 
   CommunityLanguage::is_allowed_community_language(
@@ -109,13 +143,50 @@ pub async fn create_comment(
     .post_id(data.post_id)
     .creator_id(local_user_view.person.id)
     .language_id(language_id)
+    .content_warning(content_warning)
+    .nsfw(nsfw)
     .build();
 
+  // Submitting the same form again returns the comment which it created
+  let submission = match &data.form_id {
+    Some(form_id) => {
+      let form = FormSubmissionForm {
+        creator_id: local_user_view.person.id,
+        form_id: form_id.clone(),
+      };
+      match FormSubmission::claim(&mut context.pool(), &form).await? {
+        FormClaim::New(submission) => Some(submission),
+        FormClaim::Existing(submission) => {
+          let comment_id = submission
+            .comment_id
+            .ok_or(LemmyErrorType::FormAlreadySubmitted)?;
+          let response =
+            build_comment_response(&context, comment_id, Some(local_user_view), vec![]).await?;
+          return Ok(Json(response));
+        }
+      }
+    }
+    None => None,
+  };
+
   // Create the comment
   let parent_path = parent_opt.clone().map(|t| t.path);
-  let inserted_comment = Comment::create(&mut context.pool(), &comment_form, parent_path.as_ref())
-    .await
-    .with_lemmy_type(LemmyErrorType::CouldntCreateComment)?;
+  let inserted_comment =
+    Comment::create_with_ancestor_counts(&mut context.pool(), &comment_form, parent_path.as_ref())
+      .await;
+  if let Some(submission) = submission {
+    match &inserted_comment {
+      Ok((comment, _)) => {
+        FormSubmission::set_comment(&mut context.pool(), submission.id, comment.id).await?;
+      }
+      Err(_) => {
+        FormSubmission::release(&mut context.pool(), submission.id).await?;
+      }
+    }
+  }
+  // The child counts are read along with the insert, so that concurrent replies don't change them
+  let (inserted_comment, ancestor_counts) =
+    inserted_comment.with_lemmy_type(LemmyErrorType::CouldntCreateComment)?;
 
   // Necessary to update the ap_id
   let inserted_comment_id = inserted_comment.id;
@@ -149,6 +220,16 @@ pub async fn create_comment(
   )
   .await?;
 
+  // Store the communities which the comment mentions, so that clients can link them
+  let community_mentions = scrape_text_for_community_mentions(&content);
+  store_community_mentions(
+    community_mentions,
+    None,
+    Some(inserted_comment_id),
+    &context,
+  )
+  .await;
+
   // You like your own comment by default
   let like_form = CommentLikeForm {
     comment_id: inserted_comment.id,
@@ -161,12 +242,6 @@ pub async fn create_comment(
     .await
     .with_lemmy_type(LemmyErrorType::CouldntLikeComment)?;
 
-  ActivityChannel::submit_activity(
-    SendActivityData::CreateComment(updated_comment.clone()),
-    &context,
-  )
-  .await?;
-
   // If its a reply, mark the parent as read
   if let Some(parent) = parent_opt {
     let parent_id = parent.id;
@@ -196,15 +271,32 @@ pub async fn create_comment(
     }
   }
 
-  Ok(Json(
-    build_comment_response(
-      &context,
-      inserted_comment.id,
-      Some(local_user_view),
-      recipient_ids,
-    )
-    .await?,
-  ))
+  // Everything is written now, so federate the comment. Unless federation is synchronous, this
+  // doesn't wait for remote instances.
+  ActivityChannel::submit_activity(
+    SendActivityData::CreateComment(updated_comment.clone()),
+    &context,
+  )
+  .await?;
+
+  let mut response = build_comment_response(
+    &context,
+    inserted_comment.id,
+    Some(local_user_view),
+    recipient_ids,
+  )
+  .await?;
+
+  // Let clients update the thread's counters without refetching it
+  response.ancestor_counts = ancestor_counts;
+  response.post_comments = Some(
+    PostAggregates::read(&mut context.pool(), post.id)
+      .await?
+      .comments,
+  );
+  response.community_mentions =
+    CommunityMention::list_for_comment(&mut context.pool(), inserted_comment_id).await?;
+  Ok(Json(response))
 }
 
 pub fn check_comment_depth(comment: &Comment) -> Result<(), LemmyError> {
@@ -216,3 +308,182 @@ pub fn check_comment_depth(comment: &Comment) -> Result<(), LemmyError> {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::create_comment;
+  use activitypub_federation::config::FederationConfig;
+  use actix_web::web::Json;
+  use lemmy_api_common::{comment::CreateComment, context::LemmyContext};
+  use lemmy_db_schema::{
+    source::{
+      comment::Comment,
+      community::{Community, CommunityFollower, CommunityFollowerForm, CommunityInsertForm},
+      instance::Instance,
+      local_site::{LocalSite, LocalSiteInsertForm},
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      secret::Secret,
+      site::{Site, SiteInsertForm},
+    },
+    traits::{Crud, Followable},
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_utils::{
+    claims::Claims,
+    rate_limit::{RateLimitCell, RateLimitConfig},
+    settings::SETTINGS,
+    SYNCHRONOUS_FEDERATION,
+  };
+  use reqwest::{Client, Request, Response};
+  use reqwest_middleware::{ClientBuilder, Middleware, Next};
+  use serial_test::serial;
+  use std::time::Duration;
+  use task_local_extensions::Extensions;
+  use url::Url;
+
+  /// A reqwest middleware which never finishes the request, like an unresponsive remote inbox
+  struct HangingMiddleware;
+
+  #[async_trait::async_trait]
+  impl Middleware for HangingMiddleware {
+    async fn handle(
+      &self,
+      _req: Request,
+      _extensions: &mut Extensions,
+      _next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+      std::future::pending().await
+    }
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_create_comment_doesnt_wait_for_federation() {
+    // Debug builds federate synchronously by default, like the federation tests need it
+    std::env::set_var("LEMMY_SYNCHRONOUS_FEDERATION", "");
+    assert!(!*SYNCHRONOUS_FEDERATION);
+    let pool = build_db_pool_for_tests().await;
+    let secret = Secret::init(&mut (&pool).into()).await.unwrap();
+    let client = ClientBuilder::new(Client::new())
+      .with(HangingMiddleware)
+      .build();
+    let rate_limit_cell = RateLimitCell::new(RateLimitConfig::builder().build()).await;
+    let context = LemmyContext::create(pool, client, secret.clone(), rate_limit_cell.clone());
+    let context = FederationConfig::builder()
+      .domain("example.com")
+      .app_data(context)
+      .build()
+      .await
+      .unwrap()
+      .to_request_data();
+    let pool = &mut context.pool();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let site = Site::create(pool, &site_form).await.unwrap();
+    let local_site_form = LocalSiteInsertForm::builder().site_id(site.id).build();
+    LocalSite::create(pool, &local_site_form).await.unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("creates_comment".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("123456".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_comment_latency".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    // A follower on another instance, whose inbox never responds
+    let remote_instance = Instance::read_or_create(pool, "hanging.tld".to_string())
+      .await
+      .unwrap();
+    let remote_person_form = PersonInsertForm::builder()
+      .name("hanging_follower".into())
+      .public_key("pubkey".to_string())
+      .instance_id(remote_instance.id)
+      .local(Some(false))
+      .actor_id(Some(
+        Url::parse("https://hanging.tld/u/hanging_follower")
+          .unwrap()
+          .into(),
+      ))
+      .inbox_url(Some(
+        Url::parse("https://hanging.tld/u/hanging_follower/inbox")
+          .unwrap()
+          .into(),
+      ))
+      .build();
+    let remote_person = Person::create(pool, &remote_person_form).await.unwrap();
+    let follower_form = CommunityFollowerForm {
+      community_id: inserted_community.id,
+      person_id: remote_person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &follower_form)
+      .await
+      .unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let jwt = Claims::jwt(
+      inserted_local_user.id.0,
+      &secret.jwt_secret,
+      &SETTINGS.hostname,
+    )
+    .unwrap();
+    let data = CreateComment {
+      content: "A test comment".to_string(),
+      post_id: inserted_post.id,
+      parent_id: None,
+      language_id: None,
+      content_warning: None,
+      nsfw: None,
+      form_id: None,
+      auth: jwt.into(),
+    };
+    let response = tokio::time::timeout(
+      Duration::from_secs(10),
+      create_comment(Json(data), context.reset_request_count()),
+    )
+    .await
+    .expect("response doesn't wait for remote instances")
+    .unwrap();
+    let comment = Comment::read(pool, response.comment_view.comment.id)
+      .await
+      .unwrap();
+    assert_eq!("A test comment", comment.content);
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Person::delete(pool, remote_person.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Site::delete(pool, site.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+    Instance::delete(pool, remote_instance.id).await.unwrap();
+  }
+}
@@ -25,7 +25,9 @@ pub async fn delete_comment(
   let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
 
   let comment_id = data.comment_id;
-  let orig_comment = CommentView::read(&mut context.pool(), comment_id, None).await?;
+  let orig_comment = CommentView::read(&mut context.pool(), comment_id, None)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindComment)?;
 
   // Dont delete it if its already been deleted.
   if orig_comment.comment.deleted == data.deleted {
@@ -5,12 +5,18 @@ use lemmy_api_common::{
   comment::{CommentResponse, RemoveComment},
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, is_mod_or_admin, local_user_view_from_jwt},
+  utils::{
+    check_community_ban,
+    check_mod_action_reason,
+    is_mod_or_admin,
+    local_user_view_from_jwt,
+  },
 };
 use lemmy_db_schema::{
   source::{
     comment::{Comment, CommentUpdateForm},
     comment_report::CommentReport,
+    local_site::LocalSite,
     moderator::{ModRemoveComment, ModRemoveCommentForm},
     post::Post,
   },
@@ -27,7 +33,9 @@ pub async fn remove_comment(
   let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
 
   let comment_id = data.comment_id;
-  let orig_comment = CommentView::read(&mut context.pool(), comment_id, None).await?;
+  let orig_comment = CommentView::read(&mut context.pool(), comment_id, None)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindComment)?;
 
   check_community_ban(
     local_user_view.person.id,
@@ -43,6 +51,8 @@ pub async fn remove_comment(
     orig_comment.community.id,
   )
   .await?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_mod_action_reason(&data.reason, &local_site)?;
 
   // Do the remove
   let removed = data.removed;
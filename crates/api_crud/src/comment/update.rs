@@ -1,33 +1,38 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
-  build_response::{build_comment_response, send_local_notifs},
+  build_response::{build_comment_response, send_local_notifs, store_community_mentions},
   comment::{CommentResponse, EditComment},
   context::LemmyContext,
+  image_proxy::restore_markdown_images,
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
     check_community_ban,
+    check_nsfw_allowed,
     local_site_to_slur_regex,
     local_user_view_from_jwt,
+    nsfw_with_content_warning,
     sanitize_html_opt,
   },
 };
 use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
-    comment::{Comment, CommentUpdateForm},
+    comment::CommentUpdateForm,
+    community_mention::CommunityMention,
+    edit_history::CommentEditHistory,
     local_site::LocalSite,
   },
-  traits::Crud,
-  utils::naive_now,
+  utils::{diesel_option_overwrite, naive_now},
 };
 use lemmy_db_views::structs::CommentView;
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
   utils::{
-    mention::scrape_text_for_mentions,
+    markdown::normalize_spoilers_opt,
+    mention::{scrape_text_for_community_mentions, scrape_text_for_mentions},
     slurs::remove_slurs,
-    validation::is_valid_body_field,
+    validation::{is_valid_body_field, is_valid_content_warning},
   },
 };
 
@@ -40,7 +45,9 @@ pub async fn update_comment(
   let local_site = LocalSite::read(&mut context.pool()).await?;
 
   let comment_id = data.comment_id;
-  let orig_comment = CommentView::read(&mut context.pool(), comment_id, None).await?;
+  let orig_comment = CommentView::read(&mut context.pool(), comment_id, None)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindComment)?;
 
   check_community_ban(
     local_user_view.person.id,
@@ -63,23 +70,43 @@ pub async fn update_comment(
   .await?;
 
   // Update the Content
-  let content = data
-    .content
-    .as_ref()
-    .map(|c| remove_slurs(c, &local_site_to_slur_regex(&local_site)));
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  let content = data.content.as_ref().map(|c| remove_slurs(c, &slur_regex));
   is_valid_body_field(&content, false)?;
+  let content = normalize_spoilers_opt(&content)?;
+  let content = content.map(|content| restore_markdown_images(&content, &context));
   let content = sanitize_html_opt(&content);
+  let content_warning = data
+    .content_warning
+    .as_ref()
+    .map(|c| remove_slurs(c, &slur_regex));
+  is_valid_content_warning(&content_warning)?;
+  let content_warning = diesel_option_overwrite(sanitize_html_opt(&content_warning));
+  let nsfw = nsfw_with_content_warning(
+    data.nsfw,
+    matches!(content_warning, Some(Some(_))),
+    &local_site,
+  );
+  check_nsfw_allowed(nsfw, &local_site)?;
 
   let comment_id = data.comment_id;
   let form = CommentUpdateForm {
     content,
     language_id: data.language_id,
+    content_warning,
+    nsfw,
     updated: Some(Some(naive_now())),
     ..Default::default()
   };
-  let updated_comment = Comment::update(&mut context.pool(), comment_id, &form)
-    .await
-    .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
+  // Keep the previous content, so that mods can see what was originally said
+  let updated_comment = CommentEditHistory::update_comment(
+    &mut context.pool(),
+    &orig_comment.comment,
+    &form,
+    local_user_view.person.id,
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
 
   // Do the mentions / recipients
   let updated_comment_content = updated_comment.content.clone();
@@ -94,19 +121,25 @@ pub async fn update_comment(
   )
   .await?;
 
+  // The edited content may mention other communities than before
+  CommunityMention::delete_for_comment(&mut context.pool(), comment_id).await?;
+  let community_mentions = scrape_text_for_community_mentions(&updated_comment_content);
+  store_community_mentions(community_mentions, None, Some(comment_id), &context).await;
+
   ActivityChannel::submit_activity(
     SendActivityData::UpdateComment(updated_comment.clone()),
     &context,
   )
   .await?;
 
-  Ok(Json(
-    build_comment_response(
-      &context,
-      updated_comment.id,
-      Some(local_user_view),
-      recipient_ids,
-    )
-    .await?,
-  ))
+  let mut response = build_comment_response(
+    &context,
+    updated_comment.id,
+    Some(local_user_view),
+    recipient_ids,
+  )
+  .await?;
+  response.community_mentions =
+    CommunityMention::list_for_comment(&mut context.pool(), comment_id).await?;
+  Ok(Json(response))
 }
@@ -5,6 +5,7 @@ use lemmy_api_common::{
   community::{CommunityResponse, CreateCommunity},
   context::LemmyContext,
   utils::{
+    check_nsfw_allowed,
     generate_followers_url,
     generate_inbox_url,
     generate_local_apub_endpoint,
@@ -69,6 +70,7 @@ pub async fn create_community(
 
   is_valid_actor_name(&data.name, local_site.actor_name_max_length as usize)?;
   is_valid_body_field(&data.description, false)?;
+  check_nsfw_allowed(data.nsfw, &local_site)?;
 
   // Double check for duplicate community actor_ids
   let community_actor_id = generate_local_apub_endpoint(
@@ -99,6 +101,7 @@ pub async fn create_community(
     .inbox_url(Some(generate_inbox_url(&community_actor_id)?))
     .shared_inbox_url(Some(generate_shared_inbox_url(&community_actor_id)?))
     .posting_restricted_to_mods(data.posting_restricted_to_mods)
+    .commenting_restricted_to_mods(data.commenting_restricted_to_mods)
     .instance_id(site_view.site.instance_id)
     .build();
 
@@ -25,12 +25,14 @@ pub async fn list_communities(
   let sort = data.sort;
   let listing_type = data.type_;
   let show_nsfw = data.show_nsfw.unwrap_or_default();
+  let verified_only = data.verified_only.unwrap_or_default();
   let page = data.page;
   let limit = data.limit;
   let local_user = local_user_view.map(|l| l.local_user);
   let communities = CommunityQuery {
     listing_type,
     show_nsfw,
+    verified_only,
     sort,
     local_user: local_user.as_ref(),
     page,
@@ -5,7 +5,12 @@ use lemmy_api_common::{
   community::{CommunityResponse, EditCommunity},
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{local_site_to_slur_regex, local_user_view_from_jwt, sanitize_html_opt},
+  utils::{
+    check_nsfw_allowed,
+    local_site_to_slur_regex,
+    local_user_view_from_jwt,
+    sanitize_html_opt,
+  },
 };
 use lemmy_db_schema::{
   newtypes::PersonId,
@@ -35,6 +40,7 @@ pub async fn update_community(
   check_slurs_opt(&data.title, &slur_regex)?;
   check_slurs_opt(&data.description, &slur_regex)?;
   is_valid_body_field(&data.description, false)?;
+  check_nsfw_allowed(data.nsfw, &local_site)?;
 
   let title = sanitize_html_opt(&data.title);
   let description = sanitize_html_opt(&data.description);
@@ -72,6 +78,11 @@ pub async fn update_community(
     banner,
     nsfw: data.nsfw,
     posting_restricted_to_mods: data.posting_restricted_to_mods,
+    commenting_restricted_to_mods: data.commenting_restricted_to_mods,
+    default_post_sort: data.default_post_sort,
+    default_comment_sort: data.default_comment_sort,
+    min_account_age_days_to_vote: data.min_account_age_days_to_vote,
+    only_followers_can_vote: data.only_followers_can_vote,
     updated: Some(Some(naive_now())),
     ..Default::default()
   };
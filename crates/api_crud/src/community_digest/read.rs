@@ -0,0 +1,26 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  community::{CommunityDigestResponse, GetCommunityDigest},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::source::community_digest::CommunityDigest;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_community_digest(
+  data: Query<GetCommunityDigest>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityDigestResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    data.community_id,
+  )
+  .await?;
+
+  let community_digest =
+    CommunityDigest::read_for_community(&mut context.pool(), data.community_id).await?;
+  Ok(Json(CommunityDigestResponse { community_digest }))
+}
@@ -0,0 +1,61 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{CommunityDigestResponse, EditCommunityDigest},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    community_digest::{CommunityDigest, CommunityDigestForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+/// The most posts which a digest can list.
+const MAX_DIGEST_POSTS: i32 = 50;
+
+#[tracing::instrument(skip(context))]
+pub async fn update_community_digest(
+  data: Json<EditCommunityDigest>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityDigestResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    data.community_id,
+  )
+  .await?;
+
+  // Digests are posted by a local bot, so they are only possible for local communities
+  let community = Community::read(&mut context.pool(), data.community_id).await?;
+  if !community.local {
+    Err(LemmyErrorType::InvalidCommunityDigest)?;
+  }
+  if !(0..=6).contains(&data.day_of_week)
+    || !(0..=23).contains(&data.hour)
+    || !(1..=MAX_DIGEST_POSTS).contains(&data.top_posts)
+  {
+    Err(LemmyErrorType::InvalidCommunityDigest)?;
+  }
+
+  let form = CommunityDigestForm {
+    community_id: data.community_id,
+    enabled: data.enabled,
+    day_of_week: data.day_of_week,
+    hour: data.hour,
+    top_posts: data.top_posts,
+    min_score: data.min_score,
+    feature: data.feature,
+  };
+  let community_digest = CommunityDigest::upsert(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateCommunityDigest)?;
+
+  Ok(Json(CommunityDigestResponse {
+    community_digest: Some(community_digest),
+  }))
+}
@@ -0,0 +1,59 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{CommunityPageResponse, CreateCommunityPage},
+  context::LemmyContext,
+  utils::{
+    check_community_deleted_or_removed,
+    is_mod_or_admin,
+    local_site_to_slur_regex,
+    local_user_view_from_jwt,
+    sanitize_html,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    community_page::{CommunityPage, CommunityPageInsertForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{
+    slurs::check_slurs,
+    validation::{is_valid_body_field, is_valid_page_slug, is_valid_post_title},
+  },
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn create_community_page(
+  data: Json<CreateCommunityPage>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityPageResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  check_slurs(&data.title, &slur_regex)?;
+  check_slurs(&data.content, &slur_regex)?;
+  is_valid_page_slug(&data.slug)?;
+  is_valid_post_title(&data.title)?;
+  is_valid_body_field(&Some(data.content.clone()), false)?;
+
+  let community_id = data.community_id;
+  is_mod_or_admin(&mut context.pool(), local_user_view.person.id, community_id).await?;
+  check_community_deleted_or_removed(community_id, &mut context.pool()).await?;
+
+  let page_form = CommunityPageInsertForm::builder()
+    .community_id(community_id)
+    .slug(data.slug.clone())
+    .title(sanitize_html(&data.title))
+    .content(sanitize_html(&data.content))
+    .build();
+  let community_page = CommunityPage::create(&mut context.pool(), &page_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CommunityPageSlugTaken)?;
+
+  Ok(Json(CommunityPageResponse { community_page }))
+}
@@ -0,0 +1,31 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{DeleteCommunityPage, DeleteCommunityPageResponse},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{source::community_page::CommunityPage, traits::Crud};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_community_page(
+  data: Json<DeleteCommunityPage>,
+  context: Data<LemmyContext>,
+) -> Result<Json<DeleteCommunityPageResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  let orig_page = CommunityPage::read(&mut context.pool(), data.id).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    orig_page.community_id,
+  )
+  .await?;
+
+  CommunityPage::delete(&mut context.pool(), data.id).await?;
+  Ok(Json(DeleteCommunityPageResponse {
+    id: data.id,
+    success: true,
+  }))
+}
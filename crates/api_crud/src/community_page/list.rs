@@ -0,0 +1,29 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  community::{ListCommunityPages, ListCommunityPagesResponse},
+  context::LemmyContext,
+  utils::{
+    check_community_deleted_or_removed,
+    check_private_instance,
+    local_user_view_from_jwt_opt,
+  },
+};
+use lemmy_db_schema::source::{community_page::CommunityPage, local_site::LocalSite};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_community_pages(
+  data: Query<ListCommunityPages>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListCommunityPagesResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), &context).await;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  check_private_instance(&local_user_view, &local_site)?;
+  check_community_deleted_or_removed(data.community_id, &mut context.pool()).await?;
+
+  let community_pages =
+    CommunityPage::list_for_community(&mut context.pool(), data.community_id).await?;
+
+  Ok(Json(ListCommunityPagesResponse { community_pages }))
+}
@@ -0,0 +1,58 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{CommunityPageResponse, EditCommunityPage},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_site_to_slur_regex, local_user_view_from_jwt, sanitize_html_opt},
+};
+use lemmy_db_schema::{
+  source::{
+    community_page::{CommunityPage, CommunityPageUpdateForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{
+    slurs::check_slurs_opt,
+    validation::{is_valid_body_field, is_valid_post_title},
+  },
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn update_community_page(
+  data: Json<EditCommunityPage>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityPageResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  check_slurs_opt(&data.title, &slur_regex)?;
+  check_slurs_opt(&data.content, &slur_regex)?;
+  if let Some(title) = &data.title {
+    is_valid_post_title(title)?;
+  }
+  is_valid_body_field(&data.content, false)?;
+
+  let orig_page = CommunityPage::read(&mut context.pool(), data.id).await?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    orig_page.community_id,
+  )
+  .await?;
+
+  let page_form = CommunityPageUpdateForm {
+    title: sanitize_html_opt(&data.title),
+    content: sanitize_html_opt(&data.content),
+    updated: Some(Some(naive_now())),
+  };
+  let community_page = CommunityPage::update(&mut context.pool(), data.id, &page_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateCommunityPage)?;
+
+  Ok(Json(CommunityPageResponse { community_page }))
+}
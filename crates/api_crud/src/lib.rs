@@ -1,7 +1,11 @@
 pub mod comment;
 pub mod community;
+pub mod community_digest;
+pub mod community_page;
 pub mod custom_emoji;
+pub mod oauth_provider;
 pub mod post;
+pub mod post_reminder;
 pub mod private_message;
 pub mod site;
 pub mod user;
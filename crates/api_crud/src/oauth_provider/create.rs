@@ -0,0 +1,39 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  oauth::{CreateOAuthProvider, OAuthProviderResponse},
+  request::fetch_openid_configuration,
+  utils::{is_admin, local_user_view_from_jwt, sanitize_html},
+};
+use lemmy_db_schema::{
+  source::oauth_provider::{OAuthProvider, OAuthProviderInsertForm},
+  traits::Crud,
+};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn create_oauth_provider(
+  data: Json<CreateOAuthProvider>,
+  context: Data<LemmyContext>,
+) -> Result<Json<OAuthProviderResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  // Make sure the provider can actually be used for logging in
+  let issuer = data.issuer.as_str().trim_end_matches('/').to_string();
+  fetch_openid_configuration(context.client(), &issuer).await?;
+
+  let form = OAuthProviderInsertForm {
+    display_name: sanitize_html(&data.display_name),
+    issuer,
+    client_id: data.client_id.clone(),
+    client_secret: data.client_secret.clone().into_inner(),
+    scopes: data.scopes.clone(),
+    enabled: data.enabled,
+  };
+  let oauth_provider = OAuthProvider::create(&mut context.pool(), &form).await?;
+  Ok(Json(OAuthProviderResponse { oauth_provider }))
+}
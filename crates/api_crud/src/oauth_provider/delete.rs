@@ -0,0 +1,25 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  oauth::{DeleteOAuthProvider, DeleteOAuthProviderResponse},
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{source::oauth_provider::OAuthProvider, traits::Crud};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_oauth_provider(
+  data: Json<DeleteOAuthProvider>,
+  context: Data<LemmyContext>,
+) -> Result<Json<DeleteOAuthProviderResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+  OAuthProvider::delete(&mut context.pool(), data.id).await?;
+  Ok(Json(DeleteOAuthProviderResponse {
+    id: data.id,
+    success: true,
+  }))
+}
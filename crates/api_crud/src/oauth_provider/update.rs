@@ -0,0 +1,35 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  oauth::{EditOAuthProvider, OAuthProviderResponse},
+  utils::{is_admin, local_user_view_from_jwt, sanitize_html_opt},
+};
+use lemmy_db_schema::{
+  source::oauth_provider::{OAuthProvider, OAuthProviderUpdateForm},
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn update_oauth_provider(
+  data: Json<EditOAuthProvider>,
+  context: Data<LemmyContext>,
+) -> Result<Json<OAuthProviderResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  // Make sure user is an admin
+  is_admin(&local_user_view)?;
+
+  let form = OAuthProviderUpdateForm {
+    display_name: sanitize_html_opt(&data.display_name),
+    client_id: data.client_id.clone(),
+    client_secret: data.client_secret.clone().map(|s| s.into_inner()),
+    scopes: data.scopes.clone(),
+    enabled: data.enabled,
+    updated: Some(Some(naive_now())),
+  };
+  let oauth_provider = OAuthProvider::update(&mut context.pool(), data.id, &form).await?;
+  Ok(Json(OAuthProviderResponse { oauth_provider }))
+}
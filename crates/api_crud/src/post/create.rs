@@ -1,19 +1,27 @@
+use crate::post::{check_post_translations, save_post_translations};
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use chrono::{NaiveDateTime, Utc};
 use lemmy_api_common::{
-  build_response::build_post_response,
+  build_response::{build_post_response, store_community_mentions},
   context::LemmyContext,
-  post::{CreatePost, PostResponse},
-  request::fetch_site_data,
+  image_proxy::restore_markdown_images,
+  post::{CreatePoll, CreatePost, PostResponse},
+  request::{fetch_site_data, thumbnail_needs_retry},
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
+    archivable_post_url,
+    archive_post_url,
     check_community_ban,
     check_community_deleted_or_removed,
+    check_nsfw_allowed,
+    check_posting_allowed,
     generate_local_apub_endpoint,
     honeypot_check,
     local_site_to_slur_regex,
     local_user_view_from_jwt,
     mark_post_as_read,
+    nsfw_with_content_warning,
     sanitize_html,
     sanitize_html_opt,
     EndpointType,
@@ -24,8 +32,12 @@ use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
     community::Community,
+    community_mention::CommunityMention,
+    form_submission::{FormClaim, FormSubmission, FormSubmissionForm},
     local_site::LocalSite,
+    poll::{Poll, PollInsertForm},
     post::{Post, PostInsertForm, PostLike, PostLikeForm, PostUpdateForm},
+    post_thumbnail_retry::PostThumbnailRetry,
   },
   traits::{Crud, Likeable},
 };
@@ -34,8 +46,18 @@ use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
   spawn_try_task,
   utils::{
+    markdown::normalize_spoilers_opt,
+    mention::scrape_text_for_community_mentions,
     slurs::{check_slurs, check_slurs_opt},
-    validation::{check_url_scheme, clean_url_params, is_valid_body_field, is_valid_post_title},
+    validation::{
+      check_url_scheme,
+      clean_poll_options,
+      clean_url_params,
+      is_valid_body_field,
+      is_valid_content_warning,
+      is_valid_form_id,
+      is_valid_post_title,
+    },
   },
   SYNCHRONOUS_FEDERATION,
 };
@@ -54,6 +76,7 @@ pub async fn create_post(
   let slur_regex = local_site_to_slur_regex(&local_site);
   check_slurs(&data.name, &slur_regex)?;
   check_slurs_opt(&data.body, &slur_regex)?;
+  check_slurs_opt(&data.content_warning, &slur_regex)?;
   honeypot_check(&data.honeypot)?;
 
   let data_url = data.url.as_ref();
@@ -61,7 +84,18 @@ pub async fn create_post(
 
   is_valid_post_title(&data.name)?;
   is_valid_body_field(&data.body, true)?;
+  is_valid_content_warning(&data.content_warning)?;
+  is_valid_form_id(&data.form_id)?;
   check_url_scheme(&data.url)?;
+  let poll = data
+    .poll
+    .as_ref()
+    .map(|poll| check_poll(poll, &local_site))
+    .transpose()?;
+
+  let content_warning = sanitize_html_opt(&data.content_warning).filter(|c| !c.is_empty());
+  let nsfw = nsfw_with_content_warning(data.nsfw, content_warning.is_some(), &local_site);
+  check_nsfw_allowed(nsfw, &local_site)?;
 
   check_community_ban(
     local_user_view.person.id,
@@ -72,29 +106,31 @@ pub async fn create_post(
   check_community_deleted_or_removed(data.community_id, &mut context.pool()).await?;
 
   let community_id = data.community_id;
-  let community = Community::read(&mut context.pool(), community_id).await?;
-  if community.posting_restricted_to_mods {
-    let community_id = data.community_id;
-    let is_mod = CommunityView::is_mod_or_admin(
+  let community = Community::read(&mut context.pool(), community_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
+  check_nsfw_allowed(Some(community.nsfw), &local_site)?;
+  let is_mod = community.posting_restricted_to_mods
+    && CommunityView::is_mod_or_admin(
       &mut context.pool(),
       local_user_view.local_user.person_id,
       community_id,
     )
     .await?;
-    if !is_mod {
-      return Err(LemmyErrorType::OnlyModsCanPostInCommunity)?;
-    }
-  }
+  check_posting_allowed(&community, is_mod).map_err(LemmyErrorType::from)?;
 
   // Fetch post links and pictrs cached image
   let (metadata_res, thumbnail_url) =
     fetch_site_data(context.client(), context.settings(), data_url, true).await;
-  let (embed_title, embed_description, embed_video_url) = metadata_res
-    .map(|u| (u.title, u.description, u.embed_video_url))
+  let retry_thumbnail = thumbnail_needs_retry(data_url, &thumbnail_url);
+  let (embed_title, embed_description, embed_video_url, embed_provider) = metadata_res
+    .map(|u| (u.title, u.description, u.embed_video_url, u.embed_provider))
     .unwrap_or_default();
 
   let name = sanitize_html(data.name.trim());
-  let body = sanitize_html_opt(&data.body);
+  let body = normalize_spoilers_opt(&data.body)?;
+  let body = body.map(|body| restore_markdown_images(&body, &context));
+  let body = sanitize_html_opt(&body);
   let embed_title = sanitize_html_opt(&embed_title);
   let embed_description = sanitize_html_opt(&embed_description);
 
@@ -106,6 +142,13 @@ pub async fn create_post(
     community_id,
   )
   .await?;
+  let translations = check_post_translations(
+    &data.content_translations,
+    community_id,
+    &local_site,
+    &mut context.pool(),
+  )
+  .await?;
 
   // attempt to set default language if none was provided
   let language_id = match data.language_id {
@@ -126,17 +169,66 @@ pub async fn create_post(
     .body(body)
     .community_id(data.community_id)
     .creator_id(local_user_view.person.id)
-    .nsfw(data.nsfw)
+    .nsfw(nsfw)
+    .content_warning(content_warning)
     .embed_title(embed_title)
     .embed_description(embed_description)
     .embed_video_url(embed_video_url)
+    .embed_provider(embed_provider)
     .language_id(language_id)
     .thumbnail_url(thumbnail_url)
     .build();
 
-  let inserted_post = Post::create(&mut context.pool(), &post_form)
-    .await
-    .with_lemmy_type(LemmyErrorType::CouldntCreatePost)?;
+  // Submitting the same form again returns the post which it created
+  let submission = match &data.form_id {
+    Some(form_id) => {
+      let form = FormSubmissionForm {
+        creator_id: local_user_view.person.id,
+        form_id: form_id.clone(),
+      };
+      match FormSubmission::claim(&mut context.pool(), &form).await? {
+        FormClaim::New(submission) => Some(submission),
+        FormClaim::Existing(submission) => {
+          let post_id = submission
+            .post_id
+            .ok_or(LemmyErrorType::FormAlreadySubmitted)?;
+          return build_post_response(&context, community_id, local_user_view.person.id, post_id)
+            .await;
+        }
+      }
+    }
+    None => None,
+  };
+
+  // The poll is inserted in the same transaction as the post, so that a failure doesn't leave
+  // behind a post without its poll
+  let inserted_post = match (&data.poll, poll) {
+    (Some(data_poll), Some((options, ends_at))) => {
+      let allow_multiple = data_poll.allow_multiple.unwrap_or(false);
+      let hide_results_until_voted = data_poll.hide_results_until_voted.unwrap_or(false);
+      let poll_form = |post_id| PollInsertForm {
+        post_id,
+        ends_at,
+        allow_multiple,
+        hide_results_until_voted,
+      };
+      Poll::create_with_post(&mut context.pool(), &post_form, poll_form, options)
+        .await
+        .map(|(post, _, _)| post)
+    }
+    _ => Post::create(&mut context.pool(), &post_form).await,
+  };
+  if let Some(submission) = submission {
+    match &inserted_post {
+      Ok(post) => {
+        FormSubmission::set_post(&mut context.pool(), submission.id, post.id).await?;
+      }
+      Err(_) => {
+        FormSubmission::release(&mut context.pool(), submission.id).await?;
+      }
+    }
+  }
+  let inserted_post = inserted_post.with_lemmy_type(LemmyErrorType::CouldntCreatePost)?;
 
   let inserted_post_id = inserted_post.id;
   let protocol_and_hostname = context.settings().get_protocol_and_hostname();
@@ -155,6 +247,16 @@ pub async fn create_post(
   )
   .await
   .with_lemmy_type(LemmyErrorType::CouldntCreatePost)?;
+  save_post_translations(inserted_post_id, translations, &mut context.pool()).await?;
+  if retry_thumbnail {
+    PostThumbnailRetry::schedule(&mut context.pool(), inserted_post_id).await?;
+  }
+
+  // Store the communities which the body mentions, so that clients can link them
+  if let Some(body) = &updated_post.body {
+    let community_mentions = scrape_text_for_community_mentions(body);
+    store_community_mentions(community_mentions, Some(inserted_post_id), None, &context).await;
+  }
 
   // They like their own post by default
   let person_id = local_user_view.person.id;
@@ -175,6 +277,14 @@ pub async fn create_post(
   // Mark the post as read
   mark_post_as_read(person_id, post_id, &mut context.pool()).await?;
 
+  if let Some(url) = archivable_post_url(&updated_post, &local_site, context.settings()) {
+    spawn_try_task(archive_post_url(
+      post_id,
+      url,
+      LemmyContext::clone(&context),
+    ));
+  }
+
   if let Some(url) = updated_post.url.clone() {
     let task = async move {
       let mut webmention =
@@ -197,5 +307,33 @@ pub async fn create_post(
     }
   };
 
-  build_post_response(&context, community_id, person_id, post_id).await
+  let mut response = build_post_response(&context, community_id, person_id, post_id).await?;
+  response.community_mentions =
+    CommunityMention::list_for_post(&mut context.pool(), post_id).await?;
+  Ok(response)
+}
+
+/// Validates the poll of a new post, and returns its sanitized options and end time.
+fn check_poll(
+  poll: &CreatePoll,
+  local_site: &LocalSite,
+) -> Result<(Vec<String>, Option<NaiveDateTime>), LemmyError> {
+  let slur_regex = local_site_to_slur_regex(local_site);
+  let options = clean_poll_options(&poll.options)?;
+  for option in &options {
+    check_slurs(option, &slur_regex)?;
+  }
+  let ends_at = match poll.ends_at {
+    Some(ends_at) => {
+      let ends_at =
+        NaiveDateTime::from_timestamp_opt(ends_at, 0).ok_or(LemmyErrorType::InvalidPoll)?;
+      if ends_at <= Utc::now().naive_utc() {
+        Err(LemmyErrorType::InvalidPoll)?;
+      }
+      Some(ends_at)
+    }
+    None => None,
+  };
+  let options = options.iter().map(|o| sanitize_html(o)).collect();
+  Ok((options, ends_at))
 }
@@ -1,5 +1,63 @@
+use lemmy_api_common::utils::{local_site_to_slur_regex, sanitize_html};
+use lemmy_db_schema::{
+  newtypes::{CommunityId, LanguageId, PostId},
+  source::{
+    actor_language::CommunityLanguage,
+    local_site::LocalSite,
+    post_translation::{PostTranslation, PostTranslationForm},
+  },
+  utils::DbPool,
+};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::{slurs::check_slurs, validation::is_valid_body_field},
+};
+use std::collections::HashMap;
+
 pub mod create;
 pub mod delete;
 pub mod read;
 pub mod remove;
 pub mod update;
+
+/// Checks the translations of a post body the same way as the body itself, and returns them
+/// sanitized.
+async fn check_post_translations(
+  translations: &Option<HashMap<LanguageId, String>>,
+  community_id: CommunityId,
+  local_site: &LocalSite,
+  pool: &mut DbPool<'_>,
+) -> Result<Option<Vec<(LanguageId, String)>>, LemmyError> {
+  let Some(translations) = translations else {
+    return Ok(None);
+  };
+  let slur_regex = local_site_to_slur_regex(local_site);
+  let mut checked = vec![];
+  for (language_id, content) in translations {
+    check_slurs(content, &slur_regex)?;
+    is_valid_body_field(&Some(content.clone()), true)?;
+    CommunityLanguage::is_allowed_community_language(pool, Some(*language_id), community_id)
+      .await?;
+    checked.push((*language_id, sanitize_html(content)));
+  }
+  Ok(Some(checked))
+}
+
+async fn save_post_translations(
+  post_id: PostId,
+  translations: Option<Vec<(LanguageId, String)>>,
+  pool: &mut DbPool<'_>,
+) -> Result<(), LemmyError> {
+  if let Some(translations) = translations {
+    let forms = translations
+      .into_iter()
+      .map(|(language_id, content)| PostTranslationForm {
+        post_id,
+        language_id,
+        content,
+      })
+      .collect();
+    PostTranslation::replace(pool, post_id, forms).await?;
+  }
+  Ok(())
+}
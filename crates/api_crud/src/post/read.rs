@@ -1,6 +1,8 @@
 use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::proxy_post_view_images,
+  permissions::Permissions,
   post::{GetPost, GetPostResponse},
   utils::{
     check_private_instance,
@@ -11,10 +13,18 @@ use lemmy_api_common::{
 };
 use lemmy_db_schema::{
   aggregates::structs::{PersonPostAggregates, PersonPostAggregatesForm},
-  source::{comment::Comment, local_site::LocalSite, post::Post},
+  source::{
+    comment::Comment,
+    local_site::LocalSite,
+    post::Post,
+    post_translation::PostTranslation,
+  },
   traits::Crud,
 };
-use lemmy_db_views::{post_view::PostQuery, structs::PostView};
+use lemmy_db_views::{
+  post_view::PostQuery,
+  structs::{PollView, PostView},
+};
 use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView};
 use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
 
@@ -52,7 +62,7 @@ pub async fn get_post(
   .await
   .is_ok();
 
-  let post_view = PostView::read(&mut context.pool(), post_id, person_id, is_mod_or_admin)
+  let mut post_view = PostView::read(&mut context.pool(), post_id, person_id, is_mod_or_admin)
     .await
     .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
 
@@ -90,7 +100,7 @@ pub async fn get_post(
   let moderators = CommunityModeratorView::for_community(&mut context.pool(), community_id).await?;
 
   // Fetch the cross_posts
-  let cross_posts = if let Some(url) = &post_view.post.url {
+  let mut cross_posts = if let Some(url) = &post_view.post.url {
     let mut x_posts = PostQuery {
       url_search: Some(url.inner().as_str().into()),
       ..Default::default()
@@ -105,11 +115,44 @@ pub async fn get_post(
     Vec::new()
   };
 
+  let translations = PostTranslation::list(&mut context.pool(), post_id).await?;
+
+  let poll = match post_view.poll.clone() {
+    Some(poll) => Some(
+      PollView::read(
+        &mut context.pool(),
+        poll,
+        post_view.post.creator_id,
+        person_id,
+      )
+      .await?,
+    ),
+    None => None,
+  };
+
+  let permissions = Permissions::for_post(
+    &mut context.pool(),
+    local_user_view.as_ref().map(|u| &u.person),
+    &community_view.community,
+    &post_view.post,
+  )
+  .await?;
+
+  if local_site.proxy_remote_images {
+    proxy_post_view_images(&mut post_view, &context);
+    for cross_post in &mut cross_posts {
+      proxy_post_view_images(cross_post, &context);
+    }
+  }
+
   // Return the jwt
   Ok(Json(GetPostResponse {
     post_view,
     community_view,
     moderators,
     cross_posts,
+    translations,
+    poll,
+    permissions,
   }))
 }
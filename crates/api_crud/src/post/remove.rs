@@ -5,17 +5,23 @@ use lemmy_api_common::{
   context::LemmyContext,
   post::{PostResponse, RemovePost},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_community_ban, is_mod_or_admin, local_user_view_from_jwt},
+  utils::{
+    check_community_ban,
+    check_mod_action_reason,
+    is_mod_or_admin,
+    local_user_view_from_jwt,
+  },
 };
 use lemmy_db_schema::{
   source::{
+    local_site::LocalSite,
     moderator::{ModRemovePost, ModRemovePostForm},
     post::{Post, PostUpdateForm},
     post_report::PostReport,
   },
   traits::{Crud, Reportable},
 };
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
 
 #[tracing::instrument(skip(context))]
 pub async fn remove_post(
@@ -25,7 +31,9 @@ pub async fn remove_post(
   let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
 
   let post_id = data.post_id;
-  let orig_post = Post::read(&mut context.pool(), post_id).await?;
+  let orig_post = Post::read(&mut context.pool(), post_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
 
   check_community_ban(
     local_user_view.person.id,
@@ -41,6 +49,8 @@ pub async fn remove_post(
     orig_post.community_id,
   )
   .await?;
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_mod_action_reason(&data.reason, &local_site)?;
 
   // Update the post
   let post_id = data.post_id;
@@ -1,32 +1,51 @@
+use crate::post::{check_post_translations, save_post_translations};
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_common::{
-  build_response::build_post_response,
+  build_response::{build_post_response, store_community_mentions},
   context::LemmyContext,
+  image_proxy::restore_markdown_images,
   post::{EditPost, PostResponse},
-  request::fetch_site_data,
+  request::{fetch_site_data, thumbnail_needs_retry},
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
+    archivable_post_url,
+    archive_post_url,
     check_community_ban,
+    check_nsfw_allowed,
     local_site_to_slur_regex,
     local_user_view_from_jwt,
+    nsfw_with_content_warning,
     sanitize_html_opt,
   },
 };
 use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
+    community::Community,
+    community_mention::CommunityMention,
+    edit_history::PostEditHistory,
     local_site::LocalSite,
     post::{Post, PostUpdateForm},
+    post_thumbnail_retry::PostThumbnailRetry,
   },
   traits::Crud,
   utils::{diesel_option_overwrite, naive_now},
 };
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  spawn_try_task,
   utils::{
+    markdown::normalize_spoilers_opt,
+    mention::scrape_text_for_community_mentions,
     slurs::check_slurs_opt,
-    validation::{check_url_scheme, clean_url_params, is_valid_body_field, is_valid_post_title},
+    validation::{
+      check_url_scheme,
+      clean_url_params,
+      is_valid_body_field,
+      is_valid_content_warning,
+      is_valid_post_title,
+    },
   },
 };
 use std::ops::Deref;
@@ -48,16 +67,28 @@ pub async fn update_post(
   let slur_regex = local_site_to_slur_regex(&local_site);
   check_slurs_opt(&data.name, &slur_regex)?;
   check_slurs_opt(&data.body, &slur_regex)?;
+  check_slurs_opt(&data.content_warning, &slur_regex)?;
 
   if let Some(name) = &data.name {
     is_valid_post_title(name)?;
   }
 
   is_valid_body_field(&data.body, true)?;
+  is_valid_content_warning(&data.content_warning)?;
   check_url_scheme(&data.url)?;
 
+  let content_warning = diesel_option_overwrite(sanitize_html_opt(&data.content_warning));
+  let nsfw = nsfw_with_content_warning(
+    data.nsfw,
+    matches!(content_warning, Some(Some(_))),
+    &local_site,
+  );
+  check_nsfw_allowed(nsfw, &local_site)?;
+
   let post_id = data.post_id;
-  let orig_post = Post::read(&mut context.pool(), post_id).await?;
+  let orig_post = Post::read(&mut context.pool(), post_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
 
   check_community_ban(
     local_user_view.person.id,
@@ -71,16 +102,30 @@ pub async fn update_post(
     return Err(LemmyErrorType::NoPostEditAllowed)?;
   }
 
+  // The community may have been marked as NSFW since the post was created
+  let community = Community::read(&mut context.pool(), orig_post.community_id).await?;
+  check_nsfw_allowed(Some(community.nsfw), &local_site)?;
+
   // Fetch post links and Pictrs cached image
   let data_url = data.url.as_ref();
   let (metadata_res, thumbnail_url) =
     fetch_site_data(context.client(), context.settings(), data_url, true).await;
-  let (embed_title, embed_description, embed_video_url) = metadata_res
-    .map(|u| (Some(u.title), Some(u.description), Some(u.embed_video_url)))
+  let retry_thumbnail = thumbnail_needs_retry(data_url, &thumbnail_url);
+  let (embed_title, embed_description, embed_video_url, embed_provider) = metadata_res
+    .map(|u| {
+      (
+        Some(u.title),
+        Some(u.description),
+        Some(u.embed_video_url),
+        Some(u.embed_provider),
+      )
+    })
     .unwrap_or_default();
 
   let name = sanitize_html_opt(&data.name);
-  let body = sanitize_html_opt(&data.body);
+  let body = normalize_spoilers_opt(&data.body)?;
+  let body = body.map(|body| restore_markdown_images(&body, &context));
+  let body = sanitize_html_opt(&body);
   let body = diesel_option_overwrite(body);
   let embed_title = embed_title.map(|e| sanitize_html_opt(&e));
   let embed_description = embed_description.map(|e| sanitize_html_opt(&e));
@@ -92,15 +137,29 @@ pub async fn update_post(
     orig_post.community_id,
   )
   .await?;
+  let translations = check_post_translations(
+    &data.content_translations,
+    orig_post.community_id,
+    &local_site,
+    &mut context.pool(),
+  )
+  .await?;
+
+  // The snapshot of the previous link doesn't belong to the new one
+  let url_changed = url.as_ref().is_some_and(|url| url != &orig_post.url);
+  let archive_url = if url_changed { Some(None) } else { None };
 
   let post_form = PostUpdateForm {
     name,
     url,
+    archive_url,
     body,
-    nsfw: data.nsfw,
+    nsfw,
+    content_warning,
     embed_title,
     embed_description,
     embed_video_url,
+    embed_provider,
     language_id: data.language_id,
     thumbnail_url: Some(thumbnail_url),
     updated: Some(Some(naive_now())),
@@ -108,17 +167,47 @@ pub async fn update_post(
   };
 
   let post_id = data.post_id;
-  let updated_post = Post::update(&mut context.pool(), post_id, &post_form)
-    .await
-    .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
+  // Keep the previous version, so that mods can see what was originally said
+  let updated_post = PostEditHistory::update_post(
+    &mut context.pool(),
+    &orig_post,
+    &post_form,
+    local_user_view.person.id,
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)?;
+  save_post_translations(post_id, translations, &mut context.pool()).await?;
+  if retry_thumbnail {
+    PostThumbnailRetry::schedule(&mut context.pool(), post_id).await?;
+  }
+
+  if url_changed {
+    if let Some(url) = archivable_post_url(&updated_post, &local_site, context.settings()) {
+      spawn_try_task(archive_post_url(
+        post_id,
+        url,
+        LemmyContext::clone(&context),
+      ));
+    }
+  }
+
+  // The edited body may mention other communities than before
+  CommunityMention::delete_for_post(&mut context.pool(), post_id).await?;
+  if let Some(body) = &updated_post.body {
+    let community_mentions = scrape_text_for_community_mentions(body);
+    store_community_mentions(community_mentions, Some(post_id), None, &context).await;
+  }
 
   ActivityChannel::submit_activity(SendActivityData::UpdatePost(updated_post), &context).await?;
 
-  build_post_response(
+  let mut response = build_post_response(
     context.deref(),
     orig_post.community_id,
     local_user_view.person.id,
     post_id,
   )
-  .await
+  .await?;
+  response.community_mentions =
+    CommunityMention::list_for_post(&mut context.pool(), post_id).await?;
+  Ok(response)
 }
@@ -0,0 +1,52 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{CreatePostReminder, PostReminderResponse},
+  utils::{local_user_view_from_jwt, sanitize_html_opt},
+};
+use lemmy_db_schema::{
+  source::{
+    post::Post,
+    post_reminder::{PostReminder, PostReminderInsertForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views_actor::structs::PostReminderView;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  utils::{time::naive_from_unix, validation::is_valid_body_field},
+};
+
+/// The max number of pending reminders per user.
+const MAX_POST_REMINDERS: i64 = 100;
+
+#[tracing::instrument(skip(context))]
+pub async fn create_post_reminder(
+  data: Json<CreatePostReminder>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PostReminderResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+  is_valid_body_field(&data.note, false)?;
+
+  let post = Post::read(&mut context.pool(), data.post_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
+
+  let pending = PostReminder::count_for_person(&mut context.pool(), person_id).await?;
+  if pending >= MAX_POST_REMINDERS {
+    return Err(LemmyErrorType::TooManyPostReminders)?;
+  }
+
+  let form = PostReminderInsertForm {
+    person_id,
+    post_id: post.id,
+    remind_at: naive_from_unix(data.remind_at),
+    note: sanitize_html_opt(&data.note),
+  };
+  let post_reminder = PostReminder::create(&mut context.pool(), &form).await?;
+  let post_reminder_view = PostReminderView::read(&mut context.pool(), post_reminder.id).await?;
+
+  Ok(Json(PostReminderResponse { post_reminder_view }))
+}
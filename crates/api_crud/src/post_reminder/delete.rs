@@ -0,0 +1,33 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{DeletePostReminder, DeletePostReminderResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::post_reminder::PostReminder;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_post_reminder(
+  data: Json<DeletePostReminder>,
+  context: Data<LemmyContext>,
+) -> Result<Json<DeletePostReminderResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  // Only your own reminders can be deleted
+  let deleted = PostReminder::delete_for_person(
+    &mut context.pool(),
+    data.post_reminder_id,
+    local_user_view.person.id,
+  )
+  .await?;
+  if deleted == 0 {
+    return Err(LemmyErrorType::CouldntFindPostReminder)?;
+  }
+
+  Ok(Json(DeletePostReminderResponse {
+    post_reminder_id: data.post_reminder_id,
+    success: true,
+  }))
+}
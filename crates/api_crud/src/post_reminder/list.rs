@@ -0,0 +1,21 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{ListPostReminders, ListPostRemindersResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_views_actor::structs::PostReminderView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_post_reminders(
+  data: Query<ListPostReminders>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ListPostRemindersResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  let post_reminders =
+    PostReminderView::list_for_person(&mut context.pool(), local_user_view.person.id).await?;
+
+  Ok(Json(ListPostRemindersResponse { post_reminders }))
+}
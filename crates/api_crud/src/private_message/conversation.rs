@@ -0,0 +1,63 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  private_message::{
+    GetPrivateMessageConversations,
+    GetPrivateMessagesWith,
+    PrivateMessageConversationsResponse,
+    PrivateMessagesResponse,
+  },
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_views::structs::{PrivateMessageConversationView, PrivateMessageView};
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_private_message_conversations(
+  data: Query<GetPrivateMessageConversations>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PrivateMessageConversationsResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(data.auth.as_ref(), &context).await?;
+  let person_id = local_user_view.person.id;
+
+  let mut conversations =
+    PrivateMessageConversationView::list(&mut context.pool(), person_id, data.page, data.limit)
+      .await?;
+
+  // Like in the message list, our own messages are always shown as read
+  conversations.iter_mut().for_each(|conversation| {
+    if conversation.latest_message.creator_id == person_id {
+      conversation.latest_message.read = true
+    }
+  });
+
+  Ok(Json(PrivateMessageConversationsResponse { conversations }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn get_private_messages_with(
+  data: Query<GetPrivateMessagesWith>,
+  context: Data<LemmyContext>,
+) -> Result<Json<PrivateMessagesResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(data.auth.as_ref(), &context).await?;
+  let person_id = local_user_view.person.id;
+
+  let mut messages = PrivateMessageView::list_with(
+    &mut context.pool(),
+    person_id,
+    data.person_id,
+    data.page,
+    data.limit,
+  )
+  .await?;
+
+  messages.iter_mut().for_each(|pmv| {
+    if pmv.creator.id == person_id {
+      pmv.private_message.read = true
+    }
+  });
+
+  Ok(Json(PrivateMessagesResponse {
+    private_messages: messages,
+  }))
+}
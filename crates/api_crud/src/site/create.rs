@@ -121,6 +121,8 @@ pub async fn create_site(
     comment_per_second: data.rate_limit_comment_per_second,
     search: data.rate_limit_search,
     search_per_second: data.rate_limit_search_per_second,
+    export: data.rate_limit_export,
+    export_per_second: data.rate_limit_export_per_second,
     ..Default::default()
   };
 
@@ -532,6 +534,18 @@ mod tests {
       updated: None,
       registration_mode: site_registration_mode,
       reports_email_admins: false,
+      enable_url_archiving: false,
+      url_archiving_excluded_domains: None,
+      featured_local_posts_max: 5,
+      disallow_nsfw_content: false,
+      federate_nsfw_outbound: true,
+      oauth_registration: false,
+      content_warning_sets_nsfw: false,
+      community_digest_bot_id: None,
+      account_deletion_cooling_off_days: 7,
+      application_expire_days: 0,
+      require_mod_action_reason: false,
+      proxy_remote_images: false,
     }
   }
 
@@ -581,6 +595,8 @@ mod tests {
       rate_limit_comment_per_second: None,
       rate_limit_search: None,
       rate_limit_search_per_second: None,
+      rate_limit_export: None,
+      rate_limit_export_per_second: None,
       federation_enabled: site_is_federated,
       federation_debug: None,
       captcha_enabled: None,
@@ -3,13 +3,16 @@ use lemmy_api_common::{
   context::LemmyContext,
   sensitive::Sensitive,
   site::{GetSite, GetSiteResponse, MyUserInfo},
-  utils::{check_user_valid, check_validator_time},
+  utils::{check_deletion_not_pending, check_user_valid, check_validator_time},
 };
 use lemmy_db_schema::{
   newtypes::LocalUserId,
   source::{
     actor_language::{LocalUserLanguage, SiteLanguage},
+    community_transfer_request::CommunityTransferRequest,
     language::Language,
+    oauth_provider::OAuthProvider,
+    person_keyword_block::PersonKeywordBlock,
     tagline::Tagline,
   },
 };
@@ -32,13 +35,27 @@ pub async fn get_site(
   data: Query<GetSite>,
   context: Data<LemmyContext>,
 ) -> Result<Json<GetSiteResponse>, LemmyError> {
+  match read_site(&data, &context).await {
+    Ok(site) => {
+      context.set_cached_site(&site);
+      Ok(Json(site))
+    }
+    // Without the database, the site is shown as it was last read, without user info
+    Err(e) if e.error_type == LemmyErrorType::DatabaseUnavailable => {
+      context.cached_site().map(Json).ok_or(e)
+    }
+    Err(e) => Err(e),
+  }
+}
+
+async fn read_site(data: &GetSite, context: &LemmyContext) -> Result<GetSiteResponse, LemmyError> {
   let site_view = SiteView::read_local(&mut context.pool()).await?;
 
   let admins = PersonView::admins(&mut context.pool()).await?;
 
   // Build the local user
   let my_user = if let Some(local_user_view) =
-    local_user_settings_view_from_jwt_opt(data.auth.as_ref(), &context).await
+    local_user_settings_view_from_jwt_opt(data.auth.as_ref(), context).await
   {
     let person_id = local_user_view.person.id;
     let local_user_id = local_user_view.local_user.id;
@@ -65,6 +82,15 @@ pub async fn get_site(
       .await
       .with_lemmy_type(LemmyErrorType::SystemErrLogin)?;
 
+    let pending_community_transfers =
+      CommunityTransferRequest::list_communities_for_recipient(&mut context.pool(), person_id)
+        .await
+        .with_lemmy_type(LemmyErrorType::SystemErrLogin)?;
+
+    let blocked_keywords = PersonKeywordBlock::read(&mut context.pool(), person_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::SystemErrLogin)?;
+
     Some(MyUserInfo {
       local_user_view,
       follows,
@@ -72,18 +98,26 @@ pub async fn get_site(
       community_blocks,
       person_blocks,
       discussion_languages,
+      pending_community_transfers,
+      blocked_keywords,
     })
   } else {
     None
   };
 
+  let is_admin = my_user
+    .as_ref()
+    .map(|m| m.local_user_view.person.admin)
+    .unwrap_or(false);
+  let oauth_providers = OAuthProvider::list(&mut context.pool(), !is_admin).await?;
+
   let all_languages = Language::read_all(&mut context.pool()).await?;
   let discussion_languages = SiteLanguage::read_local_raw(&mut context.pool()).await?;
   let taglines = Tagline::get_all(&mut context.pool(), site_view.local_site.id).await?;
   let custom_emojis =
     CustomEmojiView::get_all(&mut context.pool(), site_view.local_site.id).await?;
 
-  Ok(Json(GetSiteResponse {
+  Ok(GetSiteResponse {
     site_view,
     admins,
     version: version::VERSION.to_string(),
@@ -92,7 +126,8 @@ pub async fn get_site(
     discussion_languages,
     taglines,
     custom_emojis,
-  }))
+    oauth_providers,
+  })
 }
 
 #[tracing::instrument(skip_all)]
@@ -115,6 +150,7 @@ async fn local_user_settings_view_from_jwt_opt(
         local_user_view.person.deleted,
       )
       .ok()?;
+      check_deletion_not_pending(&local_user_view.local_user).ok()?;
 
       check_validator_time(&local_user_view.local_user.validator_time, &claims).ok()?;
 
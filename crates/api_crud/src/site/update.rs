@@ -103,6 +103,19 @@ pub async fn update_site(
     captcha_enabled: data.captcha_enabled,
     captcha_difficulty: data.captcha_difficulty.clone(),
     reports_email_admins: data.reports_email_admins,
+    enable_url_archiving: data.enable_url_archiving,
+    url_archiving_excluded_domains: diesel_option_overwrite(
+      data.url_archiving_excluded_domains.clone(),
+    ),
+    featured_local_posts_max: data.featured_local_posts_max,
+    disallow_nsfw_content: data.disallow_nsfw_content,
+    federate_nsfw_outbound: data.federate_nsfw_outbound,
+    oauth_registration: data.oauth_registration,
+    content_warning_sets_nsfw: data.content_warning_sets_nsfw,
+    account_deletion_cooling_off_days: data.account_deletion_cooling_off_days,
+    application_expire_days: data.application_expire_days,
+    require_mod_action_reason: data.require_mod_action_reason,
+    proxy_remote_images: data.proxy_remote_images,
     ..Default::default()
   };
 
@@ -123,6 +136,8 @@ pub async fn update_site(
     comment_per_second: data.rate_limit_comment_per_second,
     search: data.rate_limit_search,
     search_per_second: data.rate_limit_search_per_second,
+    export: data.rate_limit_export,
+    export_per_second: data.rate_limit_export_per_second,
     ..Default::default()
   };
 
@@ -530,6 +545,18 @@ mod tests {
       updated: None,
       registration_mode: site_registration_mode,
       reports_email_admins: false,
+      enable_url_archiving: false,
+      url_archiving_excluded_domains: None,
+      featured_local_posts_max: 5,
+      disallow_nsfw_content: false,
+      federate_nsfw_outbound: true,
+      oauth_registration: false,
+      content_warning_sets_nsfw: false,
+      community_digest_bot_id: None,
+      account_deletion_cooling_off_days: 7,
+      application_expire_days: 0,
+      require_mod_action_reason: false,
+      proxy_remote_images: false,
     }
   }
 
@@ -579,6 +606,8 @@ mod tests {
       rate_limit_comment_per_second: None,
       rate_limit_search: None,
       rate_limit_search_per_second: None,
+      rate_limit_export: None,
+      rate_limit_export_per_second: None,
       federation_enabled: site_is_federated,
       federation_debug: None,
       captcha_enabled: None,
@@ -588,6 +617,17 @@ mod tests {
       taglines: None,
       registration_mode: site_registration_mode,
       reports_email_admins: None,
+      enable_url_archiving: None,
+      url_archiving_excluded_domains: None,
+      featured_local_posts_max: None,
+      disallow_nsfw_content: None,
+      federate_nsfw_outbound: None,
+      oauth_registration: None,
+      content_warning_sets_nsfw: None,
+      account_deletion_cooling_off_days: None,
+      application_expire_days: None,
+      require_mod_action_reason: None,
+      proxy_remote_images: None,
       auth: Default::default(),
     }
   }
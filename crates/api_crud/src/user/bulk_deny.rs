@@ -0,0 +1,50 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{BulkDenyRegistrationApplications, BulkDenyRegistrationApplicationsResponse},
+  utils::{is_admin, local_user_view_from_jwt, sanitize_html},
+};
+use lemmy_db_schema::source::registration_application::RegistrationApplication;
+use lemmy_db_views::registration_application_view::RegistrationApplicationQuery;
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
+
+const MAX_BULK_DENY_BATCH: usize = 100;
+
+/// Denies many registration applications at once, in the same way as denying them one by one.
+/// The users see the reason when they try to log in. Applications which were already approved or
+/// denied are left as they are.
+#[tracing::instrument(skip(context))]
+pub async fn bulk_deny_registration_applications(
+  data: Json<BulkDenyRegistrationApplications>,
+  context: Data<LemmyContext>,
+) -> Result<Json<BulkDenyRegistrationApplicationsResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+
+  // Only let admins do this
+  is_admin(&local_user_view)?;
+
+  if data.ids.len() > MAX_BULK_DENY_BATCH {
+    return Err(LemmyErrorType::TooManyRegistrationApplicationsInBatch)?;
+  }
+
+  let deny_reason = sanitize_html(&data.deny_reason);
+  let denied = RegistrationApplication::deny_many(
+    &mut context.pool(),
+    &data.ids,
+    Some(local_user_view.person.id),
+    &deny_reason,
+  )
+  .await?;
+
+  let registration_applications = RegistrationApplicationQuery {
+    ids: Some(denied.iter().map(|a| a.id).collect()),
+    ..Default::default()
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  Ok(Json(BulkDenyRegistrationApplicationsResponse {
+    registration_applications,
+  }))
+}
@@ -1,14 +1,29 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use bcrypt::verify;
+use chrono::Duration;
 use lemmy_api_common::{
   context::LemmyContext,
-  person::{DeleteAccount, DeleteAccountResponse},
+  person::{CancelAccountDeletion, ConfirmAccountDeletion, DeleteAccount, DeleteAccountResponse},
   send_activity::{ActivityChannel, SendActivityData},
   utils::local_user_view_from_jwt,
 };
-use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use lemmy_db_schema::{
+  newtypes::LocalUserId,
+  source::{local_site::LocalSite, local_user::LocalUser},
+  utils::naive_now,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  claims::{AccountDeletionAction, AccountDeletionClaims},
+  email::send_email,
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+};
+use tracing::warn;
 
+/// Deactivates the account and schedules its deletion after the cooling-off period of the site.
+/// The emailed links allow to cancel the deletion, or to delete the account right away if that
+/// was requested. Accounts without an email cancel with their password instead.
 #[tracing::instrument(skip(context))]
 pub async fn delete_account(
   data: Json<DeleteAccount>,
@@ -26,11 +41,186 @@ pub async fn delete_account(
     return Err(LemmyErrorType::IncorrectLogin)?;
   }
 
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let cooling_off_days = local_site.account_deletion_cooling_off_days;
+  if cooling_off_days <= 0 {
+    ActivityChannel::submit_activity(
+      SendActivityData::DeleteUser(local_user_view.person),
+      &context,
+    )
+    .await?;
+    return Ok(Json(DeleteAccountResponse {
+      deletion_scheduled_at: None,
+    }));
+  }
+
+  // Without an email the immediate deletion couldn't be confirmed
+  let delete_immediately = data.delete_immediately.unwrap_or(false);
+  if delete_immediately && local_user_view.local_user.email.is_none() {
+    return Err(LemmyErrorType::EmailRequired)?;
+  }
+
+  let scheduled_at = naive_now() + Duration::days(cooling_off_days.into());
+  LocalUser::schedule_deletion(
+    &mut context.pool(),
+    local_user_view.local_user.id,
+    scheduled_at,
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntUpdateUser)?;
+
+  // The account is deactivated already, so a failed email only loses the links
+  if let Some(email) = &local_user_view.local_user.email {
+    send_deletion_email(
+      email,
+      &local_user_view,
+      scheduled_at,
+      delete_immediately,
+      &context,
+    )
+    .await
+    .map_err(|e| warn!("Failed to send account deletion email: {e}"))
+    .ok();
+  }
+
+  Ok(Json(DeleteAccountResponse {
+    deletion_scheduled_at: Some(scheduled_at),
+  }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn cancel_account_deletion(
+  data: Json<CancelAccountDeletion>,
+  context: Data<LemmyContext>,
+) -> Result<Json<DeleteAccountResponse>, LemmyError> {
+  let local_user_view = if let Some(token) = &data.token {
+    local_user_from_deletion_token(token, AccountDeletionAction::Cancel, &context).await?
+  } else {
+    local_user_from_password(&data, &context).await?
+  };
+  LocalUser::cancel_deletion(&mut context.pool(), local_user_view.local_user.id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdateUser)?;
+
+  Ok(Json(DeleteAccountResponse {
+    deletion_scheduled_at: None,
+  }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn confirm_account_deletion(
+  data: Json<ConfirmAccountDeletion>,
+  context: Data<LemmyContext>,
+) -> Result<Json<DeleteAccountResponse>, LemmyError> {
+  let local_user_view =
+    local_user_from_deletion_token(&data.token, AccountDeletionAction::DeleteNow, &context).await?;
+
+  // The schedule is cleared once the account is deleted
   ActivityChannel::submit_activity(
     SendActivityData::DeleteUser(local_user_view.person),
     &context,
   )
   .await?;
 
-  Ok(Json(DeleteAccountResponse {}))
+  Ok(Json(DeleteAccountResponse {
+    deletion_scheduled_at: None,
+  }))
+}
+
+/// Accounts with an email have to cancel through the emailed link, so that a stolen password
+/// isn't enough to keep a compromised account around.
+async fn local_user_from_password(
+  data: &CancelAccountDeletion,
+  context: &LemmyContext,
+) -> Result<LocalUserView, LemmyError> {
+  let (Some(username), Some(password)) = (&data.username, &data.password) else {
+    return Err(LemmyErrorType::IncorrectLogin)?;
+  };
+  let local_user_view = LocalUserView::find_by_email_or_name(&mut context.pool(), username)
+    .await
+    .with_lemmy_type(LemmyErrorType::IncorrectLogin)?;
+  let valid: bool =
+    verify(password, &local_user_view.local_user.password_encrypted).unwrap_or(false);
+  if !valid {
+    return Err(LemmyErrorType::IncorrectLogin)?;
+  }
+  if local_user_view.local_user.email.is_some()
+    || local_user_view.local_user.deletion_scheduled_at.is_none()
+    || local_user_view.person.deleted
+  {
+    return Err(LemmyErrorType::InvalidAccountDeletionLink)?;
+  }
+  Ok(local_user_view)
+}
+
+/// Checks that the token was signed for this action and for the deletion which is currently
+/// scheduled, so that links of a cancelled deletion don't work anymore.
+async fn local_user_from_deletion_token(
+  token: &str,
+  action: AccountDeletionAction,
+  context: &LemmyContext,
+) -> Result<LocalUserView, LemmyError> {
+  let claims = AccountDeletionClaims::decode(token, &context.secret().jwt_secret)
+    .with_lemmy_type(LemmyErrorType::InvalidAccountDeletionLink)?;
+  let local_user_view = LocalUserView::read(&mut context.pool(), LocalUserId(claims.sub))
+    .await
+    .with_lemmy_type(LemmyErrorType::InvalidAccountDeletionLink)?;
+  let scheduled = local_user_view
+    .local_user
+    .deletion_scheduled_at
+    .map(|s| s.timestamp());
+  if claims.action != action
+    || scheduled != Some(claims.scheduled)
+    || local_user_view.person.deleted
+  {
+    return Err(LemmyErrorType::InvalidAccountDeletionLink)?;
+  }
+  Ok(local_user_view)
+}
+
+async fn send_deletion_email(
+  email: &str,
+  local_user_view: &LocalUserView,
+  scheduled_at: chrono::NaiveDateTime,
+  delete_immediately: bool,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let settings = context.settings();
+  let link = |action: AccountDeletionAction, path: &str| -> Result<String, LemmyError> {
+    let token = AccountDeletionClaims::jwt(
+      local_user_view.local_user.id.0,
+      action,
+      scheduled_at.timestamp(),
+      &context.secret().jwt_secret,
+      &settings.hostname,
+    )?;
+    Ok(format!(
+      "{}/{path}/{token}",
+      settings.get_protocol_and_hostname()
+    ))
+  };
+
+  let cancel_link = link(AccountDeletionAction::Cancel, "cancel_account_deletion")?;
+  let mut body = format!(
+    "<h1>Account deletion</h1><p>Your account {} will be deleted on {} UTC. Until then you \
+     can't log in, and your posts and comments are hidden.</p><p>If you didn't ask for this, \
+     or changed your mind, <a href=\"{cancel_link}\">cancel the deletion</a>.</p>",
+    local_user_view.person.name,
+    scheduled_at.format("%Y-%m-%d %H:%M"),
+  );
+  if delete_immediately {
+    let confirm_link = link(AccountDeletionAction::DeleteNow, "confirm_account_deletion")?;
+    body.push_str(&format!(
+      "<p>To delete your account right away instead, <a href=\"{confirm_link}\">confirm the \
+       deletion</a>. This can't be undone.</p>"
+    ));
+  }
+  send_email(
+    &format!("Your account on {} will be deleted", settings.hostname),
+    email,
+    &local_user_view.person.name,
+    &body,
+    settings,
+  )
+  .await
 }
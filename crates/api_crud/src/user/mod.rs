@@ -1,2 +1,3 @@
+pub mod bulk_deny;
 pub mod create;
 pub mod delete;
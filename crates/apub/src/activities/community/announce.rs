@@ -1,6 +1,7 @@
 use crate::{
   activities::{
     generate_activity_id,
+    remove_nsfw_refusing_inboxes,
     send_lemmy_activity,
     verify_is_public,
     verify_person_in_community,
@@ -94,12 +95,15 @@ impl AnnounceActivity {
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
     let announce = AnnounceActivity::new(object.clone(), community, context)?;
-    let inboxes = community.get_follower_inboxes(context).await?;
+    let object_parsed: AnnouncableActivities = object.try_into()?;
+    let mut inboxes = community.get_follower_inboxes(context).await?;
+    if object_parsed.is_nsfw() {
+      remove_nsfw_refusing_inboxes(&mut inboxes, context).await?;
+    }
     send_lemmy_activity(context, announce, community, inboxes.clone(), false).await?;
 
     // Pleroma and Mastodon can't handle activities like Announce/Create/Page. So for
     // compatibility, we also send Announce/Page so that they can follow Lemmy communities.
-    let object_parsed = object.try_into()?;
     if let AnnouncableActivities::CreateOrUpdatePost(c) = object_parsed {
       // Hack: need to convert Page into a format which can be sent as activity, which requires
       //       adding actor field.
@@ -136,7 +140,7 @@ impl ActivityHandler for AnnounceActivity {
 
   #[tracing::instrument(skip_all)]
   async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
-    insert_received_activity(&self.id, context).await?;
+    insert_received_activity(self, context).await?;
     verify_is_public(&self.to, &self.cc)?;
     Ok(())
   }
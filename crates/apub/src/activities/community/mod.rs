@@ -1,5 +1,5 @@
 use crate::{
-  activities::send_lemmy_activity,
+  activities::{remove_nsfw_refusing_inboxes, send_lemmy_activity},
   activity_lists::AnnouncableActivities,
   objects::{community::ApubCommunity, person::ApubPerson},
   protocol::activities::community::announce::AnnounceActivity,
@@ -59,6 +59,9 @@ pub(crate) async fn send_activity_in_community(
     inboxes.push(community.shared_inbox_or_inbox());
   }
 
+  if activity.is_nsfw() {
+    remove_nsfw_refusing_inboxes(&mut inboxes, context).await?;
+  }
   send_lemmy_activity(context, activity.clone(), actor, inboxes, false).await?;
   Ok(())
 }
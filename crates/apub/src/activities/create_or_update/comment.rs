@@ -4,11 +4,12 @@ use crate::{
     community::send_activity_in_community,
     generate_activity_id,
     verify_is_public,
+    verify_mod_action,
     verify_person_in_community,
   },
   activity_lists::AnnouncableActivities,
   insert_received_activity,
-  mentions::MentionOrValue,
+  mentions::{fetch_community_mentions, MentionOrValue},
   objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson},
   protocol::{
     activities::{create_or_update::note::CreateOrUpdateNote, CreateOrUpdateType},
@@ -33,12 +34,14 @@ use lemmy_db_schema::{
   source::{
     comment::{Comment, CommentLike, CommentLikeForm},
     community::Community,
+    moderator::{ModLockComment, ModLockCommentForm},
     person::Person,
     post::Post,
   },
   traits::{Crud, Likeable},
 };
 use lemmy_utils::{error::LemmyError, utils::mention::scrape_text_for_mentions};
+use tracing::warn;
 use url::Url;
 
 impl CreateOrUpdateNote {
@@ -58,6 +61,12 @@ impl CreateOrUpdateNote {
       .await?
       .into();
 
+    // Mentions of unknown communities don't keep the comment from federating
+    fetch_community_mentions(&comment.content, None, Some(comment.id), &context)
+      .await
+      .map_err(|e| warn!("Failed to fetch mentioned communities: {e}"))
+      .ok();
+
     let id = generate_activity_id(
       kind.clone(),
       &context.settings().get_protocol_and_hostname(),
@@ -114,13 +123,19 @@ impl ActivityHandler for CreateOrUpdateNote {
 
   #[tracing::instrument(skip_all)]
   async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
-    insert_received_activity(&self.id, context).await?;
+    insert_received_activity(self, context).await?;
     verify_is_public(&self.to, &self.cc)?;
     let post = self.object.get_parents(context).await?.0;
     let community = self.community(context).await?;
 
     verify_person_in_community(&self.actor, &community, context).await?;
-    verify_domains_match(self.actor.inner(), self.object.id.inner())?;
+    let is_mod_action =
+      self.kind == CreateOrUpdateType::Update && self.object.is_mod_action(context).await?;
+    if is_mod_action {
+      verify_mod_action(&self.actor, self.object.id.inner(), community.id, context).await?;
+    } else {
+      verify_domains_match(self.actor.inner(), self.object.id.inner())?;
+    }
     check_community_deleted_or_removed(&community)?;
     check_post_deleted_or_removed(&post)?;
 
@@ -143,8 +158,20 @@ impl ActivityHandler for CreateOrUpdateNote {
       }
     }
 
+    let is_mod_action = self.object.is_mod_action(context).await?;
     let comment = ApubComment::from_json(self.object, context).await?;
 
+    // write mod log entry for lock
+    if is_mod_action {
+      let actor = self.actor.dereference(context).await?;
+      let form = ModLockCommentForm {
+        mod_person_id: actor.id,
+        comment_id: comment.id,
+        locked: comment.locked,
+      };
+      ModLockComment::create(&mut context.pool(), &form).await?;
+    }
+
     // author likes their own comment by default
     let like_form = CommentLikeForm {
       comment_id: comment.id,
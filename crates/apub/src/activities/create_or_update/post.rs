@@ -9,6 +9,7 @@ use crate::{
   },
   activity_lists::AnnouncableActivities,
   insert_received_activity,
+  mentions::fetch_community_mentions,
   objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
   protocol::{
     activities::{create_or_update::page::CreateOrUpdatePage, CreateOrUpdateType},
@@ -33,6 +34,7 @@ use lemmy_db_schema::{
   traits::{Crud, Likeable},
 };
 use lemmy_utils::error::{LemmyError, LemmyErrorType};
+use tracing::warn;
 use url::Url;
 
 impl CreateOrUpdatePage {
@@ -65,6 +67,13 @@ impl CreateOrUpdatePage {
     kind: CreateOrUpdateType,
     context: Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
+    // Mentions of unknown communities don't keep the post from federating
+    if let Some(body) = &post.body {
+      fetch_community_mentions(body, Some(post.id), None, &context)
+        .await
+        .map_err(|e| warn!("Failed to fetch mentioned communities: {e}"))
+        .ok();
+    }
     let post = ApubPost(post);
     let community_id = post.community_id;
     let person: ApubPerson = Person::read(&mut context.pool(), person_id).await?.into();
@@ -104,7 +113,7 @@ impl ActivityHandler for CreateOrUpdatePage {
 
   #[tracing::instrument(skip_all)]
   async fn verify(&self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
-    insert_received_activity(&self.id, context).await?;
+    insert_received_activity(self, context).await?;
     verify_is_public(&self.to, &self.cc)?;
     let community = self.community(context).await?;
     verify_person_in_community(&self.actor, &community, context).await?;
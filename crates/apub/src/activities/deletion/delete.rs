@@ -45,21 +45,14 @@ impl ActivityHandler for Delete {
 
   #[tracing::instrument(skip_all)]
   async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
-    insert_received_activity(&self.id, context).await?;
+    insert_received_activity(self, context).await?;
     verify_delete_activity(self, self.summary.is_some(), context).await?;
     Ok(())
   }
 
   #[tracing::instrument(skip_all)]
   async fn receive(self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
-    if let Some(reason) = self.summary {
-      // We set reason to empty string if it doesn't exist, to distinguish between delete and
-      // remove. Here we change it back to option, so we don't write it to db.
-      let reason = if reason.is_empty() {
-        None
-      } else {
-        Some(reason)
-      };
+    if let Some(reason) = self.removal_reason() {
       receive_remove_action(
         &self.actor.dereference(context).await?,
         self.object.id(),
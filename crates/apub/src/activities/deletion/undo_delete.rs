@@ -8,7 +8,7 @@ use crate::{
   protocol::activities::deletion::{delete::Delete, undo_delete::UndoDelete},
 };
 use activitypub_federation::{config::Data, kinds::activity::UndoType, traits::ActivityHandler};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, utils::sanitize_html_opt};
 use lemmy_db_schema::{
   source::{
     comment::{Comment, CommentUpdateForm},
@@ -42,7 +42,7 @@ impl ActivityHandler for UndoDelete {
   }
 
   async fn verify(&self, data: &Data<Self::DataType>) -> Result<(), Self::Error> {
-    insert_received_activity(&self.id, data).await?;
+    insert_received_activity(self, data).await?;
     self.object.verify(data).await?;
     verify_delete_activity(&self.object, self.object.summary.is_some(), data).await?;
     Ok(())
@@ -50,10 +50,11 @@ impl ActivityHandler for UndoDelete {
 
   #[tracing::instrument(skip_all)]
   async fn receive(self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
-    if self.object.summary.is_some() {
+    if let Some(reason) = self.object.removal_reason() {
       UndoDelete::receive_undo_remove_action(
         &self.actor.dereference(context).await?,
         self.object.object.id(),
+        reason,
         context,
       )
       .await
@@ -95,8 +96,11 @@ impl UndoDelete {
   pub(in crate::activities) async fn receive_undo_remove_action(
     actor: &ApubPerson,
     object: &Url,
+    reason: Option<String>,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
+    let reason = sanitize_html_opt(&reason);
+
     match DeletableObjects::read_from_db(object, context).await? {
       DeletableObjects::Community(community) => {
         if community.local {
@@ -106,7 +110,7 @@ impl UndoDelete {
           mod_person_id: actor.id,
           community_id: community.id,
           removed: Some(false),
-          reason: None,
+          reason: reason.clone(),
           expires: None,
         };
         ModRemoveCommunity::create(&mut context.pool(), &form).await?;
@@ -125,7 +129,7 @@ impl UndoDelete {
           mod_person_id: actor.id,
           post_id: post.id,
           removed: Some(false),
-          reason: None,
+          reason: reason.clone(),
         };
         ModRemovePost::create(&mut context.pool(), &form).await?;
         Post::update(
@@ -143,7 +147,7 @@ impl UndoDelete {
           mod_person_id: actor.id,
           comment_id: comment.id,
           removed: Some(false),
-          reason: None,
+          reason,
         };
         ModRemoveComment::create(&mut context.pool(), &form).await?;
         Comment::update(
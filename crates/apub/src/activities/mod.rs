@@ -15,6 +15,7 @@ use crate::{
       send_apub_delete_private_message,
       DeletableObjects,
     },
+    person::update::send_update_person,
     voting::send_like_activity,
   },
   objects::{community::ApubCommunity, person::ApubPerson},
@@ -34,6 +35,7 @@ use activitypub_federation::{
   traits::{ActivityHandler, Actor},
 };
 use anyhow::anyhow;
+use itertools::Itertools;
 use lemmy_api_common::{
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
@@ -41,10 +43,12 @@ use lemmy_api_common::{
 use lemmy_db_schema::{
   newtypes::CommunityId,
   source::{
-    activity::{SentActivity, SentActivityForm},
+    activity::{SentActivity, SentActivityDelivery, SentActivityDeliveryForm, SentActivityForm},
     community::Community,
     instance::Instance,
+    local_site::LocalSite,
   },
+  DeliveryStatus,
 };
 use lemmy_db_views_actor::structs::{CommunityPersonBanView, CommunityView};
 use lemmy_utils::{
@@ -55,6 +59,7 @@ use lemmy_utils::{
 use moka::future::Cache;
 use once_cell::sync::Lazy;
 use serde::Serialize;
+use serde_json::Value;
 use std::{ops::Deref, sync::Arc, time::Duration};
 use tracing::info;
 use url::{ParseError, Url};
@@ -65,6 +70,8 @@ pub mod community;
 pub mod create_or_update;
 pub mod deletion;
 pub mod following;
+pub mod person;
+pub mod retry;
 pub mod unfederated;
 pub mod voting;
 
@@ -192,7 +199,7 @@ async fn send_lemmy_activity<Activity, ActorT>(
   data: &Data<LemmyContext>,
   activity: Activity,
   actor: &ActorT,
-  mut inbox: Vec<Url>,
+  inbox: Vec<Url>,
   sensitive: bool,
 ) -> Result<(), LemmyError>
 where
@@ -212,24 +219,73 @@ where
     })
     .await?;
 
-  inbox.retain(|i| {
+  let (inbox, skipped_inboxes): (Vec<Url>, Vec<Url>) = inbox.into_iter().partition(|i| {
     let domain = i.domain().expect("has domain").to_string();
     !dead_instances.contains(&domain)
   });
   info!("Sending activity {}", activity.id().to_string());
   let activity = WithContext::new(activity, CONTEXT.deref().clone());
 
+  let activity_data = serde_json::to_value(activity.clone())?;
   let form = SentActivityForm {
     ap_id: activity.id().clone().into(),
-    data: serde_json::to_value(activity.clone())?,
+    object_id: activity_object_id(&activity_data).map(Into::into),
+    data: activity_data,
     sensitive,
   };
-  SentActivity::create(&mut data.pool(), form).await?;
+  let sent_activity = SentActivity::create(&mut data.pool(), form).await?;
+  if data.settings().activity_delivery_retention_days > 0 {
+    let deliveries: Vec<_> = inbox
+      .iter()
+      .chain(&skipped_inboxes)
+      .unique()
+      .map(|i| SentActivityDeliveryForm {
+        sent_activity_id: sent_activity.id,
+        inbox: i.clone().into(),
+        status: if skipped_inboxes.contains(i) {
+          DeliveryStatus::SkippedDeadInstance
+        } else {
+          DeliveryStatus::Queued
+        },
+      })
+      .collect();
+    SentActivityDelivery::create_many(&mut data.pool(), &deliveries).await?;
+  }
+  retry::queue_for_retry(data, &sent_activity.data, actor.id(), &skipped_inboxes).await?;
   send_activity(activity, actor, inbox, data).await?;
 
   Ok(())
 }
 
+/// Returns the id of the object which an activity refers to. For activities which wrap another
+/// activity, like `Announce`, this is the object of the innermost activity.
+pub(crate) fn activity_object_id(activity: &Value) -> Option<Url> {
+  match activity.get("object")? {
+    Value::String(id) => Url::parse(id).ok(),
+    object @ Value::Object(fields) if fields.contains_key("object") => activity_object_id(object),
+    Value::Object(fields) => Url::parse(fields.get("id")?.as_str()?).ok(),
+    _ => None,
+  }
+}
+
+/// Removes the inboxes of instances which declared that they refuse NSFW content, unless the local
+/// site is configured to federate NSFW content to them anyway.
+pub(crate) async fn remove_nsfw_refusing_inboxes(
+  inboxes: &mut Vec<Url>,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  if local_site.federate_nsfw_outbound {
+    return Ok(());
+  }
+  let refusing_nsfw = Instance::refusing_nsfw(&mut context.pool()).await?;
+  inboxes.retain(|i| {
+    let domain = i.domain().expect("has domain").to_string();
+    !refusing_nsfw.contains(&domain)
+  });
+  Ok(())
+}
+
 pub async fn handle_outgoing_activities(context: Data<LemmyContext>) -> LemmyResult<()> {
   while let Some(data) = ActivityChannel::retrieve_activity().await {
     match_outgoing_activities(data, &context.reset_request_count()).await?
@@ -293,9 +349,14 @@ pub async fn match_outgoing_activities(
       RemoveComment(comment, actor, community, reason) => {
         let is_removed = comment.removed;
         let deletable = DeletableObjects::Comment(comment.into());
+        // An empty reason still marks this as a mod action
+        let reason = reason.or_else(|| Some(String::new()));
         send_apub_delete_in_community(actor, community, deletable, reason, is_removed, &context)
           .await
       }
+      LockComment(comment, actor) => {
+        CreateOrUpdateNote::send(comment, actor.id, CreateOrUpdateType::Update, context).await
+      }
       LikePostOrComment(object_id, person, community, score) => {
         send_like_activity(object_id, person, community, score, context).await
       }
@@ -335,6 +396,7 @@ pub async fn match_outgoing_activities(
       DeletePrivateMessage(person, pm, deleted) => {
         send_apub_delete_private_message(&person.into(), pm, deleted, context).await
       }
+      UpdatePerson(person) => send_update_person(person, context).await,
       DeleteUser(person) => delete_user(person, context).await,
       CreateReport(url, actor, community, reason) => {
         Report::send(ObjectId::from(url), actor, community, reason, context).await
@@ -348,3 +410,31 @@ pub async fn match_outgoing_activities(
   }
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::activity_object_id;
+  use serde_json::json;
+  use url::Url;
+
+  #[test]
+  fn test_activity_object_id() {
+    let post_id = Url::parse("https://example.com/post/1").unwrap();
+    let vote = json!({"type": "Like", "object": "https://example.com/post/1"});
+    assert_eq!(Some(post_id.clone()), activity_object_id(&vote));
+
+    let announce = json!({
+      "type": "Announce",
+      "object": {
+        "type": "Create",
+        "object": {"type": "Page", "id": "https://example.com/post/1"}
+      }
+    });
+    assert_eq!(Some(post_id), activity_object_id(&announce));
+
+    assert_eq!(None, activity_object_id(&json!({"type": "Accept"})));
+  }
+}
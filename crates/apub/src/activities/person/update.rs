@@ -0,0 +1,82 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity, verify_is_public, verify_person},
+  insert_received_activity,
+  objects::{instance::remote_instance_inboxes, person::ApubPerson},
+  protocol::activities::person::update::UpdatePerson,
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::{activity::UpdateType, public},
+  protocol::verification::verify_urls_match,
+  traits::{ActivityHandler, Actor, Object},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::person::Person;
+use lemmy_utils::error::LemmyError;
+use url::Url;
+
+/// Sends the current profile of the person to all known instances. Only the home instance of a
+/// person can do this, changes to remote profiles are overwritten once they are fetched again.
+pub(crate) async fn send_update_person(
+  person: Person,
+  context: Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  if !person.local {
+    return Ok(());
+  }
+  let actor: ApubPerson = person.into();
+  let id = generate_activity_id(
+    UpdateType::Update,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let update = UpdatePerson {
+    actor: actor.id().into(),
+    to: vec![public()],
+    object: Box::new(actor.clone().into_json(&context).await?),
+    cc: vec![],
+    kind: UpdateType::Update,
+    id,
+  };
+
+  let inboxes = remote_instance_inboxes(&mut context.pool()).await?;
+  send_lemmy_activity(&context, update, &actor, inboxes, false).await
+}
+
+/// Like DeleteUser, this is received in the site inbox, as it isn't addressed to any community.
+#[async_trait::async_trait]
+impl ActivityHandler for UpdatePerson {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
+    insert_received_activity(self, context).await?;
+    verify_is_public(&self.to, &self.cc)?;
+    verify_person(&self.actor, context).await?;
+    verify_urls_match(self.actor.inner(), self.object.id.inner())?;
+    ApubPerson::verify(&self.object, self.actor.inner(), context).await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
+    // Updates may arrive out of order, a newer profile must not be overwritten by an older one
+    let existing = ApubPerson::read_from_id(self.object.id.inner().clone(), context).await?;
+    if let (Some(existing), Some(updated)) = (existing.and_then(|p| p.updated), self.object.updated)
+    {
+      if updated.naive_local() <= existing {
+        return Ok(());
+      }
+    }
+    ApubPerson::from_json(*self.object, context).await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,174 @@
+use crate::fetcher::user_or_community::UserOrCommunity;
+use activitypub_federation::{
+  activity_queue::send_activity,
+  config::Data,
+  fetch::object_id::ObjectId,
+  traits::ActivityHandler,
+};
+use anyhow::anyhow;
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::{
+  activity::SentActivityDelivery,
+  federation_retry::{FederationRetry, FederationRetryForm},
+  instance::Instance,
+};
+use lemmy_utils::error::{LemmyError, LemmyResult};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::time::Duration;
+use tracing::{info, warn};
+use url::Url;
+
+/// How often the retry queue is checked for deliveries which are due.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// The number of deliveries which are retried at once.
+const RETRY_BATCH_SIZE: i64 = 1000;
+
+/// Stores the deliveries for inboxes of unreachable instances, so that they can be sent once the
+/// instance is back.
+pub(crate) async fn queue_for_retry(
+  context: &Data<LemmyContext>,
+  activity: &Value,
+  actor_id: Url,
+  inboxes: &[Url],
+) -> LemmyResult<()> {
+  let mut forms = vec![];
+  for inbox in inboxes {
+    let domain = inbox.domain().expect("has domain").to_string();
+    let instance = Instance::read_or_create(&mut context.pool(), domain).await?;
+    forms.push(FederationRetryForm {
+      instance_id: instance.id,
+      inbox: inbox.clone().into(),
+      actor_id: actor_id.clone().into(),
+      activity: activity.clone(),
+    });
+  }
+  if !forms.is_empty() {
+    FederationRetry::create_many(&mut context.pool(), &forms).await?;
+  }
+  Ok(())
+}
+
+/// Periodically hands queued deliveries over to the activity queue again once their instance is
+/// reachable, and drops those which are too old.
+pub async fn retry_failed_deliveries(context: Data<LemmyContext>) {
+  let mut interval = tokio::time::interval(RETRY_INTERVAL);
+  loop {
+    interval.tick().await;
+    retry_due_deliveries(&context)
+      .await
+      .map_err(|e| warn!("Failed to retry queued deliveries: {e}"))
+      .ok();
+  }
+}
+
+async fn retry_due_deliveries(context: &Data<LemmyContext>) -> LemmyResult<()> {
+  let dead_instances = Instance::dead_instances(&mut context.pool()).await?;
+  let due = FederationRetry::list_due(&mut context.pool(), RETRY_BATCH_SIZE).await?;
+  let mut resent = 0;
+  for retry in due {
+    let domain = retry.inbox.domain().unwrap_or_default().to_string();
+    if dead_instances.contains(&domain) {
+      FederationRetry::postpone(&mut context.pool(), &retry).await?;
+      continue;
+    }
+    let result = resend(&retry, context).await;
+    record_attempt(&retry, &result, context).await;
+    match result {
+      Ok(()) => {
+        FederationRetry::delete(&mut context.pool(), retry.id).await?;
+        resent += 1;
+      }
+      Err(e) => {
+        warn!("Failed to resend activity to {}: {e}", retry.inbox);
+        FederationRetry::postpone(&mut context.pool(), &retry).await?;
+      }
+    }
+  }
+
+  let max_age_days =
+    i32::try_from(context.settings().federation_retry_max_age_days).unwrap_or(i32::MAX);
+  let dropped = FederationRetry::drop_older_than(&mut context.pool(), max_age_days).await?;
+  if resent > 0 || dropped > 0 {
+    info!("Resent {resent} queued deliveries, dropped {dropped} which were too old");
+  }
+  Ok(())
+}
+
+async fn resend(retry: &FederationRetry, context: &Data<LemmyContext>) -> LemmyResult<()> {
+  let actor: UserOrCommunity = ObjectId::from(retry.actor_id.clone())
+    .dereference_local(context)
+    .await?;
+  let activity = QueuedActivity {
+    id: queued_activity_id(&retry.activity)?,
+    actor: retry.actor_id.clone().into(),
+    data: retry.activity.clone(),
+  };
+  send_activity(activity, &actor, vec![retry.inbox.clone().into()], context).await
+}
+
+/// Stores the outcome of a resend in the delivery status of the activity, if it is still retained.
+async fn record_attempt(
+  retry: &FederationRetry,
+  result: &LemmyResult<()>,
+  context: &Data<LemmyContext>,
+) {
+  let Ok(activity_id) = queued_activity_id(&retry.activity) else {
+    return;
+  };
+  let error = result.as_ref().err().map(ToString::to_string);
+  SentActivityDelivery::record_attempt(
+    &mut context.pool(),
+    &activity_id.into(),
+    &retry.inbox,
+    error,
+  )
+  .await
+  .map_err(|e| warn!("Failed to record delivery attempt: {e}"))
+  .ok();
+}
+
+fn queued_activity_id(activity: &Value) -> LemmyResult<Url> {
+  let id = activity
+    .get("id")
+    .and_then(Value::as_str)
+    .ok_or_else(|| anyhow!("Queued activity has no id"))?;
+  Ok(Url::parse(id)?)
+}
+
+/// An activity which was already serialized when it was first sent. It is only used for sending,
+/// never received.
+struct QueuedActivity {
+  id: Url,
+  actor: Url,
+  data: Value,
+}
+
+impl Serialize for QueuedActivity {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.data.serialize(serializer)
+  }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for QueuedActivity {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    &self.actor
+  }
+
+  async fn verify(&self, _data: &Data<Self::DataType>) -> Result<(), Self::Error> {
+    Err(anyhow!("Queued activities can't be received").into())
+  }
+
+  async fn receive(self, _data: &Data<Self::DataType>) -> Result<(), Self::Error> {
+    Err(anyhow!("Queued activities can't be received").into())
+  }
+}
@@ -44,7 +44,6 @@ use lemmy_api_common::{
     GetReportCountResponse,
     GetUnreadCount,
     GetUnreadCountResponse,
-    Login,
     LoginResponse,
     MarkAllAsRead,
     MarkCommentReplyAsRead,
@@ -54,7 +53,6 @@ use lemmy_api_common::{
     PasswordResetResponse,
     PersonMentionResponse,
     Register,
-    SaveUserSettings,
     VerifyEmail,
     VerifyEmailResponse,
   },
@@ -143,10 +141,6 @@ impl SendActivity for GetComment {
   type Response = CommentResponse;
 }
 
-impl SendActivity for Login {
-  type Response = LoginResponse;
-}
-
 impl SendActivity for GetCaptcha {
   type Response = GetCaptchaResponse;
 }
@@ -203,10 +197,6 @@ impl SendActivity for PasswordChangeAfterReset {
   type Response = LoginResponse;
 }
 
-impl SendActivity for SaveUserSettings {
-  type Response = LoginResponse;
-}
-
 impl SendActivity for ChangePassword {
   type Response = LoginResponse;
 }
@@ -18,6 +18,7 @@ use crate::{
       },
       deletion::{delete::Delete, delete_user::DeleteUser, undo_delete::UndoDelete},
       following::{accept::AcceptFollow, follow::Follow, undo_follow::UndoFollow},
+      person::update::UpdatePerson,
       voting::{undo_vote::UndoVote, vote::Vote},
     },
     objects::page::Page,
@@ -106,6 +107,7 @@ pub enum SiteInboxActivities {
   BlockUser(BlockUser),
   UndoBlockUser(UndoBlockUser),
   DeleteUser(DeleteUser),
+  UpdatePerson(UpdatePerson),
 }
 
 #[async_trait::async_trait]
@@ -132,6 +134,20 @@ impl InCommunity for AnnouncableActivities {
   }
 }
 
+impl AnnouncableActivities {
+  /// Whether the activity contains a post, comment or community which is marked as NSFW.
+  pub(crate) fn is_nsfw(&self) -> bool {
+    use AnnouncableActivities::*;
+    match self {
+      CreateOrUpdateComment(a) => a.object.sensitive.unwrap_or(false),
+      CreateOrUpdatePost(a) => a.object.sensitive.unwrap_or(false),
+      UpdateCommunity(a) => a.object.sensitive.unwrap_or(false),
+      Page(p) => p.sensitive.unwrap_or(false),
+      _ => false,
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
@@ -173,5 +189,9 @@ mod tests {
       "assets/lemmy/activities/deletion/delete_user.json",
     )
     .unwrap();
+    test_parse_lemmy_item::<SiteInboxActivities>(
+      "assets/lemmy/activities/person/update_person.json",
+    )
+    .unwrap();
   }
 }
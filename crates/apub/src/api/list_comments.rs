@@ -1,30 +1,43 @@
 use crate::{
-  api::listing_type_with_default,
+  api::{comment_sort_with_default, listing_type_with_default},
   fetcher::resolve_actor_identifier,
   objects::community::ApubCommunity,
 };
 use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
+use itertools::Itertools;
 use lemmy_api_common::{
   comment::{GetComments, GetCommentsResponse},
   context::LemmyContext,
+  field_selection::{FieldSelection, SelectFields},
+  image_proxy::proxy_comment_view_images,
+  permissions::{CommunityMembership, Permissions},
   utils::{check_private_instance, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::{
-  source::{comment::Comment, community::Community, local_site::LocalSite},
+  newtypes::{CommentId, PersonId},
+  source::{
+    comment::Comment,
+    community::Community,
+    local_site::LocalSite,
+    person::Person,
+    person_block::PersonBlock,
+  },
   traits::Crud,
 };
-use lemmy_db_views::comment_view::CommentQuery;
-use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+use lemmy_db_views::{comment_view::CommentQuery, structs::CommentView};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 
 #[tracing::instrument(skip(context))]
 pub async fn list_comments(
   data: Query<GetComments>,
   context: Data<LemmyContext>,
-) -> Result<Json<GetCommentsResponse>, LemmyError> {
+) -> Result<Json<SelectFields<GetCommentsResponse>>, LemmyError> {
   let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), &context).await;
   let local_site = LocalSite::read(&mut context.pool()).await?;
   check_private_instance(&local_user_view, &local_site)?;
+  let selection = FieldSelection::new::<CommentView>(data.fields.as_deref())?;
 
   let community_id = if let Some(name) = &data.community_name {
     Some(resolve_actor_identifier::<ApubCommunity, Community>(name, &context, &None, true).await?)
@@ -32,12 +45,14 @@ pub async fn list_comments(
   } else {
     data.community_id
   };
-  let sort = data.sort;
+  let sort = comment_sort_with_default(data.sort, community_id, data.post_id, &context).await?;
   let max_depth = data.max_depth;
   let saved_only = data.saved_only.unwrap_or_default();
+  let saved_tag = data.saved_tag.clone();
 
   let liked_only = data.liked_only.unwrap_or_default();
   let disliked_only = data.disliked_only.unwrap_or_default();
+  let ignore_score_filter = data.ignore_score_filter.unwrap_or_default();
   if liked_only && disliked_only {
     return Err(LemmyError::from(LemmyErrorType::ContradictingFilters));
   }
@@ -61,24 +76,123 @@ pub async fn list_comments(
 
   let parent_path_cloned = parent_path.clone();
   let post_id = data.post_id;
-  let comments = CommentQuery {
+  let comment_query = CommentQuery {
     listing_type,
     sort,
     max_depth,
     saved_only,
+    saved_tag,
     liked_only,
     disliked_only,
     community_id,
     parent_path: parent_path_cloned,
     post_id,
     local_user: local_user_view.as_ref(),
+    ignore_score_filter,
     page,
     limit,
     ..Default::default()
+  };
+
+  // Paginate over the top-level branches instead, if asked to
+  let (mut comments, next_page) =
+    if data.page_by_branches.unwrap_or_default() || data.page_cursor.is_some() {
+      CommentQuery {
+        branch_cursor: data.page_cursor.clone(),
+        ..comment_query
+      }
+      .list_branches(&mut context.pool())
+      .await
+    } else {
+      comment_query
+        .list(&mut context.pool())
+        .await
+        .map(|comments| (comments, None))
+    }
+    .with_lemmy_type(LemmyErrorType::CouldntGetComments)?;
+
+  if local_site.proxy_remote_images {
+    for comment_view in &mut comments {
+      proxy_comment_view_images(comment_view, &context);
+    }
   }
-  .list(&mut context.pool())
-  .await
-  .with_lemmy_type(LemmyErrorType::CouldntGetComments)?;
 
-  Ok(Json(GetCommentsResponse { comments }))
+  let comment_permissions = match &local_user_view {
+    Some(local_user_view) if data.include_permissions.unwrap_or_default() => {
+      Some(comments_permissions(&comments, &local_user_view.person, &context).await?)
+    }
+    _ => None,
+  };
+
+  let response = GetCommentsResponse {
+    comments,
+    next_page,
+    comment_permissions,
+  };
+  Ok(Json(SelectFields::new(response, selection)))
+}
+
+/// The membership in each community and the blocks of the comment creators are read once for the
+/// whole page. Whether a thread is locked is taken from the ancestors in the listing, and only read
+/// for comments whose ancestors aren't all listed.
+async fn comments_permissions(
+  comments: &[CommentView],
+  person: &Person,
+  context: &LemmyContext,
+) -> LemmyResult<HashMap<CommentId, Permissions>> {
+  let creator_ids = comments
+    .iter()
+    .map(|c| c.creator.id)
+    .unique()
+    .collect::<Vec<_>>();
+  let blockers: HashSet<PersonId> =
+    PersonBlock::list_blockers(&mut context.pool(), person.id, &creator_ids)
+      .await?
+      .into_iter()
+      .collect();
+  let listed_locked: HashMap<CommentId, bool> = comments
+    .iter()
+    .map(|c| (c.comment.id, c.comment.locked))
+    .collect();
+
+  let mut memberships = HashMap::new();
+  let mut permissions = HashMap::with_capacity(comments.len());
+  for comment_view in comments {
+    let community = &comment_view.community;
+    let membership = match memberships.entry(community.id) {
+      Entry::Occupied(e) => *e.get(),
+      Entry::Vacant(e) => {
+        *e.insert(CommunityMembership::read(&mut context.pool(), person.id, community).await?)
+      }
+    };
+    let path = &comment_view.comment.path;
+    let thread_locked = match thread_locked_in_listing(&path.0, &listed_locked) {
+      Some(thread_locked) => thread_locked,
+      None => Comment::is_thread_locked(&mut context.pool(), path).await?,
+    };
+    let blocked = blockers.contains(&comment_view.creator.id);
+    let comment_permissions =
+      Permissions::for_comment(person, membership, comment_view, blocked, thread_locked);
+    permissions.insert(comment_view.comment.id, comment_permissions);
+  }
+  Ok(permissions)
+}
+
+/// Whether the comment or one of its ancestors is locked, if this can be told from the listed
+/// comments alone.
+fn thread_locked_in_listing(path: &str, listed_locked: &HashMap<CommentId, bool>) -> Option<bool> {
+  let mut all_listed = true;
+  // The first part of the path is always 0
+  for id in path.split('.').skip(1) {
+    let locked = id
+      .parse()
+      .ok()
+      .and_then(|id| listed_locked.get(&CommentId(id)));
+    match locked {
+      Some(true) => return Some(true),
+      Some(false) => {}
+      None => all_listed = false,
+    }
+  }
+  all_listed.then_some(false)
 }
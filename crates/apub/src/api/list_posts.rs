@@ -1,5 +1,5 @@
 use crate::{
-  api::listing_type_with_default,
+  api::{listing_type_with_default, post_sort_with_default},
   fetcher::resolve_actor_identifier,
   objects::community::ApubCommunity,
 };
@@ -7,24 +7,25 @@ use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  field_selection::{FieldSelection, SelectFields},
+  image_proxy::proxy_post_view_images,
   post::{GetPosts, GetPostsResponse},
   utils::{check_private_instance, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::source::{community::Community, local_site::LocalSite};
-use lemmy_db_views::post_view::PostQuery;
+use lemmy_db_views::{post_view::PostQuery, structs::PostView};
 use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
 
 #[tracing::instrument(skip(context))]
 pub async fn list_posts(
   data: Query<GetPosts>,
   context: Data<LemmyContext>,
-) -> Result<Json<GetPostsResponse>, LemmyError> {
+) -> Result<Json<SelectFields<GetPostsResponse>>, LemmyError> {
   let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), &context).await;
   let local_site = LocalSite::read(&mut context.pool()).await?;
 
   check_private_instance(&local_user_view, &local_site)?;
-
-  let sort = data.sort;
+  let selection = FieldSelection::new::<PostView>(data.fields.as_deref())?;
 
   let page = data.page;
   let limit = data.limit;
@@ -34,7 +35,9 @@ pub async fn list_posts(
   } else {
     data.community_id
   };
+  let sort = post_sort_with_default(data.sort, &local_user_view, community_id, &context).await?;
   let saved_only = data.saved_only.unwrap_or_default();
+  let saved_tag = data.saved_tag.clone();
 
   let liked_only = data.liked_only.unwrap_or_default();
   let disliked_only = data.disliked_only.unwrap_or_default();
@@ -43,6 +46,8 @@ pub async fn list_posts(
   }
 
   let moderator_view = data.moderator_view.unwrap_or_default();
+  let ignore_score_filter = data.ignore_score_filter.unwrap_or_default();
+  let full_body = data.full_body.unwrap_or_default();
 
   let listing_type = Some(listing_type_with_default(
     data.type_,
@@ -50,15 +55,18 @@ pub async fn list_posts(
     community_id,
   )?);
 
-  let posts = PostQuery {
+  let mut posts = PostQuery {
     local_user: local_user_view.as_ref(),
     listing_type,
     sort,
     community_id,
     saved_only,
+    saved_tag,
     liked_only,
     disliked_only,
     moderator_view,
+    ignore_score_filter,
+    full_body,
     page,
     limit,
     ..Default::default()
@@ -67,5 +75,12 @@ pub async fn list_posts(
   .await
   .with_lemmy_type(LemmyErrorType::CouldntGetPosts)?;
 
-  Ok(Json(GetPostsResponse { posts }))
+  if local_site.proxy_remote_images {
+    for post_view in &mut posts {
+      proxy_post_view_images(post_view, &context);
+    }
+  }
+
+  let response = GetPostsResponse { posts };
+  Ok(Json(SelectFields::new(response, selection)))
 }
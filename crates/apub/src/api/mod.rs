@@ -1,4 +1,13 @@
-use lemmy_db_schema::{newtypes::CommunityId, source::local_site::LocalSite, ListingType};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  newtypes::{CommunityId, PostId},
+  source::{community::Community, local_site::LocalSite, post::Post},
+  traits::Crud,
+  CommentSortType,
+  ListingType,
+  SortType,
+};
+use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::error::LemmyError;
 
 pub mod list_comments;
@@ -6,7 +15,10 @@ pub mod list_posts;
 pub mod read_community;
 pub mod read_person;
 pub mod resolve_object;
+pub mod resync_community;
+pub mod resync_post;
 pub mod search;
+pub mod user_data;
 
 /// Returns default listing type, depending if the query is for frontpage or community.
 fn listing_type_with_default(
@@ -23,3 +35,47 @@ fn listing_type_with_default(
   };
   Ok(listing_type)
 }
+
+/// Returns the post sort from the param, or else the default of the community if the query is for
+/// a single community, or else the default of the user.
+async fn post_sort_with_default(
+  sort: Option<SortType>,
+  local_user_view: &Option<LocalUserView>,
+  community_id: Option<CommunityId>,
+  context: &LemmyContext,
+) -> Result<Option<SortType>, LemmyError> {
+  if let Some(sort) = sort {
+    return Ok(Some(sort));
+  }
+  if let Some(community_id) = community_id {
+    let community = Community::read(&mut context.pool(), community_id).await?;
+    if community.default_post_sort.is_some() {
+      return Ok(community.default_post_sort);
+    }
+  }
+  Ok(
+    local_user_view
+      .as_ref()
+      .map(|l| l.local_user.default_sort_type),
+  )
+}
+
+/// Returns the comment sort from the param, or else the default of the community if the query is
+/// for a single community or post.
+async fn comment_sort_with_default(
+  sort: Option<CommentSortType>,
+  community_id: Option<CommunityId>,
+  post_id: Option<PostId>,
+  context: &LemmyContext,
+) -> Result<Option<CommentSortType>, LemmyError> {
+  if sort.is_some() {
+    return Ok(sort);
+  }
+  let community_id = match (community_id, post_id) {
+    (Some(community_id), _) => community_id,
+    (None, Some(post_id)) => Post::read(&mut context.pool(), post_id).await?.community_id,
+    (None, None) => return Ok(None),
+  };
+  let community = Community::read(&mut context.pool(), community_id).await?;
+  Ok(community.default_comment_sort)
+}
@@ -4,12 +4,14 @@ use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   community::{GetCommunity, GetCommunityResponse},
   context::LemmyContext,
+  permissions::Permissions,
   utils::{check_private_instance, is_mod_or_admin_opt, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::source::{
   actor_language::CommunityLanguage,
   community::Community,
   local_site::LocalSite,
+  mod_reason_template::ModReasonTemplate,
   site::Site,
 };
 use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView};
@@ -75,11 +77,24 @@ pub async fn get_community(
 
   let community_id = community_view.community.id;
   let discussion_languages = CommunityLanguage::read(&mut context.pool(), community_id).await?;
+  let mod_reason_templates = if is_mod_or_admin {
+    Some(ModReasonTemplate::list_for_community(&mut context.pool(), community_id).await?)
+  } else {
+    None
+  };
+  let permissions = Permissions::for_community(
+    &mut context.pool(),
+    local_user_view.as_ref().map(|u| &u.person),
+    &community_view.community,
+  )
+  .await?;
 
   Ok(Json(GetCommunityResponse {
     community_view,
     site,
     moderators,
     discussion_languages,
+    mod_reason_templates,
+    permissions: Some(permissions),
   }))
 }
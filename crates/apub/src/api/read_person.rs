@@ -10,7 +10,7 @@ use lemmy_db_schema::{
   source::{local_site::LocalSite, person::Person},
   utils::post_to_comment_sort_type,
 };
-use lemmy_db_views::{comment_view::CommentQuery, post_view::PostQuery};
+use lemmy_db_views::{comment_view::CommentQuery, post_view::PostQuery, structs::LocalUserView};
 use lemmy_db_views_actor::structs::{CommunityModeratorView, PersonView};
 use lemmy_utils::error::{LemmyError, LemmyErrorExt2, LemmyErrorType};
 
@@ -52,6 +52,7 @@ pub async fn read_person(
   let limit = data.limit;
   let saved_only = data.saved_only.unwrap_or_default();
   let community_id = data.community_id;
+  let full_body = data.full_body.unwrap_or_default();
   // If its saved only, you don't care what creator it was
   // Or, if its not saved, then you only want it for that specific creator
   let creator_id = if !saved_only {
@@ -66,6 +67,7 @@ pub async fn read_person(
     local_user: local_user_view.as_ref(),
     community_id,
     is_profile_view: true,
+    full_body,
     page,
     limit,
     creator_id,
@@ -91,11 +93,23 @@ pub async fn read_person(
   let moderates =
     CommunityModeratorView::for_person(&mut context.pool(), person_details_id).await?;
 
+  // Only local users can schedule the deletion of their account
+  let deletion_scheduled_at = LocalUserView::read_person(&mut context.pool(), person_details_id)
+    .await
+    .ok()
+    .and_then(|v| v.local_user.deletion_scheduled_at);
+  let is_admin = local_user_view
+    .as_ref()
+    .map(|l| l.person.admin)
+    .unwrap_or(false);
+
   // Return the jwt
   Ok(Json(GetPersonDetailsResponse {
     person_view,
     moderates,
     comments,
     posts,
+    deletion_pending: deletion_scheduled_at.is_some(),
+    deletion_scheduled_at: deletion_scheduled_at.filter(|_| is_admin),
   }))
 }
@@ -11,7 +11,11 @@ use lemmy_api_common::{
   site::{ResolveObject, ResolveObjectResponse},
   utils::{check_private_instance, local_user_view_from_jwt_opt},
 };
-use lemmy_db_schema::{newtypes::PersonId, source::local_site::LocalSite, utils::DbPool};
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  source::{local_site::LocalSite, local_user::LocalUser},
+  utils::DbPool,
+};
 use lemmy_db_views::structs::{CommentView, PostView};
 use lemmy_db_views_actor::structs::{CommunityView, PersonView};
 use lemmy_utils::error::{LemmyError, LemmyErrorExt2, LemmyErrorType};
@@ -24,7 +28,7 @@ pub async fn resolve_object(
   let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), &context).await;
   let local_site = LocalSite::read(&mut context.pool()).await?;
   check_private_instance(&local_user_view, &local_site)?;
-  let person_id = local_user_view.map(|v| v.person.id);
+  let person_id = local_user_view.as_ref().map(|v| v.person.id);
   // If we get a valid personId back we can safely assume that the user is authenticated,
   // if there's no personId then the JWT was missing or invalid.
   let is_authenticated = person_id.is_some();
@@ -38,7 +42,8 @@ pub async fn resolve_object(
   }
   .with_lemmy_type(LemmyErrorType::CouldntFindObject)?;
 
-  convert_response(res, person_id, &mut context.pool())
+  let local_user = local_user_view.as_ref().map(|v| &v.local_user);
+  convert_response(res, person_id, local_user, &mut context.pool())
     .await
     .with_lemmy_type(LemmyErrorType::CouldntFindObject)
 }
@@ -46,6 +51,7 @@ pub async fn resolve_object(
 async fn convert_response(
   object: SearchableObjects,
   user_id: Option<PersonId>,
+  local_user: Option<&LocalUser>,
   pool: &mut DbPool<'_>,
 ) -> Result<Json<ResolveObjectResponse>, LemmyError> {
   use SearchableObjects::*;
@@ -66,7 +72,9 @@ async fn convert_response(
     }
     Comment(c) => {
       removed_or_deleted = c.deleted || c.removed;
-      res.comment = Some(CommentView::read(pool, c.id, user_id).await?)
+      let mut comment_view = CommentView::read(pool, c.id, user_id).await?;
+      comment_view.redact_nsfw(local_user);
+      res.comment = Some(comment_view)
     }
   };
   // if the object was deleted from database, dont return it
@@ -0,0 +1,153 @@
+use crate::fetcher::replies::resync_post_comments;
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  community::{CommunityResyncJobResponse, GetCommunityResyncJob, ResyncRemoteCommunity},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::{
+    community::Community,
+    community_resync_job::{CommunityResyncJob, CommunityResyncJobForm},
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  spawn_try_task,
+};
+use tracing::{debug, info};
+
+/// How many posts are read from the database at once.
+const POST_BATCH_SIZE: i64 = 20;
+
+#[tracing::instrument(skip(context))]
+pub async fn resync_remote_community(
+  data: Json<ResyncRemoteCommunity>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityResyncJobResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let community = Community::read(&mut context.pool(), data.community_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
+  is_mod_or_admin(&mut context.pool(), local_user_view.person.id, community.id).await?;
+  if community.local {
+    return Err(LemmyErrorType::CantResyncLocalCommunity)?;
+  }
+  check_no_resync_in_progress(community.id, &context).await?;
+
+  let posts = Post::count_remote_for_resync(&mut context.pool(), community.id).await?;
+  let form = CommunityResyncJobForm {
+    community_id: community.id,
+    creator_id: local_user_view.person.id,
+    posts_total: posts.min(context.settings().resync.max_posts_per_community) as i32,
+    post_id: None,
+  };
+  let job = start_resync(&form, &context).await?;
+  Ok(Json(CommunityResyncJobResponse { job }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn get_community_resync_job(
+  data: Query<GetCommunityResyncJob>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityResyncJobResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let job = CommunityResyncJob::read(&mut context.pool(), data.job_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindResyncJob)?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    job.community_id,
+  )
+  .await?;
+
+  Ok(Json(CommunityResyncJobResponse { job }))
+}
+
+/// Only one resync per community runs at a time, including resyncs of single posts.
+pub(crate) async fn check_no_resync_in_progress(
+  community_id: CommunityId,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  if CommunityResyncJob::read_unfinished_for_community(&mut context.pool(), community_id)
+    .await?
+    .is_some()
+  {
+    return Err(LemmyErrorType::CommunityResyncInProgress)?;
+  }
+  Ok(())
+}
+
+/// Stores the job and runs it in the background.
+pub(crate) async fn start_resync(
+  form: &CommunityResyncJobForm,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<CommunityResyncJob> {
+  let job = CommunityResyncJob::create(&mut context.pool(), form).await?;
+  spawn_try_task(run_community_resync(
+    job.clone(),
+    context.reset_request_count(),
+  ));
+  Ok(job)
+}
+
+/// Resyncs the posts of the community from the newest to the oldest, or only the single post of
+/// the job. The progress is stored after every post, so that the job can be resumed after a
+/// restart. Posts which can't be resynced are counted as failed, and don't stop the job.
+pub async fn run_community_resync(
+  mut job: CommunityResyncJob,
+  context: Data<LemmyContext>,
+) -> LemmyResult<()> {
+  if let Some(post_id) = job.post_id {
+    if job.posts_done < job.posts_total {
+      let post = Post::read(&mut context.pool(), post_id).await?;
+      job = resync_post(&job, &post, &context).await?;
+    }
+  }
+  while job.post_id.is_none() && job.posts_done < job.posts_total {
+    let remaining = i64::from(job.posts_total - job.posts_done);
+    let posts = Post::list_remote_for_resync(
+      &mut context.pool(),
+      job.community_id,
+      job.last_post_id,
+      remaining.min(POST_BATCH_SIZE),
+    )
+    .await?;
+    if posts.is_empty() {
+      break;
+    }
+    for post in posts {
+      job = resync_post(&job, &post, &context).await?;
+    }
+  }
+
+  let job = CommunityResyncJob::finish(&mut context.pool(), job.id).await?;
+  info!(
+    "Finished resyncing community {}: {} comments added, {} of {} posts failed",
+    job.community_id, job.comments_added, job.posts_failed, job.posts_done
+  );
+  Ok(())
+}
+
+async fn resync_post(
+  job: &CommunityResyncJob,
+  post: &Post,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<CommunityResyncJob> {
+  let (failed, comments_added) = match resync_post_comments(post, context).await {
+    Ok(comments_added) => (false, comments_added),
+    Err(e) => {
+      debug!("Couldn't resync post {}: {e}", post.ap_id);
+      (true, 0)
+    }
+  };
+  let job =
+    CommunityResyncJob::advance(&mut context.pool(), job.id, post.id, failed, comments_added)
+      .await?;
+  Ok(job)
+}
@@ -0,0 +1,49 @@
+use crate::api::resync_community::{check_no_resync_in_progress, start_resync};
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::CommunityResyncJobResponse,
+  context::LemmyContext,
+  post::ResyncRemotePost,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{community_resync_job::CommunityResyncJobForm, post::Post},
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType};
+
+/// Starts a resync job which only covers the post, so that the comments are fetched in the
+/// background like for a community resync.
+#[tracing::instrument(skip(context))]
+pub async fn resync_remote_post(
+  data: Json<ResyncRemotePost>,
+  context: Data<LemmyContext>,
+) -> Result<Json<CommunityResyncJobResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let post = Post::read(&mut context.pool(), data.post_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindPost)?;
+  is_mod_or_admin(
+    &mut context.pool(),
+    local_user_view.person.id,
+    post.community_id,
+  )
+  .await?;
+  if post.local {
+    return Err(LemmyErrorType::CantResyncLocalPost)?;
+  }
+  if post.locked {
+    return Err(LemmyErrorType::PostIsLocked)?;
+  }
+  check_no_resync_in_progress(post.community_id, &context).await?;
+
+  let form = CommunityResyncJobForm {
+    community_id: post.community_id,
+    creator_id: local_user_view.person.id,
+    posts_total: 1,
+    post_id: Some(post.id),
+  };
+  let job = start_resync(&form, &context).await?;
+  Ok(Json(CommunityResyncJobResponse { job }))
+}
@@ -3,6 +3,7 @@ use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  field_selection::{FieldSelection, SelectFields, SelectableFields},
   site::{Search, SearchResponse},
   utils::{check_private_instance, is_admin, local_user_view_from_jwt_opt},
 };
@@ -11,20 +12,35 @@ use lemmy_db_schema::{
   utils::{post_to_comment_sort_type, post_to_person_sort_type},
   SearchType,
 };
-use lemmy_db_views::{comment_view::CommentQuery, post_view::PostQuery};
-use lemmy_db_views_actor::{community_view::CommunityQuery, person_view::PersonQuery};
+use lemmy_db_views::{
+  comment_view::CommentQuery,
+  post_view::PostQuery,
+  structs::{CommentView, PostView},
+};
+use lemmy_db_views_actor::{
+  community_view::CommunityQuery,
+  person_view::PersonQuery,
+  structs::{CommunityView, PersonView},
+};
 use lemmy_utils::error::LemmyError;
 
 #[tracing::instrument(skip(context))]
 pub async fn search(
   data: Query<Search>,
   context: Data<LemmyContext>,
-) -> Result<Json<SearchResponse>, LemmyError> {
+) -> Result<Json<SelectFields<SearchResponse>>, LemmyError> {
   let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), &context).await;
   let local_site = LocalSite::read(&mut context.pool()).await?;
 
   check_private_instance(&local_user_view, &local_site)?;
 
+  // The selected fields apply to the results of each type
+  let mut selectable = PostView::selectable_fields();
+  selectable.append(&mut CommentView::selectable_fields());
+  selectable.append(&mut CommunityView::selectable_fields());
+  selectable.append(&mut PersonView::selectable_fields());
+  let selection = FieldSelection::from_valid(data.fields.as_deref(), &selectable)?;
+
   let is_admin = local_user_view
     .as_ref()
     .map(|luv| is_admin(luv).is_ok())
@@ -42,6 +58,8 @@ pub async fn search(
   let limit = data.limit;
   let sort = data.sort;
   let listing_type = data.listing_type;
+  let full_body = data.full_body.unwrap_or_default();
+  let verified_only = data.verified_only.unwrap_or_default();
   let search_type = data.type_.unwrap_or(SearchType::All);
   let community_id = if let Some(name) = &data.community_name {
     Some(
@@ -63,6 +81,7 @@ pub async fn search(
         creator_id: (creator_id),
         local_user: (local_user_view.as_ref()),
         search_term: (Some(q)),
+        full_body,
         page: (page),
         limit: (limit),
         ..Default::default()
@@ -92,6 +111,7 @@ pub async fn search(
         search_term: (Some(q)),
         local_user: (local_user.as_ref()),
         is_mod_or_admin: (is_admin),
+        verified_only,
         page: (page),
         limit: (limit),
         ..Default::default()
@@ -123,6 +143,7 @@ pub async fn search(
         creator_id: (creator_id),
         local_user: (local_user_view.as_ref()),
         search_term: (Some(q)),
+        full_body,
         page: (page),
         limit: (limit),
         ..Default::default()
@@ -157,6 +178,7 @@ pub async fn search(
           search_term: (Some(q)),
           local_user: (local_user.as_ref()),
           is_mod_or_admin: (is_admin),
+          verified_only,
           page: (page),
           limit: (limit),
           ..Default::default()
@@ -187,6 +209,7 @@ pub async fn search(
         community_id: (community_id),
         creator_id: (creator_id),
         url_search: (Some(q)),
+        full_body,
         page: (page),
         limit: (limit),
         ..Default::default()
@@ -196,12 +219,12 @@ pub async fn search(
     }
   };
 
-  // Return the jwt
-  Ok(Json(SearchResponse {
+  let response = SearchResponse {
     type_: search_type,
     comments,
     posts,
     communities,
     users,
-  }))
+  };
+  Ok(Json(SelectFields::new(response, selection)))
 }
@@ -0,0 +1,469 @@
+use crate::objects::{
+  comment::ApubComment,
+  community::ApubCommunity,
+  person::ApubPerson,
+  post::ApubPost,
+};
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{
+    ExportUserData,
+    GetUserDataImportJob,
+    ImportUserData,
+    UserDataExport,
+    UserDataImportJobResponse,
+  },
+  utils::{local_user_view_from_jwt, sanitize_html_opt, update_community_follow},
+};
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{
+    comment::{CommentSaved, CommentSavedForm},
+    community_block::{CommunityBlock, CommunityBlockForm},
+    local_site::LocalSite,
+    local_user::{LocalUser, LocalUserUpdateForm},
+    person::{Person, PersonUpdateForm},
+    person_block::{PersonBlock, PersonBlockForm},
+    person_keyword_block::PersonKeywordBlock,
+    post::{PostSaved, PostSavedForm},
+    user_data_import::{
+      UserDataImportFailure,
+      UserDataImportFailureForm,
+      UserDataImportJob,
+      UserDataImportJobForm,
+    },
+  },
+  traits::{Blockable, Crud, Saveable},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::{CommunityBlockView, CommunityFollowerView, PersonBlockView};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  spawn_try_task,
+  utils::validation::{
+    clean_blocked_keywords,
+    clean_notification_muted_words,
+    is_valid_bio_field,
+    is_valid_display_name,
+    is_valid_matrix_id,
+  },
+};
+use std::ops::Deref;
+use tracing::{debug, info};
+
+/// Maximum number of follows, blocks and saves which can be imported at once. Each of them may
+/// need to be fetched from a remote instance.
+const MAX_IMPORT_ITEMS: usize = 5000;
+
+#[tracing::instrument(skip(context))]
+pub async fn export_user_data(
+  data: Query<ExportUserData>,
+  context: Data<LemmyContext>,
+) -> Result<Json<UserDataExport>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+
+  let followed_communities = CommunityFollowerView::for_person(&mut context.pool(), person_id)
+    .await?
+    .into_iter()
+    .map(|f| f.community.actor_id)
+    .collect();
+  let blocked_communities = CommunityBlockView::for_person(&mut context.pool(), person_id)
+    .await?
+    .into_iter()
+    .map(|b| b.community.actor_id)
+    .collect();
+  let blocked_users = PersonBlockView::for_person(&mut context.pool(), person_id)
+    .await?
+    .into_iter()
+    .map(|b| b.target.actor_id)
+    .collect();
+  let saved_posts = PostSaved::list_ap_ids_for_person(&mut context.pool(), person_id).await?;
+  let saved_comments = CommentSaved::list_ap_ids_for_person(&mut context.pool(), person_id).await?;
+  let blocked_keywords = PersonKeywordBlock::read(&mut context.pool(), person_id).await?;
+
+  let person = local_user_view.person;
+  let local_user = local_user_view.local_user;
+  Ok(Json(UserDataExport {
+    display_name: person.display_name,
+    bio: person.bio,
+    avatar: person.avatar,
+    banner: person.banner,
+    matrix_user_id: person.matrix_user_id,
+    bot_account: Some(person.bot_account),
+    show_nsfw: Some(local_user.show_nsfw),
+    blur_nsfw: Some(local_user.blur_nsfw),
+    auto_expand: Some(local_user.auto_expand),
+    show_scores: Some(local_user.show_scores),
+    theme: Some(local_user.theme),
+    default_sort_type: Some(local_user.default_sort_type),
+    default_listing_type: Some(local_user.default_listing_type),
+    interface_language: Some(local_user.interface_language),
+    show_avatars: Some(local_user.show_avatars),
+    send_notifications_to_email: Some(local_user.send_notifications_to_email),
+    show_bot_accounts: Some(local_user.show_bot_accounts),
+    show_read_posts: Some(local_user.show_read_posts),
+    open_links_in_new_tab: Some(local_user.open_links_in_new_tab),
+    infinite_scroll_enabled: Some(local_user.infinite_scroll_enabled),
+    hide_content_below_score: local_user.hide_content_below_score,
+    exclude_from_leaderboards: Some(local_user.exclude_from_leaderboards),
+    blocked_keywords,
+    notification_muted_words: local_user.notification_muted_words,
+    followed_communities,
+    blocked_communities,
+    blocked_users,
+    saved_posts,
+    saved_comments,
+  }))
+}
+
+/// Imports the settings, then starts a background job which resolves and imports each follow,
+/// block and save. Settings and items which can't be imported are skipped, and returned as
+/// failures of the job.
+#[tracing::instrument(skip(context))]
+pub async fn import_user_data(
+  data: Json<ImportUserData>,
+  context: Data<LemmyContext>,
+) -> Result<Json<UserDataImportJobResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let local_user_id = local_user_view.local_user.id;
+  let import = &data.data;
+
+  let items_total = import_items(import).len();
+  if items_total > MAX_IMPORT_ITEMS {
+    return Err(LemmyErrorType::UserDataImportTooLarge)?;
+  }
+  if UserDataImportJob::read_unfinished_for_user(&mut context.pool(), local_user_id)
+    .await?
+    .is_some()
+  {
+    return Err(LemmyErrorType::UserDataImportInProgress)?;
+  }
+
+  let setting_failures = import_settings(import, &local_user_view, &context).await?;
+  let form = UserDataImportJobForm {
+    local_user_id,
+    data: serde_json::to_value(import)?,
+    items_total: i32::try_from(items_total)?,
+  };
+  let job = UserDataImportJob::create(&mut context.pool(), &form).await?;
+  let failures: Vec<_> = setting_failures
+    .into_iter()
+    .map(|(setting, e)| failure_form(job.id, setting, &e))
+    .collect();
+  UserDataImportFailure::create_many(&mut context.pool(), &failures).await?;
+  spawn_try_task(run_user_data_import(
+    job.clone(),
+    context.reset_request_count(),
+  ));
+
+  let failures = UserDataImportFailure::list_for_job(&mut context.pool(), job.id).await?;
+  Ok(Json(UserDataImportJobResponse { job, failures }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn get_user_data_import_job(
+  data: Query<GetUserDataImportJob>,
+  context: Data<LemmyContext>,
+) -> Result<Json<UserDataImportJobResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let job = UserDataImportJob::read(&mut context.pool(), data.job_id)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntFindUserDataImportJob)?;
+  if job.local_user_id != local_user_view.local_user.id {
+    return Err(LemmyErrorType::CouldntFindUserDataImportJob)?;
+  }
+
+  let failures = UserDataImportFailure::list_for_job(&mut context.pool(), job.id).await?;
+  Ok(Json(UserDataImportJobResponse { job, failures }))
+}
+
+#[derive(Clone, Copy)]
+enum ImportItem {
+  Follow,
+  CommunityBlock,
+  PersonBlock,
+  SavedPost,
+  SavedComment,
+}
+
+/// The follows, blocks and saves of the import, in the order in which they are imported. The
+/// order must not change, as interrupted jobs are resumed after the items which are done.
+fn import_items(import: &UserDataExport) -> Vec<(ImportItem, &DbUrl)> {
+  fn items(kind: ImportItem, urls: &[DbUrl]) -> Vec<(ImportItem, &DbUrl)> {
+    urls.iter().map(|url| (kind, url)).collect()
+  }
+  [
+    items(ImportItem::Follow, &import.followed_communities),
+    items(ImportItem::CommunityBlock, &import.blocked_communities),
+    items(ImportItem::PersonBlock, &import.blocked_users),
+    items(ImportItem::SavedPost, &import.saved_posts),
+    items(ImportItem::SavedComment, &import.saved_comments),
+  ]
+  .concat()
+}
+
+/// Imports the follows, blocks and saves one after another. The progress is stored after every
+/// item, so that the job can be resumed after a restart. Items which can't be resolved or
+/// imported are stored as failures, and don't stop the job.
+pub async fn run_user_data_import(
+  job: UserDataImportJob,
+  context: Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let import: UserDataExport = serde_json::from_value(job.data.clone())?;
+  let local_user_view = LocalUserView::read(&mut context.pool(), job.local_user_id).await?;
+  let done = usize::try_from(job.items_done)?;
+
+  for (kind, url) in import_items(&import).into_iter().skip(done) {
+    let result = match kind {
+      ImportItem::Follow => import_follow(url, &local_user_view, &context).await,
+      ImportItem::CommunityBlock => import_community_block(url, &local_user_view, &context).await,
+      ImportItem::PersonBlock => import_person_block(url, &local_user_view, &context).await,
+      ImportItem::SavedPost => import_saved_post(url, &local_user_view, &context).await,
+      ImportItem::SavedComment => import_saved_comment(url, &local_user_view, &context).await,
+    };
+    let failure = result.err().map(|e| {
+      debug!("Failed to import {url}: {e}");
+      failure_form(job.id, url.to_string(), &e)
+    });
+    UserDataImportJob::advance(&mut context.pool(), job.id, failure).await?;
+  }
+
+  let job = UserDataImportJob::finish(&mut context.pool(), job.id).await?;
+  info!(
+    "Finished user data import {} of local user {}",
+    job.id, job.local_user_id.0
+  );
+  Ok(())
+}
+
+fn failure_form(job_id: i32, item: String, e: &LemmyError) -> UserDataImportFailureForm {
+  UserDataImportFailureForm {
+    job_id,
+    item,
+    error: e.error_type.to_string(),
+  }
+}
+
+/// Keeps an imported setting if it is valid, otherwise it is skipped and the error is recorded.
+fn valid_setting<T>(
+  setting: Option<T>,
+  name: &str,
+  failures: &mut Vec<(String, LemmyError)>,
+  check: impl FnOnce(&T) -> LemmyResult<()>,
+) -> Option<T> {
+  let setting = setting?;
+  match check(&setting) {
+    Ok(()) => Some(setting),
+    Err(e) => {
+      failures.push((name.to_string(), e));
+      None
+    }
+  }
+}
+
+/// Overwrites the profile and settings which are present in the import, with the same checks as
+/// `SaveUserSettings`. Blocked keywords and muted words are added to the existing ones. Invalid
+/// settings are skipped, and returned with the reason.
+async fn import_settings(
+  import: &UserDataExport,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<Vec<(String, LemmyError)>, LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let person_id = local_user_view.person.id;
+  let mut failures = vec![];
+
+  let display_name = valid_setting(
+    sanitize_html_opt(&import.display_name),
+    "display_name",
+    &mut failures,
+    |d| is_valid_display_name(d.trim(), local_site.actor_name_max_length as usize),
+  );
+  let bio = valid_setting(sanitize_html_opt(&import.bio), "bio", &mut failures, |b| {
+    is_valid_bio_field(b)
+  });
+  let matrix_user_id = valid_setting(
+    import.matrix_user_id.clone(),
+    "matrix_user_id",
+    &mut failures,
+    |m| is_valid_matrix_id(m),
+  );
+
+  let person_form = PersonUpdateForm {
+    display_name: display_name.map(Some),
+    bio: bio.map(Some),
+    avatar: import.avatar.clone().map(Some),
+    banner: import.banner.clone().map(Some),
+    matrix_user_id: matrix_user_id.map(Some),
+    bot_account: import.bot_account,
+    ..Default::default()
+  };
+  Person::update(&mut context.pool(), person_id, &person_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::UserAlreadyExists)?;
+
+  // Merged with the existing words, like the blocked keywords
+  let notification_muted_words = if import.notification_muted_words.is_empty() {
+    None
+  } else {
+    let mut words = local_user_view.local_user.notification_muted_words.clone();
+    words.extend(import.notification_muted_words.iter().cloned());
+    match clean_notification_muted_words(&words) {
+      Ok(words) => Some(words),
+      Err(e) => {
+        failures.push(("notification_muted_words".to_string(), e));
+        None
+      }
+    }
+  };
+  let local_user_form = LocalUserUpdateForm {
+    show_nsfw: import.show_nsfw,
+    blur_nsfw: import.blur_nsfw,
+    auto_expand: import.auto_expand,
+    show_scores: import.show_scores,
+    theme: sanitize_html_opt(&import.theme),
+    default_sort_type: import.default_sort_type,
+    default_listing_type: import.default_listing_type,
+    interface_language: import.interface_language.clone(),
+    show_avatars: import.show_avatars,
+    send_notifications_to_email: import.send_notifications_to_email,
+    show_bot_accounts: import.show_bot_accounts,
+    show_read_posts: import.show_read_posts,
+    open_links_in_new_tab: import.open_links_in_new_tab,
+    infinite_scroll_enabled: import.infinite_scroll_enabled,
+    hide_content_below_score: import.hide_content_below_score.map(Some),
+    exclude_from_leaderboards: import.exclude_from_leaderboards,
+    notification_muted_words,
+    ..Default::default()
+  };
+  LocalUser::update(
+    &mut context.pool(),
+    local_user_view.local_user.id,
+    &local_user_form,
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::UserAlreadyExists)?;
+
+  if !import.blocked_keywords.is_empty() {
+    let mut keywords = PersonKeywordBlock::read(&mut context.pool(), person_id).await?;
+    keywords.extend(import.blocked_keywords.iter().cloned());
+    match clean_blocked_keywords(&keywords) {
+      Ok(keywords) => {
+        PersonKeywordBlock::update(&mut context.pool(), person_id, keywords).await?;
+      }
+      Err(e) => failures.push(("blocked_keywords".to_string(), e)),
+    }
+  }
+  Ok(failures)
+}
+
+/// Follows the community through the same path as `FollowCommunity`.
+async fn import_follow(
+  url: &DbUrl,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let community = ObjectId::<ApubCommunity>::from(url.clone())
+    .dereference(&context.reset_request_count())
+    .await?;
+  update_community_follow(
+    community.deref().clone(),
+    &local_user_view.person,
+    true,
+    context,
+  )
+  .await
+}
+
+/// Blocks the community and unfollows it, like `BlockCommunity`.
+async fn import_community_block(
+  url: &DbUrl,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let community = ObjectId::<ApubCommunity>::from(url.clone())
+    .dereference(&context.reset_request_count())
+    .await?;
+  let community_block_form = CommunityBlockForm {
+    person_id: local_user_view.person.id,
+    community_id: community.id,
+  };
+  CommunityBlock::block(&mut context.pool(), &community_block_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CommunityBlockAlreadyExists)?;
+  update_community_follow(
+    community.deref().clone(),
+    &local_user_view.person,
+    false,
+    context,
+  )
+  .await
+}
+
+/// Blocks the person, with the same restrictions as `BlockPerson`.
+async fn import_person_block(
+  url: &DbUrl,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let target = ObjectId::<ApubPerson>::from(url.clone())
+    .dereference(&context.reset_request_count())
+    .await?;
+  if target.id == local_user_view.person.id {
+    return Err(LemmyErrorType::CantBlockYourself)?;
+  }
+  if target.admin {
+    return Err(LemmyErrorType::CantBlockAdmin)?;
+  }
+  let person_block_form = PersonBlockForm {
+    person_id: local_user_view.person.id,
+    target_id: target.id,
+  };
+  PersonBlock::block(&mut context.pool(), &person_block_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::PersonBlockAlreadyExists)?;
+  Ok(())
+}
+
+async fn import_saved_post(
+  url: &DbUrl,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let post = ObjectId::<ApubPost>::from(url.clone())
+    .dereference(&context.reset_request_count())
+    .await?;
+  let post_saved_form = PostSavedForm {
+    post_id: post.id,
+    person_id: local_user_view.person.id,
+    remind_at: None,
+    tag: None,
+  };
+  PostSaved::save(&mut context.pool(), &post_saved_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntSavePost)?;
+  Ok(())
+}
+
+async fn import_saved_comment(
+  url: &DbUrl,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let comment = ObjectId::<ApubComment>::from(url.clone())
+    .dereference(&context.reset_request_count())
+    .await?;
+  let comment_saved_form = CommentSavedForm {
+    comment_id: comment.id,
+    person_id: local_user_view.person.id,
+    remind_at: None,
+    tag: None,
+  };
+  CommentSaved::save(&mut context.pool(), &comment_saved_form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntSaveComment)?;
+  Ok(())
+}
@@ -1,6 +1,8 @@
+use crate::protocol::{collections::item_collection::ItemCollection, IdOrNestedObject};
 use activitypub_federation::{
   config::Data,
-  fetch::webfinger::webfinger_resolve_actor,
+  fetch::{fetch_object_http, webfinger::webfinger_resolve_actor},
+  protocol::verification::verify_domains_match,
   traits::{Actor, Object},
 };
 use diesel::NotFound;
@@ -9,10 +11,17 @@ use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::traits::ApubActor;
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::error::LemmyError;
+use serde_json::Value;
+use url::Url;
 
 pub mod post_or_comment;
+pub mod replies;
 pub mod search;
 pub mod user_or_community;
+pub(crate) mod votes;
+
+/// The maximum number of collection pages which are fetched, in case the pages are mostly empty.
+const MAX_PAGES_PER_COLLECTION: usize = 50;
 
 /// Resolve actor identifier like `!news@example.com` to user or community object.
 ///
@@ -62,3 +71,27 @@ where
     )
   }
 }
+
+/// Collects up to `limit` items of the collection, following its pages.
+pub(crate) async fn fetch_collection_items(
+  collection_id: &Url,
+  limit: usize,
+  context: &Data<LemmyContext>,
+) -> Result<Vec<Value>, LemmyError> {
+  let collection: ItemCollection = fetch_object_http(collection_id, context).await?;
+  let mut items = collection.ordered_items;
+  let mut page = collection.first;
+  let mut fetched_pages = 0;
+  while let Some(current) = page {
+    if items.len() >= limit || fetched_pages >= MAX_PAGES_PER_COLLECTION {
+      break;
+    }
+    verify_domains_match(current.id(), collection_id)?;
+    let current = current.object(context).await?;
+    fetched_pages += 1;
+    items.extend(current.ordered_items);
+    page = current.next.map(IdOrNestedObject::Id);
+  }
+  items.truncate(limit);
+  Ok(items)
+}
@@ -0,0 +1,170 @@
+use crate::{
+  fetcher::fetch_collection_items,
+  objects::comment::ApubComment,
+  protocol::objects::{note::Note, page::Page},
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::fetch_object_http,
+  protocol::verification::verify_domains_match,
+  traits::Object,
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::{comment::Comment, post::Post};
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  settings::structs::ResyncConfig,
+};
+use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
+use tracing::debug;
+use url::Url;
+
+/// Fetches the comments of a remote post which are missing locally, from the replies collection
+/// which the home instance of the post publishes. Returns the number of comments which were added.
+///
+/// Each comment goes through the same checks as a comment which arrives in the inbox, but nobody
+/// is notified about it. Comments which exist already are left alone, so that a comment which was
+/// removed here stays removed. If the parent of a missing comment is missing as well, the parent
+/// is fetched first. Software which only lists the direct replies of an object has the replies
+/// collections of the missing comments followed as well.
+#[tracing::instrument(skip_all)]
+pub async fn resync_post_comments(post: &Post, context: &Data<LemmyContext>) -> LemmyResult<i32> {
+  if post.locked {
+    return Err(LemmyErrorType::PostIsLocked)?;
+  }
+  let post_ap_id: Url = post.ap_id.clone().into();
+  let page: Page = fetch_object_http(&post_ap_id, context).await?;
+  let Some(replies) = page.replies else {
+    return Err(LemmyErrorType::NoRepliesCollection)?;
+  };
+
+  let mut resync = PostResync {
+    post_ap_id,
+    limits: &context.settings().resync,
+    seen: HashSet::new(),
+    fetched: 0,
+    added: 0,
+  };
+  // The replies collections left to read, with the object they belong to and their depth
+  let mut collections = VecDeque::from([(replies.id().clone(), resync.post_ap_id.clone(), 0)]);
+  while let Some((collection_id, owner_id, depth)) = collections.pop_front() {
+    let remaining = resync
+      .limits
+      .max_comments_per_post
+      .saturating_sub(resync.fetched);
+    if remaining == 0 {
+      break;
+    }
+    verify_domains_match(&collection_id, &owner_id)?;
+    let items = match fetch_collection_items(&collection_id, remaining, context).await {
+      Ok(items) => items,
+      // Only the collection of the post itself is required
+      Err(e) if depth > 0 => {
+        debug!("Skipped replies collection {collection_id}: {e}");
+        continue;
+      }
+      Err(e) => return Err(e),
+    };
+
+    for comment_id in items.iter().filter_map(item_id) {
+      let Some(note) = resync.add_missing(comment_id, context).await else {
+        continue;
+      };
+      if let (Some(replies), true) = (note.replies, depth < resync.limits.max_depth) {
+        collections.push_back((replies.id().clone(), note.id.inner().clone(), depth + 1));
+      }
+    }
+  }
+  Ok(resync.added)
+}
+
+struct PostResync<'a> {
+  post_ap_id: Url,
+  limits: &'a ResyncConfig,
+  /// The comments which were fetched or found locally, so that each is only handled once
+  seen: HashSet<Url>,
+  fetched: usize,
+  added: i32,
+}
+
+impl PostResync<'_> {
+  /// Adds the comment along with its missing parents. Returns the comment if it was missing and
+  /// could be added.
+  async fn add_missing(&mut self, comment_id: Url, context: &Data<LemmyContext>) -> Option<Note> {
+    if !self.seen.insert(comment_id.clone())
+      || self.is_known(&comment_id, context).await
+      || self.fetched >= self.limits.max_comments_per_post
+    {
+      return None;
+    }
+    // Each comment may need a few requests for its creator and parents
+    let context = &context.reset_request_count();
+    let note = self.fetch(&comment_id, context).await?;
+
+    // Collect the missing parents up to the first one which exists, or the post
+    let mut missing = vec![note.clone()];
+    while let Some(parent) = missing.last() {
+      let parent_id = parent.in_reply_to.inner().clone();
+      if parent_id == self.post_ap_id || self.is_known(&parent_id, context).await {
+        break;
+      }
+      // A parent which was seen before failed to be added, so its replies would fail as well
+      if missing.len() > self.limits.max_depth
+        || !self.seen.insert(parent_id.clone())
+        || self.fetched >= self.limits.max_comments_per_post
+      {
+        return None;
+      }
+      missing.push(self.fetch(&parent_id, context).await?);
+    }
+
+    for note in missing.into_iter().rev() {
+      let note_id: Url = note.id.inner().clone();
+      let res = match ApubComment::verify(&note, &note_id, context).await {
+        Ok(()) => ApubComment::from_json(note, context).await,
+        Err(e) => Err(e),
+      };
+      let comment = match res {
+        Ok(comment) => comment,
+        Err(e) => {
+          debug!("Skipped resynced comment {note_id}: {e}");
+          return None;
+        }
+      };
+      if let Err(e) = Comment::mark_backfilled(&mut context.pool(), comment.id).await {
+        debug!("Couldn't mark comment {note_id} as backfilled: {e}");
+      }
+      self.added += 1;
+    }
+    Some(note)
+  }
+
+  async fn fetch(&mut self, comment_id: &Url, context: &Data<LemmyContext>) -> Option<Note> {
+    self.fetched += 1;
+    match fetch_object_http::<_, Note>(comment_id, context).await {
+      Ok(note) => Some(note),
+      Err(e) => {
+        debug!("Couldn't fetch resynced comment {comment_id}: {e}");
+        None
+      }
+    }
+  }
+
+  async fn is_known(&self, comment_id: &Url, context: &Data<LemmyContext>) -> bool {
+    matches!(
+      Comment::read_from_apub_id(&mut context.pool(), comment_id.clone()).await,
+      Ok(Some(_))
+    )
+  }
+}
+
+/// Items of replies collections are either the ids of the comments, or the comments themselves.
+fn item_id(item: &Value) -> Option<Url> {
+  let id = match item {
+    Value::String(id) => id.as_str(),
+    Value::Object(object) => object.get("id")?.as_str()?,
+    _ => return None,
+  };
+  Url::parse(id).ok()
+}
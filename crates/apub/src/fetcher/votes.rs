@@ -0,0 +1,152 @@
+use crate::{
+  fetcher::{fetch_collection_items, post_or_comment::PostOrComment},
+  objects::verify_is_remote_object,
+  protocol::activities::voting::vote::{Vote, VoteType},
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::{fetch_object_http, object_id::ObjectId},
+  protocol::verification::verify_domains_match,
+  traits::ActivityHandler,
+};
+use anyhow::anyhow;
+use lemmy_api_common::context::LemmyContext;
+use lemmy_utils::{error::LemmyError, settings::structs::Settings};
+use serde_json::Value;
+use tracing::debug;
+use url::Url;
+
+/// The maximum number of votes which are backfilled for a single post or comment.
+const MAX_VOTES_PER_OBJECT: usize = 1000;
+
+/// Reads the votes which the post or comment received before we first fetched it, so that the
+/// upvote and downvote counts aren't limited to the votes that were federated to us afterwards.
+///
+/// Only collections hosted on the same instance as the object are read. The collection isn't
+/// signed, so votes are only taken from it if they are hosted on the same instance as well.
+/// Other votes are fetched from the instance of their actor. Each vote then goes through the same
+/// checks as a vote which arrives in the inbox, and invalid ones are skipped.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn backfill_votes(
+  object_id: ObjectId<PostOrComment>,
+  likes: Option<Url>,
+  dislikes: Option<Url>,
+  context: Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let mut remaining = MAX_VOTES_PER_OBJECT;
+  for (collection_id, kind) in [(likes, VoteType::Like), (dislikes, VoteType::Dislike)] {
+    let Some(collection_id) = collection_id else {
+      continue;
+    };
+    if remaining == 0 {
+      break;
+    }
+    verify_domains_match(&collection_id, object_id.inner())?;
+    let items = fetch_collection_items(&collection_id, remaining, &context).await?;
+    remaining = remaining.saturating_sub(items.len());
+
+    for item in items {
+      let vote = match read_vote(item, &collection_id, &context).await {
+        Ok(vote) => vote,
+        Err(e) => {
+          debug!("Skipped backfilled vote: {e}");
+          continue;
+        }
+      };
+      if vote.kind != kind || vote.object.inner() != object_id.inner() {
+        continue;
+      }
+      let vote_id = vote.id.clone();
+      let res = match vote.verify(&context).await {
+        Ok(()) => vote.receive(&context).await,
+        Err(e) => Err(e),
+      };
+      if let Err(e) = res {
+        debug!("Skipped backfilled vote {vote_id}: {e}");
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Reads an item of a vote collection. Votes which are embedded in the collection are only
+/// trusted if they are hosted on the instance of the collection, all others are fetched from
+/// their origin.
+async fn read_vote(
+  item: Value,
+  collection_id: &Url,
+  context: &Data<LemmyContext>,
+) -> Result<Vote, LemmyError> {
+  let vote_id = match &item {
+    Value::String(id) => Url::parse(id)?,
+    Value::Object(object) => object
+      .get("id")
+      .and_then(Value::as_str)
+      .map(Url::parse)
+      .ok_or_else(|| anyhow!("vote without id"))??,
+    _ => Err(anyhow!("invalid vote"))?,
+  };
+  let embedded = item.is_object() && verify_domains_match(&vote_id, collection_id).is_ok();
+  let vote: Vote = if embedded {
+    serde_json::from_value(item)?
+  } else {
+    fetch_object_http(&vote_id, context).await?
+  };
+  verify_vote_origin(&vote, &vote_id, context.settings())?;
+  Ok(vote)
+}
+
+/// Checks that the vote was published by the instance of its actor, and that it isn't attributed
+/// to a local person.
+fn verify_vote_origin(vote: &Vote, vote_id: &Url, settings: &Settings) -> Result<(), LemmyError> {
+  if vote.id != *vote_id {
+    Err(anyhow!("vote id doesn't match"))?;
+  }
+  verify_domains_match(vote.actor.inner(), &vote.id)?;
+  verify_is_remote_object(vote.actor.inner(), settings)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::verify_vote_origin;
+  use crate::protocol::activities::voting::vote::Vote;
+  use lemmy_utils::settings::SETTINGS;
+  use serde_json::json;
+  use url::Url;
+
+  fn vote(id: &str, actor: &str) -> Vote {
+    serde_json::from_value(json!({
+      "id": id,
+      "actor": actor,
+      "object": "https://remote.tld/post/1",
+      "type": "Like"
+    }))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_verify_vote_origin() {
+    let id = "https://remote.tld/activities/like/1";
+    let valid = vote(id, "https://remote.tld/u/alice");
+    assert!(verify_vote_origin(&valid, &Url::parse(id).unwrap(), &SETTINGS).is_ok());
+
+    // The collection can't claim votes of persons on other instances
+    let forged = vote(id, "https://other.tld/u/bob");
+    assert!(verify_vote_origin(&forged, &Url::parse(id).unwrap(), &SETTINGS).is_err());
+
+    // Nor votes of our own users
+    let local_id = format!("https://{}/activities/like/1", SETTINGS.hostname);
+    let local_actor = format!("https://{}/u/carol", SETTINGS.hostname);
+    let local = vote(&local_id, &local_actor);
+    let local_id = Url::parse(&local_id).unwrap();
+    assert!(verify_vote_origin(&local, &local_id, &SETTINGS).is_err());
+
+    // The fetched vote must be the requested one
+    let other_id = Url::parse("https://remote.tld/activities/like/2").unwrap();
+    assert!(verify_vote_origin(&valid, &other_id, &SETTINGS).is_err());
+  }
+}
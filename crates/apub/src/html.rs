@@ -0,0 +1,162 @@
+use html2md::parse_html;
+use lemmy_utils::utils::markdown::normalize_spoilers;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashSet;
+
+/// Tags which are kept in remote html. Other tags are removed, but their text is kept.
+const ALLOWED_TAGS: &[&str] = &[
+  "a",
+  "b",
+  "blockquote",
+  "br",
+  "code",
+  "del",
+  "details",
+  "em",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "hr",
+  "i",
+  "img",
+  "li",
+  "ol",
+  "p",
+  "pre",
+  "s",
+  "span",
+  "strong",
+  "sub",
+  "summary",
+  "sup",
+  "ul",
+];
+/// Tags which are removed together with everything inside them.
+const REMOVED_CONTENT_TAGS: &[&str] = &["script", "style", "svg", "math", "iframe", "object"];
+const ALLOWED_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Mastodon and others send mentions as links with the plain username as text, eg
+/// `[@user](https://example.com/@user)`. Captures the username, the link and its domain.
+static MENTION_LINK_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"\[@([\w.]+)\]\((https?://([a-zA-Z0-9.:-]+)/[^)\s]*)\)").expect("compile regex")
+});
+
+/// Links whose url was removed by the sanitizer, like `javascript:` links.
+static EMPTY_LINK_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"\[([^\]]*)\]\(\)").expect("compile regex"));
+
+/// Collapsed `<details>` blocks, which Lemmy renders spoilers as and other platforms use for
+/// content warnings. Captures the summary, which becomes the title of the spoiler.
+static DETAILS_START_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"(?s)<details>\s*(?:<summary>(.*?)</summary>)?").expect("compile regex")
+});
+
+/// html2md separates the paragraphs around the spoiler fences with blank lines, but the spoiler
+/// rule needs the fences directly next to the content.
+static SPOILER_START_NEWLINES_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"(?m)^(::: spoiler[^\n]*)\n\s*").expect("compile regex"));
+static SPOILER_END_NEWLINES_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"(?m)\s*\n:::$").expect("compile regex"));
+
+/// Converts html from federated objects into markdown. The html is sanitized against an
+/// allowlist first, so that only formatting, links and images end up in the markdown.
+pub(crate) fn html_to_markdown(html: &str) -> String {
+  let sanitized = ammonia::Builder::default()
+    .tags(ALLOWED_TAGS.iter().copied().collect())
+    .clean_content_tags(REMOVED_CONTENT_TAGS.iter().copied().collect())
+    .generic_attributes(HashSet::new())
+    .tag_attributes(
+      [
+        ("a", ["href"].into()),
+        ("img", ["src", "alt", "title"].into()),
+        ("ol", ["start"].into()),
+      ]
+      .into(),
+    )
+    .url_schemes(ALLOWED_URL_SCHEMES.iter().copied().collect())
+    .link_rel(None)
+    .clean(html)
+    .to_string();
+  // Turn `<details>` blocks into spoiler fences in their own paragraphs
+  let sanitized = DETAILS_START_REGEX.replace_all(&sanitized, "<p>::: spoiler $1</p>");
+  let sanitized = sanitized.replace("</details>", "<p>:::</p>");
+  let markdown = parse_html(&sanitized);
+  let markdown = EMPTY_LINK_REGEX.replace_all(&markdown, "$1");
+  let markdown = SPOILER_START_NEWLINES_REGEX.replace_all(&markdown, "$1\n");
+  let markdown = SPOILER_END_NEWLINES_REGEX.replace_all(&markdown, "\n:::");
+  // Nested spoilers can't be rendered, those are left as they are
+  let markdown = normalize_spoilers(&markdown).unwrap_or_else(|_| markdown.to_string());
+  // Add the domain to mentions, so that they also work as mentions on Lemmy
+  MENTION_LINK_REGEX
+    .replace_all(&markdown, "[@$1@$3]($2)")
+    .trim()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+
+  use crate::html::html_to_markdown;
+  use lemmy_utils::utils::markdown::markdown_to_html;
+  use std::fs;
+
+  /// Every `.html` file in the corpus is converted and compared against the `.md` file next to it.
+  #[test]
+  fn test_html_to_markdown_corpus() {
+    let mut files = fs::read_dir("assets/html_to_markdown")
+      .unwrap()
+      .map(|f| f.unwrap().path())
+      .filter(|p| p.extension().is_some_and(|e| e == "html"))
+      .collect::<Vec<_>>();
+    files.sort();
+    assert!(!files.is_empty());
+
+    for html_file in files {
+      let html = fs::read_to_string(&html_file).unwrap();
+      let expected = fs::read_to_string(html_file.with_extension("md")).unwrap();
+      assert_eq!(
+        expected.trim_end(),
+        html_to_markdown(&html),
+        "{}",
+        html_file.display()
+      );
+    }
+  }
+
+  #[test]
+  fn test_html_to_markdown_xss() {
+    let converted = html_to_markdown(r#"<img src="x" onerror="alert(1)">"#);
+    assert!(!converted.contains("onerror"));
+    assert!(!converted.contains("alert"));
+
+    let converted = html_to_markdown(r#"<a href="javascript:alert(1)">click</a>"#);
+    assert!(!converted.contains("javascript"));
+    assert_eq!("click", converted);
+
+    let converted =
+      html_to_markdown(r#"<svg><script>alert(1)</script><a href="\#">x</a></svg><p>text</p>"#);
+    assert!(!converted.contains("alert"));
+    assert_eq!("text", converted);
+
+    let converted = html_to_markdown(r#"<p onclick="alert(1)" style="color: red">text</p>"#);
+    assert_eq!("text", converted);
+  }
+
+  #[test]
+  fn test_spoiler_round_trip() {
+    let markdowns = [
+      "::: spoiler click to see more\nhow spicy!\n:::",
+      "Before\n\n::: spoiler a title\nhidden *text*\n:::\n\nAfter",
+    ];
+    for markdown in markdowns {
+      let html = markdown_to_html(markdown);
+      assert!(html.contains("<details>"), "{html}");
+      assert_eq!(markdown, html_to_markdown(&html), "{html}");
+    }
+  }
+}
@@ -5,7 +5,11 @@ use crate::{
     community_moderators::ApubCommunityModerators,
     community_outbox::ApubCommunityOutbox,
   },
-  http::{create_apub_response, create_apub_tombstone_response},
+  http::{
+    create_apub_response,
+    create_apub_tombstone_response,
+    inbox_admission::receive_when_admitted,
+  },
   objects::{community::ApubCommunity, person::ApubPerson},
   protocol::collections::group_followers::GroupFollowers,
 };
@@ -53,10 +57,12 @@ pub async fn community_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
-  receive_activity::<WithContext<GroupInboxActivities>, ApubPerson, LemmyContext>(
-    request, body, &data,
-  )
-  .await
+  let receive = receive_activity::<WithContext<GroupInboxActivities>, ApubPerson, LemmyContext>(
+    request,
+    body.clone(),
+    &data,
+  );
+  receive_when_admitted(&body, receive).await
 }
 
 /// Returns an empty followers collection, only populating the size (for privacy).
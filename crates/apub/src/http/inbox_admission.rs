@@ -0,0 +1,313 @@
+use actix_web::{http::header, web::Bytes, HttpResponse};
+use lemmy_utils::{error::LemmyResult, settings::SETTINGS};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::{
+  cmp::{Ordering, Reverse},
+  collections::BinaryHeap,
+  future::Future,
+  sync::{
+    atomic::{self, AtomicU64},
+    Arc,
+    Mutex,
+  },
+};
+use tokio::sync::oneshot;
+
+/// How many seconds remote instances are asked to wait before sending a shed activity again.
+const RETRY_AFTER_SECONDS: u32 = 30;
+
+/// Limits the processing of incoming activities for all inboxes.
+pub static INBOX_ADMISSION: Lazy<InboxAdmission> = Lazy::new(|| {
+  let concurrency = match SETTINGS.inbox_concurrency {
+    0 => (SETTINGS.database.pool_size / 2).max(1),
+    c => c,
+  };
+  InboxAdmission::new(concurrency, SETTINGS.inbox_queue_size)
+});
+
+/// Processes an incoming activity once the inbox has capacity for it, or asks the sender to try
+/// again later with `429 Too Many Requests` if the queue for its priority is full.
+pub(crate) async fn receive_when_admitted<F>(body: &Bytes, receive: F) -> LemmyResult<HttpResponse>
+where
+  F: Future<Output = LemmyResult<HttpResponse>>,
+{
+  let priority = InboxPriority::of_activity(body);
+  match INBOX_ADMISSION.admit(priority).await {
+    Some(_permit) => receive.await,
+    None => Ok(
+      HttpResponse::TooManyRequests()
+        .insert_header((header::RETRY_AFTER, RETRY_AFTER_SECONDS))
+        .finish(),
+    ),
+  }
+}
+
+/// Activities with a higher priority are processed first, and are shed last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InboxPriority {
+  /// Votes, which are plentiful and only change scores.
+  Low,
+  Normal,
+  /// Deletions and follows, which users expect to take effect promptly.
+  High,
+}
+
+impl InboxPriority {
+  /// Reads the type of the activity from the unverified body, looking through announces and
+  /// undos to the wrapped activity, so that an undone vote is still a vote.
+  fn of_activity(body: &[u8]) -> Self {
+    let Ok(mut activity) = serde_json::from_slice::<Value>(body) else {
+      return InboxPriority::Normal;
+    };
+    loop {
+      match activity.get("type").and_then(Value::as_str) {
+        Some("Announce" | "Undo") if activity.get("object").is_some_and(Value::is_object) => {
+          activity = activity["object"].take();
+        }
+        Some("Delete" | "Remove" | "Follow" | "Accept" | "Reject" | "Block") => {
+          return InboxPriority::High
+        }
+        Some("Like" | "Dislike") => return InboxPriority::Low,
+        _ => return InboxPriority::Normal,
+      }
+    }
+  }
+
+  /// The share of the queue which activities of this priority may fill, so that there is always
+  /// room left for activities of a higher priority.
+  fn max_queued(self, queue_size: usize) -> usize {
+    match self {
+      InboxPriority::Low => queue_size / 2,
+      InboxPriority::Normal => queue_size * 3 / 4,
+      InboxPriority::High => queue_size,
+    }
+  }
+}
+
+/// Admits at most `concurrency` activities for processing at the same time. Further activities
+/// wait in a bounded queue, ordered by priority, and are shed once the queue is full.
+pub struct InboxAdmission {
+  inner: Arc<Inner>,
+}
+
+struct Inner {
+  concurrency: usize,
+  queue_size: usize,
+  state: Mutex<State>,
+  shed: AtomicU64,
+}
+
+#[derive(Default)]
+struct State {
+  processing: usize,
+  queue: BinaryHeap<Waiting>,
+  next_seq: u64,
+}
+
+struct Waiting {
+  priority: InboxPriority,
+  seq: Reverse<u64>,
+  admit: oneshot::Sender<InboxPermit>,
+}
+
+impl Ord for Waiting {
+  fn cmp(&self, other: &Self) -> Ordering {
+    (self.priority, self.seq).cmp(&(other.priority, other.seq))
+  }
+}
+
+impl PartialOrd for Waiting {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl PartialEq for Waiting {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for Waiting {}
+
+/// Allows processing an activity. The next queued activity is admitted once this is dropped.
+pub struct InboxPermit {
+  inner: Option<Arc<Inner>>,
+}
+
+impl Drop for InboxPermit {
+  fn drop(&mut self) {
+    if let Some(inner) = self.inner.take() {
+      inner.release();
+    }
+  }
+}
+
+impl InboxAdmission {
+  pub fn new(concurrency: usize, queue_size: usize) -> Self {
+    InboxAdmission {
+      inner: Arc::new(Inner {
+        concurrency: concurrency.max(1),
+        queue_size,
+        state: Mutex::new(State::default()),
+        shed: AtomicU64::new(0),
+      }),
+    }
+  }
+
+  /// Waits until the activity may be processed. Returns `None` if it was shed instead.
+  pub async fn admit(&self, priority: InboxPriority) -> Option<InboxPermit> {
+    let admitted = {
+      let mut state = self.inner.lock();
+      if state.processing < self.inner.concurrency {
+        state.processing += 1;
+        return Some(self.inner.permit());
+      }
+      // Senders which gave up waiting still take up room
+      state.queue.retain(|w| !w.admit.is_closed());
+      if state.queue.len() >= priority.max_queued(self.inner.queue_size) {
+        self.inner.shed.fetch_add(1, atomic::Ordering::Relaxed);
+        return None;
+      }
+      let (admit, admitted) = oneshot::channel();
+      let seq = Reverse(state.next_seq);
+      state.next_seq += 1;
+      state.queue.push(Waiting {
+        priority,
+        seq,
+        admit,
+      });
+      admitted
+    };
+    admitted.await.ok()
+  }
+
+  /// The number of activities which may be processed at the same time.
+  pub fn concurrency(&self) -> usize {
+    self.inner.concurrency
+  }
+
+  /// The number of activities which are currently processed.
+  pub fn processing(&self) -> usize {
+    self.inner.lock().processing
+  }
+
+  /// The number of activities which are waiting to be processed.
+  pub fn queued(&self) -> usize {
+    self.inner.lock().queue.len()
+  }
+
+  /// The number of activities which were shed since the start.
+  pub fn shed(&self) -> u64 {
+    self.inner.shed.load(atomic::Ordering::Relaxed)
+  }
+}
+
+impl Inner {
+  fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+    // The state stays consistent even if a thread panicked while holding the lock
+    self
+      .state
+      .lock()
+      .unwrap_or_else(std::sync::PoisonError::into_inner)
+  }
+
+  fn permit(self: &Arc<Self>) -> InboxPermit {
+    InboxPermit {
+      inner: Some(self.clone()),
+    }
+  }
+
+  /// Hands the place of a finished activity to the next queued one.
+  fn release(self: &Arc<Self>) {
+    let mut state = self.lock();
+    while let Some(waiting) = state.queue.pop() {
+      match waiting.admit.send(self.permit()) {
+        Ok(()) => return,
+        // The sender gave up waiting, so the permit must not be released again
+        Err(mut permit) => permit.inner = None,
+      }
+    }
+    state.processing -= 1;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::{InboxAdmission, InboxPriority};
+  use serde_json::json;
+  use std::sync::Arc;
+  use tokio::task::yield_now;
+
+  #[test]
+  fn test_activity_priority() {
+    let priority = |activity: serde_json::Value| {
+      InboxPriority::of_activity(&serde_json::to_vec(&activity).unwrap())
+    };
+    let undo_like = json!({ "type": "Undo", "object": { "type": "Like" } });
+    assert_eq!(InboxPriority::Low, priority(undo_like.clone()));
+    assert_eq!(
+      InboxPriority::Low,
+      priority(json!({ "type": "Announce", "object": undo_like }))
+    );
+    assert_eq!(
+      InboxPriority::High,
+      priority(json!({ "type": "Delete", "object": "https://example.com/post/1" }))
+    );
+    assert_eq!(
+      InboxPriority::High,
+      priority(json!({ "type": "Undo", "object": { "type": "Follow" } }))
+    );
+    assert_eq!(
+      InboxPriority::Normal,
+      priority(json!({ "type": "Announce", "object": "https://example.com/activities/1" }))
+    );
+    assert_eq!(InboxPriority::Normal, InboxPriority::of_activity(b"{"));
+  }
+
+  /// Lets the spawned tasks run until the given number of activities waits in the queue.
+  async fn wait_until_queued(admission: &InboxAdmission, queued: usize) {
+    while admission.queued() < queued {
+      yield_now().await;
+    }
+  }
+
+  #[tokio::test]
+  async fn test_admission_order() {
+    let admission = Arc::new(InboxAdmission::new(1, 4));
+    let permit = admission.admit(InboxPriority::Normal).await.unwrap();
+
+    // Low priority activities may only fill half of the queue
+    let low = tokio::spawn({
+      let admission = admission.clone();
+      async move { admission.admit(InboxPriority::Low).await.is_some() }
+    });
+    wait_until_queued(&admission, 1).await;
+    let low_2 = tokio::spawn({
+      let admission = admission.clone();
+      async move { admission.admit(InboxPriority::Low).await.is_some() }
+    });
+    wait_until_queued(&admission, 2).await;
+    assert!(admission.admit(InboxPriority::Low).await.is_none());
+    assert_eq!(1, admission.shed());
+
+    // A later high priority activity is admitted first
+    let high = tokio::spawn({
+      let admission = admission.clone();
+      async move {
+        let _permit = admission.admit(InboxPriority::High).await;
+        admission.queued()
+      }
+    });
+    wait_until_queued(&admission, 3).await;
+    drop(permit);
+    assert_eq!(2, high.await.unwrap());
+    assert!(low.await.unwrap());
+    assert!(low_2.await.unwrap());
+    assert_eq!(0, admission.processing());
+  }
+}
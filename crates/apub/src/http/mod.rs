@@ -1,6 +1,7 @@
 use crate::{
   activity_lists::SharedInboxActivities,
   fetcher::user_or_community::UserOrCommunity,
+  http::inbox_admission::receive_when_admitted,
   protocol::objects::tombstone::Tombstone,
   CONTEXT,
 };
@@ -21,6 +22,7 @@ use url::Url;
 
 mod comment;
 mod community;
+pub mod inbox_admission;
 mod person;
 mod post;
 pub mod routes;
@@ -31,8 +33,12 @@ pub async fn shared_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  receive_activity::<SharedInboxActivities, UserOrCommunity, LemmyContext>(request, body, &data)
-    .await
+  let receive = receive_activity::<SharedInboxActivities, UserOrCommunity, LemmyContext>(
+    request,
+    body.clone(),
+    &data,
+  );
+  receive_when_admitted(&body, receive).await
 }
 
 /// Convert the data to json and turn it into an HTTP Response with the correct ActivityPub
@@ -1,7 +1,11 @@
 use crate::{
   activity_lists::PersonInboxActivities,
   fetcher::user_or_community::UserOrCommunity,
-  http::{create_apub_response, create_apub_tombstone_response},
+  http::{
+    create_apub_response,
+    create_apub_tombstone_response,
+    inbox_admission::receive_when_admitted,
+  },
   objects::person::ApubPerson,
   protocol::collections::empty_outbox::EmptyOutbox,
 };
@@ -49,10 +53,12 @@ pub async fn person_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
-  receive_activity::<WithContext<PersonInboxActivities>, UserOrCommunity, LemmyContext>(
-    request, body, &data,
-  )
-  .await
+  let receive = receive_activity::<WithContext<PersonInboxActivities>, UserOrCommunity, LemmyContext>(
+    request,
+    body.clone(),
+    &data,
+  );
+  receive_when_admitted(&body, receive).await
 }
 
 #[tracing::instrument(skip_all)]
@@ -1,13 +1,27 @@
 use crate::{
   http::{create_apub_response, create_apub_tombstone_response, err_object_not_local},
   objects::post::ApubPost,
+  protocol::collections::post_replies::PostReplies,
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::collection::OrderedCollectionType,
+  traits::Object,
 };
-use activitypub_federation::{config::Data, traits::Object};
 use actix_web::{web, HttpResponse};
-use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::{newtypes::PostId, source::post::Post, traits::Crud};
-use lemmy_utils::error::LemmyError;
+use lemmy_api_common::{context::LemmyContext, utils::generate_replies_url};
+use lemmy_db_schema::{
+  newtypes::PostId,
+  source::{comment::Comment, post::Post},
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyError, LemmyErrorType};
 use serde::Deserialize;
+use url::Url;
+
+/// The maximum number of comments in the replies collection of a post. Posts with more comments
+/// only list the newest ones.
+const MAX_REPLIES_ITEMS: i64 = 1000;
 
 #[derive(Deserialize)]
 pub(crate) struct PostQuery {
@@ -32,3 +46,34 @@ pub(crate) async fn get_apub_post(
     create_apub_tombstone_response(post.ap_id.clone())
   }
 }
+
+/// Returns the ids of the comments on a local post, so that other instances can fetch the ones
+/// which they missed.
+#[tracing::instrument(skip_all)]
+pub(crate) async fn get_apub_post_replies(
+  info: web::Path<PostQuery>,
+  context: Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let id = PostId(info.post_id.parse::<i32>()?);
+  let post = Post::read(&mut context.pool(), id).await?;
+  if !post.local {
+    return Err(err_object_not_local());
+  }
+  if post.deleted || post.removed {
+    return Err(LemmyErrorType::Deleted)?;
+  }
+
+  let ordered_items: Vec<Url> =
+    Comment::list_ap_ids_for_post(&mut context.pool(), post.id, MAX_REPLIES_ITEMS)
+      .await?
+      .into_iter()
+      .map(Into::into)
+      .collect();
+  let replies = PostReplies {
+    r#type: OrderedCollectionType::OrderedCollection,
+    id: generate_replies_url(&post.ap_id)?.into(),
+    total_items: ordered_items.len() as i32,
+    ordered_items,
+  };
+  create_apub_response(&replies)
+}
@@ -10,7 +10,7 @@ use crate::http::{
   },
   get_activity,
   person::{get_apub_person_http, get_apub_person_outbox, person_inbox},
-  post::get_apub_post,
+  post::{get_apub_post, get_apub_post_replies},
   shared_inbox,
   site::{get_apub_site_http, get_apub_site_inbox, get_apub_site_outbox},
 };
@@ -50,6 +50,10 @@ pub fn config(cfg: &mut web::ServiceConfig) {
       web::get().to(get_apub_person_outbox),
     )
     .route("/post/{post_id}", web::get().to(get_apub_post))
+    .route(
+      "/post/{post_id}/replies",
+      web::get().to(get_apub_post_replies),
+    )
     .route("/comment/{comment_id}", web::get().to(get_apub_comment))
     .route("/activities/{type_}/{id}", web::get().to(get_activity));
 
@@ -1,6 +1,6 @@
 use crate::{
   activity_lists::SiteInboxActivities,
-  http::create_apub_response,
+  http::{create_apub_response, inbox_admission::receive_when_admitted},
   objects::{instance::ApubSite, person::ApubPerson},
   protocol::collections::empty_outbox::EmptyOutbox,
 };
@@ -43,8 +43,10 @@ pub async fn get_apub_site_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
-  receive_activity::<WithContext<SiteInboxActivities>, ApubPerson, LemmyContext>(
-    request, body, &data,
-  )
-  .await
+  let receive = receive_activity::<WithContext<SiteInboxActivities>, ApubPerson, LemmyContext>(
+    request,
+    body.clone(),
+    &data,
+  );
+  receive_when_admitted(&body, receive).await
 }
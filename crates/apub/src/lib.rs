@@ -1,14 +1,19 @@
-use crate::fetcher::post_or_comment::PostOrComment;
-use activitypub_federation::config::{Data, UrlVerifier};
+use crate::{activities::activity_object_id, fetcher::post_or_comment::PostOrComment};
+use activitypub_federation::{
+  config::{Data, UrlVerifier},
+  traits::ActivityHandler,
+};
 use async_trait::async_trait;
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
+  newtypes::DbUrl,
   source::{activity::ReceivedActivity, instance::Instance, local_site::LocalSite},
   utils::{ActualDbPool, DbPool},
 };
 use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
 use moka::future::Cache;
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::{sync::Arc, time::Duration};
 use url::Url;
 
@@ -17,6 +22,7 @@ pub(crate) mod activity_lists;
 pub mod api;
 pub(crate) mod collections;
 pub mod fetcher;
+pub(crate) mod html;
 pub mod http;
 pub(crate) mod mentions;
 pub mod objects;
@@ -181,16 +187,48 @@ pub(crate) async fn check_apub_id_valid_with_strictness(
   Ok(())
 }
 
+/// Rejects incoming NSFW posts and communities if the local site doesn't allow any NSFW content,
+/// and counts them for the instance they came from.
+pub(crate) async fn verify_nsfw_allowed(
+  sensitive: Option<bool>,
+  object_id: &Url,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let local_site_data = local_site_data_cached(&mut context.pool()).await?;
+  let disallow_nsfw_content = local_site_data
+    .local_site
+    .as_ref()
+    .map(|l| l.disallow_nsfw_content)
+    .unwrap_or(false);
+  if disallow_nsfw_content && sensitive.unwrap_or(false) {
+    if let Some(domain) = object_id.domain() {
+      Instance::increment_dropped_nsfw_objects(&mut context.pool(), domain).await?;
+    }
+    Err(LemmyErrorType::NsfwNotAllowed)?;
+  }
+  Ok(())
+}
+
 /// Store received activities in the database.
 ///
 /// This ensures that the same activity doesnt get received and processed more than once, which
 /// would be a waste of resources.
-#[tracing::instrument(skip(data))]
-async fn insert_received_activity(
-  ap_id: &Url,
+#[tracing::instrument(skip_all)]
+async fn insert_received_activity<Activity>(
+  activity: &Activity,
   data: &Data<LemmyContext>,
-) -> Result<(), LemmyError> {
-  ReceivedActivity::create(&mut data.pool(), &ap_id.clone().into()).await?;
+) -> Result<(), LemmyError>
+where
+  Activity: ActivityHandler + Serialize,
+{
+  let object_id: Option<DbUrl> =
+    activity_object_id(&serde_json::to_value(activity)?).map(Into::into);
+  ReceivedActivity::create(
+    &mut data.pool(),
+    &activity.id().clone().into(),
+    object_id.as_ref(),
+  )
+  .await?;
   Ok(())
 }
 
@@ -7,11 +7,21 @@ use activitypub_federation::{
 };
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
-  source::{comment::Comment, person::Person, post::Post},
-  traits::Crud,
+  newtypes::{CommentId, PostId},
+  source::{
+    comment::Comment,
+    community::Community,
+    community_mention::{CommunityMention, CommunityMentionForm},
+    person::Person,
+    post::Post,
+  },
+  traits::{ApubActor, Crud},
   utils::DbPool,
 };
-use lemmy_utils::{error::LemmyError, utils::mention::scrape_text_for_mentions};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::mention::{scrape_text_for_community_mentions, scrape_text_for_mentions},
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use url::Url;
@@ -88,6 +98,43 @@ pub async fn collect_non_local_mentions(
   })
 }
 
+/// Fetches the remote communities which local content mentions, but which weren't known yet when
+/// the content was written, and stores those mentions. Communities which can't be fetched are
+/// ignored, so that their mentions are just left as text.
+#[tracing::instrument(skip_all)]
+pub async fn fetch_community_mentions(
+  content: &str,
+  post_id: Option<PostId>,
+  comment_id: Option<CommentId>,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let mentions = scrape_text_for_community_mentions(content)
+    .into_iter()
+    .filter(|m| !m.is_local(&context.settings().hostname));
+
+  let mut forms = Vec::new();
+  for mention in mentions {
+    let known =
+      Community::read_from_name_and_domain(&mut context.pool(), &mention.name, &mention.domain)
+        .await;
+    if known.is_ok() {
+      continue;
+    }
+    let identifier = format!("{}@{}", mention.name, mention.domain);
+    let community =
+      webfinger_resolve_actor::<LemmyContext, ApubCommunity>(&identifier, context).await;
+    if let Ok(community) = community {
+      forms.push(CommunityMentionForm {
+        community_id: community.id,
+        post_id,
+        comment_id,
+      });
+    }
+  }
+  CommunityMention::create_many(&mut context.pool(), &forms).await?;
+  Ok(())
+}
+
 /// Returns the apub ID of the person this comment is responding to. Meaning, in case this is a
 /// top-level comment, the creator of the post, otherwise the creator of the parent comment.
 #[tracing::instrument(skip(pool, comment))]
@@ -1,10 +1,11 @@
 use crate::{
   activities::{verify_is_public, verify_person_in_community},
   check_apub_id_valid_with_strictness,
+  fetcher::votes::backfill_votes,
   mentions::collect_non_local_mentions,
-  objects::{read_from_string_or_source, verify_is_remote_object},
+  objects::{read_content_warning, read_from_string_or_source, verify_is_remote_object},
   protocol::{
-    objects::{note::Note, LanguageTag},
+    objects::{note::Note, CreatorFlair, LanguageTag},
     InCommunity,
     Source,
   },
@@ -30,8 +31,10 @@ use lemmy_db_schema::{
   },
   traits::Crud,
 };
+use lemmy_db_views_actor::structs::CommunityView;
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorType},
+  spawn_try_task,
   utils::{markdown::markdown_to_html, slurs::remove_slurs, time::convert_datetime},
 };
 use std::ops::Deref;
@@ -105,6 +108,7 @@ impl Object for ApubComment {
     };
     let language = LanguageTag::new_single(self.language_id, &mut context.pool()).await?;
     let maa = collect_non_local_mentions(&self, community.actor_id.clone().into(), context).await?;
+    let creator_flair = CreatorFlair::new(&community, creator_id, &mut context.pool()).await?;
 
     let note = Note {
       r#type: NoteType::Note,
@@ -113,6 +117,8 @@ impl Object for ApubComment {
       to: vec![public()],
       cc: maa.ccs,
       content: markdown_to_html(&self.content),
+      summary: self.content_warning.clone(),
+      sensitive: Some(self.nsfw),
       media_type: Some(MediaTypeMarkdownOrHtml::Html),
       source: Some(Source::new(self.content.clone())),
       in_reply_to,
@@ -120,8 +126,13 @@ impl Object for ApubComment {
       updated: self.updated.map(convert_datetime),
       tag: maa.tags,
       distinguished: Some(self.distinguished),
+      locked: Some(self.locked),
       language,
       audience: Some(community.actor_id.into()),
+      likes: None,
+      dislikes: None,
+      replies: None,
+      creator_flair,
     };
 
     Ok(note)
@@ -133,18 +144,36 @@ impl Object for ApubComment {
     expected_domain: &Url,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
-    verify_domains_match(note.id.inner(), expected_domain)?;
+    // We can't verify the domain in case of mod action, because the mod may be on a different
+    // instance from the comment author.
+    let is_mod_action = note.is_mod_action(context).await?;
+    if !is_mod_action {
+      verify_domains_match(note.id.inner(), expected_domain)?;
+      verify_is_remote_object(note.id.inner(), context.settings())?;
+    }
     verify_domains_match(note.attributed_to.inner(), note.id.inner())?;
     verify_is_public(&note.to, &note.cc)?;
     let community = note.community(context).await?;
 
     check_apub_id_valid_with_strictness(note.id.inner(), community.local, context).await?;
-    verify_is_remote_object(note.id.inner(), context.settings())?;
     verify_person_in_community(&note.attributed_to, &community, context).await?;
-    let (post, _) = note.get_parents(context).await?;
+    if community.commenting_restricted_to_mods {
+      let creator = note.attributed_to.dereference(context).await?;
+      let is_mod =
+        CommunityView::is_mod_or_admin(&mut context.pool(), creator.id, community.id).await?;
+      if !is_mod {
+        return Err(LemmyErrorType::OnlyModsCanCommentInCommunity)?;
+      }
+    }
+    let (post, parent_comment) = note.get_parents(context).await?;
     if post.locked {
       return Err(LemmyErrorType::PostIsLocked)?;
     }
+    if let (Some(parent_comment), false) = (parent_comment, is_mod_action) {
+      if Comment::is_thread_locked(&mut context.pool(), &parent_comment.path).await? {
+        return Err(LemmyErrorType::CommentThreadLocked)?;
+      }
+    }
     Ok(())
   }
 
@@ -153,8 +182,24 @@ impl Object for ApubComment {
   /// If the parent community, post and comment(s) are not known locally, these are also fetched.
   #[tracing::instrument(skip_all)]
   async fn from_json(note: Note, context: &Data<LemmyContext>) -> Result<ApubComment, LemmyError> {
+    // A mod action comes from the mod's instance and only changes the lock, so the rest of the
+    // existing comment must be left untouched.
+    if note.is_mod_action(context).await? {
+      let old_comment = note.id.dereference_local(context).await?;
+      let form = CommentUpdateForm {
+        locked: note.locked,
+        updated: Some(note.updated.map(|u| u.naive_local())),
+        ..Default::default()
+      };
+      let comment = Comment::update(&mut context.pool(), old_comment.id, &form).await?;
+      return Ok(comment.into());
+    }
+
     let creator = note.attributed_to.dereference(context).await?;
     let (post, parent_comment) = note.get_parents(context).await?;
+    let is_new = note.id.dereference_local(context).await.is_err();
+    let (likes, dislikes) = (note.likes.clone(), note.dislikes.clone());
+    let creator_flair = note.creator_flair.clone();
 
     let content = read_from_string_or_source(&note.content, &note.media_type, &note.source);
 
@@ -162,6 +207,17 @@ impl Object for ApubComment {
     let slur_regex = &local_site_opt_to_slur_regex(&local_site);
     let content = remove_slurs(&content, slur_regex);
     let content = sanitize_html(&content);
+    let content_warning = read_content_warning(&note.summary, slur_regex);
+    let nsfw = if content_warning.is_some()
+      && local_site
+        .as_ref()
+        .map(|l| l.content_warning_sets_nsfw)
+        .unwrap_or(false)
+    {
+      Some(true)
+    } else {
+      note.sensitive
+    };
     let language_id =
       LanguageTag::to_language_id_single(note.language, &mut context.pool()).await?;
 
@@ -175,11 +231,35 @@ impl Object for ApubComment {
       deleted: Some(false),
       ap_id: Some(note.id.into()),
       distinguished: note.distinguished,
+      locked: note.locked,
       local: Some(false),
       language_id,
+      content_warning,
+      nsfw,
     };
     let parent_comment_path = parent_comment.map(|t| t.0.path);
     let comment = Comment::create(&mut context.pool(), &form, parent_comment_path.as_ref()).await?;
+
+    let community = Community::read(&mut context.pool(), post.community_id).await?;
+    CreatorFlair::store(
+      creator_flair,
+      comment.ap_id.inner(),
+      &community,
+      creator.id,
+      slur_regex,
+      context,
+    )
+    .await?;
+
+    // Votes from before the comment was first fetched are only available from its collections
+    if is_new && (likes.is_some() || dislikes.is_some()) {
+      spawn_try_task(backfill_votes(
+        comment.ap_id.clone().into(),
+        likes,
+        dislikes,
+        context.reset_request_count(),
+      ));
+    }
     Ok(comment.into())
   }
 }
@@ -281,7 +361,10 @@ pub(crate) mod tests {
     let comment = ApubComment::from_json(json, &context).await.unwrap();
 
     assert_eq!(comment.ap_id, pleroma_url.into());
-    assert_eq!(comment.content.len(), 64);
+    assert_eq!(
+      comment.content,
+      "[@popolon@pleroma.popolon.org](https://pleroma.popolon.org/users/popolon) Have what?"
+    );
     assert!(!comment.local);
     assert_eq!(context.request_count(), 1);
 
@@ -3,6 +3,7 @@ use crate::{
   local_site_data_cached,
   objects::instance::fetch_instance_actor_for_object,
   protocol::{
+    collections::group_followers::GroupFollowersCount,
     objects::{group::Group, Endpoints, LanguageTag},
     ImageObject,
     Source,
@@ -10,7 +11,9 @@ use crate::{
 };
 use activitypub_federation::{
   config::Data,
+  fetch::fetch_object_http,
   kinds::actor::GroupType,
+  protocol::verification::verify_domains_match,
   traits::{Actor, Object},
 };
 use chrono::NaiveDateTime;
@@ -19,6 +22,7 @@ use lemmy_api_common::{
   utils::{generate_featured_url, generate_moderators_url, generate_outbox_url},
 };
 use lemmy_db_schema::{
+  aggregates::structs::CommunityAggregates,
   source::{
     actor_language::CommunityLanguage,
     community::{Community, CommunityUpdateForm},
@@ -110,6 +114,7 @@ impl Object for ApubCommunity {
       published: Some(convert_datetime(self.published)),
       updated: self.updated.map(convert_datetime),
       posting_restricted_to_mods: Some(self.posting_restricted_to_mods),
+      commenting_restricted_to_mods: Some(self.commenting_restricted_to_mods),
       attributed_to: Some(generate_moderators_url(&self.actor_id)?.into()),
     };
     Ok(group)
@@ -141,24 +146,49 @@ impl Object for ApubCommunity {
 
     let community: ApubCommunity = community.into();
 
-    // Fetching mods and outbox is not necessary for Lemmy to work, so ignore errors. Besides,
-    // we need to ignore these errors so that tests can work entirely offline.
+    // Fetching mods, outbox and followers is not necessary for Lemmy to work, so ignore errors.
+    // Besides, we need to ignore these errors so that tests can work entirely offline.
     let fetch_outbox = group.outbox.dereference(&community, context);
+    let fetch_subscribers = update_subscribers(&community, &group.followers, context);
 
     if let Some(moderators) = group.attributed_to {
       let fetch_moderators = moderators.dereference(&community, context);
-      // Fetch mods and outbox in parallel
-      let res = tokio::join!(fetch_outbox, fetch_moderators);
+      // Fetch mods, outbox and followers in parallel
+      let res = tokio::join!(fetch_outbox, fetch_moderators, fetch_subscribers);
       res.0.map_err(|e| debug!("{}", e)).ok();
       res.1.map_err(|e| debug!("{}", e)).ok();
+      res.2.map_err(|e| debug!("{}", e)).ok();
     } else {
-      fetch_outbox.await.map_err(|e| debug!("{}", e)).ok();
+      let res = tokio::join!(fetch_outbox, fetch_subscribers);
+      res.0.map_err(|e| debug!("{}", e)).ok();
+      res.1.map_err(|e| debug!("{}", e)).ok();
     }
 
     Ok(community)
   }
 }
 
+/// Takes the subscribers of a remote community from the size of its followers collection, which
+/// counts the followers on all instances. This runs whenever the community is refreshed.
+async fn update_subscribers(
+  community: &ApubCommunity,
+  followers: &Url,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  if community.local {
+    return Ok(());
+  }
+  verify_domains_match(followers, community.actor_id.inner())?;
+  let collection: GroupFollowersCount = fetch_object_http(followers, context).await?;
+  CommunityAggregates::update_subscribers(
+    &mut context.pool(),
+    community.id,
+    collection.total_items,
+  )
+  .await?;
+  Ok(())
+}
+
 impl Actor for ApubCommunity {
   fn id(&self) -> Url {
     self.actor_id.inner().clone()
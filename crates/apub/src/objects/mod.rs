@@ -1,8 +1,13 @@
-use crate::protocol::Source;
+use crate::{html::html_to_markdown, protocol::Source};
 use activitypub_federation::protocol::values::MediaTypeMarkdownOrHtml;
 use anyhow::anyhow;
-use html2md::parse_html;
-use lemmy_utils::{error::LemmyError, settings::structs::Settings};
+use lemmy_api_common::utils::sanitize_html;
+use lemmy_utils::{
+  error::LemmyError,
+  settings::structs::Settings,
+  utils::{slurs::remove_slurs, validation::CONTENT_WARNING_MAX_LENGTH},
+};
+use regex::Regex;
 use url::Url;
 
 pub mod comment;
@@ -25,7 +30,7 @@ pub(crate) fn read_from_string_or_source(
     content.to_string()
   } else {
     // otherwise, convert content html to markdown
-    parse_html(content)
+    html_to_markdown(content)
   }
 }
 
@@ -39,6 +44,23 @@ pub(crate) fn read_from_string_or_source_opt(
     .map(|content| read_from_string_or_source(content, media_type, source))
 }
 
+/// Reads the content warning of a post or comment from its summary, which is where Mastodon and
+/// others put it.
+pub(crate) fn read_content_warning(
+  summary: &Option<String>,
+  slur_regex: &Option<Regex>,
+) -> Option<String> {
+  let content_warning: String = html_to_markdown(summary.as_deref()?)
+    .trim()
+    .chars()
+    .take(CONTENT_WARNING_MAX_LENGTH)
+    .collect();
+  if content_warning.is_empty() {
+    return None;
+  }
+  Some(sanitize_html(&remove_slurs(&content_warning, slur_regex)))
+}
+
 /// When for example a Post is made in a remote community, the community will send it back,
 /// wrapped in Announce. If we simply receive this like any other federated object, overwrite the
 /// existing, local Post. In particular, it will set the field local = false, so that the object
@@ -57,6 +79,7 @@ pub(crate) mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
+  use super::read_content_warning;
   use activitypub_federation::config::{Data, FederationConfig};
   use anyhow::anyhow;
   use lemmy_api_common::{context::LemmyContext, request::build_user_agent};
@@ -64,6 +87,7 @@ pub(crate) mod tests {
   use lemmy_utils::{
     rate_limit::{RateLimitCell, RateLimitConfig},
     settings::SETTINGS,
+    utils::validation::CONTENT_WARNING_MAX_LENGTH,
   };
   use reqwest::{Client, Request, Response};
   use reqwest_middleware::{ClientBuilder, Middleware, Next};
@@ -113,4 +137,24 @@ pub(crate) mod tests {
       .unwrap();
     config.to_request_data()
   }
+
+  #[test]
+  fn test_read_content_warning() {
+    let summary = Some("<p>Spoilers for the finale</p>".to_string());
+    assert_eq!(
+      Some("Spoilers for the finale".to_string()),
+      read_content_warning(&summary, &None)
+    );
+    assert_eq!(None, read_content_warning(&Some(" ".to_string()), &None));
+    assert_eq!(None, read_content_warning(&None, &None));
+
+    let long_summary = Some("A".repeat(CONTENT_WARNING_MAX_LENGTH + 10));
+    assert_eq!(
+      CONTENT_WARNING_MAX_LENGTH,
+      read_content_warning(&long_summary, &None)
+        .unwrap()
+        .chars()
+        .count()
+    );
+  }
 }
@@ -1,17 +1,26 @@
 use crate::{
   activities::{verify_is_public, verify_person_in_community},
   check_apub_id_valid_with_strictness,
+  fetcher::votes::backfill_votes,
   local_site_data_cached,
-  objects::{read_from_string_or_source_opt, verify_is_remote_object},
+  objects::{
+    read_content_warning,
+    read_from_string_or_source,
+    read_from_string_or_source_opt,
+    verify_is_remote_object,
+  },
   protocol::{
     objects::{
-      page::{Attachment, AttributedTo, Page, PageType},
+      page::{Attachment, AttributedTo, Page, PageType, QuestionOption},
+      CreatorFlair,
       LanguageTag,
     },
+    IdOrNestedObject,
     ImageObject,
     InCommunity,
     Source,
   },
+  verify_nsfw_allowed,
 };
 use activitypub_federation::{
   config::Data,
@@ -26,7 +35,7 @@ use lemmy_api_common::{
   context::LemmyContext,
   request::fetch_site_data,
   utils::{
-    is_mod_or_admin,
+    generate_replies_url,
     local_site_opt_to_sensitive,
     local_site_opt_to_slur_regex,
     sanitize_html,
@@ -37,15 +46,20 @@ use lemmy_db_schema::{
   self,
   source::{
     community::Community,
+    language::Language,
     local_site::LocalSite,
     moderator::{ModLockPost, ModLockPostForm},
     person::Person,
+    poll::Poll,
     post::{Post, PostInsertForm, PostUpdateForm},
+    post_translation::{PostTranslation, PostTranslationForm},
   },
   traits::Crud,
 };
+use lemmy_db_views_actor::structs::CommunityView;
 use lemmy_utils::{
-  error::LemmyError,
+  error::{LemmyError, LemmyErrorType},
+  spawn_try_task,
   utils::{
     markdown::markdown_to_html,
     slurs::{check_slurs_opt, remove_slurs},
@@ -53,7 +67,7 @@ use lemmy_utils::{
     validation::check_url_scheme,
   },
 };
-use std::ops::Deref;
+use std::{collections::HashMap, ops::Deref};
 use url::Url;
 
 const MAX_TITLE_LENGTH: usize = 200;
@@ -116,6 +130,33 @@ impl Object for ApubPost {
     let community_id = self.community_id;
     let community = Community::read(&mut context.pool(), community_id).await?;
     let language = LanguageTag::new_single(self.language_id, &mut context.pool()).await?;
+    let content_map = content_map(&self, &language, context).await?;
+    let creator_flair = CreatorFlair::new(&community, creator_id, &mut context.pool()).await?;
+
+    // The poll is added to the page with the same fields as a Mastodon question
+    let poll = Poll::read_for_post(&mut context.pool(), self.id).await?;
+    let (one_of, any_of, end_time, voters_count) = match poll {
+      Some(poll) => {
+        let (options, voters_count) = QuestionOption::list(&poll, &mut context.pool()).await?;
+        let (one_of, any_of) = if poll.allow_multiple {
+          (None, Some(options))
+        } else {
+          (Some(options), None)
+        };
+        let end_time = poll.ends_at.map(convert_datetime);
+        (one_of, any_of, end_time, voters_count)
+      }
+      None => (None, None, None, None),
+    };
+
+    // Only the home instance of the post knows all of its comments
+    let replies = if self.local {
+      Some(IdOrNestedObject::Id(
+        generate_replies_url(&self.ap_id)?.into(),
+      ))
+    } else {
+      None
+    };
 
     let page = Page {
       kind: PageType::Page,
@@ -125,6 +166,8 @@ impl Object for ApubPost {
       cc: vec![],
       name: Some(self.name.clone()),
       content: self.body.as_ref().map(|b| markdown_to_html(b)),
+      content_map,
+      summary: self.content_warning.clone(),
       media_type: Some(MediaTypeMarkdownOrHtml::Html),
       source: self.body.clone().map(Source::new),
       attachment: self.url.clone().map(Attachment::new).into_iter().collect(),
@@ -136,6 +179,14 @@ impl Object for ApubPost {
       updated: self.updated.map(convert_datetime),
       audience: Some(community.actor_id.into()),
       in_reply_to: None,
+      likes: None,
+      dislikes: None,
+      replies,
+      creator_flair,
+      one_of,
+      any_of,
+      end_time,
+      voters_count,
     };
     Ok(page)
   }
@@ -151,11 +202,21 @@ impl Object for ApubPost {
     if !page.is_mod_action(context).await? {
       verify_domains_match(page.id.inner(), expected_domain)?;
       verify_is_remote_object(page.id.inner(), context.settings())?;
+      verify_nsfw_allowed(page.sensitive, page.id.inner(), context).await?;
     };
 
     let community = page.community(context).await?;
     check_apub_id_valid_with_strictness(page.id.inner(), community.local, context).await?;
     verify_person_in_community(&page.creator()?, &community, context).await?;
+    // Mod actions may edit posts of other users, so only check the creator for new posts
+    if community.posting_restricted_to_mods && !page.is_mod_action(context).await? {
+      let creator = page.creator()?.dereference(context).await?;
+      let is_mod =
+        CommunityView::is_mod_or_admin(&mut context.pool(), creator.id, community.id).await?;
+      if !is_mod {
+        return Err(LemmyErrorType::OnlyModsCanPostInCommunity)?;
+      }
+    }
 
     let local_site_data = local_site_data_cached(&mut context.pool()).await?;
     let slur_regex = &local_site_opt_to_slur_regex(&local_site_data.local_site);
@@ -170,9 +231,10 @@ impl Object for ApubPost {
   async fn from_json(page: Page, context: &Data<Self::DataType>) -> Result<ApubPost, LemmyError> {
     let creator = page.creator()?.dereference(context).await?;
     let community = page.community(context).await?;
-    if community.posting_restricted_to_mods {
-      is_mod_or_admin(&mut context.pool(), creator.id, community.id).await?;
-    }
+    let (likes, dislikes) = (page.likes.clone(), page.dislikes.clone());
+    let content_map = page.content_map.clone();
+    let media_type = page.media_type.clone();
+    let creator_flair = page.creator_flair.clone();
     let mut name = page
       .name
       .clone()
@@ -191,7 +253,8 @@ impl Object for ApubPost {
     // read existing, local post if any (for generating mod log)
     let old_post = page.id.dereference_local(context).await;
 
-    let form = if !page.is_mod_action(context).await? {
+    let is_mod_action = page.is_mod_action(context).await?;
+    let form = if !is_mod_action {
       let first_attachment = page.attachment.into_iter().map(Attachment::url).next();
       let url = if first_attachment.is_some() {
         first_attachment
@@ -226,8 +289,8 @@ impl Object for ApubPost {
       // If no image was included with metadata, use post image instead when available.
       let thumbnail_url = thumbnail.or_else(|| page.image.map(|i| i.url.into()));
 
-      let (embed_title, embed_description, embed_video_url) = metadata_res
-        .map(|u| (u.title, u.description, u.embed_video_url))
+      let (embed_title, embed_description, embed_video_url, embed_provider) = metadata_res
+        .map(|u| (u.title, u.description, u.embed_video_url, u.embed_provider))
         .unwrap_or_default();
       let slur_regex = &local_site_opt_to_slur_regex(&local_site);
 
@@ -237,6 +300,18 @@ impl Object for ApubPost {
       let language_id =
         LanguageTag::to_language_id_single(page.language, &mut context.pool()).await?;
 
+      let content_warning = read_content_warning(&page.summary, slur_regex);
+      let nsfw = if content_warning.is_some()
+        && local_site
+          .as_ref()
+          .map(|l| l.content_warning_sets_nsfw)
+          .unwrap_or(false)
+      {
+        Some(true)
+      } else {
+        page.sensitive
+      };
+
       let name = sanitize_html(&name);
       let embed_title = sanitize_html_opt(&embed_title);
       let embed_description = sanitize_html_opt(&embed_description);
@@ -252,16 +327,19 @@ impl Object for ApubPost {
         published: page.published.map(|u| u.naive_local()),
         updated: page.updated.map(|u| u.naive_local()),
         deleted: Some(false),
-        nsfw: page.sensitive,
+        nsfw,
         embed_title,
         embed_description,
         embed_video_url,
+        embed_provider,
         thumbnail_url,
         ap_id: Some(page.id.clone().into()),
         local: Some(false),
         language_id,
         featured_community: None,
         featured_local: None,
+        archive_url: None,
+        content_warning,
       }
     } else {
       // if is mod action, only update locked/stickied fields, nothing else
@@ -277,6 +355,24 @@ impl Object for ApubPost {
 
     let post = Post::create(&mut context.pool(), &form).await?;
 
+    // Mod actions don't carry the content, so they leave the translations and flair alone
+    if !is_mod_action {
+      let translations =
+        translation_forms(&post, content_map.unwrap_or_default(), &media_type, context).await?;
+      PostTranslation::replace(&mut context.pool(), post.id, translations).await?;
+
+      let local_site = LocalSite::read(&mut context.pool()).await.ok();
+      CreatorFlair::store(
+        creator_flair,
+        post.ap_id.inner(),
+        &community,
+        creator.id,
+        &local_site_opt_to_slur_regex(&local_site),
+        context,
+      )
+      .await?;
+    }
+
     // write mod log entry for lock
     if Page::is_locked_changed(&old_post, &page.comments_enabled) {
       let form = ModLockPostForm {
@@ -287,10 +383,74 @@ impl Object for ApubPost {
       ModLockPost::create(&mut context.pool(), &form).await?;
     }
 
+    // Votes from before the post was first fetched are only available from its collections
+    if old_post.is_err() && (likes.is_some() || dislikes.is_some()) {
+      spawn_try_task(backfill_votes(
+        post.ap_id.clone().into(),
+        likes,
+        dislikes,
+        context.reset_request_count(),
+      ));
+    }
+
     Ok(post.into())
   }
 }
 
+/// The body in the language of the post and in all its translations, keyed by language code.
+/// Posts without translations have none.
+async fn content_map(
+  post: &Post,
+  language: &Option<LanguageTag>,
+  context: &Data<LemmyContext>,
+) -> Result<Option<HashMap<String, String>>, LemmyError> {
+  let translations = PostTranslation::list(&mut context.pool(), post.id).await?;
+  if translations.is_empty() {
+    return Ok(None);
+  }
+  let mut content_map = HashMap::new();
+  if let (Some(body), Some(language)) = (&post.body, language) {
+    content_map.insert(language.identifier.clone(), markdown_to_html(body));
+  }
+  for translation in translations {
+    let language = Language::read_from_id(&mut context.pool(), translation.language_id).await?;
+    content_map.insert(language.code, markdown_to_html(&translation.content));
+  }
+  Ok(Some(content_map))
+}
+
+/// Reads the translations of a remote post from its content map. The entry in the language of
+/// the post is the body itself, so it is skipped, as are unknown languages. Translations are
+/// converted and cleaned like the body.
+async fn translation_forms(
+  post: &Post,
+  content_map: HashMap<String, String>,
+  media_type: &Option<MediaTypeMarkdownOrHtml>,
+  context: &Data<LemmyContext>,
+) -> Result<Vec<PostTranslationForm>, LemmyError> {
+  let local_site = LocalSite::read(&mut context.pool()).await.ok();
+  let slur_regex = local_site_opt_to_slur_regex(&local_site);
+  let mut forms: Vec<PostTranslationForm> = vec![];
+  for (code, content) in content_map {
+    let Some(language_id) = Language::read_id_from_code(&mut context.pool(), Some(&code)).await?
+    else {
+      continue;
+    };
+    if language_id == post.language_id || forms.iter().any(|f| f.language_id == language_id) {
+      continue;
+    }
+    forms.push(PostTranslationForm {
+      post_id: post.id,
+      language_id,
+      content: sanitize_html(&remove_slurs(
+        &read_from_string_or_source(&content, media_type, &None),
+        &slur_regex,
+      )),
+    });
+  }
+  Ok(forms)
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
@@ -316,7 +476,12 @@ mod tests {
     let (person, site) = parse_lemmy_person(&context).await;
     let community = parse_lemmy_community(&context).await;
 
-    let json = file_to_json_object("assets/lemmy/objects/page.json").unwrap();
+    let mut json: Page = file_to_json_object("assets/lemmy/objects/page.json").unwrap();
+    // Translations are html like the content, and cleaned the same way
+    json.content_map.as_mut().unwrap().insert(
+      "es".to_string(),
+      "<p>Hola <strong>mundo</strong><script>alert(1)</script></p>".to_string(),
+    );
     let url = Url::parse("https://enterprise.lemmy.ml/post/55143").unwrap();
     ApubPost::verify(&json, &url, &context).await.unwrap();
     let post = ApubPost::from_json(json, &context).await.unwrap();
@@ -329,6 +494,17 @@ mod tests {
     assert!(!post.featured_community);
     assert_eq!(context.request_count(), 0);
 
+    // The french entry of the content map is the body itself
+    let translations = PostTranslation::list(&mut context.pool(), post.id)
+      .await
+      .unwrap();
+    assert_eq!(translations.len(), 2);
+    assert!(translations.iter().any(|t| t
+      .content
+      .starts_with("Dies ist ein Beitrag in der Community tenforward")));
+    assert!(translations.iter().any(|t| t.content.contains("**mundo**")));
+    assert!(translations.iter().all(|t| !t.content.contains("<script")));
+
     Post::delete(&mut context.pool(), post.id).await.unwrap();
     Person::delete(&mut context.pool(), person.id)
       .await
@@ -42,6 +42,17 @@ pub struct Delete {
   pub(crate) summary: Option<String>,
 }
 
+impl Delete {
+  /// Returns the reason given by the mod if this is a removal, or `None` for a user deleting their
+  /// own content. Removals without a reason are sent with an empty summary.
+  pub(crate) fn removal_reason(&self) -> Option<Option<String>> {
+    self
+      .summary
+      .as_ref()
+      .map(|reason| Some(reason.clone()).filter(|r| !r.is_empty()))
+  }
+}
+
 #[async_trait::async_trait]
 impl InCommunity for Delete {
   async fn community(&self, context: &Data<LemmyContext>) -> Result<ApubCommunity, LemmyError> {
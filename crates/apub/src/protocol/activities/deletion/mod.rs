@@ -31,4 +31,32 @@ mod tests {
     test_parse_lemmy_item::<DeleteUser>("assets/lemmy/activities/deletion/delete_user.json")
       .unwrap();
   }
+
+  #[test]
+  fn test_parse_removal_reason() {
+    let remove =
+      test_parse_lemmy_item::<Delete>("assets/lemmy/activities/deletion/remove_note.json").unwrap();
+    assert_eq!(
+      Some(Some("bad comment".to_string())),
+      remove.removal_reason()
+    );
+
+    let remove = test_parse_lemmy_item::<Delete>(
+      "assets/lemmy/activities/deletion/remove_note_without_reason.json",
+    )
+    .unwrap();
+    assert_eq!(Some(None), remove.removal_reason());
+
+    let undo_remove =
+      test_parse_lemmy_item::<UndoDelete>("assets/lemmy/activities/deletion/undo_remove_note.json")
+        .unwrap();
+    assert_eq!(
+      Some(Some("bad comment".to_string())),
+      undo_remove.object.removal_reason()
+    );
+
+    let delete =
+      test_parse_lemmy_item::<Delete>("assets/lemmy/activities/deletion/delete_page.json").unwrap();
+    assert_eq!(None, delete.removal_reason());
+  }
 }
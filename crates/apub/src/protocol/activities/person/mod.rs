@@ -0,0 +1,15 @@
+pub mod update;
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::protocol::{activities::person::update::UpdatePerson, tests::test_parse_lemmy_item};
+
+  #[test]
+  fn test_parse_lemmy_person_activities() {
+    test_parse_lemmy_item::<UpdatePerson>("assets/lemmy/activities/person/update_person.json")
+      .unwrap();
+  }
+}
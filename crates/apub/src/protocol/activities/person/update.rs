@@ -0,0 +1,25 @@
+use crate::{objects::person::ApubPerson, protocol::objects::person::Person};
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::UpdateType,
+  protocol::helpers::deserialize_one_or_many,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// This activity is sent when the profile of a local user changes, so that remote instances
+/// refresh their copy of it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePerson {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: Box<Person>,
+  #[serde(deserialize_with = "deserialize_one_or_many", default)]
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) cc: Vec<Url>,
+  #[serde(rename = "type")]
+  pub(crate) kind: UpdateType,
+  pub(crate) id: Url,
+}
@@ -15,6 +15,13 @@ pub(crate) struct GroupFollowers {
   items: Vec<()>,
 }
 
+/// The size of the followers collection of a remote community, whatever its items are.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GroupFollowersCount {
+  pub(crate) total_items: i64,
+}
+
 impl GroupFollowers {
   pub(crate) async fn new(
     community: Community,
@@ -0,0 +1,37 @@
+use crate::protocol::{Id, IdOrNestedObject};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+/// The likes, dislikes or replies collection of a post or comment. Depending on the software,
+/// items may be listed directly or split into pages.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ItemCollection {
+  pub(crate) id: Url,
+  pub(crate) total_items: Option<i32>,
+  pub(crate) first: Option<IdOrNestedObject<ItemCollectionPage>>,
+  #[serde(default, alias = "items")]
+  pub(crate) ordered_items: Vec<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ItemCollectionPage {
+  pub(crate) id: Url,
+  pub(crate) next: Option<Url>,
+  #[serde(default, alias = "items")]
+  pub(crate) ordered_items: Vec<Value>,
+}
+
+impl Id for ItemCollection {
+  fn object_id(&self) -> &Url {
+    &self.id
+  }
+}
+
+impl Id for ItemCollectionPage {
+  fn object_id(&self) -> &Url {
+    &self.id
+  }
+}
@@ -3,6 +3,8 @@ pub(crate) mod group_featured;
 pub(crate) mod group_followers;
 pub(crate) mod group_moderators;
 pub(crate) mod group_outbox;
+pub(crate) mod item_collection;
+pub(crate) mod post_replies;
 
 #[cfg(test)]
 mod tests {
@@ -16,6 +18,8 @@ mod tests {
       group_followers::GroupFollowers,
       group_moderators::GroupModerators,
       group_outbox::GroupOutbox,
+      item_collection::ItemCollection,
+      post_replies::PostReplies,
     },
     tests::{test_json, test_parse_lemmy_item},
   };
@@ -32,10 +36,16 @@ mod tests {
     test_parse_lemmy_item::<GroupModerators>("assets/lemmy/collections/group_moderators.json")
       .unwrap();
     test_parse_lemmy_item::<EmptyOutbox>("assets/lemmy/collections/person_outbox.json").unwrap();
+    let replies =
+      test_parse_lemmy_item::<PostReplies>("assets/lemmy/collections/post_replies.json").unwrap();
+    assert_eq!(replies.ordered_items.len() as i32, replies.total_items);
   }
 
   #[test]
   fn test_parse_mastodon_collections() {
     test_json::<GroupFeatured>("assets/mastodon/collections/featured.json").unwrap();
+    let likes = test_json::<ItemCollection>("assets/mastodon/collections/likes.json").unwrap();
+    assert_eq!(likes.inner().total_items, Some(3));
+    assert!(likes.inner().ordered_items.is_empty());
   }
 }
@@ -12,6 +12,7 @@ use crate::{
     ImageObject,
     Source,
   },
+  verify_nsfw_allowed,
 };
 use activitypub_federation::{
   fetch::{collection_id::CollectionId, object_id::ObjectId},
@@ -68,6 +69,8 @@ pub struct Group {
   pub(crate) attributed_to: Option<CollectionId<ApubCommunityModerators>>,
   // lemmy extension
   pub(crate) posting_restricted_to_mods: Option<bool>,
+  // lemmy extension
+  pub(crate) commenting_restricted_to_mods: Option<bool>,
   pub(crate) outbox: CollectionId<ApubCommunityOutbox>,
   pub(crate) endpoints: Option<Endpoints>,
   pub(crate) featured: Option<CollectionId<ApubCommunityFeatured>>,
@@ -93,6 +96,7 @@ impl Group {
     check_slurs_opt(&self.name, slur_regex)?;
     let description = read_from_string_or_source_opt(&self.summary, &None, &self.source);
     check_slurs_opt(&description, slur_regex)?;
+    verify_nsfw_allowed(self.sensitive, self.id.inner(), context).await?;
     Ok(())
   }
 
@@ -124,8 +128,13 @@ impl Group {
       shared_inbox_url: self.endpoints.map(|e| e.shared_inbox.into()),
       moderators_url: self.attributed_to.map(Into::into),
       posting_restricted_to_mods: self.posting_restricted_to_mods,
+      commenting_restricted_to_mods: self.commenting_restricted_to_mods,
       instance_id,
       featured_url: self.featured.map(Into::into),
+      default_post_sort: None,
+      default_comment_sort: None,
+      min_account_age_days_to_vote: None,
+      only_followers_can_vote: None,
     }
   }
 
@@ -155,7 +164,14 @@ impl Group {
       shared_inbox_url: Some(self.endpoints.map(|e| e.shared_inbox.into())),
       moderators_url: self.attributed_to.map(Into::into),
       posting_restricted_to_mods: self.posting_restricted_to_mods,
+      commenting_restricted_to_mods: self.commenting_restricted_to_mods,
       featured_url: self.featured.map(Into::into),
+      default_post_sort: None,
+      default_comment_sort: None,
+      min_account_age_days_to_vote: None,
+      only_followers_can_vote: None,
+      // Verification is up to the admins of each instance
+      verified: None,
     }
   }
 }
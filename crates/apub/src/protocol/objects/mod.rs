@@ -1,11 +1,32 @@
+use crate::objects::person::ApubPerson;
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
+use lemmy_api_common::{
+  context::LemmyContext,
+  lemmy_db_views_actor::structs::CommunityModeratorView,
+  utils::sanitize_html,
+};
 use lemmy_db_schema::{
   impls::actor_language::UNDETERMINED_ID,
-  newtypes::LanguageId,
-  source::language::Language,
+  newtypes::{LanguageId, PersonId},
+  source::{
+    community::Community,
+    community_flair::{CommunityPersonFlair, CommunityPersonFlairForm},
+    language::Language,
+    person::Person,
+  },
+  traits::Crud,
   utils::DbPool,
 };
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::{
+  error::LemmyError,
+  utils::{
+    slurs::check_slurs,
+    validation::{clean_flair, is_valid_flair_color},
+  },
+};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use url::Url;
 
 pub(crate) mod chat_message;
@@ -93,23 +114,278 @@ impl LanguageTag {
   }
 }
 
+/// The flair of the author in the community of a post or comment. Lemmy extension.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CreatorFlair {
+  pub(crate) name: String,
+  pub(crate) color: Option<String>,
+  /// The moderator who assigned the flair, or the author if they picked it themselves
+  pub(crate) set_by: Option<ObjectId<ApubPerson>>,
+}
+
+impl CreatorFlair {
+  /// Flairs belong to the instance of the community, so only those of local communities are sent.
+  pub(crate) async fn new(
+    community: &Community,
+    creator_id: PersonId,
+    pool: &mut DbPool<'_>,
+  ) -> Result<Option<CreatorFlair>, LemmyError> {
+    if !community.local {
+      return Ok(None);
+    }
+    let Some(flair) = CommunityPersonFlair::read(pool, community.id, creator_id).await? else {
+      return Ok(None);
+    };
+    let set_by = match flair.set_by_id {
+      Some(set_by_id) => Some(Person::read(pool, set_by_id).await?.actor_id.into()),
+      None => None,
+    };
+    Ok(Some(CreatorFlair {
+      name: flair.flair,
+      color: flair.color,
+      set_by,
+    }))
+  }
+
+  /// Stores the flair which a remote community sent along with a post or comment. A flair is
+  /// only accepted if one of the moderators of the community assigned it, and the object comes
+  /// from the instance of that moderator. Flairs which the author picked from those offered by
+  /// the community can't be checked against the moderators, so they need to come from the
+  /// instance of the community. Invalid flairs and those with slurs are dropped.
+  pub(crate) async fn store(
+    flair: Option<Self>,
+    object_id: &Url,
+    community: &Community,
+    creator_id: PersonId,
+    slur_regex: &Option<Regex>,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    if community.local {
+      return Ok(());
+    }
+    let from_community_instance = object_id.domain() == community.actor_id.inner().domain();
+    let Some(flair) = flair else {
+      // Only the instance of the community knows that the author has no flair
+      if from_community_instance {
+        CommunityPersonFlair::remove(&mut context.pool(), community.id, creator_id).await?;
+      }
+      return Ok(());
+    };
+    if !flair
+      .is_verified(
+        object_id,
+        community,
+        creator_id,
+        from_community_instance,
+        context,
+      )
+      .await?
+    {
+      return Ok(());
+    }
+    match flair.checked(slur_regex) {
+      Some(flair) => {
+        let form = CommunityPersonFlairForm {
+          community_id: community.id,
+          person_id: creator_id,
+          flair: flair.name,
+          color: flair.color,
+          set_by_id: None,
+        };
+        CommunityPersonFlair::set(&mut context.pool(), &form).await?;
+      }
+      None => {
+        CommunityPersonFlair::remove(&mut context.pool(), community.id, creator_id).await?;
+      }
+    }
+    Ok(())
+  }
+
+  async fn is_verified(
+    &self,
+    object_id: &Url,
+    community: &Community,
+    creator_id: PersonId,
+    from_community_instance: bool,
+    context: &Data<LemmyContext>,
+  ) -> Result<bool, LemmyError> {
+    let Some(set_by) = &self.set_by else {
+      return Ok(false);
+    };
+    if set_by.inner().domain() != object_id.domain() {
+      return Ok(false);
+    }
+    // Only known persons are checked, the moderators were received with the community
+    let Ok(set_by) = set_by.dereference_local(context).await else {
+      return Ok(false);
+    };
+    if set_by.id == creator_id {
+      return Ok(from_community_instance);
+    }
+    Ok(
+      CommunityModeratorView::is_community_moderator(&mut context.pool(), community.id, set_by.id)
+        .await?,
+    )
+  }
+
+  fn checked(self, slur_regex: &Option<Regex>) -> Option<Self> {
+    let name = clean_flair(&self.name).ok()?;
+    check_slurs(&name, slur_regex).ok()?;
+    is_valid_flair_color(&self.color).ok()?;
+    Some(CreatorFlair {
+      name: sanitize_html(&name),
+      color: self.color,
+      set_by: self.set_by,
+    })
+  }
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
-  use crate::protocol::{
+  use super::CreatorFlair;
+  use crate::{
     objects::{
-      chat_message::ChatMessage,
-      group::Group,
-      instance::Instance,
-      note::Note,
-      page::Page,
-      person::Person,
-      tombstone::Tombstone,
+      community::tests::parse_lemmy_community,
+      person::tests::parse_lemmy_person,
+      tests::init_context,
     },
-    tests::{test_json, test_parse_lemmy_item},
+    protocol::{
+      objects::{
+        chat_message::ChatMessage,
+        group::Group,
+        instance::Instance,
+        note::Note,
+        page::Page,
+        person::Person,
+        tombstone::Tombstone,
+      },
+      tests::{test_json, test_parse_lemmy_item},
+    },
+  };
+  use activitypub_federation::config::Data;
+  use lemmy_api_common::context::LemmyContext;
+  use lemmy_db_schema::{
+    newtypes::{DbUrl, PersonId},
+    source::{
+      community::{Community as DbCommunity, CommunityModerator, CommunityModeratorForm},
+      community_flair::CommunityPersonFlair,
+      instance::Instance as DbInstance,
+      person::{Person as DbPerson, PersonInsertForm},
+      site::Site,
+    },
+    traits::{Crud, Joinable},
   };
+  use serial_test::serial;
+  use url::Url;
+
+  /// Stores the flair of the creator, and returns the flair which is stored afterwards.
+  async fn store_flair(
+    flair: Option<(&str, &DbUrl)>,
+    object_id: &str,
+    community: &DbCommunity,
+    creator_id: PersonId,
+    context: &Data<LemmyContext>,
+  ) -> Option<String> {
+    let flair = flair.map(|(name, set_by)| CreatorFlair {
+      name: name.to_string(),
+      color: None,
+      set_by: Some(set_by.clone().into()),
+    });
+    let object_id = Url::parse(object_id).unwrap();
+    CreatorFlair::store(flair, &object_id, community, creator_id, &None, context)
+      .await
+      .unwrap();
+    CommunityPersonFlair::read(&mut context.pool(), community.id, creator_id)
+      .await
+      .unwrap()
+      .map(|f| f.flair)
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_store_creator_flair() {
+    let context = init_context().await;
+    let (picard, site) = parse_lemmy_person(&context).await;
+    let community = parse_lemmy_community(&context).await;
+    let instance = DbInstance::read_or_create(&mut context.pool(), "other.tld".to_string())
+      .await
+      .unwrap();
+    let form = PersonInsertForm::builder()
+      .name("mallory".to_string())
+      .actor_id(Some(
+        Url::parse("https://other.tld/u/mallory").unwrap().into(),
+      ))
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let mallory = DbPerson::create(&mut context.pool(), &form).await.unwrap();
+    let community_object = "https://enterprise.lemmy.ml/post/1";
+    let other_object = "https://other.tld/post/1";
+    let store = |flair, object_id| store_flair(flair, object_id, &community, picard.id, &context);
+
+    // A flair which the author picked is accepted from the instance of the community
+    let captain = Some("Captain".to_string());
+    assert_eq!(
+      captain,
+      store(Some(("Captain", &picard.actor_id)), community_object).await
+    );
+
+    // Other instances can't assign flairs, unless their user moderates the community
+    assert_eq!(
+      captain,
+      store(Some(("Admiral", &mallory.actor_id)), other_object).await
+    );
+    assert_eq!(
+      captain,
+      store(Some(("Admiral", &picard.actor_id)), other_object).await
+    );
+    let form = CommunityModeratorForm {
+      community_id: community.id,
+      person_id: mallory.id,
+    };
+    CommunityModerator::join(&mut context.pool(), &form)
+      .await
+      .unwrap();
+    // The moderator's own instance has to send it
+    assert_eq!(
+      captain,
+      store(Some(("Admiral", &mallory.actor_id)), community_object).await
+    );
+    let admiral = Some("Admiral".to_string());
+    assert_eq!(
+      admiral,
+      store(Some(("Admiral", &mallory.actor_id)), other_object).await
+    );
+
+    // Only the instance of the community can tell that there is no flair
+    assert_eq!(admiral, store(None, other_object).await);
+    assert_eq!(None, store(None, community_object).await);
+
+    // Nothing is sent for remote communities
+    let sent = CreatorFlair::new(&community, picard.id, &mut context.pool())
+      .await
+      .unwrap();
+    assert_eq!(None, sent);
+
+    DbCommunity::delete(&mut context.pool(), community.id)
+      .await
+      .unwrap();
+    DbPerson::delete(&mut context.pool(), mallory.id)
+      .await
+      .unwrap();
+    DbPerson::delete(&mut context.pool(), picard.id)
+      .await
+      .unwrap();
+    DbInstance::delete(&mut context.pool(), instance.id)
+      .await
+      .unwrap();
+    Site::delete(&mut context.pool(), site.id).await.unwrap();
+  }
 
   #[test]
   fn test_parse_objects_lemmy() {
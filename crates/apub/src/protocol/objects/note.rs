@@ -3,7 +3,13 @@ use crate::{
   fetcher::post_or_comment::PostOrComment,
   mentions::MentionOrValue,
   objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson, post::ApubPost},
-  protocol::{objects::LanguageTag, InCommunity, Source},
+  protocol::{
+    collections::item_collection::ItemCollection,
+    objects::{CreatorFlair, LanguageTag},
+    IdOrNestedObject,
+    InCommunity,
+    Source,
+  },
 };
 use activitypub_federation::{
   config::Data,
@@ -38,6 +44,10 @@ pub struct Note {
   #[serde(deserialize_with = "deserialize_one_or_many", default)]
   pub(crate) cc: Vec<Url>,
   pub(crate) content: String,
+  /// The content warning
+  pub(crate) summary: Option<String>,
+  /// Whether the comment is NSFW
+  pub(crate) sensitive: Option<bool>,
   pub(crate) in_reply_to: ObjectId<PostOrComment>,
 
   pub(crate) media_type: Option<MediaTypeMarkdownOrHtml>,
@@ -49,8 +59,18 @@ pub struct Note {
   pub(crate) tag: Vec<MentionOrValue>,
   // lemmy extension
   pub(crate) distinguished: Option<bool>,
+  /// Replies to the comment and to its children are rejected
+  pub(crate) locked: Option<bool>,
   pub(crate) language: Option<LanguageTag>,
   pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+  /// Collections of the votes on the comment, published by some software other than Lemmy
+  pub(crate) likes: Option<Url>,
+  pub(crate) dislikes: Option<Url>,
+  /// The replies to the comment, published by some software other than Lemmy. Lemmy lists all
+  /// comments in the replies collection of the post instead.
+  pub(crate) replies: Option<IdOrNestedObject<ItemCollection>>,
+  /// The flair of the author in the community, sent by Lemmy communities
+  pub(crate) creator_flair: Option<CreatorFlair>,
 }
 
 impl Note {
@@ -69,6 +89,19 @@ impl Note {
       }
     }
   }
+
+  /// Locking or unlocking the comment can only be done by mods, who may be on a different instance
+  /// from the comment author.
+  pub(crate) async fn is_mod_action(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<bool, LemmyError> {
+    let old_comment = self.id.clone().dereference_local(context).await;
+    if let (Some(locked), Ok(old_comment)) = (self.locked, old_comment) {
+      return Ok(locked != old_comment.locked);
+    }
+    Ok(false)
+  }
 }
 
 #[async_trait::async_trait]
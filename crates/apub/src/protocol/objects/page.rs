@@ -2,14 +2,22 @@ use crate::{
   activities::verify_community_matches,
   fetcher::user_or_community::{PersonOrGroupType, UserOrCommunity},
   objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
-  protocol::{objects::LanguageTag, ImageObject, InCommunity, Source},
+  protocol::{
+    collections::item_collection::ItemCollection,
+    objects::{CreatorFlair, LanguageTag},
+    IdOrNestedObject,
+    ImageObject,
+    InCommunity,
+    Source,
+  },
 };
 use activitypub_federation::{
   config::Data,
   fetch::object_id::ObjectId,
   kinds::{
+    collection::CollectionType,
     link::LinkType,
-    object::{DocumentType, ImageType},
+    object::{DocumentType, ImageType, NoteType},
   },
   protocol::{
     helpers::{deserialize_one_or_many, deserialize_skip_error},
@@ -20,10 +28,15 @@ use activitypub_federation::{
 use chrono::{DateTime, FixedOffset};
 use itertools::Itertools;
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::newtypes::DbUrl;
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::poll::{Poll, PollOption, PollVote},
+  utils::DbPool,
+};
 use lemmy_utils::error::{LemmyError, LemmyErrorType};
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
 use serde_with::skip_serializing_none;
+use std::collections::HashMap;
 use url::Url;
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -53,6 +66,10 @@ pub struct Page {
   #[serde(deserialize_with = "deserialize_one_or_many", default)]
   pub(crate) cc: Vec<Url>,
   pub(crate) content: Option<String>,
+  /// The content in each language, keyed by language code
+  pub(crate) content_map: Option<HashMap<String, String>>,
+  /// The content warning
+  pub(crate) summary: Option<String>,
   pub(crate) media_type: Option<MediaTypeMarkdownOrHtml>,
   #[serde(deserialize_with = "deserialize_skip_error", default)]
   pub(crate) source: Option<Source>,
@@ -67,6 +84,80 @@ pub struct Page {
   pub(crate) updated: Option<DateTime<FixedOffset>>,
   pub(crate) language: Option<LanguageTag>,
   pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+  /// Collections of the votes on the post, published by some software other than Lemmy
+  pub(crate) likes: Option<Url>,
+  pub(crate) dislikes: Option<Url>,
+  /// The comments on the post, which are read when the post is resynced
+  pub(crate) replies: Option<IdOrNestedObject<ItemCollection>>,
+  /// The flair of the author in the community, sent by Lemmy communities
+  pub(crate) creator_flair: Option<CreatorFlair>,
+  /// The options of a poll which allows a single choice. Posts with a poll are still sent as
+  /// `Page`, with the poll as an extension in the same format as Mastodon's `Question`.
+  pub(crate) one_of: Option<Vec<QuestionOption>>,
+  /// The options of a poll which allows multiple choices
+  pub(crate) any_of: Option<Vec<QuestionOption>>,
+  pub(crate) end_time: Option<DateTime<FixedOffset>>,
+  pub(crate) voters_count: Option<i64>,
+}
+
+/// A poll option of a post, in the format which Mastodon uses for `Question`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QuestionOption {
+  #[serde(rename = "type")]
+  pub(crate) kind: NoteType,
+  pub(crate) name: String,
+  /// The number of votes, left out while the poll hides its results
+  pub(crate) replies: Option<QuestionOptionVotes>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QuestionOptionVotes {
+  #[serde(rename = "type")]
+  pub(crate) kind: CollectionType,
+  pub(crate) total_items: i64,
+}
+
+impl QuestionOption {
+  /// Reads the options of a poll along with the number of voters. Only local votes are counted,
+  /// as voting from other instances isn't supported.
+  pub(crate) async fn list(
+    poll: &Poll,
+    pool: &mut DbPool<'_>,
+  ) -> Result<(Vec<Self>, Option<i64>), LemmyError> {
+    let options = PollOption::list_for_poll(pool, poll.id).await?;
+    if poll.hide_results_until_voted && !poll.has_ended() {
+      let options = options
+        .into_iter()
+        .map(|o| QuestionOption {
+          kind: NoteType::Note,
+          name: o.name,
+          replies: None,
+        })
+        .collect();
+      return Ok((options, None));
+    }
+
+    let counts = PollVote::count_by_option(pool, poll.id)
+      .await?
+      .into_iter()
+      .collect::<HashMap<_, _>>();
+    let voters = PollVote::count_voters(pool, poll.id).await?;
+    let options = options
+      .into_iter()
+      .map(|o| QuestionOption {
+        kind: NoteType::Note,
+        replies: Some(QuestionOptionVotes {
+          kind: CollectionType::Collection,
+          total_items: counts.get(&o.id).copied().unwrap_or(0),
+        }),
+        name: o.name,
+      })
+      .collect();
+    Ok((options, Some(voters)))
+  }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
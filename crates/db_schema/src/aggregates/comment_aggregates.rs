@@ -6,6 +6,7 @@ use crate::{
 };
 use diesel::{result::Error, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
+use diesel_ltree::Ltree;
 
 impl CommentAggregates {
   pub async fn read(pool: &mut DbPool<'_>, comment_id: CommentId) -> Result<Self, Error> {
@@ -16,6 +17,45 @@ impl CommentAggregates {
       .await
   }
 
+  /// The child counts of all comments above the comment with the given path, starting at the top
+  /// level comment.
+  pub async fn read_ancestor_child_counts(
+    pool: &mut DbPool<'_>,
+    path: &Ltree,
+  ) -> Result<Vec<(CommentId, i64)>, Error> {
+    // The path starts with 0, and ends with the comment itself
+    let ancestor_ids: Vec<CommentId> = path
+      .0
+      .split('.')
+      .skip(1)
+      .filter_map(|id| id.parse().ok().map(CommentId))
+      .collect::<Vec<_>>()
+      .split_last()
+      .map(|(_, ancestors)| ancestors.to_vec())
+      .unwrap_or_default();
+
+    let conn = &mut get_conn(pool).await?;
+    let counts: Vec<(CommentId, i32)> = comment_aggregates::table
+      .filter(comment_aggregates::comment_id.eq_any(&ancestor_ids))
+      .select((
+        comment_aggregates::comment_id,
+        comment_aggregates::child_count,
+      ))
+      .load(conn)
+      .await?;
+    Ok(
+      ancestor_ids
+        .iter()
+        .filter_map(|id| {
+          counts
+            .iter()
+            .find(|(c, _)| c == id)
+            .map(|(c, count)| (*c, i64::from(*count)))
+        })
+        .collect(),
+    )
+  }
+
   pub async fn update_hot_rank(
     pool: &mut DbPool<'_>,
     comment_id: CommentId,
@@ -109,10 +149,21 @@ mod tests {
       .post_id(inserted_post.id)
       .build();
 
-    let _inserted_child_comment =
-      Comment::create(pool, &child_comment_form, Some(&inserted_comment.path))
+    let (inserted_child_comment, ancestor_counts) =
+      Comment::create_with_ancestor_counts(pool, &child_comment_form, Some(&inserted_comment.path))
+        .await
+        .unwrap();
+    assert_eq!(vec![(inserted_comment.id, 1)], ancestor_counts);
+    let read_ancestor_counts =
+      CommentAggregates::read_ancestor_child_counts(pool, &inserted_child_comment.path)
+        .await
+        .unwrap();
+    assert_eq!(ancestor_counts, read_ancestor_counts);
+    let top_level_ancestor_counts =
+      CommentAggregates::read_ancestor_child_counts(pool, &inserted_comment.path)
         .await
         .unwrap();
+    assert!(top_level_ancestor_counts.is_empty());
 
     let comment_like = CommentLikeForm {
       comment_id: inserted_comment.id,
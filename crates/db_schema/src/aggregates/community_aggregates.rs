@@ -15,6 +15,21 @@ impl CommunityAggregates {
       .first::<Self>(conn)
       .await
   }
+
+  /// Sets the total subscribers of a remote community, as reported by its instance.
+  pub async fn update_subscribers(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+    subscribers: i64,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      community_aggregates::table.filter(community_aggregates::community_id.eq(community_id)),
+    )
+    .set(community_aggregates::subscribers.eq(subscribers.max(0)))
+    .get_result::<Self>(conn)
+    .await
+  }
 }
 
 #[cfg(test)]
@@ -142,6 +157,7 @@ mod tests {
       .unwrap();
 
     assert_eq!(2, community_aggregates_before_delete.subscribers);
+    assert_eq!(2, community_aggregates_before_delete.subscribers_local);
     assert_eq!(1, community_aggregates_before_delete.posts);
     assert_eq!(2, community_aggregates_before_delete.comments);
 
@@ -171,6 +187,40 @@ mod tests {
       .unwrap();
     assert_eq!(2, after_follow_again.subscribers);
 
+    // Pending follows are only counted once they are accepted
+    let pending_follow = CommunityFollowerForm {
+      community_id: another_inserted_community.id,
+      person_id: another_inserted_person.id,
+      pending: true,
+    };
+    CommunityFollower::follow(pool, &pending_follow)
+      .await
+      .unwrap();
+    let after_pending_follow = CommunityAggregates::read(pool, another_inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(1, after_pending_follow.subscribers);
+    CommunityFollower::follow_accepted(
+      pool,
+      another_inserted_community.id,
+      another_inserted_person.id,
+    )
+    .await
+    .unwrap();
+    let after_accepted_follow = CommunityAggregates::read(pool, another_inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(2, after_accepted_follow.subscribers);
+    assert_eq!(2, after_accepted_follow.subscribers_local);
+
+    // The total of remote communities comes from their instance
+    let after_subscribers_update =
+      CommunityAggregates::update_subscribers(pool, another_inserted_community.id, 5200)
+        .await
+        .unwrap();
+    assert_eq!(5200, after_subscribers_update.subscribers);
+    assert_eq!(2, after_subscribers_update.subscribers_local);
+
     // Remove a parent post (the comment count should also be 0)
     Post::delete(pool, inserted_post.id).await.unwrap();
     let after_parent_post_delete = CommunityAggregates::read(pool, inserted_community.id)
@@ -187,6 +237,7 @@ mod tests {
       .await
       .unwrap();
     assert_eq!(1, after_person_delete.subscribers);
+    assert_eq!(1, after_person_delete.subscribers_local);
 
     // This should delete all the associated rows, and fire triggers
     let person_num_deleted = Person::delete(pool, inserted_person.id).await.unwrap();
@@ -43,6 +43,8 @@ pub struct CommentAggregates {
 pub struct CommunityAggregates {
   pub id: i32,
   pub community_id: CommunityId,
+  /// The followers on all instances. For remote communities, this is refreshed from their
+  /// followers collection.
   pub subscribers: i64,
   pub posts: i64,
   pub comments: i64,
@@ -56,6 +58,8 @@ pub struct CommunityAggregates {
   /// The number of users with any activity in the last year.
   pub users_active_half_year: i64,
   pub hot_rank: i32,
+  /// The followers on this instance.
+  pub subscribers_local: i64,
 }
 
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Default)]
@@ -1,11 +1,17 @@
 use crate::{
   diesel::OptionalExtension,
   newtypes::DbUrl,
-  source::activity::{ReceivedActivity, SentActivity, SentActivityForm},
+  source::activity::{
+    ReceivedActivity,
+    SentActivity,
+    SentActivityDelivery,
+    SentActivityDeliveryForm,
+    SentActivityForm,
+  },
   utils::{get_conn, DbPool},
 };
 use diesel::{
-  dsl::insert_into,
+  dsl::{insert_into, now},
   result::{DatabaseErrorKind, Error, Error::DatabaseError},
   ExpressionMethods,
   QueryDsl,
@@ -30,14 +36,90 @@ impl SentActivity {
       .first::<Self>(conn)
       .await
   }
+
+  /// All retained activities which refer to the given object, oldest first.
+  pub async fn list_for_object(
+    pool: &mut DbPool<'_>,
+    for_object_id: &DbUrl,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::sent_activity::dsl::{object_id, published, sent_activity};
+    let conn = &mut get_conn(pool).await?;
+    sent_activity
+      .filter(object_id.eq(for_object_id))
+      .order_by(published.asc())
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+impl SentActivityDelivery {
+  pub async fn create_many(
+    pool: &mut DbPool<'_>,
+    forms: &[SentActivityDeliveryForm],
+  ) -> Result<usize, Error> {
+    use crate::schema::sent_activity_delivery::dsl::sent_activity_delivery;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(sent_activity_delivery)
+      .values(forms)
+      .execute(conn)
+      .await
+  }
+
+  pub async fn list_for_activity(
+    pool: &mut DbPool<'_>,
+    for_sent_activity_id: i64,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::sent_activity_delivery::dsl::{
+      inbox,
+      sent_activity_delivery,
+      sent_activity_id,
+    };
+    let conn = &mut get_conn(pool).await?;
+    sent_activity_delivery
+      .filter(sent_activity_id.eq(for_sent_activity_id))
+      .order_by(inbox.asc())
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Records a resend attempt of the retry queue for the delivery of the given activity to the
+  /// inbox. The error is cleared when the attempt succeeded.
+  pub async fn record_attempt(
+    pool: &mut DbPool<'_>,
+    activity_ap_id: &DbUrl,
+    for_inbox: &DbUrl,
+    error: Option<String>,
+  ) -> Result<usize, Error> {
+    use crate::schema::{sent_activity, sent_activity_delivery};
+    let conn = &mut get_conn(pool).await?;
+    let activity_ids = sent_activity::table
+      .filter(sent_activity::ap_id.eq(activity_ap_id))
+      .select(sent_activity::id);
+    diesel::update(
+      sent_activity_delivery::table
+        .filter(sent_activity_delivery::sent_activity_id.eq_any(activity_ids))
+        .filter(sent_activity_delivery::inbox.eq(for_inbox)),
+    )
+    .set((
+      sent_activity_delivery::attempts.eq(sent_activity_delivery::attempts + 1),
+      sent_activity_delivery::last_attempt_at.eq(now),
+      sent_activity_delivery::last_error.eq(error),
+    ))
+    .execute(conn)
+    .await
+  }
 }
 
 impl ReceivedActivity {
-  pub async fn create(pool: &mut DbPool<'_>, ap_id_: &DbUrl) -> Result<(), Error> {
-    use crate::schema::received_activity::dsl::{ap_id, id, received_activity};
+  pub async fn create(
+    pool: &mut DbPool<'_>,
+    ap_id_: &DbUrl,
+    object_id_: Option<&DbUrl>,
+  ) -> Result<(), Error> {
+    use crate::schema::received_activity::dsl::{ap_id, id, object_id, received_activity};
     let conn = &mut get_conn(pool).await?;
     let res = insert_into(received_activity)
-      .values(ap_id.eq(ap_id_))
+      .values((ap_id.eq(ap_id_), object_id.eq(object_id_)))
       .on_conflict_do_nothing()
       .returning(id)
       .get_result::<i64>(conn)
@@ -54,6 +136,20 @@ impl ReceivedActivity {
       ))
     }
   }
+
+  /// All retained activities which refer to the given object, oldest first.
+  pub async fn list_for_object(
+    pool: &mut DbPool<'_>,
+    for_object_id: &DbUrl,
+  ) -> Result<Vec<Self>, Error> {
+    use crate::schema::received_activity::dsl::{object_id, published, received_activity};
+    let conn = &mut get_conn(pool).await?;
+    received_activity
+      .filter(object_id.eq(for_object_id))
+      .order_by(published.asc())
+      .load::<Self>(conn)
+      .await
+  }
 }
 
 #[cfg(test)]
@@ -62,7 +158,7 @@ mod tests {
   #![allow(clippy::indexing_slicing)]
 
   use super::*;
-  use crate::utils::build_db_pool_for_tests;
+  use crate::{utils::build_db_pool_for_tests, DeliveryStatus};
   use serde_json::json;
   use serial_test::serial;
   use url::Url;
@@ -77,10 +173,10 @@ mod tests {
       .into();
 
     // inserting activity for first time
-    let res = ReceivedActivity::create(pool, &ap_id).await;
+    let res = ReceivedActivity::create(pool, &ap_id, None).await;
     assert!(res.is_ok());
 
-    let res = ReceivedActivity::create(pool, &ap_id).await;
+    let res = ReceivedActivity::create(pool, &ap_id, None).await;
     assert!(res.is_err());
   }
 
@@ -102,6 +198,7 @@ mod tests {
       ap_id: ap_id.clone(),
       data: data.clone(),
       sensitive,
+      object_id: None,
     };
 
     SentActivity::create(pool, form).await.unwrap();
@@ -111,4 +208,75 @@ mod tests {
     assert_eq!(res.data, data);
     assert_eq!(res.sensitive, sensitive);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn activities_for_object() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let object_id: DbUrl = Url::parse("http://example.com/post/12").unwrap().into();
+    let sent_ap_id: DbUrl = Url::parse("http://example.com/activity/413")
+      .unwrap()
+      .into();
+    let received_ap_id: DbUrl = Url::parse("http://example.com/activity/532")
+      .unwrap()
+      .into();
+    let inbox: DbUrl = Url::parse("http://example.net/inbox").unwrap().into();
+
+    let form = SentActivityForm {
+      ap_id: sent_ap_id.clone(),
+      data: json!({}),
+      sensitive: false,
+      object_id: Some(object_id.clone()),
+    };
+    let sent = SentActivity::create(pool, form).await.unwrap();
+    let delivery_form = SentActivityDeliveryForm {
+      sent_activity_id: sent.id,
+      inbox: inbox.clone(),
+      status: DeliveryStatus::Queued,
+    };
+    SentActivityDelivery::create_many(pool, &[delivery_form])
+      .await
+      .unwrap();
+    ReceivedActivity::create(pool, &received_ap_id, Some(&object_id))
+      .await
+      .unwrap();
+
+    let sent_list = SentActivity::list_for_object(pool, &object_id)
+      .await
+      .unwrap();
+    assert_eq!(1, sent_list.len());
+    assert_eq!(sent_ap_id, sent_list[0].ap_id);
+    let deliveries = SentActivityDelivery::list_for_activity(pool, sent.id)
+      .await
+      .unwrap();
+    assert_eq!(1, deliveries.len());
+    assert_eq!(inbox, deliveries[0].inbox);
+    assert_eq!(DeliveryStatus::Queued, deliveries[0].status);
+    assert_eq!(0, deliveries[0].attempts);
+
+    let error = Some("connection refused".to_string());
+    SentActivityDelivery::record_attempt(pool, &sent_ap_id, &inbox, error.clone())
+      .await
+      .unwrap();
+    let deliveries = SentActivityDelivery::list_for_activity(pool, sent.id)
+      .await
+      .unwrap();
+    assert_eq!(1, deliveries[0].attempts);
+    assert!(deliveries[0].last_attempt_at.is_some());
+    assert_eq!(error, deliveries[0].last_error);
+    SentActivityDelivery::record_attempt(pool, &sent_ap_id, &inbox, None)
+      .await
+      .unwrap();
+    let deliveries = SentActivityDelivery::list_for_activity(pool, sent.id)
+      .await
+      .unwrap();
+    assert_eq!(2, deliveries[0].attempts);
+    assert_eq!(None, deliveries[0].last_error);
+    let received_list = ReceivedActivity::list_for_object(pool, &object_id)
+      .await
+      .unwrap();
+    assert_eq!(1, received_list.len());
+    assert_eq!(received_ap_id, received_list[0].ap_id);
+  }
 }
@@ -1,26 +1,47 @@
 use crate::{
-  newtypes::{CommentId, DbUrl, PersonId},
-  schema::comment::dsl::{ap_id, comment, content, creator_id, deleted, path, removed, updated},
-  source::comment::{
-    Comment,
-    CommentInsertForm,
-    CommentLike,
-    CommentLikeForm,
-    CommentSaved,
-    CommentSavedForm,
-    CommentUpdateForm,
+  aggregates::structs::CommentAggregates,
+  newtypes::{CommentId, DbUrl, PersonId, PostId},
+  schema::{
+    comment::dsl::{
+      ap_id,
+      backfilled,
+      comment,
+      content,
+      creator_id,
+      deleted,
+      id,
+      locked,
+      path,
+      post_id,
+      removed,
+      updated,
+    },
+    community,
+    post,
+  },
+  source::{
+    comment::{
+      Comment,
+      CommentInsertForm,
+      CommentLike,
+      CommentLikeForm,
+      CommentSaved,
+      CommentSavedForm,
+      CommentUpdateForm,
+    },
+    local_user::LocalUser,
   },
   traits::{Crud, Likeable, Saveable},
   utils::{get_conn, naive_now, DbPool, DELETED_REPLACEMENT_TEXT},
 };
 use diesel::{
-  dsl::{insert_into, sql_query},
+  dsl::{count_star, exists, insert_into, select, sql_query},
   result::Error,
   ExpressionMethods,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
-use diesel_ltree::Ltree;
+use diesel_ltree::{Ltree, LtreeExtensions};
 use url::Url;
 
 impl Comment {
@@ -52,6 +73,29 @@ impl Comment {
       .await
   }
 
+  /// Removes the comments of the creator on posts in local communities, and returns those which
+  /// weren't removed before.
+  pub async fn remove_for_creator_in_local_communities(
+    pool: &mut DbPool<'_>,
+    for_creator_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let local_posts = post::table
+      .inner_join(community::table)
+      .filter(community::local.eq(true))
+      .select(post::id);
+
+    diesel::update(
+      comment
+        .filter(creator_id.eq(for_creator_id))
+        .filter(removed.eq(false))
+        .filter(post_id.eq_any(local_posts)),
+    )
+    .set((removed.eq(true), updated.eq(naive_now())))
+    .get_results::<Self>(conn)
+    .await
+  }
+
   pub async fn create(
     pool: &mut DbPool<'_>,
     comment_form: &CommentInsertForm,
@@ -123,6 +167,31 @@ where ca.comment_id = c.id"
       inserted_comment
     }
   }
+
+  /// Creates the comment and reads the updated child counts of its parent comments in one
+  /// transaction, so that replies which are created at the same time don't change the counts.
+  pub async fn create_with_ancestor_counts(
+    pool: &mut DbPool<'_>,
+    comment_form: &CommentInsertForm,
+    parent_path: Option<&Ltree>,
+  ) -> Result<(Comment, Vec<(CommentId, i64)>), Error> {
+    let conn = &mut get_conn(pool).await?;
+    let comment_form = comment_form.clone();
+    let parent_path = parent_path.cloned();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let inserted_comment =
+            Comment::create(&mut conn.into(), &comment_form, parent_path.as_ref()).await?;
+          let ancestor_counts =
+            CommentAggregates::read_ancestor_child_counts(&mut conn.into(), &inserted_comment.path)
+              .await?;
+          Ok((inserted_comment, ancestor_counts))
+        }) as _
+      })
+      .await
+  }
   pub async fn read_from_apub_id(
     pool: &mut DbPool<'_>,
     object_id: Url,
@@ -139,6 +208,55 @@ where ca.comment_id = c.id"
     )
   }
 
+  /// Whether the comment at the path or any of its parent comments is locked, which means that it
+  /// doesn't accept new replies.
+  pub async fn is_thread_locked(
+    pool: &mut DbPool<'_>,
+    comment_path: &Ltree,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      comment
+        .filter(path.contains(comment_path))
+        .filter(locked.eq(true)),
+    ))
+    .get_result(conn)
+    .await
+  }
+
+  /// The ids of the newest comments on the post which aren't deleted or removed. They are returned
+  /// in the order they were created, so that parents come before their replies.
+  pub async fn list_ap_ids_for_post(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+    limit: i64,
+  ) -> Result<Vec<DbUrl>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut ap_ids = comment
+      .filter(post_id.eq(for_post_id))
+      .filter(deleted.eq(false))
+      .filter(removed.eq(false))
+      .order_by(id.desc())
+      .limit(limit)
+      .select(ap_id)
+      .load::<DbUrl>(conn)
+      .await?;
+    ap_ids.reverse();
+    Ok(ap_ids)
+  }
+
+  /// Marks a comment which was fetched by a resync of its post.
+  pub async fn mark_backfilled(
+    pool: &mut DbPool<'_>,
+    comment_id: CommentId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(comment.find(comment_id))
+      .set(backfilled.eq(true))
+      .get_result::<Self>(conn)
+      .await
+  }
+
   pub fn parent_comment_id(&self) -> Option<CommentId> {
     let mut ltree_split: Vec<&str> = self.path.0.split('.').collect();
     ltree_split.remove(0); // The first is always 0
@@ -149,6 +267,20 @@ where ca.comment_id = c.id"
       None
     }
   }
+
+  /// Removes the content of an NSFW comment for users who don't want to see NSFW content, without
+  /// removing the comment from the tree. Anonymous users count as not wanting it, and authors
+  /// always see their own comments. Returns whether the content was removed.
+  pub fn redact_nsfw(&mut self, local_user: Option<&LocalUser>) -> bool {
+    let show_nsfw = local_user.is_some_and(|l| l.show_nsfw);
+    let is_creator = local_user.is_some_and(|l| l.person_id == self.creator_id);
+    if self.nsfw && !show_nsfw && !is_creator {
+      self.content = String::new();
+      true
+    } else {
+      false
+    }
+  }
 }
 
 #[async_trait]
@@ -207,6 +339,45 @@ impl Likeable for CommentLike {
   }
 }
 
+impl CommentSaved {
+  /// Activitypub ids of all comments which the person saved.
+  pub async fn list_ap_ids_for_person(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> Result<Vec<DbUrl>, Error> {
+    use crate::schema::comment_saved;
+    let conn = &mut get_conn(pool).await?;
+    comment_saved::table
+      .inner_join(comment)
+      .filter(comment_saved::person_id.eq(for_person_id))
+      .select(ap_id)
+      .load::<DbUrl>(conn)
+      .await
+  }
+
+  /// The tags which the person saved comments with, and how many comments have each tag.
+  pub async fn count_tags_for_person(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> Result<Vec<(String, i64)>, Error> {
+    use crate::schema::comment_saved;
+    let conn = &mut get_conn(pool).await?;
+    let tags = comment_saved::table
+      .filter(comment_saved::person_id.eq(for_person_id))
+      .filter(comment_saved::tag.is_not_null())
+      .group_by(comment_saved::tag)
+      .select((comment_saved::tag, count_star()))
+      .load::<(Option<String>, i64)>(conn)
+      .await?;
+    Ok(
+      tags
+        .into_iter()
+        .filter_map(|(tag, count)| Some((tag?, count)))
+        .collect(),
+    )
+  }
+}
+
 #[async_trait]
 impl Saveable for CommentSaved {
   type Form = CommentSavedForm;
@@ -325,6 +496,10 @@ mod tests {
       distinguished: false,
       local: true,
       language_id: LanguageId::default(),
+      content_warning: None,
+      locked: false,
+      backfilled: false,
+      nsfw: false,
     };
 
     let child_comment_form = CommentInsertForm::builder()
@@ -361,6 +536,8 @@ mod tests {
     let comment_saved_form = CommentSavedForm {
       comment_id: inserted_comment.id,
       person_id: inserted_person.id,
+      remind_at: None,
+      tag: None,
     };
 
     let inserted_comment_saved = CommentSaved::save(pool, &comment_saved_form).await.unwrap();
@@ -370,6 +547,8 @@ mod tests {
       comment_id: inserted_comment.id,
       person_id: inserted_person.id,
       published: inserted_comment_saved.published,
+      remind_at: None,
+      tag: None,
     };
 
     let comment_update_form = CommentUpdateForm {
@@ -382,6 +561,26 @@ mod tests {
       .unwrap();
 
     let read_comment = Comment::read(pool, inserted_comment.id).await.unwrap();
+
+    // Locking the parent comment locks the thread of its replies
+    assert!(
+      !Comment::is_thread_locked(pool, &inserted_child_comment.path)
+        .await
+        .unwrap()
+    );
+    let lock_form = CommentUpdateForm {
+      locked: Some(true),
+      ..Default::default()
+    };
+    Comment::update(pool, inserted_comment.id, &lock_form)
+      .await
+      .unwrap();
+    assert!(
+      Comment::is_thread_locked(pool, &inserted_child_comment.path)
+        .await
+        .unwrap()
+    );
+
     let like_removed = CommentLike::remove(pool, inserted_person.id, inserted_comment.id)
       .await
       .unwrap();
@@ -412,4 +611,56 @@ mod tests {
     assert_eq!(1, saved_removed);
     assert_eq!(1, num_deleted);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_list_ap_ids_for_post() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("replies_creator".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let new_community = CommunityInsertForm::builder()
+      .name("test_replies".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+    let new_post = PostInsertForm::builder()
+      .name("A post with replies".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let mut comments = vec![];
+    for content in ["first", "second", "third"] {
+      let comment_form = CommentInsertForm::builder()
+        .content(content.into())
+        .creator_id(inserted_person.id)
+        .post_id(inserted_post.id)
+        .build();
+      comments.push(Comment::create(pool, &comment_form, None).await.unwrap());
+    }
+
+    // Only the newest comments are listed, oldest first
+    let ap_ids = Comment::list_ap_ids_for_post(pool, inserted_post.id, 2)
+      .await
+      .unwrap();
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+
+    assert_eq!(
+      vec![comments[1].ap_id.clone(), comments[2].ap_id.clone()],
+      ap_ids
+    );
+  }
 }
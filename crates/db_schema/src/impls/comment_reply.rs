@@ -1,12 +1,13 @@
 use crate::{
   newtypes::{CommentId, CommentReplyId, PersonId},
-  schema::comment_reply::dsl::{comment_id, comment_reply, read, recipient_id},
+  schema::comment_reply::dsl::{comment_id, comment_reply, id, read, recipient_id},
   source::comment_reply::{CommentReply, CommentReplyInsertForm, CommentReplyUpdateForm},
   traits::Crud,
   utils::{get_conn, DbPool},
 };
 use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
+use std::collections::HashSet;
 
 #[async_trait]
 impl Crud for CommentReply {
@@ -60,6 +61,38 @@ impl CommentReply {
     .await
   }
 
+  /// Marks the replies as read or unread in a single update. If any of them doesn't exist or
+  /// belongs to another recipient, nothing is changed and `NotFound` is returned.
+  pub async fn mark_many_as_read(
+    pool: &mut DbPool<'_>,
+    reply_ids: &[CommentReplyId],
+    for_recipient_id: PersonId,
+    mark_read: bool,
+  ) -> Result<Vec<CommentReply>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let reply_ids: HashSet<_> = reply_ids.iter().copied().collect();
+    let expected = reply_ids.len();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let updated = diesel::update(
+            comment_reply
+              .filter(id.eq_any(reply_ids.into_iter().collect::<Vec<_>>()))
+              .filter(recipient_id.eq(for_recipient_id)),
+          )
+          .set(read.eq(mark_read))
+          .get_results::<Self>(conn)
+          .await?;
+          if updated.len() != expected {
+            return Err(Error::NotFound);
+          }
+          Ok(updated)
+        }) as _
+      })
+      .await
+  }
+
   pub async fn read_by_comment(
     pool: &mut DbPool<'_>,
     for_comment_id: CommentId,
@@ -146,6 +179,7 @@ mod tests {
       recipient_id: inserted_recipient.id,
       comment_id: inserted_comment.id,
       read: None,
+      muted: None,
     };
 
     let inserted_reply = CommentReply::create(pool, &comment_reply_form)
@@ -158,6 +192,7 @@ mod tests {
       comment_id: inserted_reply.comment_id,
       read: false,
       published: inserted_reply.published,
+      muted: false,
     };
 
     let read_reply = CommentReply::read(pool, inserted_reply.id).await.unwrap();
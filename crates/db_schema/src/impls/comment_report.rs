@@ -2,6 +2,7 @@ use crate::{
   newtypes::{CommentId, CommentReportId, PersonId},
   schema::comment_report::{
     comment_id,
+    creator_id,
     dsl::{comment_report, resolved, resolver_id, updated},
   },
   source::comment_report::{CommentReport, CommentReportForm},
@@ -9,13 +10,31 @@ use crate::{
   utils::{get_conn, naive_now, DbPool},
 };
 use diesel::{
-  dsl::{insert_into, update},
+  dsl::{exists, insert_into, select, update},
   result::Error,
   ExpressionMethods,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
 
+impl CommentReport {
+  /// Whether the person already reported the comment.
+  pub async fn exists_for_creator(
+    pool: &mut DbPool<'_>,
+    for_comment_id: CommentId,
+    for_creator_id: PersonId,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      comment_report
+        .filter(comment_id.eq(for_comment_id))
+        .filter(creator_id.eq(for_creator_id)),
+    ))
+    .get_result(conn)
+    .await
+  }
+}
+
 #[async_trait]
 impl Reportable for CommentReport {
   type Form = CommentReportForm;
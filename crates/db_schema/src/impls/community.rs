@@ -1,6 +1,6 @@
 use crate::{
   newtypes::{CommunityId, DbUrl, PersonId},
-  schema::{community, community_follower, instance},
+  schema::{community, community_follower, community_person_ban, instance},
   source::{
     actor_language::CommunityLanguage,
     community::{
@@ -22,7 +22,7 @@ use crate::{
 use diesel::{
   deserialize,
   dsl,
-  dsl::insert_into,
+  dsl::{exists, insert_into, select},
   pg::Pg,
   result::Error,
   sql_types,
@@ -137,6 +137,47 @@ impl Community {
     }
     Err(diesel::NotFound)
   }
+
+  /// The next local communities after the given id, ordered by id.
+  pub async fn list_local_after(
+    pool: &mut DbPool<'_>,
+    after: CommunityId,
+    limit: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community::table
+      .filter(community::local.eq(true))
+      .filter(community::id.gt(after))
+      .order_by(community::id)
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+impl CommunityFollower {
+  /// Whether the person follows the community, with an accepted follow.
+  pub async fn is_follower(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+    for_community_id: CommunityId,
+  ) -> Result<bool, Error> {
+    use crate::schema::community_follower::dsl::{
+      community_follower,
+      community_id,
+      pending,
+      person_id,
+    };
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      community_follower
+        .filter(community_id.eq(for_community_id))
+        .filter(person_id.eq(for_person_id))
+        .filter(pending.eq(false)),
+    ))
+    .get_result(conn)
+    .await
+  }
 }
 
 impl CommunityModerator {
@@ -211,6 +252,29 @@ impl Bannable for CommunityPersonBan {
   }
 }
 
+impl CommunityPersonBan {
+  /// Lists the temporary bans which are over.
+  pub async fn list_expired(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_person_ban::table
+      .filter(community_person_ban::expires.lt(dsl::now))
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Deletes a ban which is over. It is kept if it was renewed in the meantime.
+  pub async fn delete_expired(pool: &mut DbPool<'_>, ban_id: i32) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      community_person_ban::table
+        .find(ban_id)
+        .filter(community_person_ban::expires.lt(dsl::now)),
+    )
+    .execute(conn)
+    .await
+  }
+}
+
 impl CommunityFollower {
   pub fn to_subscribed_type(follower: &Option<Self>) -> SubscribedType {
     match follower {
@@ -363,8 +427,9 @@ mod tests {
       person::{Person, PersonInsertForm},
     },
     traits::{Bannable, Crud, Followable, Joinable},
-    utils::build_db_pool_for_tests,
+    utils::{build_db_pool_for_tests, naive_now},
   };
+  use chrono::Duration;
   use serial_test::serial;
 
   #[tokio::test]
@@ -416,6 +481,12 @@ mod tests {
       shared_inbox_url: None,
       moderators_url: None,
       featured_url: None,
+      default_post_sort: None,
+      default_comment_sort: None,
+      min_account_age_days_to_vote: 0,
+      only_followers_can_vote: false,
+      verified: false,
+      commenting_restricted_to_mods: false,
       hidden: false,
       posting_restricted_to_mods: false,
       instance_id: inserted_instance.id,
@@ -483,15 +554,44 @@ mod tests {
       .await
       .unwrap();
 
+    let is_follower =
+      CommunityFollower::is_follower(pool, inserted_person.id, inserted_community.id)
+        .await
+        .unwrap();
     let ignored_community = CommunityFollower::unfollow(pool, &community_follower_form)
       .await
       .unwrap();
+    let is_follower_after_unfollow =
+      CommunityFollower::is_follower(pool, inserted_person.id, inserted_community.id)
+        .await
+        .unwrap();
     let left_community = CommunityModerator::leave(pool, &community_moderator_form)
       .await
       .unwrap();
     let unban = CommunityPersonBan::unban(pool, &community_person_ban_form)
       .await
       .unwrap();
+
+    // Only bans which are over are lifted
+    let temporary_ban_form = |expires| CommunityPersonBanForm {
+      expires: Some(Some(expires)),
+      ..community_person_ban_form.clone()
+    };
+    let active =
+      CommunityPersonBan::ban(pool, &temporary_ban_form(naive_now() + Duration::hours(1)))
+        .await
+        .unwrap();
+    let not_expired = CommunityPersonBan::list_expired(pool).await.unwrap();
+    let active_deleted = CommunityPersonBan::delete_expired(pool, active.id)
+      .await
+      .unwrap();
+    CommunityPersonBan::ban(pool, &temporary_ban_form(naive_now() - Duration::hours(1)))
+      .await
+      .unwrap();
+    let expired = CommunityPersonBan::list_expired(pool).await.unwrap();
+    let expired_deleted = CommunityPersonBan::delete_expired(pool, expired[0].id)
+      .await
+      .unwrap();
     let num_deleted = Community::delete(pool, inserted_community.id)
       .await
       .unwrap();
@@ -504,9 +604,16 @@ mod tests {
     assert_eq!(expected_community_follower, inserted_community_follower);
     assert_eq!(expected_community_moderator, inserted_community_moderator);
     assert_eq!(expected_community_person_ban, inserted_community_person_ban);
+    assert!(is_follower);
     assert_eq!(1, ignored_community);
+    assert!(!is_follower_after_unfollow);
     assert_eq!(1, left_community);
     assert_eq!(1, unban);
+    assert!(not_expired.is_empty());
+    assert_eq!(0, active_deleted);
+    assert_eq!(1, expired.len());
+    assert_eq!(inserted_person.id, expired[0].person_id);
+    assert_eq!(1, expired_deleted);
     // assert_eq!(2, loaded_count);
     assert_eq!(1, num_deleted);
   }
@@ -0,0 +1,235 @@
+use crate::{
+  newtypes::CommunityId,
+  schema::community_aggregates_snapshot,
+  source::community_aggregates_snapshot::CommunityAggregatesSnapshot,
+  utils::{get_conn, naive_now, DbPool},
+};
+use chrono::Duration;
+use diesel::{result::Error, sql_query, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl CommunityAggregatesSnapshot {
+  /// Takes the snapshots of local communities for every finished day since the last snapshot, at
+  /// most 90 days back. Returns the number of snapshots which were added.
+  pub async fn create_missing(pool: &mut DbPool<'_>) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    sql_query(
+      "INSERT INTO community_aggregates_snapshot (community_id, day, subscribers, posts, comments, users_active)
+       SELECT co.id, d.day,
+         (SELECT count(*) FROM community_follower cf
+           WHERE cf.community_id = co.id AND NOT cf.pending AND cf.published < d.day + 1),
+         (SELECT count(*) FROM post p
+           WHERE p.community_id = co.id AND p.published >= d.day AND p.published < d.day + 1),
+         (SELECT count(*) FROM comment c INNER JOIN post p ON p.id = c.post_id
+           WHERE p.community_id = co.id AND c.published >= d.day AND c.published < d.day + 1),
+         (SELECT count(*) FROM (
+            SELECT c.creator_id FROM comment c INNER JOIN post p ON p.id = c.post_id
+             WHERE p.community_id = co.id AND c.published >= d.day AND c.published < d.day + 1
+            UNION
+            SELECT p.creator_id FROM post p
+             WHERE p.community_id = co.id AND p.published >= d.day AND p.published < d.day + 1) a)
+       FROM community co
+       CROSS JOIN (
+         SELECT generate_series(
+           (SELECT coalesce(max(day) + 1, current_date - 90) FROM community_aggregates_snapshot),
+           current_date - 1,
+           '1 day')::date AS day) d
+       WHERE co.local AND co.published < d.day + 1
+       ON CONFLICT (community_id, day) DO NOTHING",
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// The snapshots of the last `days` finished days, newest first. Only reads the precomputed
+  /// snapshots, so this never has to scan the content tables.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+    days: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let since = naive_now().date() - Duration::days(days);
+    community_aggregates_snapshot::table
+      .filter(community_aggregates_snapshot::community_id.eq(for_community_id))
+      .filter(community_aggregates_snapshot::day.ge(since))
+      .order_by(community_aggregates_snapshot::day.desc())
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    schema::{community_aggregates_snapshot, community_follower},
+    source::{
+      comment::{Comment, CommentInsertForm},
+      community::{Community, CommunityFollower, CommunityFollowerForm, CommunityInsertForm},
+      community_aggregates_snapshot::CommunityAggregatesSnapshot,
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::{Crud, Followable},
+    utils::{build_db_pool_for_tests, get_conn, naive_now},
+  };
+  use chrono::Duration;
+  use diesel::{delete, insert_into, update, ExpressionMethods};
+  use diesel_async::RunQueryDsl;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_list() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_snapshot".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let today = naive_now().date();
+    for days_ago in [1, 2, 40] {
+      let conn = &mut get_conn(pool).await.unwrap();
+      insert_into(community_aggregates_snapshot::table)
+        .values((
+          community_aggregates_snapshot::community_id.eq(inserted_community.id),
+          community_aggregates_snapshot::day.eq(today - Duration::days(days_ago)),
+          community_aggregates_snapshot::posts.eq(days_ago),
+        ))
+        .execute(conn)
+        .await
+        .unwrap();
+    }
+
+    let last_month = CommunityAggregatesSnapshot::list(pool, inserted_community.id, 30)
+      .await
+      .unwrap();
+    assert_eq!(2, last_month.len());
+    assert_eq!(today - Duration::days(1), last_month[0].day);
+    assert_eq!(1, last_month[0].posts);
+
+    let last_quarter = CommunityAggregatesSnapshot::list(pool, inserted_community.id, 90)
+      .await
+      .unwrap();
+    assert_eq!(3, last_quarter.len());
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_create_missing() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let conn = &mut get_conn(pool).await.unwrap();
+    // Snapshots are only added after the newest existing one
+    delete(community_aggregates_snapshot::table)
+      .execute(conn)
+      .await
+      .unwrap();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let now = naive_now();
+    let days_ago = |days| Some(now - Duration::days(days));
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_create_snapshot".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .local(Some(true))
+      .published(days_ago(3))
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let mut persons = vec![];
+    for name in ["snapshot_poster", "snapshot_commenter"] {
+      let new_person = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      persons.push(Person::create(pool, &new_person).await.unwrap());
+    }
+    let (poster, commenter) = (&persons[0], &persons[1]);
+
+    let follower_form = CommunityFollowerForm {
+      community_id: inserted_community.id,
+      person_id: commenter.id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &follower_form)
+      .await
+      .unwrap();
+    let conn = &mut get_conn(pool).await.unwrap();
+    update(community_follower::table)
+      .filter(community_follower::person_id.eq(commenter.id))
+      .set(community_follower::published.eq(days_ago(2).unwrap()))
+      .execute(conn)
+      .await
+      .unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A post".into())
+      .creator_id(poster.id)
+      .community_id(inserted_community.id)
+      .published(days_ago(2))
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+    for days in [1, 1, 2] {
+      let comment_form = CommentInsertForm::builder()
+        .content("A comment".into())
+        .creator_id(commenter.id)
+        .post_id(inserted_post.id)
+        .published(days_ago(days))
+        .build();
+      Comment::create(pool, &comment_form, None).await.unwrap();
+    }
+
+    // The community existed on each of the last three days
+    let added = CommunityAggregatesSnapshot::create_missing(pool)
+      .await
+      .unwrap();
+    assert!(added >= 3);
+    let snapshots = CommunityAggregatesSnapshot::list(pool, inserted_community.id, 90)
+      .await
+      .unwrap();
+    let counts: Vec<_> = snapshots
+      .iter()
+      .map(|s| (s.subscribers, s.posts, s.comments, s.users_active))
+      .collect();
+    assert_eq!(vec![(1, 0, 2, 1), (1, 1, 1, 2), (0, 0, 0, 0)], counts);
+    assert_eq!(now.date() - Duration::days(1), snapshots[0].day);
+
+    // Finished days are only counted once
+    assert_eq!(
+      0,
+      CommunityAggregatesSnapshot::create_missing(pool)
+        .await
+        .unwrap()
+    );
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,325 @@
+use crate::{
+  aggregates::structs::PostAggregates,
+  newtypes::{CommunityDigestId, CommunityId, PersonId, PostId},
+  schema::{community_digest, post, post_aggregates},
+  source::{
+    community_digest::{CommunityDigest, CommunityDigestForm},
+    post::{Post, PostInsertForm},
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::insert_into,
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl CommunityDigest {
+  /// Creates the digest settings of the community, or replaces the existing ones.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &CommunityDigestForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_digest::table)
+      .values(form)
+      .on_conflict(community_digest::community_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read_for_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_digest::table
+      .filter(community_digest::community_id.eq(for_community_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn list_enabled(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_digest::table
+      .filter(community_digest::enabled.eq(true))
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Marks the digest scheduled at `due` as generated. Returns false if it already was, for
+  /// example because the task was restarted in between.
+  pub async fn claim(
+    pool: &mut DbPool<'_>,
+    digest_id: CommunityDigestId,
+    due: chrono::NaiveDateTime,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let updated = diesel::update(
+      community_digest::table.find(digest_id).filter(
+        community_digest::last_generated_for
+          .is_null()
+          .or(community_digest::last_generated_for.lt(due)),
+      ),
+    )
+    .set(community_digest::last_generated_for.eq(due))
+    .execute(conn)
+    .await?;
+    Ok(updated == 1)
+  }
+
+  /// Claims the digest scheduled at `due` and creates its post in the same transaction, so that a
+  /// post which couldn't be created doesn't use up the scheduled week. Returns None if the digest
+  /// was already claimed.
+  pub async fn claim_with_post(
+    pool: &mut DbPool<'_>,
+    digest_id: CommunityDigestId,
+    due: chrono::NaiveDateTime,
+    post_form: &PostInsertForm,
+  ) -> Result<Option<Post>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let post_form = post_form.clone();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let pool = &mut conn.into();
+          if !Self::claim(pool, digest_id, due).await? {
+            return Ok(None);
+          }
+          let post = Post::create(pool, &post_form).await?;
+          Self::set_last_post(pool, digest_id, post.id).await?;
+          Ok(Some(post))
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn set_last_post(
+    pool: &mut DbPool<'_>,
+    digest_id: CommunityDigestId,
+    post_id: PostId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_digest::table.find(digest_id))
+      .set(community_digest::last_post_id.eq(post_id))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// The posts with the highest score which were published in the community in the given time,
+  /// leaving out removed and deleted posts as well as earlier digests.
+  pub async fn top_posts(
+    &self,
+    pool: &mut DbPool<'_>,
+    published_between: (chrono::NaiveDateTime, chrono::NaiveDateTime),
+    include_nsfw: bool,
+    digest_creator_id: PersonId,
+  ) -> Result<Vec<(Post, PostAggregates)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (since, until) = published_between;
+    let mut query = post::table
+      .inner_join(post_aggregates::table)
+      .filter(post::community_id.eq(self.community_id))
+      .filter(post::published.ge(since))
+      .filter(post::published.lt(until))
+      .filter(post::removed.eq(false))
+      .filter(post::deleted.eq(false))
+      .filter(post::creator_id.ne(digest_creator_id))
+      .filter(post_aggregates::score.ge(i64::from(self.min_score)))
+      .into_boxed();
+    if !include_nsfw {
+      query = query.filter(post::nsfw.eq(false));
+    }
+    query
+      .order_by(post_aggregates::score.desc())
+      .then_order_by(post::published.asc())
+      .limit(i64::from(self.top_posts))
+      .load::<(Post, PostAggregates)>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    newtypes::CommunityId,
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_digest::{CommunityDigest, CommunityDigestForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm, PostLike, PostLikeForm},
+    },
+    traits::{Crud, Likeable},
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_community_digest() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("digest_reader".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_bot = PersonInsertForm::builder()
+      .name("digest_writer".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_bot = Person::create(pool, &new_bot).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_digest".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let post = |name: &str, creator_id, nsfw| {
+      PostInsertForm::builder()
+        .name(name.into())
+        .creator_id(creator_id)
+        .community_id(inserted_community.id)
+        .nsfw(Some(nsfw))
+        .build()
+    };
+    let popular = Post::create(pool, &post("popular", inserted_person.id, false))
+      .await
+      .unwrap();
+    let unpopular = Post::create(pool, &post("unpopular", inserted_person.id, false))
+      .await
+      .unwrap();
+    let nsfw = Post::create(pool, &post("nsfw", inserted_person.id, true))
+      .await
+      .unwrap();
+    Post::create(pool, &post("last digest", inserted_bot.id, false))
+      .await
+      .unwrap();
+    for post_id in [popular.id, nsfw.id] {
+      let like_form = PostLikeForm {
+        post_id,
+        person_id: inserted_person.id,
+        score: 1,
+      };
+      PostLike::like(pool, &like_form).await.unwrap();
+    }
+
+    let form = CommunityDigestForm {
+      community_id: inserted_community.id,
+      enabled: true,
+      day_of_week: 0,
+      hour: 12,
+      top_posts: 10,
+      min_score: 0,
+      feature: false,
+    };
+    CommunityDigest::upsert(pool, &form).await.unwrap();
+    let digest = CommunityDigest::upsert(
+      pool,
+      &CommunityDigestForm {
+        min_score: 1,
+        ..form
+      },
+    )
+    .await
+    .unwrap();
+    assert_eq!(1, digest.min_score);
+    assert_eq!(
+      Some(digest.clone()),
+      CommunityDigest::read_for_community(pool, inserted_community.id)
+        .await
+        .unwrap()
+    );
+
+    let now = naive_now();
+    let week = (now - Duration::days(7), now + Duration::minutes(1));
+    let top = digest
+      .top_posts(pool, week, false, inserted_bot.id)
+      .await
+      .unwrap();
+    assert_eq!(
+      vec![popular.id],
+      top.iter().map(|(p, _)| p.id).collect::<Vec<_>>()
+    );
+    assert_eq!(1, top[0].1.score);
+    let top_with_nsfw = digest
+      .top_posts(pool, week, true, inserted_bot.id)
+      .await
+      .unwrap();
+    assert_eq!(2, top_with_nsfw.len());
+    assert!(!top_with_nsfw.iter().any(|(p, _)| p.id == unpopular.id));
+
+    // Each scheduled digest is only claimed once
+    assert!(CommunityDigest::claim(pool, digest.id, now).await.unwrap());
+    assert!(!CommunityDigest::claim(pool, digest.id, now).await.unwrap());
+    assert!(
+      CommunityDigest::claim(pool, digest.id, now + Duration::days(7))
+        .await
+        .unwrap()
+    );
+
+    let updated = CommunityDigest::set_last_post(pool, digest.id, popular.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(popular.id), updated.last_post_id);
+
+    // A post which can't be created leaves the scheduled digest unclaimed
+    let next_week = now + Duration::days(14);
+    let broken_form = PostInsertForm {
+      community_id: CommunityId(-1),
+      ..post("digest", inserted_bot.id, false)
+    };
+    assert!(
+      CommunityDigest::claim_with_post(pool, digest.id, next_week, &broken_form)
+        .await
+        .is_err()
+    );
+    let digest_form = post("digest", inserted_bot.id, false);
+    let digest_post = CommunityDigest::claim_with_post(pool, digest.id, next_week, &digest_form)
+      .await
+      .unwrap()
+      .unwrap();
+    let updated = CommunityDigest::read_for_community(pool, inserted_community.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(Some(next_week), updated.last_generated_for);
+    assert_eq!(Some(digest_post.id), updated.last_post_id);
+    // Once claimed, no second post is created
+    assert_eq!(
+      None,
+      CommunityDigest::claim_with_post(pool, digest.id, next_week, &digest_form)
+        .await
+        .unwrap()
+    );
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Person::delete(pool, inserted_bot.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,216 @@
+use crate::{
+  newtypes::{CommunityFlairOptionId, CommunityId, PersonId},
+  schema::{community_flair_option, community_person_flair},
+  source::community_flair::{
+    CommunityFlairOption,
+    CommunityFlairOptionForm,
+    CommunityPersonFlair,
+    CommunityPersonFlairForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl CommunityPersonFlair {
+  /// Gives the person the flair in the community, replacing the one they had.
+  pub async fn set(pool: &mut DbPool<'_>, form: &CommunityPersonFlairForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_person_flair::table)
+      .values(form)
+      .on_conflict((
+        community_person_flair::community_id,
+        community_person_flair::person_id,
+      ))
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+    for_person_id: PersonId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_person_flair::table
+      .filter(community_person_flair::community_id.eq(for_community_id))
+      .filter(community_person_flair::person_id.eq(for_person_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn remove(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+    for_person_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      community_person_flair::table
+        .filter(community_person_flair::community_id.eq(for_community_id))
+        .filter(community_person_flair::person_id.eq(for_person_id)),
+    )
+    .execute(conn)
+    .await
+  }
+}
+
+#[async_trait]
+impl Crud for CommunityFlairOption {
+  type InsertForm = CommunityFlairOptionForm;
+  type UpdateForm = CommunityFlairOptionForm;
+  type IdType = CommunityFlairOptionId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_flair_option::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    option_id: CommunityFlairOptionId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_flair_option::table.find(option_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl CommunityFlairOption {
+  /// The flairs which members of the community can pick, ordered by name.
+  pub async fn list_for_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_flair_option::table
+      .filter(community_flair_option::community_id.eq(for_community_id))
+      .order_by(community_flair_option::flair.asc())
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_flair::{
+        CommunityFlairOption,
+        CommunityFlairOptionForm,
+        CommunityPersonFlair,
+        CommunityPersonFlairForm,
+      },
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("flair_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_flair".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let option_form = |flair: &str| CommunityFlairOptionForm {
+      community_id: inserted_community.id,
+      flair: flair.to_string(),
+      color: None,
+    };
+    CommunityFlairOption::create(pool, &option_form("Regular"))
+      .await
+      .unwrap();
+    CommunityFlairOption::create(pool, &option_form("Newcomer"))
+      .await
+      .unwrap();
+    // Flairs are unique per community
+    assert!(CommunityFlairOption::create(pool, &option_form("Regular"))
+      .await
+      .is_err());
+    let options = CommunityFlairOption::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(2, options.len());
+    assert_eq!("Newcomer", options[0].flair);
+
+    let flair_form = CommunityPersonFlairForm {
+      community_id: inserted_community.id,
+      person_id: inserted_person.id,
+      flair: "Regular".to_string(),
+      color: Some("#1e90ff".to_string()),
+      set_by_id: Some(inserted_person.id),
+    };
+    let inserted_flair = CommunityPersonFlair::set(pool, &flair_form).await.unwrap();
+
+    // Setting it again replaces the flair
+    let replaced_flair = CommunityPersonFlair::set(
+      pool,
+      &CommunityPersonFlairForm {
+        flair: "Verified Developer".to_string(),
+        color: None,
+        set_by_id: None,
+        ..flair_form
+      },
+    )
+    .await
+    .unwrap();
+    assert_eq!(inserted_flair.id, replaced_flair.id);
+    assert_eq!(None, replaced_flair.color);
+
+    let read_flair = CommunityPersonFlair::read(pool, inserted_community.id, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(replaced_flair), read_flair);
+
+    let removed = CommunityPersonFlair::remove(pool, inserted_community.id, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(1, removed);
+    let read_removed = CommunityPersonFlair::read(pool, inserted_community.id, inserted_person.id)
+      .await
+      .unwrap();
+    assert!(read_removed.is_none());
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,83 @@
+use crate::{
+  newtypes::{CommentId, PostId},
+  schema::{community, community_mention},
+  source::{
+    community::Community,
+    community_mention::{CommunityMention, CommunityMentionForm},
+  },
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{delete, insert_into},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl CommunityMention {
+  /// Stores the mentions, skipping the ones which are already stored.
+  pub async fn create_many(
+    pool: &mut DbPool<'_>,
+    forms: &[CommunityMentionForm],
+  ) -> Result<usize, Error> {
+    if forms.is_empty() {
+      return Ok(0);
+    }
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_mention::table)
+      .values(forms)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  /// Removes the mentions of a post, before its edited body is scanned again.
+  pub async fn delete_for_post(pool: &mut DbPool<'_>, for_post_id: PostId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(community_mention::table.filter(community_mention::post_id.eq(for_post_id)))
+      .execute(conn)
+      .await
+  }
+
+  /// Removes the mentions of a comment, before its edited content is scanned again.
+  pub async fn delete_for_comment(
+    pool: &mut DbPool<'_>,
+    for_comment_id: CommentId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(community_mention::table.filter(community_mention::comment_id.eq(for_comment_id)))
+      .execute(conn)
+      .await
+  }
+
+  /// The communities which are mentioned in the post.
+  pub async fn list_for_post(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+  ) -> Result<Vec<Community>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_mention::table
+      .inner_join(community::table)
+      .filter(community_mention::post_id.eq(for_post_id))
+      .order_by(community_mention::id)
+      .select(community::all_columns)
+      .load::<Community>(conn)
+      .await
+  }
+
+  /// The communities which are mentioned in the comment.
+  pub async fn list_for_comment(
+    pool: &mut DbPool<'_>,
+    for_comment_id: CommentId,
+  ) -> Result<Vec<Community>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_mention::table
+      .inner_join(community::table)
+      .filter(community_mention::comment_id.eq(for_comment_id))
+      .order_by(community_mention::id)
+      .select(community::all_columns)
+      .load::<Community>(conn)
+      .await
+  }
+}
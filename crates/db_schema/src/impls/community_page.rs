@@ -0,0 +1,143 @@
+use crate::{
+  newtypes::{CommunityId, CommunityPageId},
+  schema::community_page::dsl::{community_id, community_page, slug, title},
+  source::community_page::{CommunityPage, CommunityPageInsertForm, CommunityPageUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for CommunityPage {
+  type InsertForm = CommunityPageInsertForm;
+  type UpdateForm = CommunityPageUpdateForm;
+  type IdType = CommunityPageId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_page)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    page_id: CommunityPageId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_page.find(page_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl CommunityPage {
+  pub async fn read_from_slug(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+    for_slug: &str,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_page
+      .filter(community_id.eq(for_community_id))
+      .filter(slug.eq(for_slug))
+      .first::<Self>(conn)
+      .await
+  }
+
+  /// All pages of the community, ordered by title.
+  pub async fn list_for_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_page
+      .filter(community_id.eq(for_community_id))
+      .order_by(title.asc())
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_page::{CommunityPage, CommunityPageInsertForm, CommunityPageUpdateForm},
+      instance::Instance,
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_page".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let page_form = CommunityPageInsertForm::builder()
+      .community_id(inserted_community.id)
+      .slug("rules".to_string())
+      .title("Rules".to_string())
+      .content("Be nice".to_string())
+      .build();
+    let inserted_page = CommunityPage::create(pool, &page_form).await.unwrap();
+
+    // Slugs are unique per community
+    assert!(CommunityPage::create(pool, &page_form).await.is_err());
+
+    let read_page = CommunityPage::read_from_slug(pool, inserted_community.id, "rules")
+      .await
+      .unwrap();
+    assert_eq!(inserted_page, read_page);
+
+    let update_form = CommunityPageUpdateForm {
+      content: Some("Be very nice".to_string()),
+      updated: Some(Some(naive_now())),
+      ..Default::default()
+    };
+    let updated_page = CommunityPage::update(pool, inserted_page.id, &update_form)
+      .await
+      .unwrap();
+    assert_eq!("Be very nice", updated_page.content);
+    assert_eq!("Rules", updated_page.title);
+
+    let pages = CommunityPage::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![updated_page], pages);
+
+    // Deleting the community deletes its pages
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    let pages_after_delete = CommunityPage::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert!(pages_after_delete.is_empty());
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,166 @@
+use crate::{
+  newtypes::{CommunityId, PostId},
+  schema::community_resync_job,
+  source::community_resync_job::{CommunityResyncJob, CommunityResyncJobForm},
+  utils::{get_conn, naive_now, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl CommunityResyncJob {
+  pub async fn create(pool: &mut DbPool<'_>, form: &CommunityResyncJobForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_resync_job::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, job_id: i32) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_resync_job::table
+      .find(job_id)
+      .first::<Self>(conn)
+      .await
+  }
+
+  /// The resync of the community which is still running, if any.
+  pub async fn read_unfinished_for_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_resync_job::table
+      .filter(community_resync_job::community_id.eq(for_community_id))
+      .filter(community_resync_job::finished.is_null())
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  /// All resyncs which were interrupted before they were finished.
+  pub async fn list_unfinished(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_resync_job::table
+      .filter(community_resync_job::finished.is_null())
+      .order_by(community_resync_job::id)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Stores the result of resyncing one more post.
+  pub async fn advance(
+    pool: &mut DbPool<'_>,
+    job_id: i32,
+    post_id: PostId,
+    failed: bool,
+    comments_added: i32,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_resync_job::table.find(job_id))
+      .set((
+        community_resync_job::posts_done.eq(community_resync_job::posts_done + 1),
+        community_resync_job::posts_failed
+          .eq(community_resync_job::posts_failed + i32::from(failed)),
+        community_resync_job::comments_added
+          .eq(community_resync_job::comments_added + comments_added),
+        community_resync_job::last_post_id.eq(post_id),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn finish(pool: &mut DbPool<'_>, job_id: i32) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_resync_job::table.find(job_id))
+      .set(community_resync_job::finished.eq(naive_now()))
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    newtypes::PostId,
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_resync_job::{CommunityResyncJob, CommunityResyncJobForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_resync_job_progress() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("resync_mod".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_resync".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let form = CommunityResyncJobForm {
+      community_id: inserted_community.id,
+      creator_id: inserted_person.id,
+      posts_total: 2,
+      post_id: None,
+    };
+    let job = CommunityResyncJob::create(pool, &form).await.unwrap();
+    assert_eq!(
+      Some(job.clone()),
+      CommunityResyncJob::read_unfinished_for_community(pool, inserted_community.id)
+        .await
+        .unwrap()
+    );
+
+    CommunityResyncJob::advance(pool, job.id, PostId(20), false, 3)
+      .await
+      .unwrap();
+    let job = CommunityResyncJob::advance(pool, job.id, PostId(10), true, 0)
+      .await
+      .unwrap();
+    assert_eq!(2, job.posts_done);
+    assert_eq!(1, job.posts_failed);
+    assert_eq!(3, job.comments_added);
+    assert_eq!(Some(PostId(10)), job.last_post_id);
+    assert_eq!(
+      vec![job.clone()],
+      CommunityResyncJob::list_unfinished(pool).await.unwrap()
+    );
+
+    let job = CommunityResyncJob::finish(pool, job.id).await.unwrap();
+    assert!(job.finished.is_some());
+    assert_eq!(job, CommunityResyncJob::read(pool, job.id).await.unwrap());
+    assert!(CommunityResyncJob::list_unfinished(pool)
+      .await
+      .unwrap()
+      .is_empty());
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,157 @@
+use crate::{
+  newtypes::{CommunityId, PersonId},
+  schema::{community, community_transfer_request},
+  source::{
+    community::Community,
+    community_transfer_request::{CommunityTransferRequest, CommunityTransferRequestForm},
+  },
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl CommunityTransferRequest {
+  /// Fails if there is already a pending transfer for the community.
+  pub async fn create(
+    pool: &mut DbPool<'_>,
+    form: &CommunityTransferRequestForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_transfer_request::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read_for_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_transfer_request::table
+      .filter(community_transfer_request::community_id.eq(for_community_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn delete_for_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      community_transfer_request::table
+        .filter(community_transfer_request::community_id.eq(for_community_id)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// The communities whose top moderator position is offered to the person.
+  pub async fn list_communities_for_recipient(
+    pool: &mut DbPool<'_>,
+    for_recipient_id: PersonId,
+  ) -> Result<Vec<Community>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_transfer_request::table
+      .inner_join(community::table)
+      .filter(community_transfer_request::recipient_id.eq(for_recipient_id))
+      .select(community::all_columns)
+      .load::<Community>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_transfer_request::{CommunityTransferRequest, CommunityTransferRequestForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = |name: &str| {
+      PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build()
+    };
+    let sender = Person::create(pool, &new_person("transfer_sender"))
+      .await
+      .unwrap();
+    let recipient = Person::create(pool, &new_person("transfer_recipient"))
+      .await
+      .unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_transfer".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let form = CommunityTransferRequestForm {
+      community_id: inserted_community.id,
+      sender_id: sender.id,
+      recipient_id: recipient.id,
+    };
+    let inserted_request = CommunityTransferRequest::create(pool, &form).await.unwrap();
+
+    // Only one pending transfer per community
+    assert!(CommunityTransferRequest::create(pool, &form).await.is_err());
+
+    let read_request = CommunityTransferRequest::read_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(inserted_request), read_request);
+
+    let offered = CommunityTransferRequest::list_communities_for_recipient(pool, recipient.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted_community.clone()], offered);
+    let offered_to_sender =
+      CommunityTransferRequest::list_communities_for_recipient(pool, sender.id)
+        .await
+        .unwrap();
+    assert!(offered_to_sender.is_empty());
+
+    let num_deleted = CommunityTransferRequest::delete_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(1, num_deleted);
+    let read_after_delete =
+      CommunityTransferRequest::read_for_community(pool, inserted_community.id)
+        .await
+        .unwrap();
+    assert_eq!(None, read_after_delete);
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, sender.id).await.unwrap();
+    Person::delete(pool, recipient.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
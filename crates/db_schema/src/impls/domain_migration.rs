@@ -0,0 +1,546 @@
+use crate::{
+  schema::domain_migration,
+  source::domain_migration::{DomainMigration, DomainMigrationForm},
+  utils::{get_conn, naive_now, DbPool},
+  DomainMigrationStage,
+};
+use diesel::{
+  dsl::insert_into,
+  result::Error,
+  sql_query,
+  sql_types::{BigInt, Integer, Text},
+  upsert::excluded,
+  ExpressionMethods,
+  QueryDsl,
+  QueryableByName,
+};
+use diesel_async::RunQueryDsl;
+
+/// How many rows are rewritten in a single transaction.
+const BATCH_SIZE: i64 = 500;
+
+/// The local rows whose urls are rewritten in one stage of the migration.
+struct StageTable {
+  table: &'static str,
+  columns: &'static [&'static str],
+  filter: &'static str,
+}
+
+const PERSON: StageTable = StageTable {
+  table: "person",
+  columns: &[
+    "actor_id",
+    "inbox_url",
+    "shared_inbox_url",
+    "avatar",
+    "banner",
+  ],
+  filter: "local",
+};
+const COMMUNITY: StageTable = StageTable {
+  table: "community",
+  columns: &[
+    "actor_id",
+    "followers_url",
+    "inbox_url",
+    "shared_inbox_url",
+    "moderators_url",
+    "featured_url",
+    "icon",
+    "banner",
+  ],
+  filter: "local",
+};
+const SITE: StageTable = StageTable {
+  table: "site",
+  columns: &["actor_id", "inbox_url", "icon", "banner"],
+  filter: "id IN (SELECT site_id FROM local_site)",
+};
+const POST: StageTable = StageTable {
+  table: "post",
+  columns: &["ap_id", "url", "thumbnail_url"],
+  filter: "local",
+};
+const COMMENT: StageTable = StageTable {
+  table: "comment",
+  columns: &["ap_id"],
+  filter: "local",
+};
+const PRIVATE_MESSAGE: StageTable = StageTable {
+  table: "private_message",
+  columns: &["ap_id"],
+  filter: "local",
+};
+const CUSTOM_EMOJI: StageTable = StageTable {
+  table: "custom_emoji",
+  columns: &["image_url"],
+  filter: "local_site_id IN (SELECT id FROM local_site)",
+};
+
+#[derive(QueryableByName)]
+struct RowCount {
+  #[diesel(sql_type = BigInt)]
+  count: i64,
+}
+
+#[derive(QueryableByName)]
+struct MigratedRow {
+  #[diesel(sql_type = Integer)]
+  id: i32,
+}
+
+impl DomainMigrationStage {
+  /// Whether the stage sends updates to other instances, instead of rewriting rows.
+  pub fn is_federation(self) -> bool {
+    matches!(
+      self,
+      DomainMigrationStage::Federate | DomainMigrationStage::FederatePersons
+    )
+  }
+
+  /// The stage which runs after this one.
+  pub fn next(self) -> Self {
+    use DomainMigrationStage::*;
+    match self {
+      Person => Community,
+      Community => Site,
+      Site => Post,
+      Post => Comment,
+      Comment => PrivateMessage,
+      PrivateMessage => CustomEmoji,
+      CustomEmoji => Instance,
+      Instance => Federate,
+      Federate => FederatePersons,
+      FederatePersons | Done => Done,
+    }
+  }
+
+  fn table(self) -> Option<StageTable> {
+    use DomainMigrationStage::*;
+    match self {
+      Person => Some(PERSON),
+      Community => Some(COMMUNITY),
+      Site => Some(SITE),
+      Post => Some(POST),
+      Comment => Some(COMMENT),
+      PrivateMessage => Some(PRIVATE_MESSAGE),
+      CustomEmoji => Some(CUSTOM_EMOJI),
+      Instance | Federate | FederatePersons | Done => None,
+    }
+  }
+}
+
+impl DomainMigration {
+  /// Starts a migration away from the old domain. Running it again for the same old domain
+  /// starts over from the first stage.
+  pub async fn create(pool: &mut DbPool<'_>, form: &DomainMigrationForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(domain_migration::table)
+      .values(form)
+      .on_conflict(domain_migration::old_domain)
+      .do_update()
+      .set((
+        domain_migration::new_domain.eq(excluded(domain_migration::new_domain)),
+        domain_migration::admin_person_id.eq(excluded(domain_migration::admin_person_id)),
+        domain_migration::stage.eq(DomainMigrationStage::Person),
+        domain_migration::last_id.eq(0),
+        domain_migration::updated.eq(naive_now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// The migration which was interrupted before it was done, if any.
+  pub async fn read_unfinished(pool: &mut DbPool<'_>) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    domain_migration::table
+      .filter(domain_migration::stage.ne(DomainMigrationStage::Done))
+      .order_by(domain_migration::published.desc())
+      .first::<Self>(conn)
+      .await
+      .map(Some)
+      .or_else(|e| match e {
+        Error::NotFound => Ok(None),
+        e => Err(e),
+      })
+  }
+
+  /// All domains which the instance was moved away from. These are kept as aliases of the
+  /// current domain.
+  pub async fn old_domains(pool: &mut DbPool<'_>) -> Result<Vec<String>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    domain_migration::table
+      .select(domain_migration::old_domain)
+      .load::<String>(conn)
+      .await
+  }
+
+  /// How many local rows of each table have urls starting with the old prefix, and would be
+  /// rewritten by the migration.
+  pub async fn count_rows(
+    pool: &mut DbPool<'_>,
+    old_domain: &str,
+    old_prefix: &str,
+  ) -> Result<Vec<(String, i64)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut counts = vec![];
+    for stage in [
+      PERSON,
+      COMMUNITY,
+      SITE,
+      POST,
+      COMMENT,
+      PRIVATE_MESSAGE,
+      CUSTOM_EMOJI,
+    ] {
+      let matches = stage
+        .columns
+        .iter()
+        .map(|c| format!("starts_with({c}, $1)"))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+      let row = sql_query(format!(
+        "SELECT count(*) AS count FROM {} WHERE {} AND ({matches})",
+        stage.table, stage.filter
+      ))
+      .bind::<Text, _>(old_prefix)
+      .get_result::<RowCount>(conn)
+      .await?;
+      counts.push((stage.table.to_string(), row.count));
+    }
+    let row = sql_query("SELECT count(*) AS count FROM instance WHERE domain = $1")
+      .bind::<Text, _>(old_domain)
+      .get_result::<RowCount>(conn)
+      .await?;
+    counts.push(("instance".to_string(), row.count));
+    Ok(counts)
+  }
+
+  /// Rewrites the next batch of rows of the current stage from the old to the new url prefix,
+  /// and stores the progress in the same transaction. Moves on to the next stage once there
+  /// are no rows left.
+  ///
+  /// The federate stages are left to the caller, which uses [`DomainMigration::advance`].
+  pub async fn migrate_next_batch(
+    &self,
+    pool: &mut DbPool<'_>,
+    old_prefix: &str,
+    new_prefix: &str,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let migration = self.clone();
+    let old_prefix = old_prefix.to_string();
+    let new_prefix = new_prefix.to_string();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let (stage, last_id) = if let Some(stage_table) = migration.stage.table() {
+            let set = stage_table
+              .columns
+              .iter()
+              .map(|c| {
+                format!(
+                  "{c} = CASE WHEN starts_with(t.{c}, $1) \
+                   THEN $2 || substr(t.{c}, length($1) + 1) ELSE t.{c} END"
+                )
+              })
+              .collect::<Vec<_>>()
+              .join(", ");
+            let rows = sql_query(format!(
+              "UPDATE {table} t SET {set}
+                 FROM (SELECT id FROM {table} WHERE {filter} AND id > $3 ORDER BY id LIMIT $4) b
+                WHERE t.id = b.id
+            RETURNING t.id",
+              table = stage_table.table,
+              filter = stage_table.filter,
+            ))
+            .bind::<Text, _>(&old_prefix)
+            .bind::<Text, _>(&new_prefix)
+            .bind::<Integer, _>(migration.last_id)
+            .bind::<BigInt, _>(BATCH_SIZE)
+            .load::<MigratedRow>(conn)
+            .await?;
+            match rows.iter().map(|r| r.id).max() {
+              Some(last_id) => (migration.stage, last_id),
+              None => (migration.stage.next(), 0),
+            }
+          } else if migration.stage == DomainMigrationStage::Instance {
+            sql_query("UPDATE instance SET domain = $2, updated = now() WHERE domain = $1")
+              .bind::<Text, _>(&migration.old_domain)
+              .bind::<Text, _>(&migration.new_domain)
+              .execute(conn)
+              .await?;
+            (migration.stage.next(), 0)
+          } else {
+            (migration.stage, migration.last_id)
+          };
+
+          diesel::update(domain_migration::table.find(migration.id))
+            .set((
+              domain_migration::stage.eq(stage),
+              domain_migration::last_id.eq(last_id),
+              domain_migration::updated.eq(naive_now()),
+            ))
+            .get_result::<Self>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+
+  /// Stores the progress of the migration.
+  pub async fn advance(
+    pool: &mut DbPool<'_>,
+    id: i32,
+    stage: DomainMigrationStage,
+    last_id: i32,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(domain_migration::table.find(id))
+      .set((
+        domain_migration::stage.eq(stage),
+        domain_migration::last_id.eq(last_id),
+        domain_migration::updated.eq(naive_now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    newtypes::PersonId,
+    schema::custom_emoji,
+    source::{
+      community::{Community, CommunityInsertForm},
+      custom_emoji::{CustomEmoji, CustomEmojiInsertForm},
+      domain_migration::{DomainMigration, DomainMigrationForm},
+      instance::Instance,
+      local_site::{LocalSite, LocalSiteInsertForm},
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      site::{Site, SiteInsertForm},
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, get_conn},
+    DomainMigrationStage,
+  };
+  use diesel::QueryDsl;
+  use diesel_async::RunQueryDsl;
+  use serial_test::serial;
+
+  #[test]
+  fn test_stage_order() {
+    use DomainMigrationStage::*;
+    let mut stages = vec![Person];
+    while let Some(&stage) = stages.last().filter(|s| **s != Done) {
+      stages.push(stage.next());
+    }
+    assert_eq!(
+      vec![
+        Person,
+        Community,
+        Site,
+        Post,
+        Comment,
+        PrivateMessage,
+        CustomEmoji,
+        Instance,
+        Federate,
+        FederatePersons,
+        Done
+      ],
+      stages
+    );
+    let federation = stages
+      .into_iter()
+      .filter(|s| s.is_federation())
+      .collect::<Vec<_>>();
+    assert_eq!(vec![Federate, FederatePersons], federation);
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_migrate_domain() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "old.tld".to_string())
+      .await
+      .unwrap();
+    let remote_instance = Instance::read_or_create(pool, "remote.tld".to_string())
+      .await
+      .unwrap();
+
+    let site_form = SiteInsertForm::builder()
+      .name("moving site".to_string())
+      .instance_id(instance.id)
+      .build();
+    let site = Site::create(pool, &site_form).await.unwrap();
+    let local_site_form = LocalSiteInsertForm::builder().site_id(site.id).build();
+    let local_site = LocalSite::create(pool, &local_site_form).await.unwrap();
+    let emoji_form = CustomEmojiInsertForm {
+      local_site_id: local_site.id,
+      shortcode: "moving".to_string(),
+      image_url: "https://old.tld/pictrs/image/emoji.png".parse().unwrap(),
+      alt_text: "moving".to_string(),
+      category: "moving".to_string(),
+    };
+    let emoji = CustomEmoji::create(pool, &emoji_form).await.unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("moving".into())
+      .public_key("nada".to_owned())
+      .actor_id(Some("https://old.tld/u/moving".parse().unwrap()))
+      .inbox_url(Some("https://old.tld/u/moving/inbox".parse().unwrap()))
+      .instance_id(instance.id)
+      .build();
+    let person = Person::create(pool, &new_person).await.unwrap();
+
+    // Remote persons keep their urls, even if they point to the old domain
+    let remote_avatar = "https://old.tld/pictrs/image/avatar.png";
+    let new_remote_person = PersonInsertForm::builder()
+      .name("staying".into())
+      .public_key("nada".to_owned())
+      .actor_id(Some("https://remote.tld/u/staying".parse().unwrap()))
+      .avatar(Some(remote_avatar.parse().unwrap()))
+      .local(Some(false))
+      .instance_id(remote_instance.id)
+      .build();
+    let remote_person = Person::create(pool, &new_remote_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("moving".to_string())
+      .title("moving".to_owned())
+      .public_key("nada".to_string())
+      .actor_id(Some("https://old.tld/c/moving".parse().unwrap()))
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("moving".into())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .ap_id(Some("https://old.tld/post/1".parse().unwrap()))
+      .url(Some(
+        "https://old.tld/pictrs/image/post.png".parse().unwrap(),
+      ))
+      .thumbnail_url(Some(
+        "https://old.tld/pictrs/image/thumbnail.png"
+          .parse()
+          .unwrap(),
+      ))
+      .build();
+    let post = Post::create(pool, &new_post).await.unwrap();
+
+    let counts = DomainMigration::count_rows(pool, "old.tld", "https://old.tld/")
+      .await
+      .unwrap();
+    assert!(counts.contains(&("person".to_string(), 1)));
+    assert!(counts.contains(&("community".to_string(), 1)));
+    assert!(counts.contains(&("post".to_string(), 1)));
+    assert!(counts.contains(&("custom_emoji".to_string(), 1)));
+    assert!(counts.contains(&("instance".to_string(), 1)));
+
+    let form = DomainMigrationForm {
+      old_domain: "old.tld".to_string(),
+      new_domain: "new.tld".to_string(),
+      admin_person_id: person.id,
+    };
+    let mut migration = DomainMigration::create(pool, &form).await.unwrap();
+    assert_eq!(DomainMigrationStage::Person, migration.stage);
+
+    // An interrupted migration continues at the stored stage
+    migration = migration
+      .migrate_next_batch(pool, "https://old.tld/", "https://new.tld/")
+      .await
+      .unwrap();
+    assert_eq!(
+      Some(migration.clone()),
+      DomainMigration::read_unfinished(pool).await.unwrap()
+    );
+
+    let mut stages = vec![migration.stage];
+    while !migration.stage.is_federation() {
+      migration = migration
+        .migrate_next_batch(pool, "https://old.tld/", "https://new.tld/")
+        .await
+        .unwrap();
+      if stages.last() != Some(&migration.stage) {
+        stages.push(migration.stage);
+      }
+    }
+    assert_eq!(DomainMigrationStage::Federate, migration.stage);
+    assert_eq!(
+      Some(&DomainMigrationStage::Instance),
+      stages.iter().rev().nth(1)
+    );
+
+    let person = Person::read(pool, person.id).await.unwrap();
+    assert_eq!("https://new.tld/u/moving", person.actor_id.inner().as_str());
+    assert_eq!(
+      "https://new.tld/u/moving/inbox",
+      person.inbox_url.inner().as_str()
+    );
+    let remote_person = Person::read(pool, remote_person.id).await.unwrap();
+    assert_eq!(
+      Some(remote_avatar),
+      remote_person.avatar.as_ref().map(|a| a.inner().as_str())
+    );
+    let community = Community::read(pool, community.id).await.unwrap();
+    assert_eq!(
+      "https://new.tld/c/moving",
+      community.actor_id.inner().as_str()
+    );
+
+    let post = Post::read(pool, post.id).await.unwrap();
+    assert_eq!("https://new.tld/post/1", post.ap_id.inner().as_str());
+    assert_eq!(
+      Some("https://new.tld/pictrs/image/post.png"),
+      post.url.as_ref().map(|u| u.inner().as_str())
+    );
+    assert_eq!(
+      Some("https://new.tld/pictrs/image/thumbnail.png"),
+      post.thumbnail_url.as_ref().map(|u| u.inner().as_str())
+    );
+
+    let emoji = {
+      let conn = &mut get_conn(pool).await.unwrap();
+      custom_emoji::table
+        .find(emoji.id)
+        .first::<CustomEmoji>(conn)
+        .await
+        .unwrap()
+    };
+    assert_eq!(
+      "https://new.tld/pictrs/image/emoji.png",
+      emoji.image_url.inner().as_str()
+    );
+
+    let instance = Instance::read_or_create(pool, "new.tld".to_string())
+      .await
+      .unwrap();
+    assert_eq!(person.instance_id, instance.id);
+    assert_eq!(
+      vec!["old.tld".to_string()],
+      DomainMigration::old_domains(pool).await.unwrap()
+    );
+
+    // Only local persons are federated afterwards
+    let local_persons = Person::list_local_after(pool, PersonId(0), 100)
+      .await
+      .unwrap();
+    assert!(local_persons.iter().any(|p| p.id == person.id));
+    assert!(!local_persons.iter().any(|p| p.id == remote_person.id));
+
+    LocalSite::delete(pool).await.unwrap();
+    Instance::delete(pool, instance.id).await.unwrap();
+    Instance::delete(pool, remote_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,316 @@
+use crate::{
+  newtypes::{CommentId, PersonId, PostId},
+  schema::{comment_edit_history, post_edit_history},
+  source::{
+    comment::{Comment, CommentUpdateForm},
+    edit_history::{
+      CommentEditHistory,
+      CommentEditHistoryInsertForm,
+      PostEditHistory,
+      PostEditHistoryInsertForm,
+    },
+    post::{Post, PostUpdateForm},
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{delete, insert_into},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+/// How many revisions are kept for each post or comment. The oldest ones are dropped first.
+pub const MAX_EDIT_HISTORY_REVISIONS: i64 = 20;
+
+impl PostEditHistory {
+  /// Stores a previous version of the post, and drops the revisions beyond the limit.
+  pub async fn create(
+    pool: &mut DbPool<'_>,
+    form: &PostEditHistoryInsertForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let form = form.clone();
+    conn
+      .build_transaction()
+      .run(|conn| Box::pin(async move { insert_post_revision(conn, form).await }) as _)
+      .await
+  }
+
+  /// Updates the post, and stores its previous name, url and body in the same transaction if
+  /// any of them changed.
+  pub async fn update_post(
+    pool: &mut DbPool<'_>,
+    orig_post: &Post,
+    form: &PostUpdateForm,
+    editor_id: PersonId,
+  ) -> Result<Post, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (orig_post, form) = (orig_post.clone(), form.clone());
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let post = Post::update(&mut conn.into(), orig_post.id, &form).await?;
+          let changed =
+            post.name != orig_post.name || post.url != orig_post.url || post.body != orig_post.body;
+          if changed {
+            let revision = PostEditHistoryInsertForm {
+              post_id: post.id,
+              name: orig_post.name,
+              url: orig_post.url,
+              content: orig_post.body.unwrap_or_default(),
+              editor_id,
+            };
+            insert_post_revision(conn, revision).await?;
+          }
+          Ok(post)
+        }) as _
+      })
+      .await
+  }
+
+  /// The revisions of the post, newest first.
+  pub async fn list_for_post(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_edit_history::table
+      .filter(post_edit_history::post_id.eq(for_post_id))
+      .order_by(post_edit_history::id.desc())
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+impl CommentEditHistory {
+  /// Stores the previous content of the comment, and drops the revisions beyond the limit.
+  pub async fn create(
+    pool: &mut DbPool<'_>,
+    form: &CommentEditHistoryInsertForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let form = form.clone();
+    conn
+      .build_transaction()
+      .run(|conn| Box::pin(async move { insert_comment_revision(conn, form).await }) as _)
+      .await
+  }
+
+  /// Updates the comment, and stores its previous content in the same transaction if it changed.
+  pub async fn update_comment(
+    pool: &mut DbPool<'_>,
+    orig_comment: &Comment,
+    form: &CommentUpdateForm,
+    editor_id: PersonId,
+  ) -> Result<Comment, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (orig_comment, form) = (orig_comment.clone(), form.clone());
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let comment = Comment::update(&mut conn.into(), orig_comment.id, &form).await?;
+          if comment.content != orig_comment.content {
+            let revision = CommentEditHistoryInsertForm {
+              comment_id: comment.id,
+              content: orig_comment.content,
+              editor_id,
+            };
+            insert_comment_revision(conn, revision).await?;
+          }
+          Ok(comment)
+        }) as _
+      })
+      .await
+  }
+
+  /// The revisions of the comment, newest first.
+  pub async fn list_for_comment(
+    pool: &mut DbPool<'_>,
+    for_comment_id: CommentId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    comment_edit_history::table
+      .filter(comment_edit_history::comment_id.eq(for_comment_id))
+      .order_by(comment_edit_history::id.desc())
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+/// Inserts a revision of a post and drops the oldest ones, meant to be called inside a
+/// transaction.
+async fn insert_post_revision(
+  conn: &mut AsyncPgConnection,
+  form: PostEditHistoryInsertForm,
+) -> Result<PostEditHistory, Error> {
+  let post_id = form.post_id;
+  let revision = insert_into(post_edit_history::table)
+    .values(form)
+    .get_result::<PostEditHistory>(conn)
+    .await?;
+  let kept = post_edit_history::table
+    .filter(post_edit_history::post_id.eq(post_id))
+    .order_by(post_edit_history::id.desc())
+    .limit(MAX_EDIT_HISTORY_REVISIONS)
+    .select(post_edit_history::id);
+  delete(
+    post_edit_history::table
+      .filter(post_edit_history::post_id.eq(post_id))
+      .filter(post_edit_history::id.ne_all(kept)),
+  )
+  .execute(conn)
+  .await?;
+  Ok(revision)
+}
+
+/// Inserts a revision of a comment and drops the oldest ones, meant to be called inside a
+/// transaction.
+async fn insert_comment_revision(
+  conn: &mut AsyncPgConnection,
+  form: CommentEditHistoryInsertForm,
+) -> Result<CommentEditHistory, Error> {
+  let comment_id = form.comment_id;
+  let revision = insert_into(comment_edit_history::table)
+    .values(form)
+    .get_result::<CommentEditHistory>(conn)
+    .await?;
+  let kept = comment_edit_history::table
+    .filter(comment_edit_history::comment_id.eq(comment_id))
+    .order_by(comment_edit_history::id.desc())
+    .limit(MAX_EDIT_HISTORY_REVISIONS)
+    .select(comment_edit_history::id);
+  delete(
+    comment_edit_history::table
+      .filter(comment_edit_history::comment_id.eq(comment_id))
+      .filter(comment_edit_history::id.ne_all(kept)),
+  )
+  .execute(conn)
+  .await?;
+  Ok(revision)
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    impls::edit_history::MAX_EDIT_HISTORY_REVISIONS,
+    source::{
+      community::{Community, CommunityInsertForm},
+      edit_history::{PostEditHistory, PostEditHistoryInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm, PostUpdateForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_post_edit_history() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("edit_history".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test community edit history".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    // Only changes of the name, url or body are kept
+    let form = PostUpdateForm {
+      nsfw: Some(true),
+      ..Default::default()
+    };
+    let post = PostEditHistory::update_post(pool, &inserted_post, &form, inserted_person.id)
+      .await
+      .unwrap();
+    assert!(PostEditHistory::list_for_post(pool, post.id)
+      .await
+      .unwrap()
+      .is_empty());
+    let form = PostUpdateForm {
+      name: Some("An edited post".into()),
+      ..Default::default()
+    };
+    let post = PostEditHistory::update_post(pool, &post, &form, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!("An edited post", post.name);
+    let revisions = PostEditHistory::list_for_post(pool, post.id).await.unwrap();
+    assert_eq!(1, revisions.len());
+    assert_eq!("A test post", revisions[0].name);
+    assert_eq!(None, revisions[0].url);
+    assert_eq!("", revisions[0].content);
+
+    // Only the newest revisions are kept
+    for i in 0..MAX_EDIT_HISTORY_REVISIONS {
+      let form = PostEditHistoryInsertForm {
+        post_id: inserted_post.id,
+        name: inserted_post.name.clone(),
+        url: None,
+        content: format!("Revision {i}"),
+        editor_id: inserted_person.id,
+      };
+      PostEditHistory::create(pool, &form).await.unwrap();
+    }
+    let revisions = PostEditHistory::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(
+      MAX_EDIT_HISTORY_REVISIONS,
+      i64::try_from(revisions.len()).unwrap()
+    );
+    assert_eq!(
+      format!("Revision {}", MAX_EDIT_HISTORY_REVISIONS - 1),
+      revisions[0].content
+    );
+    assert_eq!("Revision 0", revisions[revisions.len() - 1].content);
+
+    // Removing the post drops its history
+    let form = PostUpdateForm {
+      removed: Some(true),
+      ..Default::default()
+    };
+    Post::update(pool, inserted_post.id, &form).await.unwrap();
+    let revisions = PostEditHistory::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert!(revisions.is_empty());
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -1,15 +1,18 @@
 use crate::{
-  schema::federation_allowlist,
+  newtypes::InstanceId,
+  schema::{federation_allowlist, instance},
   source::{
     federation_allowlist::{FederationAllowList, FederationAllowListForm},
     instance::Instance,
   },
-  utils::{get_conn, DbPool},
+  utils::{get_conn, naive_now, DbPool},
 };
-use diesel::{dsl::insert_into, result::Error};
+use diesel::{dsl::insert_into, result::Error, upsert::excluded, ExpressionMethods, QueryDsl};
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
 
 impl FederationAllowList {
+  /// Replaces the allowlist with the given domains. Instances which stay allowed keep their
+  /// reason.
   pub async fn replace(pool: &mut DbPool<'_>, list_opt: Option<Vec<String>>) -> Result<(), Error> {
     let conn = &mut get_conn(pool).await?;
     conn
@@ -17,19 +20,24 @@ impl FederationAllowList {
       .run(|conn| {
         Box::pin(async move {
           if let Some(list) = list_opt {
-            Self::clear(conn).await?;
-
+            let mut instance_ids = vec![];
             for domain in list {
               // Upsert all of these as instances
               let instance = Instance::read_or_create(&mut conn.into(), domain).await?;
+              instance_ids.push(instance.id);
+            }
+            Self::clear_except(conn, &instance_ids).await?;
 
+            for instance_id in instance_ids {
               let form = FederationAllowListForm {
-                instance_id: instance.id,
-                updated: None,
+                instance_id,
+                ..Default::default()
               };
               insert_into(federation_allowlist::table)
                 .values(form)
-                .get_result::<Self>(conn)
+                .on_conflict(federation_allowlist::instance_id)
+                .do_nothing()
+                .execute(conn)
                 .await?;
             }
             Ok(())
@@ -41,11 +49,50 @@ impl FederationAllowList {
       .await
   }
 
-  async fn clear(conn: &mut AsyncPgConnection) -> Result<usize, Error> {
-    diesel::delete(federation_allowlist::table)
-      .execute(conn)
+  /// Allows the instance, or updates the reason if it is allowed already.
+  pub async fn allow(pool: &mut DbPool<'_>, form: &FederationAllowListForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_allowlist::table)
+      .values(form)
+      .on_conflict(federation_allowlist::instance_id)
+      .do_update()
+      .set((
+        federation_allowlist::reason.eq(excluded(federation_allowlist::reason)),
+        federation_allowlist::updated.eq(naive_now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn disallow(pool: &mut DbPool<'_>, instance_id: InstanceId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      federation_allowlist::table.filter(federation_allowlist::instance_id.eq(instance_id)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// The allowed instances, together with the reason for allowing each.
+  pub async fn list(pool: &mut DbPool<'_>) -> Result<Vec<(Self, Instance)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    federation_allowlist::table
+      .inner_join(instance::table)
+      .order_by(instance::domain)
+      .load::<(Self, Instance)>(conn)
       .await
   }
+
+  async fn clear_except(
+    conn: &mut AsyncPgConnection,
+    instance_ids: &[InstanceId],
+  ) -> Result<usize, Error> {
+    diesel::delete(
+      federation_allowlist::table.filter(federation_allowlist::instance_id.ne_all(instance_ids)),
+    )
+    .execute(conn)
+    .await
+  }
 }
 #[cfg(test)]
 mod tests {
@@ -1,15 +1,18 @@
 use crate::{
-  schema::federation_blocklist,
+  newtypes::InstanceId,
+  schema::{federation_blocklist, instance},
   source::{
     federation_blocklist::{FederationBlockList, FederationBlockListForm},
     instance::Instance,
   },
-  utils::{get_conn, DbPool},
+  utils::{get_conn, naive_now, DbPool},
 };
-use diesel::{dsl::insert_into, result::Error};
+use diesel::{dsl::insert_into, result::Error, upsert::excluded, ExpressionMethods, QueryDsl};
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
 
 impl FederationBlockList {
+  /// Replaces the blocklist with the given domains. Instances which stay blocked keep their
+  /// reason and expiry.
   pub async fn replace(pool: &mut DbPool<'_>, list_opt: Option<Vec<String>>) -> Result<(), Error> {
     let conn = &mut get_conn(pool).await?;
     conn
@@ -17,19 +20,24 @@ impl FederationBlockList {
       .run(|conn| {
         Box::pin(async move {
           if let Some(list) = list_opt {
-            Self::clear(conn).await?;
-
+            let mut instance_ids = vec![];
             for domain in list {
               // Upsert all of these as instances
               let instance = Instance::read_or_create(&mut conn.into(), domain).await?;
+              instance_ids.push(instance.id);
+            }
+            Self::clear_except(conn, &instance_ids).await?;
 
+            for instance_id in instance_ids {
               let form = FederationBlockListForm {
-                instance_id: instance.id,
-                updated: None,
+                instance_id,
+                ..Default::default()
               };
               insert_into(federation_blocklist::table)
                 .values(form)
-                .get_result::<Self>(conn)
+                .on_conflict(federation_blocklist::instance_id)
+                .do_nothing()
+                .execute(conn)
                 .await?;
             }
             Ok(())
@@ -41,9 +49,101 @@ impl FederationBlockList {
       .await
   }
 
-  async fn clear(conn: &mut AsyncPgConnection) -> Result<usize, Error> {
-    diesel::delete(federation_blocklist::table)
-      .execute(conn)
+  /// Blocks the instance, or updates the reason and expiry if it is blocked already.
+  pub async fn block(pool: &mut DbPool<'_>, form: &FederationBlockListForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_blocklist::table)
+      .values(form)
+      .on_conflict(federation_blocklist::instance_id)
+      .do_update()
+      .set((
+        federation_blocklist::reason.eq(excluded(federation_blocklist::reason)),
+        federation_blocklist::expires.eq(excluded(federation_blocklist::expires)),
+        federation_blocklist::updated.eq(naive_now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn unblock(pool: &mut DbPool<'_>, instance_id: InstanceId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      federation_blocklist::table.filter(federation_blocklist::instance_id.eq(instance_id)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// The blocked instances, together with the reason and expiry of each block.
+  pub async fn list(pool: &mut DbPool<'_>) -> Result<Vec<(Self, Instance)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    federation_blocklist::table
+      .inner_join(instance::table)
+      .order_by(instance::domain)
+      .load::<(Self, Instance)>(conn)
+      .await
+  }
+
+  async fn clear_except(
+    conn: &mut AsyncPgConnection,
+    instance_ids: &[InstanceId],
+  ) -> Result<usize, Error> {
+    diesel::delete(
+      federation_blocklist::table.filter(federation_blocklist::instance_id.ne_all(instance_ids)),
+    )
+    .execute(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      federation_blocklist::{FederationBlockList, FederationBlockListForm},
+      instance::Instance,
+    },
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_block_keeps_reason_on_replace() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "blocked.xyz".to_string())
+      .await
+      .unwrap();
+    let form = FederationBlockListForm {
+      instance_id: instance.id,
+      reason: Some("spam".to_string()),
+      ..Default::default()
+    };
+    FederationBlockList::block(pool, &form).await.unwrap();
+
+    // Editing the list keeps the reason of instances which are still in it
+    let domains = vec!["blocked.xyz".to_string(), "other.xyz".to_string()];
+    FederationBlockList::replace(pool, Some(domains))
       .await
+      .unwrap();
+    let blocked = FederationBlockList::list(pool).await.unwrap();
+    assert_eq!(2, blocked.len());
+    assert_eq!(Some("spam".to_string()), blocked[0].0.reason);
+    assert_eq!("blocked.xyz", blocked[0].1.domain);
+    assert_eq!(None, blocked[1].0.reason);
+
+    FederationBlockList::unblock(pool, instance.id)
+      .await
+      .unwrap();
+    let blocked = Instance::blocklist(pool).await.unwrap();
+    assert_eq!(1, blocked.len());
+    assert_eq!("other.xyz", blocked[0].domain);
+
+    Instance::delete_all(pool).await.unwrap();
   }
 }
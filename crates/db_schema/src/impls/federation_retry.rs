@@ -0,0 +1,226 @@
+use crate::{
+  diesel::dsl::IntervalDsl,
+  newtypes::InstanceId,
+  schema::{federation_retry_queue, instance},
+  source::{
+    federation_retry::{FederationRetry, FederationRetryForm},
+    instance::Instance,
+  },
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use diesel::{
+  dsl::{insert_into, now},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use std::collections::HashMap;
+
+impl FederationRetry {
+  pub async fn create_many(
+    pool: &mut DbPool<'_>,
+    forms: &[FederationRetryForm],
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_retry_queue::table)
+      .values(forms)
+      .execute(conn)
+      .await
+  }
+
+  /// The deliveries whose next attempt is due, oldest first.
+  pub async fn list_due(pool: &mut DbPool<'_>, limit: i64) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    federation_retry_queue::table
+      .filter(federation_retry_queue::next_retry_at.le(now))
+      .order_by(federation_retry_queue::id.asc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Lists the queued deliveries together with their target instance, optionally only those for
+  /// a single instance.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    for_instance_id: Option<InstanceId>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<(Self, Instance)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let mut query = federation_retry_queue::table
+      .inner_join(instance::table)
+      .into_boxed();
+    if let Some(for_instance_id) = for_instance_id {
+      query = query.filter(federation_retry_queue::instance_id.eq(for_instance_id));
+    }
+    query
+      .order_by(federation_retry_queue::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<(Self, Instance)>(conn)
+      .await
+  }
+
+  /// Removes a delivery after it was handed over to the activity queue again.
+  pub async fn delete(pool: &mut DbPool<'_>, retry_id: i64) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(federation_retry_queue::table.find(retry_id))
+      .execute(conn)
+      .await
+  }
+
+  /// Counts a failed attempt, and waits twice as long as before until the next one.
+  pub async fn postpone(pool: &mut DbPool<'_>, retry: &Self) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let wait_minutes = 2_i32
+      .saturating_pow(retry.attempts.unsigned_abs())
+      .min(MAX_WAIT_MINUTES);
+    diesel::update(federation_retry_queue::table.find(retry.id))
+      .set((
+        federation_retry_queue::attempts.eq(federation_retry_queue::attempts + 1),
+        federation_retry_queue::next_retry_at.eq(now + wait_minutes.minutes()),
+      ))
+      .execute(conn)
+      .await
+  }
+
+  /// Drops the deliveries which are older than the given age, and counts them as dropped for their
+  /// instance.
+  pub async fn drop_older_than(pool: &mut DbPool<'_>, max_age_days: i32) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let dropped = diesel::delete(
+            federation_retry_queue::table
+              .filter(federation_retry_queue::published.lt(now - max_age_days.days())),
+          )
+          .returning(federation_retry_queue::instance_id)
+          .get_results::<InstanceId>(conn)
+          .await?;
+          count_dropped(conn, &dropped).await?;
+          Ok(dropped.len())
+        }) as _
+      })
+      .await
+  }
+
+  /// Drops all deliveries for an instance, for example because it is gone for good. They are
+  /// counted as dropped.
+  pub async fn purge_for_instance(
+    pool: &mut DbPool<'_>,
+    for_instance_id: InstanceId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let dropped = diesel::delete(
+            federation_retry_queue::table
+              .filter(federation_retry_queue::instance_id.eq(for_instance_id)),
+          )
+          .returning(federation_retry_queue::instance_id)
+          .get_results::<InstanceId>(conn)
+          .await?;
+          count_dropped(conn, &dropped).await?;
+          Ok(dropped.len())
+        }) as _
+      })
+      .await
+  }
+}
+
+/// The longest wait between two attempts, 12 hours.
+const MAX_WAIT_MINUTES: i32 = 12 * 60;
+
+async fn count_dropped(conn: &mut AsyncPgConnection, dropped: &[InstanceId]) -> Result<(), Error> {
+  let mut counts = HashMap::<InstanceId, i64>::new();
+  for instance_id in dropped {
+    *counts.entry(*instance_id).or_default() += 1;
+  }
+  for (instance_id, count) in counts {
+    diesel::update(instance::table.find(instance_id))
+      .set(instance::dropped_activities.eq(instance::dropped_activities + count))
+      .execute(conn)
+      .await?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      federation_retry::{FederationRetry, FederationRetryForm},
+      instance::Instance,
+    },
+    utils::build_db_pool_for_tests,
+  };
+  use serde_json::json;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "unreachable.tld".to_string())
+      .await
+      .unwrap();
+    let form = |n: i32| FederationRetryForm {
+      instance_id: instance.id,
+      inbox: Url::parse("https://unreachable.tld/inbox").unwrap().into(),
+      actor_id: Url::parse("https://my_domain.tld/u/retry").unwrap().into(),
+      activity: json!({ "id": format!("https://my_domain.tld/activities/{n}") }),
+    };
+    let inserted = FederationRetry::create_many(pool, &[form(1), form(2)])
+      .await
+      .unwrap();
+    assert_eq!(2, inserted);
+
+    let due = FederationRetry::list_due(pool, 10).await.unwrap();
+    assert_eq!(2, due.len());
+    assert_eq!(0, due[0].attempts);
+
+    // After a failed attempt the delivery isn't due anymore
+    FederationRetry::postpone(pool, &due[0]).await.unwrap();
+    let due_after_postpone = FederationRetry::list_due(pool, 10).await.unwrap();
+    assert_eq!(vec![due[1].clone()], due_after_postpone);
+
+    let listed = FederationRetry::list(pool, Some(instance.id), None, None)
+      .await
+      .unwrap();
+    assert_eq!(2, listed.len());
+    assert_eq!(instance, listed[0].1);
+
+    // Nothing is old enough to be dropped yet
+    let dropped = FederationRetry::drop_older_than(pool, 3).await.unwrap();
+    assert_eq!(0, dropped);
+
+    FederationRetry::delete(pool, due[1].id).await.unwrap();
+    let purged = FederationRetry::purge_for_instance(pool, instance.id)
+      .await
+      .unwrap();
+    assert_eq!(1, purged);
+    let instance_after_purge = Instance::read_or_create(pool, "unreachable.tld".to_string())
+      .await
+      .unwrap();
+    assert_eq!(1, instance_after_purge.dropped_activities);
+    assert!(FederationRetry::list_due(pool, 10)
+      .await
+      .unwrap()
+      .is_empty());
+
+    Instance::delete(pool, instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,154 @@
+use crate::{
+  newtypes::{CommentId, PostId},
+  schema::form_submission,
+  source::form_submission::{FormClaim, FormSubmission, FormSubmissionForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{insert_into, now, IntervalDsl},
+  result::Error,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl FormSubmission {
+  /// Records the submission of the form, unless it was submitted already within the last ten
+  /// minutes. Older submissions are forgotten, so that the form id can be used again.
+  pub async fn claim(pool: &mut DbPool<'_>, form: &FormSubmissionForm) -> Result<FormClaim, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let this_form = form_submission::table
+      .filter(form_submission::creator_id.eq(form.creator_id))
+      .filter(form_submission::form_id.eq(&form.form_id));
+    diesel::delete(
+      this_form
+        .clone()
+        .filter(form_submission::published.lt(now - 10.minutes())),
+    )
+    .execute(conn)
+    .await?;
+
+    let inserted = insert_into(form_submission::table)
+      .values(form)
+      .on_conflict_do_nothing()
+      .get_result::<Self>(conn)
+      .await
+      .optional()?;
+    match inserted {
+      Some(submission) => Ok(FormClaim::New(submission)),
+      None => Ok(FormClaim::Existing(this_form.first::<Self>(conn).await?)),
+    }
+  }
+
+  pub async fn set_post(
+    pool: &mut DbPool<'_>,
+    submission_id: i32,
+    for_post_id: PostId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(form_submission::table.find(submission_id))
+      .set(form_submission::post_id.eq(for_post_id))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn set_comment(
+    pool: &mut DbPool<'_>,
+    submission_id: i32,
+    for_comment_id: CommentId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(form_submission::table.find(submission_id))
+      .set(form_submission::comment_id.eq(for_comment_id))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Forgets the submission, so that a failed one can be retried with the same form id.
+  pub async fn release(pool: &mut DbPool<'_>, submission_id: i32) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(form_submission::table.find(submission_id))
+      .execute(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      form_submission::{FormClaim, FormSubmission, FormSubmissionForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_claim() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("double_clicker".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_form_submission".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("Submitted once".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let form = FormSubmissionForm {
+      creator_id: inserted_person.id,
+      form_id: "form-1".to_string(),
+    };
+    let FormClaim::New(submission) = FormSubmission::claim(pool, &form).await.unwrap() else {
+      panic!("first submission must be new");
+    };
+    let submission = FormSubmission::set_post(pool, submission.id, inserted_post.id)
+      .await
+      .unwrap();
+
+    // Submitting the form again gives the post of the first submission
+    let claim = FormSubmission::claim(pool, &form).await.unwrap();
+    assert_eq!(FormClaim::Existing(submission.clone()), claim);
+
+    // A released submission can be retried
+    FormSubmission::release(pool, submission.id).await.unwrap();
+    let claim = FormSubmission::claim(pool, &form).await.unwrap();
+    assert!(matches!(claim, FormClaim::New(s) if s.post_id.is_none()));
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
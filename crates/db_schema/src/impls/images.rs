@@ -0,0 +1,44 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::local_image::dsl::{local_image, local_user_id, pictrs_alias},
+  source::images::{LocalImage, LocalImageForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl LocalImage {
+  pub async fn create(pool: &mut DbPool<'_>, form: &LocalImageForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(local_image)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn get_all_by_local_user_id(
+    pool: &mut DbPool<'_>,
+    user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    local_image
+      .filter(local_user_id.eq(user_id))
+      .load::<Self>(conn)
+      .await
+  }
+
+  pub async fn read_by_alias(pool: &mut DbPool<'_>, alias: &str) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    local_image
+      .filter(pictrs_alias.eq(alias))
+      .first::<Self>(conn)
+      .await
+  }
+
+  pub async fn delete_by_alias(pool: &mut DbPool<'_>, alias: &str) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(local_image.filter(pictrs_alias.eq(alias)))
+      .execute(conn)
+      .await
+  }
+}
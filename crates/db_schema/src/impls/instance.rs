@@ -10,6 +10,7 @@ use diesel::{
   result::Error,
   sql_types::{Nullable, Timestamp},
   ExpressionMethods,
+  OptionalExtension,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
@@ -47,6 +48,18 @@ impl Instance {
       e => e,
     }
   }
+  pub async fn read_from_domain(
+    pool: &mut DbPool<'_>,
+    domain_: &str,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .filter(lower(instance::domain).eq(domain_.to_lowercase()))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
   pub async fn delete(pool: &mut DbPool<'_>, instance_id: InstanceId) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
     diesel::delete(instance::table.find(instance_id))
@@ -54,6 +67,18 @@ impl Instance {
       .await
   }
 
+  pub async fn update(
+    pool: &mut DbPool<'_>,
+    instance_id: InstanceId,
+    form: &InstanceForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(instance::table.find(instance_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
   pub async fn read_all(pool: &mut DbPool<'_>) -> Result<Vec<Instance>, Error> {
     let conn = &mut get_conn(pool).await?;
     instance::table
@@ -71,6 +96,28 @@ impl Instance {
       .await
   }
 
+  /// Domains of the instances which declared that they don't accept NSFW content.
+  pub async fn refusing_nsfw(pool: &mut DbPool<'_>) -> Result<Vec<String>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .select(instance::domain)
+      .filter(instance::refuses_nsfw.eq(true))
+      .get_results(conn)
+      .await
+  }
+
+  /// Counts a rejected NSFW post or community from the instance with the given domain.
+  pub async fn increment_dropped_nsfw_objects(
+    pool: &mut DbPool<'_>,
+    domain_: &str,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(instance::table.filter(lower(instance::domain).eq(domain_.to_lowercase())))
+      .set(instance::dropped_nsfw_objects.eq(instance::dropped_nsfw_objects + 1))
+      .execute(conn)
+      .await
+  }
+
   #[cfg(test)]
   pub async fn delete_all(pool: &mut DbPool<'_>) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
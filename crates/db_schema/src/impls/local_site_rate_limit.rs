@@ -57,6 +57,8 @@ impl LocalSiteRateLimitUpdateForm {
       && self.comment_per_second.is_none()
       && self.search.is_none()
       && self.search_per_second.is_none()
+      && self.export.is_none()
+      && self.export_per_second.is_none()
       && self.updated.is_none()
   }
 }
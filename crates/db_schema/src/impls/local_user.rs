@@ -2,6 +2,7 @@ use crate::{
   newtypes::LocalUserId,
   schema::local_user::dsl::{
     accepted_application,
+    deletion_scheduled_at,
     email,
     email_verified,
     local_user,
@@ -20,6 +21,54 @@ use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 
 impl LocalUser {
+  /// Makes all existing logins of the user invalid.
+  pub async fn invalidate_logins(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(local_user.find(local_user_id))
+      .set(validator_time.eq(naive_now()))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Schedules the deletion of the account, and logs the user out everywhere.
+  pub async fn schedule_deletion(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+    scheduled_at: chrono::NaiveDateTime,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(local_user.find(local_user_id))
+      .set((
+        deletion_scheduled_at.eq(scheduled_at),
+        validator_time.eq(naive_now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn cancel_deletion(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(local_user.find(local_user_id))
+      .set(deletion_scheduled_at.eq::<Option<chrono::NaiveDateTime>>(None))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// The users whose account deletion is due.
+  pub async fn list_due_deletions(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    local_user
+      .filter(deletion_scheduled_at.le(naive_now()))
+      .load::<Self>(conn)
+      .await
+  }
+
   pub async fn update_password(
     pool: &mut DbPool<'_>,
     local_user_id: LocalUserId,
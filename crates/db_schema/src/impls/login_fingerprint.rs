@@ -0,0 +1,97 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::login_fingerprint::dsl::{fingerprint, last_used, local_user_id, login_fingerprint},
+  source::login_fingerprint::{LoginFingerprint, LoginFingerprintForm},
+  utils::{get_conn, naive_now, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl LoginFingerprint {
+  /// Remembers a login with the fingerprint. Returns true if the fingerprint wasn't known yet.
+  pub async fn record(
+    pool: &mut DbPool<'_>,
+    for_local_user_id: LocalUserId,
+    for_fingerprint: &str,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let updated = diesel::update(
+      login_fingerprint
+        .filter(local_user_id.eq(for_local_user_id))
+        .filter(fingerprint.eq(for_fingerprint)),
+    )
+    .set(last_used.eq(naive_now()))
+    .execute(conn)
+    .await?;
+    if updated > 0 {
+      return Ok(false);
+    }
+
+    let form = LoginFingerprintForm {
+      local_user_id: for_local_user_id,
+      fingerprint: for_fingerprint.to_string(),
+    };
+    let inserted = insert_into(login_fingerprint)
+      .values(form)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await?;
+    Ok(inserted > 0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      login_fingerprint::LoginFingerprint,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_record() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("fingerprinted".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("123456".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+
+    let first_login = LoginFingerprint::record(pool, inserted_local_user.id, "abc")
+      .await
+      .unwrap();
+    assert!(first_login);
+    let second_login = LoginFingerprint::record(pool, inserted_local_user.id, "abc")
+      .await
+      .unwrap();
+    assert!(!second_login);
+    let other_device = LoginFingerprint::record(pool, inserted_local_user.id, "def")
+      .await
+      .unwrap();
+    assert!(other_device);
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,138 @@
+use crate::{
+  newtypes::{CommunityId, ModReasonTemplateId},
+  schema::mod_reason_template,
+  source::mod_reason_template::{
+    ModReasonTemplate,
+    ModReasonTemplateInsertForm,
+    ModReasonTemplateUpdateForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for ModReasonTemplate {
+  type InsertForm = ModReasonTemplateInsertForm;
+  type UpdateForm = ModReasonTemplateUpdateForm;
+  type IdType = ModReasonTemplateId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_reason_template::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    template_id: ModReasonTemplateId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(mod_reason_template::table.find(template_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl ModReasonTemplate {
+  /// The reasons which the mods of the community can pick, ordered by text.
+  pub async fn list_for_community(
+    pool: &mut DbPool<'_>,
+    for_community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    mod_reason_template::table
+      .filter(mod_reason_template::community_id.eq(for_community_id))
+      .order_by(mod_reason_template::reason.asc())
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      mod_reason_template::{
+        ModReasonTemplate,
+        ModReasonTemplateInsertForm,
+        ModReasonTemplateUpdateForm,
+      },
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_reasons".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let form = |reason: &str| ModReasonTemplateInsertForm {
+      community_id: inserted_community.id,
+      reason: reason.to_string(),
+    };
+    let spam = ModReasonTemplate::create(pool, &form("Spam"))
+      .await
+      .unwrap();
+    ModReasonTemplate::create(pool, &form("Off topic"))
+      .await
+      .unwrap();
+    // Reasons are unique per community
+    assert!(ModReasonTemplate::create(pool, &form("Spam"))
+      .await
+      .is_err());
+
+    let update_form = ModReasonTemplateUpdateForm {
+      reason: "Spam or advertising".to_string(),
+      updated: naive_now(),
+    };
+    let updated = ModReasonTemplate::update(pool, spam.id, &update_form)
+      .await
+      .unwrap();
+    assert_eq!(spam.id, updated.id);
+    assert!(updated.updated.is_some());
+
+    let templates = ModReasonTemplate::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(2, templates.len());
+    assert_eq!("Off topic", templates[0].reason);
+    assert_eq!(updated, templates[1]);
+
+    ModReasonTemplate::delete(pool, spam.id).await.unwrap();
+    let templates = ModReasonTemplate::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(1, templates.len());
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -1,5 +1,11 @@
 use crate::{
   source::moderator::{
+    AdminAllowInstance,
+    AdminAllowInstanceForm,
+    AdminBlockInstance,
+    AdminBlockInstanceForm,
+    AdminClearPersonProfile,
+    AdminClearPersonProfileForm,
     AdminPurgeComment,
     AdminPurgeCommentForm,
     AdminPurgeCommunity,
@@ -8,6 +14,8 @@ use crate::{
     AdminPurgePersonForm,
     AdminPurgePost,
     AdminPurgePostForm,
+    AdminVerifyCommunity,
+    AdminVerifyCommunityForm,
     ModAdd,
     ModAddCommunity,
     ModAddCommunityForm,
@@ -20,6 +28,8 @@ use crate::{
     ModFeaturePostForm,
     ModHideCommunity,
     ModHideCommunityForm,
+    ModLockComment,
+    ModLockCommentForm,
     ModLockPost,
     ModLockPostForm,
     ModRemoveComment,
@@ -95,6 +105,35 @@ impl Crud for ModLockPost {
   }
 }
 
+#[async_trait]
+impl Crud for ModLockComment {
+  type InsertForm = ModLockCommentForm;
+  type UpdateForm = ModLockCommentForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &ModLockCommentForm) -> Result<Self, Error> {
+    use crate::schema::mod_lock_comment::dsl::mod_lock_comment;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_lock_comment)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: i32,
+    form: &ModLockCommentForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::mod_lock_comment::dsl::mod_lock_comment;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(mod_lock_comment.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Crud for ModFeaturePost {
   type InsertForm = ModFeaturePostForm;
@@ -464,6 +503,122 @@ impl Crud for AdminPurgeComment {
   }
 }
 
+#[async_trait]
+impl Crud for AdminBlockInstance {
+  type InsertForm = AdminBlockInstanceForm;
+  type UpdateForm = AdminBlockInstanceForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    use crate::schema::admin_block_instance::dsl::admin_block_instance;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(admin_block_instance)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: i32,
+    form: &Self::InsertForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::admin_block_instance::dsl::admin_block_instance;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(admin_block_instance.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[async_trait]
+impl Crud for AdminAllowInstance {
+  type InsertForm = AdminAllowInstanceForm;
+  type UpdateForm = AdminAllowInstanceForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    use crate::schema::admin_allow_instance::dsl::admin_allow_instance;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(admin_allow_instance)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: i32,
+    form: &Self::InsertForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::admin_allow_instance::dsl::admin_allow_instance;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(admin_allow_instance.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[async_trait]
+impl Crud for AdminClearPersonProfile {
+  type InsertForm = AdminClearPersonProfileForm;
+  type UpdateForm = AdminClearPersonProfileForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    use crate::schema::admin_clear_person_profile::dsl::admin_clear_person_profile;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(admin_clear_person_profile)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: i32,
+    form: &Self::InsertForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::admin_clear_person_profile::dsl::admin_clear_person_profile;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(admin_clear_person_profile.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[async_trait]
+impl Crud for AdminVerifyCommunity {
+  type InsertForm = AdminVerifyCommunityForm;
+  type UpdateForm = AdminVerifyCommunityForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    use crate::schema::admin_verify_community::dsl::admin_verify_community;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(admin_verify_community)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: i32,
+    form: &Self::InsertForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::admin_verify_community::dsl::admin_verify_community;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(admin_verify_community.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
@@ -575,6 +730,7 @@ mod tests {
       reason: None,
       removed: true,
       when_: inserted_mod_remove_post.when_,
+      post_name: Some("A test post thweep".to_string()),
     };
 
     // lock post
@@ -642,6 +798,7 @@ mod tests {
       reason: None,
       removed: true,
       when_: inserted_mod_remove_comment.when_,
+      comment_snippet: Some("A test comment".to_string()),
     };
 
     // community
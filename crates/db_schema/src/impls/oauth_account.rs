@@ -0,0 +1,161 @@
+use crate::{
+  diesel::dsl::IntervalDsl,
+  newtypes::OAuthProviderId,
+  schema::{oauth_account, oauth_state},
+  source::oauth_account::{OAuthAccount, OAuthAccountInsertForm, OAuthState, OAuthStateInsertForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{insert_into, now},
+  result::Error,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+impl OAuthAccount {
+  pub async fn create(pool: &mut DbPool<'_>, form: &OAuthAccountInsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(oauth_account::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// The account with the given subject identifier at the provider, if it was linked before.
+  pub async fn read_for_provider(
+    pool: &mut DbPool<'_>,
+    for_oauth_provider_id: OAuthProviderId,
+    for_oauth_user_id: &str,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    oauth_account::table
+      .filter(oauth_account::oauth_provider_id.eq(for_oauth_provider_id))
+      .filter(oauth_account::oauth_user_id.eq(for_oauth_user_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+}
+
+impl OAuthState {
+  pub async fn create(pool: &mut DbPool<'_>, form: &OAuthStateInsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(oauth_state::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Removes the pending authorization, so that each one can only be completed once. Fails if it
+  /// doesn't exist or is older than ten minutes.
+  pub async fn take(pool: &mut DbPool<'_>, for_state: Uuid) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      oauth_state::table
+        .filter(oauth_state::state.eq(for_state))
+        .filter(oauth_state::published.gt(now - 10.minutes())),
+    )
+    .get_result::<Self>(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      oauth_account::{OAuthAccount, OAuthAccountInsertForm, OAuthState, OAuthStateInsertForm},
+      oauth_provider::{OAuthProvider, OAuthProviderInsertForm, OAuthProviderUpdateForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_link_account() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("oauth_user".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("123456".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+
+    let provider_form = OAuthProviderInsertForm {
+      display_name: "Example".to_string(),
+      issuer: "https://id.example.com".to_string(),
+      client_id: "lemmy".to_string(),
+      client_secret: "secret".to_string(),
+      scopes: None,
+      enabled: None,
+    };
+    let inserted_provider = OAuthProvider::create(pool, &provider_form).await.unwrap();
+    assert_eq!("openid email", inserted_provider.scopes);
+    assert!(inserted_provider.enabled);
+
+    // Disabled providers aren't listed for users
+    let update_form = OAuthProviderUpdateForm {
+      enabled: Some(false),
+      ..Default::default()
+    };
+    OAuthProvider::update(pool, inserted_provider.id, &update_form)
+      .await
+      .unwrap();
+    assert!(OAuthProvider::list(pool, true).await.unwrap().is_empty());
+    assert_eq!(1, OAuthProvider::list(pool, false).await.unwrap().len());
+
+    // A pending authorization can only be completed once
+    let state_form = OAuthStateInsertForm {
+      oauth_provider_id: inserted_provider.id,
+      local_user_id: Some(inserted_local_user.id),
+    };
+    let inserted_state = OAuthState::create(pool, &state_form).await.unwrap();
+    let taken = OAuthState::take(pool, inserted_state.state).await.unwrap();
+    assert_eq!(inserted_state, taken);
+    assert!(OAuthState::take(pool, inserted_state.state).await.is_err());
+
+    let account_form = OAuthAccountInsertForm {
+      local_user_id: inserted_local_user.id,
+      oauth_provider_id: inserted_provider.id,
+      oauth_user_id: "1234".to_string(),
+    };
+    let inserted_account = OAuthAccount::create(pool, &account_form).await.unwrap();
+    let read_account = OAuthAccount::read_for_provider(pool, inserted_provider.id, "1234")
+      .await
+      .unwrap();
+    assert_eq!(Some(inserted_account), read_account);
+    let other_account = OAuthAccount::read_for_provider(pool, inserted_provider.id, "5678")
+      .await
+      .unwrap();
+    assert_eq!(None, other_account);
+    // The same provider account can't be linked twice
+    assert!(OAuthAccount::create(pool, &account_form).await.is_err());
+
+    OAuthProvider::delete(pool, inserted_provider.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,47 @@
+use crate::{
+  newtypes::OAuthProviderId,
+  schema::oauth_provider,
+  source::oauth_provider::{OAuthProvider, OAuthProviderInsertForm, OAuthProviderUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for OAuthProvider {
+  type InsertForm = OAuthProviderInsertForm;
+  type UpdateForm = OAuthProviderUpdateForm;
+  type IdType = OAuthProviderId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(oauth_provider::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    oauth_provider_id: OAuthProviderId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(oauth_provider::table.find(oauth_provider_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl OAuthProvider {
+  pub async fn list(pool: &mut DbPool<'_>, enabled_only: bool) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = oauth_provider::table.into_boxed();
+    if enabled_only {
+      query = query.filter(oauth_provider::enabled.eq(true));
+    }
+    query.order_by(oauth_provider::id).load::<Self>(conn).await
+  }
+}
@@ -1,18 +1,30 @@
 use crate::{
-  newtypes::{CommunityId, DbUrl, PersonId},
-  schema::{instance, local_user, person, person_follower},
-  source::person::{
-    Person,
-    PersonFollower,
-    PersonFollowerForm,
-    PersonInsertForm,
-    PersonUpdateForm,
+  newtypes::{CommunityId, DbUrl, PersonId, PostId},
+  schema::{
+    comment_report,
+    community,
+    instance,
+    local_user,
+    mod_remove_comment,
+    mod_remove_post,
+    person,
+    person_follower,
+    post,
+    post_report,
+  },
+  source::{
+    comment::Comment,
+    community::Community,
+    moderator::{ModRemoveCommentForm, ModRemovePostForm},
+    person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm, PersonUpdateForm},
+    post::Post,
   },
   traits::{ApubActor, Crud, Followable},
   utils::{functions::lower, get_conn, naive_now, DbPool},
 };
 use diesel::{dsl::insert_into, result::Error, ExpressionMethods, JoinOnDsl, QueryDsl};
 use diesel_async::RunQueryDsl;
+use std::collections::HashMap;
 
 #[async_trait]
 impl Crud for Person {
@@ -62,6 +74,24 @@ impl Person {
       .get_result::<Self>(conn)
       .await
   }
+
+  /// The next local persons after the given id which are not deleted, ordered by id.
+  pub async fn list_local_after(
+    pool: &mut DbPool<'_>,
+    after: PersonId,
+    limit: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    person::table
+      .filter(person::local.eq(true))
+      .filter(person::deleted.eq(false))
+      .filter(person::id.gt(after))
+      .order_by(person::id)
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+  }
+
   pub async fn delete_account(pool: &mut DbPool<'_>, person_id: PersonId) -> Result<Person, Error> {
     let conn = &mut get_conn(pool).await?;
 
@@ -70,6 +100,7 @@ impl Person {
       .set((
         local_user::email.eq::<Option<String>>(None),
         local_user::validator_time.eq(naive_now()),
+        local_user::deletion_scheduled_at.eq::<Option<chrono::NaiveDateTime>>(None),
       ))
       .execute(conn)
       .await?;
@@ -87,6 +118,95 @@ impl Person {
       .get_result::<Self>(conn)
       .await
   }
+
+  /// Removes the posts and comments of the person in local communities in one transaction. Their
+  /// reports are resolved, and each removal gets a modlog entry. Returns the newly removed posts,
+  /// and the newly removed comments together with their community.
+  pub async fn remove_content_in_local_communities(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    mod_person_id: PersonId,
+    reason: Option<String>,
+  ) -> Result<(Vec<Post>, Vec<(Comment, Community)>), Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let posts =
+            Post::remove_for_creator_in_local_communities(&mut conn.into(), person_id).await?;
+          let post_ids: Vec<_> = posts.iter().map(|p| p.id).collect();
+          diesel::update(post_report::table.filter(post_report::post_id.eq_any(&post_ids)))
+            .set((
+              post_report::resolved.eq(true),
+              post_report::resolver_id.eq(mod_person_id),
+              post_report::updated.eq(naive_now()),
+            ))
+            .execute(conn)
+            .await?;
+          let forms: Vec<_> = post_ids
+            .iter()
+            .map(|post_id| ModRemovePostForm {
+              mod_person_id,
+              post_id: *post_id,
+              reason: reason.clone(),
+              removed: Some(true),
+            })
+            .collect();
+          insert_into(mod_remove_post::table)
+            .values(forms)
+            .execute(conn)
+            .await?;
+
+          let comments =
+            Comment::remove_for_creator_in_local_communities(&mut conn.into(), person_id).await?;
+          let comment_ids: Vec<_> = comments.iter().map(|c| c.id).collect();
+          diesel::update(
+            comment_report::table.filter(comment_report::comment_id.eq_any(&comment_ids)),
+          )
+          .set((
+            comment_report::resolved.eq(true),
+            comment_report::resolver_id.eq(mod_person_id),
+            comment_report::updated.eq(naive_now()),
+          ))
+          .execute(conn)
+          .await?;
+          let forms: Vec<_> = comment_ids
+            .iter()
+            .map(|comment_id| ModRemoveCommentForm {
+              mod_person_id,
+              comment_id: *comment_id,
+              reason: reason.clone(),
+              removed: Some(true),
+            })
+            .collect();
+          insert_into(mod_remove_comment::table)
+            .values(forms)
+            .execute(conn)
+            .await?;
+
+          // Load the communities of all comments at once, for federating the removals
+          let comment_post_ids: Vec<_> = comments.iter().map(|c| c.post_id).collect();
+          let communities: HashMap<PostId, Community> = post::table
+            .inner_join(community::table)
+            .filter(post::id.eq_any(comment_post_ids))
+            .select((post::id, community::all_columns))
+            .load::<(PostId, Community)>(conn)
+            .await?
+            .into_iter()
+            .collect();
+          let comments = comments
+            .into_iter()
+            .filter_map(|comment| {
+              let community = communities.get(&comment.post_id)?.clone();
+              Some((comment, community))
+            })
+            .collect();
+          Ok((posts, comments))
+        }) as _
+      })
+      .await
+  }
 }
 
 pub fn is_banned(banned_: bool, expires: Option<chrono::NaiveDateTime>) -> bool {
@@ -199,13 +319,21 @@ mod tests {
   #![allow(clippy::indexing_slicing)]
 
   use crate::{
+    schema::{comment_report, mod_remove_comment, mod_remove_post, post_report},
     source::{
+      comment::{Comment, CommentInsertForm},
+      comment_report::{CommentReport, CommentReportForm},
+      community::{Community, CommunityInsertForm},
       instance::Instance,
       person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm, PersonUpdateForm},
+      post::{Post, PostInsertForm},
+      post_report::{PostReport, PostReportForm},
     },
-    traits::{Crud, Followable},
-    utils::build_db_pool_for_tests,
+    traits::{Crud, Followable, Reportable},
+    utils::{build_db_pool_for_tests, get_conn},
   };
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
   use serial_test::serial;
 
   #[tokio::test]
@@ -310,4 +438,142 @@ mod tests {
     let unfollow = PersonFollower::unfollow(pool, &follow_form).await.unwrap();
     assert_eq!(1, unfollow);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn remove_content_in_local_communities() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let person_form = |name: &str| {
+      PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build()
+    };
+    let spammer = Person::create(pool, &person_form("spammer")).await.unwrap();
+    let admin = Person::create(pool, &person_form("admin")).await.unwrap();
+
+    let community_form = |name: &str, local: bool| {
+      CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .local(Some(local))
+        .instance_id(inserted_instance.id)
+        .build()
+    };
+    let local_community = Community::create(pool, &community_form("local_spam", true))
+      .await
+      .unwrap();
+    let remote_community = Community::create(pool, &community_form("remote_spam", false))
+      .await
+      .unwrap();
+
+    let post_form = |community_id| {
+      PostInsertForm::builder()
+        .name("Spam".into())
+        .creator_id(spammer.id)
+        .community_id(community_id)
+        .build()
+    };
+    let local_post = Post::create(pool, &post_form(local_community.id))
+      .await
+      .unwrap();
+    let remote_post = Post::create(pool, &post_form(remote_community.id))
+      .await
+      .unwrap();
+    let comment_form = CommentInsertForm::builder()
+      .content("Spam".into())
+      .creator_id(spammer.id)
+      .post_id(local_post.id)
+      .build();
+    let comment = Comment::create(pool, &comment_form, None).await.unwrap();
+
+    let post_report_form = PostReportForm {
+      creator_id: admin.id,
+      post_id: local_post.id,
+      original_post_name: local_post.name.clone(),
+      original_post_url: None,
+      original_post_body: None,
+      reason: "spam".to_string(),
+    };
+    let post_report = PostReport::report(pool, &post_report_form).await.unwrap();
+    let comment_report_form = CommentReportForm {
+      creator_id: admin.id,
+      comment_id: comment.id,
+      original_comment_text: comment.content.clone(),
+      reason: "spam".to_string(),
+    };
+    let comment_report = CommentReport::report(pool, &comment_report_form)
+      .await
+      .unwrap();
+
+    let reason = Some("Spam wave".to_string());
+    let (posts, comments) =
+      Person::remove_content_in_local_communities(pool, spammer.id, admin.id, reason.clone())
+        .await
+        .unwrap();
+    // Running it again doesn't remove or log anything new
+    let (posts_again, comments_again) =
+      Person::remove_content_in_local_communities(pool, spammer.id, admin.id, reason.clone())
+        .await
+        .unwrap();
+
+    let remote_post = Post::read(pool, remote_post.id).await.unwrap();
+    let (post_report_resolved, comment_report_resolved, post_log_reasons, comment_log_reasons) = {
+      let conn = &mut get_conn(pool).await.unwrap();
+      let post_report_resolved: bool = post_report::table
+        .find(post_report.id)
+        .select(post_report::resolved)
+        .first(conn)
+        .await
+        .unwrap();
+      let comment_report_resolved: bool = comment_report::table
+        .find(comment_report.id)
+        .select(comment_report::resolved)
+        .first(conn)
+        .await
+        .unwrap();
+      let post_log_reasons: Vec<Option<String>> = mod_remove_post::table
+        .filter(mod_remove_post::mod_person_id.eq(admin.id))
+        .select(mod_remove_post::reason)
+        .load(conn)
+        .await
+        .unwrap();
+      let comment_log_reasons: Vec<Option<String>> = mod_remove_comment::table
+        .filter(mod_remove_comment::mod_person_id.eq(admin.id))
+        .select(mod_remove_comment::reason)
+        .load(conn)
+        .await
+        .unwrap();
+      (
+        post_report_resolved,
+        comment_report_resolved,
+        post_log_reasons,
+        comment_log_reasons,
+      )
+    };
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+
+    assert_eq!(1, posts.len());
+    assert_eq!(local_post.id, posts[0].id);
+    assert!(posts[0].removed);
+    assert_eq!(1, comments.len());
+    assert_eq!(comment.id, comments[0].0.id);
+    assert!(comments[0].0.removed);
+    assert_eq!(local_community.id, comments[0].1.id);
+    assert!(posts_again.is_empty());
+    assert!(comments_again.is_empty());
+    assert!(!remote_post.removed);
+    assert!(post_report_resolved);
+    assert!(comment_report_resolved);
+    assert_eq!(vec![reason.clone()], post_log_reasons);
+    assert_eq!(vec![reason], comment_log_reasons);
+  }
 }
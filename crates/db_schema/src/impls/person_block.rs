@@ -21,6 +21,21 @@ impl PersonBlock {
       .first::<Self>(conn)
       .await
   }
+
+  /// Which of the given persons block the recipient.
+  pub async fn list_blockers(
+    pool: &mut DbPool<'_>,
+    for_recipient_id: PersonId,
+    among_person_ids: &[PersonId],
+  ) -> Result<Vec<PersonId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    person_block
+      .filter(target_id.eq(for_recipient_id))
+      .filter(person_id.eq_any(among_person_ids))
+      .select(person_id)
+      .load::<PersonId>(conn)
+      .await
+  }
 }
 
 #[async_trait]
@@ -0,0 +1,114 @@
+use crate::{
+  newtypes::PersonId,
+  schema::person_keyword_block::dsl::{id, keyword, person_id, person_keyword_block},
+  source::person_keyword_block::{PersonKeywordBlock, PersonKeywordBlockForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{delete, dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PersonKeywordBlock {
+  pub async fn read(pool: &mut DbPool<'_>, for_person_id: PersonId) -> Result<Vec<String>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    person_keyword_block
+      .filter(person_id.eq(for_person_id))
+      .order_by(id)
+      .select(keyword)
+      .load::<String>(conn)
+      .await
+  }
+
+  /// Replaces all of the person's blocked keywords.
+  pub async fn update(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+    keywords: Vec<String>,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          delete(person_keyword_block.filter(person_id.eq(for_person_id)))
+            .execute(conn)
+            .await?;
+
+          for k in keywords {
+            let form = PersonKeywordBlockForm {
+              person_id: for_person_id,
+              keyword: k,
+            };
+            insert_into(person_keyword_block)
+              .values(form)
+              .execute(conn)
+              .await?;
+          }
+          Ok(())
+        }) as _
+      })
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      person_keyword_block::PersonKeywordBlock,
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_update_and_read() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("keyword_blocker".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let keywords = vec!["spoiler".to_string(), "finale".to_string()];
+    PersonKeywordBlock::update(pool, inserted_person.id, keywords.clone())
+      .await
+      .unwrap();
+    let read_keywords = PersonKeywordBlock::read(pool, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(keywords, read_keywords);
+
+    // Updating replaces the previous keywords
+    PersonKeywordBlock::update(pool, inserted_person.id, vec!["ending".to_string()])
+      .await
+      .unwrap();
+    let read_keywords = PersonKeywordBlock::read(pool, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(vec!["ending".to_string()], read_keywords);
+
+    PersonKeywordBlock::update(pool, inserted_person.id, vec![])
+      .await
+      .unwrap();
+    let read_keywords = PersonKeywordBlock::read(pool, inserted_person.id)
+      .await
+      .unwrap();
+    assert!(read_keywords.is_empty());
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
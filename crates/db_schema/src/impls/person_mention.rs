@@ -1,12 +1,13 @@
 use crate::{
   newtypes::{CommentId, PersonId, PersonMentionId},
-  schema::person_mention::dsl::{comment_id, person_mention, read, recipient_id},
+  schema::person_mention::dsl::{comment_id, id, person_mention, read, recipient_id},
   source::person_mention::{PersonMention, PersonMentionInsertForm, PersonMentionUpdateForm},
   traits::Crud,
   utils::{get_conn, DbPool},
 };
 use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
+use std::collections::HashSet;
 
 #[async_trait]
 impl Crud for PersonMention {
@@ -59,6 +60,38 @@ impl PersonMention {
     .await
   }
 
+  /// Marks the mentions as read or unread in a single update. If any of them doesn't exist or
+  /// belongs to another recipient, nothing is changed and `NotFound` is returned.
+  pub async fn mark_many_as_read(
+    pool: &mut DbPool<'_>,
+    mention_ids: &[PersonMentionId],
+    for_recipient_id: PersonId,
+    mark_read: bool,
+  ) -> Result<Vec<PersonMention>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mention_ids: HashSet<_> = mention_ids.iter().copied().collect();
+    let expected = mention_ids.len();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let updated = diesel::update(
+            person_mention
+              .filter(id.eq_any(mention_ids.into_iter().collect::<Vec<_>>()))
+              .filter(recipient_id.eq(for_recipient_id)),
+          )
+          .set(read.eq(mark_read))
+          .get_results::<Self>(conn)
+          .await?;
+          if updated.len() != expected {
+            return Err(Error::NotFound);
+          }
+          Ok(updated)
+        }) as _
+      })
+      .await
+  }
+
   pub async fn read_by_comment_and_person(
     pool: &mut DbPool<'_>,
     for_comment_id: CommentId,
@@ -147,6 +180,7 @@ mod tests {
       recipient_id: inserted_recipient.id,
       comment_id: inserted_comment.id,
       read: None,
+      muted: None,
     };
 
     let inserted_mention = PersonMention::create(pool, &person_mention_form)
@@ -159,6 +193,7 @@ mod tests {
       comment_id: inserted_mention.comment_id,
       read: false,
       published: inserted_mention.published,
+      muted: false,
     };
 
     let read_mention = PersonMention::read(pool, inserted_mention.id)
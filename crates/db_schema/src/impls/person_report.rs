@@ -0,0 +1,77 @@
+use crate::{
+  newtypes::{PersonId, PersonReportId},
+  schema::person_report::dsl::{person_id, person_report, resolved, resolver_id, updated},
+  source::person_report::{PersonReport, PersonReportForm},
+  traits::Reportable,
+  utils::{get_conn, naive_now, DbPool},
+};
+use diesel::{
+  dsl::{insert_into, update},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Reportable for PersonReport {
+  type Form = PersonReportForm;
+  type IdType = PersonReportId;
+  type ObjectIdType = PersonId;
+
+  async fn report(pool: &mut DbPool<'_>, form: &PersonReportForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(person_report)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn resolve(
+    pool: &mut DbPool<'_>,
+    report_id: Self::IdType,
+    by_resolver_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    update(person_report.find(report_id))
+      .set((
+        resolved.eq(true),
+        resolver_id.eq(by_resolver_id),
+        updated.eq(naive_now()),
+      ))
+      .execute(conn)
+      .await
+  }
+
+  async fn resolve_all_for_object(
+    pool: &mut DbPool<'_>,
+    reported_person_id: PersonId,
+    by_resolver_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    update(person_report.filter(person_id.eq(reported_person_id)))
+      .set((
+        resolved.eq(true),
+        resolver_id.eq(by_resolver_id),
+        updated.eq(naive_now()),
+      ))
+      .execute(conn)
+      .await
+  }
+
+  async fn unresolve(
+    pool: &mut DbPool<'_>,
+    report_id: Self::IdType,
+    by_resolver_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    update(person_report.find(report_id))
+      .set((
+        resolved.eq(false),
+        resolver_id.eq(by_resolver_id),
+        updated.eq(naive_now()),
+      ))
+      .execute(conn)
+      .await
+  }
+}
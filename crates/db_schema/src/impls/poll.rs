@@ -0,0 +1,326 @@
+use crate::{
+  newtypes::{PersonId, PollId, PollOptionId, PostId},
+  schema::{poll, poll_option, poll_vote},
+  source::{
+    poll::{Poll, PollInsertForm, PollOption, PollOptionInsertForm, PollVote, PollVoteForm},
+    post::{Post, PostInsertForm},
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{count_star, delete, insert_into},
+  result::Error,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+impl Poll {
+  /// Creates the poll along with its options, which keep the order in which they are given.
+  pub async fn create(
+    pool: &mut DbPool<'_>,
+    form: &PollInsertForm,
+    options: Vec<String>,
+  ) -> Result<(Self, Vec<PollOption>), Error> {
+    let conn = &mut get_conn(pool).await?;
+    let form = form.clone();
+    conn
+      .build_transaction()
+      .run(|conn| Box::pin(async move { insert_poll(conn, form, options).await }) as _)
+      .await
+  }
+
+  /// Creates a post together with its poll, in a single transaction so that a post never exists
+  /// without the poll it was submitted with. The poll form is built once the post id is known.
+  pub async fn create_with_post<F>(
+    pool: &mut DbPool<'_>,
+    post_form: &PostInsertForm,
+    poll_form: F,
+    options: Vec<String>,
+  ) -> Result<(Post, Self, Vec<PollOption>), Error>
+  where
+    F: FnOnce(PostId) -> PollInsertForm + Send,
+  {
+    let conn = &mut get_conn(pool).await?;
+    let post_form = post_form.clone();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let post = Post::create(&mut conn.into(), &post_form).await?;
+          let (poll, options) = insert_poll(conn, poll_form(post.id), options).await?;
+          Ok((post, poll, options))
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn read_for_post(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    poll::table
+      .filter(poll::post_id.eq(for_post_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  /// Checks if the poll no longer accepts votes.
+  pub fn has_ended(&self) -> bool {
+    self
+      .ends_at
+      .is_some_and(|ends_at| ends_at <= chrono::Utc::now().naive_utc())
+  }
+}
+
+/// Inserts a poll and its options, meant to be called inside a transaction.
+async fn insert_poll(
+  conn: &mut AsyncPgConnection,
+  form: PollInsertForm,
+  options: Vec<String>,
+) -> Result<(Poll, Vec<PollOption>), Error> {
+  let poll = insert_into(poll::table)
+    .values(form)
+    .get_result::<Poll>(conn)
+    .await?;
+  let forms = options
+    .into_iter()
+    .zip(0..)
+    .map(|(name, position)| PollOptionInsertForm {
+      poll_id: poll.id,
+      name,
+      position,
+    })
+    .collect::<Vec<_>>();
+  let options = insert_into(poll_option::table)
+    .values(forms)
+    .get_results::<PollOption>(conn)
+    .await?;
+  Ok((poll, options))
+}
+
+impl PollOption {
+  pub async fn list_for_poll(
+    pool: &mut DbPool<'_>,
+    for_poll_id: PollId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    poll_option::table
+      .filter(poll_option::poll_id.eq(for_poll_id))
+      .order_by(poll_option::position)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+impl PollVote {
+  /// Replaces the votes of the person in the poll. An empty list of options removes the vote.
+  pub async fn set(
+    pool: &mut DbPool<'_>,
+    for_poll_id: PollId,
+    for_person_id: PersonId,
+    option_ids: Vec<PollOptionId>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          delete(
+            poll_vote::table
+              .filter(poll_vote::poll_id.eq(for_poll_id))
+              .filter(poll_vote::person_id.eq(for_person_id)),
+          )
+          .execute(conn)
+          .await?;
+          let forms = option_ids
+            .into_iter()
+            .map(|poll_option_id| PollVoteForm {
+              poll_id: for_poll_id,
+              poll_option_id,
+              person_id: for_person_id,
+            })
+            .collect::<Vec<_>>();
+          if forms.is_empty() {
+            return Ok(vec![]);
+          }
+          insert_into(poll_vote::table)
+            .values(forms)
+            .get_results::<Self>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+
+  /// The options which the person voted for.
+  pub async fn list_for_person(
+    pool: &mut DbPool<'_>,
+    for_poll_id: PollId,
+    for_person_id: PersonId,
+  ) -> Result<Vec<PollOptionId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    poll_vote::table
+      .filter(poll_vote::poll_id.eq(for_poll_id))
+      .filter(poll_vote::person_id.eq(for_person_id))
+      .select(poll_vote::poll_option_id)
+      .load::<PollOptionId>(conn)
+      .await
+  }
+
+  /// The number of votes for each option which got any.
+  pub async fn count_by_option(
+    pool: &mut DbPool<'_>,
+    for_poll_id: PollId,
+  ) -> Result<Vec<(PollOptionId, i64)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    poll_vote::table
+      .filter(poll_vote::poll_id.eq(for_poll_id))
+      .group_by(poll_vote::poll_option_id)
+      .select((poll_vote::poll_option_id, count_star()))
+      .load::<(PollOptionId, i64)>(conn)
+      .await
+  }
+
+  /// The number of people who voted in the poll.
+  pub async fn count_voters(pool: &mut DbPool<'_>, for_poll_id: PollId) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    poll_vote::table
+      .filter(poll_vote::poll_id.eq(for_poll_id))
+      .select(poll_vote::person_id)
+      .distinct()
+      .count()
+      .get_result::<i64>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      poll::{Poll, PollInsertForm, PollOption, PollVote},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("poll_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_poll".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A poll".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let form = PollInsertForm {
+      post_id: inserted_post.id,
+      ends_at: None,
+      allow_multiple: true,
+      hide_results_until_voted: false,
+    };
+    let (poll, options) = Poll::create(pool, &form, vec!["Yes".into(), "No".into()])
+      .await
+      .unwrap();
+    assert!(!poll.has_ended());
+    assert_eq!(
+      Some(poll.clone()),
+      Poll::read_for_post(pool, inserted_post.id).await.unwrap()
+    );
+    assert_eq!(
+      options,
+      PollOption::list_for_poll(pool, poll.id).await.unwrap()
+    );
+    assert_eq!("No", options[1].name);
+
+    let (yes, no) = (options[0].id, options[1].id);
+    PollVote::set(pool, poll.id, inserted_person.id, vec![yes, no])
+      .await
+      .unwrap();
+    assert_eq!(1, PollVote::count_voters(pool, poll.id).await.unwrap());
+
+    // Voting again replaces the previous vote
+    PollVote::set(pool, poll.id, inserted_person.id, vec![no])
+      .await
+      .unwrap();
+    assert_eq!(
+      vec![no],
+      PollVote::list_for_person(pool, poll.id, inserted_person.id)
+        .await
+        .unwrap()
+    );
+    assert_eq!(
+      vec![(no, 1)],
+      PollVote::count_by_option(pool, poll.id).await.unwrap()
+    );
+
+    // A post and its poll can also be created together
+    let (other_post, other_poll, other_options) = Poll::create_with_post(
+      pool,
+      &new_post,
+      |post_id| PollInsertForm {
+        post_id,
+        ends_at: None,
+        allow_multiple: false,
+        hide_results_until_voted: true,
+      },
+      vec!["Left".into(), "Right".into()],
+    )
+    .await
+    .unwrap();
+    assert_eq!(other_post.id, other_poll.post_id);
+    assert_eq!(
+      Some(other_poll),
+      Poll::read_for_post(pool, other_post.id).await.unwrap()
+    );
+    assert_eq!(2, other_options.len());
+
+    Post::delete(pool, other_post.id).await.unwrap();
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    assert!(Poll::read_for_post(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .is_none());
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -1,21 +1,26 @@
 use super::instance::coalesce;
 use crate::{
   newtypes::{CommunityId, DbUrl, PersonId, PostId},
-  schema::post::dsl::{
-    ap_id,
-    body,
-    community_id,
-    creator_id,
-    deleted,
-    featured_community,
-    local,
-    name,
-    post,
-    published,
-    removed,
-    thumbnail_url,
-    updated,
-    url,
+  schema::{
+    community,
+    post::dsl::{
+      ap_id,
+      body,
+      community_id,
+      creator_id,
+      deleted,
+      featured_community,
+      featured_local,
+      id,
+      local,
+      name,
+      post,
+      published,
+      removed,
+      thumbnail_url,
+      updated,
+      url,
+    },
   },
   source::post::{
     Post,
@@ -33,7 +38,13 @@ use crate::{
 };
 use ::url::Url;
 use chrono::{Duration, Utc};
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl, TextExpressionMethods};
+use diesel::{
+  dsl::{count_star, insert_into},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+  TextExpressionMethods,
+};
 use diesel_async::RunQueryDsl;
 
 #[async_trait]
@@ -99,6 +110,57 @@ impl Post {
       .await
   }
 
+  /// The remote posts of the community whose comments a resync fetches, newest first. Only posts
+  /// older than `before` are listed, so that an interrupted resync can continue where it stopped.
+  pub async fn list_remote_for_resync(
+    pool: &mut DbPool<'_>,
+    the_community_id: CommunityId,
+    before: Option<PostId>,
+    limit: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = post
+      .filter(community_id.eq(the_community_id))
+      .filter(local.eq(false))
+      .filter(deleted.eq(false))
+      .filter(removed.eq(false))
+      .into_boxed();
+    if let Some(before) = before {
+      query = query.filter(id.lt(before));
+    }
+    query
+      .order_by(id.desc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+  }
+
+  pub async fn count_remote_for_resync(
+    pool: &mut DbPool<'_>,
+    the_community_id: CommunityId,
+  ) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post
+      .filter(community_id.eq(the_community_id))
+      .filter(local.eq(false))
+      .filter(deleted.eq(false))
+      .filter(removed.eq(false))
+      .select(count_star())
+      .first::<i64>(conn)
+      .await
+  }
+
+  pub async fn count_featured_local(pool: &mut DbPool<'_>) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post
+      .filter(deleted.eq(false))
+      .filter(removed.eq(false))
+      .filter(featured_local.eq(true))
+      .count()
+      .get_result::<i64>(conn)
+      .await
+  }
+
   pub async fn list_for_sitemap(
     pool: &mut DbPool<'_>,
   ) -> Result<Vec<(DbUrl, chrono::NaiveDateTime)>, Error> {
@@ -153,6 +215,28 @@ impl Post {
       .await
   }
 
+  /// Removes the posts of the creator in local communities, and returns those which weren't
+  /// removed before.
+  pub async fn remove_for_creator_in_local_communities(
+    pool: &mut DbPool<'_>,
+    for_creator_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let local_communities = community::table
+      .filter(community::local.eq(true))
+      .select(community::id);
+
+    diesel::update(
+      post
+        .filter(creator_id.eq(for_creator_id))
+        .filter(removed.eq(false))
+        .filter(community_id.eq_any(local_communities)),
+    )
+    .set((removed.eq(true), updated.eq(naive_now())))
+    .get_results::<Self>(conn)
+    .await
+  }
+
   pub fn is_post_creator(person_id: PersonId, post_creator_id: PersonId) -> bool {
     person_id == post_creator_id
   }
@@ -275,6 +359,45 @@ impl Likeable for PostLike {
   }
 }
 
+impl PostSaved {
+  /// Activitypub ids of all posts which the person saved.
+  pub async fn list_ap_ids_for_person(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> Result<Vec<DbUrl>, Error> {
+    use crate::schema::post_saved;
+    let conn = &mut get_conn(pool).await?;
+    post_saved::table
+      .inner_join(post)
+      .filter(post_saved::person_id.eq(for_person_id))
+      .select(ap_id)
+      .load::<DbUrl>(conn)
+      .await
+  }
+
+  /// The tags which the person saved posts with, and how many posts have each tag.
+  pub async fn count_tags_for_person(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> Result<Vec<(String, i64)>, Error> {
+    use crate::schema::post_saved;
+    let conn = &mut get_conn(pool).await?;
+    let tags = post_saved::table
+      .filter(post_saved::person_id.eq(for_person_id))
+      .filter(post_saved::tag.is_not_null())
+      .group_by(post_saved::tag)
+      .select((post_saved::tag, count_star()))
+      .load::<(Option<String>, i64)>(conn)
+      .await?;
+    Ok(
+      tags
+        .into_iter()
+        .filter_map(|(tag, count)| Some((tag?, count)))
+        .collect(),
+    )
+  }
+}
+
 #[async_trait]
 impl Saveable for PostSaved {
   type Form = PostSavedForm;
@@ -418,8 +541,11 @@ mod tests {
       ap_id: inserted_post.ap_id.clone(),
       local: true,
       language_id: Default::default(),
+      content_warning: None,
+      embed_provider: None,
       featured_community: false,
       featured_local: false,
+      archive_url: None,
     };
 
     // Post Like
@@ -443,15 +569,36 @@ mod tests {
     let post_saved_form = PostSavedForm {
       post_id: inserted_post.id,
       person_id: inserted_person.id,
+      remind_at: None,
+      tag: None,
     };
 
     let inserted_post_saved = PostSaved::save(pool, &post_saved_form).await.unwrap();
+    let saved_ap_ids = PostSaved::list_ap_ids_for_person(pool, inserted_person.id)
+      .await
+      .unwrap();
+
+    // Saving again with a tag updates the existing row
+    let tagged_post_saved_form = PostSavedForm {
+      post_id: inserted_post.id,
+      person_id: inserted_person.id,
+      remind_at: None,
+      tag: Some("Recipes".into()),
+    };
+    let tagged_post_saved = PostSaved::save(pool, &tagged_post_saved_form)
+      .await
+      .unwrap();
+    let saved_tags = PostSaved::count_tags_for_person(pool, inserted_person.id)
+      .await
+      .unwrap();
 
     let expected_post_saved = PostSaved {
       id: inserted_post_saved.id,
       post_id: inserted_post.id,
       person_id: inserted_person.id,
       published: inserted_post_saved.published,
+      remind_at: None,
+      tag: None,
     };
 
     // Post Read
@@ -498,10 +645,84 @@ mod tests {
     assert_eq!(expected_post, updated_post);
     assert_eq!(expected_post_like, inserted_post_like);
     assert_eq!(expected_post_saved, inserted_post_saved);
+    assert_eq!(vec![inserted_post.ap_id.clone()], saved_ap_ids);
+    assert_eq!(inserted_post_saved.id, tagged_post_saved.id);
+    assert_eq!(vec![("Recipes".to_string(), 1)], saved_tags);
     assert_eq!(expected_post_read, inserted_post_read);
     assert_eq!(1, like_removed);
     assert_eq!(1, saved_removed);
     assert_eq!(1, read_removed);
     assert_eq!(1, num_deleted);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_remove_for_creator_in_local_communities() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("gdpr_remote".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let local_community = CommunityInsertForm::builder()
+      .name("test_local_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let local_community = Community::create(pool, &local_community).await.unwrap();
+
+    let remote_community = CommunityInsertForm::builder()
+      .name("test_remote_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .local(Some(false))
+      .instance_id(inserted_instance.id)
+      .build();
+    let remote_community = Community::create(pool, &remote_community).await.unwrap();
+
+    let new_post = |community_id| {
+      PostInsertForm::builder()
+        .name("A post".into())
+        .creator_id(inserted_person.id)
+        .community_id(community_id)
+        .build()
+    };
+    let local_post = Post::create(pool, &new_post(local_community.id))
+      .await
+      .unwrap();
+    let remote_post = Post::create(pool, &new_post(remote_community.id))
+      .await
+      .unwrap();
+
+    let removed_posts = Post::remove_for_creator_in_local_communities(pool, inserted_person.id)
+      .await
+      .unwrap();
+    // Running it again doesn't remove anything new
+    let removed_again = Post::remove_for_creator_in_local_communities(pool, inserted_person.id)
+      .await
+      .unwrap();
+    let remote_post = Post::read(pool, remote_post.id).await.unwrap();
+
+    Post::delete(pool, local_post.id).await.unwrap();
+    Post::delete(pool, remote_post.id).await.unwrap();
+    Community::delete(pool, local_community.id).await.unwrap();
+    Community::delete(pool, remote_community.id).await.unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+
+    assert_eq!(1, removed_posts.len());
+    assert_eq!(local_post.id, removed_posts[0].id);
+    assert!(removed_posts[0].removed);
+    assert!(removed_again.is_empty());
+    assert!(!remote_post.removed);
+  }
 }
@@ -0,0 +1,157 @@
+use crate::{
+  newtypes::{PersonId, PostReminderId},
+  schema::post_reminder,
+  source::post_reminder::{PostReminder, PostReminderInsertForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PostReminder {
+  pub async fn create(pool: &mut DbPool<'_>, form: &PostReminderInsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_reminder::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// The number of reminders which are still pending for a user.
+  pub async fn count_for_person(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_reminder::table
+      .filter(post_reminder::person_id.eq(for_person_id))
+      .count()
+      .get_result::<i64>(conn)
+      .await
+  }
+
+  /// Only deletes the reminder if it belongs to the given user.
+  pub async fn delete_for_person(
+    pool: &mut DbPool<'_>,
+    post_reminder_id: PostReminderId,
+    for_person_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      post_reminder::table
+        .find(post_reminder_id)
+        .filter(post_reminder::person_id.eq(for_person_id)),
+    )
+    .execute(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_reminder::{PostReminder, PostReminderInsertForm},
+      reminder::Reminder,
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_post_reminder() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("remind_me".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_person_2 = PersonInsertForm::builder()
+      .name("remind_me_not".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person_2 = Person::create(pool, &new_person_2).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test community post reminder".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let due_form = PostReminderInsertForm {
+      person_id: inserted_person.id,
+      post_id: inserted_post.id,
+      remind_at: naive_now() - Duration::minutes(1),
+      note: Some("Read this".to_string()),
+    };
+    PostReminder::create(pool, &due_form).await.unwrap();
+    let later_form = PostReminderInsertForm {
+      remind_at: naive_now() + Duration::days(1),
+      note: None,
+      ..due_form
+    };
+    let later = PostReminder::create(pool, &later_form).await.unwrap();
+    assert_eq!(
+      2,
+      PostReminder::count_for_person(pool, inserted_person.id)
+        .await
+        .unwrap()
+    );
+
+    let due = Reminder::create_due(pool).await.unwrap();
+    assert_eq!(1, due.len());
+    assert_eq!(inserted_post.id, due[0].post_id);
+    assert_eq!(Some("Read this".to_string()), due[0].note);
+    assert_eq!(
+      1,
+      PostReminder::count_for_person(pool, inserted_person.id)
+        .await
+        .unwrap()
+    );
+
+    // Others can't delete the reminder
+    let deleted_by_other = PostReminder::delete_for_person(pool, later.id, inserted_person_2.id)
+      .await
+      .unwrap();
+    assert_eq!(0, deleted_by_other);
+    let deleted = PostReminder::delete_for_person(pool, later.id, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(1, deleted);
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Person::delete(pool, inserted_person_2.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
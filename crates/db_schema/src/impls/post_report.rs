@@ -1,6 +1,7 @@
 use crate::{
   newtypes::{PersonId, PostId, PostReportId},
   schema::post_report::{
+    creator_id,
     dsl::{post_report, resolved, resolver_id, updated},
     post_id,
   },
@@ -9,13 +10,31 @@ use crate::{
   utils::{get_conn, naive_now, DbPool},
 };
 use diesel::{
-  dsl::{insert_into, update},
+  dsl::{exists, insert_into, select, update},
   result::Error,
   ExpressionMethods,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
 
+impl PostReport {
+  /// Whether the person already reported the post.
+  pub async fn exists_for_creator(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+    for_creator_id: PersonId,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      post_report
+        .filter(post_id.eq(for_post_id))
+        .filter(creator_id.eq(for_creator_id)),
+    ))
+    .get_result(conn)
+    .await
+  }
+}
+
 #[async_trait]
 impl Reportable for PostReport {
   type Form = PostReportForm;
@@ -171,4 +190,25 @@ mod tests {
     Person::delete(pool, person.id).await.unwrap();
     Post::delete(pool, report.post_id).await.unwrap();
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_post_report_exists_for_creator() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let (person, report) = init(pool).await;
+
+    let exists = PostReport::exists_for_creator(pool, report.post_id, person.id)
+      .await
+      .unwrap();
+    assert!(exists);
+    let exists_other = PostReport::exists_for_creator(pool, report.post_id, PersonId(-1))
+      .await
+      .unwrap();
+    assert!(!exists_other);
+
+    Person::delete(pool, person.id).await.unwrap();
+    Post::delete(pool, report.post_id).await.unwrap();
+  }
 }
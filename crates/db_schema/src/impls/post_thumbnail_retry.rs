@@ -0,0 +1,128 @@
+use crate::{
+  newtypes::PostId,
+  schema::post_thumbnail_retry,
+  source::post_thumbnail_retry::PostThumbnailRetry,
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{insert_into, now, IntervalDsl},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl PostThumbnailRetry {
+  pub async fn schedule(pool: &mut DbPool<'_>, for_post_id: PostId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_thumbnail_retry::table)
+      .values(post_thumbnail_retry::post_id.eq(for_post_id))
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  /// The oldest retries first. Retries which are older than a day are dropped, as the linked
+  /// pages may well have changed by then.
+  pub async fn list_pending(pool: &mut DbPool<'_>, limit: i64) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      post_thumbnail_retry::table.filter(post_thumbnail_retry::published.lt(now - 1.days())),
+    )
+    .execute(conn)
+    .await?;
+    post_thumbnail_retry::table
+      .order_by(post_thumbnail_retry::published.asc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+  }
+
+  pub async fn remove(pool: &mut DbPool<'_>, for_post_id: PostId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(post_thumbnail_retry::table.find(for_post_id))
+      .execute(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_thumbnail_retry::PostThumbnailRetry,
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_schedule_and_remove() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("thumbnail_retry_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_thumbnail_retry".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    PostThumbnailRetry::schedule(pool, inserted_post.id)
+      .await
+      .unwrap();
+    // Scheduling it twice keeps a single retry
+    let scheduled_again = PostThumbnailRetry::schedule(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(0, scheduled_again);
+
+    let pending = PostThumbnailRetry::list_pending(pool, 10).await.unwrap();
+    assert_eq!(1, pending.len());
+    assert_eq!(inserted_post.id, pending[0].post_id);
+
+    let removed = PostThumbnailRetry::remove(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(1, removed);
+    assert!(PostThumbnailRetry::list_pending(pool, 10)
+      .await
+      .unwrap()
+      .is_empty());
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,135 @@
+use crate::{
+  newtypes::PostId,
+  schema::post_translation,
+  source::post_translation::{PostTranslation, PostTranslationForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PostTranslation {
+  /// Replaces all translations of the post with the given ones.
+  pub async fn replace(
+    pool: &mut DbPool<'_>,
+    for_post_id: PostId,
+    forms: Vec<PostTranslationForm>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(post_translation::table.filter(post_translation::post_id.eq(for_post_id)))
+            .execute(conn)
+            .await?;
+          if forms.is_empty() {
+            return Ok(vec![]);
+          }
+          insert_into(post_translation::table)
+            .values(forms)
+            .get_results::<Self>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn list(pool: &mut DbPool<'_>, for_post_id: PostId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_translation::table
+      .filter(post_translation::post_id.eq(for_post_id))
+      .order_by(post_translation::language_id)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    newtypes::LanguageId,
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_translation::{PostTranslation, PostTranslationForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_replace_translations() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("bilingual".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_translations".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("An announcement".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let form = |language_id: i32, content: &str| PostTranslationForm {
+      post_id: inserted_post.id,
+      language_id: LanguageId(language_id),
+      content: content.to_string(),
+    };
+    PostTranslation::replace(
+      pool,
+      inserted_post.id,
+      vec![form(37, "first"), form(40, "zuerst")],
+    )
+    .await
+    .unwrap();
+    let replaced = PostTranslation::replace(pool, inserted_post.id, vec![form(40, "erste")])
+      .await
+      .unwrap();
+    assert_eq!(1, replaced.len());
+
+    let translations = PostTranslation::list(pool, inserted_post.id).await.unwrap();
+    assert_eq!(1, translations.len());
+    assert_eq!(LanguageId(40), translations[0].language_id);
+    assert_eq!("erste", translations[0].content);
+
+    PostTranslation::replace(pool, inserted_post.id, vec![])
+      .await
+      .unwrap();
+    assert!(PostTranslation::list(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .is_empty());
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
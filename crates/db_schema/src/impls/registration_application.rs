@@ -1,14 +1,25 @@
 use crate::{
-  newtypes::LocalUserId,
-  schema::registration_application::dsl::{local_user_id, registration_application},
+  newtypes::{LocalUserId, PersonId},
+  schema::{
+    local_user,
+    registration_application::dsl::{
+      admin_id,
+      deny_reason,
+      id,
+      local_user_id,
+      published,
+      registration_application,
+    },
+  },
   source::registration_application::{
     RegistrationApplication,
     RegistrationApplicationInsertForm,
     RegistrationApplicationUpdateForm,
   },
   traits::Crud,
-  utils::{get_conn, DbPool},
+  utils::{get_conn, naive_now, DbPool},
 };
+use chrono::Duration;
 use diesel::{insert_into, result::Error, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 
@@ -50,4 +61,51 @@ impl RegistrationApplication {
       .first::<Self>(conn)
       .await
   }
+
+  /// The unread applications which were submitted more than `days` days ago.
+  pub async fn list_expired_ids(pool: &mut DbPool<'_>, days: i32) -> Result<Vec<i32>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    registration_application
+      .filter(admin_id.is_null())
+      .filter(deny_reason.is_null())
+      .filter(published.lt(naive_now() - Duration::days(days.into())))
+      .select(id)
+      .load::<i32>(conn)
+      .await
+  }
+
+  /// Denies the applications, and revokes the acceptance of their users like denying a single
+  /// application does. Without an admin the applications were denied automatically. Only unread
+  /// applications are denied, so that users who were approved in the meantime stay approved.
+  pub async fn deny_many(
+    pool: &mut DbPool<'_>,
+    ids: &[i32],
+    admin_id_: Option<PersonId>,
+    deny_reason_: &str,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let ids = ids.to_vec();
+    let deny_reason_ = deny_reason_.to_string();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let pending = registration_application
+            .filter(id.eq_any(ids))
+            .filter(admin_id.is_null())
+            .filter(deny_reason.is_null());
+          let denied = diesel::update(pending)
+            .set((admin_id.eq(admin_id_), deny_reason.eq(deny_reason_)))
+            .get_results::<Self>(conn)
+            .await?;
+          let local_user_ids: Vec<LocalUserId> = denied.iter().map(|a| a.local_user_id).collect();
+          diesel::update(local_user::table.filter(local_user::id.eq_any(local_user_ids)))
+            .set(local_user::accepted_application.eq(false))
+            .execute(conn)
+            .await?;
+          Ok(denied)
+        }) as _
+      })
+      .await
+  }
 }
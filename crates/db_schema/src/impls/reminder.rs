@@ -0,0 +1,254 @@
+use crate::{
+  newtypes::{CommentId, PersonId, PostId, ReminderId},
+  schema::{comment, comment_saved, post_reminder, post_saved, reminder},
+  source::reminder::{Reminder, ReminderInsertForm, ReminderUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{insert_into, now},
+  result::Error,
+  ExpressionMethods,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use std::collections::HashMap;
+
+#[async_trait]
+impl Crud for Reminder {
+  type InsertForm = ReminderInsertForm;
+  type UpdateForm = ReminderUpdateForm;
+  type IdType = ReminderId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(reminder::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    reminder_id: ReminderId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(reminder::table.find(reminder_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl Reminder {
+  /// Creates the reminders for all saved posts and comments and all post reminders whose reminder
+  /// time has passed, and clears the reminder time so that each one is only delivered once.
+  pub async fn create_due(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let posts =
+            diesel::update(post_saved::table.filter(post_saved::remind_at.le(now.nullable())))
+              .set(post_saved::remind_at.eq(None::<chrono::NaiveDateTime>))
+              .returning((post_saved::person_id, post_saved::post_id))
+              .get_results::<(PersonId, PostId)>(conn)
+              .await?;
+          let comments = diesel::update(
+            comment_saved::table.filter(comment_saved::remind_at.le(now.nullable())),
+          )
+          .set(comment_saved::remind_at.eq(None::<chrono::NaiveDateTime>))
+          .returning((comment_saved::person_id, comment_saved::comment_id))
+          .get_results::<(PersonId, CommentId)>(conn)
+          .await?;
+          let post_reminders =
+            diesel::delete(post_reminder::table.filter(post_reminder::remind_at.le(now)))
+              .returning((
+                post_reminder::person_id,
+                post_reminder::post_id,
+                post_reminder::note,
+              ))
+              .get_results::<(PersonId, PostId, Option<String>)>(conn)
+              .await?;
+
+          let comment_ids = comments.iter().map(|(_, comment_id)| *comment_id);
+          let comment_posts: HashMap<CommentId, PostId> = comment::table
+            .filter(comment::id.eq_any(comment_ids.collect::<Vec<_>>()))
+            .select((comment::id, comment::post_id))
+            .load::<(CommentId, PostId)>(conn)
+            .await?
+            .into_iter()
+            .collect();
+
+          let post_forms = posts
+            .into_iter()
+            .map(|(recipient_id, post_id)| ReminderInsertForm {
+              recipient_id,
+              post_id,
+              comment_id: None,
+              note: None,
+            });
+          let comment_forms = comments
+            .into_iter()
+            .filter_map(|(recipient_id, comment_id)| {
+              Some(ReminderInsertForm {
+                recipient_id,
+                post_id: *comment_posts.get(&comment_id)?,
+                comment_id: Some(comment_id),
+                note: None,
+              })
+            });
+          let reminder_forms = post_reminders
+            .into_iter()
+            .map(|(recipient_id, post_id, note)| ReminderInsertForm {
+              recipient_id,
+              post_id,
+              comment_id: None,
+              note,
+            });
+          let forms: Vec<_> = post_forms
+            .chain(comment_forms)
+            .chain(reminder_forms)
+            .collect();
+          if forms.is_empty() {
+            return Ok(vec![]);
+          }
+          insert_into(reminder::table)
+            .values(forms)
+            .get_results::<Self>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn mark_all_as_read(
+    pool: &mut DbPool<'_>,
+    for_recipient_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      reminder::table
+        .filter(reminder::recipient_id.eq(for_recipient_id))
+        .filter(reminder::read.eq(false)),
+    )
+    .set(reminder::read.eq(true))
+    .get_results::<Self>(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      comment::{Comment, CommentInsertForm, CommentSaved, CommentSavedForm},
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm, PostSaved, PostSavedForm},
+      reminder::{Reminder, ReminderUpdateForm},
+    },
+    traits::{Crud, Saveable},
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_create_due() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("forgetful".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test community reminder".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let comment_form = CommentInsertForm::builder()
+      .content("A test comment".into())
+      .creator_id(inserted_person.id)
+      .post_id(inserted_post.id)
+      .build();
+
+    let inserted_comment = Comment::create(pool, &comment_form, None).await.unwrap();
+
+    let post_saved_form = PostSavedForm {
+      post_id: inserted_post.id,
+      person_id: inserted_person.id,
+      remind_at: Some(naive_now() - Duration::minutes(1)),
+      tag: None,
+    };
+    PostSaved::save(pool, &post_saved_form).await.unwrap();
+
+    // Saving again moves the reminder into the future
+    let mut comment_saved_form = CommentSavedForm {
+      comment_id: inserted_comment.id,
+      person_id: inserted_person.id,
+      remind_at: Some(naive_now() - Duration::minutes(1)),
+      tag: None,
+    };
+    CommentSaved::save(pool, &comment_saved_form).await.unwrap();
+    comment_saved_form.remind_at = Some(naive_now() + Duration::days(1));
+    CommentSaved::save(pool, &comment_saved_form).await.unwrap();
+
+    let due = Reminder::create_due(pool).await.unwrap();
+    assert_eq!(1, due.len());
+    assert_eq!(inserted_person.id, due[0].recipient_id);
+    assert_eq!(inserted_post.id, due[0].post_id);
+    assert_eq!(None, due[0].comment_id);
+    assert!(!due[0].read);
+
+    // Each reminder is only delivered once
+    let due_again = Reminder::create_due(pool).await.unwrap();
+    assert!(due_again.is_empty());
+
+    let update_form = ReminderUpdateForm { read: Some(true) };
+    let updated = Reminder::update(pool, due[0].id, &update_form)
+      .await
+      .unwrap();
+    assert!(updated.read);
+    let marked = Reminder::mark_all_as_read(pool, inserted_person.id)
+      .await
+      .unwrap();
+    assert!(marked.is_empty());
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
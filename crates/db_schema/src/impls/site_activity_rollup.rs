@@ -0,0 +1,117 @@
+use crate::{
+  source::site_activity_rollup::SiteActivityRollup,
+  utils::{get_conn, DbPool},
+  ActivityInterval,
+};
+use diesel::{
+  result::Error,
+  sql_query,
+  sql_types::{BigInt, Text},
+};
+use diesel_async::RunQueryDsl;
+
+/// The maximum number of buckets which can be fetched at once.
+pub const ACTIVITY_BUCKETS_MAX: i64 = 365;
+
+impl SiteActivityRollup {
+  /// Returns the most recent `range` buckets, newest first. Only reads the precomputed daily
+  /// rollups, so this never has to scan the content tables.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    interval: ActivityInterval,
+    range: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let trunc = match interval {
+      ActivityInterval::Day => "day",
+      ActivityInterval::Week => "week",
+      ActivityInterval::Month => "month",
+    };
+    sql_query(
+      "SELECT date_trunc($1, day)::date AS day,
+              sum(users)::bigint AS users,
+              sum(posts)::bigint AS posts,
+              sum(comments)::bigint AS comments,
+              max(users_active) AS users_active,
+              max(instances_linked) AS instances_linked
+         FROM site_activity_rollup
+        GROUP BY 1
+        ORDER BY 1 DESC
+        LIMIT $2",
+    )
+    .bind::<Text, _>(trunc)
+    .bind::<BigInt, _>(range.clamp(1, ACTIVITY_BUCKETS_MAX))
+    .get_results::<Self>(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    schema::site_activity_rollup,
+    source::site_activity_rollup::SiteActivityRollup,
+    utils::{build_db_pool_for_tests, get_conn},
+    ActivityInterval,
+  };
+  use chrono::NaiveDate;
+  use diesel::{insert_into, ExpressionMethods};
+  use diesel_async::RunQueryDsl;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_list_buckets() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    // 2023-07-30 is a sunday, so the first day falls into another week than the others
+    let days = [(30, 7, 1), (1, 8, 2), (2, 8, 3)];
+    for (day, month, value) in days {
+      let conn = &mut get_conn(pool).await.unwrap();
+      insert_into(site_activity_rollup::table)
+        .values((
+          site_activity_rollup::day.eq(NaiveDate::from_ymd_opt(2023, month, day).unwrap()),
+          site_activity_rollup::users.eq(value),
+          site_activity_rollup::posts.eq(value * 10),
+          site_activity_rollup::comments.eq(value * 100),
+          site_activity_rollup::users_active.eq(value),
+          site_activity_rollup::instances_linked.eq(value),
+        ))
+        .execute(conn)
+        .await
+        .unwrap();
+    }
+
+    let daily = SiteActivityRollup::list(pool, ActivityInterval::Day, 2)
+      .await
+      .unwrap();
+    assert_eq!(2, daily.len());
+    assert_eq!(NaiveDate::from_ymd_opt(2023, 8, 2).unwrap(), daily[0].day);
+    assert_eq!(3, daily[0].users);
+
+    let weekly = SiteActivityRollup::list(pool, ActivityInterval::Week, 365)
+      .await
+      .unwrap();
+    assert_eq!(2, weekly.len());
+    assert_eq!(NaiveDate::from_ymd_opt(2023, 7, 31).unwrap(), weekly[0].day);
+    assert_eq!(50, weekly[0].posts);
+    assert_eq!(3, weekly[0].users_active);
+
+    let monthly = SiteActivityRollup::list(pool, ActivityInterval::Month, 365)
+      .await
+      .unwrap();
+    assert_eq!(2, monthly.len());
+    assert_eq!(500, monthly[0].comments);
+    assert_eq!(100, monthly[1].comments);
+
+    let conn = &mut get_conn(pool).await.unwrap();
+    diesel::delete(site_activity_rollup::table)
+      .execute(conn)
+      .await
+      .unwrap();
+  }
+}
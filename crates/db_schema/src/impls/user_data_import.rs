@@ -0,0 +1,213 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::{user_data_import_failure, user_data_import_job},
+  source::user_data_import::{
+    UserDataImportFailure,
+    UserDataImportFailureForm,
+    UserDataImportJob,
+    UserDataImportJobForm,
+  },
+  utils::{get_conn, naive_now, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl UserDataImportJob {
+  pub async fn create(pool: &mut DbPool<'_>, form: &UserDataImportJobForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(user_data_import_job::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, job_id: i32) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    user_data_import_job::table
+      .find(job_id)
+      .first::<Self>(conn)
+      .await
+  }
+
+  /// The import of the user which is still running, if any.
+  pub async fn read_unfinished_for_user(
+    pool: &mut DbPool<'_>,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    user_data_import_job::table
+      .filter(user_data_import_job::local_user_id.eq(for_local_user_id))
+      .filter(user_data_import_job::finished.is_null())
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  /// All imports which were interrupted before they were finished.
+  pub async fn list_unfinished(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    user_data_import_job::table
+      .filter(user_data_import_job::finished.is_null())
+      .order_by(user_data_import_job::id)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Stores that one more item was imported, or why it failed.
+  pub async fn advance(
+    pool: &mut DbPool<'_>,
+    job_id: i32,
+    failure: Option<UserDataImportFailureForm>,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          if let Some(failure) = failure {
+            insert_into(user_data_import_failure::table)
+              .values(failure)
+              .execute(conn)
+              .await?;
+          }
+          diesel::update(user_data_import_job::table.find(job_id))
+            .set(user_data_import_job::items_done.eq(user_data_import_job::items_done + 1))
+            .get_result::<Self>(conn)
+            .await
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn finish(pool: &mut DbPool<'_>, job_id: i32) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(user_data_import_job::table.find(job_id))
+      .set(user_data_import_job::finished.eq(naive_now()))
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl UserDataImportFailure {
+  pub async fn create_many(
+    pool: &mut DbPool<'_>,
+    forms: &[UserDataImportFailureForm],
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(user_data_import_failure::table)
+      .values(forms)
+      .execute(conn)
+      .await
+  }
+
+  pub async fn list_for_job(pool: &mut DbPool<'_>, for_job_id: i32) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    user_data_import_failure::table
+      .filter(user_data_import_failure::job_id.eq(for_job_id))
+      .order_by(user_data_import_failure::id)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      user_data_import::{
+        UserDataImportFailure,
+        UserDataImportFailureForm,
+        UserDataImportJob,
+        UserDataImportJobForm,
+      },
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serde_json::json;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_import_job_progress() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("importer".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("pass".to_string())
+      .build();
+    let local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+
+    let form = UserDataImportJobForm {
+      local_user_id: local_user.id,
+      data: json!({"followed_communities": []}),
+      items_total: 2,
+    };
+    let job = UserDataImportJob::create(pool, &form).await.unwrap();
+    assert_eq!(form.data, job.data);
+    assert_eq!(
+      Some(job.clone()),
+      UserDataImportJob::read_unfinished_for_user(pool, local_user.id)
+        .await
+        .unwrap()
+    );
+
+    let settings_failure = UserDataImportFailureForm {
+      job_id: job.id,
+      item: "bio".to_string(),
+      error: "bio_length_overflow".to_string(),
+    };
+    UserDataImportFailure::create_many(pool, &[settings_failure])
+      .await
+      .unwrap();
+    UserDataImportJob::advance(pool, job.id, None)
+      .await
+      .unwrap();
+    let item_failure = UserDataImportFailureForm {
+      job_id: job.id,
+      item: "https://example.com/c/gone".to_string(),
+      error: "couldnt_find_community".to_string(),
+    };
+    let job = UserDataImportJob::advance(pool, job.id, Some(item_failure))
+      .await
+      .unwrap();
+    assert_eq!(2, job.items_done);
+    assert_eq!(
+      vec![job.clone()],
+      UserDataImportJob::list_unfinished(pool).await.unwrap()
+    );
+    let failures = UserDataImportFailure::list_for_job(pool, job.id)
+      .await
+      .unwrap();
+    assert_eq!(2, failures.len());
+    assert_eq!("bio", failures[0].item);
+    assert_eq!("https://example.com/c/gone", failures[1].item);
+
+    let job = UserDataImportJob::finish(pool, job.id).await.unwrap();
+    assert!(job.finished.is_some());
+    assert_eq!(job, UserDataImportJob::read(pool, job.id).await.unwrap());
+    assert!(UserDataImportJob::list_unfinished(pool)
+      .await
+      .unwrap()
+      .is_empty());
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,405 @@
+use crate::{
+  newtypes::{CommentId, CommunityId, PostId},
+  schema::vote_anomaly,
+  source::vote_anomaly::{VoteAnomaly, VoteAnomalyForm},
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use diesel::{
+  dsl::insert_into,
+  result::Error,
+  sql_query,
+  sql_types::{BigInt, Double, Integer, Text},
+  QueryDsl,
+  QueryableByName,
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use serde::Serialize;
+
+/// How unusual a vote surge has to be before it is recorded.
+#[derive(Debug, Clone, Copy)]
+pub struct VoteAnomalyThresholds {
+  /// The votes of each post and comment are counted over this many minutes.
+  pub window_minutes: i32,
+  /// The usual number of votes per window in a community is taken from this many days.
+  pub baseline_days: i32,
+  /// Surges with fewer votes are ignored.
+  pub min_votes: i64,
+  /// How many standard deviations above the community baseline the votes have to be.
+  pub z_score: f64,
+  /// The share of votes from a single instance which is suspicious.
+  pub instance_share: f64,
+  /// Accounts younger than this many days count as new.
+  pub new_account_days: i32,
+  /// The share of votes from new accounts which is suspicious.
+  pub new_account_share: f64,
+}
+
+/// The votes on one kind of object.
+struct VoteTable {
+  table: &'static str,
+  column: &'static str,
+  is_comment: bool,
+}
+
+const POST_VOTES: VoteTable = VoteTable {
+  table: "post_like",
+  column: "post_id",
+  is_comment: false,
+};
+const COMMENT_VOTES: VoteTable = VoteTable {
+  table: "comment_like",
+  column: "comment_id",
+  is_comment: true,
+};
+
+#[derive(QueryableByName)]
+struct Surge {
+  #[diesel(sql_type = Integer)]
+  object_id: i32,
+  #[diesel(sql_type = Integer)]
+  post_id: PostId,
+  #[diesel(sql_type = Integer)]
+  community_id: CommunityId,
+  #[diesel(sql_type = BigInt)]
+  votes: i64,
+  #[diesel(sql_type = Double)]
+  z_score: f64,
+}
+
+#[derive(QueryableByName, Serialize)]
+struct NamedCount {
+  #[diesel(sql_type = Text)]
+  name: String,
+  #[diesel(sql_type = BigInt)]
+  count: i64,
+}
+
+#[derive(QueryableByName)]
+struct RowCount {
+  #[diesel(sql_type = BigInt)]
+  count: i64,
+}
+
+impl VoteAnomaly {
+  /// Looks for posts and comments whose votes in the last window are far above the usual
+  /// for their community, and records those whose voters mostly come from one instance or are
+  /// new accounts. Surges which were recorded within the window already are skipped.
+  pub async fn detect(
+    pool: &mut DbPool<'_>,
+    thresholds: &VoteAnomalyThresholds,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut anomalies = vec![];
+    for votes in [POST_VOTES, COMMENT_VOTES] {
+      let surges = sql_query(surges_query(&votes))
+        .bind::<Integer, _>(thresholds.window_minutes)
+        .bind::<BigInt, _>(thresholds.min_votes)
+        .bind::<Integer, _>(thresholds.baseline_days)
+        .bind::<Double, _>(thresholds.z_score)
+        .load::<Surge>(conn)
+        .await?;
+      for surge in surges {
+        if let Some(form) = analyze_surge(conn, &votes, &surge, thresholds).await? {
+          let anomaly = insert_into(vote_anomaly::table)
+            .values(form)
+            .get_result::<Self>(conn)
+            .await?;
+          anomalies.push(anomaly);
+        }
+      }
+    }
+    Ok(anomalies)
+  }
+
+  /// The recorded anomalies, newest first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    vote_anomaly::table
+      .order_by(vote_anomaly::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+/// Finds the objects whose votes in the window ($1 minutes) are at least $2, and at least $4
+/// standard deviations above the votes per window which objects of the same community got in
+/// the $3 days before. Windows without votes don't show up in the grouped votes, so the baseline
+/// is taken over every window of the objects which got any votes, counting the empty ones as zero.
+fn surges_query(votes: &VoteTable) -> String {
+  let VoteTable {
+    table,
+    column,
+    is_comment,
+  } = votes;
+  let recorded = if *is_comment {
+    "a.comment_id = r.object_id"
+  } else {
+    "a.post_id = r.object_id AND a.comment_id IS NULL"
+  };
+  format!(
+    "WITH recent AS (
+       SELECT l.{column} AS object_id, l.post_id, p.community_id, count(*) AS votes
+         FROM {table} l
+        INNER JOIN post p ON p.id = l.post_id
+        WHERE l.published > now() - make_interval(mins => $1)
+        GROUP BY l.{column}, l.post_id, p.community_id
+       HAVING count(*) >= $2
+     ), windows AS (
+       SELECT p.community_id, l.{column} AS object_id, count(*) AS votes
+         FROM {table} l
+        INNER JOIN post p ON p.id = l.post_id
+        WHERE l.published > now() - make_interval(days => $3)
+          AND l.published <= now() - make_interval(mins => $1)
+        GROUP BY p.community_id, l.{column}, floor(extract(epoch FROM l.published) / ($1 * 60))
+     ), moments AS (
+       SELECT community_id,
+              sum(votes)::float8 AS votes,
+              sum(votes * votes)::float8 AS squares,
+              (count(DISTINCT object_id) * $3 * 1440.0 / $1)::float8 AS window_count
+         FROM windows
+        GROUP BY community_id
+     ), baseline AS (
+       SELECT community_id,
+              votes / window_count AS mean,
+              sqrt(greatest(squares / window_count - (votes / window_count) ^ 2, 0)) AS stddev
+         FROM moments
+     )
+     SELECT r.object_id, r.post_id, r.community_id, r.votes,
+            ((r.votes - b.mean) / b.stddev)::float8 AS z_score
+       FROM recent r
+      INNER JOIN baseline b ON b.community_id = r.community_id
+      WHERE b.stddev > 0 AND (r.votes - b.mean) / b.stddev >= $4
+        AND NOT EXISTS (
+          SELECT 1 FROM vote_anomaly a
+           WHERE {recorded} AND a.published > now() - make_interval(mins => $1)
+        )"
+  )
+}
+
+/// Counts where the voters of the surge come from and how old their accounts are. Returns the
+/// anomaly to record if either looks coordinated.
+async fn analyze_surge(
+  conn: &mut AsyncPgConnection,
+  votes: &VoteTable,
+  surge: &Surge,
+  thresholds: &VoteAnomalyThresholds,
+) -> Result<Option<VoteAnomalyForm>, Error> {
+  let VoteTable { table, column, .. } = votes;
+  let voters = format!(
+    "FROM {table} l
+     INNER JOIN person pe ON pe.id = l.person_id
+     INNER JOIN instance i ON i.id = pe.instance_id
+     WHERE l.{column} = $1 AND l.published > now() - make_interval(mins => $2)"
+  );
+  let instances = sql_query(format!(
+    "SELECT i.domain AS name, count(*) AS count
+     {voters}
+     GROUP BY i.domain
+     ORDER BY count DESC, i.domain"
+  ))
+  .bind::<Integer, _>(surge.object_id)
+  .bind::<Integer, _>(thresholds.window_minutes)
+  .load::<NamedCount>(conn)
+  .await?;
+  let account_ages = sql_query(format!(
+    "SELECT CASE
+              WHEN pe.published > now() - interval '1 day' THEN 'day'
+              WHEN pe.published > now() - interval '1 week' THEN 'week'
+              WHEN pe.published > now() - interval '1 month' THEN 'month'
+              WHEN pe.published > now() - interval '1 year' THEN 'year'
+              ELSE 'older'
+            END AS name,
+            count(*) AS count
+     {voters}
+     GROUP BY 1
+     ORDER BY min(pe.published) DESC"
+  ))
+  .bind::<Integer, _>(surge.object_id)
+  .bind::<Integer, _>(thresholds.window_minutes)
+  .load::<NamedCount>(conn)
+  .await?;
+  let new_accounts = sql_query(format!(
+    "SELECT count(*) AS count {voters} AND pe.published > now() - make_interval(days => $3)"
+  ))
+  .bind::<Integer, _>(surge.object_id)
+  .bind::<Integer, _>(thresholds.window_minutes)
+  .bind::<Integer, _>(thresholds.new_account_days)
+  .get_result::<RowCount>(conn)
+  .await?;
+
+  let total = surge.votes.max(1) as f64;
+  let top_instance_share = instances.first().map_or(0.0, |i| i.count as f64 / total);
+  let new_account_share = new_accounts.count as f64 / total;
+  if top_instance_share < thresholds.instance_share
+    && new_account_share < thresholds.new_account_share
+  {
+    return Ok(None);
+  }
+
+  Ok(Some(VoteAnomalyForm {
+    community_id: surge.community_id,
+    post_id: surge.post_id,
+    comment_id: votes.is_comment.then_some(CommentId(surge.object_id)),
+    vote_count: i32::try_from(surge.votes).unwrap_or(i32::MAX),
+    z_score: surge.z_score,
+    instance_distribution: serde_json::to_value(instances).unwrap_or_default(),
+    account_age_histogram: serde_json::to_value(account_ages).unwrap_or_default(),
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{
+    impls::vote_anomaly::VoteAnomalyThresholds,
+    newtypes::{InstanceId, PersonId, PostId},
+    schema::post_like,
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm, PostLike, PostLikeForm},
+      vote_anomaly::VoteAnomaly,
+    },
+    traits::{Crud, Likeable},
+    utils::{build_db_pool_for_tests, get_conn, DbPool},
+  };
+  use diesel::{
+    dsl::{now, IntervalDsl},
+    ExpressionMethods,
+    QueryDsl,
+  };
+  use diesel_async::RunQueryDsl;
+  use serial_test::serial;
+
+  const THRESHOLDS: VoteAnomalyThresholds = VoteAnomalyThresholds {
+    window_minutes: 60,
+    baseline_days: 7,
+    min_votes: 5,
+    z_score: 3.0,
+    instance_share: 0.8,
+    new_account_days: 7,
+    new_account_share: 0.8,
+  };
+
+  async fn create_voters(
+    pool: &mut DbPool<'_>,
+    instance_id: InstanceId,
+    prefix: &str,
+    count: usize,
+  ) -> Vec<PersonId> {
+    let mut voters = vec![];
+    for i in 0..count {
+      let form = PersonInsertForm::builder()
+        .name(format!("{prefix}_{i}"))
+        .public_key("pubkey".to_string())
+        .instance_id(instance_id)
+        .build();
+      voters.push(Person::create(pool, &form).await.unwrap().id);
+    }
+    voters
+  }
+
+  async fn vote(pool: &mut DbPool<'_>, post_id: PostId, person_id: PersonId, hours_ago: i32) {
+    let form = PostLikeForm {
+      post_id,
+      person_id,
+      score: 1,
+    };
+    PostLike::like(pool, &form).await.unwrap();
+    let conn = &mut get_conn(pool).await.unwrap();
+    diesel::update(
+      post_like::table
+        .filter(post_like::post_id.eq(post_id))
+        .filter(post_like::person_id.eq(person_id)),
+    )
+    .set(post_like::published.eq(now - hours_ago.hours()))
+    .execute(conn)
+    .await
+    .unwrap();
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_detect() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "anomaly.tld".to_string())
+      .await
+      .unwrap();
+    let other_instance = Instance::read_or_create(pool, "surge.tld".to_string())
+      .await
+      .unwrap();
+    let community_form = CommunityInsertForm::builder()
+      .name("test_community_anomaly".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await.unwrap();
+    let voters = create_voters(pool, instance.id, "anomaly_voter", 3).await;
+    let surge_voters = create_voters(pool, other_instance.id, "anomaly_surge", 10).await;
+    let mut posts = vec![];
+    for name in ["Baseline", "Surge", "Quiet"] {
+      let form = PostInsertForm::builder()
+        .name(name.into())
+        .creator_id(voters[0])
+        .community_id(community.id)
+        .build();
+      posts.push(Post::create(pool, &form).await.unwrap().id);
+    }
+    let (baseline_post, surge_post, quiet_post) = (posts[0], posts[1], posts[2]);
+
+    // A single vote in each of a few earlier windows. Only counting the windows with votes, the
+    // baseline would have no deviation at all.
+    for (voter, hours_ago) in voters.iter().zip([2, 5, 10]) {
+      vote(pool, baseline_post, *voter, hours_ago).await;
+    }
+    for voter in &surge_voters {
+      vote(pool, surge_post, *voter, 0).await;
+    }
+    // Too few votes to count as a surge
+    for voter in surge_voters.iter().take(2) {
+      vote(pool, quiet_post, *voter, 0).await;
+    }
+
+    let anomalies = VoteAnomaly::detect(pool, &THRESHOLDS).await.unwrap();
+    let anomalies: Vec<_> = anomalies
+      .into_iter()
+      .filter(|a| a.community_id == community.id)
+      .collect();
+    assert_eq!(1, anomalies.len());
+    let anomaly = &anomalies[0];
+    assert_eq!(surge_post, anomaly.post_id);
+    assert_eq!(None, anomaly.comment_id);
+    assert_eq!(10, anomaly.vote_count);
+    assert!(anomaly.z_score > THRESHOLDS.z_score);
+    assert_eq!(
+      serde_json::json!([{"name": "surge.tld", "count": 10}]),
+      anomaly.instance_distribution
+    );
+    assert_eq!(
+      serde_json::json!([{"name": "day", "count": 10}]),
+      anomaly.account_age_histogram
+    );
+
+    // The same surge isn't recorded again within the window
+    let anomalies = VoteAnomaly::detect(pool, &THRESHOLDS).await.unwrap();
+    assert!(anomalies.iter().all(|a| a.community_id != community.id));
+    let listed = VoteAnomaly::list(pool, None, None).await.unwrap();
+    assert!(listed.iter().any(|a| a.id == anomaly.id));
+
+    Instance::delete(pool, instance.id).await.unwrap();
+    Instance::delete(pool, other_instance.id).await.unwrap();
+  }
+}
@@ -30,8 +30,13 @@ pub mod newtypes;
 pub mod schema;
 #[cfg(feature = "full")]
 pub mod aliases {
-  use crate::schema::person;
-  diesel::alias!(person as person1: Person1, person as person2: Person2);
+  use crate::schema::{comment, person};
+  diesel::alias!(
+    comment as comment1: Comment1,
+    person as person1: Person1,
+    person as person2: Person2,
+    person as person3: Person3
+  );
 }
 pub mod source;
 #[cfg(feature = "full")]
@@ -74,8 +79,13 @@ pub enum SortType {
   Controversial,
 }
 
-#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy)]
-#[cfg_attr(feature = "full", derive(TS))]
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::CommentSortTypeEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
 #[cfg_attr(feature = "full", ts(export))]
 /// The comment sort types. See here for descriptions: https://join-lemmy.org/docs/en/users/03-votes-and-ranking.html
 pub enum CommentSortType {
@@ -135,6 +145,65 @@ pub enum RegistrationMode {
   Open,
 }
 
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::NotificationDigestEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// How often a user gets an email summarizing their unread notifications.
+pub enum NotificationDigest {
+  #[default]
+  Never,
+  Daily,
+  Weekly,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::DomainMigrationStageEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// The steps of moving the local instance to a new domain, in the order in which they run.
+pub enum DomainMigrationStage {
+  Person,
+  Community,
+  Site,
+  Post,
+  Comment,
+  PrivateMessage,
+  CustomEmoji,
+  Instance,
+  /// Sending updates of the local communities to other instances.
+  Federate,
+  /// Sending updates of the local persons to other instances.
+  FederatePersons,
+  Done,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::DeliveryStatusEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// What happened to an outgoing activity for a single inbox.
+pub enum DeliveryStatus {
+  /// Handed to the activity queue, which sends it and retries on failure.
+  Queued,
+  /// Not sent, because the target instance hasn't been reachable for a while.
+  SkippedDeadInstance,
+}
+
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -166,6 +235,7 @@ pub enum ModlogActionType {
   All,
   ModRemovePost,
   ModLockPost,
+  ModLockComment,
   ModFeaturePost,
   ModRemoveComment,
   ModRemoveCommunity,
@@ -179,6 +249,10 @@ pub enum ModlogActionType {
   AdminPurgeCommunity,
   AdminPurgePost,
   AdminPurgeComment,
+  AdminBlockInstance,
+  AdminAllowInstance,
+  AdminClearPersonProfile,
+  AdminVerifyCommunity,
 }
 
 #[derive(
@@ -194,3 +268,41 @@ pub enum PostFeatureType {
   /// Features to the top of the community.
   Community,
 }
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq,
+)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The bucket size for site activity statistics.
+pub enum ActivityInterval {
+  #[default]
+  Day,
+  Week,
+  Month,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq,
+)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The time range over which the top contributors of a community are counted.
+pub enum ContributorRange {
+  #[default]
+  Week,
+  Month,
+  AllTime,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq,
+)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The time range of the community statistics for moderators.
+pub enum CommunityStatsRange {
+  #[default]
+  ThirtyDays,
+  NinetyDays,
+}
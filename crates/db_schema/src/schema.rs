@@ -1,10 +1,26 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "comment_sort_type_enum"))]
+    pub struct CommentSortTypeEnum;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "delivery_status_enum"))]
+    pub struct DeliveryStatusEnum;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "domain_migration_stage_enum"))]
+    pub struct DomainMigrationStageEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "listing_type_enum"))]
     pub struct ListingTypeEnum;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "notification_digest_enum"))]
+    pub struct NotificationDigestEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "registration_mode_enum"))]
     pub struct RegistrationModeEnum;
@@ -14,6 +30,41 @@ pub mod sql_types {
     pub struct SortTypeEnum;
 }
 
+diesel::table! {
+    admin_allow_instance (id) {
+        id -> Int4,
+        instance_id -> Int4,
+        admin_person_id -> Int4,
+        allowed -> Bool,
+        reason -> Nullable<Text>,
+        when_ -> Timestamp,
+    }
+}
+
+diesel::table! {
+    admin_block_instance (id) {
+        id -> Int4,
+        instance_id -> Int4,
+        admin_person_id -> Int4,
+        blocked -> Bool,
+        reason -> Nullable<Text>,
+        expires -> Nullable<Timestamp>,
+        when_ -> Timestamp,
+    }
+}
+
+diesel::table! {
+    admin_clear_person_profile (id) {
+        id -> Int4,
+        admin_person_id -> Int4,
+        person_id -> Int4,
+        cleared_display_name -> Bool,
+        cleared_bio -> Bool,
+        reason -> Nullable<Text>,
+        when_ -> Timestamp,
+    }
+}
+
 diesel::table! {
     admin_purge_comment (id) {
         id -> Int4,
@@ -52,6 +103,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    admin_verify_community (id) {
+        id -> Int4,
+        admin_person_id -> Int4,
+        community_id -> Int4,
+        verified -> Bool,
+        when_ -> Timestamp,
+    }
+}
+
 diesel::table! {
     captcha_answer (id) {
         id -> Int4,
@@ -80,6 +141,10 @@ diesel::table! {
         path -> Ltree,
         distinguished -> Bool,
         language_id -> Int4,
+        content_warning -> Nullable<Text>,
+        locked -> Bool,
+        backfilled -> Bool,
+        nsfw -> Bool,
     }
 }
 
@@ -97,6 +162,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    comment_edit_history (id) {
+        id -> Int4,
+        comment_id -> Int4,
+        content -> Text,
+        edited_at -> Timestamp,
+        editor_id -> Int4,
+    }
+}
+
 diesel::table! {
     comment_like (id) {
         id -> Int4,
@@ -115,6 +190,7 @@ diesel::table! {
         comment_id -> Int4,
         read -> Bool,
         published -> Timestamp,
+        muted -> Bool,
     }
 }
 
@@ -138,10 +214,16 @@ diesel::table! {
         comment_id -> Int4,
         person_id -> Int4,
         published -> Timestamp,
+        remind_at -> Nullable<Timestamp>,
+        tag -> Nullable<Text>,
     }
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::SortTypeEnum;
+    use super::sql_types::CommentSortTypeEnum;
+
     community (id) {
         id -> Int4,
         #[max_length = 255]
@@ -175,6 +257,12 @@ diesel::table! {
         moderators_url -> Nullable<Varchar>,
         #[max_length = 255]
         featured_url -> Nullable<Varchar>,
+        default_post_sort -> Nullable<SortTypeEnum>,
+        default_comment_sort -> Nullable<CommentSortTypeEnum>,
+        min_account_age_days_to_vote -> Int4,
+        only_followers_can_vote -> Bool,
+        commenting_restricted_to_mods -> Bool,
+        verified -> Bool,
     }
 }
 
@@ -191,6 +279,18 @@ diesel::table! {
         users_active_month -> Int8,
         users_active_half_year -> Int8,
         hot_rank -> Int4,
+        subscribers_local -> Int8,
+    }
+}
+
+diesel::table! {
+    community_aggregates_snapshot (community_id, day) {
+        community_id -> Int4,
+        day -> Date,
+        subscribers -> Int8,
+        posts -> Int8,
+        comments -> Int8,
+        users_active -> Int8,
     }
 }
 
@@ -203,6 +303,42 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    community_contributor_rollup (community_id, person_id) {
+        community_id -> Int4,
+        person_id -> Int4,
+        post_count -> Int8,
+        comment_count -> Int8,
+        score -> Int8,
+    }
+}
+
+diesel::table! {
+    community_digest (id) {
+        id -> Int4,
+        community_id -> Int4,
+        enabled -> Bool,
+        day_of_week -> Int2,
+        hour -> Int2,
+        top_posts -> Int4,
+        min_score -> Int4,
+        feature -> Bool,
+        last_generated_for -> Nullable<Timestamp>,
+        last_post_id -> Nullable<Int4>,
+        published -> Timestamp,
+    }
+}
+
+diesel::table! {
+    community_flair_option (id) {
+        id -> Int4,
+        community_id -> Int4,
+        flair -> Text,
+        color -> Nullable<Text>,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     community_follower (id) {
         id -> Int4,
@@ -221,6 +357,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    community_mention (id) {
+        id -> Int4,
+        community_id -> Int4,
+        post_id -> Nullable<Int4>,
+        comment_id -> Nullable<Int4>,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     community_moderator (id) {
         id -> Int4,
@@ -230,6 +376,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    community_page (id) {
+        id -> Int4,
+        community_id -> Int4,
+        #[max_length = 100]
+        slug -> Varchar,
+        #[max_length = 200]
+        title -> Varchar,
+        content -> Text,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     community_person_ban (id) {
         id -> Int4,
@@ -240,6 +400,44 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    community_person_flair (id) {
+        id -> Int4,
+        community_id -> Int4,
+        person_id -> Int4,
+        flair -> Text,
+        color -> Nullable<Text>,
+        set_by_id -> Nullable<Int4>,
+        published -> Timestamp,
+    }
+}
+
+diesel::table! {
+    community_resync_job (id) {
+        id -> Int4,
+        community_id -> Int4,
+        creator_id -> Int4,
+        posts_total -> Int4,
+        posts_done -> Int4,
+        posts_failed -> Int4,
+        comments_added -> Int4,
+        last_post_id -> Nullable<Int4>,
+        published -> Timestamp,
+        finished -> Nullable<Timestamp>,
+        post_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    community_transfer_request (id) {
+        id -> Int4,
+        community_id -> Int4,
+        sender_id -> Int4,
+        recipient_id -> Int4,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     custom_emoji (id) {
         id -> Int4,
@@ -263,6 +461,24 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DomainMigrationStageEnum;
+
+    domain_migration (id) {
+        id -> Int4,
+        #[max_length = 255]
+        old_domain -> Varchar,
+        #[max_length = 255]
+        new_domain -> Varchar,
+        admin_person_id -> Int4,
+        stage -> DomainMigrationStageEnum,
+        last_id -> Int4,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     email_verification (id) {
         id -> Int4,
@@ -279,6 +495,7 @@ diesel::table! {
         instance_id -> Int4,
         published -> Timestamp,
         updated -> Nullable<Timestamp>,
+        reason -> Nullable<Text>,
     }
 }
 
@@ -288,6 +505,32 @@ diesel::table! {
         instance_id -> Int4,
         published -> Timestamp,
         updated -> Nullable<Timestamp>,
+        reason -> Nullable<Text>,
+        expires -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    federation_retry_queue (id) {
+        id -> Int8,
+        instance_id -> Int4,
+        inbox -> Text,
+        actor_id -> Text,
+        activity -> Json,
+        attempts -> Int4,
+        next_retry_at -> Timestamp,
+        published -> Timestamp,
+    }
+}
+
+diesel::table! {
+    form_submission (id) {
+        id -> Int4,
+        creator_id -> Int4,
+        form_id -> Text,
+        post_id -> Nullable<Int4>,
+        comment_id -> Nullable<Int4>,
+        published -> Timestamp,
     }
 }
 
@@ -302,6 +545,9 @@ diesel::table! {
         software -> Nullable<Varchar>,
         #[max_length = 255]
         version -> Nullable<Varchar>,
+        refuses_nsfw -> Bool,
+        dropped_nsfw_objects -> Int8,
+        dropped_activities -> Int8,
     }
 }
 
@@ -314,6 +560,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    local_image (pictrs_alias) {
+        pictrs_alias -> Text,
+        pictrs_delete_token -> Text,
+        local_user_id -> Int4,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ListingTypeEnum;
@@ -344,6 +599,18 @@ diesel::table! {
         updated -> Nullable<Timestamp>,
         registration_mode -> RegistrationModeEnum,
         reports_email_admins -> Bool,
+        enable_url_archiving -> Bool,
+        url_archiving_excluded_domains -> Nullable<Text>,
+        featured_local_posts_max -> Int4,
+        disallow_nsfw_content -> Bool,
+        federate_nsfw_outbound -> Bool,
+        oauth_registration -> Bool,
+        content_warning_sets_nsfw -> Bool,
+        community_digest_bot_id -> Nullable<Int4>,
+        account_deletion_cooling_off_days -> Int4,
+        application_expire_days -> Int4,
+        require_mod_action_reason -> Bool,
+        proxy_remote_images -> Bool,
     }
 }
 
@@ -365,6 +632,8 @@ diesel::table! {
         search_per_second -> Int4,
         published -> Timestamp,
         updated -> Nullable<Timestamp>,
+        export -> Int4,
+        export_per_second -> Int4,
     }
 }
 
@@ -372,6 +641,7 @@ diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::SortTypeEnum;
     use super::sql_types::ListingTypeEnum;
+    use super::sql_types::NotificationDigestEnum;
 
     local_user (id) {
         id -> Int4,
@@ -399,6 +669,13 @@ diesel::table! {
         blur_nsfw -> Bool,
         auto_expand -> Bool,
         infinite_scroll_enabled -> Bool,
+        hide_content_below_score -> Nullable<Int4>,
+        send_notification_digest -> NotificationDigestEnum,
+        last_digest_sent_at -> Nullable<Timestamp>,
+        notify_new_logins -> Bool,
+        exclude_from_leaderboards -> Bool,
+        deletion_scheduled_at -> Nullable<Timestamp>,
+        notification_muted_words -> Array<Text>,
     }
 }
 
@@ -410,6 +687,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    login_fingerprint (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        #[max_length = 64]
+        fingerprint -> Varchar,
+        published -> Timestamp,
+        last_used -> Timestamp,
+    }
+}
+
 diesel::table! {
     mod_add (id) {
         id -> Int4,
@@ -478,6 +766,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    mod_lock_comment (id) {
+        id -> Int4,
+        mod_person_id -> Int4,
+        comment_id -> Int4,
+        locked -> Bool,
+        when_ -> Timestamp,
+    }
+}
+
 diesel::table! {
     mod_lock_post (id) {
         id -> Int4,
@@ -488,6 +786,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    mod_reason_template (id) {
+        id -> Int4,
+        community_id -> Int4,
+        reason -> Text,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     mod_remove_comment (id) {
         id -> Int4,
@@ -496,6 +804,7 @@ diesel::table! {
         reason -> Nullable<Text>,
         removed -> Bool,
         when_ -> Timestamp,
+        comment_snippet -> Nullable<Text>,
     }
 }
 
@@ -519,6 +828,7 @@ diesel::table! {
         reason -> Nullable<Text>,
         removed -> Bool,
         when_ -> Timestamp,
+        post_name -> Nullable<Text>,
     }
 }
 
@@ -532,6 +842,40 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    oauth_account (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        oauth_provider_id -> Int4,
+        oauth_user_id -> Text,
+        published -> Timestamp,
+    }
+}
+
+diesel::table! {
+    oauth_provider (id) {
+        id -> Int4,
+        display_name -> Text,
+        issuer -> Text,
+        client_id -> Text,
+        client_secret -> Text,
+        scopes -> Text,
+        enabled -> Bool,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    oauth_state (id) {
+        id -> Int4,
+        state -> Uuid,
+        oauth_provider_id -> Int4,
+        local_user_id -> Nullable<Int4>,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     password_reset_request (id) {
         id -> Int4,
@@ -611,6 +955,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    person_keyword_block (id) {
+        id -> Int4,
+        person_id -> Int4,
+        #[max_length = 50]
+        keyword -> Varchar,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     person_mention (id) {
         id -> Int4,
@@ -618,6 +972,7 @@ diesel::table! {
         comment_id -> Int4,
         read -> Bool,
         published -> Timestamp,
+        muted -> Bool,
     }
 }
 
@@ -631,6 +986,51 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    person_report (id) {
+        id -> Int4,
+        creator_id -> Int4,
+        person_id -> Int4,
+        original_display_name -> Nullable<Text>,
+        original_bio -> Nullable<Text>,
+        reason -> Text,
+        resolved -> Bool,
+        resolver_id -> Nullable<Int4>,
+        published -> Timestamp,
+        updated -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    poll (id) {
+        id -> Int4,
+        post_id -> Int4,
+        ends_at -> Nullable<Timestamp>,
+        allow_multiple -> Bool,
+        hide_results_until_voted -> Bool,
+        published -> Timestamp,
+    }
+}
+
+diesel::table! {
+    poll_option (id) {
+        id -> Int4,
+        poll_id -> Int4,
+        name -> Text,
+        position -> Int4,
+    }
+}
+
+diesel::table! {
+    poll_vote (id) {
+        id -> Int4,
+        poll_id -> Int4,
+        poll_option_id -> Int4,
+        person_id -> Int4,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     post (id) {
         id -> Int4,
@@ -657,6 +1057,9 @@ diesel::table! {
         language_id -> Int4,
         featured_community -> Bool,
         featured_local -> Bool,
+        archive_url -> Nullable<Text>,
+        content_warning -> Nullable<Text>,
+        embed_provider -> Nullable<Text>,
     }
 }
 
@@ -681,6 +1084,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_edit_history (id) {
+        id -> Int4,
+        post_id -> Int4,
+        #[max_length = 200]
+        name -> Varchar,
+        #[max_length = 512]
+        url -> Nullable<Varchar>,
+        content -> Text,
+        edited_at -> Timestamp,
+        editor_id -> Int4,
+    }
+}
+
 diesel::table! {
     post_like (id) {
         id -> Int4,
@@ -700,6 +1117,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_reminder (id) {
+        id -> Int4,
+        person_id -> Int4,
+        post_id -> Int4,
+        remind_at -> Timestamp,
+        note -> Nullable<Text>,
+        published -> Timestamp,
+    }
+}
+
 diesel::table! {
     post_report (id) {
         id -> Int4,
@@ -723,6 +1151,25 @@ diesel::table! {
         post_id -> Int4,
         person_id -> Int4,
         published -> Timestamp,
+        remind_at -> Nullable<Timestamp>,
+        tag -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    post_thumbnail_retry (post_id) {
+        post_id -> Int4,
+        published -> Timestamp,
+    }
+}
+
+diesel::table! {
+    post_translation (id) {
+        id -> Int4,
+        post_id -> Int4,
+        language_id -> Int4,
+        content -> Text,
+        published -> Timestamp,
     }
 }
 
@@ -761,6 +1208,7 @@ diesel::table! {
         id -> Int8,
         ap_id -> Text,
         published -> Timestamp,
+        object_id -> Nullable<Text>,
     }
 }
 
@@ -775,6 +1223,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    reminder (id) {
+        id -> Int4,
+        recipient_id -> Int4,
+        post_id -> Int4,
+        comment_id -> Nullable<Int4>,
+        read -> Bool,
+        published -> Timestamp,
+        note -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     secret (id) {
         id -> Int4,
@@ -789,6 +1249,23 @@ diesel::table! {
         data -> Json,
         sensitive -> Bool,
         published -> Timestamp,
+        object_id -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DeliveryStatusEnum;
+
+    sent_activity_delivery (id) {
+        id -> Int8,
+        sent_activity_id -> Int8,
+        inbox -> Text,
+        status -> DeliveryStatusEnum,
+        published -> Timestamp,
+        attempts -> Int4,
+        last_attempt_at -> Nullable<Timestamp>,
+        last_error -> Nullable<Text>,
     }
 }
 
@@ -815,6 +1292,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    site_activity_rollup (day) {
+        day -> Date,
+        users -> Int8,
+        posts -> Int8,
+        comments -> Int8,
+        users_active -> Int8,
+        instances_linked -> Int8,
+    }
+}
+
 diesel::table! {
     site_aggregates (id) {
         id -> Int4,
@@ -848,16 +1336,59 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    user_data_import_failure (id) {
+        id -> Int4,
+        job_id -> Int4,
+        item -> Text,
+        error -> Text,
+    }
+}
+
+diesel::table! {
+    user_data_import_job (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        data -> Jsonb,
+        items_total -> Int4,
+        items_done -> Int4,
+        published -> Timestamp,
+        finished -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    vote_anomaly (id) {
+        id -> Int4,
+        community_id -> Int4,
+        post_id -> Int4,
+        comment_id -> Nullable<Int4>,
+        vote_count -> Int4,
+        z_score -> Float8,
+        instance_distribution -> Jsonb,
+        account_age_histogram -> Jsonb,
+        published -> Timestamp,
+    }
+}
+
+diesel::joinable!(admin_allow_instance -> instance (instance_id));
+diesel::joinable!(admin_allow_instance -> person (admin_person_id));
+diesel::joinable!(admin_block_instance -> instance (instance_id));
+diesel::joinable!(admin_block_instance -> person (admin_person_id));
 diesel::joinable!(admin_purge_comment -> person (admin_person_id));
 diesel::joinable!(admin_purge_comment -> post (post_id));
 diesel::joinable!(admin_purge_community -> person (admin_person_id));
 diesel::joinable!(admin_purge_person -> person (admin_person_id));
 diesel::joinable!(admin_purge_post -> community (community_id));
 diesel::joinable!(admin_purge_post -> person (admin_person_id));
+diesel::joinable!(admin_verify_community -> community (community_id));
+diesel::joinable!(admin_verify_community -> person (admin_person_id));
 diesel::joinable!(comment -> language (language_id));
 diesel::joinable!(comment -> person (creator_id));
 diesel::joinable!(comment -> post (post_id));
 diesel::joinable!(comment_aggregates -> comment (comment_id));
+diesel::joinable!(comment_edit_history -> comment (comment_id));
+diesel::joinable!(comment_edit_history -> person (editor_id));
 diesel::joinable!(comment_like -> comment (comment_id));
 diesel::joinable!(comment_like -> person (person_id));
 diesel::joinable!(comment_like -> post (post_id));
@@ -868,34 +1399,60 @@ diesel::joinable!(comment_saved -> comment (comment_id));
 diesel::joinable!(comment_saved -> person (person_id));
 diesel::joinable!(community -> instance (instance_id));
 diesel::joinable!(community_aggregates -> community (community_id));
+diesel::joinable!(community_aggregates_snapshot -> community (community_id));
 diesel::joinable!(community_block -> community (community_id));
 diesel::joinable!(community_block -> person (person_id));
+diesel::joinable!(community_contributor_rollup -> community (community_id));
+diesel::joinable!(community_contributor_rollup -> person (person_id));
+diesel::joinable!(community_digest -> community (community_id));
+diesel::joinable!(community_digest -> post (last_post_id));
+diesel::joinable!(community_flair_option -> community (community_id));
 diesel::joinable!(community_follower -> community (community_id));
 diesel::joinable!(community_follower -> person (person_id));
 diesel::joinable!(community_language -> community (community_id));
 diesel::joinable!(community_language -> language (language_id));
+diesel::joinable!(community_mention -> comment (comment_id));
+diesel::joinable!(community_mention -> community (community_id));
+diesel::joinable!(community_mention -> post (post_id));
 diesel::joinable!(community_moderator -> community (community_id));
 diesel::joinable!(community_moderator -> person (person_id));
+diesel::joinable!(community_page -> community (community_id));
 diesel::joinable!(community_person_ban -> community (community_id));
 diesel::joinable!(community_person_ban -> person (person_id));
+diesel::joinable!(community_person_flair -> community (community_id));
+diesel::joinable!(community_resync_job -> community (community_id));
+diesel::joinable!(community_resync_job -> person (creator_id));
+diesel::joinable!(community_resync_job -> post (post_id));
+diesel::joinable!(community_transfer_request -> community (community_id));
 diesel::joinable!(custom_emoji -> local_site (local_site_id));
 diesel::joinable!(custom_emoji_keyword -> custom_emoji (custom_emoji_id));
+diesel::joinable!(domain_migration -> person (admin_person_id));
 diesel::joinable!(email_verification -> local_user (local_user_id));
 diesel::joinable!(federation_allowlist -> instance (instance_id));
 diesel::joinable!(federation_blocklist -> instance (instance_id));
+diesel::joinable!(federation_retry_queue -> instance (instance_id));
+diesel::joinable!(form_submission -> comment (comment_id));
+diesel::joinable!(form_submission -> person (creator_id));
+diesel::joinable!(form_submission -> post (post_id));
+diesel::joinable!(local_image -> local_user (local_user_id));
+diesel::joinable!(local_site -> person (community_digest_bot_id));
 diesel::joinable!(local_site -> site (site_id));
 diesel::joinable!(local_site_rate_limit -> local_site (local_site_id));
 diesel::joinable!(local_user -> person (person_id));
 diesel::joinable!(local_user_language -> language (language_id));
 diesel::joinable!(local_user_language -> local_user (local_user_id));
+diesel::joinable!(login_fingerprint -> local_user (local_user_id));
 diesel::joinable!(mod_add_community -> community (community_id));
 diesel::joinable!(mod_ban_from_community -> community (community_id));
 diesel::joinable!(mod_feature_post -> person (mod_person_id));
 diesel::joinable!(mod_feature_post -> post (post_id));
 diesel::joinable!(mod_hide_community -> community (community_id));
 diesel::joinable!(mod_hide_community -> person (mod_person_id));
+diesel::joinable!(mod_lock_comment -> comment (comment_id));
+diesel::joinable!(mod_lock_comment -> person (mod_person_id));
 diesel::joinable!(mod_lock_post -> person (mod_person_id));
 diesel::joinable!(mod_lock_post -> post (post_id));
+diesel::joinable!(mod_reason_template -> community (community_id));
 diesel::joinable!(mod_remove_comment -> comment (comment_id));
 diesel::joinable!(mod_remove_comment -> person (mod_person_id));
 diesel::joinable!(mod_remove_community -> community (community_id));
@@ -903,99 +1460,164 @@ diesel::joinable!(mod_remove_community -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> post (post_id));
 diesel::joinable!(mod_transfer_community -> community (community_id));
+diesel::joinable!(oauth_account -> local_user (local_user_id));
+diesel::joinable!(oauth_account -> oauth_provider (oauth_provider_id));
+diesel::joinable!(oauth_state -> local_user (local_user_id));
+diesel::joinable!(oauth_state -> oauth_provider (oauth_provider_id));
 diesel::joinable!(password_reset_request -> local_user (local_user_id));
 diesel::joinable!(person -> instance (instance_id));
 diesel::joinable!(person_aggregates -> person (person_id));
 diesel::joinable!(person_ban -> person (person_id));
+diesel::joinable!(person_keyword_block -> person (person_id));
 diesel::joinable!(person_mention -> comment (comment_id));
 diesel::joinable!(person_mention -> person (recipient_id));
 diesel::joinable!(person_post_aggregates -> person (person_id));
 diesel::joinable!(person_post_aggregates -> post (post_id));
+diesel::joinable!(poll -> post (post_id));
+diesel::joinable!(poll_option -> poll (poll_id));
+diesel::joinable!(poll_vote -> person (person_id));
+diesel::joinable!(poll_vote -> poll (poll_id));
+diesel::joinable!(poll_vote -> poll_option (poll_option_id));
 diesel::joinable!(post -> community (community_id));
 diesel::joinable!(post -> language (language_id));
 diesel::joinable!(post -> person (creator_id));
 diesel::joinable!(post_aggregates -> community (community_id));
 diesel::joinable!(post_aggregates -> person (creator_id));
 diesel::joinable!(post_aggregates -> post (post_id));
+diesel::joinable!(post_edit_history -> person (editor_id));
+diesel::joinable!(post_edit_history -> post (post_id));
 diesel::joinable!(post_like -> person (person_id));
 diesel::joinable!(post_like -> post (post_id));
 diesel::joinable!(post_read -> person (person_id));
 diesel::joinable!(post_read -> post (post_id));
+diesel::joinable!(post_reminder -> person (person_id));
+diesel::joinable!(post_reminder -> post (post_id));
 diesel::joinable!(post_report -> post (post_id));
 diesel::joinable!(post_saved -> person (person_id));
 diesel::joinable!(post_saved -> post (post_id));
+diesel::joinable!(post_thumbnail_retry -> post (post_id));
+diesel::joinable!(post_translation -> language (language_id));
+diesel::joinable!(post_translation -> post (post_id));
 diesel::joinable!(private_message_report -> private_message (private_message_id));
 diesel::joinable!(registration_application -> local_user (local_user_id));
 diesel::joinable!(registration_application -> person (admin_id));
+diesel::joinable!(reminder -> comment (comment_id));
+diesel::joinable!(reminder -> person (recipient_id));
+diesel::joinable!(reminder -> post (post_id));
+diesel::joinable!(sent_activity_delivery -> sent_activity (sent_activity_id));
 diesel::joinable!(site -> instance (instance_id));
 diesel::joinable!(site_aggregates -> site (site_id));
 diesel::joinable!(site_language -> language (language_id));
 diesel::joinable!(site_language -> site (site_id));
 diesel::joinable!(tagline -> local_site (local_site_id));
+diesel::joinable!(user_data_import_failure -> user_data_import_job (job_id));
+diesel::joinable!(user_data_import_job -> local_user (local_user_id));
+diesel::joinable!(vote_anomaly -> comment (comment_id));
+diesel::joinable!(vote_anomaly -> community (community_id));
+diesel::joinable!(vote_anomaly -> post (post_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    admin_allow_instance,
+    admin_block_instance,
+    admin_clear_person_profile,
     admin_purge_comment,
     admin_purge_community,
     admin_purge_person,
     admin_purge_post,
+    admin_verify_community,
     captcha_answer,
     comment,
     comment_aggregates,
+    comment_edit_history,
     comment_like,
     comment_reply,
     comment_report,
     comment_saved,
     community,
     community_aggregates,
+    community_aggregates_snapshot,
     community_block,
+    community_contributor_rollup,
+    community_digest,
+    community_flair_option,
     community_follower,
     community_language,
+    community_mention,
     community_moderator,
+    community_page,
     community_person_ban,
+    community_person_flair,
+    community_resync_job,
+    community_transfer_request,
     custom_emoji,
     custom_emoji_keyword,
+    domain_migration,
     email_verification,
     federation_allowlist,
     federation_blocklist,
+    federation_retry_queue,
+    form_submission,
     instance,
     language,
+    local_image,
     local_site,
     local_site_rate_limit,
     local_user,
     local_user_language,
+    login_fingerprint,
     mod_add,
     mod_add_community,
     mod_ban,
     mod_ban_from_community,
     mod_feature_post,
     mod_hide_community,
+    mod_lock_comment,
     mod_lock_post,
+    mod_reason_template,
     mod_remove_comment,
     mod_remove_community,
     mod_remove_post,
     mod_transfer_community,
+    oauth_account,
+    oauth_provider,
+    oauth_state,
     password_reset_request,
     person,
     person_aggregates,
     person_ban,
     person_block,
     person_follower,
+    person_keyword_block,
     person_mention,
     person_post_aggregates,
+    person_report,
+    poll,
+    poll_option,
+    poll_vote,
     post,
     post_aggregates,
+    post_edit_history,
     post_like,
     post_read,
+    post_reminder,
     post_report,
     post_saved,
+    post_thumbnail_retry,
+    post_translation,
     private_message,
     private_message_report,
     received_activity,
     registration_application,
+    reminder,
     secret,
     sent_activity,
+    sent_activity_delivery,
     site,
+    site_activity_rollup,
     site_aggregates,
     site_language,
     tagline,
+    user_data_import_failure,
+    user_data_import_job,
+    vote_anomaly,
 );
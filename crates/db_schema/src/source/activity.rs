@@ -1,4 +1,8 @@
-use crate::{newtypes::DbUrl, schema::sent_activity};
+use crate::{
+  newtypes::DbUrl,
+  schema::{sent_activity, sent_activity_delivery},
+  DeliveryStatus,
+};
 use serde_json::Value;
 use std::fmt::Debug;
 
@@ -10,6 +14,8 @@ pub struct SentActivity {
   pub data: Value,
   pub sensitive: bool,
   pub published: chrono::NaiveDateTime,
+  /// The object which the activity refers to, if any.
+  pub object_id: Option<DbUrl>,
 }
 #[derive(Insertable)]
 #[diesel(table_name = sent_activity)]
@@ -17,6 +23,7 @@ pub struct SentActivityForm {
   pub ap_id: DbUrl,
   pub data: Value,
   pub sensitive: bool,
+  pub object_id: Option<DbUrl>,
 }
 
 #[derive(PartialEq, Eq, Debug, Queryable)]
@@ -25,4 +32,30 @@ pub struct ReceivedActivity {
   pub id: i64,
   pub ap_id: DbUrl,
   pub published: chrono::NaiveDateTime,
+  /// The object which the activity refers to, if any.
+  pub object_id: Option<DbUrl>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Queryable)]
+#[diesel(table_name = sent_activity_delivery)]
+/// Records that an activity was handed over for delivery to an inbox, or why it wasn't.
+pub struct SentActivityDelivery {
+  pub id: i64,
+  pub sent_activity_id: i64,
+  pub inbox: DbUrl,
+  pub status: DeliveryStatus,
+  pub published: chrono::NaiveDateTime,
+  /// How often the retry queue tried to resend the activity to this inbox.
+  pub attempts: i32,
+  pub last_attempt_at: Option<chrono::NaiveDateTime>,
+  /// The error of the last resend attempt, or none if it succeeded.
+  pub last_error: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = sent_activity_delivery)]
+pub struct SentActivityDeliveryForm {
+  pub sent_activity_id: i64,
+  pub inbox: DbUrl,
+  pub status: DeliveryStatus,
 }
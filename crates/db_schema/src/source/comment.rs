@@ -43,6 +43,15 @@ pub struct Comment {
   /// Whether the comment has been distinguished(speaking officially) by a mod.
   pub distinguished: bool,
   pub language_id: LanguageId,
+  /// A content warning, behind which clients hide the comment until it is expanded.
+  pub content_warning: Option<String>,
+  /// Whether a moderator locked the comment, so that it and its replies can't get new replies.
+  pub locked: bool,
+  /// Whether the comment was fetched by a resync of its post, instead of arriving through
+  /// federation.
+  pub backfilled: bool,
+  /// Whether the comment is NSFW. Hidden from users who don't want to see NSFW content.
+  pub nsfw: bool,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -64,6 +73,9 @@ pub struct CommentInsertForm {
   pub local: Option<bool>,
   pub distinguished: Option<bool>,
   pub language_id: Option<LanguageId>,
+  pub content_warning: Option<String>,
+  pub locked: Option<bool>,
+  pub nsfw: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -79,6 +91,9 @@ pub struct CommentUpdateForm {
   pub local: Option<bool>,
   pub distinguished: Option<bool>,
   pub language_id: Option<LanguageId>,
+  pub content_warning: Option<Option<String>>,
+  pub locked: Option<bool>,
+  pub nsfw: Option<bool>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -113,11 +128,19 @@ pub struct CommentSaved {
   pub comment_id: CommentId,
   pub person_id: PersonId,
   pub published: chrono::NaiveDateTime,
+  pub remind_at: Option<chrono::NaiveDateTime>,
+  /// The folder which the user saved it into.
+  pub tag: Option<String>,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
 #[cfg_attr(feature = "full", diesel(table_name = comment_saved))]
+#[cfg_attr(feature = "full", diesel(treat_none_as_null = true))]
 pub struct CommentSavedForm {
   pub comment_id: CommentId,
   pub person_id: PersonId,
+  /// Saving again without a reminder clears it.
+  pub remind_at: Option<chrono::NaiveDateTime>,
+  /// Saving again with another tag moves it, without a tag clears it.
+  pub tag: Option<String>,
 }
@@ -3,6 +3,8 @@ use crate::schema::{community, community_follower, community_moderator, communit
 use crate::{
   newtypes::{CommunityId, DbUrl, InstanceId, PersonId},
   source::placeholder_apub_url,
+  CommentSortType,
+  SortType,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -62,6 +64,20 @@ pub struct Community {
   /// Url where featured posts collection is served over Activitypub
   #[serde(skip)]
   pub featured_url: Option<DbUrl>,
+  /// The post sort which is used if the request doesn't specify one. Logged in users get their
+  /// own default sort instead.
+  pub default_post_sort: Option<SortType>,
+  /// The comment sort which is used if the request doesn't specify one.
+  pub default_comment_sort: Option<CommentSortType>,
+  /// How old accounts need to be to vote in the community. 0 allows all accounts.
+  pub min_account_age_days_to_vote: i32,
+  /// Whether only followers of the community can vote in it.
+  pub only_followers_can_vote: bool,
+  /// Whether commenting is restricted to mods only.
+  pub commenting_restricted_to_mods: bool,
+  /// Whether the admins of this instance vouch for the community. This isn't federated, so other
+  /// instances have their own verified communities.
+  pub verified: bool,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -93,8 +109,13 @@ pub struct CommunityInsertForm {
   pub featured_url: Option<DbUrl>,
   pub hidden: Option<bool>,
   pub posting_restricted_to_mods: Option<bool>,
+  pub commenting_restricted_to_mods: Option<bool>,
   #[builder(!default)]
   pub instance_id: InstanceId,
+  pub default_post_sort: Option<SortType>,
+  pub default_comment_sort: Option<CommentSortType>,
+  pub min_account_age_days_to_vote: Option<i32>,
+  pub only_followers_can_vote: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -122,6 +143,12 @@ pub struct CommunityUpdateForm {
   pub featured_url: Option<DbUrl>,
   pub hidden: Option<bool>,
   pub posting_restricted_to_mods: Option<bool>,
+  pub commenting_restricted_to_mods: Option<bool>,
+  pub default_post_sort: Option<Option<SortType>>,
+  pub default_comment_sort: Option<Option<CommentSortType>>,
+  pub min_account_age_days_to_vote: Option<i32>,
+  pub only_followers_can_vote: Option<bool>,
+  pub verified: Option<bool>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
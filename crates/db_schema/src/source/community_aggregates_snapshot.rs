@@ -0,0 +1,26 @@
+use crate::newtypes::CommunityId;
+#[cfg(feature = "full")]
+use crate::schema::community_aggregates_snapshot;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_aggregates_snapshot))]
+#[cfg_attr(feature = "full", diesel(primary_key(community_id, day)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Statistics of a local community for a single day.
+pub struct CommunityAggregatesSnapshot {
+  pub community_id: CommunityId,
+  pub day: chrono::NaiveDate,
+  /// Subscribers at the end of the day. Days before the first snapshot are counted from the
+  /// current subscribers, as unsubscribes aren't recorded.
+  pub subscribers: i64,
+  /// New posts.
+  pub posts: i64,
+  /// New comments.
+  pub comments: i64,
+  /// People who posted or commented.
+  pub users_active: i64,
+}
@@ -0,0 +1,50 @@
+use crate::newtypes::{CommunityDigestId, CommunityId, PostId};
+#[cfg(feature = "full")]
+use crate::schema::community_digest;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_digest))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::community::Community))
+)]
+#[cfg_attr(feature = "full", ts(export))]
+/// The settings of the weekly post listing the top posts of a community.
+pub struct CommunityDigest {
+  pub id: CommunityDigestId,
+  pub community_id: CommunityId,
+  pub enabled: bool,
+  /// The day of the week on which the digest is posted, from 0 for Monday to 6 for Sunday.
+  pub day_of_week: i16,
+  /// The hour (UTC) at which the digest is posted.
+  pub hour: i16,
+  /// How many of the top posts of the past week are listed.
+  pub top_posts: i32,
+  /// Only posts with at least this score are listed.
+  pub min_score: i32,
+  /// Whether the digest is featured in the community, replacing the previous one.
+  pub feature: bool,
+  /// The scheduled time of the last digest, so that each week is only posted once.
+  pub last_generated_for: Option<chrono::NaiveDateTime>,
+  pub last_post_id: Option<PostId>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_digest))]
+pub struct CommunityDigestForm {
+  pub community_id: CommunityId,
+  pub enabled: bool,
+  pub day_of_week: i16,
+  pub hour: i16,
+  pub top_posts: i32,
+  pub min_score: i32,
+  pub feature: bool,
+}
@@ -0,0 +1,61 @@
+use crate::newtypes::{CommunityFlairOptionId, CommunityId, PersonId};
+#[cfg(feature = "full")]
+use crate::schema::{community_flair_option, community_person_flair};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_person_flair))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The title of a person in a community, like "Verified Developer".
+pub struct CommunityPersonFlair {
+  pub id: i32,
+  pub community_id: CommunityId,
+  pub person_id: PersonId,
+  pub flair: String,
+  /// A hex color like `#1e90ff`.
+  pub color: Option<String>,
+  /// The mod who assigned it, or the person themselves. None if it came from a remote community.
+  pub set_by_id: Option<PersonId>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_person_flair))]
+#[cfg_attr(feature = "full", diesel(treat_none_as_null = true))]
+pub struct CommunityPersonFlairForm {
+  pub community_id: CommunityId,
+  pub person_id: PersonId,
+  pub flair: String,
+  pub color: Option<String>,
+  pub set_by_id: Option<PersonId>,
+}
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_flair_option))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A flair which members of the community can pick for themselves.
+pub struct CommunityFlairOption {
+  pub id: CommunityFlairOptionId,
+  pub community_id: CommunityId,
+  pub flair: String,
+  pub color: Option<String>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_flair_option))]
+#[cfg_attr(feature = "full", diesel(treat_none_as_null = true))]
+pub struct CommunityFlairOptionForm {
+  pub community_id: CommunityId,
+  pub flair: String,
+  pub color: Option<String>,
+}
@@ -0,0 +1,25 @@
+use crate::newtypes::{CommentId, CommunityId, PostId};
+#[cfg(feature = "full")]
+use crate::schema::community_mention;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = community_mention))]
+/// A community which is mentioned as `!name@domain` in either a post or a comment.
+pub struct CommunityMention {
+  pub id: i32,
+  pub community_id: CommunityId,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = community_mention))]
+pub struct CommunityMentionForm {
+  pub community_id: CommunityId,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+}
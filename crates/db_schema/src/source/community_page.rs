@@ -0,0 +1,48 @@
+use crate::newtypes::{CommunityId, CommunityPageId};
+#[cfg(feature = "full")]
+use crate::schema::community_page;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+use typed_builder::TypedBuilder;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_page))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::community::Community))
+)]
+#[cfg_attr(feature = "full", ts(export))]
+/// A wiki page of a community, for rules, FAQs and similar.
+pub struct CommunityPage {
+  pub id: CommunityPageId,
+  pub community_id: CommunityId,
+  /// The slug, unique within the community.
+  pub slug: String,
+  pub title: String,
+  pub content: String,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_page))]
+pub struct CommunityPageInsertForm {
+  pub community_id: CommunityId,
+  pub slug: String,
+  pub title: String,
+  pub content: String,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_page))]
+pub struct CommunityPageUpdateForm {
+  pub title: Option<String>,
+  pub content: Option<String>,
+  pub updated: Option<Option<chrono::NaiveDateTime>>,
+}
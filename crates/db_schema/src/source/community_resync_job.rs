@@ -0,0 +1,45 @@
+use crate::newtypes::{CommunityId, PersonId, PostId};
+#[cfg(feature = "full")]
+use crate::schema::community_resync_job;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_resync_job))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::community::Community))
+)]
+#[cfg_attr(feature = "full", ts(export))]
+/// The fetching of missing comments for the posts of a remote community, or for a single post.
+pub struct CommunityResyncJob {
+  pub id: i32,
+  pub community_id: CommunityId,
+  /// The moderator or admin who started the resync.
+  pub creator_id: PersonId,
+  pub posts_total: i32,
+  pub posts_done: i32,
+  /// Posts which couldn't be fetched from their home instance. These count as done.
+  pub posts_failed: i32,
+  pub comments_added: i32,
+  /// The last post which was resynced. Posts are resynced from the newest to the oldest.
+  pub last_post_id: Option<PostId>,
+  pub published: chrono::NaiveDateTime,
+  pub finished: Option<chrono::NaiveDateTime>,
+  /// Set if only this post is resynced.
+  pub post_id: Option<PostId>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_resync_job))]
+pub struct CommunityResyncJobForm {
+  pub community_id: CommunityId,
+  pub creator_id: PersonId,
+  pub posts_total: i32,
+  pub post_id: Option<PostId>,
+}
@@ -0,0 +1,33 @@
+use crate::newtypes::{CommunityId, PersonId};
+#[cfg(feature = "full")]
+use crate::schema::community_transfer_request;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_transfer_request))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::community::Community))
+)]
+#[cfg_attr(feature = "full", ts(export))]
+/// An offer of the top moderator position, waiting for the recipient to accept it.
+pub struct CommunityTransferRequest {
+  pub id: i32,
+  pub community_id: CommunityId,
+  /// The top moderator who offered the transfer.
+  pub sender_id: PersonId,
+  pub recipient_id: PersonId,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_transfer_request))]
+pub struct CommunityTransferRequestForm {
+  pub community_id: CommunityId,
+  pub sender_id: PersonId,
+  pub recipient_id: PersonId,
+}
@@ -0,0 +1,34 @@
+#[cfg(feature = "full")]
+use crate::schema::domain_migration;
+use crate::{newtypes::PersonId, DomainMigrationStage};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = domain_migration))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The move of the local instance from an old domain to the current one.
+pub struct DomainMigration {
+  pub id: i32,
+  pub old_domain: String,
+  pub new_domain: String,
+  pub admin_person_id: PersonId,
+  pub stage: DomainMigrationStage,
+  /// The id of the last row of the current stage which was migrated.
+  pub last_id: i32,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = domain_migration))]
+pub struct DomainMigrationForm {
+  pub old_domain: String,
+  pub new_domain: String,
+  pub admin_person_id: PersonId,
+}
@@ -0,0 +1,57 @@
+use crate::newtypes::{CommentId, DbUrl, PersonId, PostId};
+#[cfg(feature = "full")]
+use crate::schema::{comment_edit_history, post_edit_history};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_edit_history))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A post as it was before it was edited.
+pub struct PostEditHistory {
+  pub id: i32,
+  pub post_id: PostId,
+  pub name: String,
+  pub url: Option<DbUrl>,
+  /// The body, empty if the post had none.
+  pub content: String,
+  pub edited_at: chrono::NaiveDateTime,
+  pub editor_id: PersonId,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_edit_history))]
+pub struct PostEditHistoryInsertForm {
+  pub post_id: PostId,
+  pub name: String,
+  pub url: Option<DbUrl>,
+  pub content: String,
+  pub editor_id: PersonId,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::comment::Comment)))]
+#[cfg_attr(feature = "full", diesel(table_name = comment_edit_history))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The content of a comment before it was edited.
+pub struct CommentEditHistory {
+  pub id: i32,
+  pub comment_id: CommentId,
+  pub content: String,
+  pub edited_at: chrono::NaiveDateTime,
+  pub editor_id: PersonId,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = comment_edit_history))]
+pub struct CommentEditHistoryInsertForm {
+  pub comment_id: CommentId,
+  pub content: String,
+  pub editor_id: PersonId,
+}
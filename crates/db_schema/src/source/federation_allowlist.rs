@@ -16,6 +16,7 @@ pub struct FederationAllowList {
   pub instance_id: InstanceId,
   pub published: chrono::NaiveDateTime,
   pub updated: Option<chrono::NaiveDateTime>,
+  pub reason: Option<String>,
 }
 
 #[derive(Clone, Default)]
@@ -24,4 +25,5 @@ pub struct FederationAllowList {
 pub struct FederationAllowListForm {
   pub instance_id: InstanceId,
   pub updated: Option<chrono::NaiveDateTime>,
+  pub reason: Option<String>,
 }
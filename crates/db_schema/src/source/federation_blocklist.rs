@@ -16,6 +16,8 @@ pub struct FederationBlockList {
   pub instance_id: InstanceId,
   pub published: chrono::NaiveDateTime,
   pub updated: Option<chrono::NaiveDateTime>,
+  pub reason: Option<String>,
+  pub expires: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Clone, Default)]
@@ -24,4 +26,6 @@ pub struct FederationBlockList {
 pub struct FederationBlockListForm {
   pub instance_id: InstanceId,
   pub updated: Option<chrono::NaiveDateTime>,
+  pub reason: Option<String>,
+  pub expires: Option<chrono::NaiveDateTime>,
 }
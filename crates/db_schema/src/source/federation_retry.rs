@@ -0,0 +1,30 @@
+use crate::{
+  newtypes::{DbUrl, InstanceId},
+  schema::federation_retry_queue,
+};
+use serde_json::Value;
+use std::fmt::Debug;
+
+#[derive(PartialEq, Eq, Debug, Clone, Queryable, Identifiable)]
+#[diesel(table_name = federation_retry_queue)]
+/// A delivery which couldn't be made because the target instance was unreachable, and which is
+/// retried later.
+pub struct FederationRetry {
+  pub id: i64,
+  pub instance_id: InstanceId,
+  pub inbox: DbUrl,
+  pub actor_id: DbUrl,
+  pub activity: Value,
+  pub attempts: i32,
+  pub next_retry_at: chrono::NaiveDateTime,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = federation_retry_queue)]
+pub struct FederationRetryForm {
+  pub instance_id: InstanceId,
+  pub inbox: DbUrl,
+  pub actor_id: DbUrl,
+  pub activity: Value,
+}
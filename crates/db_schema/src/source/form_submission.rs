@@ -0,0 +1,36 @@
+use crate::newtypes::{CommentId, PersonId, PostId};
+#[cfg(feature = "full")]
+use crate::schema::form_submission;
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = form_submission))]
+/// A recently submitted form for a post or comment, identified by the form id of the client.
+pub struct FormSubmission {
+  pub id: i32,
+  pub creator_id: PersonId,
+  pub form_id: String,
+  /// None while the post is being created, or if the form was for a comment.
+  pub post_id: Option<PostId>,
+  /// None while the comment is being created, or if the form was for a post.
+  pub comment_id: Option<CommentId>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = form_submission))]
+pub struct FormSubmissionForm {
+  pub creator_id: PersonId,
+  pub form_id: String,
+}
+
+/// The result of claiming a form id.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum FormClaim {
+  /// The form wasn't submitted recently, and this submission is recorded now.
+  New(FormSubmission),
+  /// The form was submitted already.
+  Existing(FormSubmission),
+}
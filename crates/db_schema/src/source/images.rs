@@ -0,0 +1,34 @@
+use crate::newtypes::LocalUserId;
+#[cfg(feature = "full")]
+use crate::schema::local_image;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", ts(export))]
+#[cfg_attr(feature = "full", diesel(table_name = local_image))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::local_user::LocalUser))
+)]
+#[cfg_attr(feature = "full", diesel(primary_key(pictrs_alias)))]
+/// An image uploaded to pictrs by a local user.
+pub struct LocalImage {
+  pub pictrs_alias: String,
+  pub pictrs_delete_token: String,
+  pub local_user_id: LocalUserId,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = local_image))]
+pub struct LocalImageForm {
+  pub pictrs_alias: String,
+  pub pictrs_delete_token: String,
+  pub local_user_id: LocalUserId,
+}
@@ -21,6 +21,13 @@ pub struct Instance {
   pub updated: Option<chrono::NaiveDateTime>,
   pub software: Option<String>,
   pub version: Option<String>,
+  /// Whether the instance declared that it doesn't accept NSFW content.
+  pub refuses_nsfw: bool,
+  /// The number of NSFW posts and communities from this instance which were rejected.
+  pub dropped_nsfw_objects: i64,
+  /// The number of deliveries to this instance which were given up on because it stayed
+  /// unreachable.
+  pub dropped_activities: i64,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -33,4 +40,5 @@ pub struct InstanceForm {
   pub software: Option<String>,
   pub version: Option<String>,
   pub updated: Option<chrono::NaiveDateTime>,
+  pub refuses_nsfw: Option<bool>,
 }
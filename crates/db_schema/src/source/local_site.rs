@@ -1,7 +1,7 @@
 #[cfg(feature = "full")]
 use crate::schema::local_site;
 use crate::{
-  newtypes::{LocalSiteId, SiteId},
+  newtypes::{LocalSiteId, PersonId, SiteId},
   ListingType,
   RegistrationMode,
 };
@@ -59,6 +59,34 @@ pub struct LocalSite {
   pub registration_mode: RegistrationMode,
   /// Whether to email admins on new reports.
   pub reports_email_admins: bool,
+  /// Whether to request archive.org snapshots for new post links.
+  pub enable_url_archiving: bool,
+  /// A comma-separated list of domains whose links are never archived.
+  pub url_archiving_excluded_domains: Option<String>,
+  /// The max number of posts featured to the local site at once.
+  pub featured_local_posts_max: i32,
+  /// Rejects all NSFW content, including NSFW posts and communities from other instances.
+  pub disallow_nsfw_content: bool,
+  /// Whether to send NSFW content to instances which declared that they refuse it.
+  pub federate_nsfw_outbound: bool,
+  /// Whether new accounts can be created by logging in through an OAuth provider, even if
+  /// registration is closed.
+  pub oauth_registration: bool,
+  /// Whether posts with a content warning are marked as NSFW.
+  pub content_warning_sets_nsfw: bool,
+  /// The bot account which posts the community digests, created for the first one.
+  pub community_digest_bot_id: Option<PersonId>,
+  /// How many days accounts are kept after their deletion was requested, so that the deletion
+  /// can be cancelled.
+  pub account_deletion_cooling_off_days: i32,
+  /// After how many days unread registration applications are denied automatically. 0 keeps them
+  /// until an admin handles them.
+  pub application_expire_days: i32,
+  /// Whether local mods and admins have to give a reason when they remove posts or comments,
+  /// and when they ban someone.
+  pub require_mod_action_reason: bool,
+  /// Whether remote images in posts and comments are loaded through the image proxy.
+  pub proxy_remote_images: bool,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -87,6 +115,17 @@ pub struct LocalSiteInsertForm {
   pub captcha_difficulty: Option<String>,
   pub registration_mode: Option<RegistrationMode>,
   pub reports_email_admins: Option<bool>,
+  pub enable_url_archiving: Option<bool>,
+  pub url_archiving_excluded_domains: Option<String>,
+  pub featured_local_posts_max: Option<i32>,
+  pub disallow_nsfw_content: Option<bool>,
+  pub federate_nsfw_outbound: Option<bool>,
+  pub oauth_registration: Option<bool>,
+  pub content_warning_sets_nsfw: Option<bool>,
+  pub account_deletion_cooling_off_days: Option<i32>,
+  pub application_expire_days: Option<i32>,
+  pub require_mod_action_reason: Option<bool>,
+  pub proxy_remote_images: Option<bool>,
 }
 
 #[derive(Clone, Default)]
@@ -112,5 +151,17 @@ pub struct LocalSiteUpdateForm {
   pub captcha_difficulty: Option<String>,
   pub registration_mode: Option<RegistrationMode>,
   pub reports_email_admins: Option<bool>,
+  pub enable_url_archiving: Option<bool>,
+  pub url_archiving_excluded_domains: Option<Option<String>>,
+  pub featured_local_posts_max: Option<i32>,
+  pub disallow_nsfw_content: Option<bool>,
+  pub federate_nsfw_outbound: Option<bool>,
+  pub oauth_registration: Option<bool>,
+  pub content_warning_sets_nsfw: Option<bool>,
+  pub community_digest_bot_id: Option<Option<PersonId>>,
+  pub account_deletion_cooling_off_days: Option<i32>,
+  pub application_expire_days: Option<i32>,
+  pub require_mod_action_reason: Option<bool>,
+  pub proxy_remote_images: Option<bool>,
   pub updated: Option<Option<chrono::NaiveDateTime>>,
 }
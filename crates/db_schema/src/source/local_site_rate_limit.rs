@@ -34,6 +34,8 @@ pub struct LocalSiteRateLimit {
   pub search_per_second: i32,
   pub published: chrono::NaiveDateTime,
   pub updated: Option<chrono::NaiveDateTime>,
+  pub export: i32,
+  pub export_per_second: i32,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -55,6 +57,8 @@ pub struct LocalSiteRateLimitInsertForm {
   pub comment_per_second: Option<i32>,
   pub search: Option<i32>,
   pub search_per_second: Option<i32>,
+  pub export: Option<i32>,
+  pub export_per_second: Option<i32>,
 }
 
 #[derive(Clone, Default)]
@@ -73,5 +77,7 @@ pub struct LocalSiteRateLimitUpdateForm {
   pub comment_per_second: Option<i32>,
   pub search: Option<i32>,
   pub search_per_second: Option<i32>,
+  pub export: Option<i32>,
+  pub export_per_second: Option<i32>,
   pub updated: Option<Option<chrono::NaiveDateTime>>,
 }
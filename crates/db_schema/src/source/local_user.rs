@@ -3,6 +3,7 @@ use crate::schema::local_user;
 use crate::{
   newtypes::{LocalUserId, PersonId},
   ListingType,
+  NotificationDigest,
   SortType,
 };
 use serde::{Deserialize, Serialize};
@@ -57,6 +58,20 @@ pub struct LocalUser {
   pub auto_expand: bool,
   /// Whether infinite scroll is enabled.
   pub infinite_scroll_enabled: bool,
+  /// Hide posts and comments with a lower score.
+  pub hide_content_below_score: Option<i32>,
+  /// How often to send an email digest of unread notifications.
+  pub send_notification_digest: NotificationDigest,
+  #[serde(skip)]
+  pub last_digest_sent_at: Option<chrono::NaiveDateTime>,
+  /// Send an email when logging in from a new device.
+  pub notify_new_logins: bool,
+  /// Hide the user from leaderboards like the top contributors of a community.
+  pub exclude_from_leaderboards: bool,
+  /// When the account will be deleted, if the user asked for it.
+  pub deletion_scheduled_at: Option<chrono::NaiveDateTime>,
+  /// Replies and mentions containing one of these words don't notify the user.
+  pub notification_muted_words: Vec<String>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -88,6 +103,10 @@ pub struct LocalUserInsertForm {
   pub blur_nsfw: Option<bool>,
   pub auto_expand: Option<bool>,
   pub infinite_scroll_enabled: Option<bool>,
+  pub hide_content_below_score: Option<i32>,
+  pub send_notification_digest: Option<NotificationDigest>,
+  pub notify_new_logins: Option<bool>,
+  pub exclude_from_leaderboards: Option<bool>,
 }
 
 #[derive(Clone, Default)]
@@ -115,4 +134,11 @@ pub struct LocalUserUpdateForm {
   pub blur_nsfw: Option<bool>,
   pub auto_expand: Option<bool>,
   pub infinite_scroll_enabled: Option<bool>,
+  pub hide_content_below_score: Option<Option<i32>>,
+  pub send_notification_digest: Option<NotificationDigest>,
+  pub last_digest_sent_at: Option<Option<chrono::NaiveDateTime>>,
+  pub notify_new_logins: Option<bool>,
+  pub exclude_from_leaderboards: Option<bool>,
+  pub deletion_scheduled_at: Option<Option<chrono::NaiveDateTime>>,
+  pub notification_muted_words: Option<Vec<String>>,
 }
@@ -0,0 +1,27 @@
+use crate::newtypes::LocalUserId;
+#[cfg(feature = "full")]
+use crate::schema::login_fingerprint;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::local_user::LocalUser))
+)]
+#[cfg_attr(feature = "full", diesel(table_name = login_fingerprint))]
+/// A device the user logged in from before, identified by a hash of its user agent and IP prefix.
+pub struct LoginFingerprint {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub fingerprint: String,
+  pub published: chrono::NaiveDateTime,
+  pub last_used: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = login_fingerprint))]
+pub struct LoginFingerprintForm {
+  pub local_user_id: LocalUserId,
+  pub fingerprint: String,
+}
@@ -9,30 +9,58 @@ pub mod comment;
 pub mod comment_reply;
 pub mod comment_report;
 pub mod community;
+pub mod community_aggregates_snapshot;
 pub mod community_block;
+pub mod community_digest;
+pub mod community_flair;
+pub mod community_mention;
+pub mod community_page;
+pub mod community_resync_job;
+pub mod community_transfer_request;
 pub mod custom_emoji;
 pub mod custom_emoji_keyword;
+pub mod domain_migration;
+pub mod edit_history;
 pub mod email_verification;
 pub mod federation_allowlist;
 pub mod federation_blocklist;
+#[cfg(feature = "full")]
+pub mod federation_retry;
+pub mod form_submission;
+pub mod images;
 pub mod instance;
 pub mod language;
 pub mod local_site;
 pub mod local_site_rate_limit;
 pub mod local_user;
+pub mod login_fingerprint;
+pub mod mod_reason_template;
 pub mod moderator;
+pub mod oauth_account;
+pub mod oauth_provider;
 pub mod password_reset_request;
 pub mod person;
 pub mod person_block;
+pub mod person_keyword_block;
 pub mod person_mention;
+pub mod person_report;
+pub mod poll;
 pub mod post;
+pub mod post_reminder;
 pub mod post_report;
+pub mod post_thumbnail_retry;
+pub mod post_translation;
 pub mod private_message;
 pub mod private_message_report;
 pub mod registration_application;
+pub mod reminder;
 pub mod secret;
 pub mod site;
+pub mod site_activity_rollup;
 pub mod tagline;
+pub mod user_data_import;
+#[cfg(feature = "full")]
+pub mod vote_anomaly;
 
 /// Default value for columns like [community::Community.inbox_url] which are marked as serde(skip).
 ///
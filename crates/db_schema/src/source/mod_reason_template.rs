@@ -0,0 +1,37 @@
+use crate::newtypes::{CommunityId, ModReasonTemplateId};
+#[cfg(feature = "full")]
+use crate::schema::mod_reason_template;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_reason_template))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A reason for removals and bans in a community, which its mods can pick.
+pub struct ModReasonTemplate {
+  pub id: ModReasonTemplateId,
+  pub community_id: CommunityId,
+  pub reason: String,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_reason_template))]
+pub struct ModReasonTemplateInsertForm {
+  pub community_id: CommunityId,
+  pub reason: String,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_reason_template))]
+pub struct ModReasonTemplateUpdateForm {
+  pub reason: String,
+  pub updated: chrono::NaiveDateTime,
+}
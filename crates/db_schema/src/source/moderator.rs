@@ -1,16 +1,21 @@
-use crate::newtypes::{CommentId, CommunityId, PersonId, PostId};
+use crate::newtypes::{CommentId, CommunityId, InstanceId, PersonId, PostId};
 #[cfg(feature = "full")]
 use crate::schema::{
+  admin_allow_instance,
+  admin_block_instance,
+  admin_clear_person_profile,
   admin_purge_comment,
   admin_purge_community,
   admin_purge_person,
   admin_purge_post,
+  admin_verify_community,
   mod_add,
   mod_add_community,
   mod_ban,
   mod_ban_from_community,
   mod_feature_post,
   mod_hide_community,
+  mod_lock_comment,
   mod_lock_post,
   mod_remove_comment,
   mod_remove_community,
@@ -35,6 +40,8 @@ pub struct ModRemovePost {
   pub reason: Option<String>,
   pub removed: bool,
   pub when_: chrono::NaiveDateTime,
+  /// The title of the post when it was removed. Set by the database.
+  pub post_name: Option<String>,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
@@ -103,6 +110,8 @@ pub struct ModRemoveComment {
   pub reason: Option<String>,
   pub removed: bool,
   pub when_: chrono::NaiveDateTime,
+  /// The beginning of the comment's text when it was removed. Set by the database.
+  pub comment_snippet: Option<String>,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
@@ -114,6 +123,27 @@ pub struct ModRemoveCommentForm {
   pub removed: Option<bool>,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_lock_comment))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When a moderator locks a comment thread (prevents new replies to the comment and its replies).
+pub struct ModLockComment {
+  pub id: i32,
+  pub mod_person_id: PersonId,
+  pub comment_id: CommentId,
+  pub locked: bool,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_lock_comment))]
+pub struct ModLockCommentForm {
+  pub mod_person_id: PersonId,
+  pub comment_id: CommentId,
+  pub locked: bool,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
@@ -366,3 +396,100 @@ pub struct AdminPurgeCommentForm {
   pub post_id: PostId,
   pub reason: Option<String>,
 }
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_block_instance))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin adds an instance to the federation blocklist, or removes it.
+pub struct AdminBlockInstance {
+  pub id: i32,
+  pub instance_id: InstanceId,
+  pub admin_person_id: PersonId,
+  pub blocked: bool,
+  pub reason: Option<String>,
+  pub expires: Option<chrono::NaiveDateTime>,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_block_instance))]
+pub struct AdminBlockInstanceForm {
+  pub instance_id: InstanceId,
+  pub admin_person_id: PersonId,
+  pub blocked: bool,
+  pub reason: Option<String>,
+  pub expires: Option<chrono::NaiveDateTime>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_allow_instance))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin adds an instance to the federation allowlist, or removes it.
+pub struct AdminAllowInstance {
+  pub id: i32,
+  pub instance_id: InstanceId,
+  pub admin_person_id: PersonId,
+  pub allowed: bool,
+  pub reason: Option<String>,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_allow_instance))]
+pub struct AdminAllowInstanceForm {
+  pub instance_id: InstanceId,
+  pub admin_person_id: PersonId,
+  pub allowed: bool,
+  pub reason: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_clear_person_profile))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin clears the display name or bio of a user.
+pub struct AdminClearPersonProfile {
+  pub id: i32,
+  pub admin_person_id: PersonId,
+  pub person_id: PersonId,
+  pub cleared_display_name: bool,
+  pub cleared_bio: bool,
+  pub reason: Option<String>,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_clear_person_profile))]
+pub struct AdminClearPersonProfileForm {
+  pub admin_person_id: PersonId,
+  pub person_id: PersonId,
+  pub cleared_display_name: bool,
+  pub cleared_bio: bool,
+  pub reason: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_verify_community))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin verifies a community, or revokes the verification.
+pub struct AdminVerifyCommunity {
+  pub id: i32,
+  pub admin_person_id: PersonId,
+  pub community_id: CommunityId,
+  pub verified: bool,
+  pub when_: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_verify_community))]
+pub struct AdminVerifyCommunityForm {
+  pub admin_person_id: PersonId,
+  pub community_id: CommunityId,
+  pub verified: bool,
+}
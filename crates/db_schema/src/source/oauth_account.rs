@@ -0,0 +1,50 @@
+use crate::newtypes::{LocalUserId, OAuthProviderId};
+#[cfg(feature = "full")]
+use crate::schema::{oauth_account, oauth_state};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use uuid::Uuid;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = oauth_account))]
+/// Links a local user to their account at an OAuth provider.
+pub struct OAuthAccount {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  pub oauth_provider_id: OAuthProviderId,
+  /// The subject identifier of the user at the provider.
+  pub oauth_user_id: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = oauth_account))]
+pub struct OAuthAccountInsertForm {
+  pub local_user_id: LocalUserId,
+  pub oauth_provider_id: OAuthProviderId,
+  pub oauth_user_id: String,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable))]
+#[cfg_attr(feature = "full", diesel(table_name = oauth_state))]
+/// A pending authorization at an OAuth provider.
+pub struct OAuthState {
+  pub id: i32,
+  pub state: Uuid,
+  pub oauth_provider_id: OAuthProviderId,
+  /// The logged in user who wants to link their account, if any.
+  pub local_user_id: Option<LocalUserId>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = oauth_state))]
+pub struct OAuthStateInsertForm {
+  pub oauth_provider_id: OAuthProviderId,
+  pub local_user_id: Option<LocalUserId>,
+}
@@ -0,0 +1,55 @@
+use crate::newtypes::OAuthProviderId;
+#[cfg(feature = "full")]
+use crate::schema::oauth_provider;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = oauth_provider))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An OpenID Connect provider which users can log in with.
+pub struct OAuthProvider {
+  pub id: OAuthProviderId,
+  /// The name shown on the login button.
+  pub display_name: String,
+  /// The issuer url, from which the provider configuration is discovered.
+  pub issuer: String,
+  pub client_id: String,
+  /// Never sent to clients.
+  #[serde(skip)]
+  #[cfg_attr(feature = "full", ts(skip))]
+  pub client_secret: String,
+  /// The space-separated scopes which are requested.
+  pub scopes: String,
+  pub enabled: bool,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = oauth_provider))]
+pub struct OAuthProviderInsertForm {
+  pub display_name: String,
+  pub issuer: String,
+  pub client_id: String,
+  pub client_secret: String,
+  pub scopes: Option<String>,
+  pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = oauth_provider))]
+pub struct OAuthProviderUpdateForm {
+  pub display_name: Option<String>,
+  pub client_id: Option<String>,
+  pub client_secret: Option<String>,
+  pub scopes: Option<String>,
+  pub enabled: Option<bool>,
+  pub updated: Option<Option<chrono::NaiveDateTime>>,
+}
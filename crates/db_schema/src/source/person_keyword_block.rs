@@ -0,0 +1,23 @@
+use crate::newtypes::PersonId;
+#[cfg(feature = "full")]
+use crate::schema::person_keyword_block;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::person::Person)))]
+#[cfg_attr(feature = "full", diesel(table_name = person_keyword_block))]
+/// A keyword, posts and comments containing it are hidden from the person's listings.
+pub struct PersonKeywordBlock {
+  pub id: i32,
+  pub person_id: PersonId,
+  pub keyword: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = person_keyword_block))]
+pub struct PersonKeywordBlockForm {
+  pub person_id: PersonId,
+  pub keyword: String,
+}
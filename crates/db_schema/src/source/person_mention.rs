@@ -17,6 +17,8 @@ pub struct PersonMention {
   pub comment_id: CommentId,
   pub read: bool,
   pub published: chrono::NaiveDateTime,
+  /// The comment contains a word which the recipient muted, so they weren't notified.
+  pub muted: bool,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
@@ -25,6 +27,7 @@ pub struct PersonMentionInsertForm {
   pub recipient_id: PersonId,
   pub comment_id: CommentId,
   pub read: Option<bool>,
+  pub muted: Option<bool>,
 }
 
 #[cfg_attr(feature = "full", derive(AsChangeset))]
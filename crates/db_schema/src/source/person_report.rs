@@ -0,0 +1,40 @@
+use crate::newtypes::{PersonId, PersonReportId};
+#[cfg(feature = "full")]
+use crate::schema::person_report;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = person_report))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A report of a user profile, like an offensive display name or bio.
+pub struct PersonReport {
+  pub id: PersonReportId,
+  pub creator_id: PersonId,
+  /// The reported person.
+  pub person_id: PersonId,
+  /// The display name at the time of the report.
+  pub original_display_name: Option<String>,
+  /// The bio at the time of the report.
+  pub original_bio: Option<String>,
+  pub reason: String,
+  pub resolved: bool,
+  pub resolver_id: Option<PersonId>,
+  pub published: chrono::NaiveDateTime,
+  pub updated: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = person_report))]
+pub struct PersonReportForm {
+  pub creator_id: PersonId,
+  pub person_id: PersonId,
+  pub original_display_name: Option<String>,
+  pub original_bio: Option<String>,
+  pub reason: String,
+}
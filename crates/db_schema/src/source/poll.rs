@@ -0,0 +1,78 @@
+use crate::newtypes::{PersonId, PollId, PollOptionId, PostId};
+#[cfg(feature = "full")]
+use crate::schema::{poll, poll_option, poll_vote};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = poll))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll which is attached to a post.
+pub struct Poll {
+  pub id: PollId,
+  pub post_id: PostId,
+  /// No more votes are accepted after this time.
+  pub ends_at: Option<chrono::NaiveDateTime>,
+  /// Lets voters pick more than one option.
+  pub allow_multiple: bool,
+  /// Hides the vote counts from people who haven't voted yet, until the poll ends.
+  pub hide_results_until_voted: bool,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = poll))]
+pub struct PollInsertForm {
+  pub post_id: PostId,
+  pub ends_at: Option<chrono::NaiveDateTime>,
+  pub allow_multiple: bool,
+  pub hide_results_until_voted: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::poll::Poll)))]
+#[cfg_attr(feature = "full", diesel(table_name = poll_option))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A choice in a poll.
+pub struct PollOption {
+  pub id: PollOptionId,
+  pub poll_id: PollId,
+  pub name: String,
+  pub position: i32,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = poll_option))]
+pub struct PollOptionInsertForm {
+  pub poll_id: PollId,
+  pub name: String,
+  pub position: i32,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = poll_vote))]
+pub struct PollVote {
+  pub id: i32,
+  pub poll_id: PollId,
+  pub poll_option_id: PollOptionId,
+  pub person_id: PersonId,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = poll_vote))]
+pub struct PollVoteForm {
+  pub poll_id: PollId,
+  pub poll_option_id: PollOptionId,
+  pub person_id: PersonId,
+}
@@ -53,6 +53,14 @@ pub struct Post {
   pub featured_community: bool,
   /// Whether the post is featured to its site.
   pub featured_local: bool,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  /// An archive.org snapshot of the post link.
+  pub archive_url: Option<DbUrl>,
+  /// A content warning, behind which clients hide the post until it is expanded.
+  pub content_warning: Option<String>,
+  /// The allowlisted video site whose player is the embed video url, so that clients can
+  /// decide how to sandbox it.
+  pub embed_provider: Option<String>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -83,6 +91,9 @@ pub struct PostInsertForm {
   pub language_id: Option<LanguageId>,
   pub featured_community: Option<bool>,
   pub featured_local: Option<bool>,
+  pub archive_url: Option<DbUrl>,
+  pub content_warning: Option<String>,
+  pub embed_provider: Option<String>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -107,6 +118,9 @@ pub struct PostUpdateForm {
   pub language_id: Option<LanguageId>,
   pub featured_community: Option<bool>,
   pub featured_local: Option<bool>,
+  pub archive_url: Option<Option<DbUrl>>,
+  pub content_warning: Option<Option<String>>,
+  pub embed_provider: Option<Option<String>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -139,13 +153,21 @@ pub struct PostSaved {
   pub post_id: PostId,
   pub person_id: PersonId,
   pub published: chrono::NaiveDateTime,
+  pub remind_at: Option<chrono::NaiveDateTime>,
+  /// The folder which the user saved it into.
+  pub tag: Option<String>,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
 #[cfg_attr(feature = "full", diesel(table_name = post_saved))]
+#[cfg_attr(feature = "full", diesel(treat_none_as_null = true))]
 pub struct PostSavedForm {
   pub post_id: PostId,
   pub person_id: PersonId,
+  /// Saving again without a reminder clears it.
+  pub remind_at: Option<chrono::NaiveDateTime>,
+  /// Saving again with another tag moves it, without a tag clears it.
+  pub tag: Option<String>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -0,0 +1,33 @@
+use crate::newtypes::{PersonId, PostId, PostReminderId};
+#[cfg(feature = "full")]
+use crate::schema::post_reminder;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_reminder))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A pending reminder for a post, which shows up in the inbox once it is due.
+pub struct PostReminder {
+  pub id: PostReminderId,
+  pub person_id: PersonId,
+  pub post_id: PostId,
+  pub remind_at: chrono::NaiveDateTime,
+  pub note: Option<String>,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_reminder))]
+pub struct PostReminderInsertForm {
+  pub person_id: PersonId,
+  pub post_id: PostId,
+  pub remind_at: chrono::NaiveDateTime,
+  pub note: Option<String>,
+}
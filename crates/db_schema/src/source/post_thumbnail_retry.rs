@@ -0,0 +1,14 @@
+use crate::newtypes::PostId;
+#[cfg(feature = "full")]
+use crate::schema::post_thumbnail_retry;
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_thumbnail_retry))]
+#[cfg_attr(feature = "full", diesel(primary_key(post_id)))]
+/// A post which was created while pictrs was unavailable, and still needs a thumbnail.
+pub struct PostThumbnailRetry {
+  pub post_id: PostId,
+  pub published: chrono::NaiveDateTime,
+}
@@ -0,0 +1,29 @@
+use crate::newtypes::{LanguageId, PostId};
+#[cfg(feature = "full")]
+use crate::schema::post_translation;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Identifiable, Queryable, Associations, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_translation))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The body of a post in another language, written by its author.
+pub struct PostTranslation {
+  pub id: i32,
+  pub post_id: PostId,
+  pub language_id: LanguageId,
+  pub content: String,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_translation))]
+pub struct PostTranslationForm {
+  pub post_id: PostId,
+  pub language_id: LanguageId,
+  pub content: String,
+}
@@ -0,0 +1,41 @@
+use crate::newtypes::{CommentId, PersonId, PostId, ReminderId};
+#[cfg(feature = "full")]
+use crate::schema::reminder;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = reminder))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A reminder for a post or comment, which is due.
+pub struct Reminder {
+  pub id: ReminderId,
+  pub recipient_id: PersonId,
+  pub post_id: PostId,
+  /// Only set if the reminder is for a comment.
+  pub comment_id: Option<CommentId>,
+  pub read: bool,
+  pub published: chrono::NaiveDateTime,
+  /// The note the user added when asking to be reminded.
+  pub note: Option<String>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = reminder))]
+pub struct ReminderInsertForm {
+  pub recipient_id: PersonId,
+  pub post_id: PostId,
+  pub comment_id: Option<CommentId>,
+  pub note: Option<String>,
+}
+
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = reminder))]
+pub struct ReminderUpdateForm {
+  pub read: Option<bool>,
+}
@@ -0,0 +1,26 @@
+#[cfg(feature = "full")]
+use crate::schema::site_activity_rollup;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, QueryableByName, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = site_activity_rollup))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Activity statistics of the local site for a single time bucket.
+pub struct SiteActivityRollup {
+  /// The first day of the bucket.
+  pub day: chrono::NaiveDate,
+  /// New local users.
+  pub users: i64,
+  /// New local posts.
+  pub posts: i64,
+  /// New local comments.
+  pub comments: i64,
+  /// Local users who posted or commented. For buckets longer than a day, this is the highest daily
+  /// value.
+  pub users_active: i64,
+  /// The number of linked instances at the end of the bucket.
+  pub instances_linked: i64,
+}
@@ -0,0 +1,59 @@
+use crate::newtypes::LocalUserId;
+#[cfg(feature = "full")]
+use crate::schema::{user_data_import_failure, user_data_import_job};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = user_data_import_job))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The import of follows, blocks and saved content from a user data export.
+pub struct UserDataImportJob {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  /// The imported data, only needed to run the job.
+  #[cfg(feature = "full")]
+  #[serde(skip)]
+  #[cfg_attr(feature = "full", ts(skip))]
+  pub data: serde_json::Value,
+  pub items_total: i32,
+  /// Items which were imported or failed.
+  pub items_done: i32,
+  pub published: chrono::NaiveDateTime,
+  pub finished: Option<chrono::NaiveDateTime>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = user_data_import_job))]
+pub struct UserDataImportJobForm {
+  pub local_user_id: LocalUserId,
+  #[cfg(feature = "full")]
+  pub data: serde_json::Value,
+  pub items_total: i32,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = user_data_import_failure))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A setting or item which couldn't be imported.
+pub struct UserDataImportFailure {
+  pub id: i32,
+  pub job_id: i32,
+  /// The activitypub id of the item, or the name of the setting.
+  pub item: String,
+  pub error: String,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = user_data_import_failure))]
+pub struct UserDataImportFailureForm {
+  pub job_id: i32,
+  pub item: String,
+  pub error: String,
+}
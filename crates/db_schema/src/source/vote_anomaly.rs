@@ -0,0 +1,38 @@
+use crate::{
+  newtypes::{CommentId, CommunityId, PostId},
+  schema::vote_anomaly,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+
+#[derive(PartialEq, Debug, Clone, Serialize, Queryable, Identifiable)]
+#[diesel(table_name = vote_anomaly)]
+/// A post or comment which received far more votes than usual in its community, mostly from a
+/// single instance or from new accounts.
+pub struct VoteAnomaly {
+  pub id: i32,
+  pub community_id: CommunityId,
+  pub post_id: PostId,
+  pub comment_id: Option<CommentId>,
+  pub vote_count: i32,
+  /// How many standard deviations the votes are above the community baseline.
+  pub z_score: f64,
+  /// A list of `{name, count}` with the number of votes from each instance.
+  pub instance_distribution: Value,
+  /// A list of `{name, count}` with the number of voters by account age.
+  pub account_age_histogram: Value,
+  pub published: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = vote_anomaly)]
+pub struct VoteAnomalyForm {
+  pub community_id: CommunityId,
+  pub post_id: PostId,
+  pub comment_id: Option<CommentId>,
+  pub vote_count: i32,
+  pub z_score: f64,
+  pub instance_distribution: Value,
+  pub account_age_histogram: Value,
+}
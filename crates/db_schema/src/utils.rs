@@ -9,14 +9,19 @@ use crate::{
 };
 use activitypub_federation::{fetch::object_id::ObjectId, traits::Object};
 use chrono::NaiveDateTime;
-use deadpool::Runtime;
+use deadpool::{
+  managed::{PoolError, TimeoutType},
+  Runtime,
+};
 use diesel::{
   backend::Backend,
   deserialize::FromSql,
+  dsl::sql,
   pg::Pg,
   result::{ConnectionError, ConnectionResult, Error as DieselError, Error::QueryBuilderError},
   serialize::{Output, ToSql},
-  sql_types::Text,
+  sql_query,
+  sql_types::{Bool, Text},
   PgConnection,
 };
 use diesel_async::{
@@ -29,7 +34,7 @@ use diesel_async::{
 use diesel_migrations::EmbeddedMigrations;
 use futures_util::{future::BoxFuture, Future, FutureExt};
 use lemmy_utils::{
-  error::{LemmyError, LemmyErrorExt, LemmyErrorType},
+  error::{DatabaseUnavailableError, LemmyError, LemmyErrorExt, LemmyErrorType},
   settings::structs::Settings,
 };
 use once_cell::sync::Lazy;
@@ -42,7 +47,7 @@ use std::{
   env,
   env::VarError,
   ops::{Deref, DerefMut},
-  sync::Arc,
+  sync::{Arc, Once},
   time::{Duration, SystemTime},
 };
 use tracing::{error, info};
@@ -50,7 +55,7 @@ use url::Url;
 
 const FETCH_LIMIT_DEFAULT: i64 = 10;
 pub const FETCH_LIMIT_MAX: i64 = 50;
-const POOL_TIMEOUT: Option<Duration> = Some(Duration::from_secs(5));
+const DEFAULT_POOL_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub type ActualDbPool = Pool<AsyncPgConnection>;
 
@@ -69,11 +74,26 @@ pub enum DbConn<'a> {
 
 pub async fn get_conn<'a, 'b: 'a>(pool: &'a mut DbPool<'b>) -> Result<DbConn<'a>, DieselError> {
   Ok(match pool {
-    DbPool::Pool(pool) => DbConn::Pool(pool.get().await.map_err(|e| QueryBuilderError(e.into()))?),
+    DbPool::Pool(pool) => DbConn::Pool(pool.get().await.map_err(|e| {
+      if is_database_unavailable(&e) {
+        QueryBuilderError(DatabaseUnavailableError(e.to_string()).into())
+      } else {
+        QueryBuilderError(e.into())
+      }
+    })?),
     DbPool::Conn(conn) => DbConn::Conn(conn),
   })
 }
 
+/// Only failures to open or check a connection mean that the database is gone. Waiting too long
+/// for a free connection just means that the pool is busy.
+fn is_database_unavailable<E>(error: &PoolError<E>) -> bool {
+  !matches!(
+    error,
+    PoolError::Timeout(TimeoutType::Wait) | PoolError::Closed | PoolError::NoRuntimeSpecified
+  )
+}
+
 impl<'a> Deref for DbConn<'a> {
   type Target = AsyncPgConnection;
 
@@ -158,6 +178,15 @@ pub fn fuzzy_search(q: &str) -> String {
   format!("%{replaced}%")
 }
 
+/// Like `fuzzy_search`, but only matches the exact phrase.
+pub fn contains_search(q: &str) -> String {
+  let replaced = q
+    .replace('\\', "\\\\")
+    .replace('%', "\\%")
+    .replace('_', "\\_");
+  format!("%{replaced}%")
+}
+
 pub fn limit_and_offset(
   page: Option<i64>,
   limit: Option<i64>,
@@ -243,31 +272,53 @@ async fn build_db_pool_settings_opt(
 ) -> Result<ActualDbPool, LemmyError> {
   let db_url = get_database_url(settings);
   let pool_size = settings.map(|s| s.database.pool_size).unwrap_or(5);
+  let timeout = settings.map_or(DEFAULT_POOL_TIMEOUT, |s| {
+    Duration::from_secs(s.database.connection_timeout_seconds)
+  });
+  let pool = build_pool(&db_url, pool_size, timeout)?;
+
+  // If there's no settings, that means its a unit test, and migrations need to be run
+  if settings.is_none() {
+    run_migrations(&db_url);
+  }
+
+  Ok(pool)
+}
+
+/// Connections which can't be acquired within the timeout fail with `DatabaseUnavailable`.
+pub fn build_pool(
+  db_url: &str,
+  pool_size: usize,
+  timeout: Duration,
+) -> Result<ActualDbPool, LemmyError> {
   // We only support TLS with sslmode=require currently
   let tls_enabled = db_url.contains("sslmode=require");
   let manager = if tls_enabled {
     // diesel-async does not support any TLS connections out of the box, so we need to manually
     // provide a setup function which handles creating the connection
-    AsyncDieselConnectionManager::<AsyncPgConnection>::new_with_setup(&db_url, establish_connection)
+    AsyncDieselConnectionManager::<AsyncPgConnection>::new_with_setup(db_url, establish_connection)
   } else {
-    AsyncDieselConnectionManager::<AsyncPgConnection>::new(&db_url)
+    AsyncDieselConnectionManager::<AsyncPgConnection>::new(db_url)
   };
   let pool = Pool::builder(manager)
     .max_size(pool_size)
-    .wait_timeout(POOL_TIMEOUT)
-    .create_timeout(POOL_TIMEOUT)
-    .recycle_timeout(POOL_TIMEOUT)
+    .wait_timeout(Some(timeout))
+    .create_timeout(Some(timeout))
+    .recycle_timeout(Some(timeout))
     .runtime(Runtime::Tokio1)
     .build()?;
-
-  // If there's no settings, that means its a unit test, and migrations need to be run
-  if settings.is_none() {
-    run_migrations(&db_url);
-  }
-
   Ok(pool)
 }
 
+/// Checks that a connection from the pool can run queries.
+pub async fn ping_database(pool: &ActualDbPool) -> Result<(), DieselError> {
+  use diesel_async::RunQueryDsl;
+  let mut pool = DbPool::Pool(pool);
+  let conn = &mut get_conn(&mut pool).await?;
+  sql_query("SELECT 1").execute(conn).await?;
+  Ok(())
+}
+
 fn establish_connection(config: &str) -> BoxFuture<ConnectionResult<AsyncPgConnection>> {
   let fut = async {
     let rustls_config = rustls::ClientConfig::builder()
@@ -329,6 +380,49 @@ pub async fn build_db_pool_for_tests() -> ActualDbPool {
     .expect("db pool missing")
 }
 
+/// Recreates the given database next to the one from `LEMMY_DATABASE_URL`, so that tests can run
+/// multiple instances in one process. It is copied from a template database which has all
+/// migrations applied, which is much faster than running them each time.
+pub async fn build_db_pool_for_tests_named(database: &str) -> ActualDbPool {
+  use diesel::RunQueryDsl;
+  const TEMPLATE_DATABASE: &str = "lemmy_tests_template";
+  static TEMPLATE_READY: Once = Once::new();
+
+  let main_url = get_database_url(None);
+  let database_url = |database: &str| {
+    let mut url = Url::parse(&main_url).expect("parse database url");
+    url.set_path(database);
+    url.to_string()
+  };
+  let conn = &mut PgConnection::establish(&main_url)
+    .unwrap_or_else(|e| panic!("Error connecting to {main_url}: {e}"));
+
+  TEMPLATE_READY.call_once(|| {
+    let exists = diesel::select(sql::<Bool>(&format!(
+      "EXISTS (SELECT FROM pg_database WHERE datname = '{TEMPLATE_DATABASE}')"
+    )))
+    .get_result::<bool>(conn)
+    .expect("check template database");
+    if !exists {
+      sql_query(format!("CREATE DATABASE {TEMPLATE_DATABASE}"))
+        .execute(conn)
+        .expect("create template database");
+    }
+    run_migrations(&database_url(TEMPLATE_DATABASE));
+  });
+
+  sql_query(format!("DROP DATABASE IF EXISTS {database} WITH (FORCE)"))
+    .execute(conn)
+    .expect("drop test database");
+  sql_query(format!(
+    "CREATE DATABASE {database} TEMPLATE {TEMPLATE_DATABASE}"
+  ))
+  .execute(conn)
+  .expect("create test database");
+
+  build_pool(&database_url(database), 5, DEFAULT_POOL_TIMEOUT).expect("db pool missing")
+}
+
 pub fn get_database_url(settings: Option<&Settings>) -> String {
   // The env var should override anything in the settings config
   match get_database_url_from_env() {
@@ -517,6 +611,16 @@ mod tests {
   use super::{fuzzy_search, *};
   use crate::utils::is_email_regex;
 
+  #[test]
+  fn test_is_database_unavailable() {
+    let busy: PoolError<ConnectionError> = PoolError::Timeout(TimeoutType::Wait);
+    assert!(!is_database_unavailable(&busy));
+    let create: PoolError<ConnectionError> = PoolError::Timeout(TimeoutType::Create);
+    assert!(is_database_unavailable(&create));
+    let backend = PoolError::Backend(ConnectionError::BadConnection("refused".to_string()));
+    assert!(is_database_unavailable(&backend));
+  }
+
   #[test]
   fn test_fuzzy_search() {
     let test = "This %is% _a_ fuzzy search";
@@ -526,6 +630,15 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_contains_search() {
+    let test = "This %is% _a_ phrase";
+    assert_eq!(
+      contains_search(test),
+      "%This \\%is\\% \\_a\\_ phrase%".to_string()
+    );
+  }
+
   #[test]
   fn test_email() {
     assert!(is_email_regex("gush@gmail.com"));
@@ -10,6 +10,7 @@ use diesel::{
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
+use diesel_ltree::subpath;
 use lemmy_db_schema::{
   aggregates::structs::CommentAggregates,
   aliases,
@@ -33,7 +34,16 @@ use lemmy_db_schema::{
     post::Post,
   },
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
+  utils::{
+    get_conn,
+    limit_and_offset,
+    DbConn,
+    DbPool,
+    ListFn,
+    Queries,
+    ReadFn,
+    DELETED_REPLACEMENT_TEXT,
+  },
 };
 
 fn queries<'a>() -> Queries<
@@ -61,6 +71,21 @@ fn queries<'a>() -> Queries<
         aliases::person2
           .on(comment_report::resolver_id.eq(aliases::person2.field(person::id).nullable())),
       )
+      // The parent path of top-level comments is only the root, so they have no parent
+      .left_join(
+        aliases::comment1.on(aliases::comment1.field(comment::path).eq(subpath(
+          comment::path,
+          0,
+          -1,
+        ))),
+      )
+      .left_join(
+        aliases::person3.on(
+          aliases::comment1
+            .field(comment::creator_id)
+            .eq(aliases::person3.field(person::id)),
+        ),
+      )
   };
 
   let selection = (
@@ -74,6 +99,8 @@ fn queries<'a>() -> Queries<
     community_person_ban::id.nullable().is_not_null(),
     comment_like::score.nullable(),
     aliases::person2.fields(person::all_columns).nullable(),
+    aliases::comment1.fields(comment::all_columns).nullable(),
+    aliases::person3.fields(person::all_columns).nullable(),
   );
 
   let read = move |mut conn: DbConn<'a>, (report_id, my_person_id): (CommentReportId, PersonId)| async move {
@@ -231,9 +258,17 @@ impl JoinView for CommentReportView {
     bool,
     Option<i16>,
     Option<Person>,
+    Option<Comment>,
+    Option<Person>,
   );
 
   fn from_tuple(a: Self::JoinTuple) -> Self {
+    let parent_comment = a.10.map(|mut parent| {
+      if parent.deleted || parent.removed {
+        parent.content = DELETED_REPLACEMENT_TEXT.to_string();
+      }
+      parent
+    });
     Self {
       comment_report: a.0,
       comment: a.1,
@@ -245,6 +280,8 @@ impl JoinView for CommentReportView {
       creator_banned_from_community: a.7,
       my_vote: a.8,
       resolver: a.9,
+      parent_comment,
+      parent_comment_creator: a.11,
     }
   }
 }
@@ -258,7 +295,7 @@ mod tests {
   use lemmy_db_schema::{
     aggregates::structs::CommentAggregates,
     source::{
-      comment::{Comment, CommentInsertForm},
+      comment::{Comment, CommentInsertForm, CommentUpdateForm},
       comment_report::{CommentReport, CommentReportForm},
       community::{Community, CommunityInsertForm, CommunityModerator, CommunityModeratorForm},
       instance::Instance,
@@ -266,7 +303,7 @@ mod tests {
       post::{Post, PostInsertForm},
     },
     traits::{Crud, Joinable, Reportable},
-    utils::build_db_pool_for_tests,
+    utils::{build_db_pool_for_tests, DELETED_REPLACEMENT_TEXT},
   };
   use serial_test::serial;
 
@@ -375,7 +412,7 @@ mod tests {
     let expected_jessica_report_view = CommentReportView {
       comment_report: inserted_jessica_report.clone(),
       comment: inserted_comment.clone(),
-      post: inserted_post,
+      post: inserted_post.clone(),
       community: Community {
         id: inserted_community.id,
         name: inserted_community.name,
@@ -400,6 +437,12 @@ mod tests {
         shared_inbox_url: inserted_community.shared_inbox_url,
         moderators_url: inserted_community.moderators_url,
         featured_url: inserted_community.featured_url,
+        default_post_sort: None,
+        default_comment_sort: None,
+        min_account_age_days_to_vote: 0,
+        only_followers_can_vote: false,
+        verified: false,
+        commenting_restricted_to_mods: false,
         instance_id: inserted_instance.id,
       },
       creator: Person {
@@ -464,6 +507,8 @@ mod tests {
       },
       my_vote: None,
       resolver: None,
+      parent_comment: None,
+      parent_comment_creator: None,
     };
 
     assert_eq!(read_jessica_report_view, expected_jessica_report_view);
@@ -585,6 +630,56 @@ mod tests {
         .unwrap();
     assert_eq!(1, report_count_after_resolved);
 
+    // Reports of replies include the parent comment, which is replaced once it is removed
+    let reply_form = CommentInsertForm::builder()
+      .content("A test reply crv".into())
+      .creator_id(inserted_sara.id)
+      .post_id(inserted_post.id)
+      .build();
+    let inserted_reply = Comment::create(pool, &reply_form, Some(&inserted_comment.path))
+      .await
+      .unwrap();
+    let reply_report_form = CommentReportForm {
+      creator_id: inserted_jessica.id,
+      comment_id: inserted_reply.id,
+      original_comment_text: "A test reply crv".into(),
+      reason: "from jessica".into(),
+    };
+    let inserted_reply_report = CommentReport::report(pool, &reply_report_form)
+      .await
+      .unwrap();
+
+    let read_reply_report_view =
+      CommentReportView::read(pool, inserted_reply_report.id, inserted_timmy.id)
+        .await
+        .unwrap();
+    let parent_comment = read_reply_report_view.parent_comment.unwrap();
+    assert_eq!(inserted_comment.id, parent_comment.id);
+    assert_eq!(inserted_comment.content, parent_comment.content);
+    assert_eq!(
+      Some(inserted_timmy.id),
+      read_reply_report_view.parent_comment_creator.map(|c| c.id)
+    );
+
+    let remove_form = CommentUpdateForm {
+      removed: Some(true),
+      ..Default::default()
+    };
+    Comment::update(pool, inserted_comment.id, &remove_form)
+      .await
+      .unwrap();
+    let read_reply_report_view_after_remove =
+      CommentReportView::read(pool, inserted_reply_report.id, inserted_timmy.id)
+        .await
+        .unwrap();
+    assert_eq!(
+      DELETED_REPLACEMENT_TEXT,
+      read_reply_report_view_after_remove
+        .parent_comment
+        .unwrap()
+        .content
+    );
+
     Person::delete(pool, inserted_timmy.id).await.unwrap();
     Person::delete(pool, inserted_sara.id).await.unwrap();
     Person::delete(pool, inserted_jessica.id).await.unwrap();
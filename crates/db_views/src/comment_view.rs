@@ -1,9 +1,12 @@
 use crate::structs::{CommentView, LocalUserView};
 use diesel::{
+  dsl::{exists, not, now},
   pg::Pg,
-  result::Error,
+  result::{Error, Error::QueryBuilderError},
+  sql_types::Bool,
   BoolExpressionMethods,
   ExpressionMethods,
+  IntoSql,
   JoinOnDsl,
   NullableExpressionMethods,
   PgTextExpressionMethods,
@@ -22,7 +25,10 @@ use lemmy_db_schema::{
     community,
     community_block,
     community_follower,
+    community_moderator,
     community_person_ban,
+    community_person_flair,
+    local_user,
     local_user_language,
     person,
     person_block,
@@ -31,27 +37,51 @@ use lemmy_db_schema::{
   source::{
     comment::Comment,
     community::{Community, CommunityFollower},
+    community_flair::CommunityPersonFlair,
+    local_user::LocalUser,
     person::Person,
+    person_keyword_block::PersonKeywordBlock,
     post::Post,
   },
   traits::JoinView,
-  utils::{fuzzy_search, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
+  utils::{
+    contains_search,
+    fuzzy_search,
+    get_conn,
+    limit_and_offset,
+    DbConn,
+    DbPool,
+    ListFn,
+    Queries,
+    ReadFn,
+  },
   CommentSortType,
   ListingType,
   SubscribedType,
 };
 
+/// How many comments of each tree [`CommentQuery::list_branches`] returns. Deeper replies can
+/// still be loaded by their parent.
+pub const MAX_COMMENTS_PER_BRANCH: i64 = 100;
+
 type CommentViewTuple = (
-  Comment,
-  Person,
-  Post,
-  Community,
-  CommentAggregates,
-  bool,
-  SubscribedType,
-  bool,
+  (
+    Comment,
+    Person,
+    Post,
+    Community,
+    CommentAggregates,
+    bool,
+    SubscribedType,
+    bool,
+    bool,
+    Option<i16>,
+    Option<chrono::NaiveDateTime>,
+    Option<String>,
+    Option<CommunityPersonFlair>,
+  ),
+  // Whether the comment is below the user's score threshold
   bool,
-  Option<i16>,
 );
 
 fn queries<'a>() -> Queries<
@@ -70,7 +100,12 @@ fn queries<'a>() -> Queries<
         community_person_ban::table.on(
           community::id
             .eq(community_person_ban::community_id)
-            .and(community_person_ban::person_id.eq(comment::creator_id)),
+            .and(community_person_ban::person_id.eq(comment::creator_id))
+            .and(
+              community_person_ban::expires
+                .is_null()
+                .or(community_person_ban::expires.gt(now)),
+            ),
         ),
       )
       .left_join(
@@ -101,6 +136,13 @@ fn queries<'a>() -> Queries<
             .and(comment_like::person_id.eq(person_id_join)),
         ),
       )
+      .left_join(
+        community_person_flair::table.on(
+          community::id
+            .eq(community_person_flair::community_id)
+            .and(community_person_flair::person_id.eq(comment::creator_id)),
+        ),
+      )
   };
 
   let selection = (
@@ -114,12 +156,15 @@ fn queries<'a>() -> Queries<
     comment_saved::id.nullable().is_not_null(),
     person_block::id.nullable().is_not_null(),
     comment_like::score.nullable(),
+    comment_saved::remind_at.nullable(),
+    comment_saved::tag.nullable(),
+    community_person_flair::all_columns.nullable(),
   );
 
   let read = move |mut conn: DbConn<'a>,
                    (comment_id, my_person_id): (CommentId, Option<PersonId>)| async move {
     all_joins(comment::table.find(comment_id).into_boxed(), my_person_id)
-      .select(selection)
+      .select((selection, false.into_sql::<Bool>()))
       .first::<CommentViewTuple>(&mut conn)
       .await
   };
@@ -131,6 +176,20 @@ fn queries<'a>() -> Queries<
     // The left join below will return None in this case
     let person_id_join = person_id.unwrap_or(PersonId(-1));
     let local_user_id_join = local_user_id.unwrap_or(LocalUserId(-1));
+    let is_tree_fetch = options.max_depth.is_some() || options.branch_paths.is_some();
+
+    // Comments below the user's score threshold are redacted rather than filtered out, so that
+    // the comment tree stays intact. This doesn't apply to admins, to the user's own comments or
+    // to communities which they moderate. Without a threshold, no score is below i64::MIN.
+    let min_score = options
+      .local_user
+      .filter(|l| !(options.ignore_score_filter || l.person.admin))
+      .and_then(|l| l.local_user.hide_content_below_score)
+      .map_or(i64::MIN, i64::from);
+    let hidden_by_score = comment_aggregates::score
+      .lt(min_score)
+      .and(comment::creator_id.ne(person_id_join))
+      .and(community_moderator::person_id.is_null());
 
     let mut query = all_joins(comment::table.into_boxed(), person_id)
       .left_join(
@@ -147,7 +206,14 @@ fn queries<'a>() -> Queries<
             .and(local_user_language::local_user_id.eq(local_user_id_join)),
         ),
       )
-      .select(selection);
+      .left_join(
+        community_moderator::table.on(
+          community::id
+            .eq(community_moderator::community_id)
+            .and(community_moderator::person_id.eq(person_id_join)),
+        ),
+      )
+      .select((selection, hidden_by_score));
 
     if let Some(creator_id) = options.creator_id {
       query = query.filter(comment::creator_id.eq(creator_id));
@@ -161,10 +227,21 @@ fn queries<'a>() -> Queries<
       query = query.filter(comment::path.contained_by(parent_path));
     };
 
+    if let Some(branch_paths) = options.branch_paths {
+      query = query.filter(comment::path.contained_by_any(branch_paths));
+    };
+
     if let Some(search_term) = options.search_term {
       query = query.filter(comment::content.ilike(fuzzy_search(&search_term)));
     };
 
+    // Hide comments containing any of the user's blocked keywords
+    if let Some(person_id) = person_id {
+      for keyword in PersonKeywordBlock::read(&mut (&mut conn).into(), person_id).await? {
+        query = query.filter(comment::content.not_ilike(contains_search(&keyword)));
+      }
+    }
+
     if let Some(community_id) = options.community_id {
       query = query.filter(post::community_id.eq(community_id));
     }
@@ -191,6 +268,9 @@ fn queries<'a>() -> Queries<
 
     if options.saved_only {
       query = query.filter(comment_saved::comment_id.is_not_null());
+      if let Some(saved_tag) = options.saved_tag {
+        query = query.filter(comment_saved::tag.eq(saved_tag));
+      }
     }
 
     if options.liked_only {
@@ -206,9 +286,14 @@ fn queries<'a>() -> Queries<
     }
 
     let is_admin = options.local_user.map(|l| l.person.admin).unwrap_or(false);
-    // only show removed comments to admin when viewing user profile
+    // only show removed comments, and comments of accounts which are about to be deleted, to
+    // admin when viewing user profile
     if !(options.is_profile_view && is_admin) {
-      query = query.filter(comment::removed.eq(false));
+      query = query.filter(comment::removed.eq(false)).filter(not(exists(
+        local_user::table
+          .filter(local_user::person_id.eq(comment::creator_id))
+          .filter(local_user::deletion_scheduled_at.is_not_null()),
+      )));
     }
 
     if !options
@@ -230,16 +315,18 @@ fn queries<'a>() -> Queries<
       query = query.filter(person_block::person_id.is_null());
     }
 
-    // A Max depth given means its a tree fetch
-    let (limit, offset) = if let Some(max_depth) = options.max_depth {
-      let depth_limit = if let Some(parent_path) = options.parent_path.as_ref() {
-        parent_path.0.split('.').count() as i32 + max_depth
-        // Add one because of root "0"
-      } else {
-        max_depth + 1
-      };
-
-      query = query.filter(nlevel(comment::path).le(depth_limit));
+    // A Max depth or a set of branches given means its a tree fetch
+    let (limit, offset) = if is_tree_fetch {
+      if let Some(max_depth) = options.max_depth {
+        let depth_limit = if let Some(parent_path) = options.parent_path.as_ref() {
+          parent_path.0.split('.').count() as i32 + max_depth
+          // Add one because of root "0"
+        } else {
+          max_depth + 1
+        };
+
+        query = query.filter(nlevel(comment::path).le(depth_limit));
+      }
 
       // only order if filtering by a post id, or parent_path. DOS potential otherwise and max_depth + !post_id isn't used anyways (afaik)
       if options.post_id.is_some() || options.parent_path.is_some() {
@@ -257,7 +344,11 @@ fn queries<'a>() -> Queries<
       // If a max depth is given, then you know its a tree fetch, and limits should be ignored
       // TODO a kludge to prevent attacks. Limit comments to 300 for now.
       // (i64::MAX, 0)
-      (300, 0)
+      if options.branch_paths.is_some() {
+        (MAX_COMMENTS_PER_BRANCH, 0)
+      } else {
+        (300, 0)
+      }
     } else {
       // limit_and_offset_unlimited(options.page, options.limit)
       limit_and_offset(options.page, options.limit)?
@@ -300,9 +391,15 @@ impl CommentView {
     }
     Ok(res)
   }
+
+  /// Redacts the comment if it is NSFW and the user doesn't want to see NSFW content. See
+  /// [`Comment::redact_nsfw`].
+  pub fn redact_nsfw(&mut self, local_user: Option<&LocalUser>) {
+    self.hidden_as_nsfw = self.comment.redact_nsfw(local_user);
+  }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct CommentQuery<'a> {
   pub listing_type: Option<ListingType>,
   pub sort: Option<CommentSortType>,
@@ -313,23 +410,234 @@ pub struct CommentQuery<'a> {
   pub local_user: Option<&'a LocalUserView>,
   pub search_term: Option<String>,
   pub saved_only: bool,
+  /// Only the saved comments with this tag, if `saved_only` is set
+  pub saved_tag: Option<String>,
   pub liked_only: bool,
   pub disliked_only: bool,
   pub is_profile_view: bool,
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub max_depth: Option<i32>,
+  /// Only fetch the trees below these paths
+  pub branch_paths: Option<Vec<Ltree>>,
+  /// Where [`CommentQuery::list_branches`] continues, from the previous page.
+  pub branch_cursor: Option<String>,
+  /// Don't hide comments below the user's `hide_content_below_score`
+  pub ignore_score_filter: bool,
 }
 
 impl<'a> CommentQuery<'a> {
   pub async fn list(self, pool: &mut DbPool<'_>) -> Result<Vec<CommentView>, Error> {
-    queries().list(pool, self).await
+    let local_user = self.local_user;
+    let mut comments = queries().list(pool, self).await?;
+
+    for c in comments.iter_mut().filter(|c| c.hidden_by_score) {
+      c.comment.content = String::new();
+    }
+
+    // NSFW comments are redacted the same way for users who don't want to see NSFW content
+    for c in &mut comments {
+      c.redact_nsfw(local_user.map(|l| &l.local_user));
+    }
+    Ok(comments)
+  }
+
+  /// Fetches a page of comment trees, where `limit` applies to the top-level comments below the
+  /// post or `parent_path`, rather than to individual comments. Pages continue after the
+  /// `branch_cursor` returned with the previous one, so that branches whose rank changed in the
+  /// meantime aren't skipped or repeated. Each tree is bounded by `max_depth` and by
+  /// [`MAX_COMMENTS_PER_BRANCH`]. Also returns the cursor of the next page, if there are more
+  /// top-level comments.
+  pub async fn list_branches(
+    self,
+    pool: &mut DbPool<'_>,
+  ) -> Result<(Vec<CommentView>, Option<String>), Error> {
+    if self.post_id.is_none() && self.parent_path.is_none() {
+      return Err(QueryBuilderError(
+        "Branches can only be listed for a post or a parent comment".into(),
+      ));
+    }
+    let (limit, _) = limit_and_offset(None, self.limit)?;
+    // Top-level comments are one level below the parent, or below the root "0"
+    let top_level = self
+      .parent_path
+      .as_ref()
+      .map(|p| p.0.split('.').count() as i32 + 1)
+      .unwrap_or(2);
+
+    let mut query = comment::table
+      .inner_join(comment_aggregates::table)
+      .filter(nlevel(comment::path).eq(top_level))
+      .select((
+        comment::path,
+        comment::id,
+        comment_aggregates::hot_rank,
+        comment_aggregates::score,
+        comment_aggregates::controversy_rank,
+        comment::published,
+      ))
+      .into_boxed();
+
+    if let Some(post_id) = self.post_id {
+      query = query.filter(comment::post_id.eq(post_id));
+    }
+
+    if let Some(parent_path) = self.parent_path.as_ref() {
+      query = query.filter(comment::path.contained_by(parent_path));
+    }
+
+    let sort = self.sort.unwrap_or(CommentSortType::Hot);
+    // Only the branches after the last one of the previous page, in the same order
+    if let Some(cursor) = self.branch_cursor.as_deref() {
+      let c = BranchCursor::parse(cursor)?;
+      let after_id = comment::id.gt(c.comment_id);
+      query = match sort {
+        CommentSortType::Hot => query.filter(
+          comment_aggregates::hot_rank.lt(c.hot_rank).or(
+            comment_aggregates::hot_rank.eq(c.hot_rank).and(
+              comment_aggregates::score
+                .lt(c.score)
+                .or(comment_aggregates::score.eq(c.score).and(after_id)),
+            ),
+          ),
+        ),
+        CommentSortType::Controversial => query.filter(
+          comment_aggregates::controversy_rank
+            .lt(c.controversy_rank)
+            .or(
+              comment_aggregates::controversy_rank
+                .eq(c.controversy_rank)
+                .and(after_id),
+            ),
+        ),
+        CommentSortType::New => query.filter(
+          comment::published
+            .lt(c.published)
+            .or(comment::published.eq(c.published).and(after_id)),
+        ),
+        CommentSortType::Old => query.filter(
+          comment::published
+            .gt(c.published)
+            .or(comment::published.eq(c.published).and(after_id)),
+        ),
+        CommentSortType::Top => query.filter(
+          comment_aggregates::score
+            .lt(c.score)
+            .or(comment_aggregates::score.eq(c.score).and(after_id)),
+        ),
+      };
+    }
+
+    query = match sort {
+      CommentSortType::Hot => query
+        .then_order_by(comment_aggregates::hot_rank.desc())
+        .then_order_by(comment_aggregates::score.desc()),
+      CommentSortType::Controversial => {
+        query.then_order_by(comment_aggregates::controversy_rank.desc())
+      }
+      CommentSortType::New => query.then_order_by(comment::published.desc()),
+      CommentSortType::Old => query.then_order_by(comment::published.asc()),
+      CommentSortType::Top => query.then_order_by(comment_aggregates::score.desc()),
+    };
+
+    // Fetch one extra branch to know whether there is a next page
+    let mut branches = {
+      let conn = &mut get_conn(pool).await?;
+      query
+        .then_order_by(comment::id.asc())
+        .limit(limit + 1)
+        .load::<(Ltree, CommentId, i32, i64, f64, chrono::NaiveDateTime)>(conn)
+        .await?
+    };
+    let next_page = if branches.len() as i64 > limit {
+      branches.pop();
+      branches.last().map(
+        |(_, comment_id, hot_rank, score, controversy_rank, published)| {
+          BranchCursor {
+            comment_id: *comment_id,
+            hot_rank: *hot_rank,
+            score: *score,
+            controversy_rank: *controversy_rank,
+            published: *published,
+          }
+          .to_string()
+        },
+      )
+    } else {
+      None
+    };
+
+    // Each tree is fetched on its own, so that a large one can't crowd out the others
+    let mut comments = vec![];
+    for (path, ..) in branches {
+      let branch = CommentQuery {
+        branch_paths: Some(vec![path]),
+        branch_cursor: None,
+        page: None,
+        limit: None,
+        ..self.clone()
+      }
+      .list(pool)
+      .await?;
+      comments.extend(branch);
+    }
+
+    Ok((comments, next_page))
+  }
+}
+
+/// The position of the last top-level comment of a page of branches, with the values of all the
+/// sort types so that the cursor doesn't depend on the sort of the request.
+struct BranchCursor {
+  comment_id: CommentId,
+  hot_rank: i32,
+  score: i64,
+  controversy_rank: f64,
+  published: chrono::NaiveDateTime,
+}
+
+impl BranchCursor {
+  fn parse(cursor: &str) -> Result<Self, Error> {
+    let invalid = || QueryBuilderError("Invalid branch cursor".into());
+    let mut parts = cursor.split('_');
+    let mut next = || parts.next().ok_or_else(invalid);
+    let comment_id = CommentId(next()?.parse().map_err(|_| invalid())?);
+    let hot_rank = next()?.parse().map_err(|_| invalid())?;
+    let score = next()?.parse().map_err(|_| invalid())?;
+    let controversy_rank = next()?.parse().map_err(|_| invalid())?;
+    let published = next()?
+      .parse()
+      .ok()
+      .and_then(chrono::NaiveDateTime::from_timestamp_micros)
+      .ok_or_else(invalid)?;
+    Ok(Self {
+      comment_id,
+      hot_rank,
+      score,
+      controversy_rank,
+      published,
+    })
+  }
+}
+
+impl std::fmt::Display for BranchCursor {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}_{}_{}_{}_{}",
+      self.comment_id.0,
+      self.hot_rank,
+      self.score,
+      self.controversy_rank,
+      self.published.timestamp_micros()
+    )
   }
 }
 
 impl JoinView for CommentView {
   type JoinTuple = CommentViewTuple;
-  fn from_tuple(a: Self::JoinTuple) -> Self {
+  fn from_tuple((a, hidden_by_score): Self::JoinTuple) -> Self {
+    let collapsed = a.0.content_warning.is_some();
     Self {
       comment: a.0,
       creator: a.1,
@@ -337,10 +645,16 @@ impl JoinView for CommentView {
       community: a.3,
       counts: a.4,
       creator_banned_from_community: a.5,
+      creator_flair: a.12,
       subscribed: a.6,
       saved: a.7,
+      saved_remind_at: a.10,
+      saved_tag: a.11,
       creator_blocked: a.8,
       my_vote: a.9,
+      hidden_by_score,
+      hidden_as_nsfw: false,
+      collapsed,
     }
   }
 }
@@ -363,23 +677,25 @@ mod tests {
     },
     structs::LocalUserView,
   };
+  use chrono::Duration;
   use lemmy_db_schema::{
     aggregates::structs::CommentAggregates,
     impls::actor_language::UNDETERMINED_ID,
     newtypes::LanguageId,
     source::{
       actor_language::LocalUserLanguage,
-      comment::{CommentInsertForm, CommentLike, CommentLikeForm},
-      community::CommunityInsertForm,
+      comment::{CommentInsertForm, CommentLike, CommentLikeForm, CommentUpdateForm},
+      community::{CommunityInsertForm, CommunityPersonBan, CommunityPersonBanForm},
       instance::Instance,
       language::Language,
       local_user::{LocalUser, LocalUserInsertForm},
       person::PersonInsertForm,
       person_block::{PersonBlock, PersonBlockForm},
+      person_keyword_block::PersonKeywordBlock,
       post::PostInsertForm,
     },
-    traits::{Blockable, Crud, Likeable},
-    utils::build_db_pool_for_tests,
+    traits::{Bannable, Blockable, Crud, Likeable},
+    utils::{build_db_pool_for_tests, naive_now},
     SubscribedType,
   };
   use serial_test::serial;
@@ -717,6 +1033,67 @@ mod tests {
       .eq("Comment 3"));
     assert_eq!(3, read_comment_views_parent_max_depth.len());
 
+    // Add a second top-level comment, to page over the top-level branches
+    let comment_form_6 = CommentInsertForm::builder()
+      .content("Comment 6".into())
+      .creator_id(data.inserted_person_2.id)
+      .post_id(data.inserted_post.id)
+      .build();
+    let inserted_comment_6 = Comment::create(pool, &comment_form_6, None).await.unwrap();
+
+    let (read_comment_views_branch_1, next_page) = CommentQuery {
+      post_id: (Some(data.inserted_post.id)),
+      sort: (Some(CommentSortType::Old)),
+      limit: (Some(1)),
+      ..Default::default()
+    }
+    .list_branches(pool)
+    .await
+    .unwrap();
+
+    // The first page has the entire first tree, and points to the next one
+    assert_eq!(6, read_comment_views_branch_1.len());
+    assert!(next_page.is_some());
+
+    // A new branch which sorts before the cursor doesn't shift the next page
+    let comment_form_7 = CommentInsertForm::builder()
+      .content("Comment 7".into())
+      .creator_id(data.inserted_person_2.id)
+      .post_id(data.inserted_post.id)
+      .published(Some(data.inserted_comment_0.published - Duration::days(1)))
+      .build();
+    let inserted_comment_7 = Comment::create(pool, &comment_form_7, None).await.unwrap();
+
+    let (read_comment_views_branch_2, next_page) = CommentQuery {
+      post_id: (Some(data.inserted_post.id)),
+      sort: (Some(CommentSortType::Old)),
+      max_depth: (Some(1)),
+      branch_cursor: next_page,
+      limit: (Some(1)),
+      ..Default::default()
+    }
+    .list_branches(pool)
+    .await
+    .unwrap();
+
+    assert_eq!(1, read_comment_views_branch_2.len());
+    assert_eq!(inserted_comment_6, read_comment_views_branch_2[0].comment);
+    assert_eq!(None, next_page);
+
+    // Branches are only listed for a post or a parent comment
+    let all_branches = CommentQuery::default().list_branches(pool).await;
+    assert!(all_branches.is_err());
+    let invalid_cursor = CommentQuery {
+      post_id: (Some(data.inserted_post.id)),
+      branch_cursor: Some("invalid".to_string()),
+      ..Default::default()
+    }
+    .list_branches(pool)
+    .await;
+    assert!(invalid_cursor.is_err());
+
+    Comment::delete(pool, inserted_comment_7.id).await.unwrap();
+    Comment::delete(pool, inserted_comment_6.id).await.unwrap();
     cleanup(data, pool).await;
   }
 
@@ -783,6 +1160,193 @@ mod tests {
     cleanup(data, pool).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_hide_content_below_score() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    // Sara only wants to see comments with a score of at least 1
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(data.inserted_person_2.id)
+      .password_encrypted(String::new())
+      .hide_content_below_score(Some(1))
+      .build();
+    let sara_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+    let sara_view = LocalUserView {
+      local_user: sara_local_user,
+      person: data.inserted_person_2.clone(),
+      counts: Default::default(),
+    };
+
+    let comments = CommentQuery {
+      post_id: (Some(data.inserted_post.id)),
+      local_user: (Some(&sara_view)),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+
+    // Hidden comments are still returned, to keep the tree intact
+    assert_eq!(6, comments.len());
+    let hidden: Vec<_> = comments.iter().filter(|c| c.hidden_by_score).collect();
+    assert_eq!(4, hidden.len());
+    assert!(hidden.iter().all(|c| c.comment.content.is_empty()));
+
+    // The upvoted comment, and sara's own comment are shown
+    let shown_ids: Vec<_> = comments
+      .iter()
+      .filter(|c| !c.hidden_by_score)
+      .map(|c| c.comment.id)
+      .collect();
+    assert!(shown_ids.contains(&data.inserted_comment_0.id));
+    assert!(shown_ids.contains(&data.inserted_comment_1.id));
+
+    let unfiltered_comments = CommentQuery {
+      post_id: (Some(data.inserted_post.id)),
+      local_user: (Some(&sara_view)),
+      ignore_score_filter: true,
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert!(unfiltered_comments.iter().all(|c| !c.hidden_by_score));
+
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_hide_nsfw() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let form = CommentUpdateForm {
+      nsfw: Some(true),
+      ..Default::default()
+    };
+    Comment::update(pool, data.inserted_comment_2.id, &form)
+      .await
+      .unwrap();
+
+    // Anonymous users don't see NSFW content, but the comment stays in the tree
+    let comments = CommentQuery {
+      post_id: (Some(data.inserted_post.id)),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(6, comments.len());
+    let hidden: Vec<_> = comments.iter().filter(|c| c.hidden_as_nsfw).collect();
+    assert_eq!(1, hidden.len());
+    assert_eq!(data.inserted_comment_2.id, hidden[0].comment.id);
+    assert!(hidden[0].comment.content.is_empty());
+    assert!(hidden[0].comment.nsfw);
+
+    // Timmy doesn't want to see NSFW content either, but still sees their own comment
+    let comments = CommentQuery {
+      post_id: (Some(data.inserted_post.id)),
+      local_user: (Some(&data.local_user_view)),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert!(comments.iter().all(|c| !c.hidden_as_nsfw));
+
+    // A single comment is redacted the same way
+    let mut comment_view = CommentView::read(pool, data.inserted_comment_2.id, None)
+      .await
+      .unwrap();
+    comment_view.redact_nsfw(Some(&data.local_user_view.local_user));
+    assert!(!comment_view.hidden_as_nsfw);
+    assert!(!comment_view.comment.content.is_empty());
+    comment_view.redact_nsfw(None);
+    assert!(comment_view.hidden_as_nsfw);
+    assert!(comment_view.comment.content.is_empty());
+
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_expired_community_ban() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    // A ban which is over doesn't count anymore, even before it is deleted
+    let ban_form = |expires| CommunityPersonBanForm {
+      community_id: data.inserted_community.id,
+      person_id: data.inserted_comment_0.creator_id,
+      expires: Some(Some(expires)),
+    };
+    CommunityPersonBan::ban(pool, &ban_form(naive_now() + Duration::hours(1)))
+      .await
+      .unwrap();
+    let comment_view = CommentView::read(pool, data.inserted_comment_0.id, None)
+      .await
+      .unwrap();
+    assert!(comment_view.creator_banned_from_community);
+    CommunityPersonBan::ban(pool, &ban_form(naive_now() - Duration::hours(1)))
+      .await
+      .unwrap();
+    let comment_view = CommentView::read(pool, data.inserted_comment_0.id, None)
+      .await
+      .unwrap();
+    assert!(!comment_view.creator_banned_from_community);
+
+    CommunityPersonBan::unban(pool, &ban_form(naive_now()))
+      .await
+      .unwrap();
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_keyword_block() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let query = || CommentQuery {
+      post_id: (Some(data.inserted_post.id)),
+      local_user: (Some(&data.local_user_view)),
+      ..Default::default()
+    };
+    let comments_before = query().list(pool).await.unwrap();
+
+    PersonKeywordBlock::update(
+      pool,
+      data.local_user_view.person.id,
+      vec!["COMMENT 2".to_string()],
+    )
+    .await
+    .unwrap();
+
+    let comments = query().list(pool).await.unwrap();
+    assert_eq!(comments_before.len() - 1, comments.len());
+    assert!(comments
+      .iter()
+      .all(|c| c.comment.id != data.inserted_comment_2.id));
+
+    // Reading the comment directly still works
+    let read_comment = CommentView::read(
+      pool,
+      data.inserted_comment_2.id,
+      Some(data.local_user_view.person.id),
+    )
+    .await;
+    assert!(read_comment.is_ok());
+
+    cleanup(data, pool).await;
+  }
+
   async fn cleanup(data: Data, pool: &mut DbPool<'_>) {
     CommentLike::remove(
       pool,
@@ -818,10 +1382,16 @@ mod tests {
       .unwrap();
     CommentView {
       creator_banned_from_community: false,
+      creator_flair: None,
       my_vote: None,
       subscribed: SubscribedType::NotSubscribed,
       saved: false,
+      saved_remind_at: None,
+      saved_tag: None,
       creator_blocked: false,
+      hidden_by_score: false,
+      hidden_as_nsfw: false,
+      collapsed: false,
       comment: Comment {
         id: data.inserted_comment_0.id,
         content: "Comment 0".into(),
@@ -836,6 +1406,10 @@ mod tests {
         distinguished: false,
         path: data.inserted_comment_0.clone().path,
         language_id: LanguageId(37),
+        content_warning: None,
+        locked: false,
+        backfilled: false,
+        nsfw: false,
       },
       creator: Person {
         id: data.local_user_view.person.id,
@@ -881,8 +1455,11 @@ mod tests {
         ap_id: data.inserted_post.ap_id.clone(),
         local: true,
         language_id: Default::default(),
+        content_warning: None,
+        embed_provider: None,
         featured_community: false,
         featured_local: false,
+        archive_url: None,
       },
       community: Community {
         id: data.inserted_community.id,
@@ -909,6 +1486,12 @@ mod tests {
         shared_inbox_url: data.inserted_community.shared_inbox_url.clone(),
         moderators_url: data.inserted_community.moderators_url.clone(),
         featured_url: data.inserted_community.featured_url.clone(),
+        default_post_sort: None,
+        default_comment_sort: None,
+        min_account_age_days_to_vote: 0,
+        only_followers_can_vote: false,
+        verified: false,
+        commenting_restricted_to_mods: false,
       },
       counts: CommentAggregates {
         id: agg.id,
@@ -8,8 +8,14 @@ pub mod comment_view;
 #[cfg(feature = "full")]
 pub mod custom_emoji_view;
 #[cfg(feature = "full")]
+pub mod local_image_view;
+#[cfg(feature = "full")]
 pub mod local_user_view;
 #[cfg(feature = "full")]
+pub mod person_report_view;
+#[cfg(feature = "full")]
+pub mod poll_view;
+#[cfg(feature = "full")]
 pub mod post_report_view;
 #[cfg(feature = "full")]
 pub mod post_view;
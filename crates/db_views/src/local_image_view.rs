@@ -0,0 +1,170 @@
+use crate::structs::LocalImageView;
+use diesel::{result::Error, ExpressionMethods, JoinOnDsl, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{LocalUserId, PersonId},
+  schema::{local_image, local_user, person},
+  source::{images::LocalImage, person::Person},
+  traits::JoinView,
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+
+type LocalImageViewTuple = (LocalImage, Person);
+
+#[derive(Default)]
+pub struct LocalImageQuery {
+  pub local_user_id: Option<LocalUserId>,
+  pub person_id: Option<PersonId>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+impl LocalImageQuery {
+  pub async fn list(self, pool: &mut DbPool<'_>) -> Result<Vec<LocalImageView>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = local_image::table
+      .inner_join(local_user::table.on(local_image::local_user_id.eq(local_user::id)))
+      .inner_join(person::table.on(local_user::person_id.eq(person::id)))
+      .select((local_image::all_columns, person::all_columns))
+      .into_boxed();
+
+    if let Some(local_user_id) = self.local_user_id {
+      query = query.filter(local_image::local_user_id.eq(local_user_id));
+    }
+
+    if let Some(person_id) = self.person_id {
+      query = query.filter(person::id.eq(person_id));
+    }
+
+    let (limit, offset) = limit_and_offset(self.page, self.limit)?;
+
+    let res = query
+      .order_by(local_image::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<LocalImageViewTuple>(conn)
+      .await?;
+
+    Ok(res.into_iter().map(LocalImageView::from_tuple).collect())
+  }
+}
+
+impl JoinView for LocalImageView {
+  type JoinTuple = LocalImageViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      local_image: a.0,
+      person: a.1,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::local_image_view::LocalImageQuery;
+  use lemmy_db_schema::{
+    source::{
+      images::{LocalImage, LocalImageForm},
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("uploader".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_person_2 = PersonInsertForm::builder()
+      .name("other_uploader".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person_2 = Person::create(pool, &new_person_2).await.unwrap();
+
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("pass".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+
+    let local_user_form_2 = LocalUserInsertForm::builder()
+      .person_id(inserted_person_2.id)
+      .password_encrypted("pass".to_string())
+      .build();
+    let inserted_local_user_2 = LocalUser::create(pool, &local_user_form_2).await.unwrap();
+
+    let image_form = LocalImageForm {
+      pictrs_alias: "first.png".to_string(),
+      pictrs_delete_token: "token1".to_string(),
+      local_user_id: inserted_local_user.id,
+    };
+    let inserted_image = LocalImage::create(pool, &image_form).await.unwrap();
+
+    let image_form_2 = LocalImageForm {
+      pictrs_alias: "second.png".to_string(),
+      pictrs_delete_token: "token2".to_string(),
+      local_user_id: inserted_local_user_2.id,
+    };
+    LocalImage::create(pool, &image_form_2).await.unwrap();
+
+    let own_images = LocalImageQuery {
+      local_user_id: Some(inserted_local_user.id),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(1, own_images.len());
+    assert_eq!(inserted_image, own_images[0].local_image);
+    assert_eq!(inserted_person.id, own_images[0].person.id);
+
+    let all_images = LocalImageQuery::default().list(pool).await.unwrap();
+    assert_eq!(2, all_images.len());
+
+    let by_person = LocalImageQuery {
+      person_id: Some(inserted_person_2.id),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(1, by_person.len());
+    assert_eq!("second.png", by_person[0].local_image.pictrs_alias);
+
+    let num_deleted = LocalImage::delete_by_alias(pool, "first.png")
+      .await
+      .unwrap();
+    assert_eq!(1, num_deleted);
+    let read_deleted = LocalImage::read_by_alias(pool, "first.png").await;
+    assert!(read_deleted.is_err());
+
+    // Deleting the user removes their remaining images
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Person::delete(pool, inserted_person_2.id).await.unwrap();
+    let left = LocalImage::get_all_by_local_user_id(pool, inserted_local_user_2.id)
+      .await
+      .unwrap();
+    assert!(left.is_empty());
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,204 @@
+use crate::structs::PersonReportView;
+use diesel::{
+  pg::Pg,
+  result::Error,
+  ExpressionMethods,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  aliases,
+  newtypes::PersonReportId,
+  schema::{person, person_report},
+  source::{person::Person, person_report::PersonReport},
+  traits::JoinView,
+  utils::{get_conn, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
+};
+
+type PersonReportViewTuple = (PersonReport, Person, Person, Option<Person>);
+
+fn queries<'a>() -> Queries<
+  impl ReadFn<'a, PersonReportView, PersonReportId>,
+  impl ListFn<'a, PersonReportView, PersonReportQuery>,
+> {
+  let all_joins = |query: person_report::BoxedQuery<'a, Pg>| {
+    query
+      .inner_join(person::table.on(person_report::person_id.eq(person::id)))
+      .inner_join(
+        aliases::person1.on(person_report::creator_id.eq(aliases::person1.field(person::id))),
+      )
+      .left_join(
+        aliases::person2
+          .on(person_report::resolver_id.eq(aliases::person2.field(person::id).nullable())),
+      )
+      .select((
+        person_report::all_columns,
+        person::all_columns,
+        aliases::person1.fields(person::all_columns),
+        aliases::person2.fields(person::all_columns).nullable(),
+      ))
+  };
+
+  let read = move |mut conn: DbConn<'a>, report_id: PersonReportId| async move {
+    all_joins(person_report::table.find(report_id).into_boxed())
+      .first::<PersonReportViewTuple>(&mut conn)
+      .await
+  };
+
+  let list = move |mut conn: DbConn<'a>, options: PersonReportQuery| async move {
+    let mut query = all_joins(person_report::table.into_boxed());
+
+    if options.unresolved_only {
+      query = query.filter(person_report::resolved.eq(false));
+    }
+
+    let (limit, offset) = limit_and_offset(options.page, options.limit)?;
+
+    query
+      .order_by(person_report::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<PersonReportViewTuple>(&mut conn)
+      .await
+  };
+
+  Queries::new(read, list)
+}
+
+impl PersonReportView {
+  pub async fn read(pool: &mut DbPool<'_>, report_id: PersonReportId) -> Result<Self, Error> {
+    queries().read(pool, report_id).await
+  }
+
+  /// The number of unresolved profile reports, which only admins handle.
+  pub async fn get_report_count(pool: &mut DbPool<'_>) -> Result<i64, Error> {
+    use diesel::dsl::count;
+    let conn = &mut get_conn(pool).await?;
+
+    person_report::table
+      .filter(person_report::resolved.eq(false))
+      .select(count(person_report::id))
+      .first::<i64>(conn)
+      .await
+  }
+}
+
+#[derive(Default)]
+pub struct PersonReportQuery {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub unresolved_only: bool,
+}
+
+impl PersonReportQuery {
+  pub async fn list(self, pool: &mut DbPool<'_>) -> Result<Vec<PersonReportView>, Error> {
+    queries().list(pool, self).await
+  }
+}
+
+impl JoinView for PersonReportView {
+  type JoinTuple = PersonReportViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      person_report: a.0,
+      person: a.1,
+      creator: a.2,
+      resolver: a.3,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::{person_report_view::PersonReportQuery, structs::PersonReportView};
+  use lemmy_db_schema::{
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      person_report::{PersonReport, PersonReportForm},
+    },
+    traits::{Crud, Reportable},
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person_1 = PersonInsertForm::builder()
+      .name("timmy_prv".into())
+      .display_name(Some("something offensive".to_string()))
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_timmy = Person::create(pool, &new_person_1).await.unwrap();
+
+    let new_person_2 = PersonInsertForm::builder()
+      .name("jessica_prv".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_jessica = Person::create(pool, &new_person_2).await.unwrap();
+
+    // jessica reports the profile of timmy
+    let report_form = PersonReportForm {
+      creator_id: inserted_jessica.id,
+      person_id: inserted_timmy.id,
+      original_display_name: inserted_timmy.display_name.clone(),
+      original_bio: None,
+      reason: "offensive display name".to_string(),
+    };
+    let report = PersonReport::report(pool, &report_form).await.unwrap();
+
+    let reports = PersonReportQuery {
+      unresolved_only: true,
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(1, reports.len());
+    assert_eq!(inserted_timmy.name, reports[0].person.name);
+    assert_eq!(inserted_jessica.name, reports[0].creator.name);
+    assert_eq!(
+      Some("something offensive".to_string()),
+      reports[0].person_report.original_display_name
+    );
+    assert_eq!(1, PersonReportView::get_report_count(pool).await.unwrap());
+
+    let new_person_3 = PersonInsertForm::builder()
+      .name("admin_prv".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_admin = Person::create(pool, &new_person_3).await.unwrap();
+
+    // admin clears the display name, which resolves all reports of the profile
+    let resolved = PersonReport::resolve_all_for_object(pool, inserted_timmy.id, inserted_admin.id)
+      .await
+      .unwrap();
+    assert_eq!(1, resolved);
+
+    let report_view = PersonReportView::read(pool, report.id).await.unwrap();
+    assert!(report_view.person_report.resolved);
+    assert_eq!(
+      inserted_admin.name,
+      report_view.resolver.as_ref().unwrap().name
+    );
+    assert_eq!(0, PersonReportView::get_report_count(pool).await.unwrap());
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
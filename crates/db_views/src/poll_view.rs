@@ -0,0 +1,161 @@
+use crate::structs::{PollOptionView, PollView};
+use diesel::result::Error;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  source::poll::{Poll, PollOption, PollVote},
+  utils::DbPool,
+};
+use std::collections::HashMap;
+
+impl PollView {
+  /// Reads the options and votes of the poll. If the poll hides its results until voted, the
+  /// counts are left out for users who didn't vote yet. The creator of the post and everyone
+  /// else can see them once the poll has ended.
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    poll: Poll,
+    post_creator_id: PersonId,
+    my_person_id: Option<PersonId>,
+  ) -> Result<Self, Error> {
+    let options = PollOption::list_for_poll(pool, poll.id).await?;
+    let my_votes = match my_person_id {
+      Some(person_id) => PollVote::list_for_person(pool, poll.id, person_id).await?,
+      None => vec![],
+    };
+
+    let results_hidden = poll.hide_results_until_voted
+      && my_votes.is_empty()
+      && !poll.has_ended()
+      && my_person_id != Some(post_creator_id);
+
+    let (voters, counts) = if results_hidden {
+      (None, HashMap::new())
+    } else {
+      let counts = PollVote::count_by_option(pool, poll.id).await?;
+      let voters = PollVote::count_voters(pool, poll.id).await?;
+      (Some(voters), counts.into_iter().collect::<HashMap<_, _>>())
+    };
+
+    let options = options
+      .into_iter()
+      .map(|option| PollOptionView {
+        votes: voters.map(|_| counts.get(&option.id).copied().unwrap_or(0)),
+        option,
+      })
+      .collect();
+
+    Ok(PollView {
+      poll,
+      options,
+      my_votes,
+      voters,
+      results_hidden,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::structs::PollView;
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      poll::{Poll, PollInsertForm, PollVote},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_hide_results_until_voted() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let person_form = |name: &str| {
+      PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build()
+    };
+    let creator = Person::create(pool, &person_form("poll_view_creator"))
+      .await
+      .unwrap();
+    let voter = Person::create(pool, &person_form("poll_view_voter"))
+      .await
+      .unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_poll_view".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A secret poll".into())
+      .creator_id(creator.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let form = PollInsertForm {
+      post_id: inserted_post.id,
+      ends_at: None,
+      allow_multiple: false,
+      hide_results_until_voted: true,
+    };
+    let (poll, options) = Poll::create(pool, &form, vec!["Tabs".into(), "Spaces".into()])
+      .await
+      .unwrap();
+
+    let before_vote = PollView::read(pool, poll.clone(), creator.id, Some(voter.id))
+      .await
+      .unwrap();
+    assert!(before_vote.results_hidden);
+    assert_eq!(None, before_vote.voters);
+    assert_eq!(None, before_vote.options[0].votes);
+
+    // The creator of the post sees the results anyway
+    let as_creator = PollView::read(pool, poll.clone(), creator.id, Some(creator.id))
+      .await
+      .unwrap();
+    assert!(!as_creator.results_hidden);
+    assert_eq!(Some(0), as_creator.options[0].votes);
+
+    PollVote::set(pool, poll.id, voter.id, vec![options[1].id])
+      .await
+      .unwrap();
+    let after_vote = PollView::read(pool, poll.clone(), creator.id, Some(voter.id))
+      .await
+      .unwrap();
+    assert!(!after_vote.results_hidden);
+    assert_eq!(vec![options[1].id], after_vote.my_votes);
+    assert_eq!(Some(1), after_vote.voters);
+    assert_eq!(Some(0), after_vote.options[0].votes);
+    assert_eq!(Some(1), after_vote.options[1].votes);
+
+    let anonymous = PollView::read(pool, poll, creator.id, None).await.unwrap();
+    assert!(anonymous.results_hidden);
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, creator.id).await.unwrap();
+    Person::delete(pool, voter.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
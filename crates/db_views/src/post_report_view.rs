@@ -358,6 +358,12 @@ mod tests {
         shared_inbox_url: inserted_community.shared_inbox_url.clone(),
         moderators_url: inserted_community.moderators_url.clone(),
         featured_url: inserted_community.featured_url.clone(),
+        default_post_sort: None,
+        default_comment_sort: None,
+        min_account_age_days_to_vote: 0,
+        only_followers_can_vote: false,
+        verified: false,
+        commenting_restricted_to_mods: false,
       },
       creator: Person {
         id: inserted_jessica.id,
@@ -1,7 +1,7 @@
 use crate::structs::{LocalUserView, PostView};
 use diesel::{
   debug_query,
-  dsl::{now, IntervalDsl},
+  dsl::{exists, not, now, IntervalDsl},
   pg::Pg,
   result::Error,
   sql_function,
@@ -23,10 +23,13 @@ use lemmy_db_schema::{
     community_follower,
     community_moderator,
     community_person_ban,
+    community_person_flair,
+    local_user,
     local_user_language,
     person,
     person_block,
     person_post_aggregates,
+    poll,
     post,
     post_aggregates,
     post_like,
@@ -35,17 +38,33 @@ use lemmy_db_schema::{
   },
   source::{
     community::{Community, CommunityFollower},
+    community_flair::CommunityPersonFlair,
     person::Person,
+    person_keyword_block::PersonKeywordBlock,
+    poll::Poll,
     post::Post,
   },
   traits::JoinView,
-  utils::{fuzzy_search, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
+  utils::{
+    contains_search,
+    fuzzy_search,
+    limit_and_offset,
+    DbConn,
+    DbPool,
+    ListFn,
+    Queries,
+    ReadFn,
+  },
   ListingType,
   SortType,
   SubscribedType,
 };
+use lemmy_utils::utils::markdown::markdown_excerpt;
 use tracing::debug;
 
+/// Maximum number of characters in `PostView::body_excerpt`
+const BODY_EXCERPT_LENGTH: usize = 300;
+
 type PostViewTuple = (
   Post,
   Person,
@@ -58,6 +77,10 @@ type PostViewTuple = (
   bool,
   Option<i16>,
   i64,
+  Option<chrono::NaiveDateTime>,
+  Option<String>,
+  Option<CommunityPersonFlair>,
+  Option<Poll>,
 );
 
 sql_function!(fn coalesce(x: sql_types::Nullable<sql_types::BigInt>, y: sql_types::BigInt) -> sql_types::BigInt);
@@ -77,7 +100,12 @@ fn queries<'a>() -> Queries<
         community_person_ban::table.on(
           post_aggregates::community_id
             .eq(community_person_ban::community_id)
-            .and(community_person_ban::person_id.eq(post_aggregates::creator_id)),
+            .and(community_person_ban::person_id.eq(post_aggregates::creator_id))
+            .and(
+              community_person_ban::expires
+                .is_null()
+                .or(community_person_ban::expires.gt(now)),
+            ),
         ),
       )
       .inner_join(post::table)
@@ -130,6 +158,14 @@ fn queries<'a>() -> Queries<
             .and(person_post_aggregates::person_id.eq(person_id_join)),
         ),
       )
+      .left_join(
+        community_person_flair::table.on(
+          post_aggregates::community_id
+            .eq(community_person_flair::community_id)
+            .and(community_person_flair::person_id.eq(post_aggregates::creator_id)),
+        ),
+      )
+      .left_join(poll::table.on(poll::post_id.eq(post_aggregates::post_id)))
   };
 
   let selection = (
@@ -147,6 +183,10 @@ fn queries<'a>() -> Queries<
       post_aggregates::comments.nullable() - person_post_aggregates::read_comments.nullable(),
       post_aggregates::comments,
     ),
+    post_saved::remind_at.nullable(),
+    post_saved::tag.nullable(),
+    community_person_flair::all_columns.nullable(),
+    poll::all_columns.nullable(),
   );
 
   let read =
@@ -191,6 +231,8 @@ fn queries<'a>() -> Queries<
     // The left join below will return None in this case
     let person_id_join = person_id.unwrap_or(PersonId(-1));
     let local_user_id_join = local_user_id.unwrap_or(LocalUserId(-1));
+    let creator_community_moderator =
+      diesel::alias!(community_moderator as creator_community_moderator);
 
     let mut query = all_joins(post_aggregates::table.into_boxed(), person_id)
       .left_join(
@@ -207,6 +249,16 @@ fn queries<'a>() -> Queries<
             .and(local_user_language::local_user_id.eq(local_user_id_join)),
         ),
       )
+      .left_join(
+        creator_community_moderator.on(
+          post_aggregates::community_id
+            .eq(creator_community_moderator.field(community_moderator::community_id))
+            .and(
+              post_aggregates::creator_id
+                .eq(creator_community_moderator.field(community_moderator::person_id)),
+            ),
+        ),
+      )
       .select(selection);
 
     let is_creator = options.creator_id == options.local_user.map(|l| l.person.id);
@@ -218,11 +270,17 @@ fn queries<'a>() -> Queries<
     }
 
     let is_admin = options.local_user.map(|l| l.person.admin).unwrap_or(false);
-    // only show removed posts to admin when viewing user profile
+    // only show removed posts, and posts of accounts which are about to be deleted, to admin
+    // when viewing user profile
     if !(options.is_profile_view && is_admin) {
       query = query
         .filter(community::removed.eq(false))
-        .filter(post::removed.eq(false));
+        .filter(post::removed.eq(false))
+        .filter(not(exists(
+          local_user::table
+            .filter(local_user::person_id.eq(post_aggregates::creator_id))
+            .filter(local_user::deletion_scheduled_at.is_not_null()),
+        )));
     }
 
     if options.community_id.is_none() {
@@ -240,18 +298,25 @@ fn queries<'a>() -> Queries<
     if let Some(listing_type) = options.listing_type {
       match listing_type {
         ListingType::Subscribed => query = query.filter(community_follower::pending.is_not_null()),
+        // Posts featured to the local site are always shown on the frontpage
         ListingType::Local => {
-          query = query.filter(community::local.eq(true)).filter(
-            community::hidden
-              .eq(false)
-              .or(community_follower::person_id.eq(person_id_join)),
+          query = query.filter(
+            community::local
+              .eq(true)
+              .and(
+                community::hidden
+                  .eq(false)
+                  .or(community_follower::person_id.eq(person_id_join)),
+              )
+              .or(post_aggregates::featured_local.eq(true)),
           );
         }
         ListingType::All => {
           query = query.filter(
             community::hidden
               .eq(false)
-              .or(community_follower::person_id.eq(person_id_join)),
+              .or(community_follower::person_id.eq(person_id_join))
+              .or(post_aggregates::featured_local.eq(true)),
           )
         }
       }
@@ -290,6 +355,37 @@ fn queries<'a>() -> Queries<
 
     if options.saved_only {
       query = query.filter(post_saved::id.is_not_null());
+      if let Some(saved_tag) = options.saved_tag {
+        query = query.filter(post_saved::tag.eq(saved_tag));
+      }
+    }
+
+    // Hide posts containing any of the user's blocked keywords
+    if let Some(person_id) = person_id {
+      for keyword in PersonKeywordBlock::read(&mut (&mut conn).into(), person_id).await? {
+        let pattern = contains_search(&keyword);
+        query = query.filter(post::name.not_ilike(pattern.clone())).filter(
+          post::body
+            .is_null()
+            .or(post::body.not_ilike(pattern).assume_not_null()),
+        );
+      }
+    }
+
+    // Hide posts below the user's score threshold, except their own, and those in communities
+    // they moderate
+    if let Some(min_score) = options
+      .local_user
+      .and_then(|l| l.local_user.hide_content_below_score)
+    {
+      if !(options.ignore_score_filter || options.moderator_view || is_admin) {
+        query = query.filter(
+          post_aggregates::score
+            .ge(i64::from(min_score))
+            .or(post_aggregates::creator_id.eq(person_id_join))
+            .or(community_moderator::person_id.is_not_null()),
+        );
+      }
     }
 
     if options.moderator_view {
@@ -318,10 +414,20 @@ fn queries<'a>() -> Queries<
       // Filter out the rows with missing languages
       query = query.filter(local_user_language::language_id.is_not_null());
 
-      // Don't show blocked communities or persons
+      // Don't show blocked communities or persons. Announcements which a blocked person
+      // featured in a community they moderate are still shown.
       query = query.filter(community_block::person_id.is_null());
       if !options.moderator_view {
-        query = query.filter(person_block::person_id.is_null());
+        query = query.filter(
+          person_block::person_id.is_null().or(
+            post_aggregates::featured_community.eq(true).and(
+              creator_community_moderator
+                .field(community_moderator::id)
+                .nullable()
+                .is_not_null(),
+            ),
+          ),
+        );
       }
     }
 
@@ -427,35 +533,60 @@ pub struct PostQuery<'a> {
   pub search_term: Option<String>,
   pub url_search: Option<String>,
   pub saved_only: bool,
+  /// Only the saved posts with this tag, if `saved_only` is set
+  pub saved_tag: Option<String>,
   pub liked_only: bool,
   pub disliked_only: bool,
   pub moderator_view: bool,
+  pub ignore_score_filter: bool,
   pub is_profile_view: bool,
   pub page: Option<i64>,
   pub limit: Option<i64>,
+  /// Include the full post body, rather than only the `body_excerpt`
+  pub full_body: bool,
 }
 
 impl<'a> PostQuery<'a> {
   pub async fn list(self, pool: &mut DbPool<'_>) -> Result<Vec<PostView>, Error> {
-    queries().list(pool, self).await
+    let full_body = self.full_body;
+    let mut posts = queries().list(pool, self).await?;
+    if !full_body {
+      for p in &mut posts {
+        p.post.body = None;
+      }
+    }
+    Ok(posts)
   }
 }
 
 impl JoinView for PostView {
   type JoinTuple = PostViewTuple;
   fn from_tuple(a: Self::JoinTuple) -> Self {
+    let body_excerpt = a
+      .0
+      .body
+      .as_deref()
+      .map(|b| markdown_excerpt(b, BODY_EXCERPT_LENGTH))
+      .filter(|e| !e.is_empty());
+    let collapsed = a.0.content_warning.is_some();
     Self {
       post: a.0,
       creator: a.1,
       community: a.2,
       creator_banned_from_community: a.3,
+      creator_flair: a.13,
       counts: a.4,
       subscribed: a.5,
       saved: a.6,
+      saved_remind_at: a.11,
+      saved_tag: a.12,
       read: a.7,
       creator_blocked: a.8,
       my_vote: a.9,
       unread_comments: a.10,
+      body_excerpt,
+      collapsed,
+      poll: a.14,
     }
   }
 }
@@ -469,23 +600,34 @@ mod tests {
     post_view::{PostQuery, PostView},
     structs::LocalUserView,
   };
+  use chrono::Duration;
   use lemmy_db_schema::{
-    aggregates::structs::PostAggregates,
+    aggregates::structs::{PersonPostAggregates, PersonPostAggregatesForm, PostAggregates},
     impls::actor_language::UNDETERMINED_ID,
     newtypes::LanguageId,
     source::{
       actor_language::LocalUserLanguage,
-      community::{Community, CommunityInsertForm},
+      comment::{Comment, CommentInsertForm},
+      community::{
+        Community,
+        CommunityInsertForm,
+        CommunityModerator,
+        CommunityModeratorForm,
+        CommunityPersonBan,
+        CommunityPersonBanForm,
+      },
       community_block::{CommunityBlock, CommunityBlockForm},
       instance::Instance,
       language::Language,
       local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
       person::{Person, PersonInsertForm},
       person_block::{PersonBlock, PersonBlockForm},
+      person_keyword_block::PersonKeywordBlock,
       post::{Post, PostInsertForm, PostLike, PostLikeForm, PostUpdateForm},
     },
-    traits::{Blockable, Crud, Likeable},
-    utils::{build_db_pool_for_tests, DbPool},
+    traits::{Bannable, Blockable, Crud, Joinable, Likeable},
+    utils::{build_db_pool_for_tests, naive_now, DbPool},
+    ListingType,
     SortType,
     SubscribedType,
   };
@@ -740,6 +882,178 @@ mod tests {
     cleanup(data, pool).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_unread_comments() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let comment_form = CommentInsertForm::builder()
+      .content("A test comment".to_string())
+      .creator_id(data.inserted_bot.id)
+      .post_id(data.inserted_post.id)
+      .build();
+    Comment::create(pool, &comment_form, None).await.unwrap();
+    Comment::create(pool, &comment_form, None).await.unwrap();
+
+    let unread_comments = |post_listings: Vec<PostView>| {
+      post_listings
+        .into_iter()
+        .find(|p| p.post.id == data.inserted_post.id)
+        .map(|p| p.unread_comments)
+    };
+    let query = || PostQuery {
+      sort: Some(SortType::New),
+      local_user: Some(&data.local_user_view),
+      ..Default::default()
+    };
+
+    // All comments are new if the post was never visited
+    let post_listings = query().list(pool).await.unwrap();
+    assert_eq!(Some(2), unread_comments(post_listings));
+
+    // Visit the post, then another comment is added
+    let person_post_agg_form = PersonPostAggregatesForm {
+      person_id: data.local_user_view.person.id,
+      post_id: data.inserted_post.id,
+      read_comments: 2,
+      ..Default::default()
+    };
+    PersonPostAggregates::upsert(pool, &person_post_agg_form)
+      .await
+      .unwrap();
+    Comment::create(pool, &comment_form, None).await.unwrap();
+
+    let post_listings = query().list(pool).await.unwrap();
+    assert_eq!(Some(1), unread_comments(post_listings));
+
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_body_excerpt() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let body = format!("# Heading\n\n{}", "**Ünïcödé** wörds ".repeat(50));
+    Post::update(
+      pool,
+      data.inserted_post.id,
+      &PostUpdateForm {
+        body: Some(Some(body.clone())),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap();
+
+    let find_post = |post_listings: Vec<PostView>| {
+      post_listings
+        .into_iter()
+        .find(|p| p.post.id == data.inserted_post.id)
+        .unwrap()
+    };
+    let query = |full_body| PostQuery {
+      sort: Some(SortType::New),
+      local_user: Some(&data.local_user_view),
+      full_body,
+      ..Default::default()
+    };
+
+    // Listings only contain the excerpt by default
+    let post_listing = find_post(query(false).list(pool).await.unwrap());
+    assert_eq!(None, post_listing.post.body);
+    let excerpt = post_listing.body_excerpt.unwrap();
+    assert!(excerpt.starts_with("Heading Ünïcödé wörds"));
+    assert!(excerpt.ends_with("wörds Ünïcödé…"));
+    assert!(excerpt.chars().count() <= 301);
+
+    let post_listing = find_post(query(true).list(pool).await.unwrap());
+    assert_eq!(Some(body.clone()), post_listing.post.body);
+
+    // Reading a single post always includes the full body
+    let post_view = PostView::read(pool, data.inserted_post.id, None, false)
+      .await
+      .unwrap();
+    assert_eq!(Some(body.clone()), post_view.post.body);
+    assert!(post_view.body_excerpt.is_some());
+
+    // On a listing of posts with long bodies, the excerpts make the response much smaller
+    let mut seeded_posts = vec![];
+    for i in 0..20 {
+      let form = PostInsertForm::builder()
+        .name(format!("Long post {i}"))
+        .body(Some(body.repeat(10)))
+        .creator_id(data.local_user_view.person.id)
+        .community_id(data.inserted_community.id)
+        .build();
+      seeded_posts.push(Post::create(pool, &form).await.unwrap());
+    }
+    let mut sizes = vec![];
+    for full_body in [false, true] {
+      let posts = query(full_body).list(pool).await.unwrap();
+      sizes.push(serde_json::to_string(&posts).unwrap().len());
+    }
+    let (excerpt_size, full_size) = (sizes[0], sizes[1]);
+    assert!(
+      excerpt_size * 3 < full_size,
+      "{excerpt_size} bytes with excerpts, {full_size} bytes with full bodies"
+    );
+
+    for post in seeded_posts {
+      Post::delete(pool, post.id).await.unwrap();
+    }
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_keyword_block() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let query = || PostQuery {
+      sort: Some(SortType::New),
+      local_user: Some(&data.local_user_view),
+      ..Default::default()
+    };
+    let has_post = |post_listings: Vec<PostView>| {
+      post_listings
+        .iter()
+        .any(|p| p.post.id == data.inserted_post.id)
+    };
+    assert!(has_post(query().list(pool).await.unwrap()));
+
+    // Matches the post title, case-insensitively
+    PersonKeywordBlock::update(
+      pool,
+      data.local_user_view.person.id,
+      vec!["Post 3".to_string()],
+    )
+    .await
+    .unwrap();
+    assert!(!has_post(query().list(pool).await.unwrap()));
+
+    // Reading the post directly still works
+    let post_view = PostView::read(
+      pool,
+      data.inserted_post.id,
+      Some(data.local_user_view.person.id),
+      false,
+    )
+    .await;
+    assert!(post_view.is_ok());
+
+    PersonKeywordBlock::update(pool, data.local_user_view.person.id, vec![])
+      .await
+      .unwrap();
+    cleanup(data, pool).await;
+  }
+
   #[tokio::test]
   #[serial]
   async fn post_listing_like() {
@@ -1011,6 +1325,200 @@ mod tests {
     cleanup(data, pool).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listings_featured_local() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    // Feature the newest post, from a remote community, to the local site
+    let remote_community_form = CommunityInsertForm::builder()
+      .name("test_community_remote".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(data.inserted_instance.id)
+      .local(Some(false))
+      .build();
+    let remote_community = Community::create(pool, &remote_community_form)
+      .await
+      .unwrap();
+    let featured_post_form = PostInsertForm::builder()
+      .name("featured post".to_string())
+      .creator_id(data.local_user_view.person.id)
+      .community_id(remote_community.id)
+      .build();
+    let featured_post = Post::create(pool, &featured_post_form).await.unwrap();
+    Post::update(
+      pool,
+      featured_post.id,
+      &PostUpdateForm {
+        featured_local: Some(true),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap();
+
+    for listing_type in [ListingType::Local, ListingType::All] {
+      let post_listings = PostQuery {
+        sort: Some(SortType::Old),
+        listing_type: Some(listing_type),
+        ..Default::default()
+      }
+      .list(pool)
+      .await
+      .unwrap();
+      assert_eq!(featured_post.id, post_listings[0].post.id);
+    }
+
+    Post::delete(pool, featured_post.id).await.unwrap();
+    Community::delete(pool, remote_community.id).await.unwrap();
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listings_hide_content_below_score() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let mut data = init_data(pool).await;
+
+    // Downvote the bot post, and the user's own post
+    let post_listings_before = PostQuery {
+      sort: Some(SortType::New),
+      local_user: Some(&data.local_user_view),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(2, post_listings_before.len());
+    for post in post_listings_before {
+      let post_like_form = PostLikeForm {
+        post_id: post.post.id,
+        person_id: data.inserted_blocked_person.id,
+        score: -1,
+      };
+      PostLike::like(pool, &post_like_form).await.unwrap();
+    }
+
+    // Only the user's own post is shown
+    data.local_user_view.local_user.hide_content_below_score = Some(0);
+    let post_listings = PostQuery {
+      sort: Some(SortType::New),
+      local_user: Some(&data.local_user_view),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(1, post_listings.len());
+    assert_eq!(data.inserted_post.id, post_listings[0].post.id);
+
+    let post_listings_ignore_score = PostQuery {
+      sort: Some(SortType::New),
+      local_user: Some(&data.local_user_view),
+      ignore_score_filter: true,
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(2, post_listings_ignore_score.len());
+
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listings_blocked_person_announcement() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    // The blocked person features a post in the community
+    let announcement_form = PostInsertForm::builder()
+      .name("announcement".to_string())
+      .creator_id(data.inserted_blocked_person.id)
+      .community_id(data.inserted_community.id)
+      .language_id(Some(LanguageId(1)))
+      .build();
+    let announcement = Post::create(pool, &announcement_form).await.unwrap();
+    Post::update(
+      pool,
+      announcement.id,
+      &PostUpdateForm {
+        featured_community: Some(true),
+        ..Default::default()
+      },
+    )
+    .await
+    .unwrap();
+
+    let query = || PostQuery {
+      sort: Some(SortType::New),
+      community_id: Some(data.inserted_community.id),
+      local_user: Some(&data.local_user_view),
+      ..Default::default()
+    };
+
+    // Not shown, because the blocked person isn't a mod of the community
+    let post_listings = query().list(pool).await.unwrap();
+    assert!(!post_listings.iter().any(|p| p.post.id == announcement.id));
+
+    let moderator_form = CommunityModeratorForm {
+      community_id: data.inserted_community.id,
+      person_id: data.inserted_blocked_person.id,
+    };
+    CommunityModerator::join(pool, &moderator_form)
+      .await
+      .unwrap();
+
+    // Featured posts of mods are shown, other posts of the blocked person are still hidden
+    let post_listings = query().list(pool).await.unwrap();
+    assert_eq!(3, post_listings.len());
+    assert_eq!(announcement.id, post_listings[0].post.id);
+    assert!(post_listings[0].creator_blocked);
+
+    Post::delete(pool, announcement.id).await.unwrap();
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listings_expired_community_ban() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    // A ban which is over doesn't count anymore, even before it is deleted
+    let ban_form = |expires| CommunityPersonBanForm {
+      community_id: data.inserted_community.id,
+      person_id: data.local_user_view.person.id,
+      expires: Some(Some(expires)),
+    };
+    CommunityPersonBan::ban(pool, &ban_form(naive_now() + Duration::hours(1)))
+      .await
+      .unwrap();
+    let post_view = PostView::read(pool, data.inserted_post.id, None, false)
+      .await
+      .unwrap();
+    assert!(post_view.creator_banned_from_community);
+    CommunityPersonBan::ban(pool, &ban_form(naive_now() - Duration::hours(1)))
+      .await
+      .unwrap();
+    let post_view = PostView::read(pool, data.inserted_post.id, None, false)
+      .await
+      .unwrap();
+    assert!(!post_view.creator_banned_from_community);
+
+    CommunityPersonBan::unban(pool, &ban_form(naive_now()))
+      .await
+      .unwrap();
+    cleanup(data, pool).await;
+  }
+
   async fn cleanup(data: Data, pool: &mut DbPool<'_>) {
     let num_deleted = Post::delete(pool, data.inserted_post.id).await.unwrap();
     Community::delete(pool, data.inserted_community.id)
@@ -1058,11 +1566,17 @@ mod tests {
         ap_id: inserted_post.ap_id.clone(),
         local: true,
         language_id: LanguageId(47),
+        content_warning: None,
+        embed_provider: None,
         featured_community: false,
         featured_local: false,
+        archive_url: None,
       },
       my_vote: None,
       unread_comments: 0,
+      body_excerpt: None,
+      collapsed: false,
+      poll: None,
       creator: Person {
         id: inserted_person.id,
         name: inserted_person.name.clone(),
@@ -1088,6 +1602,7 @@ mod tests {
         last_refreshed_at: inserted_person.last_refreshed_at,
       },
       creator_banned_from_community: false,
+      creator_flair: None,
       community: Community {
         id: inserted_community.id,
         name: inserted_community.name.clone(),
@@ -1113,6 +1628,12 @@ mod tests {
         shared_inbox_url: inserted_community.shared_inbox_url.clone(),
         moderators_url: inserted_community.moderators_url.clone(),
         featured_url: inserted_community.featured_url.clone(),
+        default_post_sort: None,
+        default_comment_sort: None,
+        min_account_age_days_to_vote: 0,
+        only_followers_can_vote: false,
+        verified: false,
+        commenting_restricted_to_mods: false,
       },
       counts: PostAggregates {
         id: agg.id,
@@ -1135,6 +1656,8 @@ mod tests {
       subscribed: SubscribedType::NotSubscribed,
       read: false,
       saved: false,
+      saved_remind_at: None,
+      saved_tag: None,
       creator_blocked: false,
     }
   }
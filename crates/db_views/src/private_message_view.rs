@@ -1,12 +1,15 @@
-use crate::structs::PrivateMessageView;
+use crate::structs::{PrivateMessageConversationView, PrivateMessageView};
 use diesel::{
   debug_query,
   pg::Pg,
   result::Error,
+  sql_query,
+  sql_types::{BigInt, Integer},
   BoolExpressionMethods,
   ExpressionMethods,
   JoinOnDsl,
   QueryDsl,
+  QueryableByName,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -17,6 +20,7 @@ use lemmy_db_schema::{
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
 };
+use std::collections::HashMap;
 use tracing::debug;
 
 type PrivateMessageViewTuple = (PrivateMessage, Person, Person);
@@ -94,6 +98,60 @@ fn queries<'a>() -> Queries<
   Queries::new(read, list)
 }
 
+/// The latest message with each correspondent, newest conversations first. The window function
+/// runs before `DISTINCT ON`, so it counts the unread messages of the whole conversation.
+const CONVERSATIONS_QUERY: &str = "
+  SELECT private_message_id, unread_count FROM (
+    SELECT DISTINCT ON (correspondent_id)
+      id AS private_message_id,
+      published,
+      count(*) FILTER (WHERE recipient_id = $1 AND NOT read)
+        OVER (PARTITION BY correspondent_id) AS unread_count
+    FROM (
+      SELECT *, CASE WHEN creator_id = $1 THEN recipient_id ELSE creator_id END AS correspondent_id
+      FROM private_message
+      WHERE (creator_id = $1 OR recipient_id = $1) AND NOT deleted
+    ) AS message
+    ORDER BY correspondent_id, published DESC
+  ) AS latest
+  ORDER BY published DESC
+  LIMIT $2 OFFSET $3";
+
+#[derive(QueryableByName)]
+struct LatestMessage {
+  #[diesel(sql_type = Integer)]
+  private_message_id: PrivateMessageId,
+  #[diesel(sql_type = BigInt)]
+  unread_count: i64,
+}
+
+/// Reads the given messages together with their creators and recipients.
+async fn read_many(
+  pool: &mut DbPool<'_>,
+  private_message_ids: Vec<PrivateMessageId>,
+) -> Result<HashMap<PrivateMessageId, PrivateMessageView>, Error> {
+  let conn = &mut get_conn(pool).await?;
+  let messages = private_message::table
+    .inner_join(person::table.on(private_message::creator_id.eq(person::id)))
+    .inner_join(
+      aliases::person1.on(private_message::recipient_id.eq(aliases::person1.field(person::id))),
+    )
+    .filter(private_message::id.eq_any(private_message_ids))
+    .select((
+      private_message::all_columns,
+      person::all_columns,
+      aliases::person1.fields(person::all_columns),
+    ))
+    .load::<PrivateMessageViewTuple>(conn)
+    .await?;
+  Ok(
+    messages
+      .into_iter()
+      .map(|tuple| (tuple.0.id, PrivateMessageView::from_tuple(tuple)))
+      .collect(),
+  )
+}
+
 impl PrivateMessageView {
   pub async fn read(
     pool: &mut DbPool<'_>,
@@ -117,6 +175,91 @@ impl PrivateMessageView {
       .first::<i64>(conn)
       .await
   }
+
+  /// The messages between two persons. The first page has the newest messages, but each page is
+  /// in chronological order.
+  pub async fn list_with(
+    pool: &mut DbPool<'_>,
+    my_person_id: PersonId,
+    person_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let conn = &mut get_conn(pool).await?;
+    let mut messages = private_message::table
+      .inner_join(person::table.on(private_message::creator_id.eq(person::id)))
+      .inner_join(
+        aliases::person1.on(private_message::recipient_id.eq(aliases::person1.field(person::id))),
+      )
+      .filter(
+        private_message::creator_id
+          .eq(my_person_id)
+          .and(private_message::recipient_id.eq(person_id))
+          .or(
+            private_message::creator_id
+              .eq(person_id)
+              .and(private_message::recipient_id.eq(my_person_id)),
+          ),
+      )
+      .filter(private_message::deleted.eq(false))
+      .order_by(private_message::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .select((
+        private_message::all_columns,
+        person::all_columns,
+        aliases::person1.fields(person::all_columns),
+      ))
+      .load::<PrivateMessageViewTuple>(conn)
+      .await?
+      .into_iter()
+      .map(Self::from_tuple)
+      .collect::<Vec<_>>();
+    messages.reverse();
+    Ok(messages)
+  }
+}
+
+impl PrivateMessageConversationView {
+  /// The conversations of the person, with the most recently active first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    my_person_id: PersonId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let latest = {
+      let conn = &mut get_conn(pool).await?;
+      sql_query(CONVERSATIONS_QUERY)
+        .bind::<Integer, _>(my_person_id)
+        .bind::<BigInt, _>(limit)
+        .bind::<BigInt, _>(offset)
+        .load::<LatestMessage>(conn)
+        .await?
+    };
+    let ids = latest.iter().map(|l| l.private_message_id).collect();
+    let mut messages = read_many(pool, ids).await?;
+    Ok(
+      latest
+        .into_iter()
+        .filter_map(|l| {
+          let message = messages.remove(&l.private_message_id)?;
+          let correspondent = if message.creator.id == my_person_id {
+            message.recipient
+          } else {
+            message.creator
+          };
+          Some(PrivateMessageConversationView {
+            correspondent,
+            latest_message: message.private_message,
+            unread_count: l.unread_count,
+          })
+        })
+        .collect(),
+    )
+  }
 }
 
 #[derive(Default)]
@@ -153,7 +296,10 @@ mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
-  use crate::private_message_view::PrivateMessageQuery;
+  use crate::{
+    private_message_view::PrivateMessageQuery,
+    structs::{PrivateMessageConversationView, PrivateMessageView},
+  };
   use lemmy_db_schema::{
     source::{
       instance::Instance,
@@ -296,5 +442,25 @@ mod tests {
     assert_eq!(timmy_sara_unread_messages.len(), 1);
     assert_eq!(timmy_sara_unread_messages[0].creator.id, sara.id);
     assert_eq!(timmy_sara_unread_messages[0].recipient.id, timmy.id);
+
+    let timmy_conversations = PrivateMessageConversationView::list(pool, timmy.id, None, None)
+      .await
+      .unwrap();
+
+    assert_eq!(timmy_conversations.len(), 2);
+    assert_eq!(timmy_conversations[0].correspondent.id, jess.id);
+    assert_eq!(timmy_conversations[0].latest_message.creator_id, jess.id);
+    assert_eq!(timmy_conversations[0].unread_count, 1);
+    assert_eq!(timmy_conversations[1].correspondent.id, sara.id);
+    assert_eq!(timmy_conversations[1].latest_message.creator_id, timmy.id);
+    assert_eq!(timmy_conversations[1].unread_count, 1);
+
+    let timmy_with_sara = PrivateMessageView::list_with(pool, timmy.id, sara.id, None, None)
+      .await
+      .unwrap();
+
+    assert_eq!(timmy_with_sara.len(), 2);
+    assert_eq!(timmy_with_sara[0].creator.id, sara.id);
+    assert_eq!(timmy_with_sara[1].creator.id, timmy.id);
   }
 }
@@ -1,4 +1,5 @@
 use crate::structs::RegistrationApplicationView;
+use chrono::Duration;
 use diesel::{
   dsl::count,
   pg::Pg,
@@ -18,7 +19,17 @@ use lemmy_db_schema::{
     registration_application::RegistrationApplication,
   },
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
+  utils::{
+    functions::lower,
+    get_conn,
+    limit_and_offset,
+    naive_now,
+    DbConn,
+    DbPool,
+    ListFn,
+    Queries,
+    ReadFn,
+  },
 };
 
 type RegistrationApplicationViewTuple =
@@ -58,15 +69,32 @@ fn queries<'a>() -> Queries<
   let list = move |mut conn: DbConn<'a>, options: RegistrationApplicationQuery| async move {
     let mut query = all_joins(registration_application::table.into_boxed());
 
+    // Applications which were denied automatically have no admin either
     if options.unread_only {
-      query = query.filter(registration_application::admin_id.is_null())
+      query = query
+        .filter(registration_application::admin_id.is_null())
+        .filter(registration_application::deny_reason.is_null())
+    }
+
+    if let Some(older_than_days) = options.older_than_days {
+      query = query.filter(
+        registration_application::published
+          .lt(naive_now() - Duration::days(older_than_days.into())),
+      )
     }
 
     if options.verified_email_only {
       query = query.filter(local_user::email_verified.eq(true))
     }
 
-    let (limit, offset) = limit_and_offset(options.page, options.limit)?;
+    // All of the given applications, which are limited by the caller
+    let (limit, offset) = if let Some(ids) = options.ids {
+      let limit = ids.len() as i64;
+      query = query.filter(registration_application::id.eq_any(ids));
+      (limit, 0)
+    } else {
+      limit_and_offset(options.page, options.limit)?
+    };
 
     query = query
       .limit(limit)
@@ -86,7 +114,37 @@ impl RegistrationApplicationView {
     pool: &mut DbPool<'_>,
     registration_application_id: i32,
   ) -> Result<Self, Error> {
-    queries().read(pool, registration_application_id).await
+    let mut view = queries().read(pool, registration_application_id).await?;
+    Self::fill_similar_persons(pool, std::slice::from_mut(&mut view)).await?;
+    Ok(view)
+  }
+
+  /// Finds the remote persons with the same name as the applicants, case-insensitively. Uses a
+  /// single query for all the applications.
+  async fn fill_similar_persons(pool: &mut DbPool<'_>, views: &mut [Self]) -> Result<(), Error> {
+    if views.is_empty() {
+      return Ok(());
+    }
+    let conn = &mut get_conn(pool).await?;
+    let names: Vec<_> = views
+      .iter()
+      .map(|v| v.creator.name.to_lowercase())
+      .collect();
+    let similar_persons = person::table
+      .filter(person::local.eq(false))
+      .filter(lower(person::name).eq_any(names))
+      .order_by(person::id)
+      .load::<Person>(conn)
+      .await?;
+    for view in views {
+      let name = view.creator.name.to_lowercase();
+      view.similar_persons = similar_persons
+        .iter()
+        .filter(|p| p.name.to_lowercase() == name)
+        .cloned()
+        .collect();
+    }
+    Ok(())
   }
 
   /// Returns the current unread registration_application count
@@ -105,6 +163,7 @@ impl RegistrationApplicationView {
           .on(registration_application::admin_id.eq(person_alias_1.field(person::id).nullable())),
       )
       .filter(registration_application::admin_id.is_null())
+      .filter(registration_application::deny_reason.is_null())
       .into_boxed();
 
     if verified_email_only {
@@ -122,6 +181,9 @@ impl RegistrationApplicationView {
 pub struct RegistrationApplicationQuery {
   pub unread_only: bool,
   pub verified_email_only: bool,
+  pub older_than_days: Option<i32>,
+  /// Only these applications, ignoring `page` and `limit`
+  pub ids: Option<Vec<i32>>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
 }
@@ -131,18 +193,28 @@ impl RegistrationApplicationQuery {
     self,
     pool: &mut DbPool<'_>,
   ) -> Result<Vec<RegistrationApplicationView>, Error> {
-    queries().list(pool, self).await
+    let mut views = queries().list(pool, self).await?;
+    RegistrationApplicationView::fill_similar_persons(pool, &mut views).await?;
+    Ok(views)
   }
 }
 
 impl JoinView for RegistrationApplicationView {
   type JoinTuple = RegistrationApplicationViewTuple;
   fn from_tuple(a: Self::JoinTuple) -> Self {
+    let email_domain = a
+      .1
+      .email
+      .as_ref()
+      .and_then(|e| e.rsplit_once('@'))
+      .map(|(_, domain)| domain.to_lowercase());
     Self {
       registration_application: a.0,
       creator_local_user: a.1,
       creator: a.2,
       admin: a.3,
+      email_domain,
+      similar_persons: vec![],
     }
   }
 }
@@ -169,6 +241,7 @@ mod tests {
     },
     traits::Crud,
     utils::build_db_pool_for_tests,
+    NotificationDigest,
   };
   use serial_test::serial;
 
@@ -289,6 +362,13 @@ mod tests {
         password_encrypted: inserted_sara_local_user.password_encrypted,
         open_links_in_new_tab: inserted_sara_local_user.open_links_in_new_tab,
         infinite_scroll_enabled: inserted_sara_local_user.infinite_scroll_enabled,
+        hide_content_below_score: None,
+        send_notification_digest: NotificationDigest::Never,
+        last_digest_sent_at: None,
+        notify_new_logins: false,
+        exclude_from_leaderboards: false,
+        deletion_scheduled_at: None,
+        notification_muted_words: vec![],
       },
       creator: Person {
         id: inserted_sara_person.id,
@@ -315,6 +395,8 @@ mod tests {
         last_refreshed_at: inserted_sara_person.last_refreshed_at,
       },
       admin: None,
+      email_domain: None,
+      similar_persons: vec![],
     };
 
     assert_eq!(read_sara_app_view, expected_sara_app_view);
@@ -419,6 +501,73 @@ mod tests {
       .unwrap();
     assert_eq!(all_apps.len(), 2);
 
+    // None of the applications are old enough to expire
+    let old_apps = RegistrationApplicationQuery {
+      older_than_days: Some(1),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert!(old_apps.is_empty());
+
+    // Deny jessicas application automatically, it isn't unread anymore. Saras application was
+    // approved already, so it stays approved.
+    let denied =
+      RegistrationApplication::deny_many(pool, &[jess_app.id, sara_app.id], None, "Expired")
+        .await
+        .unwrap();
+    assert_eq!(1, denied.len());
+    assert_eq!(jess_app.id, denied[0].id);
+    assert_eq!(Some("Expired".to_string()), denied[0].deny_reason);
+    let sara_local_user = LocalUser::read(pool, inserted_sara_local_user.id)
+      .await
+      .unwrap();
+    assert!(sara_local_user.accepted_application);
+    let denied_apps = RegistrationApplicationQuery {
+      ids: Some(vec![jess_app.id]),
+      ..Default::default()
+    }
+    .list(pool)
+    .await
+    .unwrap();
+    assert_eq!(1, denied_apps.len());
+    assert_eq!(
+      Some("Expired".to_string()),
+      denied_apps[0].registration_application.deny_reason
+    );
+    let unread_count_after_deny = RegistrationApplicationView::get_unread_count(pool, false)
+      .await
+      .unwrap();
+    assert_eq!(unread_count_after_deny, 0);
+
+    // A remote person with the same name as Sara shows up in Sara's application
+    let remote_instance = Instance::read_or_create(pool, "other_domain.tld".to_string())
+      .await
+      .unwrap();
+    let remote_sara_form = PersonInsertForm::builder()
+      .name("Sara_Rav".into())
+      .public_key("pubkey".to_string())
+      .local(Some(false))
+      .instance_id(remote_instance.id)
+      .build();
+    let remote_sara = Person::create(pool, &remote_sara_form).await.unwrap();
+    let apps = RegistrationApplicationQuery::default()
+      .list(pool)
+      .await
+      .unwrap();
+    let similar_ids = |app_id| {
+      apps
+        .iter()
+        .find(|a| a.registration_application.id == app_id)
+        .map(|a| a.similar_persons.iter().map(|p| p.id).collect::<Vec<_>>())
+        .unwrap()
+    };
+    assert_eq!(vec![remote_sara.id], similar_ids(sara_app.id));
+    assert!(similar_ids(jess_app.id).is_empty());
+
+    Person::delete(pool, remote_sara.id).await.unwrap();
+    Instance::delete(pool, remote_instance.id).await.unwrap();
     Person::delete(pool, inserted_timmy_person.id)
       .await
       .unwrap();
@@ -1,15 +1,20 @@
 use lemmy_db_schema::{
   aggregates::structs::{CommentAggregates, PersonAggregates, PostAggregates, SiteAggregates},
+  newtypes::PollOptionId,
   source::{
     comment::Comment,
     comment_report::CommentReport,
     community::Community,
+    community_flair::CommunityPersonFlair,
     custom_emoji::CustomEmoji,
     custom_emoji_keyword::CustomEmojiKeyword,
+    images::LocalImage,
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
     local_user::LocalUser,
     person::Person,
+    person_report::PersonReport,
+    poll::{Poll, PollOption},
     post::Post,
     post_report::PostReport,
     private_message::PrivateMessage,
@@ -40,6 +45,10 @@ pub struct CommentReportView {
   pub creator_banned_from_community: bool,
   pub my_vote: Option<i16>,
   pub resolver: Option<Person>,
+  /// The comment which the reported comment replies to. Its content is replaced if it was deleted
+  /// or removed.
+  pub parent_comment: Option<Comment>,
+  pub parent_comment_creator: Option<Person>,
 }
 
 #[skip_serializing_none]
@@ -54,10 +63,32 @@ pub struct CommentView {
   pub community: Community,
   pub counts: CommentAggregates,
   pub creator_banned_from_community: bool,
+  /// The title of the creator in the community of the comment.
+  pub creator_flair: Option<CommunityPersonFlair>,
   pub subscribed: SubscribedType,
   pub saved: bool,
+  /// When the user wants to be reminded of the saved comment.
+  pub saved_remind_at: Option<chrono::NaiveDateTime>,
+  /// The folder which the user saved the comment into.
+  pub saved_tag: Option<String>,
   pub creator_blocked: bool,
   pub my_vote: Option<i16>,
+  /// The comment is below the user's score threshold, and its content was removed.
+  pub hidden_by_score: bool,
+  /// The comment is NSFW, and its content was removed because the user doesn't want to see NSFW
+  /// content.
+  pub hidden_as_nsfw: bool,
+  /// The comment has a content warning, and should be collapsed until it is expanded.
+  pub collapsed: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A local image view.
+pub struct LocalImageView {
+  pub local_image: LocalImage,
+  pub person: Person,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -97,13 +128,52 @@ pub struct PostView {
   pub creator: Person,
   pub community: Community,
   pub creator_banned_from_community: bool,
+  /// The title of the creator in the community of the post.
+  pub creator_flair: Option<CommunityPersonFlair>,
   pub counts: PostAggregates,
   pub subscribed: SubscribedType,
   pub saved: bool,
+  /// When the user wants to be reminded of the saved post.
+  pub saved_remind_at: Option<chrono::NaiveDateTime>,
+  /// The folder which the user saved the post into.
+  pub saved_tag: Option<String>,
   pub read: bool,
   pub creator_blocked: bool,
   pub my_vote: Option<i16>,
   pub unread_comments: i64,
+  /// The start of the post body as plain text, for use in post listings.
+  pub body_excerpt: Option<String>,
+  /// The post has a content warning, and should be collapsed until it is expanded.
+  pub collapsed: bool,
+  /// The poll of the post. Its options and votes are only included in `GetPostResponse`.
+  pub poll: Option<Poll>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll with its options and the votes of the user.
+pub struct PollView {
+  pub poll: Poll,
+  pub options: Vec<PollOptionView>,
+  /// The options which the user voted for.
+  pub my_votes: Vec<PollOptionId>,
+  /// The number of people who voted. None while the results are hidden.
+  pub voters: Option<i64>,
+  /// The user has to vote before seeing the results.
+  pub results_hidden: bool,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll option with its number of votes.
+pub struct PollOptionView {
+  pub option: PollOption,
+  /// None while the results are hidden.
+  pub votes: Option<i64>,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -116,6 +186,17 @@ pub struct PrivateMessageView {
   pub recipient: Person,
 }
 
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A conversation with another person, shown with its latest message.
+pub struct PrivateMessageConversationView {
+  pub correspondent: Person,
+  pub latest_message: PrivateMessage,
+  /// The messages from the correspondent which weren't read yet.
+  pub unread_count: i64,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -129,6 +210,19 @@ pub struct PrivateMessageReportView {
   pub resolver: Option<Person>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A report of a user profile.
+pub struct PersonReportView {
+  pub person_report: PersonReport,
+  /// The reported person.
+  pub person: Person,
+  pub creator: Person,
+  pub resolver: Option<Person>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -139,6 +233,10 @@ pub struct RegistrationApplicationView {
   pub creator_local_user: LocalUser,
   pub creator: Person,
   pub admin: Option<Person>,
+  /// The domain of the applicant's email address.
+  pub email_domain: Option<String>,
+  /// Remote persons with the same name as the applicant, who may be evading a ban elsewhere.
+  pub similar_persons: Vec<Person>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
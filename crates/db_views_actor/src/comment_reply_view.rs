@@ -138,8 +138,17 @@ fn queries<'a>() -> Queries<
       query = query.filter(comment_reply::recipient_id.eq(recipient_id));
     }
 
+    // The given ids are all read, regardless of the page size
+    let batch_size = options.ids.as_ref().map(Vec::len);
+    if let Some(ids) = options.ids {
+      query = query.filter(comment_reply::id.eq_any(ids));
+    }
+
+    // Muted notifications are only listed with the read ones
     if options.unread_only {
-      query = query.filter(comment_reply::read.eq(false));
+      query = query
+        .filter(comment_reply::read.eq(false))
+        .filter(comment_reply::muted.eq(false));
     }
 
     if !options.show_bot_accounts {
@@ -156,7 +165,10 @@ fn queries<'a>() -> Queries<
       CommentSortType::Top => query.order_by(comment_aggregates::score.desc()),
     };
 
-    let (limit, offset) = limit_and_offset(options.page, options.limit)?;
+    let (limit, offset) = match batch_size {
+      Some(batch_size) => (i64::try_from(batch_size).unwrap_or(i64::MAX), 0),
+      None => limit_and_offset(options.page, options.limit)?,
+    };
 
     query
       .limit(limit)
@@ -190,6 +202,7 @@ impl CommentReplyView {
       .inner_join(comment::table)
       .filter(comment_reply::recipient_id.eq(my_person_id))
       .filter(comment_reply::read.eq(false))
+      .filter(comment_reply::muted.eq(false))
       .filter(comment::deleted.eq(false))
       .filter(comment::removed.eq(false))
       .select(count(comment_reply::id))
@@ -202,6 +215,8 @@ impl CommentReplyView {
 pub struct CommentReplyQuery {
   pub my_person_id: Option<PersonId>,
   pub recipient_id: Option<PersonId>,
+  /// Only list these, all at once.
+  pub ids: Option<Vec<CommentReplyId>>,
   pub sort: Option<CommentSortType>,
   pub unread_only: bool,
   pub show_bot_accounts: bool,
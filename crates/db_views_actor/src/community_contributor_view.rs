@@ -0,0 +1,167 @@
+use crate::structs::CommunityContributorView;
+use diesel::{
+  result::Error,
+  sql_query,
+  sql_types::{BigInt, Integer},
+  ExpressionMethods,
+  QueryDsl,
+  QueryableByName,
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use lemmy_db_schema::{
+  newtypes::{CommunityId, PersonId},
+  schema::person,
+  source::person::Person,
+  utils::{get_conn, DbPool},
+  ContributorRange,
+};
+
+/// The maximum number of top contributors which can be fetched at once.
+pub const TOP_CONTRIBUTORS_MAX: i64 = 50;
+
+/// How much each post, comment and point of received score count towards the rank of a
+/// contributor.
+#[derive(Debug, Clone, Copy)]
+pub struct ContributorWeights {
+  pub post: i32,
+  pub comment: i32,
+  pub score: i32,
+}
+
+#[derive(QueryableByName)]
+struct ContributorCounts {
+  #[diesel(sql_type = Integer)]
+  person_id: PersonId,
+  #[diesel(sql_type = BigInt)]
+  post_count: i64,
+  #[diesel(sql_type = BigInt)]
+  comment_count: i64,
+  #[diesel(sql_type = BigInt)]
+  score: i64,
+}
+
+impl CommunityContributorView {
+  /// The people with the highest weighted sum of posts, comments and received score in the
+  /// community. Banned, deleted and bot accounts, and users who opted out of leaderboards are left
+  /// out.
+  ///
+  /// Weeks and months are counted from the posts and comments published in that time. All time
+  /// counts come from the rollup table, which is rebuilt daily.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+    range: ContributorRange,
+    weights: ContributorWeights,
+    limit: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let counts_query = match range {
+      ContributorRange::Week => recent_counts_query("1 week"),
+      ContributorRange::Month => recent_counts_query("1 month"),
+      ContributorRange::AllTime => "SELECT person_id, post_count, comment_count, score
+         FROM community_contributor_rollup
+        WHERE community_id = $1"
+        .to_string(),
+    };
+    let counts = sql_query(format!(
+      "SELECT c.person_id, c.post_count, c.comment_count, c.score
+         FROM ({counts_query}) c
+        INNER JOIN person pe ON pe.id = c.person_id
+         LEFT JOIN local_user lu ON lu.person_id = pe.id
+         LEFT JOIN community_person_ban cb ON cb.person_id = pe.id AND cb.community_id = $1
+              AND (cb.expires IS NULL OR cb.expires > now())
+        WHERE NOT pe.banned AND NOT pe.deleted AND NOT pe.bot_account
+          AND cb.id IS NULL
+          AND NOT coalesce(lu.exclude_from_leaderboards, false)
+        ORDER BY c.post_count * $2 + c.comment_count * $3 + c.score * $4 DESC, c.person_id
+        LIMIT $5"
+    ))
+    .bind::<Integer, _>(community_id)
+    .bind::<BigInt, _>(i64::from(weights.post))
+    .bind::<BigInt, _>(i64::from(weights.comment))
+    .bind::<BigInt, _>(i64::from(weights.score))
+    .bind::<BigInt, _>(limit.clamp(1, TOP_CONTRIBUTORS_MAX))
+    .load::<ContributorCounts>(conn)
+    .await?;
+
+    with_persons(conn, counts).await
+  }
+
+  /// The people who wrote the most comments in the community within the last days, for its
+  /// moderators. Removed and deleted posts and comments aren't counted.
+  pub async fn list_top_commenters(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+    days: i64,
+    limit: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let counts = sql_query(format!(
+      "SELECT c.person_id, c.post_count, c.comment_count, c.score
+         FROM ({}) c
+        WHERE c.comment_count > 0
+        ORDER BY c.comment_count DESC, c.person_id
+        LIMIT $2",
+      recent_counts_query(&format!("{days} days"))
+    ))
+    .bind::<Integer, _>(community_id)
+    .bind::<BigInt, _>(limit.clamp(1, TOP_CONTRIBUTORS_MAX))
+    .load::<ContributorCounts>(conn)
+    .await?;
+
+    with_persons(conn, counts).await
+  }
+}
+
+/// Loads the persons of the counts, keeping the order of the counts.
+async fn with_persons(
+  conn: &mut AsyncPgConnection,
+  counts: Vec<ContributorCounts>,
+) -> Result<Vec<CommunityContributorView>, Error> {
+  let person_ids: Vec<PersonId> = counts.iter().map(|c| c.person_id).collect();
+  let persons = person::table
+    .filter(person::id.eq_any(person_ids))
+    .load::<Person>(conn)
+    .await?;
+
+  Ok(
+    counts
+      .into_iter()
+      .filter_map(|c| {
+        let person = persons.iter().find(|p| p.id == c.person_id)?.clone();
+        Some(CommunityContributorView {
+          person,
+          post_count: c.post_count,
+          comment_count: c.comment_count,
+          score: c.score,
+        })
+      })
+      .collect(),
+  )
+}
+
+/// Counts the posts and comments in the community which were published within the interval.
+/// Removed and deleted posts aren't counted, and neither are the comments on them.
+fn recent_counts_query(interval: &str) -> String {
+  format!(
+    "SELECT creator_id AS person_id,
+            sum(posts)::bigint AS post_count,
+            sum(comments)::bigint AS comment_count,
+            sum(score)::bigint AS score
+       FROM (
+         SELECT p.creator_id, 1 AS posts, 0 AS comments, pa.score
+           FROM post p
+          INNER JOIN post_aggregates pa ON pa.post_id = p.id
+          WHERE p.community_id = $1 AND p.published > now() - interval '{interval}'
+            AND NOT p.deleted AND NOT p.removed
+         UNION ALL
+         SELECT c.creator_id, 0, 1, ca.score
+           FROM comment c
+          INNER JOIN post p ON p.id = c.post_id
+          INNER JOIN comment_aggregates ca ON ca.comment_id = c.id
+          WHERE p.community_id = $1 AND c.published > now() - interval '{interval}'
+            AND NOT c.deleted AND NOT c.removed AND NOT p.deleted AND NOT p.removed
+       ) a
+      GROUP BY creator_id"
+  )
+}
@@ -43,6 +43,7 @@ impl CommunityFollowerView {
     let conn = &mut get_conn(pool).await?;
     let res = community_follower::table
       .filter(community_follower::community_id.eq(community_id))
+      .filter(community_follower::pending.eq(false))
       .select(count_star())
       .first::<i64>(conn)
       .await?;
@@ -1,5 +1,5 @@
 use crate::structs::CommunityPersonBanView;
-use diesel::{result::Error, ExpressionMethods, QueryDsl};
+use diesel::{dsl::now, result::Error, BoolExpressionMethods, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::{CommunityId, PersonId},
@@ -9,6 +9,8 @@ use lemmy_db_schema::{
 };
 
 impl CommunityPersonBanView {
+  /// Reads the ban of the person from the community. Temporary bans which are over don't count,
+  /// even before the scheduled task deletes them.
   pub async fn get(
     pool: &mut DbPool<'_>,
     from_person_id: PersonId,
@@ -21,6 +23,11 @@ impl CommunityPersonBanView {
       .select((community::all_columns, person::all_columns))
       .filter(community_person_ban::community_id.eq(from_community_id))
       .filter(community_person_ban::person_id.eq(from_person_id))
+      .filter(
+        community_person_ban::expires
+          .is_null()
+          .or(community_person_ban::expires.gt(now)),
+      )
       .order_by(community_person_ban::published)
       .first::<(Community, Person)>(conn)
       .await?;
@@ -139,6 +139,10 @@ fn queries<'a>() -> Queries<
       };
     }
 
+    if options.verified_only {
+      query = query.filter(community::verified.eq(true));
+    }
+
     // Don't show blocked communities or nsfw communities if not enabled in profile
     if options.local_user.is_some() {
       query = query.filter(community_block::person_id.is_null());
@@ -196,6 +200,8 @@ pub struct CommunityQuery<'a> {
   pub search_term: Option<String>,
   pub is_mod_or_admin: bool,
   pub show_nsfw: bool,
+  /// Only list communities which the admins verified.
+  pub verified_only: bool,
   pub page: Option<i64>,
   pub limit: Option<i64>,
 }
@@ -3,6 +3,8 @@ pub mod comment_reply_view;
 #[cfg(feature = "full")]
 pub mod community_block_view;
 #[cfg(feature = "full")]
+pub mod community_contributor_view;
+#[cfg(feature = "full")]
 pub mod community_follower_view;
 #[cfg(feature = "full")]
 pub mod community_moderator_view;
@@ -16,4 +18,8 @@ pub mod person_block_view;
 pub mod person_mention_view;
 #[cfg(feature = "full")]
 pub mod person_view;
+#[cfg(feature = "full")]
+pub mod post_reminder_view;
+#[cfg(feature = "full")]
+pub mod reminder_view;
 pub mod structs;
@@ -154,8 +154,17 @@ fn queries<'a>() -> Queries<
       query = query.filter(person_mention::recipient_id.eq(recipient_id));
     }
 
+    // The given ids are all read, regardless of the page size
+    let batch_size = options.ids.as_ref().map(Vec::len);
+    if let Some(ids) = options.ids {
+      query = query.filter(person_mention::id.eq_any(ids));
+    }
+
+    // Muted notifications are only listed with the read ones
     if options.unread_only {
-      query = query.filter(person_mention::read.eq(false));
+      query = query
+        .filter(person_mention::read.eq(false))
+        .filter(person_mention::muted.eq(false));
     }
 
     if !options.show_bot_accounts {
@@ -172,7 +181,10 @@ fn queries<'a>() -> Queries<
       CommentSortType::Top => query.order_by(comment_aggregates::score.desc()),
     };
 
-    let (limit, offset) = limit_and_offset(options.page, options.limit)?;
+    let (limit, offset) = match batch_size {
+      Some(batch_size) => (i64::try_from(batch_size).unwrap_or(i64::MAX), 0),
+      None => limit_and_offset(options.page, options.limit)?,
+    };
 
     query
       .limit(limit)
@@ -207,6 +219,7 @@ impl PersonMentionView {
       .inner_join(comment::table)
       .filter(person_mention::recipient_id.eq(my_person_id))
       .filter(person_mention::read.eq(false))
+      .filter(person_mention::muted.eq(false))
       .filter(comment::deleted.eq(false))
       .filter(comment::removed.eq(false))
       .select(count(person_mention::id))
@@ -219,6 +232,8 @@ impl PersonMentionView {
 pub struct PersonMentionQuery {
   pub my_person_id: Option<PersonId>,
   pub recipient_id: Option<PersonId>,
+  /// Only list these, all at once.
+  pub ids: Option<Vec<PersonMentionId>>,
   pub sort: Option<CommentSortType>,
   pub unread_only: bool,
   pub show_bot_accounts: bool,
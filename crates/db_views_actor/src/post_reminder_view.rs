@@ -0,0 +1,63 @@
+use crate::structs::PostReminderView;
+use diesel::{result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{PersonId, PostReminderId},
+  schema::{community, post, post_reminder},
+  source::{community::Community, post::Post, post_reminder::PostReminder},
+  traits::JoinView,
+  utils::{get_conn, DbPool},
+};
+
+type PostReminderViewTuple = (PostReminder, Post, Community);
+
+impl PostReminderView {
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    post_reminder_id: PostReminderId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let res = post_reminder::table
+      .find(post_reminder_id)
+      .inner_join(post::table.inner_join(community::table))
+      .select((
+        post_reminder::all_columns,
+        post::all_columns,
+        community::all_columns,
+      ))
+      .first::<PostReminderViewTuple>(conn)
+      .await?;
+    Ok(Self::from_tuple(res))
+  }
+
+  /// All pending reminders of a user, the next one first.
+  pub async fn list_for_person(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let res = post_reminder::table
+      .inner_join(post::table.inner_join(community::table))
+      .filter(post_reminder::person_id.eq(for_person_id))
+      .select((
+        post_reminder::all_columns,
+        post::all_columns,
+        community::all_columns,
+      ))
+      .order_by(post_reminder::remind_at.asc())
+      .load::<PostReminderViewTuple>(conn)
+      .await?;
+    Ok(res.into_iter().map(Self::from_tuple).collect())
+  }
+}
+
+impl JoinView for PostReminderView {
+  type JoinTuple = PostReminderViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      post_reminder: a.0,
+      post: a.1,
+      community: a.2,
+    }
+  }
+}
@@ -0,0 +1,93 @@
+use crate::structs::ReminderView;
+use diesel::{dsl::count, result::Error, ExpressionMethods, NullableExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{PersonId, ReminderId},
+  schema::{comment, community, post, reminder},
+  source::{comment::Comment, community::Community, post::Post, reminder::Reminder},
+  traits::JoinView,
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+
+type ReminderViewTuple = (Reminder, Post, Option<Comment>, Community);
+
+impl ReminderView {
+  pub async fn read(pool: &mut DbPool<'_>, reminder_id: ReminderId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let res = reminder::table
+      .find(reminder_id)
+      .inner_join(post::table.inner_join(community::table))
+      .left_join(comment::table)
+      .select((
+        reminder::all_columns,
+        post::all_columns,
+        comment::all_columns.nullable(),
+        community::all_columns,
+      ))
+      .first::<ReminderViewTuple>(conn)
+      .await?;
+    Ok(Self::from_tuple(res))
+  }
+
+  /// Gets the number of unread reminders
+  pub async fn get_unread_reminders(
+    pool: &mut DbPool<'_>,
+    my_person_id: PersonId,
+  ) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    reminder::table
+      .filter(reminder::recipient_id.eq(my_person_id))
+      .filter(reminder::read.eq(false))
+      .select(count(reminder::id))
+      .first::<i64>(conn)
+      .await
+  }
+}
+
+#[derive(Default)]
+pub struct ReminderQuery {
+  pub recipient_id: PersonId,
+  pub unread_only: bool,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+impl ReminderQuery {
+  pub async fn list(self, pool: &mut DbPool<'_>) -> Result<Vec<ReminderView>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(self.page, self.limit)?;
+    let mut query = reminder::table
+      .inner_join(post::table.inner_join(community::table))
+      .left_join(comment::table)
+      .filter(reminder::recipient_id.eq(self.recipient_id))
+      .select((
+        reminder::all_columns,
+        post::all_columns,
+        comment::all_columns.nullable(),
+        community::all_columns,
+      ))
+      .into_boxed();
+    if self.unread_only {
+      query = query.filter(reminder::read.eq(false));
+    }
+    let res = query
+      .order_by(reminder::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<ReminderViewTuple>(conn)
+      .await?;
+    Ok(res.into_iter().map(ReminderView::from_tuple).collect())
+  }
+}
+
+impl JoinView for ReminderView {
+  type JoinTuple = ReminderViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      reminder: a.0,
+      post: a.1,
+      comment: a.2,
+      community: a.3,
+    }
+  }
+}
@@ -7,6 +7,8 @@ use lemmy_db_schema::{
     person::Person,
     person_mention::PersonMention,
     post::Post,
+    post_reminder::PostReminder,
+    reminder::Reminder,
   },
   SubscribedType,
 };
@@ -117,3 +119,37 @@ pub struct PersonView {
   pub person: Person,
   pub counts: PersonAggregates,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A reminder for a post or comment, which is due.
+pub struct ReminderView {
+  pub reminder: Reminder,
+  pub post: Post,
+  pub comment: Option<Comment>,
+  pub community: Community,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A pending reminder for a post.
+pub struct PostReminderView {
+  pub post_reminder: PostReminder,
+  pub post: Post,
+  pub community: Community,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A person with their posts, comments and received score in a community.
+pub struct CommunityContributorView {
+  pub person: Person,
+  pub post_count: i64,
+  pub comment_count: i64,
+  /// The summed score of the counted posts and comments.
+  pub score: i64,
+}
@@ -0,0 +1,79 @@
+use crate::structs::{AdminAllowInstanceView, ModlogListParams};
+use diesel::{
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  IntoSql,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+  TextExpressionMethods,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  schema::{admin_allow_instance, instance, person},
+  source::{instance::Instance, moderator::AdminAllowInstance, person::Person},
+  traits::JoinView,
+  utils::{contains_search, get_conn, DbPool},
+};
+
+type AdminAllowInstanceViewTuple = (AdminAllowInstance, Option<Person>, Instance);
+
+impl AdminAllowInstanceView {
+  pub async fn list(pool: &mut DbPool<'_>, params: ModlogListParams) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let admin_person_id_join = params.mod_person_id.unwrap_or(PersonId(-1));
+    let show_mod_names = !params.hide_modlog_names;
+    let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
+
+    let admin_names_join = admin_allow_instance::admin_person_id
+      .eq(person::id)
+      .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
+
+    let mut query = admin_allow_instance::table
+      .left_join(person::table.on(admin_names_join))
+      .inner_join(instance::table.on(admin_allow_instance::instance_id.eq(instance::id)))
+      .select((
+        admin_allow_instance::all_columns,
+        person::all_columns.nullable(),
+        instance::all_columns,
+      ))
+      .into_boxed();
+
+    if let Some(admin_person_id) = params.mod_person_id {
+      query = query.filter(admin_allow_instance::admin_person_id.eq(admin_person_id));
+    };
+
+    if let Some(reason_query) = &params.reason_query {
+      query = query.filter(admin_allow_instance::reason.ilike(contains_search(reason_query)));
+    };
+
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(admin_allow_instance::id.gt(since_id))
+        .order_by(admin_allow_instance::id.asc())
+    } else {
+      query.order_by(admin_allow_instance::when_.desc())
+    };
+
+    let res = query
+      .limit(params.fetch_limit()?)
+      .load::<AdminAllowInstanceViewTuple>(conn)
+      .await?;
+
+    let results = res.into_iter().map(Self::from_tuple).collect();
+    Ok(results)
+  }
+}
+
+impl JoinView for AdminAllowInstanceView {
+  type JoinTuple = AdminAllowInstanceViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      admin_allow_instance: a.0,
+      admin: a.1,
+      instance: a.2,
+    }
+  }
+}
@@ -0,0 +1,86 @@
+use crate::structs::{AdminClearPersonProfileView, ModlogListParams};
+use diesel::{
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  IntoSql,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+  TextExpressionMethods,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  schema::{admin_clear_person_profile, person},
+  source::{moderator::AdminClearPersonProfile, person::Person},
+  traits::JoinView,
+  utils::{contains_search, get_conn, DbPool},
+};
+
+type AdminClearPersonProfileViewTuple = (AdminClearPersonProfile, Option<Person>, Person);
+
+impl AdminClearPersonProfileView {
+  pub async fn list(pool: &mut DbPool<'_>, params: ModlogListParams) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let person_alias_1 = diesel::alias!(person as person1);
+    let admin_person_id_join = params.mod_person_id.unwrap_or(PersonId(-1));
+    let show_mod_names = !params.hide_modlog_names;
+    let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
+
+    let admin_names_join = admin_clear_person_profile::admin_person_id
+      .eq(person::id)
+      .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
+    let mut query = admin_clear_person_profile::table
+      .left_join(person::table.on(admin_names_join))
+      .inner_join(
+        person_alias_1
+          .on(admin_clear_person_profile::person_id.eq(person_alias_1.field(person::id))),
+      )
+      .select((
+        admin_clear_person_profile::all_columns,
+        person::all_columns.nullable(),
+        person_alias_1.fields(person::all_columns),
+      ))
+      .into_boxed();
+
+    if let Some(admin_person_id) = params.mod_person_id {
+      query = query.filter(admin_clear_person_profile::admin_person_id.eq(admin_person_id));
+    };
+
+    if let Some(other_person_id) = params.other_person_id {
+      query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
+    };
+
+    if let Some(reason_query) = &params.reason_query {
+      query = query.filter(admin_clear_person_profile::reason.ilike(contains_search(reason_query)));
+    };
+
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(admin_clear_person_profile::id.gt(since_id))
+        .order_by(admin_clear_person_profile::id.asc())
+    } else {
+      query.order_by(admin_clear_person_profile::when_.desc())
+    };
+
+    let res = query
+      .limit(params.fetch_limit()?)
+      .load::<AdminClearPersonProfileViewTuple>(conn)
+      .await?;
+
+    let results = res.into_iter().map(Self::from_tuple).collect();
+    Ok(results)
+  }
+}
+
+impl JoinView for AdminClearPersonProfileView {
+  type JoinTuple = AdminClearPersonProfileViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      admin_clear_person_profile: a.0,
+      admin: a.1,
+      person: a.2,
+    }
+  }
+}
@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -14,7 +15,7 @@ use lemmy_db_schema::{
   schema::{admin_purge_comment, person, post},
   source::{moderator::AdminPurgeComment, person::Person, post::Post},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{contains_search, get_conn, DbPool},
 };
 
 type AdminPurgeCommentViewTuple = (AdminPurgeComment, Option<Person>, Post);
@@ -44,12 +45,20 @@ impl AdminPurgeCommentView {
       query = query.filter(admin_purge_comment::admin_person_id.eq(admin_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    if let Some(reason_query) = &params.reason_query {
+      query = query.filter(admin_purge_comment::reason.ilike(contains_search(reason_query)));
+    };
+
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(admin_purge_comment::id.gt(since_id))
+        .order_by(admin_purge_comment::id.asc())
+    } else {
+      query.order_by(admin_purge_comment::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(admin_purge_comment::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<AdminPurgeCommentViewTuple>(conn)
       .await?;
 
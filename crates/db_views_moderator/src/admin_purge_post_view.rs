@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -14,7 +15,7 @@ use lemmy_db_schema::{
   schema::{admin_purge_post, community, person},
   source::{community::Community, moderator::AdminPurgePost, person::Person},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{contains_search, get_conn, DbPool},
 };
 
 type AdminPurgePostViewTuple = (AdminPurgePost, Option<Person>, Community);
@@ -44,12 +45,20 @@ impl AdminPurgePostView {
       query = query.filter(admin_purge_post::admin_person_id.eq(admin_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    if let Some(reason_query) = &params.reason_query {
+      query = query.filter(admin_purge_post::reason.ilike(contains_search(reason_query)));
+    };
+
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(admin_purge_post::id.gt(since_id))
+        .order_by(admin_purge_post::id.asc())
+    } else {
+      query.order_by(admin_purge_post::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(admin_purge_post::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<AdminPurgePostViewTuple>(conn)
       .await?;
 
@@ -0,0 +1,78 @@
+use crate::structs::{AdminVerifyCommunityView, ModlogListParams};
+use diesel::{
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  IntoSql,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  schema::{admin_verify_community, community, person},
+  source::{community::Community, moderator::AdminVerifyCommunity, person::Person},
+  traits::JoinView,
+  utils::{get_conn, DbPool},
+};
+
+type AdminVerifyCommunityViewTuple = (AdminVerifyCommunity, Option<Person>, Community);
+
+impl AdminVerifyCommunityView {
+  pub async fn list(pool: &mut DbPool<'_>, params: ModlogListParams) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+
+    let admin_person_id_join = params.mod_person_id.unwrap_or(PersonId(-1));
+    let show_mod_names = !params.hide_modlog_names;
+    let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
+
+    let admin_names_join = admin_verify_community::admin_person_id
+      .eq(person::id)
+      .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
+    let mut query = admin_verify_community::table
+      .left_join(person::table.on(admin_names_join))
+      .inner_join(community::table.on(admin_verify_community::community_id.eq(community::id)))
+      .select((
+        admin_verify_community::all_columns,
+        person::all_columns.nullable(),
+        community::all_columns,
+      ))
+      .into_boxed();
+
+    if let Some(community_id) = params.community_id {
+      query = query.filter(admin_verify_community::community_id.eq(community_id));
+    };
+
+    if let Some(admin_person_id) = params.mod_person_id {
+      query = query.filter(admin_verify_community::admin_person_id.eq(admin_person_id));
+    };
+
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(admin_verify_community::id.gt(since_id))
+        .order_by(admin_verify_community::id.asc())
+    } else {
+      query.order_by(admin_verify_community::when_.desc())
+    };
+
+    let res = query
+      .limit(params.fetch_limit()?)
+      .load::<AdminVerifyCommunityViewTuple>(conn)
+      .await?;
+
+    let results = res.into_iter().map(Self::from_tuple).collect();
+    Ok(results)
+  }
+}
+
+impl JoinView for AdminVerifyCommunityView {
+  type JoinTuple = AdminVerifyCommunityViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      admin_verify_community: a.0,
+      admin: a.1,
+      community: a.2,
+    }
+  }
+}
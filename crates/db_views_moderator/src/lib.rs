@@ -1,4 +1,10 @@
 #[cfg(feature = "full")]
+pub mod admin_allow_instance_view;
+#[cfg(feature = "full")]
+pub mod admin_block_instance_view;
+#[cfg(feature = "full")]
+pub mod admin_clear_person_profile_view;
+#[cfg(feature = "full")]
 pub mod admin_purge_comment_view;
 #[cfg(feature = "full")]
 pub mod admin_purge_community_view;
@@ -7,6 +13,8 @@ pub mod admin_purge_person_view;
 #[cfg(feature = "full")]
 pub mod admin_purge_post_view;
 #[cfg(feature = "full")]
+pub mod admin_verify_community_view;
+#[cfg(feature = "full")]
 pub mod mod_add_community_view;
 #[cfg(feature = "full")]
 pub mod mod_add_view;
@@ -19,6 +27,8 @@ pub mod mod_feature_post_view;
 #[cfg(feature = "full")]
 pub mod mod_hide_community_view;
 #[cfg(feature = "full")]
+pub mod mod_lock_comment_view;
+#[cfg(feature = "full")]
 pub mod mod_lock_post_view;
 #[cfg(feature = "full")]
 pub mod mod_remove_comment_view;
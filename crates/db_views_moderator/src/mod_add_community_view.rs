@@ -14,7 +14,7 @@ use lemmy_db_schema::{
   schema::{community, mod_add_community, person},
   source::{community::Community, moderator::ModAddCommunity, person::Person},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
 };
 
 type ModAddCommunityViewTuple = (ModAddCommunity, Option<Person>, Community, Person);
@@ -56,12 +56,16 @@ impl ModAddCommunityView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(mod_add_community::id.gt(since_id))
+        .order_by(mod_add_community::id.asc())
+    } else {
+      query.order_by(mod_add_community::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_add_community::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<ModAddCommunityViewTuple>(conn)
       .await?;
 
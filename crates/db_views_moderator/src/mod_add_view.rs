@@ -14,7 +14,7 @@ use lemmy_db_schema::{
   schema::{mod_add, person},
   source::{moderator::ModAdd, person::Person},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
 };
 
 type ModAddViewTuple = (ModAdd, Option<Person>, Person);
@@ -48,12 +48,16 @@ impl ModAddView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(mod_add::id.gt(since_id))
+        .order_by(mod_add::id.asc())
+    } else {
+      query.order_by(mod_add::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_add::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<ModAddViewTuple>(conn)
       .await?;
 
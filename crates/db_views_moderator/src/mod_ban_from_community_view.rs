@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -14,7 +15,7 @@ use lemmy_db_schema::{
   schema::{community, mod_ban_from_community, person},
   source::{community::Community, moderator::ModBanFromCommunity, person::Person},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{contains_search, get_conn, DbPool},
 };
 
 type ModBanFromCommunityViewTuple = (ModBanFromCommunity, Option<Person>, Community, Person);
@@ -58,12 +59,20 @@ impl ModBanFromCommunityView {
       query = query.filter(mod_ban_from_community::other_person_id.eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    if let Some(reason_query) = &params.reason_query {
+      query = query.filter(mod_ban_from_community::reason.ilike(contains_search(reason_query)));
+    };
+
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(mod_ban_from_community::id.gt(since_id))
+        .order_by(mod_ban_from_community::id.asc())
+    } else {
+      query.order_by(mod_ban_from_community::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_ban_from_community::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<ModBanFromCommunityViewTuple>(conn)
       .await?;
 
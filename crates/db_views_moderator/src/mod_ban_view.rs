@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -14,7 +15,7 @@ use lemmy_db_schema::{
   schema::{mod_ban, person},
   source::{moderator::ModBan, person::Person},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{contains_search, get_conn, DbPool},
 };
 
 type ModBanViewTuple = (ModBan, Option<Person>, Person);
@@ -48,12 +49,20 @@ impl ModBanView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    if let Some(reason_query) = &params.reason_query {
+      query = query.filter(mod_ban::reason.ilike(contains_search(reason_query)));
+    };
+
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(mod_ban::id.gt(since_id))
+        .order_by(mod_ban::id.asc())
+    } else {
+      query.order_by(mod_ban::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_ban::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<ModBanViewTuple>(conn)
       .await?;
 
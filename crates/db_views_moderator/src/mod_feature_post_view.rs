@@ -14,7 +14,7 @@ use lemmy_db_schema::{
   schema::{community, mod_feature_post, person, post},
   source::{community::Community, moderator::ModFeaturePost, person::Person, post::Post},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
 };
 
 type ModFeaturePostViewTuple = (ModFeaturePost, Option<Person>, Post, Community);
@@ -55,12 +55,16 @@ impl ModFeaturePostView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(mod_feature_post::id.gt(since_id))
+        .order_by(mod_feature_post::id.asc())
+    } else {
+      query.order_by(mod_feature_post::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_feature_post::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<ModFeaturePostViewTuple>(conn)
       .await?;
 
@@ -14,7 +14,7 @@ use lemmy_db_schema::{
   schema::{community, mod_lock_post, person, post},
   source::{community::Community, moderator::ModLockPost, person::Person, post::Post},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
 };
 
 type ModLockPostViewTuple = (ModLockPost, Option<Person>, Post, Community);
@@ -56,12 +56,16 @@ impl ModLockPostView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(mod_lock_post::id.gt(since_id))
+        .order_by(mod_lock_post::id.asc())
+    } else {
+      query.order_by(mod_lock_post::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_lock_post::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<ModLockPostViewTuple>(conn)
       .await?;
 
@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -14,7 +15,7 @@ use lemmy_db_schema::{
   schema::{community, mod_remove_community, person},
   source::{community::Community, moderator::ModRemoveCommunity, person::Person},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{contains_search, get_conn, DbPool},
 };
 
 type ModRemoveCommunityTuple = (ModRemoveCommunity, Option<Person>, Community);
@@ -43,12 +44,20 @@ impl ModRemoveCommunityView {
       query = query.filter(mod_remove_community::mod_person_id.eq(mod_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    if let Some(reason_query) = &params.reason_query {
+      query = query.filter(mod_remove_community::reason.ilike(contains_search(reason_query)));
+    };
+
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(mod_remove_community::id.gt(since_id))
+        .order_by(mod_remove_community::id.asc())
+    } else {
+      query.order_by(mod_remove_community::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_remove_community::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<ModRemoveCommunityTuple>(conn)
       .await?;
 
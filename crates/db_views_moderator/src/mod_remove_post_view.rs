@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -14,7 +15,7 @@ use lemmy_db_schema::{
   schema::{community, mod_remove_post, person, post},
   source::{community::Community, moderator::ModRemovePost, person::Person, post::Post},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{contains_search, get_conn, DbPool},
 };
 
 type ModRemovePostViewTuple = (ModRemovePost, Option<Person>, Post, Community);
@@ -56,12 +57,20 @@ impl ModRemovePostView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    if let Some(reason_query) = &params.reason_query {
+      query = query.filter(mod_remove_post::reason.ilike(contains_search(reason_query)));
+    };
+
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(mod_remove_post::id.gt(since_id))
+        .order_by(mod_remove_post::id.asc())
+    } else {
+      query.order_by(mod_remove_post::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_remove_post::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<ModRemovePostViewTuple>(conn)
       .await?;
 
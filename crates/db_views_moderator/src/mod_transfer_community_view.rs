@@ -14,7 +14,7 @@ use lemmy_db_schema::{
   schema::{community, mod_transfer_community, person},
   source::{community::Community, moderator::ModTransferCommunity, person::Person},
   traits::JoinView,
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
 };
 
 type ModTransferCommunityViewTuple = (ModTransferCommunity, Option<Person>, Community, Person);
@@ -58,12 +58,16 @@ impl ModTransferCommunityView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    query = if let Some(since_id) = params.since_id {
+      query
+        .filter(mod_transfer_community::id.gt(since_id))
+        .order_by(mod_transfer_community::id.asc())
+    } else {
+      query.order_by(mod_transfer_community::when_.desc())
+    };
 
     let res = query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_transfer_community::when_.desc())
+      .limit(params.fetch_limit()?)
       .load::<ModTransferCommunityViewTuple>(conn)
       .await?;
 
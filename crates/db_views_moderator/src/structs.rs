@@ -3,17 +3,23 @@ use lemmy_db_schema::{
   source::{
     comment::Comment,
     community::Community,
+    instance::Instance,
     moderator::{
+      AdminAllowInstance,
+      AdminBlockInstance,
+      AdminClearPersonProfile,
       AdminPurgeComment,
       AdminPurgeCommunity,
       AdminPurgePerson,
       AdminPurgePost,
+      AdminVerifyCommunity,
       ModAdd,
       ModAddCommunity,
       ModBan,
       ModBanFromCommunity,
       ModFeaturePost,
       ModHideCommunity,
+      ModLockComment,
       ModLockPost,
       ModRemoveComment,
       ModRemoveCommunity,
@@ -98,6 +104,20 @@ pub struct ModLockPostView {
   pub community: Community,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When a moderator locks a comment thread.
+pub struct ModLockCommentView {
+  pub mod_lock_comment: ModLockComment,
+  pub moderator: Option<Person>,
+  pub comment: Comment,
+  pub commenter: Person,
+  pub post: Post,
+  pub community: Community,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -202,7 +222,51 @@ pub struct AdminPurgePostView {
 }
 
 #[skip_serializing_none]
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin blocks or unblocks an instance.
+pub struct AdminBlockInstanceView {
+  pub admin_block_instance: AdminBlockInstance,
+  pub admin: Option<Person>,
+  pub instance: Instance,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin adds an instance to the allowlist, or removes it.
+pub struct AdminAllowInstanceView {
+  pub admin_allow_instance: AdminAllowInstance,
+  pub admin: Option<Person>,
+  pub instance: Instance,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin clears the display name or bio of a user.
+pub struct AdminClearPersonProfileView {
+  pub admin_clear_person_profile: AdminClearPersonProfile,
+  pub admin: Option<Person>,
+  pub person: Person,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin verifies a community, or revokes the verification.
+pub struct AdminVerifyCommunityView {
+  pub admin_verify_community: AdminVerifyCommunity,
+  pub admin: Option<Person>,
+  pub community: Community,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// Querying / filtering the modlog.
@@ -213,4 +277,30 @@ pub struct ModlogListParams {
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub hide_modlog_names: bool,
+  /// Only list entries with a higher id, ordered by id instead of time.
+  pub since_id: Option<i32>,
+  /// Only list entries whose reason contains the text, ignoring case. Actions without a reason
+  /// shouldn't be listed at all then.
+  pub reason_query: Option<String>,
+}
+
+/// How deep into the modlog pages can go. Every action type is read up to the end of the page,
+/// so deeper pages get expensive quickly.
+#[cfg(feature = "full")]
+const MODLOG_MAX_DEPTH: i64 = 1000;
+
+#[cfg(feature = "full")]
+impl ModlogListParams {
+  /// The entries of all action types are merged by time before the page is cut out, so each
+  /// type is read from the newest entry up to the end of the page.
+  pub(crate) fn fetch_limit(&self) -> Result<i64, diesel::result::Error> {
+    let (limit, offset) = lemmy_db_schema::utils::limit_and_offset(self.page, self.limit)?;
+    let fetch_limit = limit + offset;
+    if fetch_limit > MODLOG_MAX_DEPTH {
+      return Err(diesel::result::Error::QueryBuilderError(
+        format!("Modlog pages end after {MODLOG_MAX_DEPTH} entries").into(),
+      ));
+    }
+    Ok(fetch_limit)
+  }
 }
@@ -0,0 +1,265 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{instance::TestInstance, TestFederation};
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  comment::GetComments,
+  person::{CancelAccountDeletion, ConfirmAccountDeletion, DeleteAccount, GetPersonDetails},
+  post::GetPosts,
+  sensitive::Sensitive,
+  utils::local_user_view_from_jwt,
+};
+use lemmy_api_crud::user::delete::{
+  cancel_account_deletion,
+  confirm_account_deletion,
+  delete_account,
+};
+use lemmy_apub::api::{
+  list_comments::list_comments,
+  list_posts::list_posts,
+  read_person::read_person,
+};
+use lemmy_db_schema::{
+  newtypes::{PersonId, PostId},
+  source::{
+    local_user::{LocalUser, LocalUserUpdateForm},
+    person::{Person, PersonUpdateForm},
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  claims::{AccountDeletionAction, AccountDeletionClaims, Claims},
+  error::LemmyErrorType,
+};
+use serial_test::serial;
+
+/// The names of the posts and the contents of the comments which anonymous users see.
+async fn listed(alpha: &TestInstance, post_id: PostId) -> (Vec<String>, Vec<String>) {
+  let posts = list_posts(Query(GetPosts::default()), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .response
+    .posts;
+  let form = GetComments {
+    post_id: Some(post_id),
+    ..Default::default()
+  };
+  let comments = list_comments(Query(form), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .response
+    .comments;
+  (
+    posts.into_iter().map(|p| p.post.name).collect(),
+    comments.into_iter().map(|c| c.comment.content).collect(),
+  )
+}
+
+fn deletion_token(
+  alpha: &TestInstance,
+  local_user: &LocalUser,
+  action: AccountDeletionAction,
+) -> Sensitive<String> {
+  let scheduled = local_user.deletion_scheduled_at.unwrap().timestamp();
+  let token = AccountDeletionClaims::jwt(
+    local_user.id.0,
+    action,
+    scheduled,
+    &alpha.context().secret().jwt_secret,
+    &alpha.settings().hostname,
+  )
+  .unwrap();
+  Sensitive::new(token)
+}
+
+async fn local_user(alpha: &TestInstance, person_id: PersonId) -> LocalUser {
+  LocalUserView::read_person(&mut alpha.pool(), person_id)
+    .await
+    .unwrap()
+    .local_user
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_account_deletion_cooling_off() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let form = PersonUpdateForm {
+    admin: Some(true),
+    ..Default::default()
+  };
+  Person::update(&mut alpha.pool(), alice.person.id, &form)
+    .await
+    .unwrap();
+  let community_id = alpha
+    .create_community("main", &alice)
+    .await
+    .unwrap()
+    .community
+    .id;
+  let post_id = alpha
+    .create_post("By alice", community_id, &alice)
+    .await
+    .unwrap()
+    .post
+    .id;
+
+  let bob = alpha.create_user("bob").await.unwrap();
+  alpha
+    .create_post("By bob", community_id, &bob)
+    .await
+    .unwrap();
+  alpha
+    .create_comment("Comment by bob", post_id, &bob)
+    .await
+    .unwrap();
+  // Changing the password logs out, so bob needs a new token
+  let local_user_id = local_user(alpha, bob.person.id).await.id;
+  LocalUser::update_password(&mut alpha.pool(), local_user_id, "bob-password")
+    .await
+    .unwrap();
+  let auth = Sensitive::new(
+    Claims::jwt(
+      local_user_id.0,
+      &alpha.context().secret().jwt_secret,
+      &alpha.settings().hostname,
+    )
+    .unwrap(),
+  );
+
+  let form = DeleteAccount {
+    password: Sensitive::new("bob-password".to_string()),
+    delete_immediately: None,
+    auth: auth.clone(),
+  };
+  let scheduled_at = delete_account(Json(form), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .deletion_scheduled_at;
+  assert!(scheduled_at.unwrap() > naive_now());
+
+  // The account is deactivated and its content hidden, except for admins viewing the profile
+  let err = local_user_view_from_jwt(&auth, &alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::AccountDeletionPending, err.error_type);
+  let (posts, comments) = listed(alpha, post_id).await;
+  assert_eq!(vec!["By alice".to_string()], posts);
+  assert!(comments.is_empty());
+
+  let mut form = GetPersonDetails {
+    person_id: Some(bob.person.id),
+    ..Default::default()
+  };
+  let details = read_person(Query(form.clone()), alpha.context())
+    .await
+    .unwrap()
+    .0;
+  assert!(details.deletion_pending);
+  assert_eq!(None, details.deletion_scheduled_at);
+  assert!(details.posts.is_empty());
+  form.auth = Some(alice.auth.clone());
+  let details = read_person(Query(form), alpha.context()).await.unwrap().0;
+  // The database stores the time with less precision
+  assert_eq!(
+    scheduled_at.map(|s| s.timestamp()),
+    details.deletion_scheduled_at.map(|s| s.timestamp())
+  );
+  assert_eq!(1, details.posts.len());
+  assert_eq!(1, details.comments.len());
+
+  // Each link only works for its own action
+  let bob_local_user = local_user(alpha, bob.person.id).await;
+  let token = deletion_token(alpha, &bob_local_user, AccountDeletionAction::Cancel);
+  let form = ConfirmAccountDeletion {
+    token: token.clone(),
+  };
+  let err = confirm_account_deletion(Json(form), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::InvalidAccountDeletionLink, err.error_type);
+
+  let form = CancelAccountDeletion {
+    token: Some(token.clone()),
+    ..Default::default()
+  };
+  cancel_account_deletion(Json(form), alpha.context())
+    .await
+    .unwrap();
+  let (posts, comments) = listed(alpha, post_id).await;
+  assert_eq!(2, posts.len());
+  assert_eq!(vec!["Comment by bob".to_string()], comments);
+
+  // The link of a cancelled deletion doesn't work anymore
+  let form = CancelAccountDeletion {
+    token: Some(token),
+    ..Default::default()
+  };
+  let err = cancel_account_deletion(Json(form), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::InvalidAccountDeletionLink, err.error_type);
+
+  // Without an email, the deletion is cancelled with the password
+  let future = naive_now() + chrono::Duration::days(1);
+  LocalUser::schedule_deletion(&mut alpha.pool(), local_user_id, future)
+    .await
+    .unwrap();
+  let mut form = CancelAccountDeletion {
+    username: Some("bob".to_string()),
+    password: Some(Sensitive::new("wrong-password".to_string())),
+    ..Default::default()
+  };
+  let err = cancel_account_deletion(Json(form.clone()), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::IncorrectLogin, err.error_type);
+  form.password = Some(Sensitive::new("bob-password".to_string()));
+  cancel_account_deletion(Json(form.clone()), alpha.context())
+    .await
+    .unwrap();
+  assert_eq!(
+    None,
+    local_user(alpha, bob.person.id).await.deletion_scheduled_at
+  );
+
+  // With an email, only the link works
+  let email_form = LocalUserUpdateForm {
+    email: Some(Some("bob@lemmy-alpha.test".to_string())),
+    ..Default::default()
+  };
+  LocalUser::update(&mut alpha.pool(), local_user_id, &email_form)
+    .await
+    .unwrap();
+  LocalUser::schedule_deletion(&mut alpha.pool(), local_user_id, future)
+    .await
+    .unwrap();
+  let err = cancel_account_deletion(Json(form), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::InvalidAccountDeletionLink, err.error_type);
+
+  // Only deletions whose cooling-off period is over are due
+  assert!(LocalUser::list_due_deletions(&mut alpha.pool())
+    .await
+    .unwrap()
+    .is_empty());
+  let past = naive_now() - chrono::Duration::hours(1);
+  LocalUser::schedule_deletion(&mut alpha.pool(), local_user_id, past)
+    .await
+    .unwrap();
+  let due = LocalUser::list_due_deletions(&mut alpha.pool())
+    .await
+    .unwrap();
+  assert_eq!(
+    vec![local_user_id],
+    due.iter().map(|l| l.id).collect::<Vec<_>>()
+  );
+}
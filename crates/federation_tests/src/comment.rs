@@ -0,0 +1,463 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{
+  instance::{TestInstance, TestUser},
+  TestFederation,
+};
+use actix_web::web::{Json, Query};
+use lemmy_api::{
+  comment::{edit_history::get_comment_edit_history, lock::lock_comment},
+  site::content_diff::get_content_diff,
+};
+use lemmy_api_common::{
+  comment::{
+    CreateComment,
+    DeleteComment,
+    EditComment,
+    GetCommentEditHistory,
+    LockComment,
+    RemoveComment,
+  },
+  community::GetCommunityResyncJob,
+  diff::{DiffHunk, DiffSpanKind},
+  post::ResyncRemotePost,
+  site::GetContentDiff,
+};
+use lemmy_api_crud::comment::{
+  create::create_comment,
+  delete::delete_comment,
+  remove::remove_comment,
+  update::update_comment,
+};
+use lemmy_apub::api::{
+  resync_community::get_community_resync_job,
+  resync_post::resync_remote_post,
+};
+use lemmy_db_schema::{
+  source::{
+    comment::{Comment, CommentUpdateForm},
+    community_resync_job::CommunityResyncJob,
+    person::{Person, PersonUpdateForm},
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+use std::time::Duration;
+
+/// Alice posts in a community on alpha, which Bob from beta follows.
+async fn setup(federation: &TestFederation) -> (TestUser, TestUser, Post, Post) {
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_community = beta
+    .fetch_community(&community.community.actor_id)
+    .await
+    .unwrap();
+  beta
+    .follow_community(beta_community.id, true, &bob)
+    .await
+    .unwrap();
+
+  let alpha_post = alpha
+    .create_post("Federation", community.community.id, &alice)
+    .await
+    .unwrap()
+    .post;
+  let beta_post = beta.read_post(&alpha_post.ap_id).await.unwrap().unwrap();
+  (alice, bob, alpha_post, beta_post)
+}
+
+/// Polls the resync job until it is finished in the background.
+async fn wait_for_resync(
+  instance: &TestInstance,
+  user: &TestUser,
+  job_id: i32,
+) -> CommunityResyncJob {
+  for _ in 0..200 {
+    let form = GetCommunityResyncJob {
+      job_id,
+      auth: user.auth.clone(),
+    };
+    let job = get_community_resync_job(Query(form), instance.context())
+      .await
+      .unwrap()
+      .0
+      .job;
+    if job.finished.is_some() {
+      return job;
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+  }
+  panic!("Resync job {job_id} didn't finish");
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_comment_create_update_delete() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let (alice, _bob, alpha_post, beta_post) = setup(&federation).await;
+
+  let comment = alpha
+    .create_comment("Hello beta", alpha_post.id, &alice)
+    .await
+    .unwrap()
+    .comment;
+  let beta_comment = beta.read_comment(&comment.ap_id).await.unwrap().unwrap();
+  assert_eq!("Hello beta", beta_comment.content);
+  assert_eq!(beta_post.id, beta_comment.post_id);
+  assert!(!beta_comment.local);
+
+  let edit = EditComment {
+    comment_id: comment.id,
+    content: Some("Hello again".to_string()),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  update_comment(Json(edit), alpha.context()).await.unwrap();
+  let beta_comment = beta.read_comment(&comment.ap_id).await.unwrap().unwrap();
+  assert_eq!("Hello again", beta_comment.content);
+  assert!(beta_comment.updated.is_some());
+
+  let delete = DeleteComment {
+    comment_id: comment.id,
+    deleted: true,
+    auth: alice.auth.clone(),
+  };
+  delete_comment(Json(delete), alpha.context()).await.unwrap();
+  let beta_comment = beta.read_comment(&comment.ap_id).await.unwrap().unwrap();
+  assert!(beta_comment.deleted);
+
+  let restore = DeleteComment {
+    comment_id: comment.id,
+    deleted: false,
+    auth: alice.auth.clone(),
+  };
+  delete_comment(Json(restore), alpha.context())
+    .await
+    .unwrap();
+  let beta_comment = beta.read_comment(&comment.ap_id).await.unwrap().unwrap();
+  assert!(!beta_comment.deleted);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_comment_on_remote_post() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let (_alice, bob, alpha_post, beta_post) = setup(&federation).await;
+
+  let comment = beta
+    .create_comment("Hello alpha", beta_post.id, &bob)
+    .await
+    .unwrap()
+    .comment;
+  let alpha_comment = alpha.read_comment(&comment.ap_id).await.unwrap().unwrap();
+  assert_eq!("Hello alpha", alpha_comment.content);
+  assert_eq!(alpha_post.id, alpha_comment.post_id);
+  assert!(!alpha_comment.local);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_comment_lock() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let (alice, bob, alpha_post, beta_post) = setup(&federation).await;
+
+  let comment = alpha
+    .create_comment("Locked thread", alpha_post.id, &alice)
+    .await
+    .unwrap()
+    .comment;
+  let lock = LockComment {
+    comment_id: comment.id,
+    locked: true,
+    auth: alice.auth.clone(),
+  };
+  lock_comment(Json(lock), alpha.context()).await.unwrap();
+  let beta_comment = beta.read_comment(&comment.ap_id).await.unwrap().unwrap();
+  assert!(beta_comment.locked);
+
+  // Replies are refused on the remote instance as well
+  let reply = CreateComment {
+    content: "Reply".to_string(),
+    post_id: beta_post.id,
+    parent_id: Some(beta_comment.id),
+    auth: bob.auth.clone(),
+    ..Default::default()
+  };
+  assert!(create_comment(Json(reply), beta.context()).await.is_err());
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_comment_lock_keeps_remote_content() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let (alice, bob, _alpha_post, beta_post) = setup(&federation).await;
+
+  let comment = beta
+    .create_comment("Written by bob", beta_post.id, &bob)
+    .await
+    .unwrap()
+    .comment;
+  let alpha_comment = alpha.read_comment(&comment.ap_id).await.unwrap().unwrap();
+
+  // The copy on the mod's instance differs, but the lock must not overwrite bob's comment
+  let form = CommentUpdateForm {
+    content: Some("Forged".to_string()),
+    nsfw: Some(true),
+    ..Default::default()
+  };
+  Comment::update(&mut alpha.pool(), alpha_comment.id, &form)
+    .await
+    .unwrap();
+  let lock = LockComment {
+    comment_id: alpha_comment.id,
+    locked: true,
+    auth: alice.auth.clone(),
+  };
+  lock_comment(Json(lock), alpha.context()).await.unwrap();
+
+  let beta_comment = Comment::read(&mut beta.pool(), comment.id).await.unwrap();
+  assert!(beta_comment.locked);
+  assert!(beta_comment.local);
+  assert!(!beta_comment.nsfw);
+  assert_eq!("Written by bob", beta_comment.content);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_resync_remote_post() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let (alice, bob, alpha_post, beta_post) = setup(&federation).await;
+
+  let kept = alpha
+    .create_comment("Removed on beta", alpha_post.id, &alice)
+    .await
+    .unwrap()
+    .comment;
+  let parent = alpha
+    .create_comment("Parent", alpha_post.id, &alice)
+    .await
+    .unwrap()
+    .comment;
+  let reply = CreateComment {
+    content: "Reply".to_string(),
+    post_id: alpha_post.id,
+    parent_id: Some(parent.id),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  let reply = create_comment(Json(reply), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .comment_view
+    .comment;
+
+  // Beta missed the parent and its reply during an outage
+  for ap_id in [&reply.ap_id, &parent.ap_id] {
+    let missing = beta.read_comment(ap_id).await.unwrap().unwrap();
+    Comment::delete(&mut beta.pool(), missing.id).await.unwrap();
+  }
+  let beta_kept = beta.read_comment(&kept.ap_id).await.unwrap().unwrap();
+  let form = CommentUpdateForm {
+    removed: Some(true),
+    ..Default::default()
+  };
+  Comment::update(&mut beta.pool(), beta_kept.id, &form)
+    .await
+    .unwrap();
+
+  let form = PersonUpdateForm {
+    admin: Some(true),
+    ..Default::default()
+  };
+  Person::update(&mut beta.pool(), bob.person.id, &form)
+    .await
+    .unwrap();
+  let resync = ResyncRemotePost {
+    post_id: beta_post.id,
+    auth: bob.auth.clone(),
+  };
+  let job = resync_remote_post(Json(resync.clone()), beta.context())
+    .await
+    .unwrap()
+    .0
+    .job;
+  assert_eq!(Some(beta_post.id), job.post_id);
+  let job = wait_for_resync(beta, &bob, job.id).await;
+  assert_eq!(1, job.posts_done);
+  assert_eq!(0, job.posts_failed);
+  assert_eq!(2, job.comments_added);
+
+  let beta_parent = beta.read_comment(&parent.ap_id).await.unwrap().unwrap();
+  let beta_reply = beta.read_comment(&reply.ap_id).await.unwrap().unwrap();
+  assert!(beta_parent.backfilled);
+  assert!(beta_reply.backfilled);
+  assert_eq!(Some(beta_parent.id), beta_reply.parent_comment_id());
+  // Comments which existed before are left alone
+  let beta_kept = beta.read_comment(&kept.ap_id).await.unwrap().unwrap();
+  assert!(beta_kept.removed);
+  assert!(!beta_kept.backfilled);
+
+  // Nothing is missing anymore
+  let job = resync_remote_post(Json(resync), beta.context())
+    .await
+    .unwrap()
+    .0
+    .job;
+  let job = wait_for_resync(beta, &bob, job.id).await;
+  assert_eq!(0, job.comments_added);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_comment_edit_history() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let bob = alpha.create_user("bob").await.unwrap();
+  let carol = alpha.create_user("carol").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let post = alpha
+    .create_post("History", community.community.id, &alice)
+    .await
+    .unwrap()
+    .post;
+  let comment = alpha
+    .create_comment("First", post.id, &bob)
+    .await
+    .unwrap()
+    .comment;
+  for content in ["Second", "Third"] {
+    let edit = EditComment {
+      comment_id: comment.id,
+      content: Some(content.to_string()),
+      auth: bob.auth.clone(),
+      ..Default::default()
+    };
+    update_comment(Json(edit), alpha.context()).await.unwrap();
+  }
+
+  // The author and the mods can read the history, others can't
+  let history_as = |user: &TestUser| GetCommentEditHistory {
+    comment_id: comment.id,
+    auth: user.auth.clone(),
+  };
+  let response = get_comment_edit_history(Query(history_as(&bob)), alpha.context())
+    .await
+    .unwrap();
+  let contents: Vec<_> = response.0.revisions.iter().map(|r| &r.content).collect();
+  assert_eq!(vec!["Second", "First"], contents);
+  assert_eq!(bob.person.id, response.0.revisions[0].editor_id);
+  let response = get_comment_edit_history(Query(history_as(&alice)), alpha.context())
+    .await
+    .unwrap();
+  assert_eq!(2, response.0.revisions.len());
+  let err = get_comment_edit_history(Query(history_as(&carol)), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::NotAModOrAdmin, err.error_type);
+
+  // Each revision is diffed against the one which replaced it
+  let diff_of = |revision_id| GetContentDiff {
+    comment_id: Some(comment.id),
+    revision_id,
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  let diff_texts = |hunks: &[DiffHunk], kind| {
+    hunks
+      .iter()
+      .flat_map(|h| h.spans.iter())
+      .filter(|s| s.kind == kind)
+      .map(|s| s.text.clone())
+      .collect::<Vec<_>>()
+  };
+  let newest = response.0.revisions[0].id;
+  let oldest = response.0.revisions[1].id;
+  let diff = get_content_diff(Query(diff_of(oldest)), alpha.context())
+    .await
+    .unwrap();
+  assert_eq!(
+    vec!["First"],
+    diff_texts(&diff.0.hunks, DiffSpanKind::Removed)
+  );
+  assert_eq!(
+    vec!["Second"],
+    diff_texts(&diff.0.hunks, DiffSpanKind::Added)
+  );
+  let diff = get_content_diff(Query(diff_of(newest)), alpha.context())
+    .await
+    .unwrap();
+  assert_eq!(
+    vec!["Third"],
+    diff_texts(&diff.0.hunks, DiffSpanKind::Added)
+  );
+  let err = get_content_diff(Query(diff_of(-1)), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::CouldntFindEditRevision, err.error_type);
+
+  // Nothing of a removed comment stays readable
+  let remove = RemoveComment {
+    comment_id: comment.id,
+    removed: true,
+    reason: None,
+    auth: alice.auth.clone(),
+  };
+  remove_comment(Json(remove), alpha.context()).await.unwrap();
+  let response = get_comment_edit_history(Query(history_as(&alice)), alpha.context())
+    .await
+    .unwrap();
+  assert!(response.0.revisions.is_empty());
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_comment_nsfw() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let (alice, _bob, alpha_post, _beta_post) = setup(&federation).await;
+
+  let form = CreateComment {
+    content: "Spoilers".to_string(),
+    post_id: alpha_post.id,
+    content_warning: Some("Season finale".to_string()),
+    nsfw: Some(true),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  let comment = create_comment(Json(form), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .comment_view
+    .comment;
+  assert!(comment.nsfw);
+  let beta_comment = beta.read_comment(&comment.ap_id).await.unwrap().unwrap();
+  assert!(beta_comment.nsfw);
+  assert_eq!(
+    Some("Season finale".to_string()),
+    beta_comment.content_warning
+  );
+
+  let edit = EditComment {
+    comment_id: comment.id,
+    nsfw: Some(false),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  update_comment(Json(edit), alpha.context()).await.unwrap();
+  let beta_comment = beta.read_comment(&comment.ap_id).await.unwrap().unwrap();
+  assert!(!beta_comment.nsfw);
+}
@@ -0,0 +1,154 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::Json;
+use chrono::Duration;
+use lemmy_api::community::ban::{ban_from_community, lift_expired_community_bans};
+use lemmy_api_common::community::BanFromCommunity;
+use lemmy_apub::activities::match_outgoing_activities;
+use lemmy_db_schema::{
+  source::{
+    community::{
+      CommunityModerator,
+      CommunityModeratorForm,
+      CommunityPersonBan,
+      CommunityPersonBanForm,
+    },
+    person::{Person, PersonUpdateForm},
+  },
+  traits::{Bannable, Crud, Joinable},
+  utils::naive_now,
+};
+use lemmy_db_views_moderator::structs::{ModBanFromCommunityView, ModlogListParams};
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+
+#[actix_web::test]
+#[serial]
+async fn test_expired_community_ban() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let bob = alpha.create_user("bob").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let community_id = community.community.id;
+
+  let ban = |expires| CommunityPersonBanForm {
+    community_id,
+    person_id: bob.person.id,
+    expires: Some(expires),
+  };
+  CommunityPersonBan::ban(&mut alpha.pool(), &ban(None))
+    .await
+    .unwrap();
+  let err = alpha
+    .create_post("Still banned", community_id, &bob)
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::BannedFromCommunity, err.error_type);
+
+  // The ban is over before the scheduled task deletes it
+  let expired = naive_now() - Duration::minutes(1);
+  CommunityPersonBan::ban(&mut alpha.pool(), &ban(Some(expired)))
+    .await
+    .unwrap();
+  alpha
+    .create_post("Back again", community_id, &bob)
+    .await
+    .unwrap();
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_lift_expired_community_ban() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let form = PersonUpdateForm {
+    admin: Some(true),
+    ..Default::default()
+  };
+  Person::update(&mut alpha.pool(), alice.person.id, &form)
+    .await
+    .unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let community_id = community.community.id;
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_community = beta
+    .fetch_community(&community.community.actor_id)
+    .await
+    .unwrap();
+  beta
+    .follow_community(beta_community.id, true, &bob)
+    .await
+    .unwrap();
+  let alpha_bob = alpha.fetch_person(&bob.person.actor_id).await.unwrap();
+
+  // Beta keeps the ban until the unban from alpha arrives
+  let ban = BanFromCommunity {
+    community_id,
+    person_id: alpha_bob.id,
+    ban: true,
+    expires: Some((naive_now() + Duration::days(1)).timestamp()),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  ban_from_community(Json(ban), alpha.context())
+    .await
+    .unwrap();
+  let form = CommunityPersonBanForm {
+    community_id,
+    person_id: alpha_bob.id,
+    expires: Some(Some(naive_now() - Duration::minutes(1))),
+  };
+  CommunityPersonBan::ban(&mut alpha.pool(), &form)
+    .await
+    .unwrap();
+  let err = beta
+    .create_post("Still banned", beta_community.id, &bob)
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::BannedFromCommunity, err.error_type);
+
+  // Without a local mod, the unban is sent in the name of an admin
+  let form = CommunityModeratorForm {
+    community_id,
+    person_id: alice.person.id,
+  };
+  CommunityModerator::leave(&mut alpha.pool(), &form)
+    .await
+    .unwrap();
+  let unbans = lift_expired_community_bans(&alpha.context()).await.unwrap();
+  assert_eq!(1, unbans.len());
+  for unban in unbans {
+    match_outgoing_activities(unban, &alpha.context())
+      .await
+      .unwrap();
+  }
+  assert!(CommunityPersonBan::list_expired(&mut alpha.pool())
+    .await
+    .unwrap()
+    .is_empty());
+  beta
+    .create_post("Back again", beta_community.id, &bob)
+    .await
+    .unwrap();
+
+  let params = ModlogListParams {
+    community_id: Some(community_id),
+    mod_person_id: None,
+    other_person_id: Some(alpha_bob.id),
+    page: None,
+    limit: None,
+    hide_modlog_names: false,
+    since_id: None,
+    reason_query: None,
+  };
+  let entries = ModBanFromCommunityView::list(&mut alpha.pool(), params)
+    .await
+    .unwrap();
+  let unban = &entries[0].mod_ban_from_community;
+  assert!(!unban.banned);
+  assert_eq!(alice.person.id, unban.mod_person_id);
+}
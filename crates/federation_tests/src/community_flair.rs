@@ -0,0 +1,95 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::Json;
+use lemmy_api::community::{
+  flair::set_community_person_flair,
+  flair_option::delete_community_flair_option,
+};
+use lemmy_api_common::community::{DeleteCommunityFlairOption, SetCommunityPersonFlair};
+use lemmy_db_schema::{
+  source::{
+    community_flair::{CommunityFlairOption, CommunityFlairOptionForm},
+    person::{Person, PersonUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::PostView;
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+
+#[actix_web::test]
+#[serial]
+async fn test_community_flair() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let bob = alpha.create_user("bob").await.unwrap();
+  let community = alpha
+    .create_community("main", &alice)
+    .await
+    .unwrap()
+    .community;
+  let carol = beta.create_user("carol").await.unwrap();
+  let beta_community = beta.fetch_community(&community.actor_id).await.unwrap();
+  beta
+    .follow_community(beta_community.id, true, &carol)
+    .await
+    .unwrap();
+
+  let form = SetCommunityPersonFlair {
+    community_id: community.id,
+    person_id: bob.person.id,
+    flair: "Regular".to_string(),
+    color: Some("#1e90ff".to_string()),
+    auth: alice.auth.clone(),
+  };
+  set_community_person_flair(Json(form), alpha.context())
+    .await
+    .unwrap();
+
+  // The flair is shown next to the posts of the member in the community
+  let post = alpha
+    .create_post("Flair", community.id, &bob)
+    .await
+    .unwrap();
+  assert_eq!(
+    Some("Regular"),
+    post.creator_flair.as_ref().map(|f| f.flair.as_str())
+  );
+
+  // It is sent along with the post, and accepted because a moderator assigned it
+  let beta_post = beta.read_post(&post.post.ap_id).await.unwrap().unwrap();
+  let beta_view = PostView::read(&mut beta.pool(), beta_post.id, None, false)
+    .await
+    .unwrap();
+  let beta_flair = beta_view.creator_flair.unwrap();
+  assert_eq!("Regular", beta_flair.flair);
+  assert_eq!(Some("#1e90ff".to_string()), beta_flair.color);
+
+  // The flairs of remote communities can only be changed on their own instance
+  let form = PersonUpdateForm {
+    admin: Some(true),
+    ..Default::default()
+  };
+  Person::update(&mut beta.pool(), carol.person.id, &form)
+    .await
+    .unwrap();
+  let form = CommunityFlairOptionForm {
+    community_id: beta_community.id,
+    flair: "Visitor".to_string(),
+    color: None,
+  };
+  let option = CommunityFlairOption::create(&mut beta.pool(), &form)
+    .await
+    .unwrap();
+  let form = DeleteCommunityFlairOption {
+    id: option.id,
+    auth: carol.auth.clone(),
+  };
+  let err = delete_community_flair_option(Json(form), beta.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::FlairNotAvailable, err.error_type);
+}
@@ -0,0 +1,51 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use lemmy_db_schema::SubscribedType;
+use lemmy_db_views_actor::structs::CommunityFollowerView;
+use serial_test::serial;
+
+#[actix_web::test]
+#[serial]
+async fn test_follow_remote_community() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+
+  let alice = alpha.create_user("alice").await.unwrap();
+  let alpha_community = alpha.create_community("main", &alice).await.unwrap();
+  let community_id = alpha_community.community.id;
+  // The creator of a community follows it
+  let followers = CommunityFollowerView::count_community_followers(&mut alpha.pool(), community_id)
+    .await
+    .unwrap();
+  assert_eq!(1, followers);
+
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_community = beta
+    .fetch_community(&alpha_community.community.actor_id)
+    .await
+    .unwrap();
+  assert!(!beta_community.local);
+
+  // The follow is accepted while the api call is running
+  let followed = beta
+    .follow_community(beta_community.id, true, &bob)
+    .await
+    .unwrap();
+  assert_eq!(SubscribedType::Subscribed, followed.subscribed);
+  let followers = CommunityFollowerView::count_community_followers(&mut alpha.pool(), community_id)
+    .await
+    .unwrap();
+  assert_eq!(2, followers);
+
+  let unfollowed = beta
+    .follow_community(beta_community.id, false, &bob)
+    .await
+    .unwrap();
+  assert_eq!(SubscribedType::NotSubscribed, unfollowed.subscribed);
+  let followers = CommunityFollowerView::count_community_followers(&mut alpha.pool(), community_id)
+    .await
+    .unwrap();
+  assert_eq!(1, followers);
+}
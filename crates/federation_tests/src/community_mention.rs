@@ -0,0 +1,64 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  comment::{CreateComment, EditComment},
+  post::CreatePost,
+};
+use lemmy_api_crud::{
+  comment::{create::create_comment, update::update_comment},
+  post::create::create_post,
+};
+use serial_test::serial;
+
+#[actix_web::test]
+#[serial]
+async fn test_community_mentions() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let main = alpha.create_community("main", &alice).await.unwrap();
+  let bob = beta.create_user("bob").await.unwrap();
+  let rust = beta.create_community("rust", &bob).await.unwrap();
+  let alpha_rust = alpha
+    .fetch_community(&rust.community.actor_id)
+    .await
+    .unwrap();
+
+  // A community which doesn't exist is left as text, without failing the post
+  let form = CreatePost {
+    name: "Mentions".to_string(),
+    body: Some("Ask in !rust@lemmy-beta.test or !nowhere@lemmy-beta.test".to_string()),
+    community_id: main.community.id,
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  let response = create_post(Json(form), alpha.context()).await.unwrap().0;
+  let post_id = response.post_view.post.id;
+  let mentioned: Vec<_> = response.community_mentions.iter().map(|c| c.id).collect();
+  assert_eq!(vec![alpha_rust.id], mentioned);
+
+  let form = CreateComment {
+    content: "Also see !main@lemmy-alpha.test and !rust@lemmy-beta.test".to_string(),
+    post_id,
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  let response = create_comment(Json(form), alpha.context()).await.unwrap().0;
+  let comment_id = response.comment_view.comment.id;
+  let mentioned: Vec<_> = response.community_mentions.iter().map(|c| c.id).collect();
+  assert_eq!(vec![main.community.id, alpha_rust.id], mentioned);
+
+  // Edits replace the mentions
+  let form = EditComment {
+    comment_id,
+    content: Some("Only !main@lemmy-alpha.test".to_string()),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  let response = update_comment(Json(form), alpha.context()).await.unwrap().0;
+  let mentioned: Vec<_> = response.community_mentions.iter().map(|c| c.id).collect();
+  assert_eq!(vec![main.community.id], mentioned);
+}
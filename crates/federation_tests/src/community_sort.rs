@@ -0,0 +1,96 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{community::EditCommunity, post::GetPosts};
+use lemmy_api_crud::community::update::update_community;
+use lemmy_apub::api::list_posts::list_posts;
+use lemmy_db_schema::{source::community::Community, traits::Crud, CommentSortType, SortType};
+use serial_test::serial;
+
+#[actix_web::test]
+#[serial]
+async fn test_community_default_sort() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let community_id = community.community.id;
+  for name in ["Older", "Newer"] {
+    alpha.create_post(name, community_id, &alice).await.unwrap();
+  }
+
+  let edit = EditCommunity {
+    community_id,
+    default_post_sort: Some(Some(SortType::Old)),
+    default_comment_sort: Some(Some(CommentSortType::Old)),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  update_community(Json(edit.clone()), alpha.context())
+    .await
+    .unwrap();
+
+  // The community default applies to logged in users, who also have their own default sort
+  let form = GetPosts {
+    community_id: Some(community_id),
+    auth: Some(alice.auth.clone()),
+    ..Default::default()
+  };
+  let posts = list_posts(Query(form.clone()), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .response
+    .posts;
+  assert_eq!("Older", posts[0].post.name);
+
+  // An explicit sort still takes precedence
+  let sorted = GetPosts {
+    sort: Some(SortType::New),
+    ..form
+  };
+  let posts = list_posts(Query(sorted), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .response
+    .posts;
+  assert_eq!("Newer", posts[0].post.name);
+
+  // Only the given defaults are changed, and null clears them
+  let edit = EditCommunity {
+    default_post_sort: None,
+    default_comment_sort: Some(None),
+    ..edit
+  };
+  update_community(Json(edit.clone()), alpha.context())
+    .await
+    .unwrap();
+  let community = Community::read(&mut alpha.pool(), community_id)
+    .await
+    .unwrap();
+  assert_eq!(Some(SortType::Old), community.default_post_sort);
+  assert_eq!(None, community.default_comment_sort);
+
+  let edit = EditCommunity {
+    default_post_sort: Some(None),
+    ..edit
+  };
+  update_community(Json(edit), alpha.context()).await.unwrap();
+  let community = Community::read(&mut alpha.pool(), community_id)
+    .await
+    .unwrap();
+  assert_eq!(None, community.default_post_sort);
+
+  // Clearing also works through the json api
+  let edit: EditCommunity = serde_json::from_value(serde_json::json!({
+    "community_id": community_id,
+    "default_post_sort": null,
+    "auth": "",
+  }))
+  .unwrap();
+  assert_eq!(Some(None), edit.default_post_sort);
+  assert_eq!(None, edit.default_comment_sort);
+}
@@ -0,0 +1,121 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::{self, Json, Query};
+use lemmy_api::community::verify::verify_community;
+use lemmy_api_common::{
+  community::{ListCommunities, VerifyCommunity},
+  context::LemmyContext,
+  site::Search,
+};
+use lemmy_api_crud::community::list::list_communities;
+use lemmy_apub::api::search::search;
+use lemmy_db_schema::{
+  source::person::{Person, PersonUpdateForm},
+  traits::Crud,
+  SearchType,
+};
+use lemmy_db_views_moderator::structs::{AdminVerifyCommunityView, ModlogListParams};
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+
+#[actix_web::test]
+#[serial]
+async fn test_verify_community() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let form = PersonUpdateForm {
+    admin: Some(true),
+    ..Default::default()
+  };
+  Person::update(&mut alpha.pool(), alice.person.id, &form)
+    .await
+    .unwrap();
+  let bob = alpha.create_user("bob").await.unwrap();
+  let official = alpha.create_community("official", &bob).await.unwrap();
+  alpha.create_community("other", &bob).await.unwrap();
+
+  // Only admins can verify, even the mods of the community can't
+  let verify_as = |auth| VerifyCommunity {
+    community_id: official.community.id,
+    verified: true,
+    auth,
+  };
+  let err = verify_community(Json(verify_as(bob.auth.clone())), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::NotAnAdmin, err.error_type);
+  let response = verify_community(Json(verify_as(alice.auth.clone())), alpha.context())
+    .await
+    .unwrap();
+  assert!(response.0.community_view.community.verified);
+
+  let form = ListCommunities {
+    verified_only: Some(true),
+    ..Default::default()
+  };
+  let web_context = web::Data::new(LemmyContext::clone(&alpha.context()));
+  let response = list_communities(Query(form), web_context).await.unwrap();
+  let communities = response.0.communities;
+  assert_eq!(1, communities.len());
+  assert_eq!(official.community.id, communities[0].community.id);
+
+  // Search only finds the verified community as well, for both search types which include
+  // communities
+  for type_ in [SearchType::Communities, SearchType::All] {
+    let form = Search {
+      q: "o".to_string(),
+      type_: Some(type_),
+      verified_only: Some(true),
+      ..Default::default()
+    };
+    let communities = search(Query(form.clone()), alpha.context())
+      .await
+      .unwrap()
+      .0
+      .response
+      .communities;
+    assert_eq!(1, communities.len());
+    assert_eq!(official.community.id, communities[0].community.id);
+    let form = Search {
+      verified_only: None,
+      ..form
+    };
+    let communities = search(Query(form), alpha.context())
+      .await
+      .unwrap()
+      .0
+      .response
+      .communities;
+    assert_eq!(2, communities.len());
+  }
+
+  let params = ModlogListParams {
+    community_id: Some(official.community.id),
+    mod_person_id: None,
+    other_person_id: None,
+    page: None,
+    limit: None,
+    hide_modlog_names: false,
+    since_id: None,
+    reason_query: None,
+  };
+  let entries = AdminVerifyCommunityView::list(&mut alpha.pool(), params)
+    .await
+    .unwrap();
+  assert_eq!(1, entries.len());
+  assert_eq!(
+    Some(alice.person.id),
+    entries[0].admin.as_ref().map(|a| a.id)
+  );
+  assert!(entries[0].admin_verify_community.verified);
+
+  // Other instances decide for themselves which communities are verified
+  let beta_community = beta
+    .fetch_community(&official.community.actor_id)
+    .await
+    .unwrap();
+  assert!(!beta_community.verified);
+}
@@ -0,0 +1,185 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{
+  instance::{TestInstance, TestUser},
+  TestFederation,
+};
+use activitypub_federation::config::FederationConfig;
+use actix_web::{
+  web::{self, Json, Query},
+  ResponseError,
+};
+use lemmy_api_common::{context::LemmyContext, post::CreatePost, site::GetSite};
+use lemmy_api_crud::{post::create::create_post, site::read::get_site};
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::secret::Secret,
+  utils::{build_pool, get_database_url},
+};
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  rate_limit::{RateLimitCell, RateLimitConfig},
+};
+use serial_test::serial;
+use std::{net::SocketAddr, time::Duration};
+use tokio::{
+  io::copy_bidirectional,
+  net::{TcpListener, TcpStream},
+  task::{JoinHandle, JoinSet},
+};
+use url::Url;
+
+/// Forwards connections to the test database, so that a restart of Postgres can be simulated by
+/// stopping and restarting the proxy.
+struct DatabaseProxy {
+  address: SocketAddr,
+  upstream: String,
+  task: Option<JoinHandle<()>>,
+}
+
+impl DatabaseProxy {
+  async fn start(upstream: String) -> Self {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut proxy = DatabaseProxy {
+      address: listener.local_addr().unwrap(),
+      upstream,
+      task: None,
+    };
+    proxy.serve(listener);
+    proxy
+  }
+
+  fn serve(&mut self, listener: TcpListener) {
+    let upstream = self.upstream.clone();
+    self.task = Some(tokio::spawn(async move {
+      // Dropped together with the task, which closes all connections
+      let mut connections = JoinSet::new();
+      while let Ok((mut client, _)) = listener.accept().await {
+        let upstream = upstream.clone();
+        connections.spawn(async move {
+          if let Ok(mut server) = TcpStream::connect(upstream).await {
+            copy_bidirectional(&mut client, &mut server).await.ok();
+          }
+        });
+      }
+    }));
+  }
+
+  /// Closes all connections and refuses new ones, like a database which is shut down.
+  async fn stop(&mut self) {
+    if let Some(task) = self.task.take() {
+      task.abort();
+      task.await.ok();
+    }
+  }
+
+  /// Accepts connections on the same address again.
+  async fn restart(&mut self) {
+    let listener = TcpListener::bind(self.address).await.unwrap();
+    self.serve(listener);
+  }
+
+  /// The url of the given database, with connections going through the proxy.
+  fn database_url(&self, database: &str) -> String {
+    let mut url = Url::parse(&get_database_url(None)).unwrap();
+    url.set_host(Some(&self.address.ip().to_string())).unwrap();
+    url.set_port(Some(self.address.port())).unwrap();
+    url.set_path(database);
+    url.to_string()
+  }
+}
+
+/// The address of the test database, which has to be reachable over tcp.
+fn database_address() -> String {
+  let url = Url::parse(&get_database_url(None)).unwrap();
+  let host = url.host_str().expect("test database needs a tcp address");
+  format!("{host}:{}", url.port().unwrap_or(5432))
+}
+
+/// The context of the instance, with a separate pool which connects through the proxy.
+async fn proxied_context(
+  instance: &TestInstance,
+  proxy: &DatabaseProxy,
+) -> LemmyResult<FederationConfig<LemmyContext>> {
+  let database_url = proxy.database_url("lemmy_federation_alpha");
+  let pool = build_pool(&database_url, 5, Duration::from_secs(1))?;
+  let secret = Secret::init(&mut (&pool).into()).await?;
+  let rate_limit_cell = RateLimitCell::new(RateLimitConfig::builder().build()).await;
+  let client = instance.context().client().clone();
+  let context = LemmyContext::create(pool, client.clone(), secret, rate_limit_cell)
+    .with_settings(instance.settings());
+  Ok(
+    FederationConfig::builder()
+      .domain(instance.settings().hostname.clone())
+      .app_data(context)
+      .client(client)
+      .debug(true)
+      .build()
+      .await?,
+  )
+}
+
+fn post_form(community_id: CommunityId, user: &TestUser) -> CreatePost {
+  CreatePost {
+    name: "Written during an outage".to_string(),
+    community_id,
+    auth: user.auth.clone(),
+    ..Default::default()
+  }
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_database_restart() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let form = post_form(community.community.id, &alice);
+
+  let mut proxy = DatabaseProxy::start(database_address()).await;
+  let config = proxied_context(alpha, &proxy).await.unwrap();
+  let context = config.to_request_data();
+  let pool = context.inner_pool();
+  let health = context.database_health();
+
+  // Open some connections, which are all broken by the restart
+  let mut connections = Vec::new();
+  for _ in 0..3 {
+    connections.push(pool.get().await.unwrap());
+  }
+  drop(connections);
+  assert!(health.check(pool).await);
+  // The site is read through actix' data, like the route does
+  let web_context = web::Data::new(LemmyContext::clone(&context));
+  let site = get_site(Query(GetSite::default()), web_context.clone())
+    .await
+    .unwrap();
+
+  proxy.stop().await;
+  let err = create_post(Json(form.clone()), config.to_request_data())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::DatabaseUnavailable, err.error_type);
+  assert_eq!(http::StatusCode::SERVICE_UNAVAILABLE, err.status_code());
+  assert!(!health.check(pool).await);
+  assert!(!health.is_available());
+  assert_eq!(0, pool.status().size);
+
+  // The site is still shown as it was before the outage
+  let cached_site = get_site(Query(GetSite::default()), web_context)
+    .await
+    .unwrap();
+  assert_eq!(site.site_view.site, cached_site.site_view.site);
+
+  // The pool recovers without creating a new one
+  proxy.restart().await;
+  assert!(health.check(pool).await);
+  assert!(health.is_available());
+  assert_eq!(2, health.state_changes());
+  assert_eq!(3, pool.status().size);
+  create_post(Json(form), config.to_request_data())
+    .await
+    .unwrap();
+}
@@ -0,0 +1,106 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::Query;
+use lemmy_api_common::{
+  post::{GetPosts, GetPostsResponse},
+  site::Search,
+};
+use lemmy_apub::api::{list_posts::list_posts, search::search};
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+
+fn fields(fields: &[&str]) -> Option<Vec<String>> {
+  Some(fields.iter().map(ToString::to_string).collect())
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_list_posts_field_selection() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  for i in 0..5 {
+    alpha
+      .create_post(&format!("Post {i}"), community.community.id, &alice)
+      .await
+      .unwrap();
+  }
+
+  let form = GetPosts {
+    community_id: Some(community.community.id),
+    ..Default::default()
+  };
+  let full = list_posts(Query(form.clone()), alpha.context())
+    .await
+    .unwrap();
+  // Without selected fields the response is the same as before
+  let full_json = serde_json::to_string(&full.0).unwrap();
+  assert_eq!(serde_json::to_string(&full.0.response).unwrap(), full_json);
+  let parsed: GetPostsResponse = serde_json::from_str(&full_json).unwrap();
+  assert_eq!(5, parsed.posts.len());
+
+  let form = GetPosts {
+    fields: fields(&["post.id", "post.name", "counts.score"]),
+    ..form
+  };
+  let selected = list_posts(Query(form), alpha.context()).await.unwrap();
+  let selected_json = serde_json::to_value(&selected.0).unwrap();
+  let posts = selected_json["posts"].as_array().unwrap();
+  assert_eq!(5, posts.len());
+  for post in posts {
+    let post = post.as_object().unwrap();
+    assert_eq!(2, post.len());
+    assert_eq!(2, post["post"].as_object().unwrap().len());
+    assert!(post["post"]["name"].as_str().unwrap().starts_with("Post "));
+    assert_eq!(1, post["counts"].as_object().unwrap().len());
+  }
+
+  // Only the ids and titles are a small part of the full views
+  let selected_size = serde_json::to_string(&selected.0).unwrap().len();
+  assert!(
+    selected_size * 10 < full_json.len(),
+    "selected {selected_size} bytes of {}",
+    full_json.len()
+  );
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_search_field_selection() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("selection", &alice).await.unwrap();
+  alpha
+    .create_post("Field selection", community.community.id, &alice)
+    .await
+    .unwrap();
+
+  // Fields of the other result types can be selected as well
+  let form = Search {
+    q: "selection".to_string(),
+    fields: fields(&["post.name", "community.name"]),
+    ..Default::default()
+  };
+  let response = search(Query(form), alpha.context()).await.unwrap();
+  let response = serde_json::to_value(&response.0).unwrap();
+  assert_eq!("Field selection", response["posts"][0]["post"]["name"]);
+  assert_eq!(1, response["posts"][0].as_object().unwrap().len());
+  assert_eq!("selection", response["communities"][0]["community"]["name"]);
+  assert_eq!(1, response["communities"][0].as_object().unwrap().len());
+
+  let form = Search {
+    q: "selection".to_string(),
+    fields: fields(&["post.name", "post.titel"]),
+    ..Default::default()
+  };
+  let err = search(Query(form), alpha.context()).await.unwrap_err();
+  let LemmyErrorType::UnknownFields(message) = err.error_type else {
+    panic!("wrong error type {:?}", err.error_type);
+  };
+  assert!(message.starts_with("post.titel. Valid fields are: "));
+  assert!(message.contains("community.name"));
+}
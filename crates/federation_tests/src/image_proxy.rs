@@ -0,0 +1,63 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::Json;
+use lemmy_api_common::post::{CreatePost, EditPost};
+use lemmy_api_crud::post::{create::create_post, update::update_post};
+use lemmy_db_schema::{
+  source::{
+    local_site::{LocalSite, LocalSiteUpdateForm},
+    post::Post,
+  },
+  traits::Crud,
+};
+use serial_test::serial;
+
+const BODY: &str = "A cat ![cat](https://remote.tld/cat.png) and a ![dog](/pictrs/image/dog.png)";
+
+#[actix_web::test]
+#[serial]
+async fn test_proxy_remote_images() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let form = LocalSiteUpdateForm {
+    proxy_remote_images: Some(true),
+    ..Default::default()
+  };
+  LocalSite::update(&mut alpha.pool(), &form).await.unwrap();
+
+  // Only the response links to the proxy, the post itself keeps the remote image
+  let form = CreatePost {
+    name: "Pets".to_string(),
+    body: Some(BODY.to_string()),
+    community_id: community.community.id,
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  let post_view = create_post(Json(form), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .post_view;
+  let proxied_body = post_view.post.body.unwrap();
+  assert!(proxied_body.contains("/api/v3/image_proxy?url=https%3A%2F%2Fremote.tld%2Fcat.png&sig="));
+  assert!(proxied_body.ends_with("![dog](/pictrs/image/dog.png)"));
+  let post = Post::read(&mut alpha.pool(), post_view.post.id)
+    .await
+    .unwrap();
+  assert_eq!(Some(BODY.to_string()), post.body);
+
+  // Clients send back the proxied links when the post is edited
+  let form = EditPost {
+    post_id: post.id,
+    body: Some(proxied_body),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  update_post(Json(form), alpha.context()).await.unwrap();
+  let post = Post::read(&mut alpha.pool(), post.id).await.unwrap();
+  assert_eq!(Some(BODY.to_string()), post.body);
+}
@@ -0,0 +1,73 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::{Json, Query};
+use lemmy_api::post::like::like_post;
+use lemmy_api_common::post::{CreatePostLike, GetPosts};
+use lemmy_apub::{api::list_posts::list_posts, http::inbox_admission::INBOX_ADMISSION};
+use lemmy_utils::settings::SETTINGS;
+use serial_test::serial;
+use std::time::{Duration, Instant};
+use tokio::{task::yield_now, time::timeout};
+
+/// Floods the community inbox of alpha with votes from beta, which go through the same handler
+/// as over the network, and checks that local requests on alpha are still answered promptly.
+#[actix_web::test]
+#[serial]
+async fn test_local_latency_while_inbox_saturated() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community_id = alpha
+    .create_community("main", &alice)
+    .await
+    .unwrap()
+    .community
+    .id;
+  let post = alpha
+    .create_post("Post", community_id, &alice)
+    .await
+    .unwrap()
+    .post;
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_post = beta.fetch_post(&post.ap_id).await.unwrap();
+
+  // Removing a vote which doesn't exist still sends an undo, without conflicting in the database
+  let flood_size = 2 * (INBOX_ADMISSION.concurrency() + SETTINGS.inbox_queue_size);
+  let flood: Vec<_> = (0..flood_size)
+    .map(|_| {
+      let form = CreatePostLike {
+        post_id: beta_post.id,
+        score: 0,
+        auth: bob.auth.clone(),
+      };
+      actix_web::rt::spawn(like_post(Json(form), beta.context()))
+    })
+    .collect();
+  timeout(Duration::from_secs(30), async {
+    while INBOX_ADMISSION.queued() == 0 {
+      yield_now().await;
+    }
+  })
+  .await
+  .unwrap();
+
+  for _ in 0..5 {
+    let start = Instant::now();
+    let form = GetPosts {
+      community_id: Some(community_id),
+      ..Default::default()
+    };
+    list_posts(Query(form), alpha.context()).await.unwrap();
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(INBOX_ADMISSION.processing() <= INBOX_ADMISSION.concurrency());
+  }
+
+  // Votes which were shed fail to be delivered, so only the tasks themselves have to finish
+  for vote in flood {
+    vote.await.unwrap().ok();
+  }
+  assert_eq!(0, INBOX_ADMISSION.processing());
+  assert_eq!(0, INBOX_ADMISSION.queued());
+}
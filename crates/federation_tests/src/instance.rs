@@ -0,0 +1,308 @@
+use crate::network::InProcessNetwork;
+use activitypub_federation::{
+  config::{Data, FederationConfig},
+  fetch::object_id::ObjectId,
+  http_signatures::generate_actor_keypair,
+};
+use actix_web::web::Json;
+use lemmy_api_common::{
+  comment::CreateComment,
+  community::{CreateCommunity, FollowCommunity},
+  context::LemmyContext,
+  post::CreatePost,
+  request::build_user_agent,
+  sensitive::Sensitive,
+  utils::{
+    generate_inbox_url,
+    generate_local_apub_endpoint,
+    generate_shared_inbox_url,
+    generate_site_inbox_url,
+    EndpointType,
+  },
+};
+use lemmy_apub::{
+  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  VerifyUrlData,
+  FEDERATION_HTTP_FETCH_LIMIT,
+};
+use lemmy_db_schema::{
+  newtypes::{CommunityId, DbUrl, PostId},
+  source::{
+    comment::Comment,
+    community::Community,
+    instance::Instance,
+    local_site::{LocalSite, LocalSiteInsertForm},
+    local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitInsertForm},
+    local_user::{LocalUser, LocalUserInsertForm},
+    person::{Person, PersonInsertForm},
+    post::Post,
+    secret::Secret,
+    site::{Site, SiteInsertForm},
+  },
+  traits::Crud,
+  utils::{build_db_pool_for_tests_named, naive_now, ActualDbPool, DbPool},
+};
+use lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_db_views_actor::structs::CommunityView;
+use lemmy_utils::{
+  claims::Claims,
+  error::LemmyResult,
+  rate_limit::{RateLimitCell, RateLimitConfig},
+  settings::{structs::Settings, SETTINGS},
+};
+use reqwest::Client;
+use reqwest_middleware::ClientBuilder;
+use std::ops::Deref;
+use url::Url;
+
+/// A Lemmy instance with its own database and settings, which is reachable by the other
+/// instances in the same network.
+pub struct TestInstance {
+  config: FederationConfig<LemmyContext>,
+  pool: ActualDbPool,
+}
+
+/// A local user of a test instance.
+pub struct TestUser {
+  pub person: Person,
+  pub auth: Sensitive<String>,
+}
+
+impl TestInstance {
+  /// Starts the instance with an empty copy of the database, and adds it to the network.
+  pub async fn start(
+    hostname: &str,
+    database: &str,
+    network: &InProcessNetwork,
+  ) -> LemmyResult<Self> {
+    let pool = build_db_pool_for_tests_named(database).await;
+
+    let mut settings = SETTINGS.clone();
+    settings.hostname = hostname.to_string();
+    settings.tls_enabled = false;
+    let settings: &'static Settings = Box::leak(Box::new(settings));
+
+    let secret = Secret::init(&mut (&pool).into()).await?;
+    let client = Client::builder()
+      .user_agent(build_user_agent(settings))
+      .build()?;
+    let client = ClientBuilder::new(client).with(network.clone()).build();
+    let rate_limit_cell = RateLimitCell::new(RateLimitConfig::builder().build()).await;
+    let context = LemmyContext::create(
+      pool.clone(),
+      client.clone(),
+      secret,
+      rate_limit_cell.clone(),
+    )
+    .with_settings(settings);
+
+    let config = FederationConfig::builder()
+      .domain(hostname)
+      .app_data(context)
+      .client(client)
+      .http_fetch_limit(FEDERATION_HTTP_FETCH_LIMIT)
+      .debug(true)
+      .http_signature_compat(true)
+      .url_verifier(Box::new(VerifyUrlData(pool.clone())))
+      .build()
+      .await?;
+
+    let instance = TestInstance { config, pool };
+    instance.create_site().await?;
+    network.serve(instance.config.clone());
+    Ok(instance)
+  }
+
+  pub fn context(&self) -> Data<LemmyContext> {
+    self.config.to_request_data()
+  }
+
+  pub fn pool(&self) -> DbPool<'_> {
+    DbPool::Pool(&self.pool)
+  }
+
+  pub fn settings(&self) -> &'static Settings {
+    self.context().settings()
+  }
+
+  /// Creates the rows which the setup of a new instance creates, with rate limits high enough for
+  /// tests.
+  async fn create_site(&self) -> LemmyResult<()> {
+    let pool = &mut self.pool();
+    let settings = self.settings();
+    let instance = Instance::read_or_create(pool, settings.hostname.clone()).await?;
+
+    let keypair = generate_actor_keypair()?;
+    let actor_id: DbUrl = Url::parse(&settings.get_protocol_and_hostname())?.into();
+    let site_form = SiteInsertForm::builder()
+      .name(settings.hostname.clone())
+      .instance_id(instance.id)
+      .actor_id(Some(actor_id.clone()))
+      .last_refreshed_at(Some(naive_now()))
+      .inbox_url(Some(generate_site_inbox_url(&actor_id)?))
+      .private_key(Some(keypair.private_key))
+      .public_key(Some(keypair.public_key))
+      .build();
+    let site = Site::create(pool, &site_form).await?;
+
+    let local_site_form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .site_setup(Some(true))
+      .build();
+    let local_site = LocalSite::create(pool, &local_site_form).await?;
+
+    let rate_limit_form = LocalSiteRateLimitInsertForm::builder()
+      .message(Some(999))
+      .post(Some(999))
+      .register(Some(999))
+      .image(Some(999))
+      .comment(Some(999))
+      .search(Some(999))
+      .local_site_id(local_site.id)
+      .build();
+    LocalSiteRateLimit::create(pool, &rate_limit_form).await?;
+    Ok(())
+  }
+
+  /// Registers a local user, without going through the signup checks.
+  pub async fn create_user(&self, name: &str) -> LemmyResult<TestUser> {
+    let pool = &mut self.pool();
+    let settings = self.settings();
+    let instance = Instance::read_or_create(pool, settings.hostname.clone()).await?;
+
+    let keypair = generate_actor_keypair()?;
+    let actor_id = generate_local_apub_endpoint(
+      EndpointType::Person,
+      name,
+      &settings.get_protocol_and_hostname(),
+    )?;
+    let person_form = PersonInsertForm::builder()
+      .name(name.to_string())
+      .actor_id(Some(actor_id.clone()))
+      .private_key(Some(keypair.private_key))
+      .public_key(keypair.public_key)
+      .inbox_url(Some(generate_inbox_url(&actor_id)?))
+      .shared_inbox_url(Some(generate_shared_inbox_url(&actor_id)?))
+      .instance_id(instance.id)
+      .build();
+    let person = Person::create(pool, &person_form).await?;
+
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(person.id)
+      .password_encrypted("federation-tests".to_string())
+      .build();
+    let local_user = LocalUser::create(pool, &local_user_form).await?;
+
+    let jwt = Claims::jwt(
+      local_user.id.0,
+      &self.context().secret().jwt_secret,
+      &settings.hostname,
+    )?;
+    Ok(TestUser {
+      person,
+      auth: Sensitive::new(jwt),
+    })
+  }
+
+  /// Creates a community through the api, with the user as its moderator.
+  pub async fn create_community(&self, name: &str, user: &TestUser) -> LemmyResult<CommunityView> {
+    let form = CreateCommunity {
+      name: name.to_string(),
+      title: name.to_string(),
+      auth: user.auth.clone(),
+      ..Default::default()
+    };
+    let response =
+      lemmy_api_crud::community::create::create_community(Json(form), self.context()).await?;
+    Ok(response.0.community_view)
+  }
+
+  /// Creates a post through the api, which sends it to the followers of the community.
+  pub async fn create_post(
+    &self,
+    name: &str,
+    community_id: CommunityId,
+    user: &TestUser,
+  ) -> LemmyResult<PostView> {
+    let form = CreatePost {
+      name: name.to_string(),
+      community_id,
+      auth: user.auth.clone(),
+      ..Default::default()
+    };
+    let response = lemmy_api_crud::post::create::create_post(Json(form), self.context()).await?;
+    Ok(response.0.post_view)
+  }
+
+  /// Creates a comment through the api, which sends it to the followers of the community.
+  pub async fn create_comment(
+    &self,
+    content: &str,
+    post_id: PostId,
+    user: &TestUser,
+  ) -> LemmyResult<CommentView> {
+    let form = CreateComment {
+      content: content.to_string(),
+      post_id,
+      auth: user.auth.clone(),
+      ..Default::default()
+    };
+    let response =
+      lemmy_api_crud::comment::create::create_comment(Json(form), self.context()).await?;
+    Ok(response.0.comment_view)
+  }
+
+  /// Follows or unfollows a community through the api.
+  pub async fn follow_community(
+    &self,
+    community_id: CommunityId,
+    follow: bool,
+    user: &TestUser,
+  ) -> LemmyResult<CommunityView> {
+    let form = FollowCommunity {
+      community_id,
+      follow,
+      auth: user.auth.clone(),
+    };
+    let response =
+      lemmy_api::community::follow::follow_community(Json(form), self.context()).await?;
+    Ok(response.0.community_view)
+  }
+
+  /// Fetches a community from another instance, like searching for its url does.
+  pub async fn fetch_community(&self, actor_id: &DbUrl) -> LemmyResult<Community> {
+    let actor_id: Url = actor_id.clone().into();
+    let community = ObjectId::<ApubCommunity>::from(actor_id)
+      .dereference(&self.context())
+      .await?;
+    Ok(community.deref().clone())
+  }
+
+  /// Fetches a user from another instance, like searching for their url does.
+  pub async fn fetch_person(&self, actor_id: &DbUrl) -> LemmyResult<Person> {
+    let actor_id: Url = actor_id.clone().into();
+    let person = ObjectId::<ApubPerson>::from(actor_id)
+      .dereference(&self.context())
+      .await?;
+    Ok(person.deref().clone())
+  }
+
+  /// Fetches a post from another instance, like searching for its url does.
+  pub async fn fetch_post(&self, ap_id: &DbUrl) -> LemmyResult<Post> {
+    let ap_id: Url = ap_id.clone().into();
+    let post = ObjectId::<ApubPost>::from(ap_id)
+      .dereference(&self.context())
+      .await?;
+    Ok(post.deref().clone())
+  }
+
+  /// Reads the copy of a post from another instance, if it was received.
+  pub async fn read_post(&self, ap_id: &DbUrl) -> LemmyResult<Option<Post>> {
+    Ok(Post::read_from_apub_id(&mut self.pool(), ap_id.clone().into()).await?)
+  }
+
+  /// Reads the copy of a comment from another instance, if it was received.
+  pub async fn read_comment(&self, ap_id: &DbUrl) -> LemmyResult<Option<Comment>> {
+    Ok(Comment::read_from_apub_id(&mut self.pool(), ap_id.clone().into()).await?)
+  }
+}
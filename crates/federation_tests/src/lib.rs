@@ -0,0 +1,78 @@
+use crate::{instance::TestInstance, network::InProcessNetwork};
+use lemmy_api_common::send_activity::MATCH_OUTGOING_ACTIVITIES;
+use lemmy_apub::activities::match_outgoing_activities;
+use lemmy_utils::error::LemmyResult;
+
+pub mod instance;
+pub mod network;
+
+#[cfg(test)]
+mod account_deletion;
+#[cfg(test)]
+mod comment;
+#[cfg(test)]
+mod community_ban;
+#[cfg(test)]
+mod community_flair;
+#[cfg(test)]
+mod community_follow;
+#[cfg(test)]
+mod community_mention;
+#[cfg(test)]
+mod community_sort;
+#[cfg(test)]
+mod community_verify;
+#[cfg(test)]
+mod database_health;
+#[cfg(test)]
+mod field_selection;
+#[cfg(test)]
+mod image_proxy;
+#[cfg(test)]
+mod inbox_admission;
+#[cfg(test)]
+mod mod_reason;
+#[cfg(test)]
+mod modlog;
+#[cfg(test)]
+mod notification;
+#[cfg(test)]
+mod nsfw;
+#[cfg(test)]
+mod permissions;
+#[cfg(test)]
+mod person;
+#[cfg(test)]
+mod person_block;
+#[cfg(test)]
+mod top_contributors;
+#[cfg(test)]
+mod user_data;
+#[cfg(test)]
+mod vote_policy;
+
+/// Two instances in one process which federate with each other, for integration tests. Each of
+/// them has its own database, so tests must run with `#[serial]`. In debug builds activities are
+/// sent synchronously, so they are received by the time that an api call returns.
+pub struct TestFederation {
+  pub alpha: TestInstance,
+  pub beta: TestInstance,
+}
+
+impl TestFederation {
+  /// Starts both instances with empty databases. Has to be called from an actix system, tests
+  /// need to use `#[actix_web::test]`.
+  pub async fn start() -> LemmyResult<Self> {
+    // The function may already be set by an earlier test in the same process
+    MATCH_OUTGOING_ACTIVITIES
+      .set(Box::new(move |d, c| {
+        Box::pin(match_outgoing_activities(d, c))
+      }))
+      .ok();
+
+    let network = InProcessNetwork::default();
+    let alpha = TestInstance::start("lemmy-alpha.test", "lemmy_federation_alpha", &network).await?;
+    let beta = TestInstance::start("lemmy-beta.test", "lemmy_federation_beta", &network).await?;
+    Ok(TestFederation { alpha, beta })
+  }
+}
@@ -0,0 +1,125 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{instance::TestInstance, TestFederation};
+use actix_web::web::{Json, Query};
+use lemmy_api::community::mod_reason_template::create_mod_reason_template;
+use lemmy_api_common::{
+  community::{CreateModReasonTemplate, GetCommunity},
+  post::RemovePost,
+};
+use lemmy_api_crud::post::remove::remove_post;
+use lemmy_apub::api::read_community::get_community;
+use lemmy_db_schema::source::local_site::{LocalSite, LocalSiteUpdateForm};
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+
+async fn require_mod_action_reason(instance: &TestInstance, require: bool) {
+  let form = LocalSiteUpdateForm {
+    require_mod_action_reason: Some(require),
+    ..Default::default()
+  };
+  LocalSite::update(&mut instance.pool(), &form)
+    .await
+    .unwrap();
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_require_mod_action_reason() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_community = beta
+    .fetch_community(&community.community.actor_id)
+    .await
+    .unwrap();
+  beta
+    .follow_community(beta_community.id, true, &bob)
+    .await
+    .unwrap();
+  let post = alpha
+    .create_post("Spam", community.community.id, &alice)
+    .await
+    .unwrap()
+    .post;
+
+  require_mod_action_reason(alpha, true).await;
+  let form = RemovePost {
+    post_id: post.id,
+    removed: true,
+    reason: Some(" ".to_string()),
+    auth: alice.auth.clone(),
+  };
+  let err = remove_post(Json(form.clone()), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::ModReasonRequired, err.error_type);
+
+  // Removals from other instances are accepted without a reason
+  require_mod_action_reason(alpha, false).await;
+  require_mod_action_reason(beta, true).await;
+  remove_post(Json(form.clone()), alpha.context())
+    .await
+    .unwrap();
+  let beta_post = beta.read_post(&post.ap_id).await.unwrap().unwrap();
+  assert!(beta_post.removed);
+
+  require_mod_action_reason(alpha, true).await;
+  let form = RemovePost {
+    removed: false,
+    reason: Some("Not spam after all".to_string()),
+    ..form
+  };
+  let response = remove_post(Json(form), alpha.context()).await.unwrap();
+  assert!(!response.post_view.post.removed);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_mod_reason_templates() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let bob = alpha.create_user("bob").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let community_id = community.community.id;
+
+  let form = CreateModReasonTemplate {
+    community_id,
+    reason: " Rule 1: No spam ".to_string(),
+    auth: alice.auth.clone(),
+  };
+  let template = create_mod_reason_template(Json(form.clone()), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .mod_reason_template;
+  assert_eq!("Rule 1: No spam", template.reason);
+
+  // Only mods can add templates, or see them
+  let form = CreateModReasonTemplate {
+    auth: bob.auth.clone(),
+    ..form
+  };
+  let err = create_mod_reason_template(Json(form), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::NotAModOrAdmin, err.error_type);
+
+  let get = |auth| GetCommunity {
+    id: Some(community_id),
+    name: None,
+    auth: Some(auth),
+  };
+  let response = get_community(Query(get(alice.auth.clone())), alpha.context())
+    .await
+    .unwrap();
+  assert_eq!(Some(vec![template]), response.0.mod_reason_templates);
+  let response = get_community(Query(get(bob.auth.clone())), alpha.context())
+    .await
+    .unwrap();
+  assert_eq!(None, response.0.mod_reason_templates);
+}
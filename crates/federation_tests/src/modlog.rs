@@ -0,0 +1,81 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::Json;
+use lemmy_api::{post::lock::lock_post, Perform};
+use lemmy_api_common::{
+  post::{LockPost, RemovePost},
+  site::GetModlog,
+};
+use lemmy_api_crud::post::remove::remove_post;
+use lemmy_db_schema::ModlogActionType;
+use serial_test::serial;
+
+#[actix_web::test]
+#[serial]
+async fn test_modlog_search() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let community_id = community.community.id;
+  let spam = alpha
+    .create_post("Buy cheap watches", community_id, &alice)
+    .await
+    .unwrap()
+    .post;
+  let flamewar = alpha
+    .create_post("Tabs or spaces?", community_id, &alice)
+    .await
+    .unwrap()
+    .post;
+
+  let form = RemovePost {
+    post_id: spam.id,
+    removed: true,
+    reason: Some("Advertising SPAM".to_string()),
+    auth: alice.auth.clone(),
+  };
+  remove_post(Json(form), alpha.context()).await.unwrap();
+  let form = LockPost {
+    post_id: flamewar.id,
+    locked: true,
+    auth: alice.auth.clone(),
+  };
+  lock_post(Json(form), alpha.context()).await.unwrap();
+
+  // Locks have no reason, so they never match a search
+  let modlog = GetModlog {
+    community_id: Some(community_id),
+    reason_query: Some(" spam ".to_string()),
+    ..Default::default()
+  }
+  .perform(&alpha.context())
+  .await
+  .unwrap();
+  assert_eq!(1, modlog.removed_posts.len());
+  assert!(modlog.locked_posts.is_empty());
+  let removal = &modlog.removed_posts[0].mod_remove_post;
+  assert_eq!(Some("Buy cheap watches".to_string()), removal.post_name);
+
+  // The pages are cut from the entries of all requested types, newest first
+  let page = |page| GetModlog {
+    community_id: Some(community_id),
+    actions: Some(vec![
+      ModlogActionType::ModRemovePost,
+      ModlogActionType::ModLockPost,
+    ]),
+    page: Some(page),
+    limit: Some(1),
+    ..Default::default()
+  };
+  let first = page(1).perform(&alpha.context()).await.unwrap();
+  assert_eq!(1, first.locked_posts.len());
+  assert!(first.removed_posts.is_empty());
+  let second = page(2).perform(&alpha.context()).await.unwrap();
+  assert!(second.locked_posts.is_empty());
+  assert_eq!(1, second.removed_posts.len());
+  let third = page(3).perform(&alpha.context()).await.unwrap();
+  assert!(third.locked_posts.is_empty() && third.removed_posts.is_empty());
+}
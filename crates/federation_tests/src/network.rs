@@ -0,0 +1,163 @@
+use activitypub_federation::config::{FederationConfig, FederationMiddleware};
+use actix_web::{
+  test::{self, TestRequest},
+  web::{Bytes, Data},
+  App,
+};
+use anyhow::anyhow;
+use http::{header::HOST, HeaderName, HeaderValue, Method, StatusCode};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_apub::http::routes;
+use reqwest::{Request, Response, ResponseBuilderExt};
+use reqwest_middleware::{Middleware, Next};
+use std::{
+  collections::HashMap,
+  rc::Rc,
+  sync::{Arc, Mutex},
+};
+use task_local_extensions::Extensions;
+use tokio::sync::{mpsc, oneshot};
+
+/// A request which was sent to one of the instances in the network.
+struct NetworkRequest {
+  method: Method,
+  path: String,
+  headers: Vec<(HeaderName, HeaderValue)>,
+  body: Bytes,
+  response: oneshot::Sender<NetworkResponse>,
+}
+
+struct NetworkResponse {
+  status: StatusCode,
+  headers: Vec<(HeaderName, HeaderValue)>,
+  body: Bytes,
+}
+
+/// Connects the http clients of the test instances without opening any sockets. Requests to a
+/// hostname which is served in the network are answered by the apub routes of that instance, so
+/// that signatures, serialization and inbox handlers run just like over a real connection.
+/// Requests to any other hostname fail.
+#[derive(Clone, Default)]
+pub struct InProcessNetwork {
+  hosts: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<NetworkRequest>>>>,
+}
+
+impl InProcessNetwork {
+  /// Answers requests for the domain of the config with the apub routes. This has to be called
+  /// from an actix system, like the one which `#[actix_web::test]` starts.
+  pub fn serve(&self, config: FederationConfig<LemmyContext>) {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<NetworkRequest>();
+    self
+      .hosts
+      .lock()
+      .expect("lock network hosts")
+      .insert(config.domain().to_string(), sender);
+
+    actix_web::rt::spawn(async move {
+      let context = LemmyContext::clone(&config.to_request_data());
+      let app = Rc::new(
+        test::init_service(
+          App::new()
+            .app_data(Data::new(context))
+            .wrap(FederationMiddleware::new(config))
+            .configure(routes::config),
+        )
+        .await,
+      );
+      while let Some(request) = receiver.recv().await {
+        // Handling an activity can fetch objects from this same instance, so requests must not
+        // wait for each other
+        let app = app.clone();
+        actix_web::rt::spawn(async move {
+          let mut test_request = TestRequest::default()
+            .method(request.method)
+            .uri(&request.path);
+          for header in request.headers {
+            test_request = test_request.append_header(header);
+          }
+          let test_request = test_request.set_payload(request.body).to_request();
+          let response = test::call_service(app.as_ref(), test_request).await;
+          let status = response.status();
+          let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+          let body = test::read_body(response).await;
+          // The sender may have stopped waiting for the response already
+          request
+            .response
+            .send(NetworkResponse {
+              status,
+              headers,
+              body,
+            })
+            .ok();
+        });
+      }
+    });
+  }
+}
+
+#[async_trait::async_trait]
+impl Middleware for InProcessNetwork {
+  async fn handle(
+    &self,
+    req: Request,
+    _extensions: &mut Extensions,
+    _next: Next<'_>,
+  ) -> reqwest_middleware::Result<Response> {
+    let url = req.url().clone();
+    let host = url.host_str().unwrap_or_default().to_string();
+    let instance = self
+      .hosts
+      .lock()
+      .expect("lock network hosts")
+      .get(&host)
+      .cloned();
+    let Some(instance) = instance else {
+      return Err(anyhow!("No instance in the network for {url}").into());
+    };
+
+    let path = match url.query() {
+      Some(query) => format!("{}?{query}", url.path()),
+      None => url.path().to_string(),
+    };
+    let mut headers = req.headers().clone();
+    if !headers.contains_key(HOST) {
+      headers.insert(
+        HOST,
+        HeaderValue::from_str(&host).map_err(anyhow::Error::from)?,
+      );
+    }
+    let body = req
+      .body()
+      .and_then(reqwest::Body::as_bytes)
+      .map(Bytes::copy_from_slice)
+      .unwrap_or_default();
+
+    let (sender, receiver) = oneshot::channel();
+    instance
+      .send(NetworkRequest {
+        method: req.method().clone(),
+        path,
+        headers: headers
+          .iter()
+          .map(|(name, value)| (name.clone(), value.clone()))
+          .collect(),
+        body,
+        response: sender,
+      })
+      .map_err(|_| anyhow!("{host} is not running anymore"))?;
+    let response = receiver
+      .await
+      .map_err(|_| anyhow!("{host} failed to answer {url}"))?;
+
+    let mut builder = http::Response::builder().status(response.status).url(url);
+    for (name, value) in response.headers {
+      builder = builder.header(name, value);
+    }
+    let response = builder.body(response.body).map_err(anyhow::Error::from)?;
+    Ok(Response::from(response))
+  }
+}
@@ -0,0 +1,97 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{
+  instance::{TestInstance, TestUser},
+  TestFederation,
+};
+use actix_web::web::Json;
+use lemmy_api::local_user::notifications::mark_replies_read::mark_replies_as_read;
+use lemmy_api_common::{comment::CreateComment, person::MarkCommentRepliesAsRead};
+use lemmy_api_crud::comment::create::create_comment;
+use lemmy_db_schema::{
+  newtypes::{CommentReplyId, PostId},
+  source::comment_reply::CommentReply,
+  traits::Crud,
+};
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+
+#[actix_web::test]
+#[serial]
+async fn test_mark_replies_as_read() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let bob = alpha.create_user("bob").await.unwrap();
+  let carol = alpha.create_user("carol").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let post = alpha
+    .create_post("Notifications", community.community.id, &alice)
+    .await
+    .unwrap()
+    .post;
+
+  // Bob replies to two comments of Alice and one of Carol
+  let first = reply(alpha, post.id, &alice, &bob).await;
+  let second = reply(alpha, post.id, &alice, &bob).await;
+  let for_carol = reply(alpha, post.id, &carol, &bob).await;
+
+  let mark_as_read = |reply_ids: Vec<CommentReplyId>| MarkCommentRepliesAsRead {
+    reply_ids,
+    read: true,
+    auth: alice.auth.clone(),
+  };
+  let form = mark_as_read(vec![first, second, for_carol]);
+  let err = mark_replies_as_read(Json(form), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::CouldntUpdateComment, err.error_type);
+  let unchanged = CommentReply::read(&mut alpha.pool(), first).await.unwrap();
+  assert!(!unchanged.read);
+
+  let form = mark_as_read(vec![first, second]);
+  let response = mark_replies_as_read(Json(form), alpha.context())
+    .await
+    .unwrap();
+  let views = response.0.comment_reply_views;
+  assert_eq!(2, views.len());
+  assert!(views.iter().all(|v| v.comment_reply.read));
+
+  let form = mark_as_read(vec![first; 101]);
+  let err = mark_replies_as_read(Json(form), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::TooManyNotificationsInBatch, err.error_type);
+}
+
+/// Replies to a new comment of the parent creator, and returns the reply they were notified of.
+async fn reply(
+  instance: &TestInstance,
+  post_id: PostId,
+  parent_creator: &TestUser,
+  user: &TestUser,
+) -> CommentReplyId {
+  let parent = instance
+    .create_comment("Parent", post_id, parent_creator)
+    .await
+    .unwrap()
+    .comment;
+  let form = CreateComment {
+    content: "Reply".to_string(),
+    post_id,
+    parent_id: Some(parent.id),
+    auth: user.auth.clone(),
+    ..Default::default()
+  };
+  let comment = create_comment(Json(form), instance.context())
+    .await
+    .unwrap()
+    .0
+    .comment_view
+    .comment;
+  CommentReply::read_by_comment(&mut instance.pool(), comment.id)
+    .await
+    .unwrap()
+    .id
+}
@@ -0,0 +1,119 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::Json;
+use lemmy_api_common::{comment::CreateComment, post::EditPost};
+use lemmy_api_crud::{comment::create::create_comment, post::update::update_post};
+use lemmy_db_schema::{
+  source::{
+    community::{Community, CommunityUpdateForm},
+    instance::{Instance, InstanceForm},
+    local_site::{LocalSite, LocalSiteUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+
+#[actix_web::test]
+#[serial]
+async fn test_nsfw_comment_not_sent_to_refusing_instance() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_community = beta
+    .fetch_community(&community.community.actor_id)
+    .await
+    .unwrap();
+  beta
+    .follow_community(beta_community.id, true, &bob)
+    .await
+    .unwrap();
+  let post = alpha
+    .create_post("Post", community.community.id, &alice)
+    .await
+    .unwrap()
+    .post;
+
+  // Beta declared that it refuses NSFW content
+  let beta_domain = beta.settings().hostname.clone();
+  let beta_instance = Instance::read_or_create(&mut alpha.pool(), beta_domain.clone())
+    .await
+    .unwrap();
+  let form = InstanceForm::builder()
+    .domain(beta_domain)
+    .refuses_nsfw(Some(true))
+    .build();
+  Instance::update(&mut alpha.pool(), beta_instance.id, &form)
+    .await
+    .unwrap();
+
+  let form = CreateComment {
+    content: "NSFW".to_string(),
+    post_id: post.id,
+    nsfw: Some(true),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  let nsfw_comment = create_comment(Json(form), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .comment_view
+    .comment;
+  let sfw_comment = alpha
+    .create_comment("SFW", post.id, &alice)
+    .await
+    .unwrap()
+    .comment;
+  assert!(beta
+    .read_comment(&nsfw_comment.ap_id)
+    .await
+    .unwrap()
+    .is_none());
+  assert!(beta
+    .read_comment(&sfw_comment.ap_id)
+    .await
+    .unwrap()
+    .is_some());
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_edit_post_in_nsfw_community() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let post = alpha
+    .create_post("Post", community.community.id, &alice)
+    .await
+    .unwrap()
+    .post;
+
+  // The community is marked as NSFW before the admin disallows NSFW content
+  let form = CommunityUpdateForm {
+    nsfw: Some(true),
+    ..Default::default()
+  };
+  Community::update(&mut alpha.pool(), community.community.id, &form)
+    .await
+    .unwrap();
+  let form = LocalSiteUpdateForm {
+    disallow_nsfw_content: Some(true),
+    ..Default::default()
+  };
+  LocalSite::update(&mut alpha.pool(), &form).await.unwrap();
+
+  let form = EditPost {
+    post_id: post.id,
+    name: Some("Edited".to_string()),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  let err = update_post(Json(form), alpha.context()).await.unwrap_err();
+  assert_eq!(LemmyErrorType::NsfwNotAllowed, err.error_type);
+}
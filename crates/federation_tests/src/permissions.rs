@@ -0,0 +1,235 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::TestFederation;
+use actix_web::web::{self, Json, Query};
+use lemmy_api::{comment::lock::lock_comment, post::lock::lock_post};
+use lemmy_api_common::{
+  comment::{CreateComment, GetComments, LockComment},
+  community::GetCommunity,
+  context::LemmyContext,
+  permissions::{DeniedReason, Permission},
+  post::{GetPost, LockPost},
+};
+use lemmy_api_crud::{comment::create::create_comment, post::read::get_post};
+use lemmy_apub::api::{list_comments::list_comments, read_community::get_community};
+use lemmy_db_schema::{
+  source::{
+    community::{Community, CommunityUpdateForm},
+    person_block::{PersonBlock, PersonBlockForm},
+  },
+  traits::{Blockable, Crud},
+};
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+
+fn denied(reason: DeniedReason) -> Permission {
+  Permission {
+    allowed: false,
+    reason: Some(reason),
+  }
+}
+
+const ALLOWED: Permission = Permission {
+  allowed: true,
+  reason: None,
+};
+
+#[actix_web::test]
+#[serial]
+async fn test_permissions_match_actions() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let bob = alpha.create_user("bob").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let community_id = community.community.id;
+  let post = alpha
+    .create_post("Rules", community_id, &alice)
+    .await
+    .unwrap()
+    .post;
+  let comment = alpha
+    .create_comment("Read them", post.id, &alice)
+    .await
+    .unwrap()
+    .comment;
+
+  let get_community_as = |auth| GetCommunity {
+    id: Some(community_id),
+    name: None,
+    auth,
+  };
+  let response = get_community(Query(get_community_as(None)), alpha.context())
+    .await
+    .unwrap();
+  let permissions = response.0.permissions.unwrap();
+  assert_eq!(denied(DeniedReason::NotLoggedIn), permissions.can_post);
+
+  // Only mods can post after the community is restricted, which both agree on
+  let form = CommunityUpdateForm {
+    posting_restricted_to_mods: Some(true),
+    ..Default::default()
+  };
+  Community::update(&mut alpha.pool(), community_id, &form)
+    .await
+    .unwrap();
+  let response = get_community(
+    Query(get_community_as(Some(bob.auth.clone()))),
+    alpha.context(),
+  )
+  .await
+  .unwrap();
+  let permissions = response.0.permissions.unwrap();
+  assert_eq!(denied(DeniedReason::OnlyModsCanPost), permissions.can_post);
+  assert_eq!(ALLOWED, permissions.can_comment);
+  assert_eq!(
+    denied(DeniedReason::NotAModOrAdmin),
+    permissions.can_moderate
+  );
+  let err = alpha
+    .create_post("Not allowed", community_id, &bob)
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::OnlyModsCanPostInCommunity, err.error_type);
+  let response = get_community(
+    Query(get_community_as(Some(alice.auth.clone()))),
+    alpha.context(),
+  )
+  .await
+  .unwrap();
+  let permissions = response.0.permissions.unwrap();
+  assert_eq!(ALLOWED, permissions.can_post);
+  assert_eq!(ALLOWED, permissions.can_moderate);
+
+  // Nobody can comment on a locked post
+  let form = LockPost {
+    post_id: post.id,
+    locked: true,
+    auth: alice.auth.clone(),
+  };
+  lock_post(Json(form), alpha.context()).await.unwrap();
+  let get_post_form = GetPost {
+    id: Some(post.id),
+    comment_id: None,
+    auth: Some(bob.auth.clone()),
+  };
+  let web_context = web::Data::new(LemmyContext::clone(&alpha.context()));
+  let response = get_post(Query(get_post_form), web_context).await.unwrap();
+  assert_eq!(
+    denied(DeniedReason::Locked),
+    response.0.permissions.can_comment
+  );
+  assert_eq!(ALLOWED, response.0.permissions.can_vote);
+  let err = alpha
+    .create_comment("Too late", post.id, &bob)
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::Locked, err.error_type);
+
+  // The comments only have permissions if they are requested
+  let form = GetComments {
+    post_id: Some(post.id),
+    auth: Some(bob.auth.clone()),
+    ..Default::default()
+  };
+  let response = list_comments(Query(form.clone()), alpha.context())
+    .await
+    .unwrap();
+  assert_eq!(None, response.0.response.comment_permissions);
+  let form = GetComments {
+    include_permissions: Some(true),
+    ..form
+  };
+  let response = list_comments(Query(form), alpha.context()).await.unwrap();
+  let comment_permissions = response.0.response.comment_permissions.unwrap();
+  let permissions = comment_permissions[&comment.id];
+  assert_eq!(denied(DeniedReason::Locked), permissions.can_comment);
+  assert_eq!(ALLOWED, permissions.can_report);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_comment_permissions_in_listing() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let bob = alpha.create_user("bob").await.unwrap();
+  let carol = alpha.create_user("carol").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let post = alpha
+    .create_post("Thread", community.community.id, &alice)
+    .await
+    .unwrap()
+    .post;
+  let parent = alpha
+    .create_comment("Parent", post.id, &alice)
+    .await
+    .unwrap()
+    .comment;
+  let reply = CreateComment {
+    content: "Reply".to_string(),
+    post_id: post.id,
+    parent_id: Some(parent.id),
+    auth: bob.auth.clone(),
+    ..Default::default()
+  };
+  let reply = create_comment(Json(reply), alpha.context())
+    .await
+    .unwrap()
+    .0
+    .comment_view
+    .comment;
+
+  let lock = LockComment {
+    comment_id: parent.id,
+    locked: true,
+    auth: alice.auth.clone(),
+  };
+  lock_comment(Json(lock), alpha.context()).await.unwrap();
+  let form = PersonBlockForm {
+    person_id: bob.person.id,
+    target_id: carol.person.id,
+  };
+  PersonBlock::block(&mut alpha.pool(), &form).await.unwrap();
+
+  // The lock of the parent applies to the listed reply, and only bob blocks carol
+  let form = GetComments {
+    post_id: Some(post.id),
+    include_permissions: Some(true),
+    auth: Some(carol.auth.clone()),
+    ..Default::default()
+  };
+  let response = list_comments(Query(form.clone()), alpha.context())
+    .await
+    .unwrap();
+  let comment_permissions = response.0.response.comment_permissions.unwrap();
+  let parent_permissions = comment_permissions[&parent.id];
+  let reply_permissions = comment_permissions[&reply.id];
+  assert_eq!(
+    denied(DeniedReason::ThreadLocked),
+    parent_permissions.can_comment
+  );
+  assert_eq!(
+    denied(DeniedReason::ThreadLocked),
+    reply_permissions.can_comment
+  );
+  assert_eq!(ALLOWED, parent_permissions.can_vote);
+  assert_eq!(
+    denied(DeniedReason::BlockedByCreator),
+    reply_permissions.can_vote
+  );
+
+  // Without the parent in the listing, the lock is still found
+  let form = GetComments {
+    parent_id: Some(reply.id),
+    ..form
+  };
+  let response = list_comments(Query(form), alpha.context()).await.unwrap();
+  let comment_permissions = response.0.response.comment_permissions.unwrap();
+  assert!(!comment_permissions.contains_key(&parent.id));
+  assert_eq!(
+    denied(DeniedReason::ThreadLocked),
+    comment_permissions[&reply.id].can_comment
+  );
+}
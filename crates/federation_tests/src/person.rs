@@ -0,0 +1,229 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{
+  instance::{TestInstance, TestUser},
+  TestFederation,
+};
+use activitypub_federation::traits::{ActivityHandler, Object};
+use actix_web::web::Json;
+use lemmy_api::local_user::{
+  clear_profile::clear_person_bio,
+  remove_content::remove_person_content,
+  save_settings::save_user_settings,
+};
+use lemmy_api_common::person::{ClearPersonBio, RemovePersonContent, SaveUserSettings};
+use lemmy_apub::{objects::person::ApubPerson, protocol::activities::person::update::UpdatePerson};
+use lemmy_db_schema::{
+  source::{
+    local_site::{LocalSite, LocalSiteUpdateForm},
+    person::{Person, PersonUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::LemmyErrorType;
+use serde_json::json;
+use serial_test::serial;
+
+/// Changes the bio through the api, and returns the profile as it was saved.
+async fn save_bio(instance: &TestInstance, user: &TestUser, bio: &str) -> Person {
+  let form = SaveUserSettings {
+    bio: Some(bio.to_string()),
+    auth: user.auth.clone(),
+    ..Default::default()
+  };
+  save_user_settings(Json(form), instance.context())
+    .await
+    .unwrap();
+  Person::read(&mut instance.pool(), user.person.id)
+    .await
+    .unwrap()
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_update_person() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+
+  // Alpha only sends the update to instances which it knows
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_community = beta
+    .fetch_community(&community.community.actor_id)
+    .await
+    .unwrap();
+  beta
+    .follow_community(beta_community.id, true, &bob)
+    .await
+    .unwrap();
+  let beta_alice = beta.fetch_person(&alice.person.actor_id).await.unwrap();
+  assert_eq!(None, beta_alice.bio);
+
+  let old_profile = save_bio(alpha, &alice, "First bio").await;
+  let new_profile = save_bio(alpha, &alice, "Second bio").await;
+  let beta_alice = beta.fetch_person(&alice.person.actor_id).await.unwrap();
+  assert_eq!(Some("Second bio".to_string()), beta_alice.bio);
+  assert_eq!(new_profile.updated, beta_alice.updated);
+
+  // An update which was delayed on the way doesn't overwrite the newer profile
+  let object = ApubPerson::from(old_profile)
+    .into_json(&alpha.context())
+    .await
+    .unwrap();
+  let stale: UpdatePerson = serde_json::from_value(json!({
+    "actor": alice.person.actor_id,
+    "to": ["https://www.w3.org/ns/activitystreams#Public"],
+    "object": object,
+    "type": "Update",
+    "id": "http://lemmy-alpha.test/activities/update/stale",
+  }))
+  .unwrap();
+  stale.verify(&beta.context()).await.unwrap();
+  stale.receive(&beta.context()).await.unwrap();
+  let beta_alice = beta.fetch_person(&alice.person.actor_id).await.unwrap();
+  assert_eq!(Some("Second bio".to_string()), beta_alice.bio);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_settings_without_profile_change() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let profile = save_bio(alpha, &alice, "Bio").await;
+  assert!(profile.updated.is_some());
+
+  // Neither other settings nor an unchanged bio touch the profile
+  let form = SaveUserSettings {
+    show_nsfw: Some(true),
+    auth: alice.auth.clone(),
+    ..Default::default()
+  };
+  save_user_settings(Json(form), alpha.context())
+    .await
+    .unwrap();
+  let unchanged = save_bio(alpha, &alice, "Bio").await;
+  assert_eq!(profile.updated, unchanged.updated);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_clear_person_bio() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let form = PersonUpdateForm {
+    admin: Some(true),
+    ..Default::default()
+  };
+  Person::update(&mut alpha.pool(), alice.person.id, &form)
+    .await
+    .unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_community = beta
+    .fetch_community(&community.community.actor_id)
+    .await
+    .unwrap();
+  beta
+    .follow_community(beta_community.id, true, &bob)
+    .await
+    .unwrap();
+
+  let carol = alpha.create_user("carol").await.unwrap();
+  save_bio(alpha, &carol, "Offensive bio").await;
+  let beta_carol = beta.fetch_person(&carol.person.actor_id).await.unwrap();
+  assert_eq!(Some("Offensive bio".to_string()), beta_carol.bio);
+
+  // The cleared profile is newer than the copy on beta, so beta takes it over
+  let form = ClearPersonBio {
+    person_id: carol.person.id,
+    reason: Some("Offensive".to_string()),
+    auth: alice.auth.clone(),
+  };
+  let response = clear_person_bio(Json(form), alpha.context()).await.unwrap();
+  let cleared = response.0.person_view.person;
+  assert_eq!(None, cleared.bio);
+  assert!(cleared.updated > beta_carol.updated);
+  let beta_carol = beta.fetch_person(&carol.person.actor_id).await.unwrap();
+  assert_eq!(None, beta_carol.bio);
+  assert_eq!(cleared.updated, beta_carol.updated);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_remove_person_content() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let form = PersonUpdateForm {
+    admin: Some(true),
+    ..Default::default()
+  };
+  Person::update(&mut alpha.pool(), alice.person.id, &form)
+    .await
+    .unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_community = beta
+    .fetch_community(&community.community.actor_id)
+    .await
+    .unwrap();
+  beta
+    .follow_community(beta_community.id, true, &bob)
+    .await
+    .unwrap();
+
+  let carol = alpha.create_user("carol").await.unwrap();
+  let post = alpha
+    .create_post("Spam", community.community.id, &carol)
+    .await
+    .unwrap()
+    .post;
+  let comment = alpha
+    .create_comment("More spam", post.id, &carol)
+    .await
+    .unwrap()
+    .comment;
+
+  // The removal is a mod action, so it needs a reason if the site requires one
+  let form = LocalSiteUpdateForm {
+    require_mod_action_reason: Some(true),
+    ..Default::default()
+  };
+  LocalSite::update(&mut alpha.pool(), &form).await.unwrap();
+  let form = RemovePersonContent {
+    person_id: carol.person.id,
+    reason: None,
+    auth: alice.auth.clone(),
+  };
+  let err = remove_person_content(Json(form.clone()), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::ModReasonRequired, err.error_type);
+  let beta_post = beta.read_post(&post.ap_id).await.unwrap().unwrap();
+  assert!(!beta_post.removed);
+
+  let form = RemovePersonContent {
+    reason: Some("Spam wave".to_string()),
+    ..form
+  };
+  let response = remove_person_content(Json(form.clone()), alpha.context())
+    .await
+    .unwrap();
+  assert_eq!(1, response.removed_posts);
+  assert_eq!(1, response.removed_comments);
+  let beta_post = beta.read_post(&post.ap_id).await.unwrap().unwrap();
+  assert!(beta_post.removed);
+  let beta_comment = beta.read_comment(&comment.ap_id).await.unwrap().unwrap();
+  assert!(beta_comment.removed);
+
+  // Only content which was added in between is removed by running it again
+  let response = remove_person_content(Json(form), alpha.context())
+    .await
+    .unwrap();
+  assert_eq!(0, response.removed_posts);
+  assert_eq!(0, response.removed_comments);
+}
@@ -0,0 +1,199 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{
+  instance::{TestInstance, TestUser},
+  TestFederation,
+};
+use actix_web::web::Json;
+use lemmy_api::{comment::like::like_comment, post::like::like_post};
+use lemmy_api_common::{
+  comment::{CreateComment, CreateCommentLike},
+  post::CreatePostLike,
+  private_message::CreatePrivateMessage,
+};
+use lemmy_api_crud::{
+  comment::create::create_comment,
+  private_message::create::create_private_message,
+};
+use lemmy_db_schema::{
+  newtypes::{CommentId, PostId},
+  source::{
+    comment::Comment,
+    comment_reply::CommentReply,
+    person_block::{PersonBlock, PersonBlockForm},
+    person_mention::PersonMention,
+    post::Post,
+  },
+  traits::Blockable,
+};
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+
+/// Creates Alice and Bob with a post each, where Alice blocks Bob. The block only keeps Bob from
+/// interacting with Alice, not the other way around.
+async fn setup(alpha: &TestInstance) -> (TestUser, TestUser, Post, Post) {
+  let alice = alpha.create_user("alice").await.unwrap();
+  let bob = alpha.create_user("bob").await.unwrap();
+  let community = alpha.create_community("main", &alice).await.unwrap();
+  let community_id = community.community.id;
+  let alice_post = alpha
+    .create_post("By Alice", community_id, &alice)
+    .await
+    .unwrap()
+    .post;
+  let bob_post = alpha
+    .create_post("By Bob", community_id, &bob)
+    .await
+    .unwrap()
+    .post;
+  let form = PersonBlockForm {
+    person_id: alice.person.id,
+    target_id: bob.person.id,
+  };
+  PersonBlock::block(&mut alpha.pool(), &form).await.unwrap();
+  (alice, bob, alice_post, bob_post)
+}
+
+async fn comment(
+  instance: &TestInstance,
+  content: &str,
+  post_id: PostId,
+  parent_id: Option<CommentId>,
+  user: &TestUser,
+) -> Comment {
+  let form = CreateComment {
+    content: content.to_string(),
+    post_id,
+    parent_id,
+    auth: user.auth.clone(),
+    ..Default::default()
+  };
+  create_comment(Json(form), instance.context())
+    .await
+    .unwrap()
+    .0
+    .comment_view
+    .comment
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_block_replies() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let (alice, bob, alice_post, bob_post) = setup(alpha).await;
+
+  // Alice isn't notified of Bob's reply
+  let parent = comment(alpha, "Parent", alice_post.id, None, &alice).await;
+  let reply = comment(alpha, "Reply", alice_post.id, Some(parent.id), &bob).await;
+  assert!(CommentReply::read_by_comment(&mut alpha.pool(), reply.id)
+    .await
+    .is_err());
+
+  // Bob is still notified of Alice's reply
+  let parent = comment(alpha, "Parent", bob_post.id, None, &bob).await;
+  let reply = comment(alpha, "Reply", bob_post.id, Some(parent.id), &alice).await;
+  let comment_reply = CommentReply::read_by_comment(&mut alpha.pool(), reply.id)
+    .await
+    .unwrap();
+  assert_eq!(bob.person.id, comment_reply.recipient_id);
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_block_mentions() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let (alice, bob, alice_post, _bob_post) = setup(alpha).await;
+  let hostname = &alpha.settings().hostname;
+
+  // Alice isn't notified of Bob's mention
+  let content = format!("Hello @alice@{hostname}");
+  let mention = comment(alpha, &content, alice_post.id, None, &bob).await;
+  assert!(PersonMention::read_by_comment_and_person(
+    &mut alpha.pool(),
+    mention.id,
+    alice.person.id
+  )
+  .await
+  .is_err());
+
+  // Bob is still notified of Alice's mention
+  let content = format!("Hello @bob@{hostname}");
+  let mention = comment(alpha, &content, alice_post.id, None, &alice).await;
+  assert!(
+    PersonMention::read_by_comment_and_person(&mut alpha.pool(), mention.id, bob.person.id)
+      .await
+      .is_ok()
+  );
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_block_private_messages() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let (alice, bob, _alice_post, _bob_post) = setup(alpha).await;
+
+  let message = |sender: &TestUser, recipient: &TestUser| CreatePrivateMessage {
+    content: "Hello".to_string(),
+    recipient_id: recipient.person.id,
+    auth: sender.auth.clone(),
+  };
+  let err = create_private_message(Json(message(&bob, &alice)), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::PersonIsBlocked, err.error_type);
+  create_private_message(Json(message(&alice, &bob)), alpha.context())
+    .await
+    .unwrap();
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_block_votes() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let (alice, bob, alice_post, bob_post) = setup(alpha).await;
+  let alice_comment = comment(alpha, "By Alice", alice_post.id, None, &alice).await;
+  let bob_comment = comment(alpha, "By Bob", alice_post.id, None, &bob).await;
+
+  let post_vote = |post_id, score, user: &TestUser| CreatePostLike {
+    post_id,
+    score,
+    auth: user.auth.clone(),
+  };
+  let comment_vote = |comment_id, score, user: &TestUser| CreateCommentLike {
+    comment_id,
+    score,
+    auth: user.auth.clone(),
+  };
+
+  // Bob can't vote on Alice's content, but can take back a vote
+  let err = like_post(Json(post_vote(alice_post.id, 1, &bob)), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::PersonIsBlocked, err.error_type);
+  like_post(Json(post_vote(alice_post.id, 0, &bob)), alpha.context())
+    .await
+    .unwrap();
+  let err = like_comment(
+    Json(comment_vote(alice_comment.id, -1, &bob)),
+    alpha.context(),
+  )
+  .await
+  .unwrap_err();
+  assert_eq!(LemmyErrorType::PersonIsBlocked, err.error_type);
+
+  // Alice can still vote on Bob's content
+  like_post(Json(post_vote(bob_post.id, 1, &alice)), alpha.context())
+    .await
+    .unwrap();
+  like_comment(
+    Json(comment_vote(bob_comment.id, -1, &alice)),
+    alpha.context(),
+  )
+  .await
+  .unwrap();
+}
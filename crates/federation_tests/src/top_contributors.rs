@@ -0,0 +1,195 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{
+  instance::{TestInstance, TestUser},
+  TestFederation,
+};
+use lemmy_db_schema::{
+  newtypes::{CommunityId, PersonId},
+  source::{
+    comment::{Comment, CommentUpdateForm},
+    community::{CommunityPersonBan, CommunityPersonBanForm},
+    local_user::{LocalUser, LocalUserUpdateForm},
+    person::{Person, PersonUpdateForm},
+    post::{Post, PostUpdateForm},
+  },
+  traits::{Bannable, Crud},
+  ContributorRange,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::{
+  community_contributor_view::ContributorWeights,
+  structs::CommunityContributorView,
+};
+use serial_test::serial;
+
+/// Creates a user who wrote one post in the community.
+async fn contributor(alpha: &TestInstance, name: &str, community_id: CommunityId) -> TestUser {
+  let user = alpha.create_user(name).await.unwrap();
+  alpha
+    .create_post(&format!("By {name}"), community_id, &user)
+    .await
+    .unwrap();
+  user
+}
+
+async fn ranked(
+  alpha: &TestInstance,
+  community_id: CommunityId,
+  post: i32,
+  comment: i32,
+) -> Vec<CommunityContributorView> {
+  let weights = ContributorWeights {
+    post,
+    comment,
+    score: 0,
+  };
+  CommunityContributorView::list(
+    &mut alpha.pool(),
+    community_id,
+    ContributorRange::Week,
+    weights,
+    10,
+  )
+  .await
+  .unwrap()
+}
+
+fn person_ids(contributors: &[CommunityContributorView]) -> Vec<PersonId> {
+  contributors.iter().map(|c| c.person.id).collect()
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_top_contributors() {
+  let federation = TestFederation::start().await.unwrap();
+  let alpha = &federation.alpha;
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community_id = alpha
+    .create_community("main", &alice)
+    .await
+    .unwrap()
+    .community
+    .id;
+
+  // Alice writes two posts, Bob one post and three comments
+  for name in ["First", "Second"] {
+    alpha.create_post(name, community_id, &alice).await.unwrap();
+  }
+  let bob = alpha.create_user("bob").await.unwrap();
+  let post_id = alpha
+    .create_post("By bob", community_id, &bob)
+    .await
+    .unwrap()
+    .post
+    .id;
+  let deleted_post_id = alpha
+    .create_post("Deleted", community_id, &bob)
+    .await
+    .unwrap()
+    .post
+    .id;
+  alpha
+    .create_comment("On a deleted post", deleted_post_id, &bob)
+    .await
+    .unwrap();
+  let form = PostUpdateForm {
+    deleted: Some(true),
+    ..Default::default()
+  };
+  Post::update(&mut alpha.pool(), deleted_post_id, &form)
+    .await
+    .unwrap();
+  for content in ["One", "Two", "Three", "Removed"] {
+    let comment = alpha
+      .create_comment(content, post_id, &bob)
+      .await
+      .unwrap()
+      .comment;
+    if content == "Removed" {
+      let form = CommentUpdateForm {
+        removed: Some(true),
+        ..Default::default()
+      };
+      Comment::update(&mut alpha.pool(), comment.id, &form)
+        .await
+        .unwrap();
+    }
+  }
+
+  // None of these are ranked, although each of them wrote a post
+  let bot = contributor(alpha, "bot", community_id).await;
+  let form = PersonUpdateForm {
+    bot_account: Some(true),
+    ..Default::default()
+  };
+  Person::update(&mut alpha.pool(), bot.person.id, &form)
+    .await
+    .unwrap();
+  let shy = contributor(alpha, "shy", community_id).await;
+  let local_user_id = LocalUserView::read_person(&mut alpha.pool(), shy.person.id)
+    .await
+    .unwrap()
+    .local_user
+    .id;
+  let form = LocalUserUpdateForm {
+    exclude_from_leaderboards: Some(true),
+    ..Default::default()
+  };
+  LocalUser::update(&mut alpha.pool(), local_user_id, &form)
+    .await
+    .unwrap();
+  let banned = contributor(alpha, "banned", community_id).await;
+  let form = CommunityPersonBanForm {
+    community_id,
+    person_id: banned.person.id,
+    expires: None,
+  };
+  CommunityPersonBan::ban(&mut alpha.pool(), &form)
+    .await
+    .unwrap();
+
+  // The deleted post with its comment and the removed comment aren't counted
+  let contributors = ranked(alpha, community_id, 10, 1).await;
+  assert_eq!(
+    vec![alice.person.id, bob.person.id],
+    person_ids(&contributors)
+  );
+  assert_eq!(2, contributors[0].post_count);
+  assert_eq!(0, contributors[0].comment_count);
+  assert_eq!(1, contributors[1].post_count);
+  assert_eq!(3, contributors[1].comment_count);
+  // Every post and comment has the upvote of its creator
+  assert_eq!(4, contributors[1].score);
+
+  // With other weights Bob ranks higher
+  let contributors = ranked(alpha, community_id, 1, 10).await;
+  assert_eq!(
+    vec![bob.person.id, alice.person.id],
+    person_ids(&contributors)
+  );
+
+  let commenters =
+    CommunityContributorView::list_top_commenters(&mut alpha.pool(), community_id, 7, 10)
+      .await
+      .unwrap();
+  assert_eq!(vec![bob.person.id], person_ids(&commenters));
+  assert_eq!(3, commenters[0].comment_count);
+
+  // All time counts are only there once the rollup was built
+  let all_time = CommunityContributorView::list(
+    &mut alpha.pool(),
+    community_id,
+    ContributorRange::AllTime,
+    ContributorWeights {
+      post: 1,
+      comment: 1,
+      score: 1,
+    },
+    10,
+  )
+  .await
+  .unwrap();
+  assert!(all_time.is_empty());
+}
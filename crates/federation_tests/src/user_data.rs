@@ -0,0 +1,130 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{
+  instance::{TestInstance, TestUser},
+  TestFederation,
+};
+use actix_web::web::{Json, Query};
+use lemmy_api_common::person::{
+  GetUserDataImportJob,
+  ImportUserData,
+  UserDataExport,
+  UserDataImportJobResponse,
+};
+use lemmy_apub::api::user_data::{get_user_data_import_job, import_user_data};
+use lemmy_db_schema::{newtypes::DbUrl, source::person::Person, traits::Crud};
+use lemmy_db_views_actor::structs::CommunityFollowerView;
+use lemmy_utils::error::LemmyErrorType;
+use serial_test::serial;
+use std::time::Duration;
+use url::Url;
+
+/// Polls the import job until the background import is finished.
+async fn wait_for_import(
+  instance: &TestInstance,
+  user: &TestUser,
+  job_id: i32,
+) -> UserDataImportJobResponse {
+  for _ in 0..200 {
+    let form = GetUserDataImportJob {
+      job_id,
+      auth: user.auth.clone(),
+    };
+    let response = get_user_data_import_job(Query(form), instance.context())
+      .await
+      .unwrap()
+      .0;
+    if response.job.finished.is_some() {
+      return response;
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+  }
+  panic!("User data import {job_id} didn't finish");
+}
+
+async fn followed_communities(instance: &TestInstance, user: &TestUser) -> Vec<DbUrl> {
+  CommunityFollowerView::for_person(&mut instance.pool(), user.person.id)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|f| f.community.actor_id)
+    .collect()
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_import_user_data() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let bob = beta.create_user("bob").await.unwrap();
+  let main = beta.create_community("main", &bob).await.unwrap().community;
+  let spam = beta.create_community("spam", &bob).await.unwrap().community;
+
+  let alice = alpha.create_user("alice").await.unwrap();
+  let alpha_spam = alpha.fetch_community(&spam.actor_id).await.unwrap();
+  alpha
+    .follow_community(alpha_spam.id, true, &alice)
+    .await
+    .unwrap();
+  assert_eq!(
+    vec![spam.actor_id.clone()],
+    followed_communities(alpha, &alice).await
+  );
+
+  let missing: DbUrl = Url::parse("http://lemmy-beta.test/c/missing")
+    .unwrap()
+    .into();
+  let data = UserDataExport {
+    display_name: Some("x".repeat(500)),
+    bio: Some("Imported bio".to_string()),
+    followed_communities: vec![main.actor_id.clone(), missing.clone()],
+    blocked_communities: vec![spam.actor_id.clone()],
+    ..Default::default()
+  };
+  let form = ImportUserData {
+    data,
+    auth: alice.auth.clone(),
+  };
+  let response = import_user_data(Json(form), alpha.context())
+    .await
+    .unwrap()
+    .0;
+  assert_eq!(3, response.job.items_total);
+
+  // The invalid display name and the missing community are skipped, without stopping the import
+  let response = wait_for_import(alpha, &alice, response.job.id).await;
+  assert_eq!(3, response.job.items_done);
+  let failed: Vec<_> = response.failures.iter().map(|f| f.item.as_str()).collect();
+  assert_eq!(vec!["display_name", missing.as_str()], failed);
+  let person = Person::read(&mut alpha.pool(), alice.person.id)
+    .await
+    .unwrap();
+  assert_eq!(None, person.display_name);
+  assert_eq!(Some("Imported bio".to_string()), person.bio);
+
+  // The follow went through the same path as FollowCommunity, and blocking unfollowed spam
+  assert_eq!(
+    vec![main.actor_id.clone()],
+    followed_communities(alpha, &alice).await
+  );
+  let beta_alice = beta.fetch_person(&alice.person.actor_id).await.unwrap();
+  let beta_followed: Vec<_> = CommunityFollowerView::for_person(&mut beta.pool(), beta_alice.id)
+    .await
+    .unwrap()
+    .into_iter()
+    .map(|f| f.community.actor_id)
+    .collect();
+  assert_eq!(vec![main.actor_id], beta_followed);
+
+  // Only the user who started the import can see it
+  let carol = alpha.create_user("carol").await.unwrap();
+  let form = GetUserDataImportJob {
+    job_id: response.job.id,
+    auth: carol.auth.clone(),
+  };
+  let err = get_user_data_import_job(Query(form), alpha.context())
+    .await
+    .unwrap_err();
+  assert_eq!(LemmyErrorType::CouldntFindUserDataImportJob, err.error_type);
+}
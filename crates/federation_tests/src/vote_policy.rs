@@ -0,0 +1,97 @@
+#![allow(clippy::unwrap_used)]
+#![allow(clippy::indexing_slicing)]
+
+use crate::{
+  instance::{TestInstance, TestUser},
+  TestFederation,
+};
+use actix_web::web::Json;
+use lemmy_api::post::like::like_post;
+use lemmy_api_common::post::CreatePostLike;
+use lemmy_db_schema::{
+  aggregates::structs::PostAggregates,
+  newtypes::{CommunityId, PostId},
+  source::community::{Community, CommunityUpdateForm},
+  traits::Crud,
+};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use serial_test::serial;
+
+async fn upvote(instance: &TestInstance, post_id: PostId, user: &TestUser) -> LemmyResult<()> {
+  let form = CreatePostLike {
+    post_id,
+    score: 1,
+    auth: user.auth.clone(),
+  };
+  like_post(Json(form), instance.context()).await?;
+  Ok(())
+}
+
+async fn score(instance: &TestInstance, post_id: PostId) -> i64 {
+  PostAggregates::read(&mut instance.pool(), post_id)
+    .await
+    .unwrap()
+    .score
+}
+
+/// Sets the policy directly in the database, without federating it.
+async fn only_followers_can_vote(instance: &TestInstance, community_id: CommunityId) {
+  let form = CommunityUpdateForm {
+    only_followers_can_vote: Some(true),
+    ..Default::default()
+  };
+  Community::update(&mut instance.pool(), community_id, &form)
+    .await
+    .unwrap();
+}
+
+#[actix_web::test]
+#[serial]
+async fn test_only_followers_can_vote() {
+  let federation = TestFederation::start().await.unwrap();
+  let (alpha, beta) = (&federation.alpha, &federation.beta);
+  let alice = alpha.create_user("alice").await.unwrap();
+  let community = alpha
+    .create_community("main", &alice)
+    .await
+    .unwrap()
+    .community;
+  let post = alpha
+    .create_post("Post", community.id, &alice)
+    .await
+    .unwrap()
+    .post;
+  let bob = beta.create_user("bob").await.unwrap();
+  let beta_community = beta.fetch_community(&community.actor_id).await.unwrap();
+  beta
+    .follow_community(beta_community.id, true, &bob)
+    .await
+    .unwrap();
+  let beta_post = beta.fetch_post(&post.ap_id).await.unwrap();
+  only_followers_can_vote(alpha, community.id).await;
+
+  // Local users have to follow the community to vote
+  let carol = alpha.create_user("carol").await.unwrap();
+  let err = upvote(alpha, post.id, &carol).await.unwrap_err();
+  assert_eq!(LemmyErrorType::NotAllowedToVote, err.error_type);
+  assert_eq!(1, score(alpha, post.id).await);
+
+  // Beta doesn't know about the policy, so the vote of a user who doesn't follow is sent, and
+  // dropped by the home instance
+  let eve = beta.create_user("eve").await.unwrap();
+  let beta_score = score(beta, beta_post.id).await;
+  upvote(beta, beta_post.id, &eve).await.unwrap();
+  assert_eq!(beta_score + 1, score(beta, beta_post.id).await);
+  assert_eq!(1, score(alpha, post.id).await);
+
+  // Beta only knows its own followers, but still counts the votes which the home instance
+  // announces
+  only_followers_can_vote(beta, beta_community.id).await;
+  alpha
+    .follow_community(community.id, true, &carol)
+    .await
+    .unwrap();
+  upvote(alpha, post.id, &carol).await.unwrap();
+  assert_eq!(2, score(alpha, post.id).await);
+  assert_eq!(beta_score + 2, score(beta, beta_post.id).await);
+}
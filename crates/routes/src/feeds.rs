@@ -139,6 +139,7 @@ async fn get_feed_data(
     sort: (Some(sort_type)),
     limit: (Some(limit)),
     page: (Some(page)),
+    full_body: true,
     ..Default::default()
   }
   .list(&mut context.pool())
@@ -259,6 +260,7 @@ async fn get_feed_user(
     creator_id: (Some(person.id)),
     limit: (Some(*limit)),
     page: (Some(*page)),
+    full_body: true,
     ..Default::default()
   }
   .list(pool)
@@ -293,6 +295,7 @@ async fn get_feed_community(
     community_id: (Some(community.id)),
     limit: (Some(*limit)),
     page: (Some(*page)),
+    full_body: true,
     ..Default::default()
   }
   .list(pool)
@@ -334,6 +337,7 @@ async fn get_feed_front(
     sort: (Some(*sort_type)),
     limit: (Some(*limit)),
     page: (Some(*page)),
+    full_body: true,
     ..Default::default()
   }
   .list(pool)
@@ -0,0 +1,93 @@
+use actix_web::{web, HttpResponse};
+use lemmy_api_common::{
+  context::LemmyContext,
+  pictrs_breaker::{BreakerState, PICTRS_BREAKER},
+};
+use serde::Serialize;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+  cfg.route("/health", web::get().to(health));
+}
+
+/// Reports if the instance can serve requests. Without the database it can't, so that is a
+/// `503 Service Unavailable`. Without pictrs it can, but images are unavailable.
+async fn health(context: web::Data<LemmyContext>) -> HttpResponse {
+  // Also lets the instance recover faster when the health is checked often
+  let pool = context.inner_pool();
+  let database = if context.database_health().check(pool).await {
+    DependencyStatus::Ok
+  } else {
+    DependencyStatus::Unavailable
+  };
+  let status = pool.status();
+  let database_pool = PoolStatus {
+    max_size: status.max_size,
+    size: status.size,
+    available: status.available,
+  };
+  let pictrs = context
+    .settings()
+    .pictrs_config()
+    .ok()
+    .map(|_| match PICTRS_BREAKER.state() {
+      BreakerState::Closed => DependencyStatus::Ok,
+      BreakerState::Open | BreakerState::HalfOpen => DependencyStatus::Unavailable,
+    });
+
+  let status = if database == DependencyStatus::Unavailable {
+    HealthStatus::Unavailable
+  } else if pictrs == Some(DependencyStatus::Unavailable) {
+    HealthStatus::Degraded
+  } else {
+    HealthStatus::Ok
+  };
+  let health = Health {
+    status,
+    dependencies: Dependencies { database, pictrs },
+    database_pool,
+  };
+  match status {
+    HealthStatus::Unavailable => HttpResponse::ServiceUnavailable().json(health),
+    HealthStatus::Ok | HealthStatus::Degraded => HttpResponse::Ok().json(health),
+  }
+}
+
+#[derive(Serialize)]
+struct Health {
+  status: HealthStatus,
+  dependencies: Dependencies,
+  database_pool: PoolStatus,
+}
+
+#[derive(Serialize)]
+struct Dependencies {
+  database: DependencyStatus,
+  /// None if pictrs isn't set up
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pictrs: Option<DependencyStatus>,
+}
+
+#[derive(Serialize)]
+struct PoolStatus {
+  max_size: usize,
+  /// Open connections
+  size: usize,
+  /// Idle connections, or the number of requests waiting for one if negative
+  available: isize,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum HealthStatus {
+  Ok,
+  /// Some features are unavailable
+  Degraded,
+  Unavailable,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum DependencyStatus {
+  Ok,
+  Unavailable,
+}
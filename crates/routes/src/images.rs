@@ -2,7 +2,16 @@ use actix_web::{
   body::BodyStream,
   error,
   http::{
-    header::{HeaderName, ACCEPT_ENCODING, HOST},
+    header::{
+      HeaderName,
+      ACCEPT_ENCODING,
+      CACHE_CONTROL,
+      CONTENT_LENGTH,
+      CONTENT_TYPE,
+      HOST,
+      LOCATION,
+      X_CONTENT_TYPE_OPTIONS,
+    },
     StatusCode,
   },
   web,
@@ -11,12 +20,30 @@ use actix_web::{
   HttpResponse,
 };
 use futures::stream::{Stream, StreamExt};
-use lemmy_api_common::{context::LemmyContext, utils::local_user_view_from_jwt};
-use lemmy_db_schema::source::local_site::LocalSite;
-use lemmy_utils::{claims::Claims, rate_limit::RateLimitCell, REQWEST_TIMEOUT};
-use reqwest::Body;
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::verify_image_proxy_signature,
+  pictrs_breaker::PICTRS_BREAKER,
+  request::build_user_agent,
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::{
+  images::{LocalImage, LocalImageForm},
+  local_site::LocalSite,
+};
+use lemmy_utils::{rate_limit::RateLimitCell, REQWEST_TIMEOUT};
+use reqwest::{redirect::Policy, Body};
 use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::lookup_host;
+use url::{Host, Url};
+
+/// Proxied images which are larger are cut off.
+const MAX_PROXIED_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Redirects of proxied images which are followed, the target of each one is checked again.
+const MAX_PROXIED_IMAGE_REDIRECTS: usize = 5;
 
 pub fn config(
   cfg: &mut web::ServiceConfig,
@@ -53,6 +80,12 @@ struct PictrsParams {
   thumbnail: Option<i32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ImageProxyParams {
+  url: String,
+  sig: String,
+}
+
 #[derive(Deserialize)]
 enum PictrsPurgeParams {
   #[serde(rename = "file")]
@@ -96,8 +129,9 @@ async fn upload(
     .cookie("jwt")
     .expect("No auth header for picture upload");
 
-  if Claims::decode(jwt.value(), &context.secret().jwt_secret).is_err() {
-    return Ok(HttpResponse::Unauthorized().finish());
+  let local_user_view = match local_user_view_from_jwt(jwt.value(), &context).await {
+    Ok(local_user_view) => local_user_view,
+    Err(_) => return Ok(HttpResponse::Unauthorized().finish()),
   };
 
   let pictrs_config = context.settings().pictrs_config()?;
@@ -109,14 +143,25 @@ async fn upload(
     client_req = client_req.header("X-Forwarded-For", addr.to_string())
   };
 
-  let res = client_req
-    .body(Body::wrap_stream(make_send(body)))
-    .send()
-    .await
-    .map_err(error::ErrorBadRequest)?;
+  // Fail fast while pictrs is down, instead of waiting for the upload to time out
+  let res = PICTRS_BREAKER
+    .send(client_req.body(Body::wrap_stream(make_send(body))))
+    .await?;
 
   let status = res.status();
   let images = res.json::<Images>().await.map_err(error::ErrorBadRequest)?;
+  if let Some(files) = &images.files {
+    for uploaded_image in files {
+      let form = LocalImageForm {
+        local_user_id: local_user_view.local_user.id,
+        pictrs_alias: uploaded_image.file.to_string(),
+        pictrs_delete_token: uploaded_image.delete_token.to_string(),
+      };
+      LocalImage::create(&mut context.pool(), &form)
+        .await
+        .map_err(error::ErrorBadRequest)?;
+    }
+  }
 
   Ok(HttpResponse::build(status).json(images))
 }
@@ -181,7 +226,7 @@ async fn image(
     client_req = client_req.header("X-Forwarded-For", addr.to_string());
   }
 
-  let res = client_req.send().await.map_err(error::ErrorBadRequest)?;
+  let res = PICTRS_BREAKER.send(client_req).await?;
 
   if res.status() == StatusCode::NOT_FOUND {
     return Ok(HttpResponse::NotFound().finish());
@@ -213,11 +258,195 @@ async fn delete(
     client_req = client_req.header("X-Forwarded-For", addr.to_string());
   }
 
-  let res = client_req.send().await.map_err(error::ErrorBadRequest)?;
+  let res = PICTRS_BREAKER.send(client_req).await?;
+
+  if res.status().is_success() {
+    LocalImage::delete_by_alias(&mut context.pool(), &file)
+      .await
+      .ok();
+  }
 
   Ok(HttpResponse::build(res.status()).body(BodyStream::new(res.bytes_stream())))
 }
 
+/// Loads a remote image for a client, so that the remote server doesn't see the IP of the user.
+/// The signature shows that the url was linked by this instance, which keeps the proxy from
+/// being used for anything else.
+pub async fn image_proxy(
+  web::Query(params): web::Query<ImageProxyParams>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let local_site = LocalSite::read(&mut context.pool())
+    .await
+    .map_err(error::ErrorBadRequest)?;
+  if !local_site.proxy_remote_images
+    || !verify_image_proxy_signature(&params.url, &params.sig, context.secret())
+  {
+    return Ok(HttpResponse::Forbidden().finish());
+  }
+  let url = Url::parse(&params.url).map_err(error::ErrorBadRequest)?;
+
+  let res = fetch_proxied_image(&url, &context).await?;
+  if !res.status().is_success() {
+    return Ok(HttpResponse::build(res.status()).finish());
+  }
+  let Some(content_type) = res
+    .headers()
+    .get(CONTENT_TYPE)
+    .filter(|c| is_proxied_content_type(c.to_str().unwrap_or_default()))
+    .cloned()
+  else {
+    return Ok(HttpResponse::UnsupportedMediaType().finish());
+  };
+  if res
+    .content_length()
+    .is_some_and(|length| length > MAX_PROXIED_IMAGE_BYTES)
+  {
+    return Ok(HttpResponse::PayloadTooLarge().finish());
+  }
+
+  let mut client_res = HttpResponse::Ok();
+  client_res
+    .insert_header((CONTENT_TYPE, content_type))
+    .insert_header((X_CONTENT_TYPE_OPTIONS, "nosniff"))
+    .insert_header((CACHE_CONTROL, "public, max-age=86400"));
+  if let Some(length) = res.headers().get(CONTENT_LENGTH) {
+    client_res.insert_header((CONTENT_LENGTH, length.clone()));
+  }
+
+  // The length header can be missing or wrong, so the size is also checked while streaming
+  let mut received = 0;
+  let body = res.bytes_stream().map(move |chunk| {
+    let chunk = chunk.map_err(error::ErrorBadGateway)?;
+    received += chunk.len() as u64;
+    if received > MAX_PROXIED_IMAGE_BYTES {
+      Err(error::ErrorPayloadTooLarge("Proxied image is too large"))
+    } else {
+      Ok(chunk)
+    }
+  });
+  Ok(client_res.body(BodyStream::new(body)))
+}
+
+/// Loads the image through pictrs if it is configured, which caches it. Versions of pictrs
+/// without the proxy feature reject the request, and then the image is loaded directly.
+async fn fetch_proxied_image(
+  url: &Url,
+  context: &LemmyContext,
+) -> Result<reqwest::Response, Error> {
+  let mut addrs = resolve_public_host(url).await?;
+  if let Ok(pictrs_config) = context.settings().pictrs_config() {
+    let pictrs_req = context
+      .client()
+      .get(format!("{}image/original", pictrs_config.url))
+      .query(&[("proxy", url.as_str())])
+      .timeout(REQWEST_TIMEOUT);
+    if let Ok(res) = PICTRS_BREAKER.send(pictrs_req).await {
+      if res.status().is_success() {
+        return Ok(res);
+      }
+    }
+  }
+
+  // The shared client would resolve the host again and follow redirects on its own, so every
+  // request goes to the checked addresses and redirects are checked here.
+  let mut url = url.clone();
+  for _ in 0..=MAX_PROXIED_IMAGE_REDIRECTS {
+    let mut builder = reqwest::Client::builder()
+      .user_agent(build_user_agent(context.settings()))
+      .redirect(Policy::none());
+    if let Some(domain) = url.domain() {
+      builder = builder.resolve_to_addrs(domain, &addrs);
+    }
+    let res = builder
+      .build()
+      .map_err(error::ErrorInternalServerError)?
+      .get(url.as_str())
+      .timeout(REQWEST_TIMEOUT)
+      .send()
+      .await
+      .map_err(error::ErrorBadGateway)?;
+    let location = res
+      .headers()
+      .get(LOCATION)
+      .and_then(|l| l.to_str().ok())
+      .filter(|_| res.status().is_redirection());
+    let Some(location) = location else {
+      return Ok(res);
+    };
+    url = url.join(location).map_err(error::ErrorBadGateway)?;
+    addrs = resolve_public_host(&url).await?;
+  }
+  Err(error::ErrorBadGateway("Too many redirects"))
+}
+
+/// Only raster images are proxied. SVGs can contain scripts, which would run on the instance's
+/// domain.
+fn is_proxied_content_type(content_type: &str) -> bool {
+  content_type.starts_with("image/") && !content_type.starts_with("image/svg")
+}
+
+/// Keeps the proxy from reaching services in the network of the instance. Domains are resolved
+/// and rejected if any of their addresses is not public.
+async fn resolve_public_host(url: &Url) -> Result<Vec<SocketAddr>, Error> {
+  let forbidden = || error::ErrorForbidden("Image is not on a public host");
+  if !matches!(url.scheme(), "http" | "https") {
+    return Err(forbidden());
+  }
+  let port = url.port_or_known_default().ok_or_else(forbidden)?;
+  let addrs: Vec<_> = match url.host() {
+    Some(Host::Domain(domain)) => {
+      let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+      if domain == "localhost" || domain.ends_with(".localhost") {
+        return Err(forbidden());
+      }
+      lookup_host((domain.as_str(), port))
+        .await
+        .map_err(error::ErrorBadGateway)?
+        .collect()
+    }
+    Some(Host::Ipv4(ip)) => vec![SocketAddr::new(IpAddr::V4(ip), port)],
+    Some(Host::Ipv6(ip)) => vec![SocketAddr::new(IpAddr::V6(ip), port)],
+    None => vec![],
+  };
+  if addrs.is_empty() || !addrs.iter().all(|a| is_public_ip(a.ip())) {
+    return Err(forbidden());
+  }
+  Ok(addrs)
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+  match ip {
+    IpAddr::V4(ip) => is_public_ipv4(ip),
+    IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+      Some(ip) => is_public_ipv4(ip),
+      None => is_public_ipv6(ip),
+    },
+  }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+  let [first, second, ..] = ip.octets();
+  // 0.0.0.0/8 is "this network" and 100.64.0.0/10 is shared address space used by carriers
+  let this_network = first == 0;
+  let shared = first == 100 && (second & 0xc0) == 64;
+  !(ip.is_loopback()
+    || ip.is_private()
+    || ip.is_link_local()
+    || ip.is_unspecified()
+    || ip.is_broadcast()
+    || ip.is_documentation()
+    || this_network
+    || shared)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+  let [first, ..] = ip.segments();
+  let unique_local = (first & 0xfe00) == 0xfc00;
+  let link_local = (first & 0xffc0) == 0xfe80;
+  !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+}
+
 fn make_send<S>(mut stream: S) -> impl Stream<Item = S::Item> + Send + Unpin + 'static
 where
   S: Stream + Unpin + 'static,
@@ -259,3 +488,64 @@ where
     std::pin::Pin::new(&mut self.rx).poll_recv(cx)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::*;
+
+  #[test]
+  fn test_is_public_ip() {
+    let public = ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"];
+    for ip in public {
+      assert!(is_public_ip(ip.parse().unwrap()), "{ip}");
+    }
+    let internal = [
+      "127.0.0.1",
+      "10.1.2.3",
+      "172.16.0.1",
+      "192.168.1.1",
+      "169.254.169.254",
+      "100.64.0.1",
+      "0.0.0.0",
+      "::1",
+      "::",
+      "fd00::1",
+      "fe80::1",
+      "::ffff:127.0.0.1",
+      "::ffff:10.0.0.1",
+    ];
+    for ip in internal {
+      assert!(!is_public_ip(ip.parse().unwrap()), "{ip}");
+    }
+  }
+
+  #[tokio::test]
+  async fn test_resolve_public_host() {
+    let internal = [
+      "http://localhost/a.png",
+      "http://localhost./a.png",
+      "http://img.localhost/a.png",
+      "http://[::ffff:127.0.0.1]/a.png",
+      "http://[fc00::1]/a.png",
+      "file:///etc/passwd",
+    ];
+    for url in internal {
+      assert!(
+        resolve_public_host(&Url::parse(url).unwrap())
+          .await
+          .is_err(),
+        "{url}"
+      );
+    }
+    let addrs = resolve_public_host(&Url::parse("https://93.184.216.34/a.png").unwrap())
+      .await
+      .unwrap();
+    assert_eq!(
+      vec!["93.184.216.34:443".parse::<SocketAddr>().unwrap()],
+      addrs
+    );
+  }
+}
@@ -68,6 +68,9 @@ async fn node_info(context: web::Data<LemmyContext>) -> Result<HttpResponse, Err
       local_comments: Some(site_view.counts.comments),
     }),
     open_registrations,
+    metadata: Some(NodeInfoMetadata {
+      disallow_nsfw_content: Some(site_view.local_site.disallow_nsfw_content),
+    }),
   };
 
   Ok(HttpResponse::Ok().json(json))
@@ -92,6 +95,14 @@ pub struct NodeInfo {
   pub protocols: Option<Vec<String>>,
   pub usage: Option<NodeInfoUsage>,
   pub open_registrations: Option<bool>,
+  pub metadata: Option<NodeInfoMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeInfoMetadata {
+  /// The instance rejects NSFW content, so it shouldn't be sent there.
+  pub disallow_nsfw_content: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -35,7 +35,10 @@ async fn get_webfinger_response(
   info: Query<Params>,
   context: Data<LemmyContext>,
 ) -> Result<HttpResponse, LemmyError> {
-  let name = extract_webfinger_name(&info.resource, &context)?;
+  let name = match extract_webfinger_name(&info.resource, &context) {
+    Ok(name) => name,
+    Err(e) => extract_alias_webfinger_name(&info.resource, &context).ok_or(e)?,
+  };
 
   let name_ = name.clone();
   let user_id: Option<Url> = Person::read_from_name(&mut context.pool(), &name_, false)
@@ -66,6 +69,17 @@ async fn get_webfinger_response(
   Ok(HttpResponse::Ok().json(json))
 }
 
+/// Accepts queries for the domains which the instance was moved away from, so that remote users
+/// can still find local actors by their old address.
+fn extract_alias_webfinger_name(query: &str, context: &LemmyContext) -> Option<String> {
+  let (name, domain) = query.strip_prefix("acct:")?.split_once('@')?;
+  context
+    .domain_aliases()
+    .iter()
+    .any(|alias| alias == domain)
+    .then(|| name.to_string())
+}
+
 fn webfinger_link_for_actor(url: Option<Url>, kind: &str) -> Vec<WebfingerLink> {
   if let Some(url) = url {
     let mut properties = HashMap::new();
@@ -34,3 +34,81 @@ impl Claims {
     Ok(encode(&Header::default(), &my_claims, &key)?)
   }
 }
+
+/// What an emailed link for a scheduled account deletion does.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountDeletionAction {
+  Cancel,
+  DeleteNow,
+}
+
+/// Signs the links which are emailed after an account deletion was scheduled. A link only works
+/// for the deletion which it was sent for, and can't be used to log in.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountDeletionClaims {
+  /// local_user_id
+  pub sub: i32,
+  pub iss: String,
+  pub action: AccountDeletionAction,
+  /// When the deletion is scheduled for, as UNIX-timestamp in seconds
+  pub scheduled: i64,
+}
+
+impl AccountDeletionClaims {
+  pub fn decode(jwt: &str, jwt_secret: &str) -> Result<AccountDeletionClaims, LemmyError> {
+    let mut validation = Validation::default();
+    validation.validate_exp = false;
+    validation.required_spec_claims.remove("exp");
+    let key = DecodingKey::from_secret(jwt_secret.as_ref());
+    Ok(decode::<AccountDeletionClaims>(jwt, &key, &validation)?.claims)
+  }
+
+  pub fn jwt(
+    local_user_id: i32,
+    action: AccountDeletionAction,
+    scheduled: i64,
+    jwt_secret: &str,
+    hostname: &str,
+  ) -> Result<Jwt, LemmyError> {
+    let claims = AccountDeletionClaims {
+      sub: local_user_id,
+      iss: hostname.to_string(),
+      action,
+      scheduled,
+    };
+
+    let key = EncodingKey::from_secret(jwt_secret.as_ref());
+    Ok(encode(&Header::default(), &claims, &key)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::claims::{AccountDeletionAction, AccountDeletionClaims, Claims};
+
+  #[test]
+  fn test_account_deletion_claims() {
+    let secret = "secret";
+    let jwt = AccountDeletionClaims::jwt(
+      5,
+      AccountDeletionAction::Cancel,
+      1693000000,
+      secret,
+      "lemmy.tld",
+    )
+    .unwrap();
+
+    let claims = AccountDeletionClaims::decode(&jwt, secret).unwrap();
+    assert_eq!(5, claims.sub);
+    assert_eq!(AccountDeletionAction::Cancel, claims.action);
+    assert_eq!(1693000000, claims.scheduled);
+
+    // The link can't be used to log in, or with another secret
+    assert!(Claims::decode(&jwt, secret).is_err());
+    assert!(AccountDeletionClaims::decode(&jwt, "other").is_err());
+  }
+}
@@ -21,14 +21,40 @@ where
 {
   fn from(t: T) -> Self {
     let cause = t.into();
+    let error_type =
+      database_error_type(&cause).unwrap_or_else(|| LemmyErrorType::Unknown(format!("{}", &cause)));
     LemmyError {
-      error_type: LemmyErrorType::Unknown(format!("{}", &cause)),
+      error_type,
       inner: cause,
       context: SpanTrace::capture(),
     }
   }
 }
 
+/// The cause of database errors which happened because no connection could be acquired.
+#[derive(Debug)]
+pub struct DatabaseUnavailableError(pub String);
+
+impl Display for DatabaseUnavailableError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Database unavailable: {}", self.0)
+  }
+}
+
+impl std::error::Error for DatabaseUnavailableError {}
+
+/// Errors because the database is unreachable are always reported as `DatabaseUnavailable`, no
+/// matter which error type the handler gave them, so that clients know to retry later.
+fn database_error_type(error: &anyhow::Error) -> Option<LemmyErrorType> {
+  use diesel::result::{DatabaseErrorKind, Error};
+  match error.downcast_ref::<Error>()? {
+    Error::QueryBuilderError(e) if e.is::<DatabaseUnavailableError>() => {}
+    Error::DatabaseError(DatabaseErrorKind::ClosedConnection, _) => {}
+    _ => return None,
+  }
+  Some(LemmyErrorType::DatabaseUnavailable)
+}
+
 impl Debug for LemmyError {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     f.debug_struct("LemmyError")
@@ -51,16 +77,72 @@ impl Display for LemmyError {
   }
 }
 
+/// Body of an error response. The flattened fields keep the format of older versions, so that
+/// existing clients can still read the `error` string.
+#[derive(Serialize)]
+pub(crate) struct ErrorResponse<'a> {
+  #[serde(flatten)]
+  legacy: &'a LemmyErrorType,
+  error_type: &'a LemmyErrorType,
+}
+
+impl<'a> ErrorResponse<'a> {
+  pub(crate) fn new(error_type: &'a LemmyErrorType) -> Self {
+    ErrorResponse {
+      legacy: error_type,
+      error_type,
+    }
+  }
+}
+
 impl actix_web::error::ResponseError for LemmyError {
   fn status_code(&self) -> http::StatusCode {
-    match self.inner.downcast_ref::<diesel::result::Error>() {
-      Some(diesel::result::Error::NotFound) => http::StatusCode::NOT_FOUND,
-      _ => http::StatusCode::BAD_REQUEST,
+    match self.error_type {
+      LemmyErrorType::ImageServiceUnavailable | LemmyErrorType::DatabaseUnavailable => {
+        http::StatusCode::SERVICE_UNAVAILABLE
+      }
+      LemmyErrorType::RateLimitError => http::StatusCode::TOO_MANY_REQUESTS,
+      LemmyErrorType::NotLoggedIn => http::StatusCode::UNAUTHORIZED,
+      LemmyErrorType::CouldntFindCommunity
+      | LemmyErrorType::CouldntFindPerson
+      | LemmyErrorType::CouldntFindPost
+      | LemmyErrorType::CouldntFindComment
+      | LemmyErrorType::CouldntFindObject
+      | LemmyErrorType::CouldntFindPostReminder
+      | LemmyErrorType::CouldntFindResyncJob
+      | LemmyErrorType::CouldntFindEditRevision
+      | LemmyErrorType::CouldntFindUserDataImportJob => http::StatusCode::NOT_FOUND,
+      LemmyErrorType::NotAModerator
+      | LemmyErrorType::NotAnAdmin
+      | LemmyErrorType::NotAModOrAdmin
+      | LemmyErrorType::NotTopAdmin
+      | LemmyErrorType::NotTopMod
+      | LemmyErrorType::SiteBan
+      | LemmyErrorType::Banned
+      | LemmyErrorType::BannedFromCommunity
+      | LemmyErrorType::PersonIsBannedFromCommunity
+      | LemmyErrorType::PersonIsBannedFromSite(_)
+      | LemmyErrorType::PersonIsBlocked
+      | LemmyErrorType::InstanceIsPrivate
+      | LemmyErrorType::NoCommentEditAllowed
+      | LemmyErrorType::NoPostEditAllowed
+      | LemmyErrorType::EditPrivateMessageNotAllowed
+      | LemmyErrorType::OnlyAdminsCanCreateCommunities
+      | LemmyErrorType::OnlyModsCanPostInCommunity
+      | LemmyErrorType::OnlyModsCanCommentInCommunity
+      | LemmyErrorType::OnlyLocalAdminCanRemoveCommunity
+      | LemmyErrorType::OnlyLocalAdminCanRestoreCommunity
+      | LemmyErrorType::OnlyAdminsCanFeatureLocalPosts
+      | LemmyErrorType::NotAllowedToVote => http::StatusCode::FORBIDDEN,
+      _ => match self.inner.downcast_ref::<diesel::result::Error>() {
+        Some(diesel::result::Error::NotFound) => http::StatusCode::NOT_FOUND,
+        _ => http::StatusCode::BAD_REQUEST,
+      },
     }
   }
 
   fn error_response(&self) -> actix_web::HttpResponse {
-    actix_web::HttpResponse::build(self.status_code()).json(&self.error_type)
+    actix_web::HttpResponse::build(self.status_code()).json(ErrorResponse::new(&self.error_type))
   }
 }
 
@@ -72,6 +154,7 @@ impl actix_web::error::ResponseError for LemmyError {
 pub enum LemmyErrorType {
   ReportReasonRequired,
   ReportTooLong,
+  AlreadyReported,
   NotAModerator,
   NotAnAdmin,
   CantBlockYourself,
@@ -118,9 +201,11 @@ pub enum LemmyErrorType {
   CommunityAlreadyExists,
   LanguageNotAllowed,
   OnlyModsCanPostInCommunity,
+  OnlyModsCanCommentInCommunity,
   CouldntUpdatePost,
   NoPostEditAllowed,
   CouldntFindPost,
+  CouldntFindComment,
   EditPrivateMessageNotAllowed,
   SiteAlreadyExists,
   ApplicationQuestionRequired,
@@ -142,6 +227,7 @@ pub enum LemmyErrorType {
   InvalidQuery,
   ObjectNotLocal,
   PostIsLocked,
+  CommentThreadLocked,
   PersonIsBannedFromSite(String),
   InvalidVoteValue,
   PageDoesNotSpecifyCreator,
@@ -156,6 +242,8 @@ pub enum LemmyErrorType {
   InvalidMatrixId,
   InvalidPostTitle,
   InvalidBodyField,
+  InvalidSpoiler,
+  InvalidPageSlug,
   BioLengthOverflow,
   MissingTotpToken,
   IncorrectTotpToken,
@@ -176,6 +264,8 @@ pub enum LemmyErrorType {
   CouldntSavePost,
   CouldntMarkPostAsRead,
   CouldntUpdateCommunity,
+  CommunityPageSlugTaken,
+  CouldntUpdateCommunityPage,
   CouldntUpdateReplies,
   CouldntUpdatePersonMentions,
   PostTitleTooLong,
@@ -208,6 +298,67 @@ pub enum LemmyErrorType {
   InvalidUrlScheme,
   CouldntSendWebmention,
   ContradictingFilters,
+  UrlArchivingDisabled,
+  PostUrlNotArchivable,
+  CouldntArchiveUrl,
+  OnlyAdminsCanFeatureLocalPosts,
+  TooManyFeaturedLocalPosts,
+  NsfwNotAllowed,
+  CommunityTransferAlreadyPending,
+  NoPendingCommunityTransfer,
+  TooManyBlockedKeywords,
+  BlockedKeywordTooLong,
+  TooManyNotificationMutedWords,
+  NotificationMutedWordTooLong,
+  CantExportAllModlogTypes,
+  NotAllowedToVote,
+  CouldntUpdateReminder,
+  OauthProviderDisabled,
+  OauthStateInvalid,
+  OauthLoginFailed,
+  OauthEmailNotVerified,
+  OauthAccountNeedsLinking,
+  OauthAccountAlreadyLinked,
+  OauthRegistrationClosed,
+  TooManyPostReminders,
+  CouldntFindPostReminder,
+  ContentWarningLengthOverflow,
+  InvalidCommunityDigest,
+  CouldntUpdateCommunityDigest,
+  UserDataImportTooLarge,
+  CantBlockLocalInstance,
+  DomainMigrationSameDomain,
+  InvalidDomainMigrationToken,
+  DomainMigrationInProgress,
+  AccountDeletionPending,
+  InvalidAccountDeletionLink,
+  SaveTagTooLong,
+  InvalidFlair,
+  InvalidFlairColor,
+  FlairNotAvailable,
+  CouldntSetFlair,
+  InvalidFormId,
+  FormAlreadySubmitted,
+  ImageServiceUnavailable,
+  InvalidPoll,
+  PollEnded,
+  InvalidPollVote,
+  CouldntVotePoll,
+  NoRepliesCollection,
+  CantResyncLocalPost,
+  CantResyncLocalCommunity,
+  CommunityResyncInProgress,
+  CouldntFindResyncJob,
+  UnknownFields(String),
+  ModReasonRequired,
+  InvalidModReason,
+  CouldntSaveModReason,
+  DatabaseUnavailable,
+  TooManyNotificationsInBatch,
+  TooManyRegistrationApplicationsInBatch,
+  CouldntFindEditRevision,
+  UserDataImportInProgress,
+  CouldntFindUserDataImportJob,
   Unknown(String),
 }
 
@@ -228,10 +379,13 @@ pub trait LemmyErrorExt<T, E: Into<anyhow::Error>> {
 
 impl<T, E: Into<anyhow::Error>> LemmyErrorExt<T, E> for Result<T, E> {
   fn with_lemmy_type(self, error_type: LemmyErrorType) -> Result<T, LemmyError> {
-    self.map_err(|error| LemmyError {
-      error_type,
-      inner: error.into(),
-      context: SpanTrace::capture(),
+    self.map_err(|error| {
+      let inner = error.into();
+      LemmyError {
+        error_type: database_error_type(&inner).unwrap_or(error_type),
+        inner,
+        context: SpanTrace::capture(),
+      }
     })
   }
 }
@@ -242,7 +396,9 @@ pub trait LemmyErrorExt2<T> {
 impl<T> LemmyErrorExt2<T> for Result<T, LemmyError> {
   fn with_lemmy_type(self, error_type: LemmyErrorType) -> Result<T, LemmyError> {
     self.map_err(|mut e| {
-      e.error_type = error_type;
+      if e.error_type != LemmyErrorType::DatabaseUnavailable {
+        e.error_type = error_type;
+      }
       e
     })
   }
@@ -261,7 +417,10 @@ mod tests {
   fn deserializes_no_message() {
     let err = LemmyError::from(LemmyErrorType::Banned).error_response();
     let json = String::from_utf8(err.into_body().try_into_bytes().unwrap().to_vec()).unwrap();
-    assert_eq!(&json, "{\"error\":\"banned\"}")
+    assert_eq!(
+      &json,
+      "{\"error\":\"banned\",\"error_type\":{\"error\":\"banned\"}}"
+    )
   }
 
   #[test]
@@ -271,10 +430,87 @@ mod tests {
     let json = String::from_utf8(err.into_body().try_into_bytes().unwrap().to_vec()).unwrap();
     assert_eq!(
       &json,
-      "{\"error\":\"registration_denied\",\"message\":\"reason\"}"
+      "{\"error\":\"registration_denied\",\"message\":\"reason\",\
+       \"error_type\":{\"error\":\"registration_denied\",\"message\":\"reason\"}}"
     )
   }
 
+  #[test]
+  fn image_service_unavailable_status() {
+    let err = LemmyError::from(LemmyErrorType::ImageServiceUnavailable);
+    assert_eq!(http::StatusCode::SERVICE_UNAVAILABLE, err.status_code());
+    assert_eq!(
+      http::StatusCode::BAD_REQUEST,
+      LemmyError::from(LemmyErrorType::Locked).status_code()
+    );
+  }
+
+  #[test]
+  fn status_codes_by_category() {
+    let status = |error_type| LemmyError::from(error_type).status_code();
+    assert_eq!(
+      http::StatusCode::NOT_FOUND,
+      status(LemmyErrorType::CouldntFindComment)
+    );
+    assert_eq!(
+      http::StatusCode::NOT_FOUND,
+      status(LemmyErrorType::CouldntFindCommunity)
+    );
+    assert_eq!(
+      http::StatusCode::FORBIDDEN,
+      status(LemmyErrorType::NotAModerator)
+    );
+    assert_eq!(
+      http::StatusCode::FORBIDDEN,
+      status(LemmyErrorType::NoPostEditAllowed)
+    );
+    assert_eq!(
+      http::StatusCode::TOO_MANY_REQUESTS,
+      status(LemmyErrorType::RateLimitError)
+    );
+    assert_eq!(
+      http::StatusCode::UNAUTHORIZED,
+      status(LemmyErrorType::NotLoggedIn)
+    );
+    assert_eq!(
+      http::StatusCode::BAD_REQUEST,
+      status(LemmyErrorType::CouldntCreateComment)
+    );
+  }
+
+  #[test]
+  fn diesel_not_found_status() {
+    let err = Err::<(), _>(diesel::result::Error::NotFound)
+      .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)
+      .unwrap_err();
+    assert_eq!(http::StatusCode::NOT_FOUND, err.status_code());
+  }
+
+  #[test]
+  fn database_unavailable_keeps_type() {
+    let pool_error = || {
+      let cause = DatabaseUnavailableError("timed out".to_string());
+      Err::<(), _>(diesel::result::Error::QueryBuilderError(cause.into()))
+    };
+    let err = LemmyError::from(pool_error().unwrap_err());
+    assert_eq!(LemmyErrorType::DatabaseUnavailable, err.error_type);
+    assert_eq!(http::StatusCode::SERVICE_UNAVAILABLE, err.status_code());
+
+    let err = pool_error()
+      .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)
+      .unwrap_err();
+    assert_eq!(LemmyErrorType::DatabaseUnavailable, err.error_type);
+    let err = Err::<(), _>(err)
+      .with_lemmy_type(LemmyErrorType::CouldntFindPost)
+      .unwrap_err();
+    assert_eq!(LemmyErrorType::DatabaseUnavailable, err.error_type);
+
+    let err = Err::<(), _>(diesel::result::Error::NotFound)
+      .with_lemmy_type(LemmyErrorType::CouldntUpdatePost)
+      .unwrap_err();
+    assert_eq!(LemmyErrorType::CouldntUpdatePost, err.error_type);
+  }
+
   /// Check if errors match translations. Disabled because many are not translated at all.
   #[test]
   #[ignore]
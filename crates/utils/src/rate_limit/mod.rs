@@ -57,6 +57,12 @@ pub struct RateLimitConfig {
   #[builder(default = 600)]
   /// Interval length for search limit, in seconds
   pub search_per_second: i32,
+  #[builder(default = 6)]
+  /// Maximum number of modlog exports and user data exports or imports in interval
+  pub export: i32,
+  #[builder(default = 600)]
+  /// Interval length for export and import limit, in seconds
+  pub export_per_second: i32,
 }
 
 #[derive(Debug, Clone)]
@@ -125,6 +131,7 @@ impl RateLimitCell {
       RateLimitType::Image => rate_limit.image_per_second,
       RateLimitType::Comment => rate_limit.comment_per_second,
       RateLimitType::Search => rate_limit.search_per_second,
+      RateLimitType::Export => rate_limit.export_per_second,
     }
     .into_values()
     .max()
@@ -162,6 +169,10 @@ impl RateLimitCell {
     self.kind(RateLimitType::Search)
   }
 
+  pub fn export(&self) -> RateLimitedGuard {
+    self.kind(RateLimitType::Export)
+  }
+
   fn kind(&self, type_: RateLimitType) -> RateLimitedGuard {
     RateLimitedGuard {
       rate_limit: self.rate_limit.clone(),
@@ -193,6 +204,7 @@ impl RateLimitedGuard {
       RateLimitType::Image => (rate_limit.image, rate_limit.image_per_second),
       RateLimitType::Comment => (rate_limit.comment, rate_limit.comment_per_second),
       RateLimitType::Search => (rate_limit.search, rate_limit.search_per_second),
+      RateLimitType::Export => (rate_limit.export, rate_limit.export_per_second),
     };
     let limiter = &mut guard.rate_limiter;
 
@@ -254,7 +266,8 @@ where
   }
 }
 
-fn get_ip(conn_info: &ConnectionInfo) -> IpAddr {
+/// The IP address of the request, taking proxy headers into account.
+pub fn get_ip(conn_info: &ConnectionInfo) -> IpAddr {
   conn_info
     .realip_remote_addr()
     .and_then(parse_ip)
@@ -1,4 +1,4 @@
-use crate::error::{LemmyError, LemmyErrorType};
+use crate::error::{ErrorResponse, LemmyError, LemmyErrorType};
 use actix_web::{
   dev::ServiceResponse,
   http::header,
@@ -28,9 +28,10 @@ pub fn jsonify_plain_text_errors<BODY>(
   let error = res
     .error()
     .expect("expected an error object in the response");
+  let error_type = LemmyErrorType::Unknown(error.to_string());
   let response = HttpResponse::build(res.status())
     .append_header(header::ContentType::json())
-    .json(LemmyErrorType::Unknown(error.to_string()));
+    .json(ErrorResponse::new(&error_type));
 
   let service_response = ServiceResponse::new(req, response);
   Ok(ErrorHandlerResponse::Response(
@@ -72,7 +73,7 @@ mod tests {
     check_for_jsonification(
       lemmy_error_service,
       StatusCode::BAD_REQUEST,
-      "{\"error\":\"email_already_exists\"}",
+      "{\"error\":\"email_already_exists\",\"error_type\":{\"error\":\"email_already_exists\"}}",
     )
     .await;
   }
@@ -86,7 +87,8 @@ mod tests {
     check_for_jsonification(
       generic_error_service,
       StatusCode::INTERNAL_SERVER_ERROR,
-      "{\"error\":\"unknown\",\"message\":\"This is not a LemmyError\"}",
+      "{\"error\":\"unknown\",\"message\":\"This is not a LemmyError\",\
+       \"error_type\":{\"error\":\"unknown\",\"message\":\"This is not a LemmyError\"}}",
     )
     .await;
   }
@@ -100,7 +102,8 @@ mod tests {
     check_for_jsonification(
       anyhow_error_service,
       StatusCode::BAD_REQUEST,
-      "{\"error\":\"unknown\",\"message\":\"This is the inner error\"}",
+      "{\"error\":\"unknown\",\"message\":\"This is the inner error\",\
+       \"error_type\":{\"error\":\"unknown\",\"message\":\"This is the inner error\"}}",
     )
     .await;
   }
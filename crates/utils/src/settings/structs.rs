@@ -45,6 +45,36 @@ pub struct Settings {
   /// The number of activitypub federation retry workers that can be in-flight concurrently
   #[default(0)]
   pub retry_count: usize,
+  /// How many days to keep the records of which inboxes outgoing activities were sent to. These
+  /// are shown to admins for debugging the federation of single posts or comments. Set to 0 to
+  /// disable the records.
+  #[default(3)]
+  pub activity_delivery_retention_days: u32,
+  /// Activities for instances which are unreachable are queued and retried later, with
+  /// increasing delays. After this many days they are dropped.
+  #[default(3)]
+  pub federation_retry_max_age_days: u32,
+  /// The number of incoming activities which are processed at the same time. Set to 0 to use
+  /// half of the database pool size.
+  #[default(0)]
+  pub inbox_concurrency: usize,
+  /// How many incoming activities may wait to be processed. Once the queue is full, remote
+  /// instances are asked to retry later, starting with votes.
+  #[default(100)]
+  pub inbox_queue_size: usize,
+  /// How the top contributors of a community are ranked
+  #[default(Default::default())]
+  pub top_contributors: TopContributorsConfig,
+  /// Detection of coordinated voting, shown to admins
+  #[default(Default::default())]
+  pub vote_anomaly: VoteAnomalyConfig,
+  /// Limits for fetching the missing comments of remote posts from their home instance
+  #[default(Default::default())]
+  pub resync: ResyncConfig,
+  /// Video sites whose links get an embedded player. Links to other sites never get one, even
+  /// if they advertise oEmbed.
+  #[default(default_embed_providers())]
+  pub embed_providers: Vec<EmbedProviderConfig>,
   // Prometheus configuration.
   #[default(None)]
   #[doku(example = "Some(Default::default())")]
@@ -62,6 +92,122 @@ pub struct PictrsConfig {
   /// Set a custom pictrs API key. ( Required for deleting images )
   #[default(None)]
   pub api_key: Option<String>,
+
+  /// Number of consecutive failed requests after which pictrs is considered unavailable. Image
+  /// uploads are then rejected right away, and thumbnails are generated later.
+  #[default(5)]
+  pub breaker_failure_threshold: u32,
+
+  /// Seconds to wait after pictrs became unavailable before it is tried again
+  #[default(30)]
+  pub breaker_open_seconds: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct TopContributorsConfig {
+  /// Points for each post in the community
+  #[default(5)]
+  pub post_weight: i32,
+  /// Points for each comment in the community
+  #[default(2)]
+  pub comment_weight: i32,
+  /// Points for each upvote minus downvote which the posts and comments received
+  #[default(1)]
+  pub score_weight: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct VoteAnomalyConfig {
+  /// How often to look for vote surges, in minutes. Set to 0 to disable.
+  #[default(15)]
+  pub interval_minutes: u32,
+  /// The votes of each post and comment are counted over this many minutes
+  #[default(60)]
+  pub window_minutes: u32,
+  /// How many days of votes the usual activity of a community is taken from
+  #[default(7)]
+  pub baseline_days: u32,
+  /// Surges with fewer votes are ignored
+  #[default(20)]
+  pub min_votes: u32,
+  /// How many standard deviations above the community baseline the votes have to be
+  #[default(3.0)]
+  pub z_score_threshold: f64,
+  /// A surge is flagged if at least this share of the votes came from a single instance
+  #[default(0.6)]
+  pub instance_share_threshold: f64,
+  /// Accounts younger than this many days count as new
+  #[default(7)]
+  pub new_account_days: u32,
+  /// A surge is flagged if at least this share of the votes came from new accounts
+  #[default(0.5)]
+  pub new_account_share_threshold: f64,
+  /// Flagged surges are posted as json to this url
+  #[default(None)]
+  #[doku(example = "https://example.com/hooks/vote-anomaly")]
+  pub webhook_url: Option<Url>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResyncConfig {
+  /// At most this many comments are fetched for a single post
+  #[default(1000)]
+  pub max_comments_per_post: usize,
+  /// How deep reply collections of comments are followed, and how many missing parents of a
+  /// comment are fetched
+  #[default(10)]
+  pub max_depth: usize,
+  /// A resync of a community covers at most this many of its newest posts
+  #[default(500)]
+  pub max_posts_per_community: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmbedProviderConfig {
+  /// Shown to clients, so that they can decide how to sandbox the player
+  #[doku(example = "YouTube")]
+  pub name: String,
+  /// Domains of the links. A leading `*.` also matches all subdomains.
+  #[doku(example = "*.youtube.com")]
+  pub domains: Vec<String>,
+  /// Url of the player, where `{id}` is replaced with the video id of the link
+  #[default(None)]
+  #[doku(example = "https://www.youtube-nocookie.com/embed/{id}")]
+  pub url_template: Option<String>,
+  /// oEmbed endpoint which is asked for the player
+  #[default(None)]
+  #[doku(example = "https://www.youtube.com/oembed")]
+  pub oembed_endpoint: Option<Url>,
+  /// Ask the PeerTube api of the linked instance for the player
+  #[default(false)]
+  pub peertube: bool,
+}
+
+fn default_embed_providers() -> Vec<EmbedProviderConfig> {
+  vec![
+    EmbedProviderConfig {
+      name: "YouTube".to_string(),
+      domains: vec![
+        "youtube.com".to_string(),
+        "*.youtube.com".to_string(),
+        "youtu.be".to_string(),
+      ],
+      url_template: None,
+      oembed_endpoint: Url::parse("https://www.youtube.com/oembed").ok(),
+      peertube: false,
+    },
+    EmbedProviderConfig {
+      name: "Vimeo".to_string(),
+      domains: vec!["vimeo.com".to_string(), "*.vimeo.com".to_string()],
+      url_template: None,
+      oembed_endpoint: Url::parse("https://vimeo.com/api/oembed.json").ok(),
+      peertube: false,
+    },
+  ]
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
@@ -73,6 +219,16 @@ pub struct DatabaseConfig {
   /// Maximum number of active sql connections
   #[default(5)]
   pub pool_size: usize,
+
+  /// Seconds to wait for a connection to the database, before a request fails with
+  /// `database_unavailable`
+  #[default(5)]
+  pub connection_timeout_seconds: u64,
+
+  /// Seconds between checks if the database is reachable. While it isn't, writes are rejected
+  /// and the site info is served from a cache.
+  #[default(10)]
+  pub health_check_interval_seconds: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
@@ -1,8 +1,33 @@
-use markdown_it::MarkdownIt;
+use crate::error::{LemmyErrorType, LemmyResult};
+use itertools::Itertools;
+use markdown_it::{
+  parser::inline::Text,
+  plugins::cmark::{
+    block::{heading::ATXHeading, lheading::SetextHeader, list::ListItem, paragraph::Paragraph},
+    inline::{
+      image::Image,
+      newline::{Hardbreak, Softbreak},
+    },
+  },
+  MarkdownIt,
+};
 use once_cell::sync::Lazy;
+use regex::Regex;
+use url::Url;
 
 mod spoiler_rule;
 
+/// Title of spoilers which are written without one, as the spoiler rule requires it.
+const DEFAULT_SPOILER_TITLE: &str = "Spoiler";
+
+/// Opening fences of spoilers in the ways that clients write them, like `:::spoiler title` or
+/// `::: Spoiler`.
+static SPOILER_START_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"^\s*:::\s*(?i:spoiler)(?:\s+(.*?))?\s*$").expect("compile spoiler start regex")
+});
+static SPOILER_END_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^\s*:::\s*$").expect("compile spoiler end regex"));
+
 static MARKDOWN_PARSER: Lazy<MarkdownIt> = Lazy::new(|| {
   let mut parser = MarkdownIt::new();
   markdown_it::plugins::cmark::add(&mut parser);
@@ -16,12 +41,152 @@ pub fn markdown_to_html(text: &str) -> String {
   MARKDOWN_PARSER.parse(text).xrender()
 }
 
+/// Rewrites spoilers into the `::: spoiler title` form which the spoiler rule and all clients
+/// understand. Spoilers which are never closed, or which are nested, are rejected as the parser
+/// would render them as plain text. Code blocks are left untouched.
+pub fn normalize_spoilers(text: &str) -> LemmyResult<String> {
+  let mut normalized = Vec::new();
+  let mut in_spoiler = false;
+  let mut in_code_block = false;
+  for line in text.split('\n') {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+      in_code_block = !in_code_block;
+    }
+    if in_code_block {
+      normalized.push(line.to_string());
+    } else if let Some(captures) = SPOILER_START_REGEX.captures(line) {
+      if in_spoiler {
+        return Err(LemmyErrorType::InvalidSpoiler)?;
+      }
+      in_spoiler = true;
+      let title = captures
+        .get(1)
+        .map(|t| t.as_str())
+        .filter(|t| !t.is_empty())
+        .unwrap_or(DEFAULT_SPOILER_TITLE);
+      normalized.push(format!("::: spoiler {title}"));
+    } else if in_spoiler && SPOILER_END_REGEX.is_match(line) {
+      in_spoiler = false;
+      normalized.push(":::".to_string());
+    } else {
+      normalized.push(line.to_string());
+    }
+  }
+  if in_spoiler {
+    return Err(LemmyErrorType::InvalidSpoiler)?;
+  }
+  Ok(normalized.join("\n"))
+}
+
+pub fn normalize_spoilers_opt(text: &Option<String>) -> LemmyResult<Option<String>> {
+  text.as_deref().map(normalize_spoilers).transpose()
+}
+
+/// Strips all markdown formatting, leaving only the text content with whitespace collapsed.
+/// Code blocks are left out, as they are usually highlighted and not meant to be read inline.
+pub fn markdown_to_plain_text(text: &str) -> String {
+  let mut plain = String::new();
+  MARKDOWN_PARSER.parse(text).walk(|node, _| {
+    if let Some(text) = node.cast::<Text>() {
+      plain.push_str(&text.content);
+    } else if node.is::<Paragraph>()
+      || node.is::<ATXHeading>()
+      || node.is::<SetextHeader>()
+      || node.is::<ListItem>()
+      || node.is::<Softbreak>()
+      || node.is::<Hardbreak>()
+    {
+      // Keep words of neighbouring blocks and lines apart
+      plain.push(' ');
+    }
+  });
+  plain.split_whitespace().join(" ")
+}
+
+/// Shortens text to at most `max_chars` characters, followed by an ellipsis if anything was cut.
+/// Cuts at the last whitespace if there is one, so that words aren't split.
+pub fn truncate_at_word_boundary(text: &str, max_chars: usize) -> String {
+  let Some((end, next)) = text.char_indices().nth(max_chars) else {
+    return text.to_string();
+  };
+  let truncated = &text[..end];
+  let truncated = if next.is_whitespace() {
+    truncated
+  } else {
+    match truncated.rfind(char::is_whitespace) {
+      Some(i) if i > 0 => &truncated[..i],
+      // A single long word, or a script without spaces
+      _ => truncated,
+    }
+  };
+  format!("{}…", truncated.trim_end())
+}
+
+/// A plain text preview of markdown, cut at a word boundary after at most `max_chars` characters.
+pub fn markdown_excerpt(text: &str, max_chars: usize) -> String {
+  truncate_at_word_boundary(&markdown_to_plain_text(text), max_chars)
+}
+
+/// Replaces the urls of inline images like `![alt](url "title")`, where `rewrite` returns the new
+/// url or `None` to keep it. The rest of the text stays exactly as it was written.
+pub fn markdown_rewrite_image_links(text: &str, rewrite: impl Fn(&Url) -> Option<Url>) -> String {
+  let mut destinations = Vec::new();
+  MARKDOWN_PARSER.parse(text).walk(|node, _| {
+    if node.is::<Image>() {
+      if let Some(srcmap) = node.srcmap {
+        destinations.extend(image_destination(text, srcmap.get_byte_offsets()));
+      }
+    }
+  });
+  destinations.sort_unstable();
+
+  // Replace from the end, so that the offsets of earlier images stay valid
+  let mut rewritten = text.to_string();
+  for (start, end) in destinations.into_iter().rev() {
+    let url = rewritten
+      .get(start..end)
+      .and_then(|url| Url::parse(url).ok());
+    if let Some(new_url) = url.as_ref().and_then(&rewrite) {
+      rewritten.replace_range(start..end, new_url.as_str());
+    }
+  }
+  rewritten
+}
+
+/// The byte range of the url in the source of an image. Reference images like `![alt][ref]` have
+/// none.
+fn image_destination(text: &str, (start, end): (usize, usize)) -> Option<(usize, usize)> {
+  let source = text.get(start..end)?;
+  let open = source.rfind("](")? + 2;
+  let rest = source.get(open..)?;
+  let trimmed = rest.trim_start();
+  let (destination, skipped) = match trimmed.strip_prefix('<') {
+    Some(destination) => (destination, rest.len() - destination.len()),
+    None => (trimmed, rest.len() - trimmed.len()),
+  };
+  let len = destination.find(|c: char| c.is_whitespace() || c == ')' || c == '>')?;
+  let url_start = start + open + skipped;
+  Some((url_start, url_start + len))
+}
+
 #[cfg(test)]
 mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
-  use crate::utils::markdown::markdown_to_html;
+  use crate::{
+    error::LemmyErrorType,
+    utils::markdown::{
+      markdown_excerpt,
+      markdown_rewrite_image_links,
+      markdown_to_html,
+      markdown_to_plain_text,
+      normalize_spoilers,
+      truncate_at_word_boundary,
+    },
+  };
+  use url::Url;
 
   #[test]
   fn test_basic_markdown() {
@@ -83,4 +248,134 @@ mod tests {
       );
     });
   }
+
+  #[test]
+  fn test_normalize_spoilers() {
+    let tests = [
+      (
+        ":::spoiler click to see more\nhow spicy!\n:::",
+        "::: spoiler click to see more\nhow spicy!\n:::",
+      ),
+      (
+        "::: Spoiler   click to see more  \nhow spicy!\n  :::  \n",
+        "::: spoiler click to see more\nhow spicy!\n:::\n",
+      ),
+      (
+        "::: spoiler\nno title\n:::",
+        "::: spoiler Spoiler\nno title\n:::",
+      ),
+      (
+        "```\n:::spoiler in code\n```\n:::",
+        "```\n:::spoiler in code\n```\n:::",
+      ),
+      ("no spoiler here", "no spoiler here"),
+    ];
+    for (input, expected) in tests {
+      assert_eq!(expected, normalize_spoilers(input).unwrap(), "{input}");
+    }
+
+    // Normalized spoilers are rendered by the spoiler rule
+    let normalized = normalize_spoilers(":::spoiler title\nhidden\n:::").unwrap();
+    assert_eq!(
+      "<details><summary>title</summary><p>hidden\n</p></details>\n",
+      markdown_to_html(&normalized)
+    );
+  }
+
+  #[test]
+  fn test_invalid_spoilers() {
+    let unclosed = normalize_spoilers("::: spoiler title\nnever closed");
+    assert_eq!(
+      Some(LemmyErrorType::InvalidSpoiler),
+      unclosed.err().map(|e| e.error_type)
+    );
+    let nested = normalize_spoilers("::: spoiler a\n::: spoiler b\n:::\n:::");
+    assert_eq!(
+      Some(LemmyErrorType::InvalidSpoiler),
+      nested.err().map(|e| e.error_type)
+    );
+  }
+
+  #[test]
+  fn test_markdown_to_plain_text() {
+    assert_eq!(
+      "Title Some bold and italic text. with a link",
+      markdown_to_plain_text(
+        "# Title\n\nSome **bold** and *italic* text.\nwith [a link](https://example.com)"
+      )
+    );
+    assert_eq!(
+      "one two after",
+      markdown_to_plain_text("- one\n- two\n\n```rust\nlet x = 1;\n```\nafter")
+    );
+  }
+
+  #[test]
+  fn test_truncate_at_word_boundary() {
+    assert_eq!("short text", truncate_at_word_boundary("short text", 20));
+    assert_eq!(
+      "hello…",
+      truncate_at_word_boundary("hello wonderful world", 12)
+    );
+    assert_eq!(
+      "hello wonderful…",
+      truncate_at_word_boundary("hello wonderful world", 15)
+    );
+    assert_eq!("abcde…", truncate_at_word_boundary("abcdefghij", 5));
+  }
+
+  #[test]
+  fn test_truncate_multibyte() {
+    // Must not panic by slicing inside a multibyte character
+    assert_eq!(
+      "안영하세요…",
+      truncate_at_word_boundary("안영하세요안영하세요", 5)
+    );
+    assert_eq!("héllo…", truncate_at_word_boundary("héllo wörld", 8));
+    assert_eq!("🦀🦀…", truncate_at_word_boundary("🦀🦀🦀", 2));
+    assert_eq!(
+      "日本語 テキスト…",
+      markdown_excerpt("**日本語** テキスト です", 9)
+    );
+  }
+
+  #[test]
+  fn test_markdown_rewrite_image_links() {
+    let proxy = |url: &Url| {
+      if url.domain() == Some("remote.com") {
+        Url::parse(&format!("https://local.com/proxy?path={}", url.path())).ok()
+      } else {
+        None
+      }
+    };
+    let tests = [
+      (
+        "![a](https://remote.com/a.png)",
+        "![a](https://local.com/proxy?path=/a.png)",
+      ),
+      (
+        "text ![a](https://remote.com/a.png \"title\") [link](https://remote.com/page)",
+        "text ![a](https://local.com/proxy?path=/a.png \"title\") [link](https://remote.com/page)",
+      ),
+      (
+        "![a](<https://remote.com/a.png>) ![b](https://local.com/b.png)",
+        "![a](<https://local.com/proxy?path=/a.png>) ![b](https://local.com/b.png)",
+      ),
+      (
+        "`![a](https://remote.com/a.png)`",
+        "`![a](https://remote.com/a.png)`",
+      ),
+      (
+        "![ref][a]\n\n[a]: https://remote.com/a.png",
+        "![ref][a]\n\n[a]: https://remote.com/a.png",
+      ),
+    ];
+    for (input, expected) in tests {
+      assert_eq!(
+        expected,
+        markdown_rewrite_image_links(input, proxy),
+        "{input}"
+      );
+    }
+  }
 }
@@ -5,7 +5,14 @@ use regex::Regex;
 static MENTIONS_REGEX: Lazy<Regex> = Lazy::new(|| {
   Regex::new(r"@(?P<name>[\w.]+)@(?P<domain>[a-zA-Z0-9._:-]+)").expect("compile regex")
 });
-// TODO nothing is done with community / group webfingers yet, so just ignore those for now
+static COMMUNITY_MENTIONS_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"!(?P<name>[\w.]+)@(?P<domain>[a-zA-Z0-9._:-]+)").expect("compile regex")
+});
+
+/// How many communities a single post or comment can mention. Further mentions are left as text,
+/// so that one item can't cause an unbounded number of lookups.
+pub const MAX_COMMUNITY_MENTIONS: usize = 10;
+
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct MentionData {
   pub name: String,
@@ -22,8 +29,20 @@ impl MentionData {
 }
 
 pub fn scrape_text_for_mentions(text: &str) -> Vec<MentionData> {
+  scrape_text(&MENTIONS_REGEX, text)
+}
+
+/// Finds the mentions of communities, written as `!name@domain`. Only the first
+/// [`MAX_COMMUNITY_MENTIONS`] distinct communities are returned.
+pub fn scrape_text_for_community_mentions(text: &str) -> Vec<MentionData> {
+  let mut mentions = scrape_text(&COMMUNITY_MENTIONS_REGEX, text);
+  mentions.truncate(MAX_COMMUNITY_MENTIONS);
+  mentions
+}
+
+fn scrape_text(regex: &Regex, text: &str) -> Vec<MentionData> {
   let mut out: Vec<MentionData> = Vec::new();
-  for caps in MENTIONS_REGEX.captures_iter(text) {
+  for caps in regex.captures_iter(text) {
     if let Some(name) = caps.name("name").map(|c| c.as_str().to_string()) {
       if let Some(domain) = caps.name("domain").map(|c| c.as_str().to_string()) {
         out.push(MentionData { name, domain });
@@ -38,7 +57,11 @@ mod test {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
-  use crate::utils::mention::scrape_text_for_mentions;
+  use crate::utils::mention::{
+    scrape_text_for_community_mentions,
+    scrape_text_for_mentions,
+    MAX_COMMUNITY_MENTIONS,
+  };
 
   #[test]
   fn test_mentions_regex() {
@@ -48,5 +71,24 @@ mod test {
     assert_eq!(mentions[0].name, "tedu".to_string());
     assert_eq!(mentions[0].domain, "honk.teduangst.com".to_string());
     assert_eq!(mentions[1].domain, "lemmy-alpha:8540".to_string());
+    assert_eq!(2, mentions.len());
+
+    let community_mentions = scrape_text_for_community_mentions(text);
+    assert_eq!(1, community_mentions.len());
+    assert_eq!(community_mentions[0].name, "test_community".to_string());
+    assert_eq!(
+      community_mentions[0].domain,
+      "fish.teduangst.com".to_string()
+    );
+  }
+  #[test]
+  fn test_community_mentions_limit() {
+    let text = (0..MAX_COMMUNITY_MENTIONS + 5)
+      .map(|i| format!("!community{i}@lemmy.tld !community{i}@lemmy.tld"))
+      .collect::<Vec<_>>()
+      .join(" ");
+    let community_mentions = scrape_text_for_community_mentions(&text);
+    assert_eq!(MAX_COMMUNITY_MENTIONS, community_mentions.len());
+    assert_eq!("community0", community_mentions[0].name);
   }
 }
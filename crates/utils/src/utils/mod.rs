@@ -1,5 +1,6 @@
 pub mod markdown;
 pub mod mention;
+pub mod muted_words;
 pub mod slurs;
 pub mod time;
 pub mod validation;
@@ -0,0 +1,50 @@
+use itertools::Itertools;
+use regex::Regex;
+
+/// Whether the text contains one of the words as a whole word, ignoring case. This works for words
+/// in any script, and for words which start or end with punctuation like `c++`, where `\b` would
+/// fail.
+pub fn contains_muted_word(text: &str, words: &[String]) -> bool {
+  if words.is_empty() {
+    return false;
+  }
+  let words = words.iter().map(|w| regex::escape(w)).join("|");
+  Regex::new(&format!(r"(?i)(?:^|\W)(?:{words})(?:\W|$)"))
+    .map(|regex| regex.is_match(text))
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::utils::muted_words::contains_muted_word;
+
+  fn words(words: &[&str]) -> Vec<String> {
+    words.iter().map(ToString::to_string).collect()
+  }
+
+  #[test]
+  fn test_contains_muted_word() {
+    let muted = words(&["finale", "c++", "big game"]);
+    assert!(contains_muted_word("Did you see the FINALE?", &muted));
+    assert!(contains_muted_word("finale", &muted));
+    assert!(contains_muted_word("I write C++ for a living", &muted));
+    assert!(contains_muted_word("Who won the Big Game", &muted));
+    // Only whole words match
+    assert!(!contains_muted_word("The finales of both seasons", &muted));
+    assert!(!contains_muted_word("semifinale", &muted));
+    assert!(!contains_muted_word("Anything", &[]));
+  }
+
+  #[test]
+  fn test_contains_muted_word_unicode() {
+    let muted = words(&["мир", "Straße", "日本"]);
+    assert!(contains_muted_word("Привет, МИР!", &muted));
+    assert!(!contains_muted_word("мирный договор", &muted));
+    assert!(contains_muted_word("Die STRAßE ist lang", &muted));
+    assert!(!contains_muted_word("Straßenbahn", &muted));
+    assert!(contains_muted_word("Ich fahre nach 日本", &muted));
+  }
+}
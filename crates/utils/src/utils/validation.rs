@@ -9,6 +9,10 @@ static VALID_ACTOR_NAME_REGEX: Lazy<Regex> =
   Lazy::new(|| Regex::new(r"^[a-zA-Z0-9_]{3,}$").expect("compile regex"));
 static VALID_POST_TITLE_REGEX: Lazy<Regex> =
   Lazy::new(|| Regex::new(r".*\S{3,200}.*").expect("compile regex"));
+static VALID_PAGE_SLUG_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^[a-z0-9_-]{1,100}$").expect("compile regex"));
+static VALID_FLAIR_COLOR_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^#[0-9a-fA-F]{6}$").expect("compile regex"));
 static VALID_MATRIX_ID_REGEX: Lazy<Regex> = Lazy::new(|| {
   Regex::new(r"^@[A-Za-z0-9._=-]+:[A-Za-z0-9.-]+\.[A-Za-z]{2,}$").expect("compile regex")
 });
@@ -21,6 +25,17 @@ static CLEAN_URL_PARAMS_REGEX: Lazy<Regex> = Lazy::new(|| {
 const BODY_MAX_LENGTH: usize = 10000;
 const POST_BODY_MAX_LENGTH: usize = 50000;
 const BIO_MAX_LENGTH: usize = 300;
+pub const CONTENT_WARNING_MAX_LENGTH: usize = 200;
+const BLOCKED_KEYWORD_MAX_LENGTH: usize = 50;
+const BLOCKED_KEYWORDS_MAX_COUNT: usize = 50;
+const NOTIFICATION_MUTED_WORDS_MAX_COUNT: usize = 30;
+const SAVE_TAG_MAX_LENGTH: usize = 50;
+const FLAIR_MAX_LENGTH: usize = 30;
+const MOD_REASON_MAX_LENGTH: usize = 300;
+const FORM_ID_MAX_LENGTH: usize = 100;
+const POLL_OPTION_MAX_LENGTH: usize = 100;
+const POLL_OPTIONS_MIN_COUNT: usize = 2;
+const POLL_OPTIONS_MAX_COUNT: usize = 10;
 const SITE_NAME_MAX_LENGTH: usize = 20;
 const SITE_NAME_MIN_LENGTH: usize = 1;
 const SITE_DESCRIPTION_MAX_LENGTH: usize = 150;
@@ -128,6 +143,15 @@ pub fn is_valid_post_title(title: &str) -> LemmyResult<()> {
   }
 }
 
+/// Community page slugs are used in urls, so only allow lowercase letters, digits, `-` and `_`.
+pub fn is_valid_page_slug(slug: &str) -> LemmyResult<()> {
+  if !VALID_PAGE_SLUG_REGEX.is_match(slug) {
+    Err(LemmyErrorType::InvalidPageSlug.into())
+  } else {
+    Ok(())
+  }
+}
+
 /// This could be post bodies, comments, or any description field
 pub fn is_valid_body_field(body: &Option<String>, post: bool) -> LemmyResult<()> {
   if let Some(body) = body {
@@ -151,6 +175,134 @@ pub fn is_valid_bio_field(bio: &str) -> LemmyResult<()> {
   max_length_check(bio, BIO_MAX_LENGTH, LemmyErrorType::BioLengthOverflow)
 }
 
+pub fn is_valid_content_warning(content_warning: &Option<String>) -> LemmyResult<()> {
+  if let Some(content_warning) = content_warning {
+    max_length_check(
+      content_warning,
+      CONTENT_WARNING_MAX_LENGTH,
+      LemmyErrorType::ContentWarningLengthOverflow,
+    )?;
+  }
+  Ok(())
+}
+
+/// Trims the keywords a user wants to block and removes duplicates, then checks that there aren't
+/// too many of them.
+pub fn clean_blocked_keywords(keywords: &[String]) -> LemmyResult<Vec<String>> {
+  let keywords = trim_unique_words(keywords);
+  if keywords.len() > BLOCKED_KEYWORDS_MAX_COUNT {
+    return Err(LemmyErrorType::TooManyBlockedKeywords.into());
+  }
+  for k in &keywords {
+    max_length_check(
+      k,
+      BLOCKED_KEYWORD_MAX_LENGTH,
+      LemmyErrorType::BlockedKeywordTooLong,
+    )?;
+  }
+  Ok(keywords)
+}
+
+/// Same as the blocked keywords, for the words which a user doesn't want to be notified about.
+pub fn clean_notification_muted_words(words: &[String]) -> LemmyResult<Vec<String>> {
+  let words = trim_unique_words(words);
+  if words.len() > NOTIFICATION_MUTED_WORDS_MAX_COUNT {
+    return Err(LemmyErrorType::TooManyNotificationMutedWords.into());
+  }
+  for w in &words {
+    max_length_check(
+      w,
+      BLOCKED_KEYWORD_MAX_LENGTH,
+      LemmyErrorType::NotificationMutedWordTooLong,
+    )?;
+  }
+  Ok(words)
+}
+
+fn trim_unique_words(words: &[String]) -> Vec<String> {
+  words
+    .iter()
+    .map(|w| w.trim())
+    .filter(|w| !w.is_empty())
+    .unique_by(|w| w.to_lowercase())
+    .map(ToString::to_string)
+    .collect()
+}
+
+/// Trims the tag of a saved post or comment. Empty tags are dropped.
+pub fn clean_save_tag(tag: &Option<String>) -> LemmyResult<Option<String>> {
+  let tag = tag
+    .as_deref()
+    .map(str::trim)
+    .filter(|t| !t.is_empty())
+    .map(ToString::to_string);
+  if let Some(tag) = &tag {
+    max_length_check(tag, SAVE_TAG_MAX_LENGTH, LemmyErrorType::SaveTagTooLong)?;
+  }
+  Ok(tag)
+}
+
+/// Trims a community flair, which has to be a single short line.
+pub fn clean_flair(flair: &str) -> LemmyResult<String> {
+  let flair = flair.trim();
+  if flair.is_empty() || has_newline(flair) || flair.chars().count() > FLAIR_MAX_LENGTH {
+    Err(LemmyErrorType::InvalidFlair.into())
+  } else {
+    Ok(flair.to_string())
+  }
+}
+
+/// Trims the text of a mod reason template, which can't be empty.
+pub fn clean_mod_reason(reason: &str) -> LemmyResult<String> {
+  let reason = reason.trim();
+  if reason.is_empty() || reason.chars().count() > MOD_REASON_MAX_LENGTH {
+    Err(LemmyErrorType::InvalidModReason.into())
+  } else {
+    Ok(reason.to_string())
+  }
+}
+
+/// Flair colors are hex colors like `#1e90ff`, so that they can be put into styles as they are.
+pub fn is_valid_flair_color(color: &Option<String>) -> LemmyResult<()> {
+  match color {
+    Some(color) if !VALID_FLAIR_COLOR_REGEX.is_match(color) => {
+      Err(LemmyErrorType::InvalidFlairColor.into())
+    }
+    _ => Ok(()),
+  }
+}
+
+/// Trims the options of a poll. Each has to be a single line, and they have to be distinct.
+pub fn clean_poll_options(options: &[String]) -> LemmyResult<Vec<String>> {
+  let options = options
+    .iter()
+    .map(|o| o.trim().to_string())
+    .collect::<Vec<_>>();
+  let valid = (POLL_OPTIONS_MIN_COUNT..=POLL_OPTIONS_MAX_COUNT).contains(&options.len())
+    && options
+      .iter()
+      .all(|o| !o.is_empty() && !has_newline(o) && o.chars().count() <= POLL_OPTION_MAX_LENGTH)
+    && options.iter().all_unique();
+  if valid {
+    Ok(options)
+  } else {
+    Err(LemmyErrorType::InvalidPoll.into())
+  }
+}
+
+pub fn is_valid_form_id(form_id: &Option<String>) -> LemmyResult<()> {
+  if let Some(form_id) = form_id {
+    min_max_length_check(
+      form_id,
+      1,
+      FORM_ID_MAX_LENGTH,
+      LemmyErrorType::InvalidFormId,
+      LemmyErrorType::InvalidFormId,
+    )?;
+  }
+  Ok(())
+}
+
 /// Checks the site name length, the limit as defined in the DB.
 pub fn site_name_length_check(name: &str) -> LemmyResult<()> {
   min_max_length_check(
@@ -321,16 +473,27 @@ mod tests {
       build_and_check_regex,
       check_site_visibility_valid,
       check_url_scheme,
+      clean_blocked_keywords,
+      clean_flair,
+      clean_mod_reason,
+      clean_notification_muted_words,
+      clean_poll_options,
+      clean_save_tag,
       clean_url_params,
       generate_totp_2fa_secret,
       is_valid_actor_name,
       is_valid_bio_field,
+      is_valid_content_warning,
       is_valid_display_name,
+      is_valid_flair_color,
+      is_valid_form_id,
       is_valid_matrix_id,
+      is_valid_page_slug,
       is_valid_post_title,
       site_description_length_check,
       site_name_length_check,
       BIO_MAX_LENGTH,
+      CONTENT_WARNING_MAX_LENGTH,
       SITE_DESCRIPTION_MAX_LENGTH,
       SITE_NAME_MAX_LENGTH,
     },
@@ -387,6 +550,94 @@ mod tests {
     assert!(is_valid_post_title("\n \n \n \n    		").is_err()); // tabs/spaces/newlines
   }
 
+  #[test]
+  fn test_valid_page_slug() {
+    assert!(is_valid_page_slug("rules").is_ok());
+    assert!(is_valid_page_slug("faq-2_en").is_ok());
+    assert!(is_valid_page_slug("").is_err());
+    assert!(is_valid_page_slug("Rules").is_err());
+    assert!(is_valid_page_slug("a/b").is_err());
+    assert!(is_valid_page_slug(&"a".repeat(101)).is_err());
+  }
+
+  #[test]
+  fn test_clean_blocked_keywords() {
+    let keywords = ["  Spoiler ", "spoiler", "", "finale"].map(String::from);
+    assert_eq!(
+      vec!["Spoiler".to_string(), "finale".to_string()],
+      clean_blocked_keywords(&keywords).unwrap()
+    );
+    assert!(clean_blocked_keywords(&["a".repeat(51)]).is_err());
+    let too_many: Vec<String> = (0..51).map(|i| i.to_string()).collect();
+    assert!(clean_blocked_keywords(&too_many).is_err());
+  }
+
+  #[test]
+  fn test_clean_notification_muted_words() {
+    let words = [" Finale", "FINALE", "ending "].map(String::from);
+    assert_eq!(
+      vec!["Finale".to_string(), "ending".to_string()],
+      clean_notification_muted_words(&words).unwrap()
+    );
+    let too_many: Vec<String> = (0..31).map(|i| i.to_string()).collect();
+    assert!(clean_notification_muted_words(&too_many).is_err());
+  }
+
+  #[test]
+  fn test_clean_save_tag() {
+    assert_eq!(
+      Some("Recipes".to_string()),
+      clean_save_tag(&Some(" Recipes ".to_string())).unwrap()
+    );
+    assert_eq!(None, clean_save_tag(&Some("  ".to_string())).unwrap());
+    assert_eq!(None, clean_save_tag(&None).unwrap());
+    assert!(clean_save_tag(&Some("a".repeat(51))).is_err());
+  }
+
+  #[test]
+  fn test_flair() {
+    assert_eq!("Regular", clean_flair(" Regular ").unwrap());
+    assert!(clean_flair(" ").is_err());
+    assert!(clean_flair("Verified\nDeveloper").is_err());
+    assert!(clean_flair(&"a".repeat(31)).is_err());
+
+    assert!(is_valid_flair_color(&Some("#1e90FF".to_string())).is_ok());
+    assert!(is_valid_flair_color(&None).is_ok());
+    assert!(is_valid_flair_color(&Some("red".to_string())).is_err());
+    assert!(is_valid_flair_color(&Some("#1e90ff;display:none".to_string())).is_err());
+  }
+
+  #[test]
+  fn test_clean_mod_reason() {
+    assert_eq!("Spam", clean_mod_reason(" Spam\n").unwrap());
+    assert!(clean_mod_reason(" \n ").is_err());
+    assert!(clean_mod_reason(&"a".repeat(301)).is_err());
+  }
+
+  #[test]
+  fn test_poll_options() {
+    let options = |o: &[&str]| o.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+      options(&["Yes", "No"]),
+      clean_poll_options(&options(&[" Yes", "No "])).unwrap()
+    );
+    assert!(clean_poll_options(&options(&["Yes"])).is_err());
+    assert!(clean_poll_options(&options(&["Yes", " "])).is_err());
+    assert!(clean_poll_options(&options(&["Yes", "Yes "])).is_err());
+    assert!(clean_poll_options(&options(&["Yes", "No\nMaybe"])).is_err());
+    assert!(clean_poll_options(&options(&["Yes", &"a".repeat(101)])).is_err());
+    let too_many = (0..11).map(|i| i.to_string()).collect::<Vec<_>>();
+    assert!(clean_poll_options(&too_many).is_err());
+  }
+
+  #[test]
+  fn test_valid_form_id() {
+    assert!(is_valid_form_id(&None).is_ok());
+    assert!(is_valid_form_id(&Some("3f2b8c1e-77d4-4c5e-9a61-0c6e2d9b1f40".to_string())).is_ok());
+    assert!(is_valid_form_id(&Some(String::new())).is_err());
+    assert!(is_valid_form_id(&Some("a".repeat(101))).is_err());
+  }
+
   #[test]
   fn test_valid_matrix_id() {
     assert!(is_valid_matrix_id("@dess:matrix.org").is_ok());
@@ -458,6 +709,22 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_valid_content_warning() {
+    assert!(is_valid_content_warning(&Some("Spoilers for the finale".to_string())).is_ok());
+    assert!(is_valid_content_warning(&None).is_ok());
+
+    let invalid_result = is_valid_content_warning(&Some(
+      (0..CONTENT_WARNING_MAX_LENGTH + 1)
+        .map(|_| 'A')
+        .collect::<String>(),
+    ));
+    assert_eq!(
+      LemmyErrorType::ContentWarningLengthOverflow,
+      invalid_result.unwrap_err().error_type
+    );
+  }
+
   #[test]
   fn test_valid_site_description() {
     assert!(site_description_length_check(
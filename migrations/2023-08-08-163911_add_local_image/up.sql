@@ -0,0 +1,9 @@
+CREATE TABLE local_image (
+    pictrs_alias text PRIMARY KEY,
+    pictrs_delete_token text NOT NULL,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    published timestamp NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_local_image_local_user_id ON local_image (local_user_id);
+
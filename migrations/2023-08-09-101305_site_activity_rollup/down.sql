@@ -0,0 +1,2 @@
+DROP TABLE site_activity_rollup;
+
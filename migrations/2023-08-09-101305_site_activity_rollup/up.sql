@@ -0,0 +1,11 @@
+-- Daily activity statistics for the local site. Rows are only added for finished days, by a
+-- scheduled task, and never change afterwards.
+CREATE TABLE site_activity_rollup (
+    day date PRIMARY KEY,
+    users bigint NOT NULL DEFAULT 0,
+    posts bigint NOT NULL DEFAULT 0,
+    comments bigint NOT NULL DEFAULT 0,
+    users_active bigint NOT NULL DEFAULT 0,
+    instances_linked bigint NOT NULL DEFAULT 0
+);
+
@@ -0,0 +1,9 @@
+ALTER TABLE post
+    DROP COLUMN archive_url;
+
+ALTER TABLE local_site
+    DROP COLUMN enable_url_archiving;
+
+ALTER TABLE local_site
+    DROP COLUMN url_archiving_excluded_domains;
+
@@ -0,0 +1,9 @@
+ALTER TABLE post
+    ADD COLUMN archive_url text;
+
+ALTER TABLE local_site
+    ADD COLUMN enable_url_archiving boolean DEFAULT FALSE NOT NULL;
+
+ALTER TABLE local_site
+    ADD COLUMN url_archiving_excluded_domains text;
+
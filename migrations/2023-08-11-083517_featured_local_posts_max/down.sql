@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    DROP COLUMN featured_local_posts_max;
+
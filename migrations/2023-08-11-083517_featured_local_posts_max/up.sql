@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN featured_local_posts_max integer DEFAULT 5 NOT NULL;
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_user
+    ADD COLUMN hide_content_below_score integer;
+
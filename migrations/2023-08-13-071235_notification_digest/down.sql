@@ -0,0 +1,6 @@
+ALTER TABLE local_user
+    DROP COLUMN send_notification_digest,
+    DROP COLUMN last_digest_sent_at;
+
+DROP TYPE notification_digest_enum;
+
@@ -0,0 +1,10 @@
+CREATE TYPE notification_digest_enum AS enum (
+    'Never',
+    'Daily',
+    'Weekly'
+);
+
+ALTER TABLE local_user
+    ADD COLUMN send_notification_digest notification_digest_enum DEFAULT 'Never' NOT NULL,
+    ADD COLUMN last_digest_sent_at timestamp;
+
@@ -0,0 +1,8 @@
+ALTER TABLE local_site
+    DROP COLUMN disallow_nsfw_content,
+    DROP COLUMN federate_nsfw_outbound;
+
+ALTER TABLE instance
+    DROP COLUMN refuses_nsfw,
+    DROP COLUMN dropped_nsfw_objects;
+
@@ -0,0 +1,8 @@
+ALTER TABLE local_site
+    ADD COLUMN disallow_nsfw_content boolean DEFAULT FALSE NOT NULL,
+    ADD COLUMN federate_nsfw_outbound boolean DEFAULT TRUE NOT NULL;
+
+ALTER TABLE instance
+    ADD COLUMN refuses_nsfw boolean DEFAULT FALSE NOT NULL,
+    ADD COLUMN dropped_nsfw_objects bigint DEFAULT 0 NOT NULL;
+
@@ -0,0 +1,11 @@
+CREATE TABLE community_page (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    slug varchar(100) NOT NULL,
+    title varchar(200) NOT NULL,
+    content text NOT NULL,
+    published timestamp NOT NULL DEFAULT now(),
+    updated timestamp,
+    UNIQUE (community_id, slug)
+);
+
@@ -0,0 +1,10 @@
+DROP TABLE sent_activity_delivery;
+
+DROP TYPE delivery_status_enum;
+
+ALTER TABLE sent_activity
+    DROP COLUMN object_id;
+
+ALTER TABLE received_activity
+    DROP COLUMN object_id;
+
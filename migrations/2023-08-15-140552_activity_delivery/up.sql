@@ -0,0 +1,30 @@
+-- Keep track of which inboxes an activity was handed to, so that admins can debug federation of
+-- single objects. Rows are cleared by a scheduled task after a configurable retention window.
+CREATE TYPE delivery_status_enum AS enum (
+    'Queued',
+    'SkippedDeadInstance'
+);
+
+CREATE TABLE sent_activity_delivery (
+    id bigserial PRIMARY KEY,
+    sent_activity_id bigint REFERENCES sent_activity ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    inbox text NOT NULL,
+    status delivery_status_enum NOT NULL,
+    published timestamp NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_sent_activity_delivery_sent_activity ON sent_activity_delivery (sent_activity_id);
+
+CREATE INDEX idx_sent_activity_delivery_published ON sent_activity_delivery (published);
+
+-- The post, comment or other object which an activity refers to
+ALTER TABLE sent_activity
+    ADD COLUMN object_id text;
+
+ALTER TABLE received_activity
+    ADD COLUMN object_id text;
+
+CREATE INDEX idx_sent_activity_object_id ON sent_activity (object_id);
+
+CREATE INDEX idx_received_activity_object_id ON received_activity (object_id);
+
@@ -0,0 +1,11 @@
+-- A transfer of the top moderator position, which is pending until the recipient accepts it
+CREATE TABLE community_transfer_request (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL UNIQUE,
+    sender_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    recipient_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    published timestamp NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_community_transfer_request_recipient ON community_transfer_request (recipient_id);
+
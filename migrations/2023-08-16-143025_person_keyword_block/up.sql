@@ -0,0 +1,8 @@
+CREATE TABLE person_keyword_block (
+    id serial PRIMARY KEY,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    keyword varchar(50) NOT NULL,
+    published timestamp NOT NULL DEFAULT now(),
+    UNIQUE (person_id, keyword)
+);
+
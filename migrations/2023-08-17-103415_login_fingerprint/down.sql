@@ -0,0 +1,5 @@
+DROP TABLE login_fingerprint;
+
+ALTER TABLE local_user
+    DROP COLUMN notify_new_logins;
+
@@ -0,0 +1,15 @@
+ALTER TABLE local_user
+    ADD COLUMN notify_new_logins boolean NOT NULL DEFAULT FALSE;
+
+-- Hashes of the user agent and IP prefix of previous logins, to recognize new devices
+CREATE TABLE login_fingerprint (
+    id serial PRIMARY KEY,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    fingerprint varchar(64) NOT NULL,
+    published timestamp NOT NULL DEFAULT now(),
+    last_used timestamp NOT NULL DEFAULT now(),
+    UNIQUE (local_user_id, fingerprint)
+);
+
+CREATE INDEX idx_login_fingerprint_last_used ON login_fingerprint (last_used);
+
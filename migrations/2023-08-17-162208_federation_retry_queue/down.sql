@@ -0,0 +1,5 @@
+DROP TABLE federation_retry_queue;
+
+ALTER TABLE instance
+    DROP COLUMN dropped_activities;
+
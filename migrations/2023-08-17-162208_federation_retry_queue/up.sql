@@ -0,0 +1,20 @@
+-- Deliveries to unreachable instances, which are retried with exponential backoff until they
+-- succeed or become too old
+CREATE TABLE federation_retry_queue (
+    id bigserial PRIMARY KEY,
+    instance_id int REFERENCES instance ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    inbox text NOT NULL,
+    actor_id text NOT NULL,
+    activity json NOT NULL,
+    attempts int NOT NULL DEFAULT 0,
+    next_retry_at timestamp NOT NULL DEFAULT now(),
+    published timestamp NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_federation_retry_queue_instance ON federation_retry_queue (instance_id);
+
+CREATE INDEX idx_federation_retry_queue_next_retry_at ON federation_retry_queue (next_retry_at);
+
+ALTER TABLE instance
+    ADD COLUMN dropped_activities bigint DEFAULT 0 NOT NULL;
+
@@ -0,0 +1,6 @@
+ALTER TABLE community
+    DROP COLUMN default_post_sort,
+    DROP COLUMN default_comment_sort;
+
+DROP TYPE comment_sort_type_enum;
+
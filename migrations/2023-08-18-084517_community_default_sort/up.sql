@@ -0,0 +1,13 @@
+CREATE TYPE comment_sort_type_enum AS enum (
+    'Hot',
+    'Top',
+    'New',
+    'Old',
+    'Controversial'
+);
+
+-- Sorts which are used for the community when the request and the user don't specify any
+ALTER TABLE community
+    ADD COLUMN default_post_sort sort_type_enum,
+    ADD COLUMN default_comment_sort comment_sort_type_enum;
+
@@ -0,0 +1,4 @@
+ALTER TABLE local_site_rate_limit
+    DROP COLUMN export,
+    DROP COLUMN export_per_second;
+
@@ -0,0 +1,4 @@
+ALTER TABLE local_site_rate_limit
+    ADD COLUMN export int NOT NULL DEFAULT 6,
+    ADD COLUMN export_per_second int NOT NULL DEFAULT 600;
+
@@ -0,0 +1,4 @@
+ALTER TABLE community
+    DROP COLUMN min_account_age_days_to_vote,
+    DROP COLUMN only_followers_can_vote;
+
@@ -0,0 +1,5 @@
+-- Restrictions on who is allowed to vote in a community, against brigading from fresh accounts
+ALTER TABLE community
+    ADD COLUMN min_account_age_days_to_vote int NOT NULL DEFAULT 0,
+    ADD COLUMN only_followers_can_vote boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,8 @@
+DROP TABLE reminder;
+
+ALTER TABLE post_saved
+    DROP COLUMN remind_at;
+
+ALTER TABLE comment_saved
+    DROP COLUMN remind_at;
+
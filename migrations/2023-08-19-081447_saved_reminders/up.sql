@@ -0,0 +1,26 @@
+ALTER TABLE post_saved
+    ADD COLUMN remind_at timestamp;
+
+ALTER TABLE comment_saved
+    ADD COLUMN remind_at timestamp;
+
+CREATE INDEX idx_post_saved_remind_at ON post_saved (remind_at)
+WHERE
+    remind_at IS NOT NULL;
+
+CREATE INDEX idx_comment_saved_remind_at ON comment_saved (remind_at)
+WHERE
+    remind_at IS NOT NULL;
+
+-- Reminders which are due show up in the inbox
+CREATE TABLE reminder (
+    id serial PRIMARY KEY,
+    recipient_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    comment_id int REFERENCES COMMENT ON UPDATE CASCADE ON DELETE CASCADE,
+    read boolean DEFAULT FALSE NOT NULL,
+    published timestamp NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_reminder_recipient ON reminder (recipient_id);
+
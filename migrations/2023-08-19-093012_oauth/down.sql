@@ -0,0 +1,9 @@
+ALTER TABLE local_site
+    DROP COLUMN oauth_registration;
+
+DROP TABLE oauth_state;
+
+DROP TABLE oauth_account;
+
+DROP TABLE oauth_provider;
+
@@ -0,0 +1,36 @@
+CREATE TABLE oauth_provider (
+    id serial PRIMARY KEY,
+    display_name text NOT NULL,
+    issuer text NOT NULL UNIQUE,
+    client_id text NOT NULL,
+    client_secret text NOT NULL,
+    scopes text NOT NULL DEFAULT 'openid email',
+    enabled boolean NOT NULL DEFAULT TRUE,
+    published timestamp NOT NULL DEFAULT now(),
+    updated timestamp
+);
+
+CREATE TABLE oauth_account (
+    id serial PRIMARY KEY,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    oauth_provider_id int REFERENCES oauth_provider ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    oauth_user_id text NOT NULL,
+    published timestamp NOT NULL DEFAULT now(),
+    UNIQUE (oauth_provider_id, oauth_user_id)
+);
+
+CREATE INDEX idx_oauth_account_local_user ON oauth_account (local_user_id);
+
+-- The pending authorizations, only kept for a few minutes
+CREATE TABLE oauth_state (
+    id serial PRIMARY KEY,
+    state uuid NOT NULL UNIQUE DEFAULT gen_random_uuid (),
+    oauth_provider_id int REFERENCES oauth_provider ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    -- Set if a logged in user wants to link the account
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE,
+    published timestamp NOT NULL DEFAULT now()
+);
+
+ALTER TABLE local_site
+    ADD COLUMN oauth_registration boolean NOT NULL DEFAULT FALSE;
+
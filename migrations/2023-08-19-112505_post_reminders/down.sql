@@ -0,0 +1,5 @@
+ALTER TABLE reminder
+    DROP COLUMN note;
+
+DROP TABLE post_reminder;
+
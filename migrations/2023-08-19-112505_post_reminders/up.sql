@@ -0,0 +1,16 @@
+CREATE TABLE post_reminder (
+    id serial PRIMARY KEY,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    remind_at timestamp NOT NULL,
+    note text,
+    published timestamp NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_post_reminder_person ON post_reminder (person_id);
+
+CREATE INDEX idx_post_reminder_remind_at ON post_reminder (remind_at);
+
+ALTER TABLE reminder
+    ADD COLUMN note text;
+
@@ -0,0 +1,9 @@
+ALTER TABLE post
+    DROP COLUMN content_warning;
+
+ALTER TABLE comment
+    DROP COLUMN content_warning;
+
+ALTER TABLE local_site
+    DROP COLUMN content_warning_sets_nsfw;
+
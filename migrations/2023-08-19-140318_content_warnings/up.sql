@@ -0,0 +1,9 @@
+ALTER TABLE post
+    ADD COLUMN content_warning text;
+
+ALTER TABLE comment
+    ADD COLUMN content_warning text;
+
+ALTER TABLE local_site
+    ADD COLUMN content_warning_sets_nsfw boolean NOT NULL DEFAULT FALSE;
+
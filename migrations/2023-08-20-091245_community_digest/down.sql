@@ -0,0 +1,5 @@
+ALTER TABLE local_site
+    DROP COLUMN community_digest_bot_id;
+
+DROP TABLE community_digest;
+
@@ -0,0 +1,17 @@
+CREATE TABLE community_digest (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL UNIQUE,
+    enabled boolean NOT NULL DEFAULT TRUE,
+    day_of_week smallint NOT NULL,
+    hour smallint NOT NULL,
+    top_posts int NOT NULL,
+    min_score int NOT NULL,
+    feature boolean NOT NULL DEFAULT FALSE,
+    last_generated_for timestamp,
+    last_post_id int REFERENCES post ON UPDATE CASCADE ON DELETE SET NULL,
+    published timestamp NOT NULL DEFAULT now()
+);
+
+ALTER TABLE local_site
+    ADD COLUMN community_digest_bot_id int REFERENCES person ON UPDATE CASCADE ON DELETE SET NULL;
+
@@ -0,0 +1,3 @@
+ALTER TABLE community
+    DROP COLUMN commenting_restricted_to_mods;
+
@@ -0,0 +1,3 @@
+ALTER TABLE community
+    ADD COLUMN commenting_restricted_to_mods boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,5 @@
+DROP TABLE community_contributor_rollup;
+
+ALTER TABLE local_user
+    DROP COLUMN exclude_from_leaderboards;
+
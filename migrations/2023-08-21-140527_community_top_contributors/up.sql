@@ -0,0 +1,14 @@
+ALTER TABLE local_user
+    ADD COLUMN exclude_from_leaderboards boolean NOT NULL DEFAULT FALSE;
+
+-- All time contributions of each person to a community. This is rebuilt by a daily scheduled
+-- task, shorter ranges are counted from the post and comment tables directly.
+CREATE TABLE community_contributor_rollup (
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    post_count bigint NOT NULL DEFAULT 0,
+    comment_count bigint NOT NULL DEFAULT 0,
+    score bigint NOT NULL DEFAULT 0,
+    PRIMARY KEY (community_id, person_id)
+);
+
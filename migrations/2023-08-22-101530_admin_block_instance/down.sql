@@ -0,0 +1,11 @@
+DROP TABLE admin_allow_instance;
+
+DROP TABLE admin_block_instance;
+
+ALTER TABLE federation_allowlist
+    DROP COLUMN reason;
+
+ALTER TABLE federation_blocklist
+    DROP COLUMN reason,
+    DROP COLUMN expires;
+
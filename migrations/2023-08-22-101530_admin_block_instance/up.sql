@@ -0,0 +1,27 @@
+ALTER TABLE federation_blocklist
+    ADD COLUMN reason text,
+    ADD COLUMN expires timestamp;
+
+ALTER TABLE federation_allowlist
+    ADD COLUMN reason text;
+
+-- Modlog entries for changes to the federation blocklist and allowlist
+CREATE TABLE admin_block_instance (
+    id serial PRIMARY KEY,
+    instance_id int REFERENCES instance ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    blocked boolean NOT NULL,
+    reason text,
+    expires timestamp,
+    when_ timestamp NOT NULL DEFAULT now()
+);
+
+CREATE TABLE admin_allow_instance (
+    id serial PRIMARY KEY,
+    instance_id int REFERENCES instance ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    allowed boolean NOT NULL,
+    reason text,
+    when_ timestamp NOT NULL DEFAULT now()
+);
+
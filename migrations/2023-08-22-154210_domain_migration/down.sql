@@ -0,0 +1,4 @@
+DROP TABLE domain_migration;
+
+DROP TYPE domain_migration_stage_enum;
+
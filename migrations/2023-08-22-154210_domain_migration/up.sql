@@ -0,0 +1,26 @@
+CREATE TYPE domain_migration_stage_enum AS enum (
+    'Person',
+    'Community',
+    'Site',
+    'Post',
+    'Comment',
+    'PrivateMessage',
+    'Instance',
+    'Federate',
+    'Done'
+);
+
+-- Moving the local instance to a new domain. The progress is stored so that an interrupted
+-- migration can be resumed, and old domains are kept as aliases which redirect to the new one.
+CREATE TABLE domain_migration (
+    id serial PRIMARY KEY,
+    old_domain varchar(255) NOT NULL UNIQUE,
+    new_domain varchar(255) NOT NULL,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    stage domain_migration_stage_enum NOT NULL DEFAULT 'Person',
+    -- The id of the last row of the current stage which was migrated
+    last_id int NOT NULL DEFAULT 0,
+    published timestamp NOT NULL DEFAULT now(),
+    updated timestamp
+);
+
@@ -0,0 +1,4 @@
+DROP INDEX idx_post_like_published, idx_comment_like_published;
+
+DROP TABLE vote_anomaly;
+
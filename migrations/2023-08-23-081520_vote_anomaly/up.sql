@@ -0,0 +1,23 @@
+-- Posts and comments which received a vote surge far above the usual in their community, and
+-- whose voters mostly came from a single instance or from new accounts. Only shown to admins.
+CREATE TABLE vote_anomaly (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    -- Null if the votes were on the post itself
+    comment_id int REFERENCES COMMENT ON UPDATE CASCADE ON DELETE CASCADE,
+    vote_count int NOT NULL,
+    z_score float8 NOT NULL,
+    -- Number of votes per instance domain of the voters
+    instance_distribution jsonb NOT NULL,
+    -- Number of voters per account age
+    account_age_histogram jsonb NOT NULL,
+    published timestamp NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_vote_anomaly_published ON vote_anomaly (published DESC);
+
+CREATE INDEX idx_post_like_published ON post_like (published);
+
+CREATE INDEX idx_comment_like_published ON comment_like (published);
+
@@ -0,0 +1,11 @@
+-- Translations of a post body, written by the author. The post body stays the canonical version.
+CREATE TABLE post_translation (
+    id serial PRIMARY KEY,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    language_id int REFERENCES
+    LANGUAGE ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    content text NOT NULL,
+    published timestamp NOT NULL DEFAULT now(),
+    UNIQUE (post_id, language_id)
+);
+
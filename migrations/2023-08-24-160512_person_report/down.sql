@@ -0,0 +1,4 @@
+DROP TABLE admin_clear_person_profile;
+
+DROP TABLE person_report;
+
@@ -0,0 +1,27 @@
+-- Reports of a user profile itself, like an offensive display name or bio
+CREATE TABLE person_report (
+    id serial PRIMARY KEY,
+    creator_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    original_display_name text,
+    original_bio text,
+    reason text NOT NULL,
+    resolved bool NOT NULL DEFAULT FALSE,
+    resolver_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE,
+    published timestamp NOT NULL DEFAULT now(),
+    updated timestamp NULL
+);
+
+CREATE INDEX idx_person_report_published ON person_report (published DESC);
+
+-- Modlog entries for clearing the display name or bio of a user
+CREATE TABLE admin_clear_person_profile (
+    id serial PRIMARY KEY,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    cleared_display_name boolean NOT NULL,
+    cleared_bio boolean NOT NULL,
+    reason text,
+    when_ timestamp NOT NULL DEFAULT now()
+);
+
@@ -0,0 +1,6 @@
+ALTER TABLE local_site
+    DROP COLUMN account_deletion_cooling_off_days;
+
+ALTER TABLE local_user
+    DROP COLUMN deletion_scheduled_at;
+
@@ -0,0 +1,12 @@
+ALTER TABLE local_site
+    ADD COLUMN account_deletion_cooling_off_days int NOT NULL DEFAULT 7;
+
+-- When the account gets deleted, if the user asked for it. Until then they can't log in, and
+-- their posts and comments are hidden.
+ALTER TABLE local_user
+    ADD COLUMN deletion_scheduled_at timestamp;
+
+CREATE INDEX idx_local_user_deletion_scheduled_at ON local_user (deletion_scheduled_at)
+WHERE
+    deletion_scheduled_at IS NOT NULL;
+
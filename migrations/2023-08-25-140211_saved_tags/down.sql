@@ -0,0 +1,6 @@
+ALTER TABLE post_saved
+    DROP COLUMN tag;
+
+ALTER TABLE comment_saved
+    DROP COLUMN tag;
+
@@ -0,0 +1,11 @@
+-- Lets users sort their saved posts and comments into folders. Tags are private to the user.
+ALTER TABLE post_saved
+    ADD COLUMN tag text;
+
+ALTER TABLE comment_saved
+    ADD COLUMN tag text;
+
+CREATE INDEX idx_post_saved_person_tag ON post_saved (person_id, tag);
+
+CREATE INDEX idx_comment_saved_person_tag ON comment_saved (person_id, tag);
+
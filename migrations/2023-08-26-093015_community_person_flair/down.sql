@@ -0,0 +1,4 @@
+DROP TABLE community_person_flair;
+
+DROP TABLE community_flair_option;
+
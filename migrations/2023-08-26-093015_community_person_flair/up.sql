@@ -0,0 +1,23 @@
+-- The title of a person in a community, shown next to their name on content in that community.
+CREATE TABLE community_person_flair (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    flair text NOT NULL,
+    color text,
+    -- The mod who assigned it, or the person themselves. Empty for flairs of remote communities.
+    set_by_id int REFERENCES person ON UPDATE CASCADE ON DELETE SET NULL,
+    published timestamp NOT NULL DEFAULT now(),
+    UNIQUE (community_id, person_id)
+);
+
+-- Flairs which members can pick for themselves. Members can only pick one if there are any.
+CREATE TABLE community_flair_option (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    flair text NOT NULL,
+    color text,
+    published timestamp NOT NULL DEFAULT now(),
+    UNIQUE (community_id, flair)
+);
+
@@ -0,0 +1,2 @@
+DROP TABLE form_submission;
+
@@ -0,0 +1,13 @@
+-- The form ids of recently created posts and comments. Submitting the same form again returns the
+-- existing post or comment instead of creating a duplicate.
+CREATE TABLE form_submission (
+    id serial PRIMARY KEY,
+    creator_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    form_id text NOT NULL,
+    -- Empty while the post or comment is being created
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    comment_id int REFERENCES COMMENT ON UPDATE CASCADE ON DELETE CASCADE,
+    published timestamp NOT NULL DEFAULT now(),
+    UNIQUE (creator_id, form_id)
+);
+
@@ -0,0 +1,7 @@
+-- Posts which were created while pictrs was unavailable, so that their thumbnails are generated
+-- once it is back.
+CREATE TABLE post_thumbnail_retry (
+    post_id int PRIMARY KEY REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    published timestamp NOT NULL DEFAULT now()
+);
+
@@ -0,0 +1,12 @@
+-- Daily statistics of local communities for their moderators. Rows are only added for finished
+-- days, by a scheduled task, and never change afterwards.
+CREATE TABLE community_aggregates_snapshot (
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    day date NOT NULL,
+    subscribers bigint NOT NULL DEFAULT 0,
+    posts bigint NOT NULL DEFAULT 0,
+    comments bigint NOT NULL DEFAULT 0,
+    users_active bigint NOT NULL DEFAULT 0,
+    PRIMARY KEY (community_id, day)
+);
+
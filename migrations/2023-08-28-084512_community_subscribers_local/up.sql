@@ -0,0 +1,101 @@
+-- Subscribers of remote communities are refreshed from their followers collection, so that the
+-- total is the same on all instances. The local followers are counted separately.
+ALTER TABLE community_aggregates
+    ADD COLUMN subscribers_local bigint NOT NULL DEFAULT 0;
+
+-- Pending follows weren't excluded, and accepting a follow didn't count it. If the person was
+-- deleted along with their follows, they aren't known anymore, so the local followers are
+-- recounted.
+CREATE OR REPLACE FUNCTION community_aggregates_subscriber_count ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+    diff bigint := 0;
+    follow_community_id int;
+    follow_person_id int;
+    person_local boolean;
+BEGIN
+    IF (TG_OP = 'INSERT') THEN
+        IF NOT NEW.pending THEN
+            diff := 1;
+        END IF;
+        follow_community_id := NEW.community_id;
+        follow_person_id := NEW.person_id;
+    ELSIF (TG_OP = 'DELETE') THEN
+        IF NOT OLD.pending THEN
+            diff := -1;
+        END IF;
+        follow_community_id := OLD.community_id;
+        follow_person_id := OLD.person_id;
+    ELSIF (TG_OP = 'UPDATE') THEN
+        IF (OLD.pending AND NOT NEW.pending) THEN
+            diff := 1;
+        ELSIF (NOT OLD.pending AND NEW.pending) THEN
+            diff := -1;
+        END IF;
+        follow_community_id := NEW.community_id;
+        follow_person_id := NEW.person_id;
+    END IF;
+    IF diff = 0 THEN
+        RETURN NULL;
+    END IF;
+    SELECT
+        local INTO person_local
+    FROM
+        person
+    WHERE
+        id = follow_person_id;
+    UPDATE
+        community_aggregates
+    SET
+        subscribers = subscribers + diff,
+        subscribers_local = CASE WHEN person_local THEN
+            subscribers_local + diff
+        WHEN person_local IS NULL THEN
+            (
+                SELECT
+                    count(*)
+                FROM
+                    community_follower cf
+                    INNER JOIN person pe ON pe.id = cf.person_id
+                WHERE
+                    cf.community_id = follow_community_id
+                    AND pe.local
+                    AND NOT cf.pending)
+        ELSE
+            subscribers_local
+        END
+    WHERE
+        community_id = follow_community_id;
+    RETURN NULL;
+END
+$$;
+
+DROP TRIGGER community_aggregates_subscriber_count ON community_follower;
+
+CREATE TRIGGER community_aggregates_subscriber_count
+    AFTER INSERT OR DELETE OR UPDATE OF pending ON community_follower
+    FOR EACH ROW
+    EXECUTE PROCEDURE community_aggregates_subscriber_count ();
+
+UPDATE
+    community_aggregates ca
+SET
+    subscribers = coalesce(cf.subscribers, 0),
+    subscribers_local = coalesce(cf.subscribers_local, 0)
+FROM (
+    SELECT
+        c.id AS community_id,
+        count(f.id) AS subscribers,
+        count(f.id) FILTER (WHERE pe.local) AS subscribers_local
+    FROM
+        community c
+        LEFT JOIN community_follower f ON f.community_id = c.id
+            AND NOT f.pending
+        LEFT JOIN person pe ON pe.id = f.person_id
+    GROUP BY
+        c.id) cf
+WHERE
+    cf.community_id = ca.community_id;
+
@@ -0,0 +1,28 @@
+CREATE TABLE poll (
+    id serial PRIMARY KEY,
+    post_id int NOT NULL UNIQUE REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    ends_at timestamp,
+    allow_multiple boolean NOT NULL DEFAULT FALSE,
+    hide_results_until_voted boolean NOT NULL DEFAULT FALSE,
+    published timestamp NOT NULL DEFAULT now()
+);
+
+CREATE TABLE poll_option (
+    id serial PRIMARY KEY,
+    poll_id int NOT NULL REFERENCES poll ON UPDATE CASCADE ON DELETE CASCADE,
+    name text NOT NULL,
+    position int NOT NULL,
+    UNIQUE (poll_id, position)
+);
+
+CREATE TABLE poll_vote (
+    id serial PRIMARY KEY,
+    poll_id int NOT NULL REFERENCES poll ON UPDATE CASCADE ON DELETE CASCADE,
+    poll_option_id int NOT NULL REFERENCES poll_option ON UPDATE CASCADE ON DELETE CASCADE,
+    person_id int NOT NULL REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE,
+    published timestamp NOT NULL DEFAULT now(),
+    UNIQUE (poll_option_id, person_id)
+);
+
+CREATE INDEX idx_poll_vote_poll_person ON poll_vote (poll_id, person_id);
+
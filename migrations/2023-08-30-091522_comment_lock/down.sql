@@ -0,0 +1,5 @@
+DROP TABLE mod_lock_comment;
+
+ALTER TABLE comment
+    DROP COLUMN locked;
+
@@ -0,0 +1,12 @@
+-- Locked comments don't accept any more replies in their subtree
+ALTER TABLE comment
+    ADD COLUMN locked boolean NOT NULL DEFAULT FALSE;
+
+CREATE TABLE mod_lock_comment (
+    id serial PRIMARY KEY,
+    mod_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    comment_id int REFERENCES comment ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    locked boolean NOT NULL DEFAULT TRUE,
+    when_ timestamp NOT NULL DEFAULT now()
+);
+
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    DROP COLUMN application_expire_days;
+
@@ -0,0 +1,4 @@
+-- Unread registration applications are denied after this many days, 0 keeps them forever
+ALTER TABLE local_site
+    ADD COLUMN application_expire_days int NOT NULL DEFAULT 0;
+
@@ -0,0 +1,9 @@
+ALTER TABLE local_user
+    DROP COLUMN notification_muted_words;
+
+ALTER TABLE comment_reply
+    DROP COLUMN muted;
+
+ALTER TABLE person_mention
+    DROP COLUMN muted;
+
@@ -0,0 +1,10 @@
+-- Replies and mentions containing one of these words are stored, but don't notify the user
+ALTER TABLE local_user
+    ADD COLUMN notification_muted_words text[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE comment_reply
+    ADD COLUMN muted boolean NOT NULL DEFAULT FALSE;
+
+ALTER TABLE person_mention
+    ADD COLUMN muted boolean NOT NULL DEFAULT FALSE;
+
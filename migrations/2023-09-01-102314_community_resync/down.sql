@@ -0,0 +1,5 @@
+DROP TABLE community_resync_job;
+
+ALTER TABLE comment
+    DROP COLUMN backfilled;
+
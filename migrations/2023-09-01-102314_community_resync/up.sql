@@ -0,0 +1,22 @@
+-- Remote comments which were fetched by a resync, instead of arriving through federation
+ALTER TABLE comment
+    ADD COLUMN backfilled boolean NOT NULL DEFAULT FALSE;
+
+-- Fetching the missing comments of all posts in a remote community. The progress is stored so
+-- that the job can be resumed after a restart, and shown to the moderator who started it.
+CREATE TABLE community_resync_job (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    creator_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    posts_total int NOT NULL,
+    posts_done int NOT NULL DEFAULT 0,
+    posts_failed int NOT NULL DEFAULT 0,
+    comments_added int NOT NULL DEFAULT 0,
+    -- Posts are resynced from the newest to the oldest, this is the last one which was done
+    last_post_id int,
+    published timestamp NOT NULL DEFAULT now(),
+    finished timestamp
+);
+
+CREATE INDEX idx_community_resync_job_community ON community_resync_job (community_id);
+
@@ -0,0 +1,5 @@
+DROP TABLE mod_reason_template;
+
+ALTER TABLE local_site
+    DROP COLUMN require_mod_action_reason;
+
@@ -0,0 +1,14 @@
+-- Local mods and admins have to give a reason for removals and bans
+ALTER TABLE local_site
+    ADD COLUMN require_mod_action_reason boolean NOT NULL DEFAULT FALSE;
+
+-- Reasons which the mods of a community can pick from, instead of writing them each time
+CREATE TABLE mod_reason_template (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    reason text NOT NULL,
+    published timestamp NOT NULL DEFAULT now(),
+    updated timestamp,
+    UNIQUE (community_id, reason)
+);
+
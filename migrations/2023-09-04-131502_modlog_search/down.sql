@@ -0,0 +1,12 @@
+DROP TRIGGER mod_remove_post_content ON mod_remove_post;
+
+DROP TRIGGER mod_remove_comment_content ON mod_remove_comment;
+
+DROP FUNCTION mod_remove_post_content, mod_remove_comment_content;
+
+ALTER TABLE mod_remove_post
+    DROP COLUMN post_name;
+
+ALTER TABLE mod_remove_comment
+    DROP COLUMN comment_snippet;
+
@@ -0,0 +1,67 @@
+-- The removed content as it was at the time of the removal, so that the modlog still shows it
+-- after the content was edited or purged.
+ALTER TABLE mod_remove_post
+    ADD COLUMN post_name text;
+
+ALTER TABLE mod_remove_comment
+    ADD COLUMN comment_snippet text;
+
+CREATE FUNCTION mod_remove_post_content ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    SELECT
+        name INTO NEW.post_name
+    FROM
+        post
+    WHERE
+        id = NEW.post_id;
+    RETURN NEW;
+END
+$$;
+
+CREATE TRIGGER mod_remove_post_content
+    BEFORE INSERT ON mod_remove_post
+    FOR EACH ROW
+    EXECUTE PROCEDURE mod_remove_post_content ();
+
+CREATE FUNCTION mod_remove_comment_content ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    SELECT
+        left(content, 300) INTO NEW.comment_snippet
+    FROM
+        comment
+    WHERE
+        id = NEW.comment_id;
+    RETURN NEW;
+END
+$$;
+
+CREATE TRIGGER mod_remove_comment_content
+    BEFORE INSERT ON mod_remove_comment
+    FOR EACH ROW
+    EXECUTE PROCEDURE mod_remove_comment_content ();
+
+-- Older entries get the current content, which is the best that is known
+UPDATE
+    mod_remove_post m
+SET
+    post_name = p.name
+FROM
+    post p
+WHERE
+    p.id = m.post_id;
+
+UPDATE
+    mod_remove_comment m
+SET
+    comment_snippet = left(c.content, 300)
+FROM
+    comment c
+WHERE
+    c.id = m.comment_id;
+
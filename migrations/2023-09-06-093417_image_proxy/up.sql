@@ -0,0 +1,5 @@
+-- Remote images in posts and comments are loaded through the instance, which hides the IPs of
+-- users from the remote servers
+ALTER TABLE local_site
+    ADD COLUMN proxy_remote_images boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,5 @@
+DROP TABLE admin_verify_community;
+
+ALTER TABLE community
+    DROP COLUMN verified;
+
@@ -0,0 +1,14 @@
+-- Communities which the admins of this instance vouch for. Only known locally, as it isn't
+-- federated.
+ALTER TABLE community
+    ADD COLUMN verified boolean NOT NULL DEFAULT FALSE;
+
+-- Modlog entries for verifying communities
+CREATE TABLE admin_verify_community (
+    id serial PRIMARY KEY,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    verified boolean NOT NULL,
+    when_ timestamp NOT NULL DEFAULT now()
+);
+
@@ -0,0 +1,12 @@
+DROP TRIGGER post_edit_history_removed ON post;
+
+DROP FUNCTION post_edit_history_removed;
+
+DROP TRIGGER comment_edit_history_removed ON comment;
+
+DROP FUNCTION comment_edit_history_removed;
+
+DROP TABLE post_edit_history;
+
+DROP TABLE comment_edit_history;
+
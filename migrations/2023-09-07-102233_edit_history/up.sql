@@ -0,0 +1,59 @@
+-- The previous versions of edited posts and comments, so that mods can see what was originally said
+CREATE TABLE post_edit_history (
+    id serial PRIMARY KEY,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    name varchar(200) NOT NULL,
+    url varchar(512),
+    content text NOT NULL,
+    edited_at timestamp NOT NULL DEFAULT now(),
+    editor_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL
+);
+
+CREATE INDEX idx_post_edit_history_post ON post_edit_history (post_id, edited_at);
+
+CREATE TABLE comment_edit_history (
+    id serial PRIMARY KEY,
+    comment_id int REFERENCES comment ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    content text NOT NULL,
+    edited_at timestamp NOT NULL DEFAULT now(),
+    editor_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL
+);
+
+CREATE INDEX idx_comment_edit_history_comment ON comment_edit_history (comment_id, edited_at);
+
+-- Removed content shouldn't stay readable through its history, no matter how it was removed.
+-- Purged content is covered by the foreign keys.
+CREATE FUNCTION post_edit_history_removed ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    DELETE FROM post_edit_history
+    WHERE post_id = NEW.id;
+    RETURN NULL;
+END
+$$;
+
+CREATE TRIGGER post_edit_history_removed
+    AFTER UPDATE ON post
+    FOR EACH ROW
+    WHEN (NOT old.removed AND new.removed)
+    EXECUTE FUNCTION post_edit_history_removed ();
+
+CREATE FUNCTION comment_edit_history_removed ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    DELETE FROM comment_edit_history
+    WHERE comment_id = NEW.id;
+    RETURN NULL;
+END
+$$;
+
+CREATE TRIGGER comment_edit_history_removed
+    AFTER UPDATE ON comment
+    FOR EACH ROW
+    WHEN (NOT old.removed AND new.removed)
+    EXECUTE FUNCTION comment_edit_history_removed ();
+
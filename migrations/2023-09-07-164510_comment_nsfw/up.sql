@@ -0,0 +1,4 @@
+-- Comments can be marked as NSFW, like posts. Federated as the sensitive flag of the Note.
+ALTER TABLE comment
+    ADD COLUMN nsfw boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,18 @@
+-- Mentions of communities as !name@domain in posts and comments, so that clients can link them
+CREATE TABLE community_mention (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE,
+    comment_id int REFERENCES COMMENT ON UPDATE CASCADE ON DELETE CASCADE,
+    published timestamp NOT NULL DEFAULT now(),
+    CHECK ((post_id IS NULL) <> (comment_id IS NULL))
+);
+
+CREATE UNIQUE INDEX idx_community_mention_post ON community_mention (post_id, community_id)
+WHERE
+    post_id IS NOT NULL;
+
+CREATE UNIQUE INDEX idx_community_mention_comment ON community_mention (comment_id, community_id)
+WHERE
+    comment_id IS NOT NULL;
+
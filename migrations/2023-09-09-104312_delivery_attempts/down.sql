@@ -0,0 +1,5 @@
+ALTER TABLE sent_activity_delivery
+    DROP COLUMN attempts,
+    DROP COLUMN last_attempt_at,
+    DROP COLUMN last_error;
+
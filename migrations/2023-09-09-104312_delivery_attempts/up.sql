@@ -0,0 +1,7 @@
+-- Record the resend attempts of the retry queue, so that admins can see why an activity didn't
+-- arrive at an instance.
+ALTER TABLE sent_activity_delivery
+    ADD COLUMN attempts int NOT NULL DEFAULT 0,
+    ADD COLUMN last_attempt_at timestamp,
+    ADD COLUMN last_error text;
+
@@ -0,0 +1,4 @@
+DROP TABLE user_data_import_failure;
+
+DROP TABLE user_data_import_job;
+
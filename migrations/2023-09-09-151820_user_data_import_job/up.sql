@@ -0,0 +1,26 @@
+-- User data imports run in the background, because each follow, block and save may need to be
+-- fetched from a remote instance. The progress is stored so that the import can be resumed after
+-- a restart, and shown to the user.
+CREATE TABLE user_data_import_job (
+    id serial PRIMARY KEY,
+    local_user_id int REFERENCES local_user ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    -- The imported data, items are imported in a fixed order so that items_done is enough to resume
+    data jsonb NOT NULL,
+    items_total int NOT NULL,
+    items_done int NOT NULL DEFAULT 0,
+    published timestamp NOT NULL DEFAULT now(),
+    finished timestamp
+);
+
+CREATE INDEX idx_user_data_import_job_local_user ON user_data_import_job (local_user_id);
+
+-- Settings and items which couldn't be imported, and why
+CREATE TABLE user_data_import_failure (
+    id serial PRIMARY KEY,
+    job_id int REFERENCES user_data_import_job ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    item text NOT NULL,
+    error text NOT NULL
+);
+
+CREATE INDEX idx_user_data_import_failure_job ON user_data_import_failure (job_id);
+
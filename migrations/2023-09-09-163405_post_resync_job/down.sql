@@ -0,0 +1,3 @@
+ALTER TABLE community_resync_job
+    DROP COLUMN post_id;
+
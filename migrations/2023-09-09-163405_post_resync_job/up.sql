@@ -0,0 +1,4 @@
+-- Resyncs of a single post run as a job as well, which only covers that post
+ALTER TABLE community_resync_job
+    ADD COLUMN post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE;
+
@@ -0,0 +1,40 @@
+UPDATE
+    domain_migration
+SET
+    stage = 'Instance'
+WHERE
+    stage = 'CustomEmoji';
+
+UPDATE
+    domain_migration
+SET
+    stage = 'Done'
+WHERE
+    stage = 'FederatePersons';
+
+ALTER TYPE domain_migration_stage_enum RENAME TO domain_migration_stage_enum__;
+
+CREATE TYPE domain_migration_stage_enum AS enum (
+    'Person',
+    'Community',
+    'Site',
+    'Post',
+    'Comment',
+    'PrivateMessage',
+    'Instance',
+    'Federate',
+    'Done'
+);
+
+ALTER TABLE domain_migration
+    ALTER COLUMN stage DROP DEFAULT;
+
+ALTER TABLE domain_migration
+    ALTER COLUMN stage TYPE domain_migration_stage_enum
+    USING stage::text::domain_migration_stage_enum;
+
+ALTER TABLE domain_migration
+    ALTER COLUMN stage SET DEFAULT 'Person';
+
+DROP TYPE domain_migration_stage_enum__;
+
@@ -0,0 +1,8 @@
+-- Custom emojis are moved to the new domain as well, and local persons are federated after the
+-- communities
+ALTER TYPE domain_migration_stage_enum
+    ADD VALUE 'CustomEmoji' AFTER 'PrivateMessage';
+
+ALTER TYPE domain_migration_stage_enum
+    ADD VALUE 'FederatePersons' AFTER 'Federate';
+
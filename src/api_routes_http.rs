@@ -1,21 +1,84 @@
 use actix_web::{guard, web, Error, HttpResponse, Result};
 use lemmy_api::{
-  comment::{distinguish::distinguish_comment, like::like_comment, save::save_comment},
+  comment::{
+    distinguish::distinguish_comment,
+    edit_history::get_comment_edit_history,
+    like::like_comment,
+    lock::lock_comment,
+    save::save_comment,
+  },
   comment_report::{
     create::create_comment_report,
     list::list_comment_reports,
     resolve::resolve_comment_report,
   },
   community::{
+    accept_transfer::accept_community_transfer,
     add_mod::add_mod_to_community,
     ban::ban_from_community,
     block::block_community,
+    flair::{pick_community_flair, remove_community_person_flair, set_community_person_flair},
+    flair_option::{
+      create_community_flair_option,
+      delete_community_flair_option,
+      list_community_flair_options,
+    },
     follow::follow_community,
     hide::hide_community,
+    mod_reason_template::{
+      create_mod_reason_template,
+      delete_mod_reason_template,
+      list_mod_reason_templates,
+      update_mod_reason_template,
+    },
+    stats::get_community_stats,
+    top_contributors::get_community_top_contributors,
+    verify::verify_community,
+  },
+  local_user::{
+    ban_person::ban_from_site,
+    clear_profile::{clear_person_bio, clear_person_display_name},
+    list_media::list_media,
+    list_save_tags::list_save_tags,
+    login::login,
+    logout_everywhere::logout_everywhere,
+    notifications::{
+      list_reminders::list_reminders,
+      mark_mentions_read::mark_mentions_as_read,
+      mark_reminder_read::mark_reminder_as_read,
+      mark_replies_read::mark_replies_as_read,
+      mark_reply_read::mark_reply_as_read,
+    },
+    remove_content::remove_person_content,
+    save_settings::save_user_settings,
+  },
+  oauth::{authorize::authorize_oauth, callback::oauth_callback, link::link_oauth_account},
+  person_report::{
+    create::create_person_report,
+    list::list_person_reports,
+    resolve::resolve_person_report,
+  },
+  post::{
+    edit_history::get_post_edit_history,
+    feature::feature_post,
+    like::like_post,
+    lock::lock_post,
+    request_archive::request_post_archive,
+    vote_poll::vote_poll,
   },
-  local_user::{ban_person::ban_from_site, notifications::mark_reply_read::mark_reply_as_read},
-  post::{feature::feature_post, like::like_post, lock::lock_post},
   post_report::create::create_post_report,
+  site::{
+    activity_timeseries::get_site_activity_timeseries,
+    content_diff::get_content_diff,
+    domain_migration::migrate_domain,
+    federation_failures::{list_federation_failures, purge_federation_failures},
+    federation_lists::{admin_allow_instance, admin_block_instance},
+    list_all_media::list_all_media,
+    modlog_export::get_modlog_export,
+    object_federation_status::get_object_federation_status,
+    purge::media::purge_media,
+    vote_anomalies::list_vote_anomalies,
+  },
   sitemap::get_sitemap,
   Perform,
 };
@@ -32,12 +95,10 @@ use lemmy_api_common::{
     GetReplies,
     GetReportCount,
     GetUnreadCount,
-    Login,
     MarkAllAsRead,
     MarkPersonMentionAsRead,
     PasswordChangeAfterReset,
     PasswordReset,
-    SaveUserSettings,
     VerifyEmail,
   },
   post::{GetSiteMetadata, ListPostReports, MarkPostAsRead, ResolvePostReport, SavePost},
@@ -75,11 +136,24 @@ use lemmy_api_crud::{
     remove::remove_community,
     update::update_community,
   },
+  community_digest::{read::get_community_digest, update::update_community_digest},
+  community_page::{
+    create::create_community_page,
+    delete::delete_community_page,
+    list::list_community_pages,
+    read::get_community_page,
+    update::update_community_page,
+  },
   custom_emoji::{
     create::create_custom_emoji,
     delete::delete_custom_emoji,
     update::update_custom_emoji,
   },
+  oauth_provider::{
+    create::create_oauth_provider,
+    delete::delete_oauth_provider,
+    update::update_oauth_provider,
+  },
   post::{
     create::create_post,
     delete::delete_post,
@@ -87,14 +161,24 @@ use lemmy_api_crud::{
     remove::remove_post,
     update::update_post,
   },
+  post_reminder::{
+    create::create_post_reminder,
+    delete::delete_post_reminder,
+    list::list_post_reminders,
+  },
   private_message::{
+    conversation::{get_private_message_conversations, get_private_messages_with},
     create::create_private_message,
     delete::delete_private_message,
     read::get_private_message,
     update::update_private_message,
   },
   site::{create::create_site, read::get_site, update::update_site},
-  user::{create::register, delete::delete_account},
+  user::{
+    bulk_deny::bulk_deny_registration_applications,
+    create::register,
+    delete::{cancel_account_deletion, confirm_account_deletion, delete_account},
+  },
 };
 use lemmy_apub::{
   api::{
@@ -103,17 +187,36 @@ use lemmy_apub::{
     read_community::get_community,
     read_person::read_person,
     resolve_object::resolve_object,
+    resync_community::{get_community_resync_job, resync_remote_community},
+    resync_post::resync_remote_post,
     search::search,
+    user_data::{export_user_data, get_user_data_import_job, import_user_data},
   },
   SendActivity,
 };
-use lemmy_utils::{rate_limit::RateLimitCell, spawn_try_task, SYNCHRONOUS_FEDERATION};
+use lemmy_routes::images::image_proxy;
+use lemmy_utils::{
+  cache_header::cache_1hour,
+  rate_limit::RateLimitCell,
+  spawn_try_task,
+  SYNCHRONOUS_FEDERATION,
+};
 use serde::Deserialize;
 
 pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
   cfg.service(
     web::scope("/api/v3")
       // Site
+      .service(
+        // Handle this separately to use the stricter search() rate limiter
+        web::resource("/site/activity")
+          .wrap(rate_limit.search())
+          .route(
+            web::get()
+              .to(get_site_activity_timeseries)
+              .wrap(cache_1hour()),
+          ),
+      )
       .service(
         web::scope("/site")
           .wrap(rate_limit.message())
@@ -127,6 +230,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.message())
           .route(web::get().to(route_get::<GetModlog>)),
       )
+      .service(
+        web::resource("/modlog/export")
+          .wrap(rate_limit.export())
+          .route(web::get().to(get_modlog_export)),
+      )
       .service(
         web::resource("/search")
           .wrap(rate_limit.search())
@@ -137,6 +245,18 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.message())
           .route(web::get().to(resolve_object)),
       )
+      .service(
+        web::resource("/content_diff")
+          .wrap(rate_limit.message())
+          .route(web::get().to(get_content_diff)),
+      )
+      // Uses the more generous message limit instead of the image one, as a page can show many
+      // proxied images
+      .service(
+        web::resource("/image_proxy")
+          .wrap(rate_limit.message())
+          .route(web::get().to(image_proxy)),
+      )
       // Community
       .service(
         web::resource("/community")
@@ -150,6 +270,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("", web::get().to(get_community))
           .route("", web::put().to(update_community))
           .route("/hide", web::put().to(hide_community))
+          .route("/verify", web::put().to(verify_community))
           .route("/list", web::get().to(list_communities))
           .route("/follow", web::post().to(follow_community))
           .route("/block", web::post().to(block_community))
@@ -157,8 +278,51 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           // Mod Actions
           .route("/remove", web::post().to(remove_community))
           .route("/transfer", web::post().to(route_post::<TransferCommunity>))
+          .route(
+            "/transfer/accept",
+            web::post().to(accept_community_transfer),
+          )
           .route("/ban_user", web::post().to(ban_from_community))
-          .route("/mod", web::post().to(add_mod_to_community)),
+          .route("/mod", web::post().to(add_mod_to_community))
+          .route("/flair", web::post().to(set_community_person_flair))
+          .route(
+            "/flair/remove",
+            web::post().to(remove_community_person_flair),
+          )
+          .route("/flair/pick", web::post().to(pick_community_flair))
+          .route(
+            "/flair/option",
+            web::post().to(create_community_flair_option),
+          )
+          .route(
+            "/flair/option/delete",
+            web::post().to(delete_community_flair_option),
+          )
+          .route(
+            "/flair/option/list",
+            web::get().to(list_community_flair_options),
+          )
+          .route("/mod_reason", web::post().to(create_mod_reason_template))
+          .route("/mod_reason", web::put().to(update_mod_reason_template))
+          .route(
+            "/mod_reason/delete",
+            web::post().to(delete_mod_reason_template),
+          )
+          .route("/mod_reason/list", web::get().to(list_mod_reason_templates))
+          .route("/page", web::get().to(get_community_page))
+          .route("/page", web::post().to(create_community_page))
+          .route("/page", web::put().to(update_community_page))
+          .route("/page/delete", web::post().to(delete_community_page))
+          .route("/page/list", web::get().to(list_community_pages))
+          .route("/digest", web::get().to(get_community_digest))
+          .route("/digest", web::put().to(update_community_digest))
+          .route(
+            "/top_contributors",
+            web::get().to(get_community_top_contributors),
+          )
+          .route("/stats", web::get().to(get_community_stats))
+          .route("/resync", web::post().to(resync_remote_community))
+          .route("/resync", web::get().to(get_community_resync_job)),
       )
       .service(
         web::scope("/federated_instances")
@@ -180,15 +344,22 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("", web::put().to(update_post))
           .route("/delete", web::post().to(delete_post))
           .route("/remove", web::post().to(remove_post))
+          .route("/edit_history", web::get().to(get_post_edit_history))
           .route(
             "/mark_as_read",
             web::post().to(route_post::<MarkPostAsRead>),
           )
           .route("/lock", web::post().to(lock_post))
           .route("/feature", web::post().to(feature_post))
+          .route("/archive", web::post().to(request_post_archive))
+          .route("/resync", web::post().to(resync_remote_post))
           .route("/list", web::get().to(list_posts))
           .route("/like", web::post().to(like_post))
+          .route("/poll/vote", web::post().to(vote_poll))
           .route("/save", web::put().to(route_post::<SavePost>))
+          .route("/reminder", web::post().to(create_post_reminder))
+          .route("/reminder", web::get().to(list_post_reminders))
+          .route("/reminder/delete", web::post().to(delete_post_reminder))
           .route("/report", web::post().to(create_post_report))
           .route(
             "/report/resolve",
@@ -215,9 +386,12 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("", web::put().to(update_comment))
           .route("/delete", web::post().to(delete_comment))
           .route("/remove", web::post().to(remove_comment))
+          .route("/edit_history", web::get().to(get_comment_edit_history))
           .route("/mark_as_read", web::post().to(mark_reply_as_read))
+          .route("/mark_as_read/batch", web::post().to(mark_replies_as_read))
           .route("/distinguish", web::post().to(distinguish_comment))
           .route("/like", web::post().to(like_comment))
+          .route("/lock", web::post().to(lock_comment))
           .route("/save", web::put().to(save_comment))
           .route("/list", web::get().to(list_comments))
           .route("/report", web::post().to(create_comment_report))
@@ -229,6 +403,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
         web::scope("/private_message")
           .wrap(rate_limit.message())
           .route("/list", web::get().to(get_private_message))
+          .route(
+            "/conversation/list",
+            web::get().to(get_private_message_conversations),
+          )
+          .route("/conversation", web::get().to(get_private_messages_with))
           .route("", web::post().to(create_private_message))
           .route("", web::put().to(update_private_message))
           .route("/delete", web::post().to(delete_private_message))
@@ -264,6 +443,19 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.post())
           .route(web::get().to(route_get::<GetCaptcha>)),
       )
+      .service(
+        // Checking the progress of an import is cheap, unlike the import itself
+        web::resource("/user/data/import_job")
+          .wrap(rate_limit.message())
+          .route(web::get().to(get_user_data_import_job)),
+      )
+      .service(
+        // Exports and imports fetch a lot of data, so they use the export rate limit
+        web::scope("/user/data")
+          .wrap(rate_limit.export())
+          .route("/export", web::get().to(export_user_data))
+          .route("/import", web::post().to(import_user_data)),
+      )
       // User actions
       .service(
         web::scope("/user")
@@ -274,14 +466,41 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             "/mention/mark_as_read",
             web::post().to(route_post::<MarkPersonMentionAsRead>),
           )
+          .route(
+            "/mention/mark_as_read/batch",
+            web::post().to(mark_mentions_as_read),
+          )
           .route("/replies", web::get().to(route_get::<GetReplies>))
+          .route("/reminder", web::get().to(list_reminders))
+          .route(
+            "/reminder/mark_as_read",
+            web::post().to(mark_reminder_as_read),
+          )
           // Admin action. I don't like that it's in /user
           .route("/ban", web::post().to(ban_from_site))
+          .route(
+            "/clear_display_name",
+            web::post().to(clear_person_display_name),
+          )
+          .route("/clear_bio", web::post().to(clear_person_bio))
+          .route("/remove_content", web::post().to(remove_person_content))
+          .route("/report", web::post().to(create_person_report))
+          .route("/report/resolve", web::put().to(resolve_person_report))
+          .route("/report/list", web::get().to(list_person_reports))
           .route("/banned", web::get().to(route_get::<GetBannedPersons>))
           .route("/block", web::post().to(route_post::<BlockPerson>))
           // Account actions. I don't like that they're in /user maybe /accounts
-          .route("/login", web::post().to(route_post::<Login>))
+          .route("/login", web::post().to(login))
+          .route("/logout_everywhere", web::post().to(logout_everywhere))
           .route("/delete_account", web::post().to(delete_account))
+          .route(
+            "/delete_account/cancel",
+            web::post().to(cancel_account_deletion),
+          )
+          .route(
+            "/delete_account/confirm",
+            web::post().to(confirm_account_deletion),
+          )
           .route(
             "/password_reset",
             web::post().to(route_post::<PasswordReset>),
@@ -295,15 +514,14 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             "/mark_all_as_read",
             web::post().to(route_post::<MarkAllAsRead>),
           )
-          .route(
-            "/save_user_settings",
-            web::put().to(route_post::<SaveUserSettings>),
-          )
+          .route("/save_user_settings", web::put().to(save_user_settings))
           .route(
             "/change_password",
             web::put().to(route_post::<ChangePassword>),
           )
           .route("/report_count", web::get().to(route_get::<GetReportCount>))
+          .route("/list_media", web::get().to(list_media))
+          .route("/save_tags", web::get().to(list_save_tags))
           .route("/unread_count", web::get().to(route_get::<GetUnreadCount>))
           .route("/verify_email", web::post().to(route_post::<VerifyEmail>))
           .route("/leave_admin", web::post().to(route_post::<LeaveAdmin>)),
@@ -325,12 +543,34 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             "/registration_application/approve",
             web::put().to(route_post::<ApproveRegistrationApplication>),
           )
+          .route(
+            "/registration_application/bulk_deny",
+            web::post().to(bulk_deny_registration_applications),
+          )
+          .route("/list_all_media", web::get().to(list_all_media))
+          .route(
+            "/federation_status",
+            web::get().to(get_object_federation_status),
+          )
+          .route(
+            "/federation_failures",
+            web::get().to(list_federation_failures),
+          )
+          .route(
+            "/federation_failures/purge",
+            web::post().to(purge_federation_failures),
+          )
+          .route("/instance/block", web::post().to(admin_block_instance))
+          .route("/instance/allow", web::post().to(admin_allow_instance))
+          .route("/domain_migration", web::post().to(migrate_domain))
+          .route("/vote_anomaly/list", web::get().to(list_vote_anomalies))
           .service(
             web::scope("/purge")
               .route("/person", web::post().to(route_post::<PurgePerson>))
               .route("/community", web::post().to(route_post::<PurgeCommunity>))
               .route("/post", web::post().to(route_post::<PurgePost>))
-              .route("/comment", web::post().to(route_post::<PurgeComment>)),
+              .route("/comment", web::post().to(route_post::<PurgeComment>))
+              .route("/media", web::post().to(purge_media)),
           ),
       )
       .service(
@@ -339,6 +579,20 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("", web::post().to(create_custom_emoji))
           .route("", web::put().to(update_custom_emoji))
           .route("/delete", web::post().to(delete_custom_emoji)),
+      )
+      .service(
+        web::scope("/oauth_provider")
+          .wrap(rate_limit.message())
+          .route("", web::post().to(create_oauth_provider))
+          .route("", web::put().to(update_oauth_provider))
+          .route("/delete", web::post().to(delete_oauth_provider)),
+      )
+      .service(
+        web::scope("/oauth")
+          .wrap(rate_limit.message())
+          .route("/authorize", web::get().to(authorize_oauth))
+          .route("/callback", web::post().to(oauth_callback))
+          .route("/link", web::post().to(link_oauth_account)),
       ),
   );
   cfg.service(
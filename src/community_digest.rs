@@ -0,0 +1,380 @@
+use activitypub_federation::http_signatures::generate_actor_keypair;
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime};
+use lemmy_api_common::{
+  context::LemmyContext,
+  lemmy_db_views::structs::SiteView,
+  lemmy_db_views_actor::structs::CommunityModeratorView,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{
+    generate_inbox_url,
+    generate_local_apub_endpoint,
+    generate_shared_inbox_url,
+    EndpointType,
+  },
+};
+use lemmy_db_schema::{
+  aggregates::structs::PostAggregates,
+  impls::actor_language::UNDETERMINED_ID,
+  newtypes::LanguageId,
+  source::{
+    actor_language::CommunityLanguage,
+    community::{Community, CommunityModerator, CommunityModeratorForm},
+    community_digest::CommunityDigest,
+    local_site::{LocalSite, LocalSiteUpdateForm},
+    moderator::{ModAddCommunity, ModAddCommunityForm, ModFeaturePost, ModFeaturePostForm},
+    person::{Person, PersonInsertForm},
+    post::{Post, PostInsertForm, PostLike, PostLikeForm, PostUpdateForm},
+  },
+  traits::{ApubActor, Crud, Joinable, Likeable},
+  utils::naive_now,
+};
+use lemmy_utils::error::LemmyResult;
+use std::fmt::Write;
+use tracing::warn;
+
+/// The name of the bot account which posts the digests. A number is added if it is taken.
+const DIGEST_BOT_NAME: &str = "digest_bot";
+
+/// Posts the community digests which are due. Each scheduled week is only posted once, even if
+/// the task runs again after a restart.
+pub async fn publish_due_digests(context: &LemmyContext) -> LemmyResult<()> {
+  let digests = CommunityDigest::list_enabled(&mut context.pool()).await?;
+  if digests.is_empty() {
+    return Ok(());
+  }
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let bot = digest_bot(&local_site, context).await?;
+
+  let now = naive_now();
+  for digest in digests {
+    let due = digest_due_at(now, digest.day_of_week, digest.hour);
+    // The first digest is posted on the first scheduled day after it was enabled
+    if due < digest.published || digest.last_generated_for >= Some(due) {
+      continue;
+    }
+    publish_digest(&digest, due, &bot, &local_site, context)
+      .await
+      .map_err(|e| {
+        warn!(
+          "Failed to publish digest of community {}: {e}",
+          digest.community_id.0
+        )
+      })
+      .ok();
+  }
+  Ok(())
+}
+
+async fn publish_digest(
+  digest: &CommunityDigest,
+  due: NaiveDateTime,
+  bot: &Person,
+  local_site: &LocalSite,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let community = Community::read(&mut context.pool(), digest.community_id).await?;
+  if community.removed || community.deleted {
+    return Ok(());
+  }
+  let since = due - Duration::days(7);
+  let include_nsfw = community.nsfw && local_site.enable_nsfw;
+  let top_posts = digest
+    .top_posts(&mut context.pool(), (since, due), include_nsfw, bot.id)
+    .await?;
+  if top_posts.is_empty() {
+    CommunityDigest::claim(&mut context.pool(), digest.id, due).await?;
+    return Ok(());
+  }
+
+  let post_form = PostInsertForm::builder()
+    .name(digest_title(since, due))
+    .body(Some(digest_body(&top_posts)))
+    .community_id(community.id)
+    .creator_id(bot.id)
+    .nsfw(Some(community.nsfw))
+    .language_id(digest_language(&community, context).await?)
+    .featured_community(Some(digest.feature))
+    .build();
+  // The claim is only kept if the post was created, otherwise the next run tries again
+  let Some(inserted_post) =
+    CommunityDigest::claim_with_post(&mut context.pool(), digest.id, due, &post_form).await?
+  else {
+    return Ok(());
+  };
+  let apub_id = generate_local_apub_endpoint(
+    EndpointType::Post,
+    &inserted_post.id.to_string(),
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let post = Post::update(
+    &mut context.pool(),
+    inserted_post.id,
+    &PostUpdateForm {
+      ap_id: Some(apub_id),
+      ..Default::default()
+    },
+  )
+  .await?;
+  let like_form = PostLikeForm {
+    post_id: post.id,
+    person_id: bot.id,
+    score: 1,
+  };
+  PostLike::like(&mut context.pool(), &like_form).await?;
+  ActivityChannel::queue_activity(SendActivityData::CreatePost(post.clone()))?;
+
+  if digest.feature {
+    join_digest_moderators(&community, bot, context).await?;
+    feature_digest(&post, true, bot, context).await?;
+    // Only the latest digest stays featured
+    if let Some(last_post_id) = digest.last_post_id {
+      let last_post = Post::update(
+        &mut context.pool(),
+        last_post_id,
+        &PostUpdateForm {
+          featured_community: Some(false),
+          ..Default::default()
+        },
+      )
+      .await?;
+      feature_digest(&last_post, false, bot, context).await?;
+    }
+  }
+  Ok(())
+}
+
+/// The language of the digest post. Like `default_post_language`, a single allowed language
+/// besides undetermined is used, and one of the allowed languages if undetermined isn't.
+async fn digest_language(
+  community: &Community,
+  context: &LemmyContext,
+) -> LemmyResult<Option<LanguageId>> {
+  let mut languages = CommunityLanguage::read(&mut context.pool(), community.id).await?;
+  // All languages are allowed
+  if languages.is_empty() {
+    return Ok(None);
+  }
+  let allows_undetermined = languages.contains(&UNDETERMINED_ID);
+  languages.retain(|l| l != &UNDETERMINED_ID);
+  if languages.len() == 1 || !allows_undetermined {
+    Ok(languages.first().copied())
+  } else {
+    Ok(None)
+  }
+}
+
+/// Featuring posts is a mod action, so the bot moderates the communities where it features
+/// digests, instead of acting for the whole site.
+async fn join_digest_moderators(
+  community: &Community,
+  bot: &Person,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  if CommunityModeratorView::is_community_moderator(&mut context.pool(), community.id, bot.id)
+    .await?
+  {
+    return Ok(());
+  }
+  let form = CommunityModeratorForm {
+    community_id: community.id,
+    person_id: bot.id,
+  };
+  CommunityModerator::join(&mut context.pool(), &form).await?;
+  let form = ModAddCommunityForm {
+    mod_person_id: bot.id,
+    other_person_id: bot.id,
+    community_id: community.id,
+    removed: Some(false),
+  };
+  ModAddCommunity::create(&mut context.pool(), &form).await?;
+  ActivityChannel::queue_activity(SendActivityData::AddModToCommunity(
+    bot.clone(),
+    community.id,
+    bot.id,
+    true,
+  ))
+}
+
+async fn feature_digest(
+  post: &Post,
+  featured: bool,
+  bot: &Person,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let form = ModFeaturePostForm {
+    mod_person_id: bot.id,
+    post_id: post.id,
+    featured,
+    is_featured_community: true,
+  };
+  ModFeaturePost::create(&mut context.pool(), &form).await?;
+  ActivityChannel::queue_activity(SendActivityData::FeaturePost(
+    post.clone(),
+    bot.clone(),
+    featured,
+  ))
+}
+
+/// Reads the bot account which posts the digests, and creates it if there is none yet.
+async fn digest_bot(local_site: &LocalSite, context: &LemmyContext) -> LemmyResult<Person> {
+  if let Some(bot_id) = local_site.community_digest_bot_id {
+    return Ok(Person::read(&mut context.pool(), bot_id).await?);
+  }
+
+  let mut name = DIGEST_BOT_NAME.to_string();
+  let mut number = 1;
+  while Person::read_from_name(&mut context.pool(), &name, true)
+    .await
+    .is_ok()
+  {
+    number += 1;
+    name = format!("{DIGEST_BOT_NAME}{number}");
+  }
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let keypair = generate_actor_keypair()?;
+  let actor_id = generate_local_apub_endpoint(
+    EndpointType::Person,
+    &name,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let person_form = PersonInsertForm::builder()
+    .name(name)
+    .display_name(Some("Community digest".to_string()))
+    .bot_account(Some(true))
+    .actor_id(Some(actor_id.clone()))
+    .private_key(Some(keypair.private_key))
+    .public_key(keypair.public_key)
+    .inbox_url(Some(generate_inbox_url(&actor_id)?))
+    .shared_inbox_url(Some(generate_shared_inbox_url(&actor_id)?))
+    .instance_id(site_view.site.instance_id)
+    .build();
+  let bot = Person::create(&mut context.pool(), &person_form).await?;
+
+  let local_site_form = LocalSiteUpdateForm {
+    community_digest_bot_id: Some(Some(bot.id)),
+    ..Default::default()
+  };
+  LocalSite::update(&mut context.pool(), &local_site_form).await?;
+  Ok(bot)
+}
+
+/// The latest time at or before `now` which is on the given day of the week (0 for Monday) and at
+/// the given hour.
+fn digest_due_at(now: NaiveDateTime, day_of_week: i16, hour: i16) -> NaiveDateTime {
+  let days_back =
+    (i64::from(now.weekday().num_days_from_monday()) - i64::from(day_of_week)).rem_euclid(7);
+  let time =
+    NaiveTime::from_hms_opt(u32::try_from(hour).unwrap_or_default(), 0, 0).unwrap_or_default();
+  let due = (now.date() - Duration::days(days_back)).and_time(time);
+  if due > now {
+    due - Duration::days(7)
+  } else {
+    due
+  }
+}
+
+fn digest_title(since: NaiveDateTime, until: NaiveDateTime) -> String {
+  format!(
+    "Weekly digest: {} to {}",
+    since.format("%Y-%m-%d"),
+    until.format("%Y-%m-%d")
+  )
+}
+
+/// A numbered list linking the posts, with their scores and comment counts.
+fn digest_body(top_posts: &[(Post, PostAggregates)]) -> String {
+  let mut body = "The top posts of the past week:\n\n".to_string();
+  for (i, (post, counts)) in top_posts.iter().enumerate() {
+    let title = post
+      .name
+      .replace('\\', "\\\\")
+      .replace('[', "\\[")
+      .replace(']', "\\]");
+    // Writing to a string can't fail
+    writeln!(
+      body,
+      "{}. [{title}]({}) ({} points, {} comments)",
+      i + 1,
+      post.ap_id,
+      counts.score,
+      counts.comments
+    )
+    .ok();
+  }
+  body
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use super::{digest_body, digest_due_at};
+  use chrono::NaiveDate;
+  use lemmy_db_schema::{aggregates::structs::PostAggregates, source::post::Post};
+  use serde_json::json;
+
+  #[test]
+  fn test_digest_due_at() {
+    // A Wednesday
+    let now = NaiveDate::from_ymd_opt(2023, 8, 16)
+      .unwrap()
+      .and_hms_opt(10, 30, 0)
+      .unwrap();
+    let at = |day, hour| {
+      NaiveDate::from_ymd_opt(2023, 8, day)
+        .unwrap()
+        .and_hms_opt(hour, 0, 0)
+    };
+    assert_eq!(at(14, 12), Some(digest_due_at(now, 0, 12)));
+    assert_eq!(at(16, 10), Some(digest_due_at(now, 2, 10)));
+    // Later on the same day means last week
+    assert_eq!(at(9, 11), Some(digest_due_at(now, 2, 11)));
+    assert_eq!(at(13, 0), Some(digest_due_at(now, 6, 0)));
+  }
+
+  #[test]
+  fn test_digest_body() {
+    let post: Post = serde_json::from_value(json!({
+      "id": 1,
+      "name": "A [great] post",
+      "creator_id": 2,
+      "community_id": 3,
+      "removed": false,
+      "locked": false,
+      "published": "2023-08-14T12:00:00",
+      "deleted": false,
+      "nsfw": false,
+      "ap_id": "https://my_domain.tld/post/1",
+      "local": true,
+      "language_id": 0,
+      "featured_community": false,
+      "featured_local": false
+    }))
+    .unwrap();
+    let counts: PostAggregates = serde_json::from_value(json!({
+      "id": 1,
+      "post_id": 1,
+      "comments": 3,
+      "score": 12,
+      "upvotes": 12,
+      "downvotes": 0,
+      "published": "2023-08-14T12:00:00",
+      "newest_comment_time_necro": "2023-08-14T12:00:00",
+      "newest_comment_time": "2023-08-14T12:00:00",
+      "featured_community": false,
+      "featured_local": false,
+      "hot_rank": 1728,
+      "hot_rank_active": 1728,
+      "community_id": 3,
+      "creator_id": 2,
+      "controversy_rank": 0.0
+    }))
+    .unwrap();
+    assert_eq!(
+      "The top posts of the past week:\n\n\
+       1. [A \\[great\\] post](https://my_domain.tld/post/1) (12 points, 3 comments)\n",
+      digest_body(&[(post, counts)])
+    );
+  }
+}
@@ -1,5 +1,6 @@
 pub mod api_routes_http;
 pub mod code_migrations;
+pub mod community_digest;
 #[cfg(feature = "prometheus-metrics")]
 pub mod prometheus_metrics;
 pub mod root_span_builder;
@@ -11,14 +12,24 @@ use crate::{code_migrations::run_advanced_migrations, root_span_builder::Quieter
 use activitypub_federation::config::{FederationConfig, FederationMiddleware};
 use actix_cors::Cors;
 use actix_web::{
+  dev::{Service, ServiceRequest, ServiceResponse},
+  http::{
+    header::{HeaderValue, LOCATION, RETRY_AFTER},
+    Method,
+  },
   middleware::{self, ErrorHandlers},
   web::Data,
   App,
+  HttpResponse,
   HttpServer,
+  ResponseError,
   Result,
 };
+use futures_util::future::{ready, Either, FutureExt};
+use lemmy_api::site::domain_migration::run_domain_migration;
 use lemmy_api_common::{
   context::LemmyContext,
+  database_health::DatabaseHealth,
   lemmy_db_views::structs::SiteView,
   request::build_user_agent,
   send_activity::{ActivityChannel, MATCH_OUTGOING_ACTIVITIES},
@@ -28,20 +39,31 @@ use lemmy_api_common::{
   },
 };
 use lemmy_apub::{
-  activities::{handle_outgoing_activities, match_outgoing_activities},
+  activities::{
+    handle_outgoing_activities,
+    match_outgoing_activities,
+    retry::retry_failed_deliveries,
+  },
+  api::{resync_community::run_community_resync, user_data::run_user_data_import},
   VerifyUrlData,
   FEDERATION_HTTP_FETCH_LIMIT,
 };
 use lemmy_db_schema::{
-  source::secret::Secret,
+  source::{
+    community_resync_job::CommunityResyncJob,
+    domain_migration::DomainMigration,
+    secret::Secret,
+    user_data_import::UserDataImportJob,
+  },
   utils::{build_db_pool, get_database_url, run_migrations},
 };
-use lemmy_routes::{feeds, images, nodeinfo, webfinger};
+use lemmy_routes::{feeds, health, images, nodeinfo, webfinger};
 use lemmy_utils::{
-  error::LemmyError,
+  error::{LemmyError, LemmyErrorType},
   rate_limit::RateLimitCell,
   response::jsonify_plain_text_errors,
   settings::SETTINGS,
+  spawn_try_task,
   SYNCHRONOUS_FEDERATION,
 };
 use reqwest::Client;
@@ -133,12 +155,21 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
     rate_limit_cell.clone(),
   );
 
+  // Notice when the database goes away and comes back, also without requests
+  tokio::task::spawn(DatabaseHealth::monitor(context.clone()));
+
+  // Keep answering for the domains which the instance was moved away from
+  for domain in DomainMigration::old_domains(&mut context.pool()).await? {
+    context.add_domain_alias(domain);
+  }
+
   if scheduled_tasks_enabled {
     // Schedules various cleanup tasks for the DB
     thread::spawn({
       let context = context.clone();
+      let runtime = tokio::runtime::Handle::current();
       move || {
-        scheduled_tasks::setup(db_url, user_agent, context)
+        scheduled_tasks::setup(db_url, user_agent, context, runtime)
           .expect("Couldn't set up scheduled_tasks");
       }
     });
@@ -177,6 +208,39 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
     .expect("set function pointer");
   let request_data = federation_config.to_request_data();
   let outgoing_activities_task = tokio::task::spawn(handle_outgoing_activities(request_data));
+  if scheduled_tasks_enabled {
+    // Resend activities for instances which were unreachable
+    tokio::task::spawn(retry_failed_deliveries(federation_config.to_request_data()));
+  }
+
+  // Resume a domain migration which was interrupted by a restart
+  if let Some(migration) = DomainMigration::read_unfinished(&mut context.pool()).await? {
+    if migration.new_domain == settings.hostname {
+      spawn_try_task(run_domain_migration(migration, context.clone()));
+    } else {
+      tracing::warn!(
+        "Not resuming the migration to {}, the hostname is {}",
+        migration.new_domain,
+        settings.hostname
+      );
+    }
+  }
+
+  // Resume community resyncs which were interrupted by a restart
+  for job in CommunityResyncJob::list_unfinished(&mut context.pool()).await? {
+    spawn_try_task(run_community_resync(
+      job,
+      federation_config.to_request_data(),
+    ));
+  }
+
+  // Resume user data imports which were interrupted by a restart
+  for job in UserDataImportJob::list_unfinished(&mut context.pool()).await? {
+    spawn_try_task(run_user_data_import(
+      job,
+      federation_config.to_request_data(),
+    ));
+  }
 
   // Create Http server with websocket support
   HttpServer::new(move || {
@@ -204,7 +268,29 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
       .wrap(ErrorHandlers::new().default_handler(jsonify_plain_text_errors))
       .app_data(Data::new(context.clone()))
       .app_data(Data::new(rate_limit_cell.clone()))
-      .wrap(FederationMiddleware::new(federation_config.clone()));
+      .wrap(FederationMiddleware::new(federation_config.clone()))
+      .wrap_fn(|req, srv| match domain_alias_redirect(&req) {
+        Some(location) => {
+          let res = HttpResponse::MovedPermanently()
+            .insert_header((LOCATION, location))
+            .finish();
+          Either::Left(ready(Ok(req.into_response(res).map_into_right_body())))
+        }
+        None => Either::Right(
+          srv
+            .call(req)
+            .map(|res| res.map(ServiceResponse::map_into_left_body)),
+        ),
+      })
+      .wrap_fn(|req, srv| match reject_write_without_database(&req) {
+        Some(res) => Either::Left(ready(Ok(req.into_response(res).map_into_right_body()))),
+        None => Either::Right(srv.call(req).map(|res| {
+          res.map(|mut res| {
+            handle_database_unavailable(&mut res);
+            res.map_into_left_body()
+          })
+        })),
+      });
 
     #[cfg(feature = "prometheus-metrics")]
     let app = app.wrap(prom_api_metrics.clone());
@@ -221,6 +307,7 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
       .configure(feeds::config)
       .configure(|cfg| images::config(cfg, pictrs_client.clone(), rate_limit_cell))
       .configure(nodeinfo::config)
+      .configure(health::config)
   })
   .bind((settings_bind.bind, settings_bind.port))?
   .run()
@@ -232,6 +319,62 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
   Ok(())
 }
 
+/// Requests for a domain which the instance was moved away from are redirected to the same path
+/// on the current domain.
+fn domain_alias_redirect(req: &ServiceRequest) -> Option<String> {
+  let context = req.app_data::<Data<LemmyContext>>()?;
+  let host = req.connection_info().host().to_string();
+  if !context.domain_aliases().contains(&host) {
+    return None;
+  }
+  let path = req.uri().path_and_query().map_or("/", |p| p.as_str());
+  Some(format!(
+    "{}{path}",
+    context.settings().get_protocol_and_hostname()
+  ))
+}
+
+/// While the database is unavailable, writes are rejected right away instead of waiting for a
+/// connection. Reads are still attempted, as some of them can be answered from caches.
+fn reject_write_without_database(req: &ServiceRequest) -> Option<HttpResponse> {
+  let context = req.app_data::<Data<LemmyContext>>()?;
+  let read = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+  if read || context.database_health().is_available() {
+    return None;
+  }
+  let mut res = LemmyError::from(LemmyErrorType::DatabaseUnavailable).error_response();
+  insert_retry_after(&mut res, context);
+  Some(res)
+}
+
+/// A request which failed because of the database marks it as unavailable right away, without
+/// waiting for the next health check.
+fn handle_database_unavailable<B>(res: &mut ServiceResponse<B>) {
+  let unavailable = res
+    .response()
+    .error()
+    .and_then(|e| e.as_error::<LemmyError>())
+    .is_some_and(|e| e.error_type == LemmyErrorType::DatabaseUnavailable);
+  let Some(context) = res.request().app_data::<Data<LemmyContext>>().cloned() else {
+    return;
+  };
+  if unavailable {
+    let reason = "a request couldn't open a database connection";
+    context
+      .database_health()
+      .record_failure(context.inner_pool(), reason);
+    insert_retry_after(res.response_mut(), &context);
+  }
+}
+
+/// Clients should retry once the database was checked again.
+fn insert_retry_after<B>(res: &mut HttpResponse<B>, context: &LemmyContext) {
+  let seconds = context.settings().database.health_check_interval_seconds;
+  res
+    .headers_mut()
+    .insert(RETRY_AFTER, HeaderValue::from(seconds));
+}
+
 pub fn init_logging(opentelemetry_url: &Option<Url>) -> Result<(), LemmyError> {
   LogTracer::init()?;
 
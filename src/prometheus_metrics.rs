@@ -1,9 +1,10 @@
 // TODO: should really not unwrap everywhere here....
 #![allow(clippy::unwrap_used)]
 use actix_web::{rt::System, web, App, HttpResponse, HttpServer, Responder};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, pictrs_breaker::PICTRS_BREAKER};
+use lemmy_apub::http::inbox_admission::INBOX_ADMISSION;
 use lemmy_utils::settings::structs::PrometheusConfig;
-use prometheus::{default_registry, Encoder, Gauge, Opts, TextEncoder};
+use prometheus::{default_registry, Encoder, Gauge, IntCounter, Opts, TextEncoder};
 use std::{
   net::{IpAddr, Ipv4Addr},
   sync::Arc,
@@ -13,6 +14,8 @@ use std::{
 struct PromContext {
   lemmy: LemmyContext,
   db_pool_metrics: DbPoolMetrics,
+  inbox_metrics: InboxMetrics,
+  pictrs_metrics: PictrsMetrics,
 }
 
 struct DbPoolMetrics {
@@ -21,6 +24,17 @@ struct DbPoolMetrics {
   available: Gauge,
 }
 
+struct InboxMetrics {
+  processing: Gauge,
+  queued: Gauge,
+  shed: IntCounter,
+}
+
+struct PictrsMetrics {
+  breaker_state: Gauge,
+  breaker_state_changes: IntCounter,
+}
+
 static DEFAULT_BIND: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 static DEFAULT_PORT: i32 = 10002;
 
@@ -28,6 +42,8 @@ pub fn serve_prometheus(config: Option<&PrometheusConfig>, lemmy_context: LemmyC
   let context = Arc::new(PromContext {
     lemmy: lemmy_context,
     db_pool_metrics: create_db_pool_metrics(),
+    inbox_metrics: create_inbox_metrics(),
+    pictrs_metrics: create_pictrs_metrics(),
   });
 
   let (bind, port) = match config {
@@ -63,6 +79,8 @@ pub fn serve_prometheus(config: Option<&PrometheusConfig>, lemmy_context: LemmyC
 async fn metrics(context: web::Data<Arc<PromContext>>) -> impl Responder {
   // collect metrics
   collect_db_pool_metrics(&context).await;
+  collect_inbox_metrics(&context);
+  collect_pictrs_metrics(&context);
 
   let mut buffer = Vec::new();
   let encoder = TextEncoder::new();
@@ -120,3 +138,85 @@ async fn collect_db_pool_metrics(context: &PromContext) {
     .available
     .set(pool_status.available as f64);
 }
+
+// create lemmy_inbox_* metrics and register them with the default registry
+fn create_inbox_metrics() -> InboxMetrics {
+  let metrics = InboxMetrics {
+    processing: Gauge::with_opts(Opts::new(
+      "lemmy_inbox_processing_activities",
+      "Number of incoming activities which are being processed",
+    ))
+    .unwrap(),
+    queued: Gauge::with_opts(Opts::new(
+      "lemmy_inbox_queued_activities",
+      "Number of incoming activities which are waiting to be processed",
+    ))
+    .unwrap(),
+    shed: IntCounter::with_opts(Opts::new(
+      "lemmy_inbox_shed_activities",
+      "Number of incoming activities which were rejected because the queue was full",
+    ))
+    .unwrap(),
+  };
+
+  default_registry()
+    .register(Box::new(metrics.processing.clone()))
+    .unwrap();
+  default_registry()
+    .register(Box::new(metrics.queued.clone()))
+    .unwrap();
+  default_registry()
+    .register(Box::new(metrics.shed.clone()))
+    .unwrap();
+
+  metrics
+}
+
+fn collect_inbox_metrics(context: &PromContext) {
+  context
+    .inbox_metrics
+    .processing
+    .set(INBOX_ADMISSION.processing() as f64);
+  context
+    .inbox_metrics
+    .queued
+    .set(INBOX_ADMISSION.queued() as f64);
+  // The counter only goes up, so it catches up with the admission's own count
+  let counter = &context.inbox_metrics.shed;
+  counter.inc_by(INBOX_ADMISSION.shed().saturating_sub(counter.get()));
+}
+
+// create lemmy_pictrs_* metrics and register them with the default registry
+fn create_pictrs_metrics() -> PictrsMetrics {
+  let metrics = PictrsMetrics {
+    breaker_state: Gauge::with_opts(Opts::new(
+      "lemmy_pictrs_breaker_state",
+      "State of the circuit breaker for pictrs requests (0 = closed, 1 = half open, 2 = open)",
+    ))
+    .unwrap(),
+    breaker_state_changes: IntCounter::with_opts(Opts::new(
+      "lemmy_pictrs_breaker_state_changes",
+      "Number of times the circuit breaker for pictrs requests changed its state",
+    ))
+    .unwrap(),
+  };
+
+  default_registry()
+    .register(Box::new(metrics.breaker_state.clone()))
+    .unwrap();
+  default_registry()
+    .register(Box::new(metrics.breaker_state_changes.clone()))
+    .unwrap();
+
+  metrics
+}
+
+fn collect_pictrs_metrics(context: &PromContext) {
+  context
+    .pictrs_metrics
+    .breaker_state
+    .set(PICTRS_BREAKER.state().as_metric());
+  // The counter only goes up, so it catches up with the breaker's own count
+  let counter = &context.pictrs_metrics.breaker_state_changes;
+  counter.inc_by(PICTRS_BREAKER.state_changes().saturating_sub(counter.get()));
+}
@@ -1,8 +1,10 @@
+use crate::community_digest::publish_due_digests;
 use chrono::NaiveDateTime;
 use clokwerk::{Scheduler, TimeUnits as CTimeUnits};
 use diesel::{
   dsl::{now, IntervalDsl},
   sql_types::{Integer, Timestamp},
+  BoolExpressionMethods,
   Connection,
   ExpressionMethods,
   NullableExpressionMethods,
@@ -11,40 +13,84 @@ use diesel::{
 };
 // Import week days and WeekDay
 use diesel::{sql_query, PgConnection, RunQueryDsl};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api::community::ban::lift_expired_community_bans;
+use lemmy_api_common::{
+  context::LemmyContext,
+  lemmy_db_views::structs::LocalUserView,
+  pictrs_breaker::{BreakerState, PICTRS_BREAKER},
+  request::{fetch_site_data, probe_pictrs, thumbnail_needs_retry},
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::send_email_to_user,
+};
 use lemmy_db_schema::{
+  impls::vote_anomaly::VoteAnomalyThresholds,
+  newtypes::{CommentId, LocalUserId, PersonId, PostId},
   schema::{
     captcha_answer,
     comment,
-    community_person_ban,
+    comment_reply,
+    federation_blocklist,
+    form_submission,
     instance,
+    local_site,
+    local_user,
+    login_fingerprint,
+    oauth_state,
     person,
+    person_mention,
     post,
+    private_message,
     received_activity,
     sent_activity,
+    sent_activity_delivery,
+  },
+  source::{
+    community_aggregates_snapshot::CommunityAggregatesSnapshot,
+    instance::{Instance, InstanceForm},
+    local_site::LocalSite,
+    local_user::LocalUser,
+    person::Person,
+    post::{Post, PostUpdateForm},
+    post_thumbnail_retry::PostThumbnailRetry,
+    registration_application::RegistrationApplication,
+    reminder::Reminder,
+    vote_anomaly::VoteAnomaly,
   },
-  source::instance::{Instance, InstanceForm},
+  traits::Crud,
   utils::{naive_now, DELETED_REPLACEMENT_TEXT},
+  NotificationDigest,
 };
 use lemmy_routes::nodeinfo::NodeInfo;
 use lemmy_utils::{
+  email::send_email,
   error::{LemmyError, LemmyResult},
+  settings::structs::Settings,
   REQWEST_TIMEOUT,
 };
 use reqwest::blocking::Client;
 use std::{thread, time::Duration};
+use tokio::runtime::Handle;
 use tracing::{error, info, warn};
 
+/// Shown to users whose registration application expired, when they try to log in.
+const EXPIRED_APPLICATION_REASON: &str =
+  "Your application expired before an admin could review it.";
+
 /// Schedules various cleanup tasks for lemmy in a background thread
 pub fn setup(
   db_url: String,
   user_agent: String,
   context_1: LemmyContext,
+  runtime: Handle,
 ) -> Result<(), LemmyError> {
   // Setup the connections
   let mut scheduler = Scheduler::new();
 
   startup_jobs(&db_url);
+  runtime
+    .block_on(update_community_aggregates_snapshot(&context_1))
+    .map_err(|e| warn!("Failed to update community statistics snapshot: {e}"))
+    .ok();
 
   // Update active counts every hour
   let url = db_url.clone();
@@ -73,12 +119,15 @@ pub fn setup(
       .ok();
   });
 
-  // Delete any captcha answers older than ten minutes, every ten minutes
+  // Delete any captcha answers, OAuth authorizations and form submissions older than ten
+  // minutes, every ten minutes
   let url = db_url.clone();
   scheduler.every(CTimeUnits::minutes(10)).run(move || {
     PgConnection::establish(&url)
       .map(|mut conn| {
         delete_expired_captcha_answers(&mut conn);
+        delete_expired_oauth_states(&mut conn);
+        delete_expired_form_submissions(&mut conn);
       })
       .map_err(|e| {
         error!("Failed to establish db connection for captcha cleanup: {e}");
@@ -99,12 +148,165 @@ pub fn setup(
       .ok();
   });
 
+  // Forget login fingerprints which weren't used for a while, every day
+  let url = db_url.clone();
+  scheduler.every(CTimeUnits::days(1)).run(move || {
+    PgConnection::establish(&url)
+      .map(|mut conn| {
+        clear_unused_login_fingerprints(&mut conn);
+      })
+      .map_err(|e| {
+        error!("Failed to establish db connection for login fingerprint cleanup: {e}");
+      })
+      .ok();
+  });
+
+  // Deliver the reminders for saved posts and comments which are due, every five minutes
+  let context = context_1.clone();
+  let reminder_runtime = runtime.clone();
+  scheduler.every(CTimeUnits::minutes(5)).run(move || {
+    reminder_runtime
+      .block_on(send_due_reminders(&context))
+      .map_err(|e| warn!("Failed to send reminders: {e}"))
+      .ok();
+  });
+
+  // Delete the accounts whose cooling-off period is over, every hour
+  let context = context_1.clone();
+  let deletion_runtime = runtime.clone();
+  scheduler.every(CTimeUnits::hour(1)).run(move || {
+    deletion_runtime
+      .block_on(delete_due_accounts(&context))
+      .map_err(|e| warn!("Failed to delete accounts: {e}"))
+      .ok();
+  });
+
+  // Lift the community bans which expired, every ten minutes
+  let context = context_1.clone();
+  let ban_runtime = runtime.clone();
+  scheduler.every(CTimeUnits::minutes(10)).run(move || {
+    ban_runtime
+      .block_on(lift_expired_bans(&context))
+      .map_err(|e| warn!("Failed to lift expired community bans: {e}"))
+      .ok();
+  });
+
+  // Deny the registration applications which nobody reviewed in time, every hour
+  let context = context_1.clone();
+  let application_runtime = runtime.clone();
+  scheduler.every(CTimeUnits::hour(1)).run(move || {
+    application_runtime
+      .block_on(deny_expired_applications(&context))
+      .map_err(|e| warn!("Failed to deny expired registration applications: {e}"))
+      .ok();
+  });
+
+  // Post the weekly community digests which are due, every hour
+  let context = context_1.clone();
+  let digest_runtime = runtime.clone();
+  scheduler.every(CTimeUnits::hour(1)).run(move || {
+    digest_runtime
+      .block_on(publish_due_digests(&context))
+      .map_err(|e| warn!("Failed to publish community digests: {e}"))
+      .ok();
+  });
+
+  // Probe pictrs while it is unavailable, and generate the thumbnails which were skipped in the
+  // meantime, every minute
+  let context = context_1.clone();
+  let thumbnail_runtime = runtime.clone();
+  scheduler.every(CTimeUnits::minute(1)).run(move || {
+    thumbnail_runtime
+      .block_on(retry_post_thumbnails(&context))
+      .map_err(|e| warn!("Failed to retry post thumbnails: {e}"))
+      .ok();
+  });
+
+  // Look for coordinated voting at the configured interval
+  let interval = context_1.settings().vote_anomaly.interval_minutes;
+  if interval > 0 {
+    let context = context_1.clone();
+    let anomaly_runtime = runtime.clone();
+    scheduler.every(CTimeUnits::minutes(interval)).run(move || {
+      anomaly_runtime
+        .block_on(detect_vote_anomalies(&context))
+        .map_err(|e| warn!("Failed to detect vote anomalies: {e}"))
+        .ok();
+    });
+  }
+
+  // Send notification digest emails to users whose digest is due
+  let url = db_url.clone();
+  let context = context_1.clone();
+  scheduler.every(CTimeUnits::hour(1)).run(move || {
+    PgConnection::establish(&url)
+      .map(|mut conn| {
+        send_notification_digests(&mut conn, &context, &runtime)
+          .map_err(|e| warn!("Failed to send notification digests: {e}"))
+          .ok();
+      })
+      .map_err(|e| {
+        error!("Failed to establish db connection for notification digests: {e}");
+      })
+      .ok();
+  });
+
+  // Clear expired activity delivery records every hour
+  let url = db_url.clone();
+  let retention_days = context_1.settings().activity_delivery_retention_days;
+  scheduler.every(CTimeUnits::hour(1)).run(move || {
+    PgConnection::establish(&url)
+      .map(|mut conn| {
+        clear_old_activity_deliveries(&mut conn, retention_days);
+      })
+      .map_err(|e| {
+        error!("Failed to establish db connection for activity delivery cleanup: {e}");
+      })
+      .ok();
+  });
+
   // Remove old rate limit buckets after 1 to 2 hours of inactivity
   scheduler.every(CTimeUnits::hour(1)).run(move || {
     let hour = Duration::from_secs(3600);
     context_1.settings_updated_channel().remove_older_than(hour);
   });
 
+  // Roll up the activity statistics of finished days
+  let url = db_url.clone();
+  scheduler.every(CTimeUnits::hour(1)).run(move || {
+    PgConnection::establish(&url)
+      .map(|mut conn| {
+        update_site_activity_rollup(&mut conn);
+      })
+      .map_err(|e| {
+        error!("Failed to establish db connection for site activity rollup: {e}");
+      })
+      .ok();
+  });
+
+  // Rebuild the all time top contributors of communities every day
+  let url = db_url.clone();
+  scheduler.every(CTimeUnits::days(1)).run(move || {
+    PgConnection::establish(&url)
+      .map(|mut conn| {
+        update_community_contributor_rollup(&mut conn);
+      })
+      .map_err(|e| {
+        error!("Failed to establish db connection for community contributor rollup: {e}");
+      })
+      .ok();
+  });
+
+  // Take the daily statistics snapshots of local communities every hour, once a day is finished
+  let context = context_1.clone();
+  let snapshot_runtime = runtime.clone();
+  scheduler.every(CTimeUnits::hour(1)).run(move || {
+    snapshot_runtime
+      .block_on(update_community_aggregates_snapshot(&context))
+      .map_err(|e| warn!("Failed to update community statistics snapshot: {e}"))
+      .ok();
+  });
+
   // Overwrite deleted & removed posts and comments every day
   let url = db_url.clone();
   scheduler.every(CTimeUnits::days(1)).run(move || {
@@ -147,6 +349,8 @@ fn startup_jobs(db_url: &str) {
   update_banned_when_expired(&mut conn);
   clear_old_activities(&mut conn);
   overwrite_deleted_posts_and_comments(&mut conn);
+  update_site_activity_rollup(&mut conn);
+  update_community_contributor_rollup(&mut conn);
 }
 
 /// Update the hot_rank columns for the aggregates tables
@@ -250,6 +454,24 @@ fn delete_expired_captcha_answers(conn: &mut PgConnection) {
   .ok();
 }
 
+fn delete_expired_oauth_states(conn: &mut PgConnection) {
+  diesel::delete(
+    oauth_state::table.filter(oauth_state::published.lt(now - IntervalDsl::minutes(10))),
+  )
+  .execute(conn)
+  .map_err(|e| error!("Failed to clear old OAuth authorizations: {e}"))
+  .ok();
+}
+
+fn delete_expired_form_submissions(conn: &mut PgConnection) {
+  diesel::delete(
+    form_submission::table.filter(form_submission::published.lt(now - IntervalDsl::minutes(10))),
+  )
+  .execute(conn)
+  .map_err(|e| error!("Failed to clear old form submissions: {e}"))
+  .ok();
+}
+
 /// Clear old activities (this table gets very large)
 fn clear_old_activities(conn: &mut PgConnection) {
   info!("Clearing old activities...");
@@ -267,6 +489,31 @@ fn clear_old_activities(conn: &mut PgConnection) {
   .ok();
 }
 
+/// Delete login fingerprints unused for 90 days, so that logging in from there notifies again
+fn clear_unused_login_fingerprints(conn: &mut PgConnection) {
+  info!("Clearing unused login fingerprints...");
+  diesel::delete(
+    login_fingerprint::table.filter(login_fingerprint::last_used.lt(now - IntervalDsl::days(90))),
+  )
+  .execute(conn)
+  .map(|_| info!("Done."))
+  .map_err(|e| error!("Failed to clear unused login fingerprints: {e}"))
+  .ok();
+}
+
+/// Clear the records of activity deliveries which are older than the retention window
+fn clear_old_activity_deliveries(conn: &mut PgConnection, retention_days: u32) {
+  info!("Clearing old activity deliveries...");
+  let retention = IntervalDsl::days(i32::try_from(retention_days).unwrap_or(i32::MAX));
+  diesel::delete(
+    sent_activity_delivery::table.filter(sent_activity_delivery::published.lt(now - retention)),
+  )
+  .execute(conn)
+  .map(|_| info!("Done."))
+  .map_err(|e| error!("Failed to clear old activity deliveries: {e}"))
+  .ok();
+}
+
 /// overwrite posts and comments 30d after deletion
 fn overwrite_deleted_posts_and_comments(conn: &mut PgConnection) {
   info!("Overwriting deleted posts...");
@@ -334,7 +581,92 @@ fn active_counts(conn: &mut PgConnection) {
   info!("Done.");
 }
 
-/// Set banned to false after ban expires
+/// Adds the activity statistics for all finished days which are not rolled up yet. On the first
+/// run this goes back at most one year.
+fn update_site_activity_rollup(conn: &mut PgConnection) {
+  info!("Updating site activity rollup ...");
+
+  let count_local = |table: &str| {
+    format!(
+      "(SELECT count(*) FROM {table} t WHERE t.local AND t.published >= d.day AND t.published < d.day + 1)"
+    )
+  };
+  let stmt = format!(
+    "INSERT INTO site_activity_rollup (day, users, posts, comments, users_active, instances_linked)
+     SELECT d.day, {users}, {posts}, {comments},
+       (SELECT count(*) FROM (
+          SELECT c.creator_id FROM comment c INNER JOIN person pe ON c.creator_id = pe.id
+           WHERE c.published >= d.day AND c.published < d.day + 1
+             AND pe.local AND NOT pe.bot_account
+          UNION
+          SELECT p.creator_id FROM post p INNER JOIN person pe ON p.creator_id = pe.id
+           WHERE p.published >= d.day AND p.published < d.day + 1
+             AND pe.local AND NOT pe.bot_account) a),
+       (SELECT count(*) FROM instance i
+          LEFT JOIN site s ON s.instance_id = i.id
+          LEFT JOIN local_site ls ON ls.site_id = s.id
+         WHERE ls.id IS NULL AND i.published < d.day + 1)
+     FROM (
+       SELECT generate_series(
+         (SELECT coalesce(max(day) + 1, greatest(
+           (SELECT min(published)::date FROM local_site), current_date - 365))
+            FROM site_activity_rollup),
+         current_date - 1,
+         '1 day')::date AS day) d
+     ON CONFLICT (day) DO NOTHING",
+    users = count_local("person"),
+    posts = count_local("post"),
+    comments = count_local("comment"),
+  );
+  sql_query(stmt)
+    .execute(conn)
+    .map(|rows| info!("Done, added {rows} days."))
+    .map_err(|e| error!("Failed to update site activity rollup: {e}"))
+    .ok();
+}
+
+/// Recounts the posts, comments and received score of everyone who contributed to a community.
+fn update_community_contributor_rollup(conn: &mut PgConnection) {
+  info!("Updating community contributor rollup ...");
+
+  let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+    sql_query("DELETE FROM community_contributor_rollup").execute(conn)?;
+    sql_query(
+      "INSERT INTO community_contributor_rollup (community_id, person_id, post_count, comment_count, score)
+       SELECT community_id, creator_id, sum(posts), sum(comments), sum(score)
+         FROM (
+           SELECT p.community_id, p.creator_id, 1 AS posts, 0 AS comments, pa.score
+             FROM post p
+            INNER JOIN post_aggregates pa ON pa.post_id = p.id
+            WHERE NOT p.deleted AND NOT p.removed
+           UNION ALL
+           SELECT p.community_id, c.creator_id, 0, 1, ca.score
+             FROM comment c
+            INNER JOIN post p ON p.id = c.post_id
+            INNER JOIN comment_aggregates ca ON ca.comment_id = c.id
+            WHERE NOT c.deleted AND NOT c.removed AND NOT p.deleted AND NOT p.removed
+         ) a
+        GROUP BY community_id, creator_id",
+    )
+    .execute(conn)
+  });
+  match result {
+    Ok(rows) => info!("Done, counted {rows} contributors."),
+    Err(e) => error!("Failed to update community contributor rollup: {e}"),
+  }
+}
+
+/// Adds the statistics of local communities for all finished days which don't have a snapshot
+/// yet. On the first run this goes back 90 days, which is the longest range mods can look at.
+async fn update_community_aggregates_snapshot(context: &LemmyContext) -> LemmyResult<()> {
+  info!("Updating community statistics snapshot ...");
+  let rows = CommunityAggregatesSnapshot::create_missing(&mut context.pool()).await?;
+  info!("Done, added {rows} snapshots.");
+  Ok(())
+}
+
+/// Set banned to false after ban expires, and lift expired instance blocks. Expired community bans
+/// are lifted by `lift_expired_community_bans`.
 fn update_banned_when_expired(conn: &mut PgConnection) {
   info!("Updating banned column if it expires ...");
 
@@ -348,12 +680,343 @@ fn update_banned_when_expired(conn: &mut PgConnection) {
   .map_err(|e| error!("Failed to update person.banned when expires: {e}"))
   .ok();
 
-  diesel::delete(community_person_ban::table.filter(community_person_ban::expires.lt(now)))
+  diesel::delete(federation_blocklist::table.filter(federation_blocklist::expires.lt(now)))
     .execute(conn)
-    .map_err(|e| error!("Failed to remove community_ban expired rows: {e}"))
+    .map_err(|e| error!("Failed to remove expired federation_blocklist rows: {e}"))
     .ok();
 }
 
+/// Sends a single email with the number of unread replies, mentions and private messages to every
+/// user who opted into notification digests, if they have anything unread.
+fn send_notification_digests(
+  conn: &mut PgConnection,
+  context: &LemmyContext,
+  runtime: &Handle,
+) -> LemmyResult<()> {
+  info!("Sending notification digests...");
+
+  let require_email_verification = local_site::table
+    .select(local_site::require_email_verification)
+    .first::<bool>(conn)?;
+
+  let mut query = local_user::table
+    .inner_join(person::table)
+    .filter(local_user::send_notification_digest.ne(NotificationDigest::Never))
+    .filter(local_user::send_notifications_to_email.eq(true))
+    .filter(local_user::email.is_not_null())
+    .filter(
+      local_user::last_digest_sent_at
+        .is_null()
+        .or(local_user::last_digest_sent_at.lt(now.nullable() - IntervalDsl::days(1))),
+    )
+    .filter(person::banned.eq(false))
+    .filter(person::deleted.eq(false))
+    .select((
+      local_user::id,
+      local_user::send_notification_digest,
+      local_user::email.assume_not_null(),
+      person::id,
+      person::name,
+    ))
+    .into_boxed();
+  if require_email_verification {
+    query = query.filter(local_user::email_verified.eq(true));
+  }
+  let users = query.load::<(LocalUserId, NotificationDigest, String, PersonId, String)>(conn)?;
+
+  let mut sent = 0;
+  for (local_user_id, digest, email, person_id, name) in users {
+    let replies = comment_reply::table
+      .inner_join(comment::table)
+      .filter(comment_reply::recipient_id.eq(person_id))
+      .filter(comment_reply::read.eq(false))
+      .filter(comment_reply::muted.eq(false))
+      .filter(comment::deleted.eq(false))
+      .filter(comment::removed.eq(false))
+      .count()
+      .get_result::<i64>(conn)?;
+    let mentions = person_mention::table
+      .inner_join(comment::table)
+      .filter(person_mention::recipient_id.eq(person_id))
+      .filter(person_mention::read.eq(false))
+      .filter(person_mention::muted.eq(false))
+      .filter(comment::deleted.eq(false))
+      .filter(comment::removed.eq(false))
+      .count()
+      .get_result::<i64>(conn)?;
+    let messages = private_message::table
+      .filter(private_message::recipient_id.eq(person_id))
+      .filter(private_message::read.eq(false))
+      .filter(private_message::deleted.eq(false))
+      .count()
+      .get_result::<i64>(conn)?;
+
+    let Some((subject, body)) =
+      notification_digest_email(&name, replies, mentions, messages, context.settings())
+    else {
+      continue;
+    };
+
+    // Claim the digest first, so that an overlapping run doesn't send it a second time
+    let interval = if digest == NotificationDigest::Weekly {
+      IntervalDsl::days(7)
+    } else {
+      IntervalDsl::days(1)
+    };
+    let claimed = diesel::update(
+      local_user::table.find(local_user_id).filter(
+        local_user::last_digest_sent_at
+          .is_null()
+          .or(local_user::last_digest_sent_at.lt(now.nullable() - interval)),
+      ),
+    )
+    .set(local_user::last_digest_sent_at.eq(now))
+    .execute(conn)?;
+    if claimed == 0 {
+      continue;
+    }
+
+    match runtime.block_on(send_email(
+      &subject,
+      &email,
+      &name,
+      &body,
+      context.settings(),
+    )) {
+      Ok(_) => sent += 1,
+      Err(e) => warn!("Failed to send notification digest to {name}: {e}"),
+    }
+  }
+  info!("Done, sent {sent} notification digests.");
+  Ok(())
+}
+
+/// Builds the subject and body of a notification digest email. Returns None if there is nothing
+/// unread, so that no empty digests are sent.
+fn notification_digest_email(
+  name: &str,
+  replies: i64,
+  mentions: i64,
+  messages: i64,
+  settings: &Settings,
+) -> Option<(String, String)> {
+  let inbox_link = format!("{}/inbox", settings.get_protocol_and_hostname());
+  let items = [
+    (replies, "replies"),
+    (mentions, "mentions"),
+    (messages, "private messages"),
+  ]
+  .iter()
+  .filter(|(count, _)| *count > 0)
+  .map(|(count, label)| format!("<li><a href=\"{inbox_link}\">{count} unread {label}</a></li>"))
+  .collect::<String>();
+  if items.is_empty() {
+    return None;
+  }
+
+  let total = replies + mentions + messages;
+  let subject = format!("{} - {total} unread notifications", settings.hostname);
+  let body = format!(
+    "<h1>{subject}</h1><p>Hi {name}, here is what you missed:</p><ul>{items}</ul>\
+     <p><a href=\"{inbox_link}\">Go to your inbox</a></p>"
+  );
+  Some((subject, body))
+}
+
+/// Puts the reminders for posts and comments which are due into the inbox of their users, and
+/// emails those who get notifications by email. Reminders for deleted posts are delivered too, so
+/// that users know what happened.
+async fn send_due_reminders(context: &LemmyContext) -> LemmyResult<()> {
+  let reminders = Reminder::create_due(&mut context.pool()).await?;
+  for reminder in &reminders {
+    // The reminder is in the inbox already, so a failed email only skips that one
+    let Ok(local_user_view) =
+      LocalUserView::read_person(&mut context.pool(), reminder.recipient_id).await
+    else {
+      continue;
+    };
+    let Ok(post) = Post::read(&mut context.pool(), reminder.post_id).await else {
+      continue;
+    };
+    let (subject, body) = reminder_email(
+      post.id,
+      &post.name,
+      reminder.comment_id,
+      reminder.note.as_deref(),
+      post.deleted || post.removed,
+      context.settings(),
+    );
+    send_email_to_user(&local_user_view, &subject, &body, context.settings()).await;
+  }
+  if !reminders.is_empty() {
+    info!("Delivered {} reminders.", reminders.len());
+  }
+  Ok(())
+}
+
+/// Hands the accounts whose scheduled deletion is due over to the same deletion as an immediate
+/// one, which also clears the schedule.
+async fn delete_due_accounts(context: &LemmyContext) -> LemmyResult<()> {
+  let local_users = LocalUser::list_due_deletions(&mut context.pool()).await?;
+  for local_user in &local_users {
+    let person = Person::read(&mut context.pool(), local_user.person_id).await?;
+    ActivityChannel::queue_activity(SendActivityData::DeleteUser(person))?;
+  }
+  if !local_users.is_empty() {
+    info!("Deleting {} accounts.", local_users.len());
+  }
+  Ok(())
+}
+
+/// Lifts the community bans which expired, and sends the unbans to other instances.
+async fn lift_expired_bans(context: &LemmyContext) -> LemmyResult<()> {
+  let unbans = lift_expired_community_bans(context).await?;
+  if !unbans.is_empty() {
+    info!("Lifted {} expired community bans.", unbans.len());
+  }
+  for unban in unbans {
+    ActivityChannel::queue_activity(unban)?;
+  }
+  Ok(())
+}
+
+/// Denies the unread registration applications which are older than the expiry of the site, if
+/// it is set.
+async fn deny_expired_applications(context: &LemmyContext) -> LemmyResult<()> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let expire_days = local_site.application_expire_days;
+  if expire_days <= 0 {
+    return Ok(());
+  }
+  let ids = RegistrationApplication::list_expired_ids(&mut context.pool(), expire_days).await?;
+  if !ids.is_empty() {
+    RegistrationApplication::deny_many(&mut context.pool(), &ids, None, EXPIRED_APPLICATION_REASON)
+      .await?;
+    info!("Denied {} expired registration applications.", ids.len());
+  }
+  Ok(())
+}
+
+/// Generates the thumbnails of posts which were created while pictrs was unavailable, once it is
+/// back. The updated posts are sent out again, so that other instances get the thumbnails too.
+async fn retry_post_thumbnails(context: &LemmyContext) -> LemmyResult<()> {
+  if context.settings().pictrs_config().is_err() {
+    return Ok(());
+  }
+  if PICTRS_BREAKER.state() != BreakerState::Closed {
+    probe_pictrs(context.client(), context.settings())
+      .await
+      .ok();
+    if PICTRS_BREAKER.state() != BreakerState::Closed {
+      return Ok(());
+    }
+  }
+
+  let retries = PostThumbnailRetry::list_pending(&mut context.pool(), 50).await?;
+  let mut generated = 0;
+  for retry in &retries {
+    let post = Post::read(&mut context.pool(), retry.post_id).await?;
+    let url = post.url.clone().map(Into::into);
+    let (_, thumbnail_url) =
+      fetch_site_data(context.client(), context.settings(), url.as_ref(), true).await;
+    // Pictrs went down again, keep the remaining retries for later
+    if thumbnail_needs_retry(url.as_ref(), &thumbnail_url) {
+      break;
+    }
+    if thumbnail_url.is_some() && !post.deleted && !post.removed {
+      let form = PostUpdateForm {
+        thumbnail_url: Some(thumbnail_url),
+        ..Default::default()
+      };
+      let updated_post = Post::update(&mut context.pool(), post.id, &form).await?;
+      ActivityChannel::queue_activity(SendActivityData::UpdatePost(updated_post))?;
+      generated += 1;
+    }
+    PostThumbnailRetry::remove(&mut context.pool(), post.id).await?;
+  }
+  if generated > 0 {
+    info!("Generated {generated} post thumbnails which were skipped.");
+  }
+  Ok(())
+}
+
+/// Builds the subject and body of a reminder email, linking to the comment if the reminder is for
+/// one, and to the post otherwise.
+fn reminder_email(
+  post_id: PostId,
+  post_name: &str,
+  comment_id: Option<CommentId>,
+  note: Option<&str>,
+  post_gone: bool,
+  settings: &Settings,
+) -> (String, String) {
+  let protocol_and_hostname = settings.get_protocol_and_hostname();
+  let (what, link) = match comment_id {
+    Some(comment_id) => (
+      "a comment in ",
+      format!("{protocol_and_hostname}/comment/{comment_id}"),
+    ),
+    None => ("", format!("{protocol_and_hostname}/post/{post_id}")),
+  };
+  let subject = format!("{} - Reminder: {post_name}", settings.hostname);
+  let mut body = format!(
+    "<h1>Reminder</h1><p>You asked to be reminded of {what}\
+     <a href=\"{link}\">{}</a>.</p>",
+    escape_html(post_name)
+  );
+  if let Some(note) = note {
+    body.push_str(&format!("<p>Your note: {}</p>", escape_html(note)));
+  }
+  if post_gone {
+    body.push_str("<p>The post has been deleted since.</p>");
+  }
+  (subject, body)
+}
+
+/// Escapes user provided text, so that it is shown as is in html emails.
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+/// Records posts and comments with abnormal vote surges, and posts them to the webhook if one is
+/// configured. This is only for detection, nothing is done about the votes.
+async fn detect_vote_anomalies(context: &LemmyContext) -> LemmyResult<()> {
+  let config = &context.settings().vote_anomaly;
+  let thresholds = VoteAnomalyThresholds {
+    window_minutes: i32::try_from(config.window_minutes)?,
+    baseline_days: i32::try_from(config.baseline_days)?,
+    min_votes: i64::from(config.min_votes),
+    z_score: config.z_score_threshold,
+    instance_share: config.instance_share_threshold,
+    new_account_days: i32::try_from(config.new_account_days)?,
+    new_account_share: config.new_account_share_threshold,
+  };
+  let anomalies = VoteAnomaly::detect(&mut context.pool(), &thresholds).await?;
+  if !anomalies.is_empty() {
+    info!("Flagged {} vote surges", anomalies.len());
+  }
+
+  if let Some(webhook_url) = &config.webhook_url {
+    for anomaly in &anomalies {
+      context
+        .client()
+        .post(webhook_url.clone())
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_string(anomaly)?)
+        .send()
+        .await
+        .and_then(|res| res.error_for_status().map_err(Into::into))
+        .map_err(|e| warn!("Failed to post vote anomaly to webhook: {e}"))
+        .ok();
+    }
+  }
+  Ok(())
+}
+
 /// Updates the instance software and version
 ///
 /// TODO: this should be async
@@ -395,6 +1058,12 @@ fn update_instance_software(conn: &mut PgConnection, user_agent: &str) -> LemmyR
               .updated(Some(naive_now()))
               .software(software.and_then(|s| s.name.clone()))
               .version(software.and_then(|s| s.version.clone()))
+              .refuses_nsfw(
+                node_info
+                  .metadata
+                  .as_ref()
+                  .and_then(|m| m.disallow_nsfw_content),
+              )
               .build(),
           )
         }
@@ -423,7 +1092,10 @@ mod tests {
   #![allow(clippy::unwrap_used)]
   #![allow(clippy::indexing_slicing)]
 
+  use crate::scheduled_tasks::{notification_digest_email, reminder_email};
+  use lemmy_db_schema::newtypes::{CommentId, PostId};
   use lemmy_routes::nodeinfo::NodeInfo;
+  use lemmy_utils::settings::structs::Settings;
   use reqwest::Client;
 
   #[tokio::test]
@@ -441,4 +1113,78 @@ mod tests {
 
     assert_eq!(lemmy_ml_nodeinfo.software.unwrap().name.unwrap(), "lemmy");
   }
+
+  #[test]
+  fn test_notification_digest_email() {
+    let settings = Settings::default();
+    assert_eq!(None, notification_digest_email("bob", 0, 0, 0, &settings));
+
+    let (subject, body) = notification_digest_email("bob", 2, 0, 1, &settings).unwrap();
+    assert_eq!(
+      format!("{} - 3 unread notifications", settings.hostname),
+      subject
+    );
+    let replies_link = format!(
+      "<a href=\"{}/inbox\">2 unread replies</a>",
+      settings.get_protocol_and_hostname()
+    );
+    assert!(body.contains(&replies_link));
+    assert!(body.contains("1 unread private messages"));
+    assert!(!body.contains("mentions"));
+  }
+
+  #[test]
+  fn test_reminder_email() {
+    let settings = Settings::default();
+    let protocol_and_hostname = settings.get_protocol_and_hostname();
+
+    let (subject, body) = reminder_email(PostId(3), "Read me", None, None, false, &settings);
+    assert_eq!(
+      format!("{} - Reminder: Read me", settings.hostname),
+      subject
+    );
+    assert!(body.contains(&format!(
+      "reminded of <a href=\"{protocol_and_hostname}/post/3\">Read me</a>"
+    )));
+    assert!(!body.contains("note"));
+    assert!(!body.contains("deleted"));
+
+    let (_, body) = reminder_email(
+      PostId(3),
+      "Read me",
+      Some(CommentId(5)),
+      None,
+      false,
+      &settings,
+    );
+    assert!(body.contains("of a comment in"));
+    assert!(body.contains(&format!("{protocol_and_hostname}/comment/5")));
+
+    let (_, body) = reminder_email(
+      PostId(3),
+      "Read me",
+      None,
+      Some("Reply to this"),
+      true,
+      &settings,
+    );
+    assert!(body.contains("<p>Your note: Reply to this</p>"));
+    assert!(body.contains("The post has been deleted since."));
+  }
+
+  #[test]
+  fn test_reminder_email_escapes_html() {
+    let settings = Settings::default();
+    let (_, body) = reminder_email(
+      PostId(3),
+      "<script>alert(\"Tom & Jerry\")</script>",
+      None,
+      Some("<b>bold</b>"),
+      false,
+      &settings,
+    );
+    assert!(!body.contains("<script>"));
+    assert!(body.contains("&lt;script&gt;alert(&quot;Tom &amp; Jerry&quot;)&lt;/script&gt;</a>"));
+    assert!(body.contains("<p>Your note: &lt;b&gt;bold&lt;/b&gt;</p>"));
+  }
 }